@@ -0,0 +1,110 @@
+use crate::{
+    error::{CliError, CliResult},
+    file_config, CliConfig,
+};
+use clap::Subcommand;
+use serde_json::{Map, Value};
+use std::{collections::HashMap, fs};
+
+#[derive(Subcommand, Debug)]
+pub enum AddressBookSubCommand {
+    /// Adds a named address, or replaces the address of an existing name
+    Add {
+        name: String,
+        address: String,
+        #[clap(long)]
+        config_file: String,
+    },
+    Remove {
+        name: String,
+        #[clap(long)]
+        config_file: String,
+    },
+    List {
+        #[clap(long)]
+        config_file: String,
+    },
+}
+
+pub fn match_subcommand(cmd: AddressBookSubCommand) -> CliResult<()> {
+    match cmd {
+        AddressBookSubCommand::Add {
+            name,
+            address,
+            config_file,
+        } => update_address_book(&config_file, |address_book| {
+            address_book.insert(name, Value::String(address));
+            Ok(())
+        }),
+        AddressBookSubCommand::Remove { name, config_file } => {
+            update_address_book(&config_file, |address_book| {
+                match address_book.remove(&name) {
+                    Some(_) => Ok(()),
+                    None => Err(CliError::InvalidArgument(format!(
+                        "{name} is not in the address book"
+                    ))),
+                }
+            })
+        }
+        AddressBookSubCommand::List { config_file } => {
+            let mut entries = file_config(&config_file)?
+                .address_book
+                .unwrap_or_default()
+                .into_iter()
+                .collect::<Vec<_>>();
+            entries.sort();
+
+            for (name, address) in entries {
+                println!("{name}: {address}");
+            }
+
+            Ok(())
+        }
+    }
+}
+
+/// Returns the address named `value` in the address book of the config file, or `value` itself if it isn't a name
+pub fn resolve(config_cli: &CliConfig, value: String) -> CliResult<String> {
+    let address_book: HashMap<String, String> = match config_cli.config_file.as_deref() {
+        Some(path) => file_config(path)?.address_book.unwrap_or_default(),
+        None => HashMap::new(),
+    };
+
+    match address_book.get(&value) {
+        Some(address) => {
+            tracing::info!("Resolved {value} to {address}");
+            Ok(address.clone())
+        }
+        None => Ok(value),
+    }
+}
+
+/// Edits the `address_book` section of the config file, keeping the rest of the file untouched
+fn update_address_book(
+    path: &str,
+    update: impl FnOnce(&mut Map<String, Value>) -> CliResult<()>,
+) -> CliResult<()> {
+    let mut config: Value = match fs::read_to_string(path) {
+        Ok(data) => serde_json::from_str(&data)
+            .map_err(|e| CliError::Config(format!("Unable to parse config file {path}: {e}")))?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Value::Object(Map::new()),
+        Err(err) => {
+            return Err(CliError::Config(format!(
+                "Unable to read config file {path}: {err}"
+            )))
+        }
+    };
+
+    let address_book = config
+        .as_object_mut()
+        .ok_or_else(|| CliError::Config(format!("Config file {path} is not a JSON object")))?
+        .entry("address_book")
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+        .ok_or_else(|| CliError::Config("address_book is not a JSON object".to_string()))?;
+    update(address_book)?;
+
+    let data = serde_json::to_string_pretty(&config).expect("A JSON value always serializes");
+    fs::write(path, data)
+        .map_err(|e| CliError::Config(format!("Unable to write config file {path}: {e}")))
+}
@@ -0,0 +1,146 @@
+use crate::{
+    address_book, combined_config,
+    error::{parse, CliError, CliResult},
+    eth_tx_options, log_self_check, signer, CliConfig, CommandConfig,
+};
+use aurora_connector::{AuroraConnector, AuroraConnectorBuilder};
+use bridge_connector_common::{contract_call::NearTxOptions, token_amount::TokenAmount};
+use clap::Subcommand;
+use ethers_core::types::Address;
+use near_primitives::types::AccountId;
+
+#[derive(Subcommand, Debug)]
+pub enum AuroraConnectorSubCommand {
+    Deposit {
+        #[clap(short, long)]
+        token: String,
+        /// Amount in raw units, e.g. `1500000`, or in whole tokens with a decimal point, e.g. `1.5`
+        #[clap(short, long)]
+        amount: String,
+        #[clap(short, long)]
+        recipient_address: String,
+        #[command(flatten)]
+        config_cli: CliConfig,
+    },
+    Withdraw {
+        #[clap(short, long)]
+        erc20_address: String,
+        /// Amount in raw units, e.g. `1500000`, or in whole tokens with a decimal point, e.g. `1.5`
+        #[clap(short, long)]
+        amount: String,
+        #[clap(short, long)]
+        recipient_account_id: String,
+        #[command(flatten)]
+        config_cli: CliConfig,
+    },
+}
+
+impl AuroraConnectorSubCommand {
+    pub fn config_cli(&self) -> &CliConfig {
+        match self {
+            Self::Deposit { config_cli, .. } | Self::Withdraw { config_cli, .. } => config_cli,
+        }
+    }
+}
+
+pub async fn match_subcommand(
+    cmd: AuroraConnectorSubCommand,
+    config: &CommandConfig,
+) -> CliResult<()> {
+    match cmd {
+        AuroraConnectorSubCommand::Deposit {
+            token,
+            amount,
+            recipient_address,
+            config_cli,
+        } => {
+            let recipient_address = address_book::resolve(&config_cli, recipient_address)?;
+            let token_id = parse::<AccountId>(&token, "--token")?;
+            let connector = aurora_connector(config_cli, config).await?;
+            let amount = parse::<TokenAmount>(&amount, "--amount")?
+                .resolve(async { Ok(connector.get_token_metadata(token_id).await?.decimals) })
+                .await?;
+
+            connector
+                .deposit(
+                    token,
+                    amount,
+                    parse::<Address>(&recipient_address, "--recipient-address")?,
+                )
+                .await?;
+        }
+        AuroraConnectorSubCommand::Withdraw {
+            erc20_address,
+            amount,
+            recipient_account_id,
+            config_cli,
+        } => {
+            let recipient_account_id = address_book::resolve(&config_cli, recipient_account_id)?;
+            let erc20_address = address_book::resolve(&config_cli, erc20_address)?;
+            let erc20_address = parse::<Address>(&erc20_address, "--erc20-address")?;
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+            let connector = aurora_connector(config_cli, config).await?;
+            let amount = parse::<TokenAmount>(&amount, "--amount")?
+                .resolve(connector.get_erc20_decimals(erc20_address))
+                .await?;
+
+            connector
+                .withdraw(
+                    erc20_address,
+                    amount,
+                    parse::<AccountId>(&recipient_account_id, "--recipient-account-id")?,
+                    tx_opts,
+                )
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn aurora_connector(
+    cli_config: CliConfig,
+    config: &CommandConfig,
+) -> CliResult<AuroraConnector> {
+    let combined_config = combined_config(cli_config, config.network.clone())?;
+    let near_signer = signer::custom_near_signer(&combined_config).await?;
+    let aurora_signer =
+        signer::custom_eth_signer(&combined_config, combined_config.aurora_chain_id).await?;
+
+    let connector = AuroraConnectorBuilder::default()
+        .aurora_endpoint(combined_config.aurora_rpc)
+        .aurora_chain_id(combined_config.aurora_chain_id)
+        .aurora_private_key(combined_config.aurora_private_key)
+        .near_endpoint(combined_config.near_rpc)
+        .near_signer(combined_config.near_signer)
+        .near_private_key(combined_config.near_private_key)
+        .aurora_engine_account_id(combined_config.aurora_engine_account_id)
+        .disabled_operations(parse(
+            &combined_config.disabled_operations.unwrap_or_default(),
+            "--disabled-operations",
+        )?)
+        .method_names(parse(
+            &combined_config.method_names.unwrap_or_default(),
+            "--method-names",
+        )?)
+        .near_tx_options(NearTxOptions {
+            gas: combined_config.near_gas,
+            deposit: combined_config.near_deposit,
+            proof_chunk_size: combined_config.proof_chunk_size,
+        })
+        .dry_run(combined_config.dry_run)
+        .custom_near_signer(near_signer)
+        .custom_aurora_signer(aurora_signer)
+        .near_retry_policy(config.near_retry_policy())
+        .near_outcome_timeout(config.near_outcome_timeout())
+        .eth_nonce_journal(config.eth_nonce_journal.clone())
+        .rpc_scheduler(config.rpc_scheduler())
+        .build()
+        .map_err(|e| CliError::Config(e.to_string()))?;
+
+    if config.self_check {
+        log_self_check(connector.self_check().await)?;
+    }
+
+    Ok(connector)
+}
@@ -0,0 +1,157 @@
+use borsh::{
+    schema::{BorshSchemaContainer, Definition, Fields},
+    BorshSchema,
+};
+use clap::{Args, ValueEnum};
+use eth_connector::WithdrawArgs;
+use fast_bridge::TransferMessage;
+use near_rpc_client::light_client_proof::LightClientExecutionProof;
+use omni_connector::{
+    omni_types::{ChainKind, OmniAddress, ProofKind},
+    payload::TransferMessagePayload,
+};
+use serde_json::{json, Map, Value};
+
+/// Bridge types with a stable borsh encoding
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SchemaType {
+    /// Proof of a Near receipt passed to the prover on Ethereum
+    LightClientExecutionProof,
+    /// Arguments of the eth connector `withdraw`
+    WithdrawArgs,
+    /// Fast bridge transfer from Near to Ethereum
+    TransferMessage,
+    ChainKind,
+    ProofKind,
+    OmniAddress,
+    /// Transfer message signed by the MPC network
+    TransferMessagePayload,
+}
+
+#[derive(Args, Debug)]
+pub struct BorshSchemaArgs {
+    /// Type to print the schema of. Can be repeated, defaults to all types
+    #[arg(long = "type", value_enum)]
+    pub types: Vec<SchemaType>,
+}
+
+pub fn run(args: BorshSchemaArgs) {
+    let types = match args.types.is_empty() {
+        true => SchemaType::value_variants().to_vec(),
+        false => args.types,
+    };
+
+    let schemas = types
+        .into_iter()
+        .map(|schema_type| {
+            let container = schema_type.container();
+            (container.declaration().clone(), container_json(&container))
+        })
+        .collect::<Map<_, _>>();
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&schemas).expect("Schema always serializes")
+    );
+}
+
+impl SchemaType {
+    fn container(self) -> BorshSchemaContainer {
+        match self {
+            SchemaType::LightClientExecutionProof => container_of::<LightClientExecutionProof>(),
+            SchemaType::WithdrawArgs => container_of::<WithdrawArgs>(),
+            SchemaType::TransferMessage => container_of::<TransferMessage>(),
+            SchemaType::ChainKind => container_of::<ChainKind>(),
+            SchemaType::ProofKind => container_of::<ProofKind>(),
+            SchemaType::OmniAddress => container_of::<OmniAddress>(),
+            SchemaType::TransferMessagePayload => container_of::<TransferMessagePayload>(),
+        }
+    }
+}
+
+fn container_of<T: BorshSchema>() -> BorshSchemaContainer {
+    borsh::schema_container_of::<T>()
+}
+
+/// The declaration of the type and the definitions of all the types it refers to
+fn container_json(container: &BorshSchemaContainer) -> Value {
+    let definitions = container
+        .definitions()
+        .map(|(declaration, definition)| (declaration.clone(), definition_json(definition)))
+        .collect::<Map<_, _>>();
+
+    json!({
+        "declaration": container.declaration(),
+        "definitions": definitions,
+    })
+}
+
+fn definition_json(definition: &Definition) -> Value {
+    match definition {
+        Definition::Primitive(size) => json!({ "primitive": { "size": size } }),
+        Definition::Sequence {
+            length_width,
+            length_range,
+            elements,
+        } => json!({
+            "sequence": {
+                "length_width": length_width,
+                "min_length": length_range.start(),
+                "max_length": length_range.end(),
+                "elements": elements,
+            }
+        }),
+        Definition::Tuple { elements } => json!({ "tuple": { "elements": elements } }),
+        Definition::Enum {
+            tag_width,
+            variants,
+        } => json!({
+            "enum": {
+                "tag_width": tag_width,
+                "variants": variants
+                    .iter()
+                    .map(|(discriminant, name, declaration)| json!({
+                        "discriminant": discriminant,
+                        "name": name,
+                        "declaration": declaration,
+                    }))
+                    .collect::<Vec<_>>(),
+            }
+        }),
+        Definition::Struct { fields } => match fields {
+            Fields::NamedFields(fields) => json!({
+                "struct": {
+                    "fields": fields
+                        .iter()
+                        .map(|(name, declaration)| json!({ "name": name, "declaration": declaration }))
+                        .collect::<Vec<_>>(),
+                }
+            }),
+            Fields::UnnamedFields(fields) => json!({ "struct": { "fields": fields } }),
+            Fields::Empty => json!({ "struct": { "fields": [] } }),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn withdraw_args_schema() {
+        let schema = container_json(&SchemaType::WithdrawArgs.container());
+
+        assert_eq!(schema["declaration"], "WithdrawArgs");
+        assert_eq!(
+            schema["definitions"]["WithdrawArgs"],
+            json!({
+                "struct": {
+                    "fields": [
+                        { "name": "recipient_address", "declaration": "[u8; 20]" },
+                        { "name": "amount", "declaration": "u128" },
+                    ]
+                }
+            })
+        );
+    }
+}
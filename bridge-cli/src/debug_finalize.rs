@@ -0,0 +1,323 @@
+use crate::{
+    combined_config,
+    error::{parse, parse_required, required, CliError, CliResult},
+    CliConfig, CommandConfig,
+};
+use borsh::BorshDeserialize;
+use bridge_connector_common::self_check::{CheckStatus, SelfCheckReport};
+use clap::Args;
+use eth_light_client_on_near::EthOnNearClient;
+use eth_proof::{EthRPCClient, Proof};
+use ethers::{
+    providers::{Http, Middleware, Provider},
+    types::{Address, H256},
+    utils::rlp::Rlp,
+};
+use near_primitives::{
+    hash::CryptoHash,
+    types::{AccountId, BlockId, BlockReference},
+    views::{ActionView, ExecutionStatusView, FinalExecutionOutcomeView},
+};
+
+/// Index of `number` in the RLP encoded block header
+const HEADER_NUMBER_INDEX: usize = 8;
+/// The finalization is a past transaction, so its outcome is expected right away
+const TX_LOOKUP_TIMEOUT_SEC: u64 = 10;
+
+#[derive(Args, Debug)]
+pub struct DebugFinalizeArgs {
+    /// Hash of the finalization transaction on Near, e.g. a failed `finalize-withdraw` or `finalize-deposit`
+    #[clap(long)]
+    pub tx: String,
+    /// Signer of the finalization transaction. Defaults to `--near-signer`
+    #[clap(long)]
+    pub sender: Option<String>,
+    #[command(flatten)]
+    pub config_cli: CliConfig,
+}
+
+/// Replays the checks the prover ran for a finalization on Near, at the blocks the finalization used, and reports
+/// which of them doesn't hold
+pub async fn run(args: DebugFinalizeArgs, command_config: &CommandConfig) -> CliResult<()> {
+    let config = combined_config(args.config_cli, command_config.network.clone())?;
+
+    let near_rpc = required(config.near_rpc, "near-rpc")?;
+    let eth_rpc = required(config.eth_rpc, "eth-rpc")?;
+    let eth_provider: Provider<Http> = parse(&eth_rpc, "--eth-rpc")?;
+    let tx_hash: CryptoHash = parse(&args.tx, "--tx")?;
+    let sender: AccountId =
+        parse_required(args.sender.or(config.near_signer).as_deref(), "near-signer")?;
+
+    let outcome = near_rpc_client::wait_for_tx_final_outcome(
+        tx_hash,
+        sender,
+        &near_rpc,
+        TX_LOOKUP_TIMEOUT_SEC,
+    )
+    .await
+    .map_err(|e| CliError::InvalidArgument(format!("Unable to load transaction: {e}")))?;
+    let proof = submitted_proof(&outcome)?;
+    let block_hash = H256(proof.block_hash().0);
+    let block_number: u64 = Rlp::new(&proof.header_data)
+        .val_at(HEADER_NUMBER_INDEX)
+        .map_err(|e| {
+            CliError::InvalidArgument(format!("Invalid block header in the proof: {e}"))
+        })?;
+
+    tracing::info!(
+        block_number,
+        block_hash = format!("{block_hash:#x}"),
+        receipt_index = proof.receipt_index,
+        log_index = proof.log_index,
+        "Loaded the submitted proof"
+    );
+
+    let expected_emitters = [
+        config.bridge_token_factory_address,
+        config.eth_custodian_address,
+    ]
+    .iter()
+    .flatten()
+    .map(|address| parse::<Address>(address, "contract address"))
+    .collect::<CliResult<Vec<_>>>()?;
+
+    let mut report = SelfCheckReport::default();
+    report.push("Finalization outcome", finalization_outcome(&outcome));
+    report.push("Receipt and log inclusion", inclusion(&proof));
+    report.push("Log emitter", log_emitter(&proof, &expected_emitters));
+    report.push(
+        "Canonical Ethereum block",
+        canonical_block(&eth_provider, block_number, block_hash).await,
+    );
+    report.push(
+        "Proof replay",
+        replay(
+            &eth_provider,
+            &EthRPCClient::new_with_options(&eth_rpc, command_config.eth_rpc_options()),
+            &proof,
+            block_number,
+        )
+        .await,
+    );
+    report.push(
+        "Ethereum light client",
+        match &config.eth_light_client_account_id {
+            Some(account_id) => {
+                let client = EthOnNearClient::new(
+                    parse(account_id, "--eth-light-client-account-id")?,
+                    near_rpc.clone(),
+                )
+                .at_block(execution_block(&near_rpc, &outcome).await?);
+
+                light_client(&client, block_number, block_hash).await
+            }
+            None => CheckStatus::Skipped,
+        },
+    );
+
+    tracing::info!("Finalization report:\n{report}");
+
+    match report.is_ok() {
+        true => Ok(()),
+        false => Err(CliError::Failed(
+            "The finalization doesn't pass all checks".to_string(),
+        )),
+    }
+}
+
+/// Ethereum event proof passed to the finalization method
+fn submitted_proof(outcome: &FinalExecutionOutcomeView) -> CliResult<Proof> {
+    outcome
+        .transaction
+        .actions
+        .iter()
+        .find_map(|action| match action {
+            ActionView::FunctionCall { args, .. } => Proof::try_from_slice(args).ok(),
+            _ => None,
+        })
+        .ok_or(CliError::InvalidArgument(
+            "The transaction doesn't submit an Ethereum event proof".to_string(),
+        ))
+}
+
+/// State right before the finalization receipt was executed, which is the state the prover saw
+async fn execution_block(
+    near_rpc: &str,
+    outcome: &FinalExecutionOutcomeView,
+) -> CliResult<BlockReference> {
+    let receipt = outcome.receipts_outcome.first().ok_or(CliError::Failed(
+        "The transaction has no receipts".to_string(),
+    ))?;
+    let block = near_rpc_client::get_block(
+        near_rpc,
+        BlockReference::BlockId(BlockId::Hash(receipt.block_hash)),
+    )
+    .await
+    .map_err(|e| CliError::Failed(format!("Unable to load the execution block: {e}")))?;
+
+    Ok(BlockReference::BlockId(BlockId::Hash(
+        block.header.prev_hash,
+    )))
+}
+
+fn finalization_outcome(outcome: &FinalExecutionOutcomeView) -> CheckStatus {
+    let failure =
+        outcome
+            .receipts_outcome
+            .iter()
+            .find_map(|receipt| match &receipt.outcome.status {
+                ExecutionStatusView::Failure(err) => Some(err.to_string()),
+                _ => None,
+            });
+
+    match failure {
+        Some(err) => CheckStatus::Failed(err),
+        None => CheckStatus::Passed("the finalization succeeded".to_string()),
+    }
+}
+
+/// Root mismatches and logs that aren't part of the proven receipt
+fn inclusion(proof: &Proof) -> CheckStatus {
+    match eth_proof::verify_proof(proof) {
+        Ok(()) => CheckStatus::Passed(format!(
+            "receipt {} and its log {} are included in the block",
+            proof.receipt_index, proof.log_index
+        )),
+        Err(err) => CheckStatus::Failed(err.to_string()),
+    }
+}
+
+fn log_emitter(proof: &Proof, expected_emitters: &[Address]) -> CheckStatus {
+    let log = Rlp::new(&proof.log_entry_data);
+    let emitter: Address = match log.val_at(0) {
+        Ok(emitter) => emitter,
+        Err(err) => return CheckStatus::Failed(format!("Invalid log entry: {err}")),
+    };
+    let topic: Option<H256> = log.at(1).and_then(|topics| topics.val_at(0)).ok();
+
+    let details = format!("emitted by {emitter:?}, topic {topic:?}");
+    match expected_emitters.is_empty() || expected_emitters.contains(&emitter) {
+        true => CheckStatus::Passed(details),
+        false => CheckStatus::Failed(format!(
+            "{details}, which isn't one of the configured bridge contracts"
+        )),
+    }
+}
+
+async fn canonical_block(
+    eth_provider: &Provider<Http>,
+    block_number: u64,
+    block_hash: H256,
+) -> CheckStatus {
+    match eth_provider.get_block(block_number).await {
+        Ok(Some(block)) if block.hash == Some(block_hash) => {
+            CheckStatus::Passed(format!("block {block_number} is {block_hash:#x}"))
+        }
+        Ok(Some(block)) => CheckStatus::Failed(format!(
+            "the proof header hashes to {block_hash:#x}, but block {block_number} is {:#x}. The block was reorged or the header is invalid",
+            block.hash.unwrap_or_default()
+        )),
+        Ok(None) => CheckStatus::Failed(format!("block {block_number} doesn't exist")),
+        Err(err) => CheckStatus::Failed(err.to_string()),
+    }
+}
+
+/// Generates the proof of the same log again and compares it with the submitted one
+async fn replay(
+    eth_provider: &Provider<Http>,
+    eth_rpc: &EthRPCClient,
+    proof: &Proof,
+    block_number: u64,
+) -> CheckStatus {
+    let result = async {
+        let block = eth_provider
+            .get_block(block_number)
+            .await?
+            .ok_or(format!("block {block_number} doesn't exist"))?;
+        let tx_hash = *block
+            .transactions
+            .get(proof.receipt_index as usize)
+            .ok_or(format!(
+                "block {block_number} has no receipt {}",
+                proof.receipt_index
+            ))?;
+        let receipt = eth_provider
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .ok_or(format!("transaction {tx_hash:#x} has no receipt"))?;
+        let log_index = receipt
+            .logs
+            .get(proof.log_index as usize)
+            .and_then(|log| log.log_index)
+            .ok_or(format!(
+                "transaction {tx_hash:#x} has no log {}",
+                proof.log_index
+            ))?;
+
+        let replayed =
+            eth_proof::get_proof_for_event_from_source(eth_rpc, tx_hash, log_index.as_u64())
+                .await?;
+
+        Ok::<_, Box<dyn std::error::Error>>((tx_hash, replayed))
+    }
+    .await;
+
+    let (tx_hash, replayed) = match result {
+        Ok(result) => result,
+        Err(err) => return CheckStatus::Failed(err.to_string()),
+    };
+
+    let mismatches = [
+        ("header", replayed.header_data == proof.header_data),
+        ("receipt", replayed.receipt_data == proof.receipt_data),
+        ("log", replayed.log_entry_data == proof.log_entry_data),
+        ("log index", replayed.log_index == proof.log_index),
+        ("receipt trie proof", replayed.proof == proof.proof),
+    ]
+    .into_iter()
+    .filter(|(_, matches)| !matches)
+    .map(|(field, _)| field)
+    .collect::<Vec<_>>();
+
+    match mismatches.is_empty() {
+        true => CheckStatus::Passed(format!(
+            "the proof of transaction {tx_hash:#x} is generated identically"
+        )),
+        false => CheckStatus::Failed(format!(
+            "the proof of transaction {tx_hash:#x} differs in: {}",
+            mismatches.join(", ")
+        )),
+    }
+}
+
+/// Stale light clients, and blocks that the light client finalized with another hash
+async fn light_client(
+    client: &EthOnNearClient,
+    block_number: u64,
+    block_hash: H256,
+) -> CheckStatus {
+    let last_block_number = match client.last_block_number().await {
+        Ok(last_block_number) => last_block_number,
+        Err(err) => return CheckStatus::Failed(err.to_string()),
+    };
+
+    if last_block_number < block_number {
+        return CheckStatus::Failed(format!(
+            "the light client was at block {last_block_number}, behind the proof block {block_number}"
+        ));
+    }
+
+    match client.block_hash_safe(block_number).await {
+        Ok(Some(hash)) if hash == block_hash.0 => CheckStatus::Passed(format!(
+            "block {block_number} was finalized, the light client was at block {last_block_number}"
+        )),
+        Ok(Some(hash)) => CheckStatus::Failed(format!(
+            "the light client finalized block {block_number} as {:#x}, the proof is for {block_hash:#x}",
+            H256(hash)
+        )),
+        Ok(None) => CheckStatus::Failed(format!(
+            "block {block_number} wasn't finalized on the light client, or was already garbage collected"
+        )),
+        Err(err) => CheckStatus::Failed(err.to_string()),
+    }
+}
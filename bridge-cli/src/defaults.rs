@@ -6,12 +6,17 @@ pub const TOKEN_LOCKER_ID_MAINNET: &str = "ft-locker.bridge.near";
 pub const BRIDGE_TOKEN_FACTORY_ADDRESS_MAINNET: &str = "0x252e87862A3A720287E7fd527cE6e8d0738427A2";
 pub const NEAR_LIGHT_CLIENT_ETH_ADDRESS_MAINNET: &str =
     "0x3FEFc5A4B1c02f21cBc8D3613643ba0635b9a873";
+pub const ETH_LIGHT_CLIENT_ACCOUNT_ID_MAINNET: &str = "client-eth2.bridge.near";
+pub const WRAPPED_NEAR_ID_MAINNET: &str = "wrap.near";
 pub const ETH_CONNECTOR_ACCOUNT_ID_MAINNET: &str = "aurora";
 pub const ETH_CUSTODIAN_ADDRESS_MAINNET: &str = "0x6BFaD42cFC4EfC96f529D786D643Ff4A8B89FA52";
 pub const FAST_BRIDGE_ACCOUNT_ID_MAINNET: &str = "fast.bridge.near";
 pub const FAST_BRIDGE_ADDRESS_MAINNET: &str = "0xC5E985976d9b521904AD96306356ECA0fE4F3a1f";
+pub const AURORA_RPC_MAINNET: &str = "https://mainnet.aurora.dev";
+pub const AURORA_CHAIN_ID_MAINNET: u64 = 1313161554;
+pub const AURORA_ENGINE_ACCOUNT_ID_MAINNET: &str = "aurora";
 
-/// Testnet
+/// Testnet (NEAR testnet + Sepolia)
 pub const NEAR_RPC_TESTNET: &str = "https://rpc.testnet.near.org/";
 pub const ETH_RPC_TESTNET: &str = "https://ethereum-sepolia.blockpi.network/v1/rpc/public";
 pub const ETH_CHAIN_ID_TESTNET: u64 = 11155111;
@@ -19,7 +24,12 @@ pub const TOKEN_LOCKER_ID_TESTNET: &str = "ft-locker.sepolia.testnet";
 pub const BRIDGE_TOKEN_FACTORY_ADDRESS_TESTNET: &str = "0xa9108f7F83Fb661e611991116D526fCa1a9585ab";
 pub const NEAR_LIGHT_CLIENT_ETH_ADDRESS_TESTNET: &str =
     "0x202cdf10bfa45a3d2190901373edd864f071d707";
+pub const ETH_LIGHT_CLIENT_ACCOUNT_ID_TESTNET: &str = "client-eth2.sepolia.testnet";
+pub const WRAPPED_NEAR_ID_TESTNET: &str = "wrap.testnet";
 pub const ETH_CONNECTOR_ACCOUNT_ID_TESTNET: &str = "aurora";
 pub const ETH_CUSTODIAN_ADDRESS_TESTNET: &str = "0xe0320b199863D7f6D5bBa62741aACB312110DEf7";
 pub const FAST_BRIDGE_ACCOUNT_ID_TESTNET: &str = "fastbridge.testnet";
 pub const FAST_BRIDGE_ADDRESS_TESTNET: &str = "0x0B2C4871C9bAD795746C05c1539A8B1f26c26357";
+pub const AURORA_RPC_TESTNET: &str = "https://testnet.aurora.dev";
+pub const AURORA_CHAIN_ID_TESTNET: u64 = 1313161555;
+pub const AURORA_ENGINE_ACCOUNT_ID_TESTNET: &str = "aurora";
@@ -0,0 +1,473 @@
+use crate::{
+    address_book, combined_config,
+    error::{parse, parse_required, required, CliError, CliResult},
+    eth_tx_options,
+    nep141_connector_command::nep141_connector,
+    signer,
+    smoke_test::{SmokeTest, SmokeTestError, SmokeTestReport},
+    snapshot::ft_balance_of,
+    CliConfig, CommandConfig,
+};
+use clap::Args;
+use ethers::{
+    abi::parse_abi,
+    contract::Contract,
+    providers::{Http, Provider},
+    types::{Address, TransactionReceipt, H256, U256},
+    utils::keccak256,
+};
+use omni_connector::{
+    omni_types::OmniAddress, payload::MpcSignature, OmniConnector, OmniConnectorBuilder,
+};
+use std::{future, sync::Arc, time::Duration};
+
+#[derive(Args, Debug)]
+pub struct DifferentialTestArgs {
+    /// NEP-141 token bridged to Ethereum through both flows. It has to be deployed on Ethereum by both the legacy
+    /// factory and the omni bridge
+    #[clap(short, long)]
+    pub token: String,
+    /// Address of the Ethereum account receiving the tokens of both transfers
+    #[clap(short, long)]
+    pub eth_recipient: String,
+    /// Amount of the token bridged by each flow
+    #[clap(long, default_value_t = 1)]
+    pub amount: u128,
+    /// Omni locker account id on Near
+    #[clap(long)]
+    pub omni_locker_id: String,
+    /// Omni bridge address on Ethereum
+    #[clap(long)]
+    pub omni_bridge_address: String,
+    /// Timeout of the steps sending a transaction, including the MPC signature of the omni transfer
+    #[clap(long, default_value_t = 300)]
+    pub step_timeout_sec: u64,
+    /// Timeout of the legacy step waiting for the Near light client on Ethereum to sync the transfer
+    #[clap(long, default_value_t = 14_400)]
+    pub sync_timeout_sec: u64,
+    #[command(flatten)]
+    pub config_cli: CliConfig,
+}
+
+/// Balances the two transfers change
+#[derive(Debug, Clone, Copy)]
+struct Balances {
+    /// Token balance of the Near signer
+    near: u128,
+    /// Balance of the recipient of the token deployed by the legacy factory
+    legacy_eth: U256,
+    /// Balance of the recipient of the token deployed by the omni bridge
+    omni_eth: U256,
+}
+
+/// Tokens minted on Ethereum by a finalization, as `(token, recipient, amount)`
+type Mints = Vec<(Address, Address, U256)>;
+
+struct DifferentialTest {
+    legacy: SmokeTest,
+    omni: OmniConnector,
+    legacy_eth_token: Address,
+    omni_eth_token: Address,
+    recipient: Address,
+}
+
+/// Bridges the same amount of a token from Near to Ethereum through the legacy proof-based flow and through the omni
+/// MPC-signature flow, and fails if the two transfers don't change the balances the same way or don't mint the same
+/// tokens to the recipient
+pub async fn run(args: DifferentialTestArgs, command_config: &CommandConfig) -> CliResult<()> {
+    let config = combined_config(args.config_cli.clone(), command_config.network.clone())?;
+    let eth_recipient = address_book::resolve(&args.config_cli, args.eth_recipient)?;
+    let tx_opts = eth_tx_options(&args.config_cli, command_config.network.clone())?;
+    let eth_provider: Provider<Http> = parse_required(config.eth_rpc.as_deref(), "eth-rpc")?;
+    let bridge_token_factory_address: Address = parse_required(
+        config.bridge_token_factory_address.as_deref(),
+        "bridge-token-factory-address",
+    )?;
+    let omni_bridge_address: Address = parse(&args.omni_bridge_address, "--omni-bridge-address")?;
+
+    // The configured contracts are the legacy ones, whatever the configured version
+    let mut legacy_config = args.config_cli.clone();
+    legacy_config.contract_version = Some("v1".to_string());
+
+    let omni = OmniConnectorBuilder::default()
+        .eth_endpoint(config.eth_rpc.clone())
+        .eth_chain_id(config.eth_chain_id)
+        .eth_private_key(config.eth_private_key.clone())
+        .eth_keystore_path(config.eth_keystore.clone())
+        .eth_keystore_password(signer::eth_keystore_password(&config)?)
+        .omni_bridge_address(Some(args.omni_bridge_address))
+        .near_endpoint(config.near_rpc.clone())
+        .near_private_key(config.near_private_key.clone())
+        .near_signer(config.near_signer.clone())
+        .omni_locker_id(Some(args.omni_locker_id))
+        .custom_near_signer(signer::custom_near_signer(&config).await?)
+        .custom_eth_signer(signer::custom_eth_signer(&config, config.eth_chain_id).await?)
+        .near_retry_policy(command_config.near_retry_policy())
+        .near_outcome_timeout(command_config.near_outcome_timeout())
+        .eth_nonce_journal(command_config.eth_nonce_journal.clone())
+        .eth_rpc_options(command_config.eth_rpc_options())
+        .proof_options(command_config.proof_options())
+        .build()
+        .map_err(|e| CliError::Config(e.to_string()))?;
+
+    let test = DifferentialTest {
+        legacy_eth_token: bridged_token(&eth_provider, bridge_token_factory_address, &args.token)
+            .await?,
+        omni_eth_token: bridged_token(&eth_provider, omni_bridge_address, &args.token).await?,
+        recipient: parse(&eth_recipient, "--eth-recipient")?,
+        legacy: SmokeTest {
+            connector: nep141_connector(
+                legacy_config,
+                &CommandConfig {
+                    self_check: false,
+                    ..command_config.clone()
+                },
+            )
+            .await?,
+            eth_provider,
+            near_endpoint: required(config.near_rpc, "near-rpc")?,
+            near_signer: parse_required(config.near_signer.as_deref(), "near-signer")?,
+            token_locker_id: parse_required(config.token_locker_id.as_deref(), "token-locker-id")?,
+            bridge_token_factory_address,
+            token: args.token,
+            eth_recipient,
+            amount: args.amount,
+            step_timeout: Duration::from_secs(args.step_timeout_sec),
+            tx_opts,
+        },
+        omni,
+    };
+    let step_timeout = test.legacy.step_timeout;
+    let sync_timeout = Duration::from_secs(args.sync_timeout_sec);
+
+    let mut report = SmokeTestReport::default();
+
+    let before = report
+        .step("Read balances", step_timeout, Some(()), |_| test.balances())
+        .await;
+
+    let receipt_id = report
+        .step("Legacy: deposit on Near", step_timeout, before, |_| {
+            test.legacy.deposit()
+        })
+        .await;
+    let receipt_id = report
+        .step(
+            "Legacy: sync Near light client on Ethereum",
+            sync_timeout,
+            receipt_id,
+            |receipt_id| test.legacy.wait_for_near_proof(receipt_id),
+        )
+        .await;
+    let legacy_receipt = report
+        .step(
+            "Legacy: finalize deposit on Ethereum",
+            step_timeout,
+            receipt_id,
+            |receipt_id| test.legacy.finalize_deposit(receipt_id),
+        )
+        .await;
+    let after_legacy = report
+        .step(
+            "Read balances after the legacy transfer",
+            step_timeout,
+            legacy_receipt.as_ref(),
+            |_| test.balances(),
+        )
+        .await;
+
+    let nonce = report
+        .step(
+            "Omni: init transfer on Near",
+            step_timeout,
+            after_legacy,
+            |_| test.init_transfer(),
+        )
+        .await;
+    let signature = report
+        .step(
+            "Omni: sign transfer with MPC",
+            step_timeout,
+            nonce,
+            |nonce| test.sign_transfer(nonce),
+        )
+        .await;
+    let omni_receipt = report
+        .step(
+            "Omni: finalize transfer on Ethereum",
+            step_timeout,
+            nonce.zip(signature),
+            |(nonce, signature)| test.fin_transfer(nonce, signature),
+        )
+        .await;
+    let after_omni = report
+        .step(
+            "Read balances after the omni transfer",
+            step_timeout,
+            omni_receipt.as_ref(),
+            |_| test.balances(),
+        )
+        .await;
+
+    report
+        .step(
+            "Compare balances",
+            step_timeout,
+            before.zip(after_legacy).zip(after_omni),
+            |((before, after_legacy), after_omni)| {
+                future::ready(test.compare_balances(before, after_legacy, after_omni))
+            },
+        )
+        .await;
+    report
+        .step(
+            "Compare events",
+            step_timeout,
+            legacy_receipt.zip(omni_receipt),
+            |(legacy_receipt, omni_receipt)| {
+                future::ready(test.compare_mints(&legacy_receipt, &omni_receipt))
+            },
+        )
+        .await;
+
+    tracing::info!("Differential test report:\n{report}");
+
+    match report.is_ok() {
+        true => Ok(()),
+        false => Err(CliError::Failed("Differential test failed".to_string())),
+    }
+}
+
+impl DifferentialTest {
+    async fn balances(&self) -> Result<(Balances, String), SmokeTestError> {
+        let near = ft_balance_of(
+            &self.legacy.near_endpoint,
+            &self.legacy.token,
+            &self.legacy.near_signer,
+        )
+        .await
+        .map_err(SmokeTestError)?
+        .parse::<u128>()
+        .map_err(|_| SmokeTestError("Invalid ft_balance_of response".to_string()))?;
+        let balances = Balances {
+            near,
+            legacy_eth: self.eth_balance(self.legacy_eth_token).await?,
+            omni_eth: self.eth_balance(self.omni_eth_token).await?,
+        };
+
+        Ok((
+            balances,
+            format!(
+                "Near {}, legacy token {}, omni token {}",
+                balances.near, balances.legacy_eth, balances.omni_eth
+            ),
+        ))
+    }
+
+    async fn eth_balance(&self, token: Address) -> Result<U256, SmokeTestError> {
+        let erc20 = Contract::new(
+            token,
+            parse_abi(&["function balanceOf(address account) external view returns (uint256)"])
+                .expect("Valid abi"),
+            Arc::new(self.legacy.eth_provider.clone()),
+        );
+
+        erc20
+            .method::<_, U256>("balanceOf", self.recipient)
+            .map_err(|e| SmokeTestError(e.to_string()))?
+            .call()
+            .await
+            .map_err(|e| SmokeTestError(e.to_string()))
+    }
+
+    async fn init_transfer(&self) -> Result<(u128, String), SmokeTestError> {
+        let deposit = self
+            .omni
+            .init_transfer(
+                self.legacy.token.clone(),
+                self.legacy.amount,
+                0,
+                0,
+                OmniAddress::Eth(self.recipient),
+            )
+            .await?;
+
+        Ok((
+            deposit.nonce,
+            format!("tx {}, nonce {}", deposit.tx_hash, deposit.nonce),
+        ))
+    }
+
+    async fn sign_transfer(&self, nonce: u128) -> Result<(Vec<u8>, String), SmokeTestError> {
+        let tx_hash = self
+            .omni
+            .sign_transfer(nonce, Some(self.legacy.near_signer.clone()))
+            .await?;
+        let outcome = self.legacy.wait_for_near_tx(tx_hash).await?;
+
+        let signature = MpcSignature::from_outcome(&outcome)
+            .ok_or(SmokeTestError(
+                "SignTransferEvent was not emitted by the omni locker".to_string(),
+            ))?
+            .to_bytes()
+            .map_err(SmokeTestError)?;
+
+        Ok((signature, format!("tx {tx_hash}")))
+    }
+
+    async fn fin_transfer(
+        &self,
+        nonce: u128,
+        signature: Vec<u8>,
+    ) -> Result<(TransactionReceipt, String), SmokeTestError> {
+        let tx_hash = self
+            .omni
+            .fin_transfer(
+                nonce,
+                self.omni_eth_token,
+                self.legacy.amount,
+                OmniAddress::Eth(self.recipient),
+                self.legacy.near_signer.to_string(),
+                signature,
+                self.legacy.tx_opts.clone(),
+            )
+            .await?;
+        let receipt = self.legacy.wait_for_eth_tx(tx_hash).await?;
+
+        Ok((receipt, format!("tx {tx_hash:?}")))
+    }
+
+    fn compare_balances(
+        &self,
+        before: Balances,
+        after_legacy: Balances,
+        after_omni: Balances,
+    ) -> Result<((), String), SmokeTestError> {
+        let legacy_locked = before.near.checked_sub(after_legacy.near);
+        let omni_locked = after_legacy.near.checked_sub(after_omni.near);
+        let legacy_minted = after_legacy.legacy_eth.checked_sub(before.legacy_eth);
+        let omni_minted = after_omni.omni_eth.checked_sub(after_legacy.omni_eth);
+
+        let expected = Some(self.legacy.amount);
+        let details = format!(
+            "locked on Near: legacy {legacy_locked:?}, omni {omni_locked:?}; minted on Ethereum: legacy \
+             {legacy_minted:?}, omni {omni_minted:?}"
+        );
+
+        match legacy_locked == expected
+            && omni_locked == expected
+            && legacy_minted == expected.map(U256::from)
+            && omni_minted == expected.map(U256::from)
+        {
+            true => Ok(((), details)),
+            false => Err(SmokeTestError(format!(
+                "expected {} locked and minted by each flow, {details}",
+                self.legacy.amount
+            ))),
+        }
+    }
+
+    fn compare_mints(
+        &self,
+        legacy_receipt: &TransactionReceipt,
+        omni_receipt: &TransactionReceipt,
+    ) -> Result<((), String), SmokeTestError> {
+        let legacy_mints = mints(legacy_receipt);
+        let omni_mints = mints(omni_receipt);
+        let expected = |token| vec![(token, self.recipient, U256::from(self.legacy.amount))];
+        let details = format!("legacy mints {legacy_mints:?}, omni mints {omni_mints:?}");
+
+        match legacy_mints == expected(self.legacy_eth_token)
+            && omni_mints == expected(self.omni_eth_token)
+        {
+            true => Ok(((), details)),
+            false => Err(SmokeTestError(format!(
+                "expected a single mint of {} to {:?} by each flow, {details}",
+                self.legacy.amount, self.recipient
+            ))),
+        }
+    }
+}
+
+/// Ethereum token deployed for `token` by the legacy factory or the omni bridge, which share the lookup method
+async fn bridged_token(
+    eth_provider: &Provider<Http>,
+    bridge_address: Address,
+    token: &str,
+) -> CliResult<Address> {
+    let bridge = Contract::new(
+        bridge_address,
+        parse_abi(&[
+            "function nearToEthToken(string calldata nearTokenId) external view returns (address)",
+        ])
+        .expect("Valid abi"),
+        Arc::new(eth_provider.clone()),
+    );
+    let eth_token: Address = bridge
+        .method("nearToEthToken", token.to_string())
+        .map_err(|e| CliError::Failed(e.to_string()))?
+        .call()
+        .await
+        .map_err(|e| {
+            CliError::Failed(format!(
+                "Failed to look up {token} on {bridge_address:?}: {e}"
+            ))
+        })?;
+
+    match eth_token.is_zero() {
+        true => Err(CliError::InvalidArgument(format!(
+            "{token} is not deployed on Ethereum by {bridge_address:?}"
+        ))),
+        false => Ok(eth_token),
+    }
+}
+
+/// ERC-20 `Transfer` events from the zero address in the receipt
+fn mints(receipt: &TransactionReceipt) -> Mints {
+    let transfer_topic = H256(keccak256("Transfer(address,address,uint256)"));
+
+    receipt
+        .logs
+        .iter()
+        .filter(|log| {
+            log.topics.len() == 3
+                && log.topics[0] == transfer_topic
+                && log.topics[1] == H256::zero()
+        })
+        .map(|log| {
+            (
+                log.address,
+                Address::from(log.topics[2]),
+                U256::from_big_endian(&log.data),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{Bytes, Log};
+
+    #[test]
+    fn mints_are_read_from_transfer_events() {
+        let token = Address::repeat_byte(1);
+        let recipient = Address::repeat_byte(2);
+        let transfer = |from: Address| Log {
+            address: token,
+            topics: vec![
+                H256(keccak256("Transfer(address,address,uint256)")),
+                H256::from(from),
+                H256::from(recipient),
+            ],
+            data: Bytes::from(H256::from_low_u64_be(7).0.to_vec()),
+            ..Default::default()
+        };
+        let receipt = TransactionReceipt {
+            logs: vec![transfer(Address::zero()), transfer(Address::repeat_byte(3))],
+            ..Default::default()
+        };
+
+        assert_eq!(mints(&receipt), [(token, recipient, U256::from(7))]);
+    }
+}
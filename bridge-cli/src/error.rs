@@ -0,0 +1,196 @@
+use bridge_connector_common::{contract_failure::ContractFailure, result::BridgeSdkError};
+use bridge_relayer::RelayerError;
+use std::{fmt::Display, str::FromStr};
+
+/// Exit codes of the CLI, so that scripts can tell failures apart without parsing the output
+pub mod exit_code {
+    /// The command ran but its outcome is a failure, e.g. a failed self-check or smoke test
+    pub const FAILURE: i32 = 1;
+    /// Invalid argument. Also used by clap for malformed command lines
+    pub const INVALID_ARGUMENT: i32 = 2;
+    /// Missing or invalid configuration
+    pub const CONFIG: i32 = 3;
+    /// Ethereum or Near RPC request failed, including proof retrieval
+    pub const RPC: i32 = 4;
+    /// A transaction was sent but failed on chain
+    pub const TRANSACTION_FAILED: i32 = 5;
+    /// The signer couldn't be loaded or refused to sign
+    pub const SIGNER: i32 = 6;
+    /// The operation is disabled by `--disabled-operations`
+    pub const OPERATION_DISABLED: i32 = 7;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CliError {
+    #[error("{0}")]
+    Config(String),
+    #[error("{0}")]
+    InvalidArgument(String),
+    #[error("{0}")]
+    Signer(String),
+    #[error("{0}")]
+    Failed(String),
+    #[error(transparent)]
+    Sdk(#[from] BridgeSdkError),
+    #[error(transparent)]
+    Relayer(#[from] RelayerError),
+}
+
+pub type CliResult<T> = Result<T, CliError>;
+
+impl CliError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Config(_) => exit_code::CONFIG,
+            CliError::InvalidArgument(_) => exit_code::INVALID_ARGUMENT,
+            CliError::Signer(_) => exit_code::SIGNER,
+            CliError::Failed(_) => exit_code::FAILURE,
+            CliError::Sdk(err) | CliError::Relayer(RelayerError::SdkError(err)) => {
+                sdk_exit_code(err)
+            }
+            CliError::Relayer(_) => exit_code::FAILURE,
+        }
+    }
+
+    /// Known cause of a failed contract call, see [`BridgeSdkError::contract_failure`]
+    pub fn contract_failure(&self) -> Option<ContractFailure> {
+        match self {
+            CliError::Sdk(err) | CliError::Relayer(RelayerError::SdkError(err)) => {
+                err.contract_failure()
+            }
+            _ => None,
+        }
+    }
+
+    /// What the user can do about the error, printed below it
+    pub fn hint(&self) -> Option<&'static str> {
+        if let Some(failure) = self.contract_failure() {
+            return Some(match failure {
+                ContractFailure::AccountNotRegistered => {
+                    "Register the account on the token with storage_deposit, or pass --register-withdraw-recipient"
+                }
+                ContractFailure::InsufficientStorageDeposit => {
+                    "Attach at least the minimum of the storage_balance_bounds of the contract"
+                }
+                ContractFailure::ProofAlreadyUsed => {
+                    "The transfer is already finalized, there's nothing left to do"
+                }
+                ContractFailure::InvalidProof => {
+                    "Wait for the light client to sync the block of the transfer, then retry"
+                }
+                ContractFailure::Paused => "The bridge contract is paused, retry once it's unpaused",
+            });
+        }
+
+        match self.exit_code() {
+            exit_code::CONFIG => Some(
+                "Set the value with its command line flag, its environment variable or in the --config-file",
+            ),
+            exit_code::RPC => Some(
+                "Check that the rpc endpoint is reachable, or set another one with --eth-rpc or --near-rpc",
+            ),
+            exit_code::OPERATION_DISABLED => {
+                Some("Remove the operation from --disabled-operations to run it")
+            }
+            _ => None,
+        }
+    }
+}
+
+fn sdk_exit_code(err: &BridgeSdkError) -> i32 {
+    match err {
+        BridgeSdkError::ConfigError(_) => exit_code::CONFIG,
+        BridgeSdkError::InvalidArgument(_)
+        | BridgeSdkError::InsufficientBalance { .. }
+        | BridgeSdkError::MissingEvent(_) => exit_code::INVALID_ARGUMENT,
+        BridgeSdkError::EthRpcError(_)
+        | BridgeSdkError::NearRpcError(_)
+        | BridgeSdkError::EthProofError(_)
+        | BridgeSdkError::NearProofError(_)
+        | BridgeSdkError::SolanaRpcError(_)
+        | BridgeSdkError::InvalidEvent(_)
+        | BridgeSdkError::RpcOverloaded(_)
+        | BridgeSdkError::DeadlineExceeded(_) => exit_code::RPC,
+        BridgeSdkError::NearOutcomeError(_)
+        | BridgeSdkError::EthTxFailed { .. }
+        | BridgeSdkError::SolanaTxFailed { .. } => exit_code::TRANSACTION_FAILED,
+        BridgeSdkError::SignerError(_) => exit_code::SIGNER,
+        BridgeSdkError::OperationDisabled(_) => exit_code::OPERATION_DISABLED,
+        BridgeSdkError::TransferRejected(_)
+        | BridgeSdkError::SerializationError(_)
+        | BridgeSdkError::InvalidEthProof(_)
+        | BridgeSdkError::IoError(_) => exit_code::FAILURE,
+    }
+}
+
+/// Returns the configured value, or an error naming the flag that sets it
+pub fn required<T>(value: Option<T>, flag: &str) -> CliResult<T> {
+    value.ok_or_else(|| CliError::Config(format!("--{flag} is not set")))
+}
+
+/// Parses a command line or config value, naming it in the error
+pub fn parse<T: FromStr>(value: &str, name: &str) -> CliResult<T>
+where
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|e| CliError::InvalidArgument(format!("Invalid {name} `{value}`: {e}")))
+}
+
+/// Parses a configured value, or fails if it isn't set
+pub fn parse_required<T: FromStr>(value: Option<&str>, flag: &str) -> CliResult<T>
+where
+    T::Err: Display,
+{
+    parse(required(value, flag)?, &format!("--{flag}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::Address;
+
+    #[test]
+    fn exit_codes_follow_sdk_errors() {
+        let disabled = CliError::from(BridgeSdkError::OperationDisabled("deposit".to_string()));
+        assert_eq!(disabled.exit_code(), exit_code::OPERATION_DISABLED);
+        assert!(disabled.hint().is_some());
+
+        let config = CliError::from(BridgeSdkError::ConfigError("no rpc".to_string()));
+        assert_eq!(config.exit_code(), exit_code::CONFIG);
+
+        let balance = CliError::from(BridgeSdkError::InsufficientBalance {
+            have: 1.into(),
+            need: 2.into(),
+        });
+        assert_eq!(balance.exit_code(), exit_code::INVALID_ARGUMENT);
+        assert_eq!(balance.to_string(), "Insufficient balance: have 1, need 2");
+
+        let relayer = CliError::from(RelayerError::SdkError(BridgeSdkError::SignerError(
+            "locked".to_string(),
+        )));
+        assert_eq!(relayer.exit_code(), exit_code::SIGNER);
+
+        let used_proof = CliError::from(BridgeSdkError::NearOutcomeError(
+            "Smart contract panicked: Event cannot be reused for depositing.".to_string(),
+        ));
+        assert_eq!(used_proof.exit_code(), exit_code::TRANSACTION_FAILED);
+        assert_eq!(
+            used_proof.contract_failure(),
+            Some(ContractFailure::ProofAlreadyUsed)
+        );
+    }
+
+    #[test]
+    fn parse_names_the_flag() {
+        let err = parse_required::<Address>(Some("0xnot-an-address"), "eth-recipient").unwrap_err();
+        assert_eq!(err.exit_code(), exit_code::INVALID_ARGUMENT);
+        assert!(err
+            .to_string()
+            .starts_with("Invalid --eth-recipient `0xnot-an-address`"));
+
+        let err = parse_required::<Address>(None, "eth-recipient").unwrap_err();
+        assert_eq!(err.to_string(), "--eth-recipient is not set");
+    }
+}
@@ -1,23 +1,34 @@
-use crate::{combined_config, CliConfig, Network};
+use crate::{
+    address_book, combined_config,
+    error::{parse, CliError, CliResult},
+    eth_tx_options, log_self_check, signer, CliConfig, CommandConfig,
+};
+use bridge_connector_common::{
+    bridge_connector::DEFAULT_LIGHT_CLIENT_SYNC_TIMEOUT,
+    contract_call::NearTxOptions,
+    token_amount::{TokenAmount, ETH_DECIMALS},
+};
 use clap::Subcommand;
 use eth_connector::{EthConnector, EthConnectorBuilder};
 use ethers_core::types::{Address, TxHash};
 use near_primitives::hash::CryptoHash;
-use std::str::FromStr;
+use std::time::Duration;
 
 #[derive(Subcommand, Debug)]
 pub enum EthConnectorSubCommand {
     DepositToNear {
+        /// Amount in wei, e.g. `1500000000000000000`, or in ETH with a decimal point, e.g. `1.5`
         #[clap(short, long)]
-        amount: u128,
+        amount: String,
         #[clap(short, long)]
         recipient_account_id: String,
         #[command(flatten)]
         config_cli: CliConfig,
     },
     DepositToEvm {
+        /// Amount in wei, e.g. `1500000000000000000`, or in ETH with a decimal point, e.g. `1.5`
         #[clap(short, long)]
-        amount: u128,
+        amount: String,
         #[clap(short, long)]
         recipient_address: String,
         #[command(flatten)]
@@ -26,14 +37,16 @@ pub enum EthConnectorSubCommand {
     FinalizeDeposit {
         #[clap(short, long)]
         tx_hash: String,
+        /// Index of the deposit event in the block. Looked up in the transaction logs if omitted
         #[clap(short, long)]
-        log_index: u64,
+        log_index: Option<u64>,
         #[command(flatten)]
         config_cli: CliConfig,
     },
     WithdrawFromNear {
+        /// Amount in wei, e.g. `1500000000000000000`, or in ETH with a decimal point, e.g. `1.5`
         #[clap(short, long)]
-        amount: u128,
+        amount: String,
         #[clap(short, long)]
         recipient_address: String,
         #[command(flatten)]
@@ -47,79 +60,154 @@ pub enum EthConnectorSubCommand {
     },
 }
 
-pub async fn match_subcommand(cmd: EthConnectorSubCommand, network: Network) {
+impl EthConnectorSubCommand {
+    pub fn config_cli(&self) -> &CliConfig {
+        match self {
+            Self::DepositToNear { config_cli, .. }
+            | Self::DepositToEvm { config_cli, .. }
+            | Self::FinalizeDeposit { config_cli, .. }
+            | Self::WithdrawFromNear { config_cli, .. }
+            | Self::FinalizeWithdraw { config_cli, .. } => config_cli,
+        }
+    }
+}
+
+pub async fn match_subcommand(
+    cmd: EthConnectorSubCommand,
+    config: &CommandConfig,
+) -> CliResult<()> {
     match cmd {
         EthConnectorSubCommand::DepositToNear {
             amount,
             recipient_account_id,
             config_cli,
         } => {
-            eth_connector(network, config_cli)
-                .deposit_to_near(amount, recipient_account_id)
-                .await
-                .unwrap();
+            let recipient_account_id = address_book::resolve(&config_cli, recipient_account_id)?;
+            let amount = parse::<TokenAmount>(&amount, "--amount")?.to_raw(ETH_DECIMALS)?;
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+
+            eth_connector(config_cli, config)
+                .await?
+                .deposit_to_near(amount, recipient_account_id, tx_opts)
+                .await?;
         }
         EthConnectorSubCommand::DepositToEvm {
             amount,
             recipient_address,
             config_cli,
         } => {
-            eth_connector(network, config_cli)
-                .deposit_to_evm(amount, recipient_address)
-                .await
-                .unwrap();
+            let recipient_address = address_book::resolve(&config_cli, recipient_address)?;
+            let amount = parse::<TokenAmount>(&amount, "--amount")?.to_raw(ETH_DECIMALS)?;
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+
+            eth_connector(config_cli, config)
+                .await?
+                .deposit_to_evm(amount, recipient_address, tx_opts)
+                .await?;
         }
         EthConnectorSubCommand::FinalizeDeposit {
             tx_hash,
             log_index,
             config_cli,
         } => {
-            eth_connector(network, config_cli)
-                .finalize_deposit(
-                    TxHash::from_str(&tx_hash).expect("Invalid tx_hash"),
-                    log_index,
-                )
-                .await
-                .unwrap();
+            let connector = eth_connector(config_cli, config).await?;
+            let tx_hash = parse::<TxHash>(&tx_hash, "--tx-hash")?;
+
+            match log_index {
+                Some(log_index) => connector.finalize_deposit(tx_hash, log_index).await?,
+                None => connector.finalize_deposit_from_tx(tx_hash).await?,
+            };
         }
         EthConnectorSubCommand::WithdrawFromNear {
             amount,
             recipient_address,
             config_cli,
         } => {
-            eth_connector(network, config_cli)
+            let recipient_address = address_book::resolve(&config_cli, recipient_address)?;
+            let amount = parse::<TokenAmount>(&amount, "--amount")?.to_raw(ETH_DECIMALS)?;
+
+            eth_connector(config_cli, config)
+                .await?
                 .withdraw(
                     amount,
-                    Address::from_str(&recipient_address).expect("Invalid recipient_address"),
+                    parse::<Address>(&recipient_address, "--recipient-address")?,
                 )
-                .await
-                .unwrap();
+                .await?;
         }
         EthConnectorSubCommand::FinalizeWithdraw {
             reciept_id,
             config_cli,
         } => {
-            eth_connector(network, config_cli)
-                .finalize_withdraw(CryptoHash::from_str(&reciept_id).expect("Invalid receipt_id"))
-                .await
-                .unwrap();
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+
+            eth_connector(config_cli, config)
+                .await?
+                .finalize_withdraw(parse::<CryptoHash>(&reciept_id, "--reciept-id")?, tx_opts)
+                .await?;
         }
     }
+
+    Ok(())
 }
 
-fn eth_connector(network: Network, cli_config: CliConfig) -> EthConnector {
-    let combined_config = combined_config(cli_config, network);
+async fn eth_connector(cli_config: CliConfig, config: &CommandConfig) -> CliResult<EthConnector> {
+    let combined_config = combined_config(cli_config, config.network.clone())?;
+    let near_signer = signer::custom_near_signer(&combined_config).await?;
+    let eth_signer =
+        signer::custom_eth_signer(&combined_config, combined_config.eth_chain_id).await?;
+    let eth_keystore_password = signer::eth_keystore_password(&combined_config)?;
 
-    EthConnectorBuilder::default()
+    let connector = EthConnectorBuilder::default()
         .eth_endpoint(combined_config.eth_rpc)
         .eth_chain_id(combined_config.eth_chain_id)
         .eth_private_key(combined_config.eth_private_key)
+        .eth_keystore_path(combined_config.eth_keystore)
+        .eth_keystore_password(eth_keystore_password)
         .near_endpoint(combined_config.near_rpc)
         .near_signer(combined_config.near_signer)
         .near_private_key(combined_config.near_private_key)
         .eth_custodian_address(combined_config.eth_custodian_address)
         .eth_connector_account_id(combined_config.eth_connector_account_id)
         .near_light_client_address(combined_config.near_light_client_eth_address)
+        .eth_light_client_account_id(combined_config.eth_light_client_account_id)
+        .disabled_operations(parse(
+            &combined_config.disabled_operations.unwrap_or_default(),
+            "--disabled-operations",
+        )?)
+        .recipient_validation(parse(
+            &combined_config.recipient_validation.unwrap_or_default(),
+            "--recipient-validation",
+        )?)
+        .method_names(parse(
+            &combined_config.method_names.unwrap_or_default(),
+            "--method-names",
+        )?)
+        .near_tx_options(NearTxOptions {
+            gas: combined_config.near_gas,
+            deposit: combined_config.near_deposit,
+            proof_chunk_size: combined_config.proof_chunk_size,
+        })
+        .dry_run(combined_config.dry_run)
+        .wait_for_light_client(!combined_config.no_light_client_wait)
+        .light_client_sync_timeout(
+            combined_config
+                .light_client_sync_timeout_sec
+                .map_or(DEFAULT_LIGHT_CLIENT_SYNC_TIMEOUT, Duration::from_secs),
+        )
+        .custom_near_signer(near_signer)
+        .custom_eth_signer(eth_signer)
+        .near_retry_policy(config.near_retry_policy())
+        .near_outcome_timeout(config.near_outcome_timeout())
+        .eth_nonce_journal(config.eth_nonce_journal.clone())
+        .rpc_scheduler(config.rpc_scheduler())
+        .eth_rpc_options(config.eth_rpc_options())
+        .proof_options(config.proof_options())
         .build()
-        .unwrap()
+        .map_err(|e| CliError::Config(e.to_string()))?;
+
+    if config.self_check {
+        log_self_check(connector.self_check().await)?;
+    }
+
+    Ok(connector)
 }
@@ -1,9 +1,17 @@
-use crate::{combined_config, CliConfig, Network};
+use crate::{
+    address_book, combined_config,
+    error::{parse, CliError, CliResult},
+    eth_tx_options, log_self_check, signer, CliConfig, CommandConfig,
+};
+use bridge_connector_common::contract_call::NearTxOptions;
 use clap::Subcommand;
 use ethers_core::types::{Address, TxHash};
 use fast_bridge::{FastBridge, FastBridgeBuilder};
 use near_primitives::types::AccountId;
-use std::{ops::Add, str::FromStr, time::{Duration, SystemTime, UNIX_EPOCH}};
+use std::{
+    ops::Add,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 #[derive(Subcommand, Debug)]
 pub enum FastBridgeSubCommand {
@@ -57,7 +65,18 @@ pub enum FastBridgeSubCommand {
     },
 }
 
-pub async fn match_subcommand(cmd: FastBridgeSubCommand, network: Network) {
+impl FastBridgeSubCommand {
+    pub fn config_cli(&self) -> &CliConfig {
+        match self {
+            Self::Transfer { config_cli, .. }
+            | Self::CompleteTransferOnEth { config_cli, .. }
+            | Self::LpUnlock { config_cli, .. }
+            | Self::Withdraw { config_cli, .. } => config_cli,
+        }
+    }
+}
+
+pub async fn match_subcommand(cmd: FastBridgeSubCommand, config: &CommandConfig) -> CliResult<()> {
     match cmd {
         FastBridgeSubCommand::Transfer {
             token,
@@ -68,28 +87,32 @@ pub async fn match_subcommand(cmd: FastBridgeSubCommand, network: Network) {
             valid_till,
             config_cli,
         } => {
+            let recipient = address_book::resolve(&config_cli, recipient)?;
+            let eth_token_address = address_book::resolve(&config_cli, eth_token_address)?;
+
             let valid_till = valid_till.unwrap_or_else(|| {
                 let duration = SystemTime::now()
                     .duration_since(UNIX_EPOCH)
                     .expect("Coudn't calculate valid_till");
                 // 30 minutes as default fast bridge transfer timeout
-                duration.add(Duration::from_secs(60 * 30))
+                duration
+                    .add(Duration::from_secs(60 * 30))
                     .as_secs()
                     .checked_mul(1_000_000_000)
                     .expect("Coudn't calculate valid_till")
             });
 
-            fast_bridge(network, config_cli)
+            fast_bridge(config_cli, config)
+                .await?
                 .transfer(
-                    AccountId::from_str(&token).expect("Invalid token"),
+                    parse::<AccountId>(&token, "--token")?,
                     amount,
                     fee,
-                    Address::from_str(&eth_token_address).expect("Invalid eth_token_address"),
-                    Address::from_str(&recipient).expect("Invalid recipient"),
+                    parse::<Address>(&eth_token_address, "--eth-token-address")?,
+                    parse::<Address>(&recipient, "--recipient")?,
                     valid_till,
                 )
-                .await
-                .unwrap();
+                .await?;
         }
         FastBridgeSubCommand::CompleteTransferOnEth {
             token,
@@ -100,63 +123,105 @@ pub async fn match_subcommand(cmd: FastBridgeSubCommand, network: Network) {
             valid_till_block_height,
             config_cli,
         } => {
-            fast_bridge(network, config_cli)
+            let recipient = address_book::resolve(&config_cli, recipient)?;
+            let unlock_recipient = address_book::resolve(&config_cli, unlock_recipient)?;
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+
+            fast_bridge(config_cli, config)
+                .await?
                 .complete_transfer_on_eth(
-                    Address::from_str(&token).expect("Invalid token"),
-                    Address::from_str(&recipient).expect("Invalid recipient"),
+                    parse::<Address>(&token, "--token")?,
+                    parse::<Address>(&recipient, "--recipient")?,
                     nonce.into(),
                     amount.into(),
                     unlock_recipient,
                     valid_till_block_height.into(),
+                    tx_opts,
                 )
-                .await
-                .unwrap();
+                .await?;
         }
         FastBridgeSubCommand::LpUnlock {
             tx_hash,
             log_index,
             config_cli,
         } => {
-            fast_bridge(network, config_cli)
-                .lp_unlock(
-                    TxHash::from_str(&tx_hash).expect("Invalid tx_hash"),
-                    log_index,
-                )
-                .await
-                .unwrap();
+            fast_bridge(config_cli, config)
+                .await?
+                .lp_unlock(parse::<TxHash>(&tx_hash, "--tx-hash")?, log_index)
+                .await?;
         }
         FastBridgeSubCommand::Withdraw {
             token,
             recipient,
             config_cli,
         } => {
-            fast_bridge(network, config_cli)
+            let recipient = recipient
+                .map(|recipient| address_book::resolve(&config_cli, recipient))
+                .transpose()?;
+
+            fast_bridge(config_cli, config)
+                .await?
                 .withdraw(
-                    AccountId::from_str(&token).expect("Invalid token"),
+                    parse::<AccountId>(&token, "--token")?,
                     None,
-                    recipient.map(|recipient| {
-                        AccountId::from_str(&recipient).expect("Invalid recipient")
-                    }),
+                    recipient
+                        .map(|recipient| parse::<AccountId>(&recipient, "--recipient"))
+                        .transpose()?,
                     None,
                 )
-                .await
-                .unwrap();
+                .await?;
         }
     }
+
+    Ok(())
 }
 
-fn fast_bridge(network: Network, cli_config: CliConfig) -> FastBridge {
-    let combined_config = combined_config(cli_config, network);
+async fn fast_bridge(cli_config: CliConfig, config: &CommandConfig) -> CliResult<FastBridge> {
+    let combined_config = combined_config(cli_config, config.network.clone())?;
+    let near_signer = signer::custom_near_signer(&combined_config).await?;
+    let eth_signer =
+        signer::custom_eth_signer(&combined_config, combined_config.eth_chain_id).await?;
+    let eth_keystore_password = signer::eth_keystore_password(&combined_config)?;
 
-    FastBridgeBuilder::default()
+    let connector = FastBridgeBuilder::default()
         .eth_endpoint(combined_config.eth_rpc)
         .eth_chain_id(combined_config.eth_chain_id)
         .eth_private_key(combined_config.eth_private_key)
+        .eth_keystore_path(combined_config.eth_keystore)
+        .eth_keystore_password(eth_keystore_password)
         .near_endpoint(combined_config.near_rpc)
         .near_private_key(combined_config.near_private_key)
         .near_signer(combined_config.near_signer)
         .fast_bridge_account_id(combined_config.fast_bridge_account_id)
         .fast_bridge_address(combined_config.fast_bridge_address)
+        .disabled_operations(parse(
+            &combined_config.disabled_operations.unwrap_or_default(),
+            "--disabled-operations",
+        )?)
+        .method_names(parse(
+            &combined_config.method_names.unwrap_or_default(),
+            "--method-names",
+        )?)
+        .near_tx_options(NearTxOptions {
+            gas: combined_config.near_gas,
+            deposit: combined_config.near_deposit,
+            proof_chunk_size: combined_config.proof_chunk_size,
+        })
+        .dry_run(combined_config.dry_run)
+        .custom_near_signer(near_signer)
+        .custom_eth_signer(eth_signer)
+        .near_retry_policy(config.near_retry_policy())
+        .near_outcome_timeout(config.near_outcome_timeout())
+        .eth_nonce_journal(config.eth_nonce_journal.clone())
+        .rpc_scheduler(config.rpc_scheduler())
+        .eth_rpc_options(config.eth_rpc_options())
+        .proof_options(config.proof_options())
         .build()
-        .unwrap()
+        .map_err(|e| CliError::Config(e.to_string()))?;
+
+    if config.self_check {
+        log_self_check(connector.self_check().await)?;
+    }
+
+    Ok(connector)
 }
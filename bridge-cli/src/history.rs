@@ -0,0 +1,251 @@
+use crate::{
+    combined_config,
+    error::{CliError, CliResult},
+    CliConfig, Network,
+};
+use ethers::utils::{hex, keccak256};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Time during which re-running a transaction command with the same arguments requires `--force`
+const DEFAULT_RESUBMIT_WINDOW_SEC: u64 = 600;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct HistoryEntry {
+    /// Hash of the network and the command with all its arguments
+    fingerprint: String,
+    /// Unix timestamp in seconds
+    timestamp: u64,
+}
+
+/// Transaction commands run recently, persisted to `--history-file`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CommandHistory {
+    commands: Vec<HistoryEntry>,
+}
+
+impl CommandHistory {
+    fn open(path: &Path) -> CliResult<Self> {
+        match fs::read_to_string(path) {
+            Ok(data) => serde_json::from_str(&data).map_err(|e| {
+                CliError::Config(format!(
+                    "Unable to parse history file {}: {e}",
+                    path.display()
+                ))
+            }),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(CliError::Config(format!(
+                "Unable to read history file {}: {err}",
+                path.display()
+            ))),
+        }
+    }
+
+    fn save(&self, path: &Path) -> CliResult<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| {
+                CliError::Config(format!("Unable to create {}: {e}", dir.display()))
+            })?;
+        }
+
+        let data = serde_json::to_string_pretty(self).expect("History always serializes");
+        fs::write(path, data).map_err(|e| {
+            CliError::Config(format!(
+                "Unable to write history file {}: {e}",
+                path.display()
+            ))
+        })
+    }
+
+    /// Timestamp of the last run of the command, if it is within the window
+    fn last_run(&self, fingerprint: &str, now: u64, window_sec: u64) -> Option<u64> {
+        self.commands
+            .iter()
+            .filter(|entry| entry.fingerprint == fingerprint)
+            .map(|entry| entry.timestamp)
+            .filter(|timestamp| now.saturating_sub(*timestamp) < window_sec)
+            .max()
+    }
+
+    /// Records a run and forgets the runs that fell out of the window
+    fn record(&mut self, fingerprint: String, now: u64, window_sec: u64) {
+        self.commands
+            .retain(|entry| now.saturating_sub(entry.timestamp) < window_sec);
+        self.commands.push(HistoryEntry {
+            fingerprint,
+            timestamp: now,
+        });
+    }
+
+    fn forget(&mut self, fingerprint: &str, timestamp: u64) {
+        self.commands
+            .retain(|entry| entry.fingerprint != fingerprint || entry.timestamp != timestamp);
+    }
+}
+
+/// Guards against sending the same transactions twice, e.g. when a command is replayed from the shell history
+pub struct ResubmissionGuard {
+    path: PathBuf,
+    history: CommandHistory,
+    fingerprint: String,
+    window_sec: u64,
+    recorded_at: Option<u64>,
+}
+
+impl ResubmissionGuard {
+    /// Fails if the same command was run on the same network within the resubmit window, unless `force` is set.
+    /// Returns `None` for dry runs and when the window is 0
+    pub fn check(
+        config_cli: &CliConfig,
+        network: Network,
+        command: &impl Debug,
+        force: bool,
+    ) -> CliResult<Option<Self>> {
+        let config = combined_config(config_cli.clone(), network.clone())?;
+        let window_sec = config
+            .resubmit_window_sec
+            .unwrap_or(DEFAULT_RESUBMIT_WINDOW_SEC);
+        if config.dry_run || window_sec == 0 {
+            return Ok(None);
+        }
+
+        let path = match config.history_file {
+            Some(path) => PathBuf::from(path),
+            None => default_history_file()?,
+        };
+        let history = CommandHistory::open(&path)?;
+        let fingerprint = hex::encode(keccak256(format!("{network:?} {command:?}")));
+
+        let now = unix_now();
+        if let Some(last_run) = history.last_run(&fingerprint, now, window_sec) {
+            let elapsed = now.saturating_sub(last_run);
+            match force {
+                true => tracing::warn!("Resubmitting a command already run {elapsed}s ago"),
+                false => {
+                    return Err(CliError::InvalidArgument(format!(
+                        "The same command was already run {elapsed}s ago. Rerun it with --force to submit it again"
+                    )))
+                }
+            }
+        }
+
+        Ok(Some(Self {
+            path,
+            history,
+            fingerprint,
+            window_sec,
+            recorded_at: None,
+        }))
+    }
+
+    /// Records the command before it runs, so that a command failing after it sent a transaction, e.g. while waiting
+    /// for its outcome, is guarded too
+    pub fn record(&mut self) -> CliResult<()> {
+        let now = unix_now();
+        self.history
+            .record(self.fingerprint.clone(), now, self.window_sec);
+        self.recorded_at = Some(now);
+        self.history.save(&self.path)
+    }
+
+    /// Forgets the recorded run if the command failed before it could send a transaction
+    pub fn finish(mut self, result: &CliResult<()>) -> CliResult<()> {
+        match (result, self.recorded_at) {
+            (Err(err), Some(recorded_at)) if failed_before_submission(err) => {
+                self.history.forget(&self.fingerprint, recorded_at);
+                self.history.save(&self.path)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Only the errors of the command line and signer setup are known to happen before any transaction is sent. Errors
+/// of the connectors may follow a transaction sent by an earlier step, e.g. the deposit of `bridge-near`
+fn failed_before_submission(err: &CliError) -> bool {
+    matches!(
+        err,
+        CliError::Config(_) | CliError::InvalidArgument(_) | CliError::Signer(_)
+    )
+}
+
+fn default_history_file() -> CliResult<PathBuf> {
+    let home = std::env::var_os("HOME").ok_or_else(|| {
+        CliError::Config("HOME is not set, set --history-file instead".to_string())
+    })?;
+
+    Ok(PathBuf::from(home).join(".bridge-cli").join("history.json"))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bridge_connector_common::result::BridgeSdkError;
+
+    #[test]
+    fn last_run_only_sees_the_window() {
+        let mut history = CommandHistory::default();
+        history.record("deposit".to_string(), 1_000, 600);
+
+        assert_eq!(history.last_run("deposit", 1_300, 600), Some(1_000));
+        assert_eq!(history.last_run("deposit", 1_600, 600), None);
+        assert_eq!(history.last_run("withdraw", 1_300, 600), None);
+
+        history.record("withdraw".to_string(), 1_700, 600);
+        assert_eq!(
+            history.commands,
+            vec![HistoryEntry {
+                fingerprint: "withdraw".to_string(),
+                timestamp: 1_700,
+            }]
+        );
+
+        history.forget("withdraw", 1_600);
+        assert_eq!(history.commands.len(), 1);
+        history.forget("withdraw", 1_700);
+        assert!(history.commands.is_empty());
+    }
+
+    #[test]
+    fn guard_keeps_runs_that_may_have_sent_transactions() {
+        let path = std::env::temp_dir().join(format!("cli-history-{}.json", std::process::id()));
+        let guard = |force| {
+            let config_cli = CliConfig {
+                history_file: Some(path.to_string_lossy().into_owned()),
+                ..Default::default()
+            };
+            ResubmissionGuard::check(&config_cli, Network::Testnet, &"deposit", force)
+        };
+
+        let mut first = guard(false).unwrap().unwrap();
+        first.record().unwrap();
+        first
+            .finish(&Err(CliError::InvalidArgument("bad amount".to_string())))
+            .unwrap();
+
+        let mut second = guard(false).unwrap().unwrap();
+        second.record().unwrap();
+        second
+            .finish(&Err(CliError::Sdk(BridgeSdkError::DeadlineExceeded(
+                "outcome".to_string(),
+            ))))
+            .unwrap();
+
+        assert!(guard(false).is_err());
+        assert!(guard(true).unwrap().is_some());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
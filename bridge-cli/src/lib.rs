@@ -0,0 +1,951 @@
+pub use address_book::AddressBookSubCommand;
+pub use aurora_connector_command::AuroraConnectorSubCommand;
+pub use borsh_schema_command::{BorshSchemaArgs, SchemaType};
+use bridge_connector_common::{
+    contract_call::EthTxOptions, registry, rpc_scheduler::RpcScheduler, self_check::SelfCheckReport,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+pub use debug_finalize::DebugFinalizeArgs;
+pub use differential_test::DifferentialTestArgs;
+pub use error::{CliError, CliResult};
+pub use eth_connector_command::EthConnectorSubCommand;
+use eth_proof::{
+    proof_cache::ProofCache,
+    rate_limit::{RateLimit, RateLimiter},
+    EthRPCClientOptions, ProofOptions,
+};
+use ethers_core::{types::U256, utils::parse_units};
+pub use fast_bridge_command::FastBridgeSubCommand;
+use history::ResubmissionGuard;
+use logging::LogConfig;
+pub use nep141_connector_command::Nep141ConnectorSubCommand;
+pub use nft_connector_command::NftConnectorSubCommand;
+pub use permissions::PermissionsArgs;
+pub use relay_command::RelayArgs;
+use serde::Deserialize;
+pub use smoke_test::SmokeTestArgs;
+pub use snapshot::SnapshotArgs;
+use std::{
+    collections::HashMap, env, fs::File, io::BufReader, path::PathBuf, sync::Arc, time::Duration,
+};
+
+mod address_book;
+mod aurora_connector_command;
+mod borsh_schema_command;
+mod debug_finalize;
+mod defaults;
+mod differential_test;
+mod error;
+mod eth_connector_command;
+mod fast_bridge_command;
+mod history;
+pub mod logging;
+mod nep141_connector_command;
+mod nft_connector_command;
+mod permissions;
+mod relay_command;
+mod signer;
+mod smoke_test;
+mod snapshot;
+
+#[derive(Args, Debug, Clone, Deserialize, Default)]
+pub struct CliConfig {
+    /// Ethereum RPC endpoint, or a comma separated list of endpoints failed over to in turn
+    #[arg(long)]
+    pub eth_rpc: Option<String>,
+    #[arg(long)]
+    pub eth_chain_id: Option<u64>,
+    /// Near RPC endpoint, or a comma separated list of endpoints in priority order failed over to in turn
+    #[arg(long)]
+    pub near_rpc: Option<String>,
+    #[arg(long)]
+    pub near_signer: Option<String>,
+    #[arg(long)]
+    pub near_private_key: Option<String>,
+    #[arg(long)]
+    pub near_credentials_file: Option<String>,
+    #[arg(long)]
+    pub eth_private_key: Option<String>,
+    #[arg(long)]
+    pub eth_keystore: Option<String>,
+    #[arg(long)]
+    #[serde(default)]
+    pub near_ledger: bool,
+    #[arg(long)]
+    pub near_ledger_hd_path: Option<String>,
+    #[arg(long)]
+    #[serde(default)]
+    pub eth_ledger: bool,
+    #[arg(long)]
+    pub eth_ledger_account_index: Option<usize>,
+    #[arg(long)]
+    pub eth_kms_key_id: Option<String>,
+    #[arg(long)]
+    pub near_remote_signer_url: Option<String>,
+    #[arg(long)]
+    pub near_remote_signer_public_key: Option<String>,
+    #[arg(long)]
+    pub near_remote_signer_auth_token: Option<String>,
+    #[arg(long)]
+    pub token_locker_id: Option<String>,
+    #[arg(long)]
+    pub bridge_token_factory_address: Option<String>,
+    #[arg(long)]
+    pub near_light_client_eth_address: Option<String>,
+    #[arg(long)]
+    pub eth_light_client_account_id: Option<String>,
+    #[arg(long)]
+    pub wrapped_near_id: Option<String>,
+    #[arg(long)]
+    pub eth_custodian_address: Option<String>,
+    #[arg(long)]
+    pub eth_connector_account_id: Option<String>,
+    #[arg(long)]
+    pub fast_bridge_account_id: Option<String>,
+    #[arg(long)]
+    pub fast_bridge_address: Option<String>,
+    #[arg(long)]
+    pub aurora_rpc: Option<String>,
+    #[arg(long)]
+    pub aurora_chain_id: Option<u64>,
+    #[arg(long)]
+    pub aurora_private_key: Option<String>,
+    #[arg(long)]
+    pub aurora_engine_account_id: Option<String>,
+    #[arg(long)]
+    pub nft_locker_id: Option<String>,
+    #[arg(long)]
+    pub nft_factory_address: Option<String>,
+    /// Near account publishing the current bridge contracts with `get_contracts`. The configured contracts, including
+    /// the defaults, are compared to it before sending transactions, and the ones that differ are warned about
+    #[arg(long)]
+    pub registry_account_id: Option<String>,
+    #[arg(long, value_enum)]
+    pub eth_testnet: Option<EthTestnet>,
+    #[arg(long)]
+    pub max_fee: Option<String>,
+    #[arg(long)]
+    pub priority_fee: Option<String>,
+    #[arg(long)]
+    pub gas_limit: Option<u64>,
+    #[arg(long)]
+    pub wait_confirmations: Option<usize>,
+    /// Gas attached to the Near function calls, overriding the connector defaults
+    #[arg(long)]
+    pub near_gas: Option<u64>,
+    /// Deposit in yoctoNEAR attached to the Near calls paying for storage or fees, e.g. `finalize-withdraw`
+    #[arg(long)]
+    pub near_deposit: Option<u128>,
+    /// Ethereum proofs larger than this many bytes are uploaded to Near in chunks of this size, for contracts
+    /// accepting chunked uploads with `upload_init`, `upload_append` and `upload_finalize`
+    #[arg(long)]
+    pub proof_chunk_size: Option<usize>,
+    #[arg(long)]
+    pub disabled_operations: Option<String>,
+    /// Exchange deposit address patterns flagged as recipients, with `contracts` also flagging Ethereum contracts and
+    /// `strict` rejecting flagged recipients instead of warning, e.g. `*.binance.near,contracts,strict`
+    #[arg(long)]
+    pub recipient_validation: Option<String>,
+    /// Contract methods renamed by a private or forked deployment, e.g.
+    /// `near:withdraw=finish_withdraw,eth:deposit=finishDeposit`
+    #[arg(long)]
+    pub method_names: Option<String>,
+    #[arg(long)]
+    #[serde(default)]
+    pub dry_run: bool,
+    #[arg(long)]
+    #[serde(default)]
+    pub no_light_client_wait: bool,
+    /// Registers the recipient of a withdrawal on the token when it isn't registered yet, paid by the Near signer
+    #[arg(long)]
+    #[serde(default)]
+    pub register_withdraw_recipient: bool,
+    #[arg(long)]
+    pub light_client_sync_timeout_sec: Option<u64>,
+    /// Version of the nep141 connector contracts: `v1`, `omni`, or `auto` to detect it from the deployed contracts
+    #[arg(long)]
+    pub contract_version: Option<String>,
+    #[arg(long)]
+    pub resubmit_window_sec: Option<u64>,
+    #[arg(long)]
+    pub history_file: Option<String>,
+    /// Directory keeping the proofs of failed finalizations, reused by the `retry-finalize-*` commands instead of
+    /// generating them again
+    #[arg(long)]
+    pub proof_store_dir: Option<String>,
+    #[arg(long)]
+    pub config_file: Option<String>,
+    #[arg(skip)]
+    pub log: Option<LogConfig>,
+    /// Named addresses usable in place of any address or account id. Read from the `address_book` section of the
+    /// config file
+    #[arg(skip)]
+    pub address_book: Option<HashMap<String, String>>,
+}
+
+impl CliConfig {
+    fn or(self, other: Self) -> Self {
+        Self {
+            eth_rpc: self.eth_rpc.or(other.eth_rpc),
+            eth_chain_id: self.eth_chain_id.or(other.eth_chain_id),
+            near_rpc: self.near_rpc.or(other.near_rpc),
+            near_signer: self.near_signer.or(other.near_signer),
+            near_private_key: self.near_private_key.or(other.near_private_key),
+            near_credentials_file: self.near_credentials_file.or(other.near_credentials_file),
+            eth_private_key: self.eth_private_key.or(other.eth_private_key),
+            eth_keystore: self.eth_keystore.or(other.eth_keystore),
+            near_ledger: self.near_ledger || other.near_ledger,
+            near_ledger_hd_path: self.near_ledger_hd_path.or(other.near_ledger_hd_path),
+            eth_ledger: self.eth_ledger || other.eth_ledger,
+            eth_ledger_account_index: self
+                .eth_ledger_account_index
+                .or(other.eth_ledger_account_index),
+            eth_kms_key_id: self.eth_kms_key_id.or(other.eth_kms_key_id),
+            near_remote_signer_url: self.near_remote_signer_url.or(other.near_remote_signer_url),
+            near_remote_signer_public_key: self
+                .near_remote_signer_public_key
+                .or(other.near_remote_signer_public_key),
+            near_remote_signer_auth_token: self
+                .near_remote_signer_auth_token
+                .or(other.near_remote_signer_auth_token),
+            token_locker_id: self.token_locker_id.or(other.token_locker_id),
+            bridge_token_factory_address: self
+                .bridge_token_factory_address
+                .or(other.bridge_token_factory_address),
+            near_light_client_eth_address: self
+                .near_light_client_eth_address
+                .or(other.near_light_client_eth_address),
+            eth_light_client_account_id: self
+                .eth_light_client_account_id
+                .or(other.eth_light_client_account_id),
+            wrapped_near_id: self.wrapped_near_id.or(other.wrapped_near_id),
+            eth_custodian_address: self.eth_custodian_address.or(other.eth_custodian_address),
+            eth_connector_account_id: self
+                .eth_connector_account_id
+                .or(other.eth_connector_account_id),
+            fast_bridge_account_id: self.fast_bridge_account_id.or(other.fast_bridge_account_id),
+            fast_bridge_address: self.fast_bridge_address.or(other.fast_bridge_address),
+            aurora_rpc: self.aurora_rpc.or(other.aurora_rpc),
+            aurora_chain_id: self.aurora_chain_id.or(other.aurora_chain_id),
+            aurora_private_key: self.aurora_private_key.or(other.aurora_private_key),
+            aurora_engine_account_id: self
+                .aurora_engine_account_id
+                .or(other.aurora_engine_account_id),
+            nft_locker_id: self.nft_locker_id.or(other.nft_locker_id),
+            nft_factory_address: self.nft_factory_address.or(other.nft_factory_address),
+            registry_account_id: self.registry_account_id.or(other.registry_account_id),
+            eth_testnet: self.eth_testnet.or(other.eth_testnet),
+            max_fee: self.max_fee.or(other.max_fee),
+            priority_fee: self.priority_fee.or(other.priority_fee),
+            gas_limit: self.gas_limit.or(other.gas_limit),
+            near_gas: self.near_gas.or(other.near_gas),
+            near_deposit: self.near_deposit.or(other.near_deposit),
+            proof_chunk_size: self.proof_chunk_size.or(other.proof_chunk_size),
+            wait_confirmations: self.wait_confirmations.or(other.wait_confirmations),
+            disabled_operations: self.disabled_operations.or(other.disabled_operations),
+            recipient_validation: self.recipient_validation.or(other.recipient_validation),
+            method_names: self.method_names.or(other.method_names),
+            dry_run: self.dry_run || other.dry_run,
+            no_light_client_wait: self.no_light_client_wait || other.no_light_client_wait,
+            register_withdraw_recipient: self.register_withdraw_recipient
+                || other.register_withdraw_recipient,
+            light_client_sync_timeout_sec: self
+                .light_client_sync_timeout_sec
+                .or(other.light_client_sync_timeout_sec),
+            contract_version: self.contract_version.or(other.contract_version),
+            resubmit_window_sec: self.resubmit_window_sec.or(other.resubmit_window_sec),
+            history_file: self.history_file.or(other.history_file),
+            proof_store_dir: self.proof_store_dir.or(other.proof_store_dir),
+            config_file: self.config_file.or(other.config_file),
+            log: self.log.or(other.log),
+            address_book: self.address_book.or(other.address_book),
+        }
+    }
+}
+
+fn env_config() -> CliConfig {
+    CliConfig {
+        eth_rpc: env::var("ETH_RPC").ok(),
+        eth_chain_id: env::var("ETH_CHAIN_ID")
+            .ok()
+            .and_then(|val| val.parse::<u64>().ok()),
+        near_rpc: env::var("NEAR_RPC").ok(),
+        near_signer: env::var("NEAR_SIGNER").ok(),
+        near_private_key: env::var("NEAR_PRIVATE_KEY").ok(),
+        near_credentials_file: env::var("NEAR_CREDENTIALS_FILE").ok(),
+        eth_private_key: env::var("ETH_PRIVATE_KEY").ok(),
+        eth_keystore: env::var("ETH_KEYSTORE").ok(),
+        near_ledger: env::var("NEAR_LEDGER").is_ok_and(|val| val == "true"),
+        near_ledger_hd_path: env::var("NEAR_LEDGER_HD_PATH").ok(),
+        eth_ledger: env::var("ETH_LEDGER").is_ok_and(|val| val == "true"),
+        eth_ledger_account_index: env::var("ETH_LEDGER_ACCOUNT_INDEX")
+            .ok()
+            .and_then(|val| val.parse::<usize>().ok()),
+        eth_kms_key_id: env::var("ETH_KMS_KEY_ID").ok(),
+        near_remote_signer_url: env::var("NEAR_REMOTE_SIGNER_URL").ok(),
+        near_remote_signer_public_key: env::var("NEAR_REMOTE_SIGNER_PUBLIC_KEY").ok(),
+        near_remote_signer_auth_token: env::var("NEAR_REMOTE_SIGNER_AUTH_TOKEN").ok(),
+        token_locker_id: env::var("TOKEN_LOCKER_ID").ok(),
+        bridge_token_factory_address: env::var("BRIDGE_TOKEN_FACTORY_ADDRESS").ok(),
+        near_light_client_eth_address: env::var("NEAR_LIGHT_CLIENT_ADDRESS").ok(),
+        eth_light_client_account_id: env::var("ETH_LIGHT_CLIENT_ACCOUNT_ID").ok(),
+        wrapped_near_id: env::var("WRAPPED_NEAR_ID").ok(),
+        eth_custodian_address: env::var("ETH_CUSTODIAN_ADDRESS").ok(),
+        eth_connector_account_id: env::var("ETH_CONNECTOR_ACCOUNT_ID").ok(),
+        fast_bridge_account_id: env::var("FAST_BRIDGE_ACCOUNT_ID").ok(),
+        fast_bridge_address: env::var("FAST_BRIDGE_ADDRESS").ok(),
+        aurora_rpc: env::var("AURORA_RPC").ok(),
+        aurora_chain_id: env::var("AURORA_CHAIN_ID")
+            .ok()
+            .and_then(|val| val.parse::<u64>().ok()),
+        aurora_private_key: env::var("AURORA_PRIVATE_KEY").ok(),
+        aurora_engine_account_id: env::var("AURORA_ENGINE_ACCOUNT_ID").ok(),
+        nft_locker_id: env::var("NFT_LOCKER_ID").ok(),
+        nft_factory_address: env::var("NFT_FACTORY_ADDRESS").ok(),
+        registry_account_id: env::var("REGISTRY_ACCOUNT_ID").ok(),
+        eth_testnet: env::var("ETH_TESTNET")
+            .ok()
+            .and_then(|val| EthTestnet::from_str(&val, true).ok()),
+        max_fee: env::var("ETH_MAX_FEE").ok(),
+        priority_fee: env::var("ETH_PRIORITY_FEE").ok(),
+        gas_limit: env::var("ETH_GAS_LIMIT")
+            .ok()
+            .and_then(|val| val.parse::<u64>().ok()),
+        wait_confirmations: env::var("ETH_WAIT_CONFIRMATIONS")
+            .ok()
+            .and_then(|val| val.parse::<usize>().ok()),
+        near_gas: env::var("NEAR_GAS")
+            .ok()
+            .and_then(|val| val.parse::<u64>().ok()),
+        near_deposit: env::var("NEAR_DEPOSIT")
+            .ok()
+            .and_then(|val| val.parse::<u128>().ok()),
+        proof_chunk_size: env::var("PROOF_CHUNK_SIZE")
+            .ok()
+            .and_then(|val| val.parse::<usize>().ok()),
+        disabled_operations: env::var("DISABLED_OPERATIONS").ok(),
+        recipient_validation: env::var("RECIPIENT_VALIDATION").ok(),
+        method_names: env::var("METHOD_NAMES").ok(),
+        dry_run: env::var("DRY_RUN").is_ok_and(|val| val == "true"),
+        no_light_client_wait: env::var("NO_LIGHT_CLIENT_WAIT").is_ok_and(|val| val == "true"),
+        register_withdraw_recipient: env::var("REGISTER_WITHDRAW_RECIPIENT")
+            .is_ok_and(|val| val == "true"),
+        light_client_sync_timeout_sec: env::var("LIGHT_CLIENT_SYNC_TIMEOUT_SEC")
+            .ok()
+            .and_then(|val| val.parse::<u64>().ok()),
+        contract_version: env::var("CONTRACT_VERSION").ok(),
+        resubmit_window_sec: env::var("RESUBMIT_WINDOW_SEC")
+            .ok()
+            .and_then(|val| val.parse::<u64>().ok()),
+        history_file: env::var("HISTORY_FILE").ok(),
+        proof_store_dir: env::var("PROOF_STORE_DIR").ok(),
+        config_file: None,
+        log: None,
+        address_book: None,
+    }
+}
+
+fn default_config(network: Network, eth_testnet: EthTestnet) -> CliConfig {
+    match network {
+        Network::Mainnet => CliConfig {
+            eth_rpc: Some(defaults::ETH_RPC_MAINNET.to_owned()),
+            eth_chain_id: Some(defaults::ETH_CHAIN_ID_MAINNET),
+            near_rpc: Some(defaults::NEAR_RPC_MAINNET.to_owned()),
+            near_signer: None,
+            near_private_key: None,
+            near_credentials_file: None,
+            eth_private_key: None,
+            eth_keystore: None,
+            near_ledger: false,
+            near_ledger_hd_path: None,
+            eth_ledger: false,
+            eth_ledger_account_index: None,
+            eth_kms_key_id: None,
+            near_remote_signer_url: None,
+            near_remote_signer_public_key: None,
+            near_remote_signer_auth_token: None,
+            token_locker_id: Some(defaults::TOKEN_LOCKER_ID_MAINNET.to_owned()),
+            bridge_token_factory_address: Some(
+                defaults::BRIDGE_TOKEN_FACTORY_ADDRESS_MAINNET.to_owned(),
+            ),
+            near_light_client_eth_address: Some(
+                defaults::NEAR_LIGHT_CLIENT_ETH_ADDRESS_MAINNET.to_owned(),
+            ),
+            eth_light_client_account_id: Some(
+                defaults::ETH_LIGHT_CLIENT_ACCOUNT_ID_MAINNET.to_owned(),
+            ),
+            wrapped_near_id: Some(defaults::WRAPPED_NEAR_ID_MAINNET.to_owned()),
+            eth_connector_account_id: Some(defaults::ETH_CONNECTOR_ACCOUNT_ID_MAINNET.to_owned()),
+            eth_custodian_address: Some(defaults::ETH_CUSTODIAN_ADDRESS_MAINNET.to_owned()),
+            fast_bridge_account_id: Some(defaults::FAST_BRIDGE_ACCOUNT_ID_MAINNET.to_owned()),
+            fast_bridge_address: Some(defaults::FAST_BRIDGE_ADDRESS_MAINNET.to_owned()),
+            aurora_rpc: Some(defaults::AURORA_RPC_MAINNET.to_owned()),
+            aurora_chain_id: Some(defaults::AURORA_CHAIN_ID_MAINNET),
+            aurora_private_key: None,
+            aurora_engine_account_id: Some(defaults::AURORA_ENGINE_ACCOUNT_ID_MAINNET.to_owned()),
+            nft_locker_id: None,
+            nft_factory_address: None,
+            registry_account_id: None,
+            eth_testnet: None,
+            max_fee: None,
+            priority_fee: None,
+            gas_limit: None,
+            near_gas: None,
+            near_deposit: None,
+            proof_chunk_size: None,
+            wait_confirmations: None,
+            disabled_operations: None,
+            recipient_validation: None,
+            method_names: None,
+            dry_run: false,
+            no_light_client_wait: false,
+            register_withdraw_recipient: false,
+            light_client_sync_timeout_sec: None,
+            contract_version: None,
+            resubmit_window_sec: None,
+            history_file: None,
+            proof_store_dir: None,
+            config_file: None,
+            log: None,
+            address_book: None,
+        },
+        Network::Testnet => match eth_testnet {
+            EthTestnet::Sepolia => CliConfig {
+                eth_rpc: Some(defaults::ETH_RPC_TESTNET.to_owned()),
+                eth_chain_id: Some(defaults::ETH_CHAIN_ID_TESTNET),
+                near_rpc: Some(defaults::NEAR_RPC_TESTNET.to_owned()),
+                near_signer: None,
+                near_private_key: None,
+                near_credentials_file: None,
+                eth_private_key: None,
+                eth_keystore: None,
+                near_ledger: false,
+                near_ledger_hd_path: None,
+                eth_ledger: false,
+                eth_ledger_account_index: None,
+                eth_kms_key_id: None,
+                near_remote_signer_url: None,
+                near_remote_signer_public_key: None,
+                near_remote_signer_auth_token: None,
+                token_locker_id: Some(defaults::TOKEN_LOCKER_ID_TESTNET.to_owned()),
+                bridge_token_factory_address: Some(
+                    defaults::BRIDGE_TOKEN_FACTORY_ADDRESS_TESTNET.to_owned(),
+                ),
+                near_light_client_eth_address: Some(
+                    defaults::NEAR_LIGHT_CLIENT_ETH_ADDRESS_TESTNET.to_owned(),
+                ),
+                eth_light_client_account_id: Some(
+                    defaults::ETH_LIGHT_CLIENT_ACCOUNT_ID_TESTNET.to_owned(),
+                ),
+                wrapped_near_id: Some(defaults::WRAPPED_NEAR_ID_TESTNET.to_owned()),
+                eth_connector_account_id: Some(
+                    defaults::ETH_CONNECTOR_ACCOUNT_ID_TESTNET.to_owned(),
+                ),
+                eth_custodian_address: Some(defaults::ETH_CUSTODIAN_ADDRESS_TESTNET.to_owned()),
+                fast_bridge_account_id: Some(defaults::FAST_BRIDGE_ACCOUNT_ID_TESTNET.to_owned()),
+                fast_bridge_address: Some(defaults::FAST_BRIDGE_ADDRESS_TESTNET.to_owned()),
+                aurora_rpc: Some(defaults::AURORA_RPC_TESTNET.to_owned()),
+                aurora_chain_id: Some(defaults::AURORA_CHAIN_ID_TESTNET),
+                aurora_private_key: None,
+                aurora_engine_account_id: Some(
+                    defaults::AURORA_ENGINE_ACCOUNT_ID_TESTNET.to_owned(),
+                ),
+                nft_locker_id: None,
+                nft_factory_address: None,
+                registry_account_id: None,
+                eth_testnet: None,
+                max_fee: None,
+                priority_fee: None,
+                gas_limit: None,
+                near_gas: None,
+                near_deposit: None,
+                proof_chunk_size: None,
+                wait_confirmations: None,
+                disabled_operations: None,
+                recipient_validation: None,
+                method_names: None,
+                dry_run: false,
+                no_light_client_wait: false,
+                register_withdraw_recipient: false,
+                light_client_sync_timeout_sec: None,
+                contract_version: None,
+                resubmit_window_sec: None,
+                history_file: None,
+                proof_store_dir: None,
+                config_file: None,
+                log: None,
+                address_book: None,
+            },
+        },
+    }
+}
+
+fn file_config(path: &str) -> CliResult<CliConfig> {
+    let file = File::open(path)
+        .map_err(|e| CliError::Config(format!("Unable to open config file {path}: {e}")))?;
+    let reader = BufReader::new(file);
+
+    serde_json::from_reader(reader)
+        .map_err(|e| CliError::Config(format!("Unable to parse config file {path}: {e}")))
+}
+
+fn combined_config(cli_config: CliConfig, network: Network) -> CliResult<CliConfig> {
+    let file_config = match &cli_config.config_file {
+        Some(path) => file_config(path)?,
+        None => CliConfig::default(),
+    };
+
+    let config = cli_config.or(env_config()).or(file_config);
+    let eth_testnet = config.eth_testnet.clone().unwrap_or_default();
+
+    Ok(config.or(default_config(network, eth_testnet)))
+}
+
+/// Operation of the CLI. Orchestration systems can build and [`Command::execute`] one directly instead of running
+/// the binary
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    Nep141Connector {
+        #[clap(subcommand)]
+        cmd: Nep141ConnectorSubCommand,
+    },
+    EthConnector {
+        #[clap(subcommand)]
+        cmd: EthConnectorSubCommand,
+    },
+    FastBridge {
+        #[clap(subcommand)]
+        cmd: FastBridgeSubCommand,
+    },
+    AuroraConnector {
+        #[clap(subcommand)]
+        cmd: AuroraConnectorSubCommand,
+    },
+    NftConnector {
+        #[clap(subcommand)]
+        cmd: NftConnectorSubCommand,
+    },
+    /// Watches NEP-141 transfers in both directions and finalizes them once they can be proven
+    Relay {
+        #[command(flatten)]
+        args: RelayArgs,
+    },
+    /// Manages the named addresses of the config file
+    AddressBook {
+        #[clap(subcommand)]
+        cmd: AddressBookSubCommand,
+    },
+    /// Bridges a small amount of a NEP-141 token to Ethereum and back, reporting the outcome of each step
+    SmokeTest {
+        #[command(flatten)]
+        args: SmokeTestArgs,
+    },
+    /// Bridges the same amount of a NEP-141 token to Ethereum through the legacy and the omni flows, and compares the
+    /// balances and events of the two transfers
+    DifferentialTest {
+        #[command(flatten)]
+        args: DifferentialTestArgs,
+    },
+    /// Records locked balances, minted supplies, light client heights and relayer balances to a timestamped JSON
+    /// file
+    Snapshot {
+        #[command(flatten)]
+        args: SnapshotArgs,
+    },
+    /// Replays the proof checks of a finalization on Near at the blocks it used, reporting a root mismatch, a wrong
+    /// log or a stale light client
+    DebugFinalize {
+        #[command(flatten)]
+        args: DebugFinalizeArgs,
+    },
+    /// Reports what the configured keys are allowed to do, to verify they hold no more permissions than bridge
+    /// operations need
+    Permissions {
+        #[command(flatten)]
+        args: PermissionsArgs,
+    },
+    /// Prints the borsh schemas of the bridge types as JSON, for implementations that need to stay byte-compatible
+    /// with the SDK encodings
+    BorshSchema {
+        #[command(flatten)]
+        args: BorshSchemaArgs,
+    },
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+pub enum Network {
+    Mainnet,
+    Testnet,
+}
+
+/// Ethereum network used together with NEAR testnet. Only testnets with the bridge contracts deployed have a preset
+#[derive(ValueEnum, Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EthTestnet {
+    #[default]
+    Sepolia,
+}
+
+/// Arguments of the `bridge-cli` binary
+#[derive(Parser, Debug)]
+#[clap(version)]
+pub struct Arguments {
+    #[command(flatten)]
+    pub config: CommandConfig,
+    #[command(subcommand)]
+    pub cmd: Command,
+}
+
+/// Settings of a [`Command`] besides its own arguments: the network and the global flags of the CLI
+#[derive(Args, Debug, Clone)]
+pub struct CommandConfig {
+    pub network: Network,
+    /// Verify RPC reachability, contract deployments and signer balances before running the command
+    #[arg(long, global = true)]
+    pub self_check: bool,
+    /// Send the transactions even if the same command was already run within `--resubmit-window-sec`
+    #[arg(long, global = true)]
+    pub force: bool,
+    /// Append the duration, block receipt count and trie size of the generated Ethereum proofs to this file as JSON
+    /// lines
+    #[arg(long, global = true)]
+    pub proof_metrics_file: Option<PathBuf>,
+    /// Attempts of an RPC request failing with a timeout, rate limiting or an unavailable node, including the first
+    /// one. 1 disables the retries
+    #[arg(long, global = true, default_value_t = 3)]
+    pub rpc_retry_attempts: u32,
+    /// Delay before the first retry of an RPC request, doubled with each retry
+    #[arg(long, global = true, default_value_t = 500)]
+    pub rpc_retry_delay_ms: u64,
+    /// Fraction of the retry delay that is randomized
+    #[arg(long, global = true, default_value_t = 0.2)]
+    pub rpc_retry_jitter: f64,
+    /// Requests per second sent to the Ethereum RPC by proof generation and the other Ethereum RPC reads, e.g. to
+    /// stay under the limit of an API key. Unlimited if unset
+    #[arg(long, global = true)]
+    pub eth_rpc_rate_limit: Option<f64>,
+    /// Keeps the blocks of the generated Ethereum proofs in memory and in this directory, so that proving other
+    /// events of the same blocks doesn't fetch them again
+    #[arg(long, global = true)]
+    pub proof_cache_dir: Option<PathBuf>,
+    /// Strict mode for Ethereum transactions: the transactions of a signer are sent one at a time, each one recorded
+    /// in this journal before it's broadcast and awaited until mined. A transaction left pending by a crash is
+    /// broadcast again instead of sending a new one. The journal must not be shared by several processes
+    #[arg(long, global = true)]
+    pub eth_nonce_journal: Option<PathBuf>,
+    /// Waits up to this many seconds for each Near transaction to be executed, failing with the contract error if it
+    /// panicked, e.g. on insufficient storage deposit. 0 only broadcasts the Near transactions
+    #[arg(long, global = true, default_value_t = near_rpc_client::DEFAULT_WAIT_FINAL_OUTCOME_TIMEOUT_SEC)]
+    pub near_outcome_timeout_sec: u64,
+    /// Scheduler of the RPC requests of the connectors, set by the commands with their own limits, e.g. `relay`
+    #[arg(skip)]
+    pub rpc_scheduler: Option<Arc<RpcScheduler>>,
+}
+
+impl CommandConfig {
+    /// Retries of the Near requests of the connectors
+    fn near_retry_policy(&self) -> near_rpc_client::retry::RetryPolicy {
+        near_rpc_client::retry::RetryPolicy {
+            attempts: self.rpc_retry_attempts.max(1),
+            base_delay: Duration::from_millis(self.rpc_retry_delay_ms),
+            jitter: self.rpc_retry_jitter,
+            ..Default::default()
+        }
+    }
+
+    /// Time the connectors wait for the outcome of each Near transaction
+    fn near_outcome_timeout(&self) -> Option<Duration> {
+        match self.near_outcome_timeout_sec {
+            0 => None,
+            timeout_sec => Some(Duration::from_secs(timeout_sec)),
+        }
+    }
+
+    /// Scheduler of the RPC requests of the connectors, the shared one with the default limits if unset
+    fn rpc_scheduler(&self) -> Arc<RpcScheduler> {
+        self.rpc_scheduler
+            .clone()
+            .unwrap_or_else(RpcScheduler::shared)
+    }
+
+    /// Retries and rate limit of the Ethereum RPC clients of the connectors
+    fn eth_rpc_options(&self) -> EthRPCClientOptions {
+        EthRPCClientOptions {
+            retry_policy: eth_proof::retry::RetryPolicy {
+                attempts: self.rpc_retry_attempts.max(1),
+                base_delay: Duration::from_millis(self.rpc_retry_delay_ms),
+                jitter: self.rpc_retry_jitter,
+                ..Default::default()
+            },
+            rate_limiter: self
+                .eth_rpc_rate_limit
+                .map(|rate| Arc::new(RateLimiter::new(RateLimit::per_second(rate)))),
+            ..Default::default()
+        }
+    }
+
+    /// Block cache and metrics of the Ethereum proofs generated by the connectors
+    fn proof_options(&self) -> ProofOptions {
+        ProofOptions {
+            cache: self
+                .proof_cache_dir
+                .clone()
+                .map(|dir| Arc::new(ProofCache::default().with_dir(dir))),
+            metrics_file: self.proof_metrics_file.clone(),
+        }
+    }
+}
+
+impl Command {
+    /// Runs the command the way the CLI does, with the same config resolution, validation, resubmission guard and
+    /// outcome. Errors are returned rather than logged, see [`CliError::exit_code`] for the exit code of the CLI
+    pub async fn execute(self, config: &CommandConfig) -> CliResult<()> {
+        let network = config.network.clone();
+        let mut resubmission_guard = match transaction_command(&self) {
+            Some(config_cli) => {
+                ResubmissionGuard::check(config_cli, network.clone(), &self, config.force)?
+            }
+            None => None,
+        };
+        if let Some(config_cli) = transaction_command(&self) {
+            warn_config_drift(config_cli, network.clone()).await?;
+        }
+
+        if let Some(guard) = &mut resubmission_guard {
+            guard.record()?;
+        }
+
+        // The Near requests made outside of a connector, e.g. by the light client checks, retry as configured too
+        let result =
+            near_rpc_client::retry::scope(config.near_retry_policy(), self.run(config)).await;
+
+        // The error of the command matters more than a history that couldn't be updated
+        if let Some(Err(err)) = resubmission_guard.map(|guard| guard.finish(&result)) {
+            tracing::warn!("Failed to update the command history: {err}");
+        }
+        result
+    }
+
+    async fn run(self, config: &CommandConfig) -> CliResult<()> {
+        match self {
+            Command::Nep141Connector { cmd } => {
+                nep141_connector_command::match_subcommand(cmd, config).await
+            }
+            Command::EthConnector { cmd } => {
+                eth_connector_command::match_subcommand(cmd, config).await
+            }
+            Command::FastBridge { cmd } => fast_bridge_command::match_subcommand(cmd, config).await,
+            Command::AuroraConnector { cmd } => {
+                aurora_connector_command::match_subcommand(cmd, config).await
+            }
+            Command::NftConnector { cmd } => {
+                nft_connector_command::match_subcommand(cmd, config).await
+            }
+            Command::Relay { args } => relay_command::run(args, config).await,
+            Command::SmokeTest { args } => smoke_test::run(args, config).await,
+            Command::DifferentialTest { args } => differential_test::run(args, config).await,
+            Command::Snapshot { args } => snapshot::run(args, config).await,
+            Command::AddressBook { cmd } => address_book::match_subcommand(cmd),
+            Command::DebugFinalize { args } => debug_finalize::run(args, config).await,
+            Command::Permissions { args } => permissions::run(args, config).await,
+            Command::BorshSchema { args } => {
+                borsh_schema_command::run(args);
+                Ok(())
+            }
+        }
+    }
+
+    /// Logging set up by the CLI for the command, if it has its own
+    pub fn log_config(&self, network: Network) -> Option<LogConfig> {
+        match self {
+            Command::Relay { args } => relay_command::log_config(args, network),
+            _ => None,
+        }
+    }
+}
+
+/// Config of the commands sending transactions, which are guarded against an accidental resubmission
+fn transaction_command(cmd: &Command) -> Option<&CliConfig> {
+    match cmd {
+        Command::Nep141Connector { cmd } if cmd.sends_transactions() => Some(cmd.config_cli()),
+        Command::EthConnector { cmd } => Some(cmd.config_cli()),
+        Command::FastBridge { cmd } => Some(cmd.config_cli()),
+        Command::AuroraConnector { cmd } => Some(cmd.config_cli()),
+        Command::NftConnector { cmd } => Some(cmd.config_cli()),
+        _ => None,
+    }
+}
+
+/// Warns about the configured contracts that differ from the ones published by the registry account, e.g. defaults
+/// rotated by a bridge upgrade. A registry that can't be read is only warned about too
+async fn warn_config_drift(config_cli: &CliConfig, network: Network) -> CliResult<()> {
+    let config = combined_config(config_cli.clone(), network)?;
+    let (Some(near_rpc), Some(registry_account_id)) =
+        (&config.near_rpc, &config.registry_account_id)
+    else {
+        return Ok(());
+    };
+
+    let configured = [
+        ("token_locker_id", &config.token_locker_id),
+        (
+            "bridge_token_factory_address",
+            &config.bridge_token_factory_address,
+        ),
+        (
+            "near_light_client_eth_address",
+            &config.near_light_client_eth_address,
+        ),
+        (
+            "eth_light_client_account_id",
+            &config.eth_light_client_account_id,
+        ),
+        ("wrapped_near_id", &config.wrapped_near_id),
+        ("eth_custodian_address", &config.eth_custodian_address),
+        ("eth_connector_account_id", &config.eth_connector_account_id),
+        ("fast_bridge_account_id", &config.fast_bridge_account_id),
+        ("fast_bridge_address", &config.fast_bridge_address),
+        ("aurora_engine_account_id", &config.aurora_engine_account_id),
+        ("nft_locker_id", &config.nft_locker_id),
+        ("nft_factory_address", &config.nft_factory_address),
+    ];
+    let configured = configured
+        .iter()
+        .filter_map(|(name, value)| Some((*name, value.as_deref()?)));
+
+    match registry::check_drift(near_rpc, registry_account_id, configured).await {
+        Ok(drift) => {
+            for drift in drift {
+                tracing::warn!("Configured contract differs from the registry: {drift}");
+            }
+        }
+        Err(err) => {
+            tracing::warn!("Failed to read the contract registry {registry_account_id}: {err}")
+        }
+    }
+
+    Ok(())
+}
+
+/// Gas settings of the Ethereum transactions sent by a command. Fees are given in gwei
+fn eth_tx_options(cli_config: &CliConfig, network: Network) -> CliResult<EthTxOptions> {
+    let config = combined_config(cli_config.clone(), network)?;
+    let parse_fee = |fee: String| -> CliResult<U256> {
+        parse_units(&fee, "gwei")
+            .map(Into::into)
+            .map_err(|_| CliError::InvalidArgument(format!("Invalid fee `{fee}`, expected gwei")))
+    };
+
+    Ok(EthTxOptions {
+        gas_limit: config.gas_limit.map(U256::from),
+        confirmations: config.wait_confirmations,
+        max_fee_per_gas: config.max_fee.map(parse_fee).transpose()?,
+        max_priority_fee_per_gas: config.priority_fee.map(parse_fee).transpose()?,
+        ..EthTxOptions::default()
+    })
+}
+
+fn log_self_check(report: SelfCheckReport) -> CliResult<()> {
+    tracing::info!("Self-check report:\n{report}");
+
+    match report.is_ok() {
+        true => Ok(()),
+        false => Err(CliError::Failed("Self-check failed".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_arguments_parse_into_command() {
+        Arguments::command().debug_assert();
+
+        let args = Arguments::try_parse_from([
+            "bridge-cli",
+            "testnet",
+            "--self-check",
+            "nep141-connector",
+            "log-metadata",
+            "--token",
+            "wrap.testnet",
+        ])
+        .unwrap();
+
+        assert!(matches!(args.config.network, Network::Testnet));
+        assert!(args.config.self_check);
+        assert!(matches!(
+            args.cmd,
+            Command::Nep141Connector {
+                cmd: Nep141ConnectorSubCommand::LogMetadata { ref token, .. }
+            } if token == "wrap.testnet"
+        ));
+    }
+
+    #[test]
+    fn test_global_flags_map_to_connector_options() {
+        let args = Arguments::try_parse_from([
+            "bridge-cli",
+            "testnet",
+            "--rpc-retry-attempts",
+            "0",
+            "--eth-rpc-rate-limit",
+            "5",
+            "--proof-cache-dir",
+            "proofs",
+            "--near-outcome-timeout-sec",
+            "0",
+            "nep141-connector",
+            "log-metadata",
+            "--token",
+            "wrap.testnet",
+        ])
+        .unwrap();
+
+        // A request is always sent at least once
+        assert_eq!(args.config.near_retry_policy().attempts, 1);
+
+        let eth_rpc_options = args.config.eth_rpc_options();
+        assert_eq!(eth_rpc_options.retry_policy.attempts, 1);
+        assert_eq!(
+            eth_rpc_options.rate_limiter.unwrap().rate_limit(),
+            RateLimit::per_second(5.0)
+        );
+
+        let proof_options = args.config.proof_options();
+        assert!(proof_options.cache.is_some());
+        assert!(proof_options.metrics_file.is_none());
+
+        assert_eq!(args.config.near_outcome_timeout(), None);
+
+        // Near transactions are awaited by default
+        let args = Arguments::try_parse_from(["bridge-cli", "testnet", "borsh-schema"]).unwrap();
+        assert_eq!(
+            args.config.near_outcome_timeout(),
+            Some(Duration::from_secs(
+                near_rpc_client::DEFAULT_WAIT_FINAL_OUTCOME_TIMEOUT_SEC
+            ))
+        );
+    }
+
+    #[test]
+    fn test_only_commands_sending_transactions_are_guarded() {
+        let command = |subcommand| {
+            Arguments::try_parse_from([
+                "bridge-cli",
+                "testnet",
+                "nep141-connector",
+                subcommand,
+                "--receipt-id",
+                "8kPDBQ6bFNV9UGyvYxnsTn5ufkmuEeeWhFaCqkDuuphv",
+            ])
+            .unwrap()
+            .cmd
+        };
+
+        assert!(transaction_command(&command("finalize-deposit")).is_some());
+        assert!(transaction_command(&command("simulate-finalize-deposit")).is_none());
+    }
+}
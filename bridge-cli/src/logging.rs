@@ -0,0 +1,138 @@
+use crate::error::{CliError, CliResult};
+use rolling_file::{BasicRollingFileAppender, RollingConditionBasic};
+use serde::Deserialize;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::{
+    field::MakeExt, fmt::format, layer::SubscriberExt, EnvFilter, Layer, Registry,
+};
+
+/// Log sinks used in addition to stdout by long-running commands. Read from the `log` section of the config file
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct LogConfig {
+    pub file: Option<LogFileConfig>,
+    /// Sends the logs to the systemd journal
+    #[serde(default)]
+    pub journald: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LogFileConfig {
+    /// Path of the active log file. Rotated files get a numeric suffix, e.g. `relayer.log.1`
+    pub path: String,
+    /// Rotates the file every hour or every day
+    pub rotation: Option<LogRotation>,
+    /// Rotates the file once it grows over the given number of megabytes
+    pub max_size_mb: Option<u64>,
+    /// Number of rotated files kept
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Hourly,
+    Daily,
+}
+
+/// Crates logged at debug level whatever the `RUST_LOG` filter
+const DEBUG_CRATES: &[&str] = &[
+    "nep141_connector",
+    "eth_connector",
+    "fast_bridge",
+    "aurora_connector",
+    "nft_connector",
+];
+
+fn default_max_files() -> usize {
+    7
+}
+
+/// Returns a guard that flushes the log file when dropped, so it has to be kept alive until the program exits. Fails
+/// if a configured sink can't be opened
+pub fn init_logger(log_config: Option<&LogConfig>) -> CliResult<Option<WorkerGuard>> {
+    let field_formatter = format::debug_fn(|writer, field, value| match field.name() {
+        "message" => write!(writer, "{:?}", value),
+        _ => write!(writer, "{}={:?}", field, value),
+    })
+    .display_messages()
+    .delimited("\n");
+
+    let mut env_filter = EnvFilter::builder()
+        .with_default_directive(LevelFilter::INFO.into())
+        .from_env_lossy();
+    for name in DEBUG_CRATES {
+        let directive = format!("{name}=debug")
+            .parse()
+            .map_err(|err| CliError::Failed(format!("Invalid log filter for {name}: {err}")))?;
+        env_filter = env_filter.add_directive(directive);
+    }
+
+    let mut layers: Vec<Box<dyn Layer<Registry> + Send + Sync>> =
+        vec![tracing_subscriber::fmt::layer()
+            .with_file(false)
+            .with_target(false)
+            .with_line_number(false)
+            .with_level(false)
+            .fmt_fields(field_formatter)
+            .boxed()];
+
+    let mut guard = None;
+    if let Some(file_config) = log_config.and_then(|config| config.file.as_ref()) {
+        let (writer, file_guard) = tracing_appender::non_blocking(file_appender(file_config)?);
+        layers.push(
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(writer)
+                .boxed(),
+        );
+        guard = Some(file_guard);
+    }
+
+    if log_config.is_some_and(|config| config.journald) {
+        layers.push(journald_layer()?);
+    }
+
+    let subscriber = Registry::default().with(layers).with(env_filter);
+
+    tracing::subscriber::set_global_default(subscriber)
+        .map_err(|err| CliError::Failed(format!("Unable to set up logging: {err}")))?;
+
+    Ok(guard)
+}
+
+fn file_appender(file_config: &LogFileConfig) -> CliResult<BasicRollingFileAppender> {
+    let mut condition = RollingConditionBasic::new();
+    condition = match file_config.rotation {
+        Some(LogRotation::Hourly) => condition.hourly(),
+        Some(LogRotation::Daily) => condition.daily(),
+        None => condition,
+    };
+    if let Some(max_size_mb) = file_config.max_size_mb {
+        condition = condition.max_size(max_size_mb * 1024 * 1024);
+    }
+
+    BasicRollingFileAppender::new(&file_config.path, condition, file_config.max_files).map_err(
+        |err| {
+            CliError::Config(format!(
+                "Unable to open log file {}: {err}",
+                file_config.path
+            ))
+        },
+    )
+}
+
+#[cfg(target_os = "linux")]
+fn journald_layer() -> CliResult<Box<dyn Layer<Registry> + Send + Sync>> {
+    Ok(tracing_journald::layer()
+        .map_err(|err| CliError::Config(format!("Unable to connect to journald: {err}")))?
+        .boxed())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn journald_layer() -> CliResult<Box<dyn Layer<Registry> + Send + Sync>> {
+    Err(CliError::Config(
+        "journald logging is only supported on Linux".to_string(),
+    ))
+}
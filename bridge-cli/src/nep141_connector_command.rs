@@ -1,8 +1,22 @@
-use crate::{combined_config, CliConfig, Network};
+use crate::{
+    address_book, combined_config,
+    error::{parse, CliError, CliResult},
+    eth_tx_options, log_self_check, signer, CliConfig, CommandConfig,
+};
+use bridge_connector_common::{
+    bridge_connector::{TransferId, DEFAULT_LIGHT_CLIENT_SYNC_TIMEOUT},
+    contract_call::NearTxOptions,
+    proof_store::ProofStore,
+    token_amount::TokenAmount,
+};
 use clap::Subcommand;
 use ethers_core::types::TxHash;
-use nep141_connector::{Nep141Connector, Nep141ConnectorBuilder};
-use std::str::FromStr;
+use near_primitives::types::AccountId;
+use nep141_connector::{ContractVersion, Nep141Connector, Nep141ConnectorBuilder};
+use std::time::Duration;
+
+/// Decimals of NEAR and of wNEAR
+const NEAR_DECIMALS: u8 = 24;
 
 #[derive(Subcommand, Debug)]
 pub enum Nep141ConnectorSubCommand {
@@ -29,8 +43,9 @@ pub enum Nep141ConnectorSubCommand {
     Deposit {
         #[clap(short, long)]
         token: String,
+        /// Amount in raw units, e.g. `1500000`, or in whole tokens with a decimal point, e.g. `1.5`
         #[clap(short, long)]
-        amount: u128,
+        amount: String,
         #[clap(short, long)]
         recipient: String,
         #[command(flatten)]
@@ -42,11 +57,37 @@ pub enum Nep141ConnectorSubCommand {
         #[command(flatten)]
         config_cli: CliConfig,
     },
+    /// Finalizes several deposits against the same light client block, with consecutive nonces. A failed finalization
+    /// doesn't stop the following ones
+    FinalizeDeposits {
+        /// Can be repeated
+        #[clap(short, long = "receipt-id", required = true)]
+        receipt_ids: Vec<String>,
+        #[command(flatten)]
+        config_cli: CliConfig,
+    },
+    /// Finalizes the deposit again after a failed `finalize-deposit`, reusing the proof kept in `--proof-store-dir` if
+    /// it's still valid
+    RetryFinalizeDeposit {
+        #[clap(short, long)]
+        receipt_id: String,
+        #[command(flatten)]
+        config_cli: CliConfig,
+    },
+    /// Executes `finalize-deposit` on a local anvil fork of Ethereum, with the final Near block injected into its light
+    /// client, so that no light client sync or real gas is needed. Requires the `anvil` binary of Foundry
+    SimulateFinalizeDeposit {
+        #[clap(short, long)]
+        receipt_id: String,
+        #[command(flatten)]
+        config_cli: CliConfig,
+    },
     Withdraw {
         #[clap(short, long)]
         token: String,
+        /// Amount in raw units, e.g. `1500000`, or in whole tokens with a decimal point, e.g. `1.5`
         #[clap(short, long)]
-        amount: u128,
+        amount: String,
         #[clap(short, long)]
         recipient: String,
         #[command(flatten)]
@@ -55,40 +96,99 @@ pub enum Nep141ConnectorSubCommand {
     FinalizeWithdraw {
         #[clap(short, long)]
         tx_hash: String,
+        /// Index of the withdrawal event in the block. Looked up in the transaction logs if omitted
+        #[clap(short, long)]
+        log_index: Option<u64>,
+        #[command(flatten)]
+        config_cli: CliConfig,
+    },
+    /// Finalizes the withdrawal again after a failed `finalize-withdraw`, reusing the proof kept in `--proof-store-dir`
+    /// if it's still valid
+    RetryFinalizeWithdraw {
+        #[clap(short, long)]
+        tx_hash: String,
+        /// Index of the withdrawal event in the block. Looked up in the transaction logs if omitted
         #[clap(short, long)]
-        log_index: u64,
+        log_index: Option<u64>,
         #[command(flatten)]
         config_cli: CliConfig,
     },
+    /// Wraps NEAR to wNEAR, deposits it to the token locker and finalizes the deposit on Ethereum
+    BridgeNear {
+        /// Amount in yoctoNEAR, e.g. `1500000000000000000000000`, or in NEAR with a decimal point, e.g. `1.5`
+        #[clap(short, long)]
+        amount: String,
+        #[clap(short, long)]
+        recipient: String,
+        #[command(flatten)]
+        config_cli: CliConfig,
+    },
+    /// Finalizes a withdrawal of wNEAR to the signer and unwraps it to NEAR
+    FinalizeNearWithdraw {
+        #[clap(short, long)]
+        tx_hash: String,
+        #[command(flatten)]
+        config_cli: CliConfig,
+    },
+}
+
+impl Nep141ConnectorSubCommand {
+    pub fn config_cli(&self) -> &CliConfig {
+        match self {
+            Self::LogMetadata { config_cli, .. }
+            | Self::StorageDeposit { config_cli, .. }
+            | Self::DeployToken { config_cli, .. }
+            | Self::Deposit { config_cli, .. }
+            | Self::FinalizeDeposit { config_cli, .. }
+            | Self::FinalizeDeposits { config_cli, .. }
+            | Self::RetryFinalizeDeposit { config_cli, .. }
+            | Self::SimulateFinalizeDeposit { config_cli, .. }
+            | Self::Withdraw { config_cli, .. }
+            | Self::FinalizeWithdraw { config_cli, .. }
+            | Self::RetryFinalizeWithdraw { config_cli, .. }
+            | Self::BridgeNear { config_cli, .. }
+            | Self::FinalizeNearWithdraw { config_cli, .. } => config_cli,
+        }
+    }
+
+    /// Whether the subcommand sends transactions, unlike the simulations on a local fork
+    pub fn sends_transactions(&self) -> bool {
+        !matches!(self, Self::SimulateFinalizeDeposit { .. })
+    }
 }
 
-pub async fn match_subcommand(cmd: Nep141ConnectorSubCommand, network: Network) {
+pub async fn match_subcommand(
+    cmd: Nep141ConnectorSubCommand,
+    config: &CommandConfig,
+) -> CliResult<()> {
     match cmd {
         Nep141ConnectorSubCommand::LogMetadata { token, config_cli } => {
-            nep141_connector(network, config_cli)
+            nep141_connector(config_cli, config)
+                .await?
                 .log_token_metadata(token)
-                .await
-                .unwrap();
+                .await?;
         }
         Nep141ConnectorSubCommand::StorageDeposit {
             token,
             amount,
             config_cli,
         } => {
-            nep141_connector(network, config_cli)
+            nep141_connector(config_cli, config)
+                .await?
                 .storage_deposit_for_token(token, amount)
-                .await
-                .unwrap();
+                .await?;
         }
         Nep141ConnectorSubCommand::DeployToken {
             receipt_id,
             config_cli,
         } => {
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+
             // TODO: use tx hash instead receipt_id
-            nep141_connector(network, config_cli)
-                .deploy_token(receipt_id.parse().expect("Invalid receipt_id"))
-                .await
-                .unwrap();
+            nep141_connector(config_cli, config)
+                .await?
+                .deploy_token(parse(&receipt_id, "--receipt-id")?, tx_opts)
+                .await?;
         }
         Nep141ConnectorSubCommand::Deposit {
             token,
@@ -96,20 +196,72 @@ pub async fn match_subcommand(cmd: Nep141ConnectorSubCommand, network: Network)
             recipient,
             config_cli,
         } => {
-            nep141_connector(network, config_cli)
-                .deposit(token, amount, recipient)
-                .await
-                .unwrap();
+            let recipient = address_book::resolve(&config_cli, recipient)?;
+            let connector = nep141_connector(config_cli, config).await?;
+            let amount = nep141_amount(&connector, &token, &amount).await?;
+
+            connector.deposit(token, amount, recipient).await?;
         }
         Nep141ConnectorSubCommand::FinalizeDeposit {
             receipt_id,
             config_cli,
         } => {
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+
             // TODO: use tx hash instead receipt_id
-            nep141_connector(network, config_cli)
-                .finalize_deposit(receipt_id.parse().expect("Invalid rreceipt_id"))
-                .await
-                .unwrap();
+            nep141_connector(config_cli, config)
+                .await?
+                .finalize_deposit(parse(&receipt_id, "--receipt-id")?, tx_opts)
+                .await?;
+        }
+        Nep141ConnectorSubCommand::FinalizeDeposits {
+            receipt_ids,
+            config_cli,
+        } => {
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+            let receipt_ids = receipt_ids
+                .iter()
+                .map(|receipt_id| parse(receipt_id, "--receipt-id"))
+                .collect::<CliResult<Vec<_>>>()?;
+
+            let results = nep141_connector(config_cli, config)
+                .await?
+                .finalize_deposits(receipt_ids, tx_opts)
+                .await?;
+            // The failures are logged with their receipt by the connector
+            if let Some(err) = results.into_iter().find_map(Result::err) {
+                return Err(err.into());
+            }
+        }
+        Nep141ConnectorSubCommand::RetryFinalizeDeposit {
+            receipt_id,
+            config_cli,
+        } => {
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+            let transfer_id = TransferId::Near {
+                receipt_id: parse(&receipt_id, "--receipt-id")?,
+            };
+
+            nep141_connector(config_cli, config)
+                .await?
+                .retry_finalize(transfer_id, tx_opts)
+                .await?;
+        }
+        Nep141ConnectorSubCommand::SimulateFinalizeDeposit {
+            receipt_id,
+            config_cli,
+        } => {
+            let simulation = nep141_connector(config_cli, config)
+                .await?
+                .simulate_finalize_deposit(parse(&receipt_id, "--receipt-id")?)
+                .await?;
+
+            if let Some(reason) = simulation.revert_reason {
+                return Err(CliError::Failed(format!(
+                    "Simulated finalize deposit {:?} reverted: {reason}",
+                    simulation.tx_hash
+                )));
+            }
         }
         Nep141ConnectorSubCommand::Withdraw {
             token,
@@ -117,40 +269,160 @@ pub async fn match_subcommand(cmd: Nep141ConnectorSubCommand, network: Network)
             recipient,
             config_cli,
         } => {
-            nep141_connector(network, config_cli)
-                .withdraw(token, amount, recipient)
-                .await
-                .unwrap();
+            let recipient = address_book::resolve(&config_cli, recipient)?;
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+            let connector = nep141_connector(config_cli, config).await?;
+            let amount = nep141_amount(&connector, &token, &amount).await?;
+
+            connector
+                .withdraw(token, amount, recipient, tx_opts)
+                .await?;
         }
         Nep141ConnectorSubCommand::FinalizeWithdraw {
             tx_hash,
             log_index,
             config_cli,
         } => {
-            nep141_connector(network, config_cli)
-                .finalize_withdraw(
-                    TxHash::from_str(&tx_hash).expect("Invalid tx_hash"),
-                    log_index,
-                )
-                .await
-                .unwrap();
+            let connector = nep141_connector(config_cli, config).await?;
+            let tx_hash = parse::<TxHash>(&tx_hash, "--tx-hash")?;
+
+            match log_index {
+                Some(log_index) => connector.finalize_withdraw(tx_hash, log_index).await?,
+                None => connector.finalize_withdraw_from_tx(tx_hash).await?,
+            };
+        }
+        Nep141ConnectorSubCommand::RetryFinalizeWithdraw {
+            tx_hash,
+            log_index,
+            config_cli,
+        } => {
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+            let connector = nep141_connector(config_cli, config).await?;
+            let tx_hash = parse::<TxHash>(&tx_hash, "--tx-hash")?;
+            let log_index = match log_index {
+                Some(log_index) => log_index,
+                None => connector.withdraw_log_index(tx_hash).await?,
+            };
+
+            connector
+                .retry_finalize(TransferId::Eth { tx_hash, log_index }, tx_opts)
+                .await?;
+        }
+        Nep141ConnectorSubCommand::BridgeNear {
+            amount,
+            recipient,
+            config_cli,
+        } => {
+            let recipient = address_book::resolve(&config_cli, recipient)?;
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+            let amount = parse::<TokenAmount>(&amount, "--amount")?.to_raw(NEAR_DECIMALS)?;
+
+            nep141_connector(config_cli, config)
+                .await?
+                .bridge_near(amount, recipient, tx_opts)
+                .await?;
+        }
+        Nep141ConnectorSubCommand::FinalizeNearWithdraw {
+            tx_hash,
+            config_cli,
+        } => {
+            nep141_connector(config_cli, config)
+                .await?
+                .finalize_near_withdraw(parse(&tx_hash, "--tx-hash")?)
+                .await?;
         }
     }
+
+    Ok(())
+}
+
+/// Raw amount of a NEP-141 token, converted with the token metadata decimals if entered in whole tokens
+async fn nep141_amount(connector: &Nep141Connector, token: &str, amount: &str) -> CliResult<u128> {
+    let token_id = parse::<AccountId>(token, "--token")?;
+
+    Ok(parse::<TokenAmount>(amount, "--amount")?
+        .resolve(async { Ok(connector.get_token_metadata(token_id).await?.decimals) })
+        .await?)
 }
 
-fn nep141_connector(network: Network, cli_config: CliConfig) -> Nep141Connector {
-    let combined_config = combined_config(cli_config, network);
+pub async fn nep141_connector(
+    cli_config: CliConfig,
+    config: &CommandConfig,
+) -> CliResult<Nep141Connector> {
+    let combined_config = combined_config(cli_config, config.network.clone())?;
+    let near_signer = signer::custom_near_signer(&combined_config).await?;
+    let eth_signer =
+        signer::custom_eth_signer(&combined_config, combined_config.eth_chain_id).await?;
+    let eth_keystore_password = signer::eth_keystore_password(&combined_config)?;
+    let detect_contract_version = combined_config.contract_version.as_deref() == Some("auto");
 
-    Nep141ConnectorBuilder::default()
+    let mut connector = Nep141ConnectorBuilder::default()
         .eth_endpoint(combined_config.eth_rpc)
         .eth_chain_id(combined_config.eth_chain_id)
         .near_endpoint(combined_config.near_rpc)
         .token_locker_id(combined_config.token_locker_id)
         .bridge_token_factory_address(combined_config.bridge_token_factory_address)
         .near_light_client_address(combined_config.near_light_client_eth_address)
+        .eth_light_client_account_id(combined_config.eth_light_client_account_id)
+        .proof_store(combined_config.proof_store_dir.map(ProofStore::new))
+        .wrapped_near_id(combined_config.wrapped_near_id)
         .eth_private_key(combined_config.eth_private_key)
+        .eth_keystore_path(combined_config.eth_keystore)
+        .eth_keystore_password(eth_keystore_password)
         .near_signer(combined_config.near_signer)
         .near_private_key(combined_config.near_private_key)
+        .disabled_operations(parse(
+            &combined_config.disabled_operations.unwrap_or_default(),
+            "--disabled-operations",
+        )?)
+        .recipient_validation(parse(
+            &combined_config.recipient_validation.unwrap_or_default(),
+            "--recipient-validation",
+        )?)
+        .method_names(parse(
+            &combined_config.method_names.unwrap_or_default(),
+            "--method-names",
+        )?)
+        .near_tx_options(NearTxOptions {
+            gas: combined_config.near_gas,
+            deposit: combined_config.near_deposit,
+            proof_chunk_size: combined_config.proof_chunk_size,
+        })
+        .dry_run(combined_config.dry_run)
+        .wait_for_light_client(!combined_config.no_light_client_wait)
+        .register_withdraw_recipient(combined_config.register_withdraw_recipient)
+        .light_client_sync_timeout(
+            combined_config
+                .light_client_sync_timeout_sec
+                .map_or(DEFAULT_LIGHT_CLIENT_SYNC_TIMEOUT, Duration::from_secs),
+        )
+        .contract_version(
+            combined_config
+                .contract_version
+                .as_deref()
+                .filter(|_| !detect_contract_version)
+                .map(|version| parse::<ContractVersion>(version, "--contract-version"))
+                .transpose()?
+                .unwrap_or_default(),
+        )
+        .custom_near_signer(near_signer)
+        .custom_eth_signer(eth_signer)
+        .near_retry_policy(config.near_retry_policy())
+        .near_outcome_timeout(config.near_outcome_timeout())
+        .eth_nonce_journal(config.eth_nonce_journal.clone())
+        .rpc_scheduler(config.rpc_scheduler())
+        .eth_rpc_options(config.eth_rpc_options())
+        .proof_options(config.proof_options())
         .build()
-        .unwrap()
+        .map_err(|e| CliError::Config(e.to_string()))?;
+
+    if detect_contract_version {
+        connector.detect_contract_interface().await?;
+    }
+
+    if config.self_check {
+        log_self_check(connector.self_check().await)?;
+    }
+
+    Ok(connector)
 }
@@ -0,0 +1,208 @@
+use crate::{
+    address_book, combined_config,
+    error::{parse, CliError, CliResult},
+    eth_tx_options, log_self_check, signer, CliConfig, CommandConfig,
+};
+use bridge_connector_common::{
+    bridge_connector::DEFAULT_LIGHT_CLIENT_SYNC_TIMEOUT, contract_call::NearTxOptions,
+};
+use clap::Subcommand;
+use ethers_core::types::TxHash;
+use nft_connector::{NftConnector, NftConnectorBuilder};
+use std::time::Duration;
+
+#[derive(Subcommand, Debug)]
+pub enum NftConnectorSubCommand {
+    LogMetadata {
+        #[clap(short, long)]
+        token: String,
+        #[command(flatten)]
+        config_cli: CliConfig,
+    },
+    DeployToken {
+        #[clap(short, long)]
+        receipt_id: String,
+        #[command(flatten)]
+        config_cli: CliConfig,
+    },
+    Deposit {
+        #[clap(short, long)]
+        token: String,
+        #[clap(short = 'i', long)]
+        token_id: String,
+        #[clap(short, long)]
+        recipient: String,
+        #[command(flatten)]
+        config_cli: CliConfig,
+    },
+    FinalizeDeposit {
+        #[clap(short, long)]
+        receipt_id: String,
+        #[command(flatten)]
+        config_cli: CliConfig,
+    },
+    Withdraw {
+        #[clap(short, long)]
+        token: String,
+        #[clap(short = 'i', long)]
+        token_id: String,
+        #[clap(short, long)]
+        recipient: String,
+        #[command(flatten)]
+        config_cli: CliConfig,
+    },
+    FinalizeWithdraw {
+        #[clap(short, long)]
+        tx_hash: String,
+        #[clap(short, long)]
+        log_index: u64,
+        #[command(flatten)]
+        config_cli: CliConfig,
+    },
+}
+
+impl NftConnectorSubCommand {
+    pub fn config_cli(&self) -> &CliConfig {
+        match self {
+            Self::LogMetadata { config_cli, .. }
+            | Self::DeployToken { config_cli, .. }
+            | Self::Deposit { config_cli, .. }
+            | Self::FinalizeDeposit { config_cli, .. }
+            | Self::Withdraw { config_cli, .. }
+            | Self::FinalizeWithdraw { config_cli, .. } => config_cli,
+        }
+    }
+}
+
+pub async fn match_subcommand(
+    cmd: NftConnectorSubCommand,
+    config: &CommandConfig,
+) -> CliResult<()> {
+    match cmd {
+        NftConnectorSubCommand::LogMetadata { token, config_cli } => {
+            nft_connector(config_cli, config)
+                .await?
+                .log_token_metadata(token)
+                .await?;
+        }
+        NftConnectorSubCommand::DeployToken {
+            receipt_id,
+            config_cli,
+        } => {
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+
+            nft_connector(config_cli, config)
+                .await?
+                .deploy_token(parse(&receipt_id, "--receipt-id")?, tx_opts)
+                .await?;
+        }
+        NftConnectorSubCommand::Deposit {
+            token,
+            token_id,
+            recipient,
+            config_cli,
+        } => {
+            let recipient = address_book::resolve(&config_cli, recipient)?;
+
+            nft_connector(config_cli, config)
+                .await?
+                .deposit(token, token_id, recipient)
+                .await?;
+        }
+        NftConnectorSubCommand::FinalizeDeposit {
+            receipt_id,
+            config_cli,
+        } => {
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+
+            nft_connector(config_cli, config)
+                .await?
+                .finalize_deposit(parse(&receipt_id, "--receipt-id")?, tx_opts)
+                .await?;
+        }
+        NftConnectorSubCommand::Withdraw {
+            token,
+            token_id,
+            recipient,
+            config_cli,
+        } => {
+            let recipient = address_book::resolve(&config_cli, recipient)?;
+            let tx_opts = eth_tx_options(&config_cli, config.network.clone())?;
+
+            nft_connector(config_cli, config)
+                .await?
+                .withdraw(token, token_id, recipient, tx_opts)
+                .await?;
+        }
+        NftConnectorSubCommand::FinalizeWithdraw {
+            tx_hash,
+            log_index,
+            config_cli,
+        } => {
+            nft_connector(config_cli, config)
+                .await?
+                .finalize_withdraw(parse::<TxHash>(&tx_hash, "--tx-hash")?, log_index)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn nft_connector(cli_config: CliConfig, config: &CommandConfig) -> CliResult<NftConnector> {
+    let combined_config = combined_config(cli_config, config.network.clone())?;
+    let near_signer = signer::custom_near_signer(&combined_config).await?;
+    let eth_signer =
+        signer::custom_eth_signer(&combined_config, combined_config.eth_chain_id).await?;
+    let eth_keystore_password = signer::eth_keystore_password(&combined_config)?;
+
+    let connector = NftConnectorBuilder::default()
+        .eth_endpoint(combined_config.eth_rpc)
+        .eth_chain_id(combined_config.eth_chain_id)
+        .eth_private_key(combined_config.eth_private_key)
+        .eth_keystore_path(combined_config.eth_keystore)
+        .eth_keystore_password(eth_keystore_password)
+        .nft_factory_address(combined_config.nft_factory_address)
+        .near_endpoint(combined_config.near_rpc)
+        .near_signer(combined_config.near_signer)
+        .near_private_key(combined_config.near_private_key)
+        .nft_locker_id(combined_config.nft_locker_id)
+        .near_light_client_address(combined_config.near_light_client_eth_address)
+        .eth_light_client_account_id(combined_config.eth_light_client_account_id)
+        .disabled_operations(parse(
+            &combined_config.disabled_operations.unwrap_or_default(),
+            "--disabled-operations",
+        )?)
+        .method_names(parse(
+            &combined_config.method_names.unwrap_or_default(),
+            "--method-names",
+        )?)
+        .near_tx_options(NearTxOptions {
+            gas: combined_config.near_gas,
+            deposit: combined_config.near_deposit,
+            proof_chunk_size: combined_config.proof_chunk_size,
+        })
+        .dry_run(combined_config.dry_run)
+        .wait_for_light_client(!combined_config.no_light_client_wait)
+        .light_client_sync_timeout(
+            combined_config
+                .light_client_sync_timeout_sec
+                .map_or(DEFAULT_LIGHT_CLIENT_SYNC_TIMEOUT, Duration::from_secs),
+        )
+        .custom_near_signer(near_signer)
+        .custom_eth_signer(eth_signer)
+        .near_retry_policy(config.near_retry_policy())
+        .near_outcome_timeout(config.near_outcome_timeout())
+        .eth_nonce_journal(config.eth_nonce_journal.clone())
+        .rpc_scheduler(config.rpc_scheduler())
+        .eth_rpc_options(config.eth_rpc_options())
+        .proof_options(config.proof_options())
+        .build()
+        .map_err(|e| CliError::Config(e.to_string()))?;
+
+    if config.self_check {
+        log_self_check(connector.self_check().await)?;
+    }
+
+    Ok(connector)
+}
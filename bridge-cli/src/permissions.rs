@@ -0,0 +1,109 @@
+use crate::{
+    combined_config,
+    error::{parse, CliError, CliResult},
+    CliConfig, CommandConfig,
+};
+use bridge_connector_common::self_check::{self, SelfCheckReport};
+use clap::Args;
+use ethers::{
+    signers::{LocalWallet, Signer},
+    types::Address,
+};
+use near_crypto::{PublicKey, SecretKey};
+
+#[derive(Args, Debug)]
+pub struct PermissionsArgs {
+    /// Public key of the Near signer. Defaults to the key of `--near-private-key` or
+    /// `--near-remote-signer-public-key`, required for other signers, e.g. a Ledger
+    #[clap(long)]
+    pub near_public_key: Option<String>,
+    /// Address of the Ethereum signer. Defaults to the address of `--eth-private-key`, required for other signers,
+    /// e.g. a Ledger or AWS KMS
+    #[clap(long)]
+    pub eth_address: Option<String>,
+    #[command(flatten)]
+    pub config_cli: CliConfig,
+}
+
+/// Reports what the configured keys are allowed to do: the permission and allowance of the Near access key, and the
+/// admin roles the Ethereum key holds on the bridge contracts, which operations don't need
+pub async fn run(args: PermissionsArgs, command_config: &CommandConfig) -> CliResult<()> {
+    let config = combined_config(args.config_cli, command_config.network.clone())?;
+
+    let near_public_key = match args
+        .near_public_key
+        .or(config.near_remote_signer_public_key)
+    {
+        Some(public_key) => Some(parse::<PublicKey>(&public_key, "--near-public-key")?),
+        None => config
+            .near_private_key
+            .map(|private_key| {
+                private_key
+                    .parse::<SecretKey>()
+                    .map(|secret_key| secret_key.public_key())
+                    .map_err(|_| CliError::Config("Invalid near private key".to_string()))
+            })
+            .transpose()?,
+    };
+    let eth_address = match args.eth_address {
+        Some(address) => Some(parse::<Address>(&address, "--eth-address")?),
+        None => config
+            .eth_private_key
+            .map(|private_key| {
+                private_key
+                    .parse::<LocalWallet>()
+                    .map(|wallet| wallet.address())
+                    .map_err(|_| CliError::Config("Invalid ethereum private key".to_string()))
+            })
+            .transpose()?,
+    };
+    let contract = |address: &Option<String>, name: &str| {
+        address
+            .as_deref()
+            .map(|address| parse::<Address>(address, name))
+            .transpose()
+    };
+    let bridge_token_factory_address = contract(
+        &config.bridge_token_factory_address,
+        "--bridge-token-factory-address",
+    )?;
+    let eth_custodian_address = contract(&config.eth_custodian_address, "--eth-custodian-address")?;
+
+    let mut report = SelfCheckReport::default();
+    report.push(
+        "Near access key",
+        self_check::check_near_access_key(
+            config.near_rpc.as_deref(),
+            config.near_signer.as_deref(),
+            near_public_key.map(Ok),
+        )
+        .await,
+    );
+    report.push(
+        "Ethereum roles on the bridge token factory",
+        self_check::check_eth_admin_roles(
+            config.eth_rpc.as_deref(),
+            bridge_token_factory_address.map(Ok),
+            eth_address.map(Ok),
+        )
+        .await,
+    );
+    report.push(
+        "Ethereum roles on the ETH custodian",
+        self_check::check_eth_admin_roles(
+            config.eth_rpc.as_deref(),
+            eth_custodian_address.map(Ok),
+            eth_address.map(Ok),
+        )
+        .await,
+    );
+
+    tracing::info!("Permissions report:\n{report}");
+
+    match report.is_ok() {
+        true => Ok(()),
+        false => Err(CliError::Failed(
+            "The configured keys don't pass all permission checks".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{
+    combined_config,
+    error::{parse, parse_required, required, CliResult},
+    logging::LogConfig,
+    nep141_connector_command::nep141_connector,
+    CliConfig, CommandConfig, Network,
+};
+use bridge_connector_common::rpc_scheduler::{RpcScheduler, RpcSchedulerConfig};
+use bridge_relayer::{
+    queue::connect_queue, EthEventWatcher, EthListener, EthOnNearHeight, LightClientMonitor,
+    ListenerMode, MonitorConfig, NearEventWatcher, NearListener, NearOnEthHeight, Relayer,
+    RelayerConfig, TransferStore, Worker,
+};
+use clap::Args;
+use ethers_core::types::Address;
+use near_primitives::types::AccountId;
+use std::{sync::Arc, time::Duration};
+
+#[derive(Args, Debug)]
+pub struct RelayArgs {
+    /// File the relayer state is persisted to, so that a restarted relayer resumes where it stopped
+    #[clap(long, default_value = "relayer-state.json")]
+    pub store_path: String,
+    /// Number of blocks an Ethereum block has to be buried under before its transfers are picked up
+    #[clap(long, default_value_t = 12)]
+    pub eth_confirmations: u64,
+    /// Redis (`redis://`) or NATS (`nats://`) url of a queue the finalize jobs are published to, so that they are
+    /// executed by `--worker` instances instead of this relayer
+    #[clap(long)]
+    pub queue_url: Option<String>,
+    /// Name of the queue streams, shared by the relayer and its workers
+    #[clap(long, default_value = "bridge-relayer")]
+    pub queue_name: String,
+    /// Execute finalize jobs from `--queue-url` instead of watching the chains
+    #[clap(long, requires = "queue_url")]
+    pub worker: bool,
+    /// Time a light client height has to stay unchanged before an alert is raised
+    #[clap(long, default_value_t = 7200)]
+    pub light_client_stall_threshold_sec: u64,
+    /// Requests sent concurrently to a single RPC endpoint
+    #[clap(long, default_value_t = 16)]
+    pub rpc_max_concurrent_requests: usize,
+    /// Concurrent requests per RPC endpoint reserved for sending transactions, so that scanning and proof generation
+    /// can't delay them
+    #[clap(long, default_value_t = 4)]
+    pub rpc_reserved_for_submissions: usize,
+    /// Scanning and proof requests waiting for a single RPC endpoint, after which new ones are postponed
+    #[clap(long, default_value_t = 256)]
+    pub rpc_max_queued_requests: usize,
+    /// Starts scanning as soon as a new Ethereum block arrives: `ws` subscribes to new blocks over `--eth-ws-rpc`,
+    /// `http` polls the block number, `hybrid` subscribes and polls while the WebSocket endpoint is unavailable.
+    /// Otherwise the chains are only scanned every poll interval
+    #[clap(long)]
+    pub eth_listener: Option<String>,
+    /// Ethereum WebSocket RPC endpoint of the `ws` and `hybrid` listeners
+    #[clap(long)]
+    pub eth_ws_rpc: Option<String>,
+    /// Delay between two Ethereum block number requests of the `http` and `hybrid` listeners
+    #[clap(long, default_value_t = 12)]
+    pub eth_listener_interval_sec: u64,
+    /// Starts scanning as soon as a new final Near block is found by polling the Near RPC with this delay
+    #[clap(long)]
+    pub near_listener_interval_sec: Option<u64>,
+    #[command(flatten)]
+    pub config_cli: CliConfig,
+}
+
+/// Configuration errors are reported by `run`, once the logger is set up
+pub fn log_config(args: &RelayArgs, network: Network) -> Option<LogConfig> {
+    combined_config(args.config_cli.clone(), network).ok()?.log
+}
+
+pub async fn run(args: RelayArgs, command_config: &CommandConfig) -> CliResult<()> {
+    let config = combined_config(args.config_cli.clone(), command_config.network.clone())?;
+
+    // Shared by the connector and the watchers, so that scanning can't delay the proofs and submissions
+    let rpc_scheduler = Arc::new(RpcScheduler::new(RpcSchedulerConfig {
+        max_concurrent_requests: args.rpc_max_concurrent_requests,
+        reserved_for_submissions: args.rpc_reserved_for_submissions,
+        max_queued_requests: args.rpc_max_queued_requests,
+    }));
+    let command_config = &CommandConfig {
+        rpc_scheduler: Some(rpc_scheduler.clone()),
+        ..command_config.clone()
+    };
+
+    let near_rpc = required(config.near_rpc, "near-rpc")?;
+    let eth_rpc = required(config.eth_rpc, "eth-rpc")?;
+    let token_locker_id: AccountId =
+        parse_required(config.token_locker_id.as_deref(), "token-locker-id")?;
+    let bridge_token_factory_address: Address = parse_required(
+        config.bridge_token_factory_address.as_deref(),
+        "bridge-token-factory-address",
+    )?;
+
+    let mut monitor = LightClientMonitor::new(MonitorConfig {
+        stall_threshold: Duration::from_secs(args.light_client_stall_threshold_sec),
+        ..MonitorConfig::default()
+    });
+    if let Some(address) = &config.near_light_client_eth_address {
+        monitor = monitor.with_light_client(NearOnEthHeight::new(
+            &eth_rpc,
+            parse(address, "--near-light-client-eth-address")?,
+        ));
+    }
+    if let Some(account_id) = &config.eth_light_client_account_id {
+        monitor = monitor.with_light_client(EthOnNearHeight::new(
+            &near_rpc,
+            parse(account_id, "--eth-light-client-account-id")?,
+        ));
+    }
+
+    let connector = nep141_connector(args.config_cli, command_config).await?;
+
+    let queue = match &args.queue_url {
+        Some(queue_url) => Some(
+            connect_queue(
+                queue_url,
+                &args.queue_name,
+                &format!("{}-{}", args.queue_name, std::process::id()),
+            )
+            .await?,
+        ),
+        None => None,
+    };
+
+    if args.worker {
+        let worker = Worker::new(connector, required(queue, "queue-url")?);
+        return Ok(worker.run().await?);
+    }
+
+    let store = TransferStore::open(args.store_path)?;
+
+    let relayer_config = RelayerConfig {
+        rpc_scheduler,
+        ..RelayerConfig::default()
+    };
+    let mut relayer = Relayer::new(connector, store, relayer_config)
+        .with_watcher(NearEventWatcher::new(
+            "token_locker",
+            &near_rpc,
+            token_locker_id,
+            "ft_on_transfer",
+        ))
+        .with_watcher(EthEventWatcher::new(
+            "bridge_token_factory",
+            &eth_rpc,
+            bridge_token_factory_address,
+            "Withdraw(string,address,uint256,string)",
+            args.eth_confirmations,
+        ));
+
+    if let Some(eth_listener) = &args.eth_listener {
+        relayer = relayer.with_listener(EthListener::new(
+            &eth_rpc,
+            args.eth_ws_rpc.as_deref(),
+            parse::<ListenerMode>(eth_listener, "--eth-listener")?,
+            Duration::from_secs(args.eth_listener_interval_sec),
+        )?);
+    }
+    if let Some(interval_sec) = args.near_listener_interval_sec {
+        relayer = relayer.with_listener(NearListener::new(
+            &near_rpc,
+            Duration::from_secs(interval_sec),
+        ));
+    }
+
+    if let Some(queue) = queue {
+        relayer = relayer.with_queue(queue);
+    }
+
+    tokio::spawn(async move { monitor.run().await });
+
+    Ok(relayer.run().await?)
+}
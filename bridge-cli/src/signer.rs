@@ -0,0 +1,207 @@
+use crate::{
+    error::{parse_required, required, CliError, CliResult},
+    CliConfig,
+};
+use bridge_connector_common::signer::{EthSigner, NearSigner};
+use std::{env, path::PathBuf, sync::Arc};
+
+/// Near signer selected by the signer flags, or `None` to sign with `near_private_key`
+pub async fn custom_near_signer(config: &CliConfig) -> CliResult<Option<Arc<dyn NearSigner>>> {
+    if config.near_ledger {
+        return ledger::near_signer(config).await.map(Some);
+    }
+
+    if config.near_remote_signer_url.is_some() {
+        return remote::near_signer(config).map(Some);
+    }
+
+    if let Some(path) = &config.near_credentials_file {
+        return credentials_file_signer(path).map(Some);
+    }
+
+    Ok(None)
+}
+
+/// Ethereum or Aurora signer selected by the signer flags, or `None` to sign with the configured private key
+pub async fn custom_eth_signer(
+    config: &CliConfig,
+    chain_id: Option<u64>,
+) -> CliResult<Option<Arc<dyn EthSigner>>> {
+    if config.eth_ledger {
+        return ledger::eth_signer(config, chain_id).await.map(Some);
+    }
+
+    if config.eth_kms_key_id.is_some() {
+        return aws_kms::eth_signer(config, chain_id).await.map(Some);
+    }
+
+    Ok(None)
+}
+
+/// Password of the `--eth-keystore` file, read from `ETH_KEYSTORE_PASSWORD` or prompted for
+pub fn eth_keystore_password(config: &CliConfig) -> CliResult<Option<String>> {
+    if config.eth_keystore.is_none() {
+        return Ok(None);
+    }
+
+    let password = match env::var("ETH_KEYSTORE_PASSWORD") {
+        Ok(password) => password,
+        Err(_) => rpassword::prompt_password("Ethereum keystore password: ")
+            .map_err(|e| CliError::Signer(format!("Unable to read the keystore password: {e}")))?,
+    };
+
+    Ok(Some(password))
+}
+
+/// Loads a key file written by `near login`. A leading `~` is expanded, since paths from the config file don't go
+/// through the shell
+fn credentials_file_signer(path: &str) -> CliResult<Arc<dyn NearSigner>> {
+    let path = match (path.strip_prefix("~/"), std::env::var_os("HOME")) {
+        (Some(relative_path), Some(home)) => PathBuf::from(home).join(relative_path),
+        _ => PathBuf::from(path),
+    };
+
+    let signer = near_rpc_client::signer_from_credentials_file(&path).map_err(|e| {
+        CliError::Signer(format!(
+            "Unable to load the near credentials file {}: {e}",
+            path.display()
+        ))
+    })?;
+
+    Ok(Arc::new(signer))
+}
+
+#[cfg(feature = "ledger")]
+mod ledger {
+    use super::*;
+    use bridge_connector_common::ledger::{
+        LedgerEthSigner, LedgerNearSigner, DEFAULT_NEAR_HD_PATH,
+    };
+
+    pub async fn near_signer(config: &CliConfig) -> CliResult<Arc<dyn NearSigner>> {
+        let account_id = parse_required(config.near_signer.as_deref(), "near-signer")?;
+        let hd_path = config
+            .near_ledger_hd_path
+            .as_deref()
+            .unwrap_or(DEFAULT_NEAR_HD_PATH);
+
+        let signer = LedgerNearSigner::new(account_id, hd_path)
+            .await
+            .map_err(|e| {
+                CliError::Signer(format!(
+                    "Unable to connect to the Near app on the Ledger device: {e}"
+                ))
+            })?;
+
+        Ok(Arc::new(signer))
+    }
+
+    pub async fn eth_signer(
+        config: &CliConfig,
+        chain_id: Option<u64>,
+    ) -> CliResult<Arc<dyn EthSigner>> {
+        let signer = LedgerEthSigner::new(
+            config.eth_ledger_account_index.unwrap_or_default(),
+            required(chain_id, "eth-chain-id")?,
+        )
+        .await
+        .map_err(|e| {
+            CliError::Signer(format!(
+                "Unable to connect to the Ethereum app on the Ledger device: {e}"
+            ))
+        })?;
+
+        Ok(Arc::new(signer))
+    }
+}
+
+#[cfg(not(feature = "ledger"))]
+mod ledger {
+    use super::*;
+
+    pub async fn near_signer(_config: &CliConfig) -> CliResult<Arc<dyn NearSigner>> {
+        Err(CliError::Config(
+            "Ledger support is disabled, rebuild with `--features ledger`".to_string(),
+        ))
+    }
+
+    pub async fn eth_signer(
+        _config: &CliConfig,
+        _chain_id: Option<u64>,
+    ) -> CliResult<Arc<dyn EthSigner>> {
+        Err(CliError::Config(
+            "Ledger support is disabled, rebuild with `--features ledger`".to_string(),
+        ))
+    }
+}
+
+#[cfg(feature = "aws-kms")]
+mod aws_kms {
+    use super::*;
+    use bridge_connector_common::aws_kms::AwsKmsEthSigner;
+
+    pub async fn eth_signer(
+        config: &CliConfig,
+        chain_id: Option<u64>,
+    ) -> CliResult<Arc<dyn EthSigner>> {
+        let signer = AwsKmsEthSigner::new(
+            required(config.eth_kms_key_id.as_ref(), "eth-kms-key-id")?,
+            required(chain_id, "eth-chain-id")?,
+        )
+        .await
+        .map_err(|e| CliError::Signer(format!("Unable to load the KMS key: {e}")))?;
+
+        Ok(Arc::new(signer))
+    }
+}
+
+#[cfg(not(feature = "aws-kms"))]
+mod aws_kms {
+    use super::*;
+
+    pub async fn eth_signer(
+        _config: &CliConfig,
+        _chain_id: Option<u64>,
+    ) -> CliResult<Arc<dyn EthSigner>> {
+        Err(CliError::Config(
+            "AWS KMS support is disabled, rebuild with `--features aws-kms`".to_string(),
+        ))
+    }
+}
+
+#[cfg(feature = "remote-signer")]
+mod remote {
+    use super::*;
+    use bridge_connector_common::remote_signer::RemoteNearSigner;
+
+    pub fn near_signer(config: &CliConfig) -> CliResult<Arc<dyn NearSigner>> {
+        let signer = RemoteNearSigner::new(
+            required(
+                config.near_remote_signer_url.clone(),
+                "near-remote-signer-url",
+            )?,
+            parse_required(config.near_signer.as_deref(), "near-signer")?,
+            parse_required(
+                config.near_remote_signer_public_key.as_deref(),
+                "near-remote-signer-public-key",
+            )?,
+        );
+
+        match &config.near_remote_signer_auth_token {
+            Some(auth_token) => Ok(Arc::new(signer.with_auth_token(auth_token.clone()))),
+            None => Ok(Arc::new(signer)),
+        }
+    }
+}
+
+#[cfg(not(feature = "remote-signer"))]
+mod remote {
+    use super::*;
+
+    pub fn near_signer(_config: &CliConfig) -> CliResult<Arc<dyn NearSigner>> {
+        Err(CliError::Config(
+            "Remote signer support is disabled, rebuild with `--features remote-signer`"
+                .to_string(),
+        ))
+    }
+}
@@ -0,0 +1,408 @@
+use crate::{
+    address_book, combined_config,
+    error::{parse_required, required, CliError, CliResult},
+    eth_tx_options,
+    nep141_connector_command::nep141_connector,
+    CliConfig, CommandConfig,
+};
+use bridge_connector_common::{
+    bridge_connector::{BridgeConnector, TransferId, TransferStatus},
+    contract_call::EthTxOptions,
+    result::BridgeSdkError,
+};
+use clap::{ArgGroup, Args};
+use ethers::{
+    providers::{Http, Middleware, Provider, ProviderError},
+    types::{Address, TransactionReceipt, TxHash, H256},
+    utils::keccak256,
+};
+use near_primitives::{
+    hash::CryptoHash,
+    types::AccountId,
+    views::{ExecutionStatusView, FinalExecutionOutcomeView, FinalExecutionStatus},
+};
+use near_rpc_client::NearRpcError;
+use nep141_connector::Nep141Connector;
+use std::{
+    fmt,
+    future::Future,
+    time::{Duration, Instant},
+};
+
+/// Amount bridged with `--amount-tiny`, the smallest unit of the token
+const TINY_AMOUNT: u128 = 1;
+
+/// Delay between checks while waiting for a transaction or a light client
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Args, Debug)]
+#[command(group(
+    ArgGroup::new("smoke_test_amount")
+        .required(true)
+        .args(["amount_tiny", "amount"])
+))]
+pub struct SmokeTestArgs {
+    /// NEP-141 token bridged to Ethereum and back
+    #[clap(short, long)]
+    pub token: String,
+    /// Address of the configured Ethereum signer. It receives the bridged tokens and burns them on the way back
+    #[clap(short, long)]
+    pub eth_recipient: String,
+    /// Bridge the smallest unit of the token
+    #[clap(long)]
+    pub amount_tiny: bool,
+    /// Amount of the token to bridge
+    #[clap(long)]
+    pub amount: Option<u128>,
+    /// Timeout of the steps sending a transaction
+    #[clap(long, default_value_t = 300)]
+    pub step_timeout_sec: u64,
+    /// Timeout of the steps waiting for a light client to sync the transfer
+    #[clap(long, default_value_t = 14_400)]
+    pub sync_timeout_sec: u64,
+    #[command(flatten)]
+    pub config_cli: CliConfig,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("{0}")]
+pub(crate) struct SmokeTestError(pub(crate) String);
+
+impl From<BridgeSdkError> for SmokeTestError {
+    fn from(error: BridgeSdkError) -> Self {
+        Self(error.to_string())
+    }
+}
+
+impl From<NearRpcError> for SmokeTestError {
+    fn from(error: NearRpcError) -> Self {
+        Self(error.to_string())
+    }
+}
+
+impl From<ProviderError> for SmokeTestError {
+    fn from(error: ProviderError) -> Self {
+        Self(error.to_string())
+    }
+}
+
+enum StepStatus {
+    Passed(String),
+    Failed(String),
+    Skipped,
+}
+
+struct Step {
+    name: &'static str,
+    status: StepStatus,
+    elapsed: Duration,
+}
+
+#[derive(Default)]
+pub(crate) struct SmokeTestReport {
+    steps: Vec<Step>,
+}
+
+impl SmokeTestReport {
+    /// Runs `f` with the output of the previous step, or skips it if the previous step didn't pass
+    pub(crate) async fn step<I, T, F, Fut>(
+        &mut self,
+        name: &'static str,
+        timeout: Duration,
+        input: Option<I>,
+        f: F,
+    ) -> Option<T>
+    where
+        F: FnOnce(I) -> Fut,
+        Fut: Future<Output = Result<(T, String), SmokeTestError>>,
+    {
+        let Some(input) = input else {
+            self.steps.push(Step {
+                name,
+                status: StepStatus::Skipped,
+                elapsed: Duration::ZERO,
+            });
+            return None;
+        };
+
+        tracing::info!("{name}");
+
+        let started_at = Instant::now();
+        let (output, status) = match tokio::time::timeout(timeout, f(input)).await {
+            Ok(Ok((output, details))) => (Some(output), StepStatus::Passed(details)),
+            Ok(Err(err)) => (None, StepStatus::Failed(err.to_string())),
+            Err(_) => (
+                None,
+                StepStatus::Failed(format!("Timed out after {}s", timeout.as_secs())),
+            ),
+        };
+
+        self.steps.push(Step {
+            name,
+            status,
+            elapsed: started_at.elapsed(),
+        });
+
+        output
+    }
+
+    pub(crate) fn is_ok(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|step| matches!(step.status, StepStatus::Passed(_)))
+    }
+}
+
+impl fmt::Display for SmokeTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            let elapsed = step.elapsed.as_secs();
+            match &step.status {
+                StepStatus::Passed(details) => {
+                    writeln!(f, "[PASS] {} ({elapsed}s): {details}", step.name)?
+                }
+                StepStatus::Failed(error) => {
+                    writeln!(f, "[FAIL] {} ({elapsed}s): {error}", step.name)?
+                }
+                StepStatus::Skipped => writeln!(f, "[SKIP] {}", step.name)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) struct SmokeTest {
+    pub(crate) connector: Nep141Connector,
+    pub(crate) eth_provider: Provider<Http>,
+    pub(crate) near_endpoint: String,
+    pub(crate) near_signer: AccountId,
+    pub(crate) token_locker_id: AccountId,
+    pub(crate) bridge_token_factory_address: Address,
+    pub(crate) token: String,
+    pub(crate) eth_recipient: String,
+    pub(crate) amount: u128,
+    pub(crate) step_timeout: Duration,
+    pub(crate) tx_opts: EthTxOptions,
+}
+
+/// Bridges a small amount of a NEP-141 token to Ethereum and back, and fails if any step fails
+pub async fn run(args: SmokeTestArgs, command_config: &CommandConfig) -> CliResult<()> {
+    let config = combined_config(args.config_cli.clone(), command_config.network.clone())?;
+    let eth_recipient = address_book::resolve(&args.config_cli, args.eth_recipient)?;
+    let tx_opts = eth_tx_options(&args.config_cli, command_config.network.clone())?;
+
+    let smoke_test = SmokeTest {
+        connector: nep141_connector(args.config_cli, command_config).await?,
+        eth_provider: parse_required(config.eth_rpc.as_deref(), "eth-rpc")?,
+        near_endpoint: required(config.near_rpc, "near-rpc")?,
+        near_signer: parse_required(config.near_signer.as_deref(), "near-signer")?,
+        token_locker_id: parse_required(config.token_locker_id.as_deref(), "token-locker-id")?,
+        bridge_token_factory_address: parse_required(
+            config.bridge_token_factory_address.as_deref(),
+            "bridge-token-factory-address",
+        )?,
+        token: args.token,
+        eth_recipient,
+        amount: args.amount.unwrap_or(TINY_AMOUNT),
+        step_timeout: Duration::from_secs(args.step_timeout_sec),
+        tx_opts,
+    };
+    let sync_timeout = Duration::from_secs(args.sync_timeout_sec);
+    let step_timeout = smoke_test.step_timeout;
+
+    let mut report = SmokeTestReport::default();
+
+    let receipt_id = report
+        .step("Deposit on Near", step_timeout, Some(()), |_| {
+            smoke_test.deposit()
+        })
+        .await;
+    let receipt_id = report
+        .step(
+            "Sync Near light client on Ethereum",
+            sync_timeout,
+            receipt_id,
+            |receipt_id| smoke_test.wait_for_near_proof(receipt_id),
+        )
+        .await;
+    let deposit_finalized = report
+        .step(
+            "Finalize deposit on Ethereum",
+            step_timeout,
+            receipt_id,
+            |receipt_id| smoke_test.finalize_deposit(receipt_id),
+        )
+        .await;
+    let withdrawal = report
+        .step(
+            "Withdraw on Ethereum",
+            step_timeout,
+            deposit_finalized,
+            |_| smoke_test.withdraw(),
+        )
+        .await;
+    report
+        .step(
+            "Finalize withdraw on Near",
+            sync_timeout,
+            withdrawal,
+            |(tx_hash, log_index)| smoke_test.finalize_withdraw(tx_hash, log_index),
+        )
+        .await;
+
+    tracing::info!("Smoke test report:\n{report}");
+
+    match report.is_ok() {
+        true => Ok(()),
+        false => Err(CliError::Failed("Smoke test failed".to_string())),
+    }
+}
+
+impl SmokeTest {
+    pub(crate) async fn deposit(&self) -> Result<(CryptoHash, String), SmokeTestError> {
+        let tx_hash = self
+            .connector
+            .deposit(self.token.clone(), self.amount, self.eth_recipient.clone())
+            .await?;
+        let outcome = self.wait_for_near_tx(tx_hash).await?;
+
+        let locker_receipt = outcome
+            .receipts_outcome
+            .iter()
+            .find(|receipt| receipt.outcome.executor_id == self.token_locker_id)
+            .ok_or(SmokeTestError(
+                "The token locker wasn't called by the deposit".to_string(),
+            ))?;
+
+        if let ExecutionStatusView::Failure(err) = &locker_receipt.outcome.status {
+            return Err(SmokeTestError(format!("Token locker failed: {err:?}")));
+        }
+
+        Ok((
+            locker_receipt.id,
+            format!("tx {tx_hash}, receipt {}", locker_receipt.id),
+        ))
+    }
+
+    pub(crate) async fn wait_for_near_proof(
+        &self,
+        receipt_id: CryptoHash,
+    ) -> Result<(CryptoHash, String), SmokeTestError> {
+        loop {
+            match self
+                .connector
+                .get_status(TransferId::Near { receipt_id })
+                .await?
+            {
+                TransferStatus::ReadyToFinalize => {
+                    return Ok((receipt_id, "receipt is provable on Ethereum".to_string()))
+                }
+                TransferStatus::Failed => {
+                    return Err(SmokeTestError("Deposit receipt failed".to_string()))
+                }
+                TransferStatus::Pending | TransferStatus::Initiated => {
+                    tokio::time::sleep(POLL_INTERVAL).await
+                }
+            }
+        }
+    }
+
+    pub(crate) async fn finalize_deposit(
+        &self,
+        receipt_id: CryptoHash,
+    ) -> Result<(TransactionReceipt, String), SmokeTestError> {
+        let tx_hash = self
+            .connector
+            .finalize_deposit(receipt_id, self.tx_opts.clone())
+            .await?;
+        let receipt = self.wait_for_eth_tx(tx_hash).await?;
+
+        Ok((receipt, format!("tx {tx_hash:?}")))
+    }
+
+    async fn withdraw(&self) -> Result<((TxHash, u64), String), SmokeTestError> {
+        let tx_hash = self
+            .connector
+            .withdraw(
+                self.token.clone(),
+                self.amount,
+                self.near_signer.to_string(),
+                self.tx_opts.clone(),
+            )
+            .await?;
+        let receipt = self.wait_for_eth_tx(tx_hash).await?;
+
+        let withdraw_topic = H256(keccak256("Withdraw(string,address,uint256,string)"));
+        let log_index = receipt
+            .logs
+            .iter()
+            .find(|log| {
+                log.address == self.bridge_token_factory_address
+                    && log.topics.first() == Some(&withdraw_topic)
+            })
+            .and_then(|log| log.log_index)
+            .ok_or(SmokeTestError(
+                "Withdraw event not found in the transaction logs".to_string(),
+            ))?
+            .as_u64();
+
+        Ok((
+            (tx_hash, log_index),
+            format!("tx {tx_hash:?}, log index {log_index}"),
+        ))
+    }
+
+    /// The Ethereum light client on Near has to sync the withdrawal block before it can be proven, so the
+    /// finalization is retried until it succeeds
+    async fn finalize_withdraw(
+        &self,
+        tx_hash: TxHash,
+        log_index: u64,
+    ) -> Result<((), String), SmokeTestError> {
+        loop {
+            let finalization_tx = self.connector.finalize_withdraw(tx_hash, log_index).await?;
+
+            match self.wait_for_near_tx(finalization_tx).await {
+                Ok(_) => return Ok(((), format!("tx {finalization_tx}"))),
+                Err(err) => {
+                    tracing::warn!(error = %err, "Finalization failed, retrying");
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    pub(crate) async fn wait_for_near_tx(
+        &self,
+        tx_hash: CryptoHash,
+    ) -> Result<FinalExecutionOutcomeView, SmokeTestError> {
+        let outcome = near_rpc_client::wait_for_tx_final_outcome(
+            tx_hash,
+            self.near_signer.clone(),
+            &self.near_endpoint,
+            self.step_timeout.as_secs(),
+        )
+        .await?;
+
+        match &outcome.status {
+            FinalExecutionStatus::SuccessValue(_) => Ok(outcome),
+            status => Err(SmokeTestError(format!(
+                "Transaction {tx_hash} failed: {status:?}"
+            ))),
+        }
+    }
+
+    pub(crate) async fn wait_for_eth_tx(
+        &self,
+        tx_hash: TxHash,
+    ) -> Result<TransactionReceipt, SmokeTestError> {
+        loop {
+            match self.eth_provider.get_transaction_receipt(tx_hash).await? {
+                Some(receipt) if receipt.status == Some(1.into()) => return Ok(receipt),
+                Some(_) => return Err(SmokeTestError(format!("Transaction {tx_hash:?} reverted"))),
+                None => tokio::time::sleep(POLL_INTERVAL).await,
+            }
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use crate::{
+    combined_config,
+    error::{parse, parse_required, required, CliError, CliResult},
+    CliConfig, CommandConfig,
+};
+use bridge_connector_common::near_args::{self, AccountIdArgs};
+use bridge_relayer::{EthOnNearHeight, LightClientHeight, NearOnEthHeight};
+use clap::Args;
+use ethers::{
+    abi::parse_abi,
+    contract::Contract,
+    providers::{Http, Middleware, Provider},
+    types::{Address, U256},
+};
+use near_jsonrpc_primitives::types::query::QueryResponseKind;
+use near_primitives::types::{AccountId, BlockReference, Finality};
+use serde::Serialize;
+use std::{
+    path::Path,
+    str::FromStr,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Args, Debug)]
+pub struct SnapshotArgs {
+    /// NEP-141 token whose locked balance and minted supply are recorded. Can be repeated
+    #[clap(long = "token", required = true)]
+    pub tokens: Vec<String>,
+    /// Ethereum address of a relayer whose balance is recorded. Can be repeated
+    #[clap(long = "relayer-eth-address")]
+    pub relayer_eth_addresses: Vec<String>,
+    /// Near account of a relayer whose balance is recorded. Can be repeated
+    #[clap(long = "relayer-near-account-id")]
+    pub relayer_near_account_ids: Vec<String>,
+    /// Directory the `snapshot-<timestamp>.json` file is written to
+    #[clap(long, default_value = ".")]
+    pub output_dir: String,
+    /// Url the snapshot is POSTed to as JSON after being written
+    #[clap(long)]
+    pub webhook_url: Option<String>,
+    #[command(flatten)]
+    pub config_cli: CliConfig,
+}
+
+/// Value read from a chain, or the error that prevented reading it. A failed read doesn't abort the snapshot
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "snake_case")]
+enum Reading {
+    Value(String),
+    Error(String),
+}
+
+impl<T: ToString, E: ToString> From<Result<T, E>> for Reading {
+    fn from(result: Result<T, E>) -> Self {
+        match result {
+            Ok(value) => Reading::Value(value.to_string()),
+            Err(error) => Reading::Error(error.to_string()),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct TokenSnapshot {
+    token: String,
+    /// Balance of the token locker on Near
+    locked: Reading,
+    /// Total supply of the bridged token on Ethereum
+    minted: Reading,
+}
+
+#[derive(Serialize, Debug)]
+struct LightClientSnapshot {
+    name: String,
+    height: Reading,
+}
+
+#[derive(Serialize, Debug)]
+struct RelayerSnapshot {
+    chain: &'static str,
+    account: String,
+    /// Balance in wei or yoctoNEAR
+    balance: Reading,
+}
+
+#[derive(Serialize, Debug)]
+struct Snapshot {
+    /// Unix timestamp in seconds
+    timestamp: u64,
+    network: String,
+    tokens: Vec<TokenSnapshot>,
+    light_clients: Vec<LightClientSnapshot>,
+    relayers: Vec<RelayerSnapshot>,
+}
+
+/// Records the current bridge balances and light client heights to a JSON file, and optionally uploads it
+pub async fn run(args: SnapshotArgs, command_config: &CommandConfig) -> CliResult<()> {
+    let config = combined_config(args.config_cli, command_config.network.clone())?;
+
+    let near_rpc = required(config.near_rpc, "near-rpc")?;
+    let eth_rpc = required(config.eth_rpc, "eth-rpc")?;
+    let eth_provider: Provider<Http> = parse(&eth_rpc, "--eth-rpc")?;
+    let token_locker_id: AccountId =
+        parse_required(config.token_locker_id.as_deref(), "token-locker-id")?;
+    let bridge_token_factory_address: Address = parse_required(
+        config.bridge_token_factory_address.as_deref(),
+        "bridge-token-factory-address",
+    )?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before the unix epoch")
+        .as_secs();
+
+    let mut tokens = Vec::new();
+    for token in args.tokens {
+        tokens.push(TokenSnapshot {
+            locked: ft_balance_of(&near_rpc, &token, &token_locker_id)
+                .await
+                .into(),
+            minted: minted_supply(&eth_provider, bridge_token_factory_address, &token)
+                .await
+                .into(),
+            token,
+        });
+    }
+
+    let mut light_client_sources: Vec<Box<dyn LightClientHeight>> = Vec::new();
+    if let Some(address) = &config.near_light_client_eth_address {
+        light_client_sources.push(Box::new(NearOnEthHeight::new(
+            &eth_rpc,
+            parse(address, "--near-light-client-eth-address")?,
+        )));
+    }
+    if let Some(account_id) = &config.eth_light_client_account_id {
+        light_client_sources.push(Box::new(EthOnNearHeight::new(
+            &near_rpc,
+            parse(account_id, "--eth-light-client-account-id")?,
+        )));
+    }
+
+    let mut light_clients = Vec::new();
+    for source in light_client_sources {
+        light_clients.push(LightClientSnapshot {
+            name: source.name().to_string(),
+            height: source.height().await.into(),
+        });
+    }
+
+    let mut relayers = Vec::new();
+    for address in args.relayer_eth_addresses {
+        let balance = match Address::from_str(&address) {
+            Ok(parsed) => eth_provider
+                .get_balance(parsed, None)
+                .await
+                .map_err(|e| e.to_string())
+                .into(),
+            Err(_) => Reading::Error(format!("Invalid address: {address}")),
+        };
+        relayers.push(RelayerSnapshot {
+            chain: "eth",
+            account: address,
+            balance,
+        });
+    }
+    for account_id in args.relayer_near_account_ids {
+        relayers.push(RelayerSnapshot {
+            chain: "near",
+            balance: near_balance(&near_rpc, &account_id).await.into(),
+            account: account_id,
+        });
+    }
+
+    let snapshot = Snapshot {
+        timestamp,
+        network: format!("{:?}", command_config.network).to_lowercase(),
+        tokens,
+        light_clients,
+        relayers,
+    };
+    let json = serde_json::to_string_pretty(&snapshot).expect("Failed to serialize the snapshot");
+
+    let path = Path::new(&args.output_dir).join(format!("snapshot-{timestamp}.json"));
+    std::fs::write(&path, &json)
+        .map_err(|e| CliError::Failed(format!("Failed to write {}: {e}", path.display())))?;
+    tracing::info!("Snapshot written to {}", path.display());
+
+    if let Some(webhook_url) = args.webhook_url {
+        reqwest::Client::new()
+            .post(&webhook_url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(json)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| CliError::Failed(format!("Failed to upload the snapshot: {e}")))?;
+        tracing::info!("Snapshot uploaded to {webhook_url}");
+    }
+
+    Ok(())
+}
+
+pub(crate) async fn ft_balance_of(
+    near_rpc: &str,
+    token: &str,
+    account_id: &AccountId,
+) -> Result<String, String> {
+    let token = AccountId::from_str(token).map_err(|_| format!("Invalid token id: {token}"))?;
+    let response = near_rpc_client::view_raw(
+        near_rpc,
+        BlockReference::Finality(Finality::Final),
+        token,
+        "ft_balance_of".to_string(),
+        near_args::to_bytes(&AccountIdArgs {
+            account_id: account_id.to_string(),
+        }),
+    )
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let QueryResponseKind::CallResult(result) = response.kind else {
+        return Err("Unexpected ft_balance_of response".to_string());
+    };
+
+    serde_json::from_slice::<String>(&result.result)
+        .map_err(|_| "Invalid ft_balance_of response".to_string())
+}
+
+/// Total supply of the Ethereum token the factory deployed for `token`
+async fn minted_supply(
+    eth_provider: &Provider<Http>,
+    bridge_token_factory_address: Address,
+    token: &str,
+) -> Result<U256, String> {
+    let client = Arc::new(eth_provider.clone());
+
+    let factory = Contract::new(
+        bridge_token_factory_address,
+        parse_abi(&[
+            "function nearToEthToken(string calldata nearTokenId) external view returns (address)",
+        ])
+        .expect("Valid abi"),
+        client.clone(),
+    );
+    let eth_token: Address = factory
+        .method("nearToEthToken", token.to_string())
+        .map_err(|e| e.to_string())?
+        .call()
+        .await
+        .map_err(|e| e.to_string())?;
+    if eth_token.is_zero() {
+        return Err(format!("{token} is not bridged to Ethereum"));
+    }
+
+    let erc20 = Contract::new(
+        eth_token,
+        parse_abi(&["function totalSupply() external view returns (uint256)"]).expect("Valid abi"),
+        client,
+    );
+    erc20
+        .method::<_, U256>("totalSupply", ())
+        .map_err(|e| e.to_string())?
+        .call()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn near_balance(near_rpc: &str, account_id: &str) -> Result<u128, String> {
+    let account_id =
+        AccountId::from_str(account_id).map_err(|_| format!("Invalid account id: {account_id}"))?;
+
+    near_rpc_client::view_account(near_rpc, account_id)
+        .await
+        .map(|account| account.amount)
+        .map_err(|e| e.to_string())
+}
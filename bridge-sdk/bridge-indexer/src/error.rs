@@ -0,0 +1,15 @@
+use eth_proof::EthClientError;
+use near_rpc_client::NearRpcError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum IndexerError {
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+    #[error("Error communicating with Ethereum RPC: {0}")]
+    EthRpcError(#[from] EthClientError),
+    #[error("Error communicating with Near RPC: {0}")]
+    NearRpcError(#[from] NearRpcError),
+    #[cfg(feature = "sqlite")]
+    #[error("Could not persist events: {0}")]
+    SqliteError(#[from] rusqlite::Error),
+}
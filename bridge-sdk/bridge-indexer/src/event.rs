@@ -0,0 +1,135 @@
+use bridge_connector_common::bridge_connector::TransferId;
+use ethereum_types::{H160, H256};
+use hasher::{Hasher, HasherKeccak};
+use near_primitives::types::AccountId;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Chain {
+    Near,
+    Eth,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BridgeEventKind {
+    /// NEP-141 tokens locked in the token locker on Near
+    TokenLocked,
+    /// NEP-141 tokens unlocked by the token locker on Near
+    TokenUnlocked,
+    /// Bridged tokens minted by the token factory on Ethereum
+    TokenMinted,
+    /// Bridged tokens burned through the token factory on Ethereum
+    TokenBurned,
+    /// ETH deposited to the EthCustodian
+    EthDeposited,
+    /// ETH withdrawn from the EthCustodian
+    EthWithdrawn,
+}
+
+/// Bridge event found on one of the chains
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeTransferEvent {
+    pub kind: BridgeEventKind,
+    pub transfer_id: TransferId,
+    pub block_height: u64,
+    /// Account id or address of the contract
+    pub contract: String,
+    /// Function call arguments on Near, log data on Ethereum
+    pub data: Vec<u8>,
+}
+
+impl BridgeTransferEvent {
+    pub fn chain(&self) -> Chain {
+        match self.transfer_id {
+            TransferId::Near { .. } => Chain::Near,
+            TransferId::Eth { .. } => Chain::Eth,
+        }
+    }
+}
+
+/// Contract whose events are indexed
+#[derive(Debug, Clone)]
+pub enum EventSource {
+    /// Receipts calling `method_name` on `account_id`
+    Near {
+        account_id: AccountId,
+        method_name: String,
+        kind: BridgeEventKind,
+    },
+    /// Logs emitted by `address` with the first topic `topic`
+    Eth {
+        address: H160,
+        topic: H256,
+        kind: BridgeEventKind,
+    },
+}
+
+impl EventSource {
+    pub fn near(account_id: AccountId, method_name: &str, kind: BridgeEventKind) -> Self {
+        Self::Near {
+            account_id,
+            method_name: method_name.to_string(),
+            kind,
+        }
+    }
+
+    /// `event_signature` is the canonical event signature, e.g. `Withdrawn(address,uint128)`
+    pub fn eth(address: H160, event_signature: &str, kind: BridgeEventKind) -> Self {
+        Self::Eth {
+            address,
+            topic: H256::from_slice(&HasherKeccak::new().digest(event_signature.as_bytes())),
+            kind,
+        }
+    }
+
+    /// Lock and unlock calls of the token locker
+    pub fn token_locker(account_id: AccountId) -> Vec<Self> {
+        vec![
+            Self::near(
+                account_id.clone(),
+                "ft_on_transfer",
+                BridgeEventKind::TokenLocked,
+            ),
+            Self::near(account_id, "withdraw", BridgeEventKind::TokenUnlocked),
+        ]
+    }
+
+    /// Mint and burn events of the bridge token factory
+    pub fn token_factory(address: H160) -> Vec<Self> {
+        vec![
+            Self::eth(
+                address,
+                "Deposit(string,uint256,address)",
+                BridgeEventKind::TokenMinted,
+            ),
+            Self::eth(
+                address,
+                "Withdraw(string,address,uint256,string)",
+                BridgeEventKind::TokenBurned,
+            ),
+        ]
+    }
+
+    /// Deposit and withdraw events of the EthCustodian
+    pub fn eth_custodian(address: H160) -> Vec<Self> {
+        vec![
+            Self::eth(
+                address,
+                "Deposited(address,string,uint256,uint256)",
+                BridgeEventKind::EthDeposited,
+            ),
+            Self::eth(
+                address,
+                "Withdrawn(address,uint128)",
+                BridgeEventKind::EthWithdrawn,
+            ),
+        ]
+    }
+
+    pub fn chain(&self) -> Chain {
+        match self {
+            Self::Near { .. } => Chain::Near,
+            Self::Eth { .. } => Chain::Eth,
+        }
+    }
+}
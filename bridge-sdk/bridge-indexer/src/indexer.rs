@@ -0,0 +1,405 @@
+use crate::{
+    error::IndexerError,
+    event::{BridgeTransferEvent, Chain, EventSource},
+};
+use bridge_connector_common::bridge_connector::TransferId;
+use eth_proof::{types::Log, EthClientError, EthRPCClient, EthRpc};
+use futures::Stream;
+use near_jsonrpc_client::{
+    errors::{JsonRpcError, JsonRpcServerError},
+    methods::block::RpcBlockError,
+};
+use near_primitives::{
+    types::{BlockId, BlockReference, Finality},
+    views::{ActionView, ReceiptEnumView},
+};
+use near_rpc_client::{NearRpc, NearRpcClient, NearRpcError};
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+
+#[cfg(feature = "sqlite")]
+use crate::sqlite::SqliteEventStore;
+
+/// Maximum number of blocks scanned on each chain in a single poll
+const MAX_BLOCKS_PER_POLL: u64 = 1000;
+
+pub struct IndexerConfig {
+    /// NEAR RPC endpoint. Required for Near event sources, unless the indexer reads Near through
+    /// [`Indexer::with_near_rpc`]
+    pub near_endpoint: Option<String>,
+    /// Ethereum RPC endpoint. Required for Ethereum event sources, unless the indexer reads Ethereum through
+    /// [`Indexer::with_eth_rpc`]
+    pub eth_endpoint: Option<String>,
+    /// Block to start indexing Near from when no progress is persisted. Defaults to the last final block
+    pub near_start_block: Option<u64>,
+    /// Block to start indexing Ethereum from when no progress is persisted. Defaults to the latest confirmed block
+    pub eth_start_block: Option<u64>,
+    /// Number of blocks an Ethereum block has to be buried under to be indexed
+    pub eth_confirmations: u64,
+    /// Delay between polls once the indexer has caught up with the chains
+    pub poll_interval: Duration,
+}
+
+impl Default for IndexerConfig {
+    fn default() -> Self {
+        Self {
+            near_endpoint: None,
+            eth_endpoint: None,
+            near_start_block: None,
+            eth_start_block: None,
+            eth_confirmations: 12,
+            poll_interval: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Scans Near blocks and Ethereum logs for the events of the configured sources
+pub struct Indexer {
+    config: IndexerConfig,
+    sources: Vec<EventSource>,
+    near_cursor: Option<u64>,
+    eth_cursor: Option<u64>,
+    caught_up: bool,
+    near_rpc: Option<Arc<dyn NearRpc>>,
+    eth_rpc: Option<Arc<dyn EthRpc>>,
+    #[cfg(feature = "sqlite")]
+    store: Option<SqliteEventStore>,
+}
+
+impl Indexer {
+    pub fn new(config: IndexerConfig, sources: Vec<EventSource>) -> Self {
+        Self {
+            near_cursor: config.near_start_block,
+            eth_cursor: config.eth_start_block,
+            // Created once, so that the polls share the health of the endpoints
+            near_rpc: config
+                .near_endpoint
+                .as_deref()
+                .map(|endpoint| Arc::new(NearRpcClient::new(endpoint)) as Arc<dyn NearRpc>),
+            config,
+            sources,
+            caught_up: false,
+            eth_rpc: None,
+            #[cfg(feature = "sqlite")]
+            store: None,
+        }
+    }
+
+    /// Reads Near through `near_rpc` instead of `near_endpoint`, e.g. a `MockNearRpc` in tests
+    pub fn with_near_rpc(mut self, near_rpc: Arc<dyn NearRpc>) -> Self {
+        self.near_rpc = Some(near_rpc);
+        self
+    }
+
+    /// Reads Ethereum through `eth_rpc` instead of `eth_endpoint`, e.g. a `MockEthRpc` in tests
+    pub fn with_eth_rpc(mut self, eth_rpc: Arc<dyn EthRpc>) -> Self {
+        self.eth_rpc = Some(eth_rpc);
+        self
+    }
+
+    /// Persists the indexed events and resumes from the progress recorded in the store
+    #[cfg(feature = "sqlite")]
+    pub fn with_store(mut self, store: SqliteEventStore) -> Result<Self, IndexerError> {
+        if let Some(cursor) = store.cursor(Chain::Near)? {
+            self.near_cursor = Some(cursor);
+        }
+        if let Some(cursor) = store.cursor(Chain::Eth)? {
+            self.eth_cursor = Some(cursor);
+        }
+
+        self.store = Some(store);
+        Ok(self)
+    }
+
+    /// Scans the blocks produced since the last poll
+    pub async fn poll(&mut self) -> Result<Vec<BridgeTransferEvent>, IndexerError> {
+        let mut events = Vec::new();
+        let mut caught_up = true;
+
+        if self.has_sources(Chain::Near) {
+            let (near_events, near_caught_up) = self.poll_near().await?;
+            events.extend(near_events);
+            caught_up &= near_caught_up;
+        }
+
+        if self.has_sources(Chain::Eth) {
+            let (eth_events, eth_caught_up) = self.poll_eth().await?;
+            events.extend(eth_events);
+            caught_up &= eth_caught_up;
+        }
+
+        self.caught_up = caught_up;
+
+        #[cfg(feature = "sqlite")]
+        if let Some(store) = &mut self.store {
+            store.insert_events(&events, self.near_cursor, self.eth_cursor)?;
+        }
+
+        Ok(events)
+    }
+
+    /// Endless stream of the indexed events. RPC errors are yielded without ending the stream, so that the consumer
+    /// decides whether to keep going
+    pub fn stream(self) -> impl Stream<Item = Result<BridgeTransferEvent, IndexerError>> {
+        futures::stream::unfold(
+            (self, VecDeque::new()),
+            |(mut indexer, mut buffer)| async move {
+                loop {
+                    if let Some(event) = buffer.pop_front() {
+                        return Some((Ok(event), (indexer, buffer)));
+                    }
+
+                    if indexer.caught_up {
+                        tokio::time::sleep(indexer.config.poll_interval).await;
+                    }
+
+                    match indexer.poll().await {
+                        Ok(events) => buffer.extend(events),
+                        Err(err) => {
+                            // Back off, so that a failing RPC isn't polled in a tight loop
+                            indexer.caught_up = true;
+                            return Some((Err(err), (indexer, buffer)));
+                        }
+                    }
+                }
+            },
+        )
+    }
+
+    async fn poll_near(&mut self) -> Result<(Vec<BridgeTransferEvent>, bool), IndexerError> {
+        let final_block = self
+            .near_rpc()?
+            .get_block(BlockReference::Finality(Finality::Final))
+            .await?
+            .header
+            .height;
+
+        let from_block = self.near_cursor.unwrap_or(final_block);
+        if from_block > final_block {
+            return Ok((Vec::new(), true));
+        }
+
+        let to_block = final_block.min(from_block + MAX_BLOCKS_PER_POLL - 1);
+
+        let mut events = Vec::new();
+        for height in from_block..=to_block {
+            events.extend(self.scan_near_block(height).await?);
+        }
+
+        tracing::debug!(
+            from_block,
+            to_block,
+            found = events.len(),
+            "Indexed Near blocks"
+        );
+
+        self.near_cursor = Some(to_block + 1);
+        Ok((events, to_block == final_block))
+    }
+
+    async fn scan_near_block(&self, height: u64) -> Result<Vec<BridgeTransferEvent>, IndexerError> {
+        let near_rpc = self.near_rpc()?;
+        let block = match near_rpc
+            .get_block(BlockReference::BlockId(BlockId::Height(height)))
+            .await
+        {
+            Ok(block) => block,
+            // Not every height has a block
+            Err(NearRpcError::RpcBlockError(JsonRpcError::ServerError(
+                JsonRpcServerError::HandlerError(RpcBlockError::UnknownBlock { .. }),
+            ))) => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut events = Vec::new();
+        for chunk_header in block.chunks {
+            if chunk_header.height_included != height {
+                continue;
+            }
+
+            let chunk = near_rpc.get_chunk(chunk_header.chunk_hash).await?;
+            for receipt in chunk.receipts {
+                let ReceiptEnumView::Action { actions, .. } = &receipt.receipt else {
+                    continue;
+                };
+
+                for action in actions {
+                    let ActionView::FunctionCall {
+                        method_name, args, ..
+                    } = action
+                    else {
+                        continue;
+                    };
+
+                    events.extend(self.sources.iter().filter_map(|source| match source {
+                        EventSource::Near {
+                            account_id,
+                            method_name: source_method_name,
+                            kind,
+                        } if *account_id == receipt.receiver_id
+                            && source_method_name == method_name =>
+                        {
+                            Some(BridgeTransferEvent {
+                                kind: *kind,
+                                transfer_id: TransferId::Near {
+                                    receipt_id: receipt.receipt_id,
+                                },
+                                block_height: height,
+                                contract: account_id.to_string(),
+                                data: args.to_vec(),
+                            })
+                        }
+                        _ => None,
+                    }));
+                }
+            }
+        }
+
+        Ok(events)
+    }
+
+    async fn poll_eth(&mut self) -> Result<(Vec<BridgeTransferEvent>, bool), IndexerError> {
+        let eth_client = self.eth_rpc()?;
+        let latest_block = eth_client
+            .get_block_number()
+            .await?
+            .as_u64()
+            .saturating_sub(self.config.eth_confirmations);
+
+        let from_block = self.eth_cursor.unwrap_or(latest_block);
+        if from_block > latest_block {
+            return Ok((Vec::new(), true));
+        }
+
+        let to_block = latest_block.min(from_block + MAX_BLOCKS_PER_POLL - 1);
+
+        let mut events = Vec::new();
+        for source in &self.sources {
+            let EventSource::Eth {
+                address,
+                topic,
+                kind,
+            } = source
+            else {
+                continue;
+            };
+
+            let logs = eth_client
+                .get_logs(address, topic, from_block.into(), to_block.into())
+                .await?;
+            let logs: Vec<Log> = serde_json::from_value(logs).map_err(EthClientError::from)?;
+
+            events.extend(logs.into_iter().filter_map(|log| {
+                Some(BridgeTransferEvent {
+                    kind: *kind,
+                    transfer_id: TransferId::Eth {
+                        tx_hash: log.transaction_hash?,
+                        log_index: log.log_index.as_u64(),
+                    },
+                    block_height: log.block_number?.as_u64(),
+                    contract: format!("{address:#x}"),
+                    data: log.data.0,
+                })
+            }));
+        }
+
+        events.sort_by_key(|event| match event.transfer_id {
+            TransferId::Eth { log_index, .. } => (event.block_height, log_index),
+            TransferId::Near { .. } => (event.block_height, 0),
+        });
+
+        tracing::debug!(
+            from_block,
+            to_block,
+            found = events.len(),
+            "Indexed Ethereum logs"
+        );
+
+        self.eth_cursor = Some(to_block + 1);
+        Ok((events, to_block == latest_block))
+    }
+
+    fn has_sources(&self, chain: Chain) -> bool {
+        self.sources.iter().any(|source| source.chain() == chain)
+    }
+
+    /// Set by [`Indexer::with_near_rpc`], or created from `near_endpoint` by [`Indexer::new`]
+    fn near_rpc(&self) -> Result<Arc<dyn NearRpc>, IndexerError> {
+        self.near_rpc.clone().ok_or(IndexerError::ConfigError(
+            "Near rpc endpoint is not set".to_string(),
+        ))
+    }
+
+    fn eth_rpc(&self) -> Result<Arc<dyn EthRpc>, IndexerError> {
+        match &self.eth_rpc {
+            Some(eth_rpc) => Ok(eth_rpc.clone()),
+            None => Ok(Arc::new(EthRPCClient::new(self.eth_endpoint()?))),
+        }
+    }
+
+    fn eth_endpoint(&self) -> Result<&str, IndexerError> {
+        self.config
+            .eth_endpoint
+            .as_deref()
+            .ok_or(IndexerError::ConfigError(
+                "Ethereum rpc endpoint is not set".to_string(),
+            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::BridgeEventKind;
+    use eth_proof::MockEthRpc;
+    use ethereum_types::{H160, H256};
+
+    #[tokio::test]
+    async fn test_poll_eth() {
+        let address = H160::repeat_byte(1);
+        let tx_hash = H256::repeat_byte(2);
+        let source = EventSource::eth(
+            address,
+            "Withdrawn(address,uint128)",
+            BridgeEventKind::EthWithdrawn,
+        );
+        let EventSource::Eth { topic, .. } = source.clone() else {
+            unreachable!()
+        };
+
+        let eth_rpc = MockEthRpc::new(112);
+        for (block_number, log_index) in [(100u64, 2u64), (101, 0)] {
+            eth_rpc.add_log(serde_json::json!({
+                "address": format!("{address:#x}"),
+                "topics": [format!("{topic:#x}")],
+                "data": "0x01",
+                "logIndex": format!("{log_index:#x}"),
+                "blockNumber": format!("{block_number:#x}"),
+                "transactionHash": format!("{tx_hash:#x}"),
+            }));
+        }
+
+        let mut indexer = Indexer::new(
+            IndexerConfig {
+                eth_start_block: Some(90),
+                ..Default::default()
+            },
+            vec![source],
+        )
+        .with_eth_rpc(Arc::new(eth_rpc));
+
+        // The log of block 101 doesn't have enough confirmations yet
+        assert_eq!(
+            indexer.poll().await.unwrap(),
+            vec![BridgeTransferEvent {
+                kind: BridgeEventKind::EthWithdrawn,
+                transfer_id: TransferId::Eth {
+                    tx_hash,
+                    log_index: 2
+                },
+                block_height: 100,
+                contract: format!("{address:#x}"),
+                data: vec![1],
+            }]
+        );
+    }
+}
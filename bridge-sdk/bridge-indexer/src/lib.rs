@@ -0,0 +1,11 @@
+mod error;
+mod event;
+mod indexer;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+pub use error::IndexerError;
+pub use event::{BridgeEventKind, BridgeTransferEvent, Chain, EventSource};
+pub use indexer::{Indexer, IndexerConfig};
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteEventStore;
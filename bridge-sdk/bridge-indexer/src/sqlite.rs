@@ -0,0 +1,140 @@
+use crate::{
+    error::IndexerError,
+    event::{BridgeTransferEvent, Chain},
+};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+
+/// SQLite persistence of the indexed events and of the indexing progress
+pub struct SqliteEventStore {
+    connection: Connection,
+}
+
+impl SqliteEventStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, IndexerError> {
+        Self::init(Connection::open(path)?)
+    }
+
+    pub fn in_memory() -> Result<Self, IndexerError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(connection: Connection) -> Result<Self, IndexerError> {
+        connection.execute_batch(
+            "CREATE TABLE IF NOT EXISTS events (
+                transfer_id TEXT NOT NULL PRIMARY KEY,
+                chain TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                block_height INTEGER NOT NULL,
+                contract TEXT NOT NULL,
+                event TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS cursors (
+                chain TEXT NOT NULL PRIMARY KEY,
+                next_block INTEGER NOT NULL
+            );",
+        )?;
+
+        Ok(Self { connection })
+    }
+
+    /// Stores the events together with the progress they were indexed up to, in a single transaction
+    pub fn insert_events(
+        &mut self,
+        events: &[BridgeTransferEvent],
+        near_cursor: Option<u64>,
+        eth_cursor: Option<u64>,
+    ) -> Result<(), IndexerError> {
+        let tx = self.connection.transaction()?;
+
+        for event in events {
+            tx.execute(
+                "INSERT OR IGNORE INTO events (transfer_id, chain, kind, block_height, contract, event)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    to_json(&event.transfer_id)?,
+                    to_json(&event.chain())?,
+                    to_json(&event.kind)?,
+                    event.block_height,
+                    event.contract,
+                    to_json(event)?,
+                ],
+            )?;
+        }
+
+        for (chain, cursor) in [(Chain::Near, near_cursor), (Chain::Eth, eth_cursor)] {
+            if let Some(cursor) = cursor {
+                tx.execute(
+                    "INSERT OR REPLACE INTO cursors (chain, next_block) VALUES (?1, ?2)",
+                    params![to_json(&chain)?, cursor],
+                )?;
+            }
+        }
+
+        Ok(tx.commit()?)
+    }
+
+    /// Events stored so far, ordered by chain and block
+    pub fn events(&self) -> Result<Vec<BridgeTransferEvent>, IndexerError> {
+        let mut statement = self
+            .connection
+            .prepare("SELECT event FROM events ORDER BY chain, block_height")?;
+
+        let events = statement
+            .query_map([], |row| row.get::<_, String>(0))?
+            .map(|event| serde_json::from_str(&event?).map_err(json_error))
+            .collect::<Result<Vec<_>, IndexerError>>()?;
+
+        Ok(events)
+    }
+
+    pub fn cursor(&self, chain: Chain) -> Result<Option<u64>, IndexerError> {
+        Ok(self
+            .connection
+            .query_row(
+                "SELECT next_block FROM cursors WHERE chain = ?1",
+                params![to_json(&chain)?],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, IndexerError> {
+    serde_json::to_string(value).map_err(json_error)
+}
+
+fn json_error(err: serde_json::Error) -> IndexerError {
+    IndexerError::SqliteError(rusqlite::Error::ToSqlConversionFailure(Box::new(err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::BridgeEventKind;
+    use bridge_connector_common::bridge_connector::TransferId;
+    use ethereum_types::H256;
+
+    #[test]
+    fn stores_events_and_cursors() {
+        let mut store = SqliteEventStore::in_memory().unwrap();
+        let event = BridgeTransferEvent {
+            kind: BridgeEventKind::TokenBurned,
+            transfer_id: TransferId::Eth {
+                tx_hash: H256::repeat_byte(1),
+                log_index: 7,
+            },
+            block_height: 100,
+            contract: "0x3fefc5a4b1c02f21cbc8d3613643ba0635b9a873".to_string(),
+            data: vec![1, 2, 3],
+        };
+
+        store
+            .insert_events(&[event.clone(), event.clone()], None, Some(101))
+            .unwrap();
+
+        assert_eq!(store.events().unwrap(), vec![event]);
+        assert_eq!(store.cursor(Chain::Eth).unwrap(), Some(101));
+        assert_eq!(store.cursor(Chain::Near).unwrap(), None);
+    }
+}
@@ -0,0 +1,11 @@
+use bridge_connector_common::result::BridgeSdkError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum RelayerError {
+    #[error("Bridge SDK error: {0}")]
+    SdkError(#[from] BridgeSdkError),
+    #[error("Could not persist relayer state: {0}")]
+    StoreError(String),
+    #[error("Job queue error: {0}")]
+    QueueError(String),
+}
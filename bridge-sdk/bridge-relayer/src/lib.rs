@@ -0,0 +1,17 @@
+mod error;
+mod listener;
+mod monitor;
+pub mod queue;
+mod relayer;
+mod store;
+mod watcher;
+
+pub use error::RelayerError;
+pub use listener::{ChainListener, EthListener, ListenerMode, NearListener};
+pub use monitor::{
+    EthOnNearHeight, LightClientAlert, LightClientHeight, LightClientMonitor, MonitorConfig,
+    NearOnEthHeight,
+};
+pub use relayer::{Relayer, RelayerConfig, Worker};
+pub use store::{FailedTransfer, FinalizedTransfer, PendingTransfer, TransferStore};
+pub use watcher::{EthEventWatcher, NearEventWatcher, TransferWatcher};
@@ -0,0 +1,242 @@
+use async_trait::async_trait;
+use bridge_connector_common::{
+    failover,
+    result::{BridgeSdkError, EthRpcError, Result},
+};
+use ethers::providers::{Middleware, Provider, ProviderError, Ws};
+use futures::StreamExt;
+use near_primitives::types::{BlockReference, Finality};
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+use tokio::sync::Notify;
+
+/// Delay before reconnecting a dropped WebSocket subscription
+const WS_RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// Time a hybrid listener polls over HTTP before trying the WebSocket endpoint again
+const HYBRID_FALLBACK_DURATION: Duration = Duration::from_secs(5 * 60);
+
+/// Notifies the relayer of new blocks of a chain, so that it scans and finalizes as soon as there's something new
+/// instead of waiting for the end of its poll interval
+#[async_trait]
+pub trait ChainListener: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Signals `new_block` whenever the chain head advances. Only returns on an error the listener can't recover from
+    async fn listen(&self, new_block: &Notify) -> Result<()>;
+}
+
+/// How a listener learns about new blocks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListenerMode {
+    /// Subscribes to new heads over WebSocket, reconnecting when the connection drops
+    Ws,
+    /// Polls the chain head over HTTP, which every RPC provider supports
+    #[default]
+    Http,
+    /// Subscribes over WebSocket, and polls over HTTP while the WebSocket endpoint is unavailable
+    Hybrid,
+}
+
+impl FromStr for ListenerMode {
+    type Err = BridgeSdkError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "ws" => Ok(Self::Ws),
+            "http" => Ok(Self::Http),
+            "hybrid" => Ok(Self::Hybrid),
+            _ => Err(BridgeSdkError::InvalidArgument(format!(
+                "Unknown listener mode `{s}`, expected `ws`, `http` or `hybrid`"
+            ))),
+        }
+    }
+}
+
+/// Listens to new Ethereum blocks
+pub struct EthListener {
+    eth_endpoint: String,
+    ws_endpoint: Option<String>,
+    mode: ListenerMode,
+    poll_interval: Duration,
+}
+
+impl EthListener {
+    /// `ws_endpoint` is required by the `Ws` and `Hybrid` modes. `poll_interval` is the delay between two head
+    /// requests while polling
+    pub fn new(
+        eth_endpoint: &str,
+        ws_endpoint: Option<&str>,
+        mode: ListenerMode,
+        poll_interval: Duration,
+    ) -> Result<Self> {
+        if mode != ListenerMode::Http && ws_endpoint.is_none() {
+            return Err(BridgeSdkError::ConfigError(format!(
+                "The {mode:?} Ethereum listener requires a WebSocket endpoint"
+            )));
+        }
+
+        Ok(Self {
+            eth_endpoint: eth_endpoint.to_string(),
+            ws_endpoint: ws_endpoint.map(str::to_string),
+            mode,
+            poll_interval,
+        })
+    }
+
+    /// Returns once the subscription ends, with the reason
+    async fn subscribe(&self, ws_endpoint: &str, new_block: &Notify) -> BridgeSdkError {
+        let provider = match Provider::<Ws>::connect(ws_endpoint).await {
+            Ok(provider) => provider,
+            Err(err) => return err.into(),
+        };
+        let mut blocks = match provider.subscribe_blocks().await {
+            Ok(blocks) => blocks,
+            Err(err) => return err.into(),
+        };
+
+        tracing::debug!(listener = self.name(), "Subscribed to new Ethereum blocks");
+        while blocks.next().await.is_some() {
+            new_block.notify_one();
+        }
+
+        BridgeSdkError::EthRpcError(EthRpcError::ProviderError(ProviderError::CustomError(
+            "WebSocket connection closed".to_string(),
+        )))
+    }
+
+    async fn poll(&self, new_block: &Notify, until: Option<Instant>) {
+        poll_heads(
+            self.name(),
+            new_block,
+            self.poll_interval,
+            until,
+            || async {
+                let eth_provider = failover::eth_provider(&self.eth_endpoint)?;
+
+                Ok(eth_provider.get_block_number().await?.as_u64())
+            },
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl ChainListener for EthListener {
+    fn name(&self) -> &str {
+        "eth"
+    }
+
+    async fn listen(&self, new_block: &Notify) -> Result<()> {
+        let Some(ws_endpoint) = self
+            .ws_endpoint
+            .as_deref()
+            .filter(|_| self.mode != ListenerMode::Http)
+        else {
+            self.poll(new_block, None).await;
+            return Ok(());
+        };
+
+        loop {
+            let err = self.subscribe(ws_endpoint, new_block).await;
+            tracing::warn!(listener = self.name(), error = %err, "Ethereum block subscription ended");
+
+            match self.mode {
+                ListenerMode::Hybrid => {
+                    self.poll(new_block, Some(Instant::now() + HYBRID_FALLBACK_DURATION))
+                        .await
+                }
+                _ => tokio::time::sleep(WS_RECONNECT_DELAY).await,
+            }
+        }
+    }
+}
+
+/// Listens to new final Near blocks. Near RPC nodes don't push new blocks, so the head is polled
+pub struct NearListener {
+    near_endpoint: String,
+    poll_interval: Duration,
+}
+
+impl NearListener {
+    pub fn new(near_endpoint: &str, poll_interval: Duration) -> Self {
+        Self {
+            near_endpoint: near_endpoint.to_string(),
+            poll_interval,
+        }
+    }
+}
+
+#[async_trait]
+impl ChainListener for NearListener {
+    fn name(&self) -> &str {
+        "near"
+    }
+
+    async fn listen(&self, new_block: &Notify) -> Result<()> {
+        poll_heads(self.name(), new_block, self.poll_interval, None, || async {
+            let block = near_rpc_client::get_block(
+                &self.near_endpoint,
+                BlockReference::Finality(Finality::Final),
+            )
+            .await?;
+
+            Ok(block.header.height)
+        })
+        .await;
+
+        Ok(())
+    }
+}
+
+/// Polls the chain head every `interval` until `until`, or forever, signaling `new_block` when it advances. Failed
+/// requests are logged and retried at the next interval
+async fn poll_heads<F, Fut>(
+    listener: &str,
+    new_block: &Notify,
+    interval: Duration,
+    until: Option<Instant>,
+    head: F,
+) where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = Result<u64>>,
+{
+    let mut last_head = None;
+
+    while !until.is_some_and(|until| Instant::now() >= until) {
+        match head().await {
+            Ok(height) if last_head < Some(height) => {
+                last_head = Some(height);
+                new_block.notify_one();
+            }
+            Ok(_) => {}
+            Err(err) => tracing::warn!(listener, error = %err, "Failed to poll the chain head"),
+        }
+
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listener_mode() {
+        assert_eq!("ws".parse::<ListenerMode>().unwrap(), ListenerMode::Ws);
+        assert_eq!(
+            " Hybrid".parse::<ListenerMode>().unwrap(),
+            ListenerMode::Hybrid
+        );
+        assert!("push".parse::<ListenerMode>().is_err());
+
+        assert!(EthListener::new(
+            "http://localhost:8545",
+            None,
+            ListenerMode::Hybrid,
+            Duration::from_secs(12)
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,243 @@
+use async_trait::async_trait;
+use bridge_connector_common::result::Result;
+use eth_light_client_on_near::EthOnNearClient;
+use ethers::types::Address;
+use near_light_client_on_eth::NearOnEthClient;
+use near_primitives::types::AccountId;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Light client whose height is monitored
+#[async_trait]
+pub trait LightClientHeight: Send + Sync {
+    fn name(&self) -> &str;
+
+    async fn height(&self) -> Result<u64>;
+}
+
+/// Height of the Near light client on Ethereum
+pub struct NearOnEthHeight {
+    client: NearOnEthClient,
+}
+
+impl NearOnEthHeight {
+    pub fn new(eth_endpoint: &str, light_client_address: Address) -> Self {
+        Self {
+            client: NearOnEthClient::new(light_client_address, eth_endpoint.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl LightClientHeight for NearOnEthHeight {
+    fn name(&self) -> &str {
+        "near_on_eth"
+    }
+
+    async fn height(&self) -> Result<u64> {
+        Ok(self.client.get_sync_height().await?)
+    }
+}
+
+/// Height of the Ethereum light client on Near, e.g. `client-eth2.bridge.near`
+pub struct EthOnNearHeight {
+    client: EthOnNearClient,
+}
+
+impl EthOnNearHeight {
+    pub fn new(near_endpoint: &str, light_client_account_id: AccountId) -> Self {
+        Self {
+            client: EthOnNearClient::new(light_client_account_id, near_endpoint.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl LightClientHeight for EthOnNearHeight {
+    fn name(&self) -> &str {
+        "eth_on_near"
+    }
+
+    async fn height(&self) -> Result<u64> {
+        Ok(self.client.last_block_number().await?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "alert", rename_all = "snake_case")]
+pub enum LightClientAlert {
+    /// The height didn't change for longer than the stall threshold
+    Stalled {
+        light_client: String,
+        height: u64,
+        stalled_for_sec: u64,
+    },
+    /// The height advanced again after a stall
+    Recovered {
+        light_client: String,
+        height: u64,
+        stalled_for_sec: u64,
+    },
+}
+
+pub struct MonitorConfig {
+    /// Delay between two height checks
+    pub poll_interval: Duration,
+    /// Time a light client height has to stay unchanged before it is reported as stalled
+    pub stall_threshold: Duration,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(60),
+            stall_threshold: Duration::from_secs(2 * 60 * 60),
+        }
+    }
+}
+
+struct MonitoredClient {
+    source: Box<dyn LightClientHeight>,
+    height: Option<u64>,
+    changed_at: Instant,
+    stalled: bool,
+}
+
+/// Tracks light client heights over time and raises alerts when they stop advancing, since a stalled light client
+/// silently blocks every finalization on its chain
+pub struct LightClientMonitor {
+    clients: Vec<MonitoredClient>,
+    config: MonitorConfig,
+}
+
+impl LightClientMonitor {
+    pub fn new(config: MonitorConfig) -> Self {
+        Self {
+            clients: Vec::new(),
+            config,
+        }
+    }
+
+    pub fn with_light_client(mut self, source: impl LightClientHeight + 'static) -> Self {
+        self.clients.push(MonitoredClient {
+            source: Box::new(source),
+            height: None,
+            changed_at: Instant::now(),
+            stalled: false,
+        });
+        self
+    }
+
+    /// Checks the heights forever, logging the alerts as warnings
+    #[tracing::instrument(skip_all, name = "LIGHT_CLIENT_MONITOR")]
+    pub async fn run(&mut self) {
+        loop {
+            for alert in self.check().await {
+                let alert_json = serde_json::to_string(&alert).unwrap_or_default();
+                match &alert {
+                    LightClientAlert::Stalled { .. } => {
+                        tracing::warn!(alert = %alert_json, "Light client stalled")
+                    }
+                    LightClientAlert::Recovered { .. } => {
+                        tracing::info!(alert = %alert_json, "Light client recovered")
+                    }
+                }
+            }
+
+            tokio::time::sleep(self.config.poll_interval).await;
+        }
+    }
+
+    /// Fetches the current heights and returns the alerts raised since the previous check
+    pub async fn check(&mut self) -> Vec<LightClientAlert> {
+        let mut alerts = Vec::new();
+
+        for client in self.clients.iter_mut() {
+            match client.source.height().await {
+                Ok(height) => alerts.extend(client.update(height, self.config.stall_threshold)),
+                Err(err) => tracing::warn!(
+                    light_client = client.source.name(),
+                    error = %err,
+                    "Failed to fetch light client height"
+                ),
+            }
+        }
+
+        alerts
+    }
+}
+
+impl MonitoredClient {
+    fn update(&mut self, height: u64, stall_threshold: Duration) -> Option<LightClientAlert> {
+        let now = Instant::now();
+        let unchanged_for = now.duration_since(self.changed_at);
+        let light_client = self.source.name().to_string();
+
+        if self.height != Some(height) {
+            self.height = Some(height);
+            self.changed_at = now;
+
+            if self.stalled {
+                self.stalled = false;
+                return Some(LightClientAlert::Recovered {
+                    light_client,
+                    height,
+                    stalled_for_sec: unchanged_for.as_secs(),
+                });
+            }
+
+            return None;
+        }
+
+        if !self.stalled && unchanged_for >= stall_threshold {
+            self.stalled = true;
+            return Some(LightClientAlert::Stalled {
+                light_client,
+                height,
+                stalled_for_sec: unchanged_for.as_secs(),
+            });
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedHeight;
+
+    #[async_trait]
+    impl LightClientHeight for FixedHeight {
+        fn name(&self) -> &str {
+            "fixed"
+        }
+
+        async fn height(&self) -> Result<u64> {
+            Ok(0)
+        }
+    }
+
+    #[test]
+    fn alerts_once_on_stall_and_on_recovery() {
+        let mut client = MonitoredClient {
+            source: Box::new(FixedHeight),
+            height: None,
+            changed_at: Instant::now(),
+            stalled: false,
+        };
+
+        assert_eq!(client.update(10, Duration::ZERO), None);
+        assert!(matches!(
+            client.update(10, Duration::ZERO),
+            Some(LightClientAlert::Stalled { height: 10, .. })
+        ));
+        assert_eq!(client.update(10, Duration::ZERO), None);
+        assert!(matches!(
+            client.update(11, Duration::ZERO),
+            Some(LightClientAlert::Recovered { height: 11, .. })
+        ));
+        assert_eq!(client.update(11, Duration::from_secs(60)), None);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::error::RelayerError;
+use async_trait::async_trait;
+use bridge_connector_common::bridge_connector::{TransactionId, TransferId};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "nats")]
+mod nats_jetstream;
+#[cfg(feature = "redis")]
+mod redis_streams;
+
+#[cfg(feature = "nats")]
+pub use nats_jetstream::NatsQueue;
+#[cfg(feature = "redis")]
+pub use redis_streams::RedisQueue;
+
+/// Request to finalize a transfer, published by a queue-backed [`Relayer`](crate::Relayer)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinalizeJob {
+    pub transfer_id: TransferId,
+}
+
+/// Outcome of a [`FinalizeJob`], published by a [`Worker`](crate::Worker)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinalizeResult {
+    pub transfer_id: TransferId,
+    pub outcome: JobOutcome,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JobOutcome {
+    /// Finalized by the transaction, `None` if the transfer was already finalized by another relayer
+    Finalized(Option<TransactionId>),
+    /// The transfer can't be proven on the destination chain yet, or its status couldn't be fetched
+    NotReady,
+    /// The transfer failed on the source chain, so it will never be finalized
+    SourceFailed,
+    /// The finalization transaction couldn't be sent or failed
+    FinalizationFailed(String),
+    /// The finalization failed in a way that retrying won't fix, e.g. an invalid proof or configuration
+    FinalizationRejected(String),
+}
+
+/// Message received from a queue. It is redelivered until acknowledged
+#[derive(Debug, Clone)]
+pub struct Delivery<T> {
+    pub id: String,
+    pub message: T,
+}
+
+/// External queue shared by a relayer publishing finalize jobs and any number of workers executing them
+#[async_trait]
+pub trait JobQueue: Send + Sync {
+    async fn publish_job(&self, job: &FinalizeJob) -> Result<(), RelayerError>;
+
+    /// Waits for the next job. Returns `None` if no job arrived within the queue's read timeout
+    async fn next_job(&self) -> Result<Option<Delivery<FinalizeJob>>, RelayerError>;
+
+    async fn ack_job(&self, delivery_id: &str) -> Result<(), RelayerError>;
+
+    async fn publish_result(&self, result: &FinalizeResult) -> Result<(), RelayerError>;
+
+    /// Waits for the next result. Returns `None` if no result arrived within the queue's read timeout
+    async fn next_result(&self) -> Result<Option<Delivery<FinalizeResult>>, RelayerError>;
+
+    async fn ack_result(&self, delivery_id: &str) -> Result<(), RelayerError>;
+}
+
+/// Connects to the queue at `url`, picking the backend from the scheme: `redis://`, `rediss://` or `nats://`.
+/// Messages are namespaced with `name`, and `consumer` identifies this instance among the ones sharing the queue
+#[cfg_attr(not(any(feature = "redis", feature = "nats")), allow(unused_variables))]
+pub async fn connect_queue(
+    url: &str,
+    name: &str,
+    consumer: &str,
+) -> Result<Box<dyn JobQueue>, RelayerError> {
+    let scheme = url.split("://").next().unwrap_or_default();
+
+    match scheme {
+        #[cfg(feature = "redis")]
+        "redis" | "rediss" => Ok(Box::new(RedisQueue::connect(url, name, consumer).await?)),
+        #[cfg(feature = "nats")]
+        "nats" => Ok(Box::new(NatsQueue::connect(url, name, consumer).await?)),
+        _ => Err(RelayerError::QueueError(format!(
+            "Unsupported queue url {url}. Supported backends depend on the enabled `redis` and `nats` features"
+        ))),
+    }
+}
+
+#[cfg(any(feature = "redis", feature = "nats"))]
+fn queue_error(err: impl std::fmt::Display) -> RelayerError {
+    RelayerError::QueueError(err.to_string())
+}
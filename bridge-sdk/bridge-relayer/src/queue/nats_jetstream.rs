@@ -0,0 +1,153 @@
+use super::{queue_error, Delivery, FinalizeJob, FinalizeResult, JobQueue};
+use crate::error::RelayerError;
+use async_nats::jetstream::{
+    self,
+    consumer::{pull, AckPolicy, PullConsumer},
+    Message,
+};
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+const JOBS_SUBJECT: &str = "jobs";
+const RESULTS_SUBJECT: &str = "results";
+/// Durable consumer shared by the workers, so that every job is delivered to a single worker
+const WORKERS_CONSUMER: &str = "workers";
+const RELAYER_CONSUMER: &str = "relayer";
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// [`JobQueue`] backed by a JetStream stream named `name`, with jobs and results published to the `<name>.jobs` and
+/// `<name>.results` subjects
+pub struct NatsQueue {
+    context: jetstream::Context,
+    name: String,
+    jobs: PullConsumer,
+    results: PullConsumer,
+    /// Delivered messages waiting for an ack, keyed by delivery id
+    unacked: Mutex<HashMap<String, Message>>,
+}
+
+impl NatsQueue {
+    /// Every JetStream consumer is durable and shared, so `consumer` is only used to name the connection
+    pub async fn connect(url: &str, name: &str, consumer: &str) -> Result<Self, RelayerError> {
+        let client = async_nats::ConnectOptions::new()
+            .name(consumer)
+            .connect(url)
+            .await
+            .map_err(queue_error)?;
+        let context = jetstream::new(client);
+
+        let stream = context
+            .get_or_create_stream(jetstream::stream::Config {
+                name: name.to_string(),
+                subjects: vec![format!("{name}.>")],
+                ..Default::default()
+            })
+            .await
+            .map_err(queue_error)?;
+
+        let create_consumer = |durable_name: &str, subject: &str| {
+            stream.get_or_create_consumer(
+                durable_name,
+                pull::Config {
+                    durable_name: Some(durable_name.to_string()),
+                    filter_subject: format!("{name}.{subject}"),
+                    ack_policy: AckPolicy::Explicit,
+                    ..Default::default()
+                },
+            )
+        };
+        let jobs = create_consumer(WORKERS_CONSUMER, JOBS_SUBJECT)
+            .await
+            .map_err(queue_error)?;
+        let results = create_consumer(RELAYER_CONSUMER, RESULTS_SUBJECT)
+            .await
+            .map_err(queue_error)?;
+
+        Ok(Self {
+            context,
+            name: name.to_string(),
+            jobs,
+            results,
+            unacked: Mutex::new(HashMap::new()),
+        })
+    }
+
+    async fn publish<T: Serialize>(&self, subject: &str, message: &T) -> Result<(), RelayerError> {
+        let data = serde_json::to_vec(message).map_err(queue_error)?;
+
+        self.context
+            .publish(format!("{}.{subject}", self.name), data.into())
+            .await
+            .map_err(queue_error)?
+            .await
+            .map_err(queue_error)?;
+
+        Ok(())
+    }
+
+    async fn next<T: DeserializeOwned>(
+        &self,
+        consumer: &PullConsumer,
+    ) -> Result<Option<Delivery<T>>, RelayerError> {
+        let mut messages = consumer
+            .fetch()
+            .max_messages(1)
+            .expires(READ_TIMEOUT)
+            .messages()
+            .await
+            .map_err(queue_error)?;
+
+        let Some(message) = messages.next().await.transpose().map_err(queue_error)? else {
+            return Ok(None);
+        };
+
+        let info = message.info().map_err(queue_error)?;
+        let id = format!("{}:{}", info.stream, info.stream_sequence);
+        let payload = serde_json::from_slice(&message.payload).map_err(queue_error)?;
+
+        self.unacked.lock().unwrap().insert(id.clone(), message);
+
+        Ok(Some(Delivery {
+            id,
+            message: payload,
+        }))
+    }
+
+    async fn ack(&self, delivery_id: &str) -> Result<(), RelayerError> {
+        let message = self.unacked.lock().unwrap().remove(delivery_id);
+
+        match message {
+            Some(message) => message.ack().await.map_err(queue_error),
+            None => Err(queue_error(format!("Unknown delivery {delivery_id}"))),
+        }
+    }
+}
+
+#[async_trait]
+impl JobQueue for NatsQueue {
+    async fn publish_job(&self, job: &FinalizeJob) -> Result<(), RelayerError> {
+        self.publish(JOBS_SUBJECT, job).await
+    }
+
+    async fn next_job(&self) -> Result<Option<Delivery<FinalizeJob>>, RelayerError> {
+        self.next(&self.jobs).await
+    }
+
+    async fn ack_job(&self, delivery_id: &str) -> Result<(), RelayerError> {
+        self.ack(delivery_id).await
+    }
+
+    async fn publish_result(&self, result: &FinalizeResult) -> Result<(), RelayerError> {
+        self.publish(RESULTS_SUBJECT, result).await
+    }
+
+    async fn next_result(&self) -> Result<Option<Delivery<FinalizeResult>>, RelayerError> {
+        self.next(&self.results).await
+    }
+
+    async fn ack_result(&self, delivery_id: &str) -> Result<(), RelayerError> {
+        self.ack(delivery_id).await
+    }
+}
@@ -0,0 +1,148 @@
+use super::{queue_error, Delivery, FinalizeJob, FinalizeResult, JobQueue};
+use crate::error::RelayerError;
+use async_trait::async_trait;
+use redis::{
+    aio::MultiplexedConnection,
+    streams::{StreamReadOptions, StreamReadReply},
+    AsyncCommands, Client,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::time::Duration;
+
+const JOBS_STREAM: &str = "jobs";
+const RESULTS_STREAM: &str = "results";
+/// Consumer group shared by the workers, so that every job is delivered to a single worker
+const WORKERS_GROUP: &str = "workers";
+const RELAYER_GROUP: &str = "relayer";
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// [`JobQueue`] backed by two Redis streams, `<name>:jobs` and `<name>:results`, read through consumer groups
+pub struct RedisQueue {
+    connection: MultiplexedConnection,
+    name: String,
+    consumer: String,
+}
+
+impl RedisQueue {
+    pub async fn connect(url: &str, name: &str, consumer: &str) -> Result<Self, RelayerError> {
+        let connection = Client::open(url)
+            .map_err(queue_error)?
+            .get_multiplexed_tokio_connection()
+            .await
+            .map_err(queue_error)?;
+
+        let queue = Self {
+            connection,
+            name: name.to_string(),
+            consumer: consumer.to_string(),
+        };
+        queue.create_group(JOBS_STREAM, WORKERS_GROUP).await?;
+        queue.create_group(RESULTS_STREAM, RELAYER_GROUP).await?;
+
+        Ok(queue)
+    }
+
+    fn key(&self, stream: &str) -> String {
+        format!("{}:{stream}", self.name)
+    }
+
+    async fn create_group(&self, stream: &str, group: &str) -> Result<(), RelayerError> {
+        let result: redis::RedisResult<()> = self
+            .connection
+            .clone()
+            .xgroup_create_mkstream(self.key(stream), group, "0")
+            .await;
+
+        match result {
+            Err(err) if err.code() != Some("BUSYGROUP") => Err(queue_error(err)),
+            _ => Ok(()),
+        }
+    }
+
+    async fn publish<T: Serialize>(&self, stream: &str, message: &T) -> Result<(), RelayerError> {
+        let data = serde_json::to_string(message).map_err(queue_error)?;
+
+        let _: String = self
+            .connection
+            .clone()
+            .xadd(self.key(stream), "*", &[("data", data)])
+            .await
+            .map_err(queue_error)?;
+
+        Ok(())
+    }
+
+    async fn next<T: DeserializeOwned>(
+        &self,
+        stream: &str,
+        group: &str,
+    ) -> Result<Option<Delivery<T>>, RelayerError> {
+        let options = StreamReadOptions::default()
+            .group(group, &self.consumer)
+            .count(1)
+            .block(READ_TIMEOUT.as_millis() as usize);
+
+        let reply: Option<StreamReadReply> = self
+            .connection
+            .clone()
+            .xread_options(&[self.key(stream)], &[">"], &options)
+            .await
+            .map_err(queue_error)?;
+
+        let Some(entry) = reply
+            .into_iter()
+            .flat_map(|reply| reply.keys)
+            .flat_map(|key| key.ids)
+            .next()
+        else {
+            return Ok(None);
+        };
+
+        let data: String = entry
+            .get("data")
+            .ok_or_else(|| queue_error(format!("Entry {} has no data", entry.id)))?;
+
+        Ok(Some(Delivery {
+            id: entry.id,
+            message: serde_json::from_str(&data).map_err(queue_error)?,
+        }))
+    }
+
+    async fn ack(&self, stream: &str, group: &str, delivery_id: &str) -> Result<(), RelayerError> {
+        let _: u64 = self
+            .connection
+            .clone()
+            .xack(self.key(stream), group, &[delivery_id])
+            .await
+            .map_err(queue_error)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl JobQueue for RedisQueue {
+    async fn publish_job(&self, job: &FinalizeJob) -> Result<(), RelayerError> {
+        self.publish(JOBS_STREAM, job).await
+    }
+
+    async fn next_job(&self) -> Result<Option<Delivery<FinalizeJob>>, RelayerError> {
+        self.next(JOBS_STREAM, WORKERS_GROUP).await
+    }
+
+    async fn ack_job(&self, delivery_id: &str) -> Result<(), RelayerError> {
+        self.ack(JOBS_STREAM, WORKERS_GROUP, delivery_id).await
+    }
+
+    async fn publish_result(&self, result: &FinalizeResult) -> Result<(), RelayerError> {
+        self.publish(RESULTS_STREAM, result).await
+    }
+
+    async fn next_result(&self) -> Result<Option<Delivery<FinalizeResult>>, RelayerError> {
+        self.next(RESULTS_STREAM, RELAYER_GROUP).await
+    }
+
+    async fn ack_result(&self, delivery_id: &str) -> Result<(), RelayerError> {
+        self.ack(RESULTS_STREAM, RELAYER_GROUP, delivery_id).await
+    }
+}
@@ -0,0 +1,599 @@
+use crate::{
+    error::RelayerError,
+    listener::ChainListener,
+    queue::{FinalizeJob, FinalizeResult, JobOutcome, JobQueue},
+    store::TransferStore,
+    watcher::TransferWatcher,
+};
+use bridge_connector_common::{
+    bridge_connector::{BridgeConnector, TransferId, TransferStatus},
+    contract_failure::ContractFailure,
+    result::BridgeSdkError,
+    rpc_scheduler::RpcScheduler,
+};
+use std::{sync::Arc, time::Duration};
+use tokio::sync::Notify;
+
+pub struct RelayerConfig {
+    /// Delay between two rounds of scanning and finalization. A new block reported by a [`ChainListener`] starts the
+    /// next round earlier
+    pub poll_interval: Duration,
+    /// Number of failed finalization attempts after which a transfer is given up. Failures that retrying won't fix,
+    /// e.g. an invalid proof, give it up at once
+    pub max_attempts: u32,
+    /// Upper bound of the exponential backoff between finalization attempts
+    pub max_retry_delay: Duration,
+    /// Delay after which a job published to the queue without a result is published again, e.g. because the worker
+    /// executing it crashed
+    pub job_timeout: Duration,
+    /// Limits of the concurrent requests of the watchers to each RPC endpoint. Should be the scheduler of the
+    /// connector, so that scanning can't delay its proofs and submissions
+    pub rpc_scheduler: Arc<RpcScheduler>,
+}
+
+impl Default for RelayerConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(30),
+            max_attempts: 20,
+            max_retry_delay: Duration::from_secs(60 * 60),
+            job_timeout: Duration::from_secs(10 * 60),
+            rpc_scheduler: RpcScheduler::shared(),
+        }
+    }
+}
+
+/// Discovers transfers with the watchers and finalizes them through the connector once they can be proven on the
+/// destination chain. With a queue, finalization is delegated to [`Worker`]s instead
+pub struct Relayer<C: BridgeConnector> {
+    connector: C,
+    store: TransferStore,
+    watchers: Vec<Box<dyn TransferWatcher>>,
+    listeners: Vec<Arc<dyn ChainListener>>,
+    queue: Option<Box<dyn JobQueue>>,
+    config: RelayerConfig,
+}
+
+impl<C: BridgeConnector + Sync> Relayer<C> {
+    pub fn new(connector: C, store: TransferStore, config: RelayerConfig) -> Self {
+        Self {
+            connector,
+            store,
+            watchers: Vec::new(),
+            listeners: Vec::new(),
+            queue: None,
+            config,
+        }
+    }
+
+    pub fn with_watcher(mut self, watcher: impl TransferWatcher + 'static) -> Self {
+        self.watchers.push(Box::new(watcher));
+        self
+    }
+
+    /// Starts a round as soon as the listener reports a new block, rather than only every `poll_interval`
+    pub fn with_listener(mut self, listener: impl ChainListener + 'static) -> Self {
+        self.listeners.push(Arc::new(listener));
+        self
+    }
+
+    /// Publishes due transfers as jobs to the queue and applies the results published by the workers, instead of
+    /// finalizing the transfers itself
+    pub fn with_queue(mut self, queue: Box<dyn JobQueue>) -> Self {
+        self.queue = Some(queue);
+        self
+    }
+
+    pub fn store(&self) -> &TransferStore {
+        &self.store
+    }
+
+    /// Adds a transfer that wasn't discovered by the watchers, e.g. one initiated before the relayer was started
+    pub fn track(&mut self, transfer_id: TransferId) -> Result<(), RelayerError> {
+        self.store.add(transfer_id);
+        self.store.save()
+    }
+
+    #[tracing::instrument(skip_all, name = "RELAYER")]
+    pub async fn run(&mut self) -> Result<(), RelayerError> {
+        let new_block = Arc::new(Notify::new());
+        let listeners = self
+            .listeners
+            .iter()
+            .map(|listener| {
+                let (listener, new_block) = (listener.clone(), new_block.clone());
+                tokio::spawn(async move {
+                    if let Err(err) = listener.listen(&new_block).await {
+                        tracing::warn!(listener = listener.name(), error = %err, "Listener stopped");
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let result = async {
+            loop {
+                self.tick().await?;
+                tokio::select! {
+                    _ = new_block.notified() => {}
+                    _ = tokio::time::sleep(self.config.poll_interval) => {}
+                }
+            }
+        }
+        .await;
+
+        for listener in listeners {
+            listener.abort();
+        }
+        result
+    }
+
+    /// Runs a single round of scanning and finalization
+    pub async fn tick(&mut self) -> Result<(), RelayerError> {
+        self.poll_watchers().await;
+        self.store.save()?;
+
+        if let Some(queue) = self.queue.take() {
+            let result = self.dispatch(queue.as_ref()).await;
+            self.queue = Some(queue);
+            return result;
+        }
+
+        for transfer in self.store.due() {
+            let outcome = finalize(&self.connector, transfer.transfer_id).await;
+            self.apply(transfer.transfer_id, outcome);
+            self.store.save()?;
+        }
+
+        Ok(())
+    }
+
+    async fn poll_watchers(&mut self) {
+        for watcher in self.watchers.iter_mut() {
+            let cursor = self.store.cursor(watcher.name());
+
+            match watcher.poll(&self.config.rpc_scheduler, cursor).await {
+                Ok((transfers, next_block)) => {
+                    for transfer_id in transfers {
+                        if self.store.add(transfer_id) {
+                            tracing::info!(
+                                transfer = format!("{:?}", transfer_id),
+                                "Discovered transfer"
+                            );
+                        }
+                    }
+
+                    self.store.set_cursor(watcher.name(), next_block);
+                }
+                Err(err) => {
+                    tracing::warn!(watcher = watcher.name(), error = %err, "Failed to poll watcher");
+                }
+            }
+        }
+    }
+
+    async fn dispatch(&mut self, queue: &dyn JobQueue) -> Result<(), RelayerError> {
+        while let Some(delivery) = queue.next_result().await? {
+            let FinalizeResult {
+                transfer_id,
+                outcome,
+            } = delivery.message;
+
+            self.apply(transfer_id, outcome);
+            self.store.save()?;
+            queue.ack_result(&delivery.id).await?;
+        }
+
+        for transfer in self.store.due() {
+            queue
+                .publish_job(&FinalizeJob {
+                    transfer_id: transfer.transfer_id,
+                })
+                .await?;
+            self.store
+                .postpone(transfer.transfer_id, self.config.job_timeout.as_secs());
+            self.store.save()?;
+        }
+
+        Ok(())
+    }
+
+    fn apply(&mut self, transfer_id: TransferId, outcome: JobOutcome) {
+        // Results of jobs published more than once can arrive after the transfer was settled
+        let Some(attempts) = self
+            .store
+            .pending()
+            .iter()
+            .find(|transfer| transfer.transfer_id == transfer_id)
+            .map(|transfer| transfer.attempts)
+        else {
+            return;
+        };
+
+        match outcome {
+            JobOutcome::Finalized(finalization) => {
+                tracing::info!(
+                    transfer = format!("{:?}", transfer_id),
+                    finalization = format!("{:?}", finalization),
+                    "Finalized transfer"
+                );
+                self.store.mark_finalized(transfer_id, finalization);
+            }
+            JobOutcome::NotReady => self.store.postpone(transfer_id, 0),
+            JobOutcome::SourceFailed => {
+                tracing::warn!(
+                    transfer = format!("{:?}", transfer_id),
+                    "Transfer failed on the source chain"
+                );
+                self.store.mark_failed(
+                    transfer_id,
+                    "Transfer failed on the source chain".to_string(),
+                );
+            }
+            JobOutcome::FinalizationFailed(err) => {
+                let attempts = self.store.record_attempt(
+                    transfer_id,
+                    err.clone(),
+                    self.retry_delay(attempts).as_secs(),
+                );
+
+                tracing::warn!(
+                    transfer = format!("{:?}", transfer_id),
+                    attempts,
+                    error = %err,
+                    "Failed to finalize transfer"
+                );
+
+                if attempts >= self.config.max_attempts {
+                    self.store.mark_failed(transfer_id, err);
+                }
+            }
+            JobOutcome::FinalizationRejected(err) => {
+                tracing::warn!(
+                    transfer = format!("{:?}", transfer_id),
+                    error = %err,
+                    "Finalization of transfer can't succeed"
+                );
+                self.store.mark_failed(transfer_id, err);
+            }
+        }
+    }
+
+    fn retry_delay(&self, attempts: u32) -> Duration {
+        self.config
+            .poll_interval
+            .saturating_mul(2u32.saturating_pow(attempts))
+            .min(self.config.max_retry_delay)
+    }
+}
+
+/// Executes finalize jobs published by a queue-backed [`Relayer`]. Any number of workers can share a queue
+pub struct Worker<C: BridgeConnector> {
+    connector: C,
+    queue: Box<dyn JobQueue>,
+}
+
+impl<C: BridgeConnector + Sync> Worker<C> {
+    pub fn new(connector: C, queue: Box<dyn JobQueue>) -> Self {
+        Self { connector, queue }
+    }
+
+    #[tracing::instrument(skip_all, name = "WORKER")]
+    pub async fn run(&self) -> Result<(), RelayerError> {
+        loop {
+            self.work().await?;
+        }
+    }
+
+    /// Waits for a single job and executes it. Returns `false` if no job arrived within the queue's read timeout
+    pub async fn work(&self) -> Result<bool, RelayerError> {
+        let Some(delivery) = self.queue.next_job().await? else {
+            return Ok(false);
+        };
+
+        let transfer_id = delivery.message.transfer_id;
+        let outcome = finalize(&self.connector, transfer_id).await;
+
+        self.queue
+            .publish_result(&FinalizeResult {
+                transfer_id,
+                outcome,
+            })
+            .await?;
+        self.queue.ack_job(&delivery.id).await?;
+
+        Ok(true)
+    }
+}
+
+/// Finalizes the transfer if it can be proven on the destination chain
+async fn finalize<C: BridgeConnector + Sync>(connector: &C, transfer_id: TransferId) -> JobOutcome {
+    let status = match connector.get_status(transfer_id).await {
+        Ok(status) => status,
+        Err(err) => {
+            tracing::warn!(
+                transfer = format!("{:?}", transfer_id),
+                error = %err,
+                "Failed to get transfer status"
+            );
+            return JobOutcome::NotReady;
+        }
+    };
+
+    // The Ethereum light client on Near isn't queried, so transfers from Ethereum are finalized once executed
+    // and retried until the light client catches up
+    let ready = match (status, transfer_id) {
+        (TransferStatus::ReadyToFinalize, _) => true,
+        (TransferStatus::Initiated, TransferId::Eth { .. }) => true,
+        (TransferStatus::Failed, _) => return JobOutcome::SourceFailed,
+        _ => false,
+    };
+
+    if !ready {
+        return JobOutcome::NotReady;
+    }
+
+    match connector.finalize_transfer(transfer_id).await {
+        Ok(finalization) => JobOutcome::Finalized(Some(finalization)),
+        // Retried without counting an attempt, so that transfers are picked up again once the operation is re-enabled
+        Err(BridgeSdkError::OperationDisabled(operation)) => {
+            tracing::warn!(
+                transfer = format!("{:?}", transfer_id),
+                operation,
+                "Finalization is disabled"
+            );
+            JobOutcome::NotReady
+        }
+        Err(BridgeSdkError::RpcOverloaded(endpoint)) => {
+            tracing::warn!(
+                transfer = format!("{:?}", transfer_id),
+                endpoint,
+                "RPC endpoint is overloaded, postponing finalization"
+            );
+            JobOutcome::NotReady
+        }
+        Err(err) if err.contract_failure() == Some(ContractFailure::Paused) => {
+            tracing::warn!(
+                transfer = format!("{:?}", transfer_id),
+                error = %err,
+                "Bridge contract is paused, postponing finalization"
+            );
+            JobOutcome::NotReady
+        }
+        Err(err) if err.contract_failure() == Some(ContractFailure::ProofAlreadyUsed) => {
+            tracing::info!(
+                transfer = format!("{:?}", transfer_id),
+                "Transfer was already finalized"
+            );
+            JobOutcome::Finalized(None)
+        }
+        Err(err) if !err.is_retryable() => JobOutcome::FinalizationRejected(err.to_string()),
+        Err(err) => JobOutcome::FinalizationFailed(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use bridge_connector_common::{
+        bridge_connector::{FeeEstimate, TokenTransfer, TransactionId},
+        result::Result,
+    };
+    use ethers::types::{TxHash, U256};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Reports the same status for every transfer and finalizes them with `finalization`
+    struct StubConnector {
+        status: TransferStatus,
+        finalization: fn() -> Result<TransactionId>,
+        finalize_calls: AtomicUsize,
+    }
+
+    impl StubConnector {
+        fn new(status: TransferStatus, finalization: fn() -> Result<TransactionId>) -> Self {
+            Self {
+                status,
+                finalization,
+                finalize_calls: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl BridgeConnector for StubConnector {
+        type TransferRequest = TokenTransfer;
+
+        async fn initiate_transfer(&self, _request: TokenTransfer) -> Result<TransactionId> {
+            unimplemented!()
+        }
+
+        async fn finalize_transfer(&self, _transfer_id: TransferId) -> Result<TransactionId> {
+            self.finalize_calls.fetch_add(1, Ordering::SeqCst);
+            (self.finalization)()
+        }
+
+        async fn get_status(&self, _transfer_id: TransferId) -> Result<TransferStatus> {
+            Ok(self.status)
+        }
+
+        async fn estimate_fees(&self, _request: &TokenTransfer) -> Result<FeeEstimate> {
+            Ok(FeeEstimate::default())
+        }
+    }
+
+    fn transfer_id(byte: u8) -> TransferId {
+        TransferId::Eth {
+            tx_hash: TxHash::repeat_byte(byte),
+            log_index: 0,
+        }
+    }
+
+    fn finalization_tx() -> TransactionId {
+        TransactionId::Eth(TxHash::repeat_byte(0xff))
+    }
+
+    async fn finalize_with(
+        status: TransferStatus,
+        finalization: fn() -> Result<TransactionId>,
+    ) -> JobOutcome {
+        finalize(&StubConnector::new(status, finalization), transfer_id(1)).await
+    }
+
+    #[tokio::test]
+    async fn test_finalize() {
+        use TransferStatus::*;
+
+        assert_eq!(
+            finalize_with(ReadyToFinalize, || Ok(finalization_tx())).await,
+            JobOutcome::Finalized(Some(finalization_tx()))
+        );
+        assert_eq!(
+            finalize_with(Initiated, || Err(BridgeSdkError::NearOutcomeError(
+                "Smart contract panicked: Event cannot be reused for depositing.".to_string()
+            )))
+            .await,
+            JobOutcome::Finalized(None)
+        );
+
+        // Transfers from Near are only finalized once they can be proven
+        let connector = StubConnector::new(Initiated, || Ok(finalization_tx()));
+        let outcome = finalize(
+            &connector,
+            TransferId::Near {
+                receipt_id: Default::default(),
+            },
+        )
+        .await;
+        assert_eq!(outcome, JobOutcome::NotReady);
+        assert_eq!(connector.finalize_calls.load(Ordering::SeqCst), 0);
+
+        assert_eq!(
+            finalize_with(Failed, || Ok(finalization_tx())).await,
+            JobOutcome::SourceFailed
+        );
+        assert_eq!(
+            finalize_with(ReadyToFinalize, || Err(BridgeSdkError::NearOutcomeError(
+                "Smart contract panicked: Pausable: paused".to_string()
+            )))
+            .await,
+            JobOutcome::NotReady
+        );
+        assert_eq!(
+            finalize_with(ReadyToFinalize, || Err(BridgeSdkError::OperationDisabled(
+                "finalize_withdraw".to_string()
+            )))
+            .await,
+            JobOutcome::NotReady
+        );
+        assert!(matches!(
+            finalize_with(ReadyToFinalize, || Err(BridgeSdkError::DeadlineExceeded(
+                "outcome".to_string()
+            )))
+            .await,
+            JobOutcome::FinalizationFailed(_)
+        ));
+
+        // Errors that retrying won't fix
+        let rejected: [fn() -> Result<TransactionId>; 3] = [
+            || Err(BridgeSdkError::ConfigError("missing signer".to_string())),
+            || Err(BridgeSdkError::InvalidEthProof("trie error".to_string())),
+            || {
+                Err(BridgeSdkError::InsufficientBalance {
+                    have: U256::zero(),
+                    need: U256::one(),
+                })
+            },
+        ];
+        for finalization in rejected {
+            assert!(matches!(
+                finalize_with(ReadyToFinalize, finalization).await,
+                JobOutcome::FinalizationRejected(_)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tick_records_transfers_finalized_by_others() {
+        let connector = StubConnector::new(TransferStatus::ReadyToFinalize, || {
+            Err(BridgeSdkError::NearOutcomeError(
+                "Smart contract panicked: Event cannot be reused for depositing.".to_string(),
+            ))
+        });
+        let mut relayer = Relayer::new(
+            connector,
+            TransferStore::in_memory(),
+            RelayerConfig::default(),
+        );
+        relayer.track(transfer_id(1)).unwrap();
+
+        relayer.tick().await.unwrap();
+
+        assert!(relayer.store().pending().is_empty());
+        assert_eq!(relayer.store().finalized()[0].finalization, None);
+        assert_eq!(relayer.connector.finalize_calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_apply() {
+        let connector =
+            StubConnector::new(TransferStatus::ReadyToFinalize, || Ok(finalization_tx()));
+        let mut relayer = Relayer::new(
+            connector,
+            TransferStore::in_memory(),
+            RelayerConfig {
+                max_attempts: 2,
+                ..Default::default()
+            },
+        );
+        for byte in 1..=4 {
+            relayer.track(transfer_id(byte)).unwrap();
+        }
+
+        relayer.apply(transfer_id(1), JobOutcome::NotReady);
+        relayer.apply(
+            transfer_id(2),
+            JobOutcome::FinalizationFailed("timeout".to_string()),
+        );
+        let pending = relayer.store().pending();
+        assert_eq!((pending[0].attempts, pending[1].attempts), (0, 1));
+        assert_eq!(pending[1].last_error.as_deref(), Some("timeout"));
+
+        // Failed after `max_attempts` retryable failures, or at once if retrying won't help
+        relayer.apply(
+            transfer_id(2),
+            JobOutcome::FinalizationFailed("timeout".to_string()),
+        );
+        relayer.apply(
+            transfer_id(3),
+            JobOutcome::FinalizationRejected("invalid proof".to_string()),
+        );
+        let failed = relayer.store().failed();
+        assert_eq!(failed.len(), 2);
+        assert_eq!(failed[1].transfer_id, transfer_id(3));
+
+        relayer.apply(transfer_id(4), JobOutcome::Finalized(None));
+        assert_eq!(relayer.store().finalized()[0].transfer_id, transfer_id(4));
+
+        // Results of settled transfers are ignored
+        relayer.apply(
+            transfer_id(4),
+            JobOutcome::Finalized(Some(finalization_tx())),
+        );
+        assert_eq!(relayer.store().finalized().len(), 1);
+        assert_eq!(relayer.store().pending().len(), 1);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let relayer = Relayer::new(
+            StubConnector::new(TransferStatus::Pending, || Ok(finalization_tx())),
+            TransferStore::in_memory(),
+            RelayerConfig::default(),
+        );
+
+        assert_eq!(relayer.retry_delay(0), Duration::from_secs(30));
+        assert_eq!(relayer.retry_delay(3), Duration::from_secs(240));
+        assert_eq!(relayer.retry_delay(7), Duration::from_secs(60 * 60));
+        assert_eq!(relayer.retry_delay(u32::MAX), Duration::from_secs(60 * 60));
+    }
+}
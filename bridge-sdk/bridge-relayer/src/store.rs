@@ -0,0 +1,257 @@
+use crate::error::RelayerError;
+use bridge_connector_common::bridge_connector::{TransactionId, TransferId};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingTransfer {
+    pub transfer_id: TransferId,
+    pub attempts: u32,
+    /// Unix timestamp in seconds before which the transfer isn't retried
+    pub retry_after: u64,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinalizedTransfer {
+    pub transfer_id: TransferId,
+    /// `None` if the transfer was finalized by another relayer
+    pub finalization: Option<TransactionId>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FailedTransfer {
+    pub transfer_id: TransferId,
+    pub error: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoreState {
+    pending: Vec<PendingTransfer>,
+    finalized: Vec<FinalizedTransfer>,
+    failed: Vec<FailedTransfer>,
+    /// Next block to scan, keyed by watcher name
+    cursors: HashMap<String, u64>,
+}
+
+/// Relayer state. When opened from a file, it is persisted on every `save`, so that a restarted relayer resumes
+/// where it stopped
+pub struct TransferStore {
+    path: Option<PathBuf>,
+    state: StoreState,
+}
+
+impl TransferStore {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, RelayerError> {
+        let path = path.into();
+
+        let state = match path.exists() {
+            true => {
+                let data = fs::read_to_string(&path).map_err(|err| {
+                    RelayerError::StoreError(format!("Could not read {}: {err}", path.display()))
+                })?;
+                serde_json::from_str(&data).map_err(|err| {
+                    RelayerError::StoreError(format!("Could not parse {}: {err}", path.display()))
+                })?
+            }
+            false => StoreState::default(),
+        };
+
+        Ok(Self {
+            path: Some(path),
+            state,
+        })
+    }
+
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            state: StoreState::default(),
+        }
+    }
+
+    /// Adds a transfer to the pending ones, unless it is already known
+    pub fn add(&mut self, transfer_id: TransferId) -> bool {
+        let known = self
+            .state
+            .pending
+            .iter()
+            .any(|transfer| transfer.transfer_id == transfer_id)
+            || self
+                .state
+                .finalized
+                .iter()
+                .any(|transfer| transfer.transfer_id == transfer_id)
+            || self
+                .state
+                .failed
+                .iter()
+                .any(|transfer| transfer.transfer_id == transfer_id);
+
+        if !known {
+            self.state.pending.push(PendingTransfer {
+                transfer_id,
+                attempts: 0,
+                retry_after: 0,
+                last_error: None,
+            });
+        }
+
+        !known
+    }
+
+    /// Pending transfers whose retry delay has passed
+    pub fn due(&self) -> Vec<PendingTransfer> {
+        let now = unix_now();
+
+        self.state
+            .pending
+            .iter()
+            .filter(|transfer| transfer.retry_after <= now)
+            .cloned()
+            .collect()
+    }
+
+    pub fn pending(&self) -> &[PendingTransfer] {
+        &self.state.pending
+    }
+
+    pub fn finalized(&self) -> &[FinalizedTransfer] {
+        &self.state.finalized
+    }
+
+    pub fn failed(&self) -> &[FailedTransfer] {
+        &self.state.failed
+    }
+
+    pub fn mark_finalized(&mut self, transfer_id: TransferId, finalization: Option<TransactionId>) {
+        self.remove_pending(transfer_id);
+        self.state.finalized.push(FinalizedTransfer {
+            transfer_id,
+            finalization,
+        });
+    }
+
+    pub fn mark_failed(&mut self, transfer_id: TransferId, error: String) {
+        self.remove_pending(transfer_id);
+        self.state
+            .failed
+            .push(FailedTransfer { transfer_id, error });
+    }
+
+    /// Postpones the transfer by `delay_sec`. Returns the number of attempts made so far
+    pub fn record_attempt(
+        &mut self,
+        transfer_id: TransferId,
+        error: String,
+        delay_sec: u64,
+    ) -> u32 {
+        let Some(transfer) = self.pending_mut(transfer_id) else {
+            return 0;
+        };
+
+        transfer.attempts += 1;
+        transfer.retry_after = unix_now() + delay_sec;
+        transfer.last_error = Some(error);
+
+        transfer.attempts
+    }
+
+    /// Postpones the transfer by `delay_sec` without counting an attempt
+    pub fn postpone(&mut self, transfer_id: TransferId, delay_sec: u64) {
+        if let Some(transfer) = self.pending_mut(transfer_id) {
+            transfer.retry_after = unix_now() + delay_sec;
+        }
+    }
+
+    pub fn cursor(&self, watcher: &str) -> Option<u64> {
+        self.state.cursors.get(watcher).copied()
+    }
+
+    pub fn set_cursor(&mut self, watcher: &str, next_block: u64) {
+        self.state.cursors.insert(watcher.to_string(), next_block);
+    }
+
+    pub fn save(&self) -> Result<(), RelayerError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let data = serde_json::to_string_pretty(&self.state)
+            .map_err(|err| RelayerError::StoreError(err.to_string()))?;
+
+        // Writing to a temporary file first keeps the previous state intact if the relayer is killed mid-write
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, data)
+            .and_then(|_| fs::rename(&tmp_path, path))
+            .map_err(|err| {
+                RelayerError::StoreError(format!("Could not write {}: {err}", path.display()))
+            })
+    }
+
+    fn pending_mut(&mut self, transfer_id: TransferId) -> Option<&mut PendingTransfer> {
+        self.state
+            .pending
+            .iter_mut()
+            .find(|transfer| transfer.transfer_id == transfer_id)
+    }
+
+    fn remove_pending(&mut self, transfer_id: TransferId) {
+        self.state
+            .pending
+            .retain(|transfer| transfer.transfer_id != transfer_id);
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::TxHash;
+
+    #[test]
+    fn store_persists_transfers() {
+        let path = std::env::temp_dir().join("bridge_relayer_store_test.json");
+        let _ = fs::remove_file(&path);
+
+        let transfer_id = TransferId::Eth {
+            tx_hash: TxHash::repeat_byte(1),
+            log_index: 3,
+        };
+
+        let mut store = TransferStore::open(&path).unwrap();
+        assert!(store.add(transfer_id));
+        assert!(!store.add(transfer_id));
+        assert_eq!(
+            store.record_attempt(transfer_id, "not ready".to_string(), 60),
+            1
+        );
+        assert!(store.due().is_empty());
+        store.set_cursor("eth", 42);
+        store.save().unwrap();
+
+        let mut store = TransferStore::open(&path).unwrap();
+        assert_eq!(store.pending().len(), 1);
+        assert_eq!(store.cursor("eth"), Some(42));
+
+        store.mark_finalized(
+            transfer_id,
+            Some(TransactionId::Eth(TxHash::repeat_byte(2))),
+        );
+        assert!(store.pending().is_empty());
+        assert!(!store.add(transfer_id));
+
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+use bridge_connector_common::{
+    bridge_connector::TransferId,
+    failover,
+    result::Result,
+    rpc_scheduler::{RpcPriority, RpcScheduler},
+};
+use ethers::{prelude::*, utils::keccak256};
+use near_jsonrpc_client::{
+    errors::{JsonRpcError, JsonRpcServerError},
+    methods::block::RpcBlockError,
+};
+use near_primitives::{
+    types::{AccountId, BlockId, BlockReference, Finality},
+    views::{ActionView, ReceiptEnumView, ReceiptView},
+};
+use near_rpc_client::NearRpcError;
+
+/// Maximum number of blocks scanned in a single poll, so that a relayer catching up doesn't hit RPC limits
+const MAX_BLOCKS_PER_POLL: u64 = 1000;
+
+/// Discovers transfers initiated on one of the chains
+#[async_trait]
+pub trait TransferWatcher: Send + Sync {
+    /// Unique name, used to persist the scanning progress
+    fn name(&self) -> &str;
+
+    /// Scans the blocks starting at `from_block`, or at the chain head if it's `None`, running the scans through
+    /// `scheduler`. Returns the discovered transfers and the next block to scan
+    async fn poll(
+        &mut self,
+        scheduler: &RpcScheduler,
+        from_block: Option<u64>,
+    ) -> Result<(Vec<TransferId>, u64)>;
+}
+
+/// Watches Ethereum logs of a contract, e.g. `Withdraw` events of the bridge token factory or `Deposited` events of
+/// the EthCustodian
+pub struct EthEventWatcher {
+    name: String,
+    eth_endpoint: String,
+    address: Address,
+    topic: H256,
+    confirmations: u64,
+}
+
+impl EthEventWatcher {
+    /// `event_signature` is the canonical event signature, e.g. `Withdraw(string,address,uint256,string)`
+    pub fn new(
+        name: &str,
+        eth_endpoint: &str,
+        address: Address,
+        event_signature: &str,
+        confirmations: u64,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            eth_endpoint: eth_endpoint.to_string(),
+            address,
+            topic: H256(keccak256(event_signature)),
+            confirmations,
+        }
+    }
+}
+
+#[async_trait]
+impl TransferWatcher for EthEventWatcher {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn poll(
+        &mut self,
+        scheduler: &RpcScheduler,
+        from_block: Option<u64>,
+    ) -> Result<(Vec<TransferId>, u64)> {
+        let eth_provider = failover::eth_provider(&self.eth_endpoint)?;
+
+        let latest_block = eth_provider
+            .get_block_number()
+            .await?
+            .as_u64()
+            .saturating_sub(self.confirmations);
+        let from_block = from_block.unwrap_or(latest_block);
+
+        if from_block > latest_block {
+            return Ok((Vec::new(), from_block));
+        }
+
+        let to_block = latest_block.min(from_block + MAX_BLOCKS_PER_POLL - 1);
+        let filter = Filter::new()
+            .address(self.address)
+            .topic0(self.topic)
+            .from_block(from_block)
+            .to_block(to_block);
+
+        let transfers = scheduler
+            .run(&self.eth_endpoint, RpcPriority::Scan, async {
+                Ok(eth_provider.get_logs(&filter).await?)
+            })
+            .await?
+            .into_iter()
+            .filter_map(|log| {
+                Some(TransferId::Eth {
+                    tx_hash: log.transaction_hash?,
+                    log_index: log.log_index?.as_u64(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        tracing::debug!(
+            watcher = %self.name,
+            from_block,
+            to_block,
+            found = transfers.len(),
+            "Scanned Ethereum logs"
+        );
+
+        Ok((transfers, to_block + 1))
+    }
+}
+
+/// Watches receipts calling `method_name` on a Near contract, e.g. `ft_on_transfer` of the token locker
+pub struct NearEventWatcher {
+    name: String,
+    near_endpoint: String,
+    account_id: AccountId,
+    method_name: String,
+}
+
+impl NearEventWatcher {
+    pub fn new(name: &str, near_endpoint: &str, account_id: AccountId, method_name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            near_endpoint: near_endpoint.to_string(),
+            account_id,
+            method_name: method_name.to_string(),
+        }
+    }
+
+    async fn scan_block(&self, height: u64) -> Result<Vec<TransferId>> {
+        let block = match near_rpc_client::get_block(
+            &self.near_endpoint,
+            BlockReference::BlockId(BlockId::Height(height)),
+        )
+        .await
+        {
+            Ok(block) => block,
+            // Not every height has a block
+            Err(NearRpcError::RpcBlockError(JsonRpcError::ServerError(
+                JsonRpcServerError::HandlerError(RpcBlockError::UnknownBlock { .. }),
+            ))) => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+
+        let mut transfers = Vec::new();
+        for chunk_header in block.chunks {
+            if chunk_header.height_included != block.header.height {
+                continue;
+            }
+
+            let chunk =
+                near_rpc_client::get_chunk(&self.near_endpoint, chunk_header.chunk_hash).await?;
+            transfers.extend(
+                chunk
+                    .receipts
+                    .into_iter()
+                    .filter(|receipt| receipt.receiver_id == self.account_id)
+                    .filter(|receipt| calls_method(receipt, &self.method_name))
+                    .map(|receipt| TransferId::Near {
+                        receipt_id: receipt.receipt_id,
+                    }),
+            );
+        }
+
+        Ok(transfers)
+    }
+}
+
+#[async_trait]
+impl TransferWatcher for NearEventWatcher {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn poll(
+        &mut self,
+        scheduler: &RpcScheduler,
+        from_block: Option<u64>,
+    ) -> Result<(Vec<TransferId>, u64)> {
+        let final_block = near_rpc_client::get_block(
+            &self.near_endpoint,
+            BlockReference::Finality(Finality::Final),
+        )
+        .await?
+        .header
+        .height;
+        let from_block = from_block.unwrap_or(final_block);
+
+        if from_block > final_block {
+            return Ok((Vec::new(), from_block));
+        }
+
+        let to_block = final_block.min(from_block + MAX_BLOCKS_PER_POLL - 1);
+
+        let mut transfers = Vec::new();
+        for height in from_block..=to_block {
+            transfers.extend(
+                scheduler
+                    .run(
+                        &self.near_endpoint,
+                        RpcPriority::Scan,
+                        self.scan_block(height),
+                    )
+                    .await?,
+            );
+        }
+
+        tracing::debug!(
+            watcher = %self.name,
+            from_block,
+            to_block,
+            found = transfers.len(),
+            "Scanned Near blocks"
+        );
+
+        Ok((transfers, to_block + 1))
+    }
+}
+
+fn calls_method(receipt: &ReceiptView, method: &str) -> bool {
+    let ReceiptEnumView::Action { actions, .. } = &receipt.receipt else {
+        return false;
+    };
+
+    actions.iter().any(|action| {
+        matches!(action, ActionView::FunctionCall { method_name, .. } if method_name == method)
+    })
+}
@@ -0,0 +1,16 @@
+#!/usr/bin/env bash
+# Builds the mock contracts into this directory, where `TestContracts::from_env` loads them from. Requires `forge` of
+# Foundry and `rustup`, which installs the toolchain pinned by `near/rust-toolchain.toml`
+set -euo pipefail
+
+cd "$(dirname "$0")"
+
+(cd eth && forge build)
+for name in BridgeTokenFactory EthCustodian; do
+    cp "eth/out/$name.sol/$name.json" "$name.json"
+done
+
+(cd near && cargo build --target wasm32-unknown-unknown --release)
+for name in locker token; do
+    cp "near/target/wasm32-unknown-unknown/release/$name.wasm" "$name.wasm"
+done
@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.24;
+
+/// ERC-20 of a bridged NEP-141 token, minted and burned by the factory that deployed it
+contract BridgeToken {
+    string public name;
+    string public symbol;
+    uint8 public constant decimals = 18;
+    uint256 public totalSupply;
+    address public immutable factory;
+
+    mapping(address => uint256) public balanceOf;
+    mapping(address => mapping(address => uint256)) public allowance;
+
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+
+    modifier onlyFactory() {
+        require(msg.sender == factory, "Only the factory can mint and burn");
+        _;
+    }
+
+    constructor(string memory name_, string memory symbol_) {
+        name = name_;
+        symbol = symbol_;
+        factory = msg.sender;
+    }
+
+    function transfer(address to, uint256 amount) external returns (bool) {
+        _transfer(msg.sender, to, amount);
+        return true;
+    }
+
+    function approve(address spender, uint256 amount) external returns (bool) {
+        allowance[msg.sender][spender] = amount;
+        emit Approval(msg.sender, spender, amount);
+        return true;
+    }
+
+    function transferFrom(address from, address to, uint256 amount) external returns (bool) {
+        _spendAllowance(from, msg.sender, amount);
+        _transfer(from, to, amount);
+        return true;
+    }
+
+    function mint(address to, uint256 amount) external onlyFactory {
+        totalSupply += amount;
+        balanceOf[to] += amount;
+        emit Transfer(address(0), to, amount);
+    }
+
+    /// Burns tokens the owner approved the factory to spend
+    function burnFrom(address owner, uint256 amount) external onlyFactory {
+        _spendAllowance(owner, msg.sender, amount);
+        require(balanceOf[owner] >= amount, "ERC20: burn amount exceeds balance");
+        balanceOf[owner] -= amount;
+        totalSupply -= amount;
+        emit Transfer(owner, address(0), amount);
+    }
+
+    function _transfer(address from, address to, uint256 amount) private {
+        require(balanceOf[from] >= amount, "ERC20: transfer amount exceeds balance");
+        balanceOf[from] -= amount;
+        balanceOf[to] += amount;
+        emit Transfer(from, to, amount);
+    }
+
+    function _spendAllowance(address owner, address spender, uint256 amount) private {
+        require(allowance[owner][spender] >= amount, "ERC20: insufficient allowance");
+        allowance[owner][spender] -= amount;
+    }
+}
@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.24;
+
+import {BridgeToken} from "./BridgeToken.sol";
+
+/// Mock of the bridged token factory. Near proofs aren't verified, so bridged tokens are minted with `mintBridgeToken`
+/// instead of `deposit`
+contract BridgeTokenFactory {
+    mapping(string => address) private nearToEthTokens;
+    mapping(address => string) private ethToNearTokens;
+
+    event Withdraw(string token, address indexed sender, uint256 amount, string recipient);
+
+    function nearToEthToken(string calldata nearTokenId) external view returns (address) {
+        return nearToEthTokens[nearTokenId];
+    }
+
+    function ethToNearToken(address ethTokenAddress) external view returns (string memory) {
+        return ethToNearTokens[ethTokenAddress];
+    }
+
+    /// Mints bridged tokens of the NEP-141 token to the recipient, deploying the bridged token on first use
+    function mintBridgeToken(string calldata nearTokenId, address recipient, uint256 amount)
+        external
+        returns (address tokenAddress)
+    {
+        tokenAddress = nearToEthTokens[nearTokenId];
+        if (tokenAddress == address(0)) {
+            tokenAddress = address(new BridgeToken(nearTokenId, nearTokenId));
+            nearToEthTokens[nearTokenId] = tokenAddress;
+            ethToNearTokens[tokenAddress] = nearTokenId;
+        }
+
+        BridgeToken(tokenAddress).mint(recipient, amount);
+    }
+
+    /// Burns the approved bridged tokens of the sender, to be released to the Near recipient
+    function withdraw(string memory token, uint128 amount, string memory recipient) external {
+        address tokenAddress = nearToEthTokens[token];
+        require(tokenAddress != address(0), "ERR_NOT_BRIDGE_TOKEN");
+
+        BridgeToken(tokenAddress).burnFrom(msg.sender, amount);
+        emit Withdraw(token, msg.sender, amount, recipient);
+    }
+}
@@ -0,0 +1,17 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.24;
+
+/// Mock of the eth connector custodian, holding the deposited ETH. Near proofs aren't verified, so nothing is released
+contract EthCustodian {
+    event Deposited(address indexed sender, string recipient, uint256 amount, uint256 fee);
+
+    function depositToNear(string memory nearRecipientAccountId, uint256 fee) external payable {
+        require(fee < msg.value, "The fee cannot be bigger than Ether");
+        emit Deposited(msg.sender, nearRecipientAccountId, msg.value, fee);
+    }
+
+    function depositToEVM(string memory ethRecipientOnNear, uint256 fee) external payable {
+        require(fee < msg.value, "The fee cannot be bigger than Ether");
+        emit Deposited(msg.sender, ethRecipientOnNear, msg.value, fee);
+    }
+}
@@ -0,0 +1,48 @@
+//! Mock of the NEP-141 token locker. Deposits are recorded, but Ethereum proofs aren't verified, so nothing is released
+
+use near_sdk::{
+    env, json_types::U128, log, near, store::LookupMap, AccountId, PanicOnDefault, PromiseOrValue,
+};
+
+#[near(contract_state)]
+#[derive(PanicOnDefault)]
+pub struct Locker {
+    /// Amount deposited of each token
+    locked: LookupMap<AccountId, u128>,
+}
+
+#[near]
+impl Locker {
+    #[init]
+    pub fn new() -> Self {
+        Self {
+            locked: LookupMap::new(b"l"),
+        }
+    }
+
+    /// Locks the whole transferred amount for the Ethereum recipient in `msg`
+    pub fn ft_on_transfer(
+        &mut self,
+        sender_id: AccountId,
+        amount: U128,
+        msg: String,
+    ) -> PromiseOrValue<U128> {
+        let token_id = env::predecessor_account_id();
+        let locked = self.get_locked(token_id.clone()).0 + amount.0;
+        self.locked.insert(token_id.clone(), locked);
+
+        log!(
+            "Locked {} {} of {} for {}",
+            amount.0,
+            token_id,
+            sender_id,
+            msg
+        );
+
+        PromiseOrValue::Value(U128(0))
+    }
+
+    pub fn get_locked(&self, token_id: AccountId) -> U128 {
+        U128(self.locked.get(&token_id).copied().unwrap_or(0))
+    }
+}
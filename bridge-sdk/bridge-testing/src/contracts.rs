@@ -0,0 +1,94 @@
+use crate::error::HarnessError;
+use ethers::{abi::Abi, types::Bytes};
+use serde::Deserialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Environment variable overriding the directory of the mock contracts
+pub const CONTRACTS_DIR_ENV: &str = "BRIDGE_TEST_CONTRACTS_DIR";
+
+/// Compiled mock contracts deployed by [`crate::BridgeSandbox`]: Near contracts as `<name>.wasm`, Ethereum contracts as
+/// Foundry artifacts `<name>.json`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestContracts {
+    dir: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct FoundryArtifact {
+    abi: Abi,
+    bytecode: FoundryBytecode,
+}
+
+#[derive(Deserialize)]
+struct FoundryBytecode {
+    object: Bytes,
+}
+
+impl TestContracts {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Directory set by [`CONTRACTS_DIR_ENV`], or the `contracts` directory of this crate
+    pub fn from_env() -> Self {
+        Self::new(
+            std::env::var_os(CONTRACTS_DIR_ENV)
+                .map(PathBuf::from)
+                .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("contracts")),
+        )
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn near_wasm(&self, name: &str) -> Result<Vec<u8>, HarnessError> {
+        let path = self.dir.join(format!("{name}.wasm"));
+
+        fs::read(&path)
+            .map_err(|err| HarnessError::ContractError(format!("{}: {err}", path.display())))
+    }
+
+    /// ABI and creation bytecode of the Ethereum contract
+    pub fn eth_artifact(&self, name: &str) -> Result<(Abi, Bytes), HarnessError> {
+        let path = self.dir.join(format!("{name}.json"));
+
+        let artifact: FoundryArtifact = fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|data| serde_json::from_str(&data).map_err(|err| err.to_string()))
+            .map_err(|err| HarnessError::ContractError(format!("{}: {err}", path.display())))?;
+
+        Ok((artifact.abi, artifact.bytecode.object))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eth_artifact() {
+        let contracts = TestContracts::new(
+            std::env::temp_dir().join(format!("bridge-test-contracts-{}", std::process::id())),
+        );
+        fs::create_dir_all(contracts.dir()).unwrap();
+        fs::write(
+            contracts.dir().join("EthCustodian.json"),
+            r#"{
+                "abi": [{"type": "function", "name": "admin", "inputs": [], "outputs": [{"name": "", "type": "address"}], "stateMutability": "view"}],
+                "bytecode": {"object": "0x6080"}
+            }"#,
+        )
+        .unwrap();
+
+        let (abi, bytecode) = contracts.eth_artifact("EthCustodian").unwrap();
+        assert!(abi.function("admin").is_ok());
+        assert_eq!(bytecode.to_vec(), vec![0x60, 0x80]);
+        assert!(contracts.near_wasm("locker").is_err());
+
+        fs::remove_dir_all(contracts.dir()).unwrap();
+    }
+}
@@ -0,0 +1,9 @@
+#[derive(thiserror::Error, Debug)]
+pub enum HarnessError {
+    #[error("Could not load mock contract: {0}")]
+    ContractError(String),
+    #[error("Near sandbox error: {0}")]
+    NearError(String),
+    #[error("Anvil error: {0}")]
+    EthError(String),
+}
@@ -0,0 +1,9 @@
+//! Local Near sandbox and anvil node with mock bridge contracts, to run connector flows as integration tests
+
+mod contracts;
+mod error;
+mod sandbox;
+
+pub use contracts::{TestContracts, CONTRACTS_DIR_ENV};
+pub use error::HarnessError;
+pub use sandbox::{BridgeSandbox, EthClient, NEAR_TOKEN_SUPPLY};
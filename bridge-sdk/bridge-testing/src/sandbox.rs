@@ -0,0 +1,399 @@
+use crate::{contracts::TestContracts, error::HarnessError};
+use eth_connector::EthConnectorBuilder;
+use ethers::{
+    contract::{abigen, ContractFactory},
+    middleware::SignerMiddleware,
+    providers::{Http, Provider},
+    signers::{LocalWallet, Signer},
+    types::Address,
+    utils::{Anvil, AnvilInstance},
+};
+use near_workspaces::{
+    network::Sandbox, operations::CallTransaction, types::NearToken, Account, Contract, Worker,
+};
+use nep141_connector::Nep141ConnectorBuilder;
+use serde_json::json;
+use std::sync::Arc;
+
+abigen!(
+    MockBridgeTokenFactory,
+    r#"[
+      function mintBridgeToken(string calldata nearTokenId, address recipient, uint256 amount) external returns (address)
+      function nearToEthToken(string calldata nearTokenId) external view returns (address)
+    ]"#
+);
+
+/// Ethereum client signing with the first anvil key
+pub type EthClient = SignerMiddleware<Provider<Http>, LocalWallet>;
+
+/// Supply of the NEP-141 token deployed on the sandbox, owned by the dev account
+pub const NEAR_TOKEN_SUPPLY: u128 = 1_000_000_000;
+
+/// Near contracts deployed on the sandbox. The locker is initialized with `new` and no arguments
+const NEAR_LOCKER: &str = "locker";
+const NEAR_TOKEN: &str = "token";
+
+/// Ethereum contracts deployed on anvil, with constructors taking no arguments
+const ETH_FACTORY: &str = "BridgeTokenFactory";
+const ETH_CUSTODIAN: &str = "EthCustodian";
+
+/// Near sandbox and anvil node with the mock locker, NEP-141 token, token factory and custodian deployed. The sandbox
+/// binary is downloaded by `near-workspaces` on first use, and the `anvil` binary of Foundry must be in the `PATH`.
+/// Both nodes are stopped when dropped
+pub struct BridgeSandbox {
+    worker: Worker<Sandbox>,
+    near_account: Account,
+    locker: Contract,
+    token: Contract,
+    eth_client: Arc<EthClient>,
+    factory_address: Address,
+    custodian_address: Address,
+    // Kills the node when dropped
+    anvil: AnvilInstance,
+}
+
+impl BridgeSandbox {
+    pub async fn start(contracts: &TestContracts) -> Result<Self, HarnessError> {
+        let worker = near_workspaces::sandbox().await.map_err(near_error)?;
+        let near_account = worker.dev_create_account().await.map_err(near_error)?;
+        let locker = worker
+            .dev_deploy(&contracts.near_wasm(NEAR_LOCKER)?)
+            .await
+            .map_err(near_error)?;
+        transact(locker.call("new").args_json(json!({}))).await?;
+
+        let token = worker
+            .dev_deploy(&contracts.near_wasm(NEAR_TOKEN)?)
+            .await
+            .map_err(near_error)?;
+        transact(token.call("new").args_json(json!({
+            "owner_id": near_account.id(),
+            "total_supply": NEAR_TOKEN_SUPPLY.to_string(),
+        })))
+        .await?;
+        transact(
+            near_account
+                .call(token.id(), "storage_deposit")
+                .args_json(json!({ "account_id": locker.id() }))
+                .deposit(NearToken::from_millinear(10)),
+        )
+        .await?;
+
+        let anvil = Anvil::new().try_spawn().map_err(eth_error)?;
+        let provider = Provider::<Http>::try_from(anvil.endpoint()).map_err(eth_error)?;
+        let wallet = LocalWallet::from(anvil.keys()[0].clone()).with_chain_id(anvil.chain_id());
+        let eth_client = Arc::new(SignerMiddleware::new(provider, wallet));
+
+        let factory_address = deploy_eth_contract(&eth_client, contracts, ETH_FACTORY).await?;
+        let custodian_address = deploy_eth_contract(&eth_client, contracts, ETH_CUSTODIAN).await?;
+
+        tracing::info!(
+            near_endpoint = worker.rpc_addr(),
+            eth_endpoint = anvil.endpoint(),
+            "Started bridge sandbox"
+        );
+
+        Ok(Self {
+            worker,
+            near_account,
+            locker,
+            token,
+            eth_client,
+            factory_address,
+            custodian_address,
+            anvil,
+        })
+    }
+
+    pub fn worker(&self) -> &Worker<Sandbox> {
+        &self.worker
+    }
+
+    /// Funded dev account signing the Near transactions of the connectors
+    pub fn near_account(&self) -> &Account {
+        &self.near_account
+    }
+
+    pub fn locker(&self) -> &Contract {
+        &self.locker
+    }
+
+    /// NEP-141 token with the locker registered, see [`NEAR_TOKEN_SUPPLY`]
+    pub fn token(&self) -> &Contract {
+        &self.token
+    }
+
+    pub fn eth_client(&self) -> Arc<EthClient> {
+        self.eth_client.clone()
+    }
+
+    pub fn factory_address(&self) -> Address {
+        self.factory_address
+    }
+
+    pub fn custodian_address(&self) -> Address {
+        self.custodian_address
+    }
+
+    pub fn near_endpoint(&self) -> String {
+        self.worker.rpc_addr()
+    }
+
+    pub fn eth_endpoint(&self) -> String {
+        self.anvil.endpoint()
+    }
+
+    /// Connector of the sandbox contracts, signing with the dev account and the first anvil key. No light client is
+    /// deployed, so it doesn't wait for them. Further settings can be set before building it
+    pub fn nep141_connector_builder(&self) -> Nep141ConnectorBuilder {
+        let mut builder = Nep141ConnectorBuilder::default();
+        builder
+            .eth_endpoint(Some(self.eth_endpoint()))
+            .eth_chain_id(Some(self.anvil.chain_id()))
+            .eth_private_key(Some(self.eth_private_key()))
+            .bridge_token_factory_address(Some(format!("{:?}", self.factory_address)))
+            .near_endpoint(Some(self.near_endpoint()))
+            .near_private_key(Some(self.near_account.secret_key().to_string()))
+            .near_signer(Some(self.near_account.id().to_string()))
+            .token_locker_id(Some(self.locker.id().to_string()))
+            .near_light_client_address(None)
+            .wait_for_light_client(false);
+        builder
+    }
+
+    /// Connector of the sandbox custodian, see [`Self::nep141_connector_builder`]. The Near side of the eth connector
+    /// isn't deployed, so `eth_connector_account_id` is left unset
+    pub fn eth_connector_builder(&self) -> EthConnectorBuilder {
+        let mut builder = EthConnectorBuilder::default();
+        builder
+            .eth_endpoint(Some(self.eth_endpoint()))
+            .eth_chain_id(Some(self.anvil.chain_id()))
+            .eth_private_key(Some(self.eth_private_key()))
+            .eth_custodian_address(Some(format!("{:?}", self.custodian_address)))
+            .near_endpoint(Some(self.near_endpoint()))
+            .near_private_key(Some(self.near_account.secret_key().to_string()))
+            .near_signer(Some(self.near_account.id().to_string()))
+            .eth_connector_account_id(None)
+            .near_light_client_address(None)
+            .wait_for_light_client(false);
+        builder
+    }
+
+    /// Mints bridged tokens of the NEP-141 token to the recipient, deploying the bridged token on first use, and
+    /// returns the bridged token. Stands in for `finalize_deposit`, whose proof the mock factory doesn't verify
+    pub async fn mint_bridge_token(
+        &self,
+        near_token_id: &str,
+        recipient: Address,
+        amount: u128,
+    ) -> Result<Address, HarnessError> {
+        let factory = MockBridgeTokenFactory::new(self.factory_address, self.eth_client.clone());
+
+        let receipt = factory
+            .mint_bridge_token(near_token_id.to_string(), recipient, amount.into())
+            .send()
+            .await
+            .map_err(eth_error)?
+            .await
+            .map_err(eth_error)?;
+        if receipt.and_then(|receipt| receipt.status) != Some(1.into()) {
+            return Err(HarnessError::EthError(format!(
+                "Minting bridged {near_token_id} failed"
+            )));
+        }
+
+        factory
+            .near_to_eth_token(near_token_id.to_string())
+            .call()
+            .await
+            .map_err(eth_error)
+    }
+
+    /// Hex encoded without the `0x` prefix, as expected by the connectors
+    fn eth_private_key(&self) -> String {
+        hex::encode(self.anvil.keys()[0].to_bytes())
+    }
+}
+
+async fn deploy_eth_contract(
+    client: &Arc<EthClient>,
+    contracts: &TestContracts,
+    name: &str,
+) -> Result<Address, HarnessError> {
+    let (abi, bytecode) = contracts.eth_artifact(name)?;
+
+    let contract = ContractFactory::new(abi, bytecode, client.clone())
+        .deploy(())
+        .map_err(eth_error)?
+        .send()
+        .await
+        .map_err(eth_error)?;
+
+    tracing::debug!(
+        name,
+        address = format!("{:?}", contract.address()),
+        "Deployed mock contract"
+    );
+
+    Ok(contract.address())
+}
+
+/// Sends the call and fails if it didn't succeed
+async fn transact(call: CallTransaction) -> Result<(), HarnessError> {
+    call.transact()
+        .await
+        .map_err(near_error)?
+        .into_result()
+        .map_err(near_error)?;
+
+    Ok(())
+}
+
+fn near_error(err: impl std::fmt::Display) -> HarnessError {
+    HarnessError::NearError(err.to_string())
+}
+
+fn eth_error(err: impl std::fmt::Display) -> HarnessError {
+    HarnessError::EthError(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bridge_connector_common::{contract_call::EthTxOptions, result::BridgeSdkError};
+    use ethers::providers::Middleware;
+
+    const ETH_RECIPIENT: &str = "0x5a08feed678c056650b3eb4a5cb1b9bb6f0fe265";
+    const NEAR_RECIPIENT: &str = "alice.test.near";
+
+    // The tests need `anvil` in the `PATH` and the mock contracts built by `contracts/build.sh`, and download the Near
+    // sandbox on first use
+    async fn start_sandbox() -> BridgeSandbox {
+        BridgeSandbox::start(&TestContracts::from_env())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs anvil and contracts/build.sh"]
+    async fn test_start_sandbox() {
+        let sandbox = start_sandbox().await;
+
+        let connector = sandbox.nep141_connector_builder().build().unwrap();
+        assert_eq!(
+            connector
+                .get_erc20_for_nep141("unknown.test.near".to_string())
+                .await
+                .unwrap(),
+            Address::zero()
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs anvil and contracts/build.sh"]
+    async fn test_deposit_flow() {
+        let sandbox = start_sandbox().await;
+        let token_id = sandbox.token().id();
+
+        let deposit = sandbox
+            .nep141_connector_builder()
+            .build()
+            .unwrap()
+            .deposit_and_wait(token_id.to_string(), 100, ETH_RECIPIENT.to_string())
+            .await
+            .unwrap();
+        assert_eq!(deposit.amount, 100);
+
+        let locked: String = sandbox
+            .locker()
+            .view("get_locked")
+            .args_json(json!({ "token_id": token_id }))
+            .await
+            .unwrap()
+            .json()
+            .unwrap();
+        assert_eq!(locked, "100");
+    }
+
+    #[tokio::test]
+    #[ignore = "needs anvil and contracts/build.sh"]
+    async fn test_withdraw_flow() {
+        let sandbox = start_sandbox().await;
+        let token_id = "token.test.near";
+        let bridge_token = sandbox
+            .mint_bridge_token(token_id, sandbox.eth_client().address(), 100)
+            .await
+            .unwrap();
+
+        let connector = sandbox.nep141_connector_builder().build().unwrap();
+        assert_eq!(
+            connector
+                .get_erc20_for_nep141(token_id.to_string())
+                .await
+                .unwrap(),
+            bridge_token
+        );
+
+        let withdrawal = connector
+            .withdraw_and_wait(
+                token_id.to_string(),
+                40,
+                NEAR_RECIPIENT.to_string(),
+                EthTxOptions::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(withdrawal.amount, 40);
+
+        // The withdrawn tokens were burned
+        let err = connector
+            .withdraw(
+                token_id.to_string(),
+                100,
+                NEAR_RECIPIENT.to_string(),
+                EthTxOptions::default(),
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, BridgeSdkError::InsufficientBalance { have, .. } if have == 60.into())
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "needs anvil and contracts/build.sh"]
+    async fn test_eth_deposit_flow() {
+        let sandbox = start_sandbox().await;
+
+        let tx_hash = sandbox
+            .eth_connector_builder()
+            .build()
+            .unwrap()
+            .deposit_to_near(
+                1000,
+                NEAR_RECIPIENT.to_string(),
+                EthTxOptions {
+                    confirmations: Some(1),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+
+        let receipt = sandbox
+            .eth_client()
+            .get_transaction_receipt(tx_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(receipt.logs.len(), 1);
+        assert_eq!(
+            sandbox
+                .eth_client()
+                .get_balance(sandbox.custodian_address(), None)
+                .await
+                .unwrap(),
+            1000.into()
+        );
+    }
+}
@@ -0,0 +1,511 @@
+use bridge_connector_common::{
+    bridge_connector::TransferDirection,
+    client_cache::ClientCache,
+    contract_call::{self, EthTxOptions, NearTxOptions},
+    events::ConnectorEvents,
+    failover,
+    method_names::MethodNames,
+    near_args::{self, FtTransferCallArgs},
+    operations::DisabledOperations,
+    result::{BridgeSdkError, Result},
+    rpc_scheduler::RpcScheduler,
+    screening::{self, ScreenedTransfer, TransferScreening},
+    self_check::{self, SelfCheckReport},
+    signer::{self, CallbackNearSigner, DynEthSigner, EthSigner, EthSignerClient, NearSigner},
+    token_amount,
+};
+use ethers::{
+    abi::{Address, Token},
+    prelude::*,
+};
+use near_crypto::SecretKey;
+use near_primitives::{hash::CryptoHash, types::AccountId};
+use near_rpc_client::{retry::RetryPolicy, FungibleTokenMetadata, NearRpc, NearRpcClient};
+use std::{path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+
+abigen!(
+    AuroraErc20,
+    r#"[
+      function withdrawToNear(bytes memory recipient, uint256 amount) external
+    ]"#
+);
+
+/// Bridging NEP-141 tokens between NEAR and Aurora EVM. Clones share the cached clients
+#[derive(Builder, Clone)]
+pub struct AuroraConnector {
+    #[doc = r"Aurora RPC endpoint. Required for `withdraw`"]
+    aurora_endpoint: Option<String>,
+    #[doc = r"Aurora chain id. Required for `withdraw`"]
+    aurora_chain_id: Option<u64>,
+    #[doc = r"Aurora private key. Required for `withdraw`"]
+    aurora_private_key: Option<String>,
+    #[doc = r"NEAR RPC endpoint. Required for `deposit`"]
+    near_endpoint: Option<String>,
+    #[doc = r"NEAR private key. Required for `deposit`"]
+    near_private_key: Option<String>,
+    #[doc = r"NEAR account id of the transaction signer. Required for `deposit`"]
+    near_signer: Option<String>,
+    #[doc = r"Aurora engine account id on Near. Required for `deposit`"]
+    aurora_engine_account_id: Option<String>,
+    #[doc = r"Operations rejected with `OperationDisabled`, e.g. during an incident"]
+    #[builder(default)]
+    disabled_operations: DisabledOperations,
+    #[doc = r"Contract methods renamed by the deployment, e.g. `withdraw` deployed as `finish_withdraw`"]
+    #[builder(default)]
+    method_names: MethodNames,
+    #[doc = r"Gas and attached deposit of the Near transactions, overriding the connector defaults"]
+    #[builder(default)]
+    near_tx_options: NearTxOptions,
+    #[doc = r"Screening of the transfers before they are submitted, e.g. for sanctions compliance. A rejected transfer fails with `TransferRejected`"]
+    #[builder(default)]
+    transfer_screening: Option<Arc<dyn TransferScreening>>,
+    #[doc = r"Simulates transactions instead of sending them and returns the hashes they would have. Ethereum calls are checked with `eth_estimateGas` and `eth_call`, Near calls are run as view calls and checked against the signer balance"]
+    #[builder(default)]
+    dry_run: bool,
+    #[doc = r"Custom NEAR signer, e.g. a hardware wallet or a remote signer. Takes precedence over `near_private_key`"]
+    #[builder(default)]
+    custom_near_signer: Option<Arc<dyn NearSigner>>,
+    #[doc = r"Custom Aurora signer, e.g. a hardware wallet or a remote signer. Takes precedence over `aurora_private_key`"]
+    #[builder(default)]
+    custom_aurora_signer: Option<Arc<dyn EthSigner>>,
+    #[doc = r"Near RPC serving the requests instead of `near_endpoint`, e.g. a `MockNearRpc` in tests"]
+    #[builder(default)]
+    near_rpc: Option<Arc<dyn NearRpc>>,
+    #[doc = r"Retries of the Near requests failing with a transient error. Ignored with `near_rpc`"]
+    #[builder(default)]
+    near_retry_policy: RetryPolicy,
+    #[doc = r"Time each Near transaction is awaited for its outcome, failing if it did. Only broadcast with `None`"]
+    #[builder(default = "Some(signer::DEFAULT_NEAR_OUTCOME_TIMEOUT)")]
+    near_outcome_timeout: Option<Duration>,
+    #[doc = r"Journal of the strict mode of the Aurora transactions, see `contract_call::send_eth_call`. Disabled if unset"]
+    #[builder(default)]
+    eth_nonce_journal: Option<PathBuf>,
+    #[doc = r"Observer of the progress of the operations, e.g. transactions submitted and light clients lagging. Events are only logged if unset"]
+    #[builder(default)]
+    events: Option<Arc<dyn ConnectorEvents>>,
+    #[doc = r"Limits of the concurrent requests to each RPC endpoint. Connectors and relayers given the same scheduler share the limits of an endpoint"]
+    #[builder(default = "RpcScheduler::shared()")]
+    rpc_scheduler: Arc<RpcScheduler>,
+    #[builder(setter(skip))]
+    aurora_client_cache: ClientCache<Arc<EthSignerClient>>,
+    #[builder(setter(skip))]
+    near_rpc_cache: ClientCache<Arc<dyn NearRpc>>,
+}
+
+impl AuroraConnectorBuilder {
+    /// Signs the Near transactions with a callback, e.g. a wallet app or a custodial signing service, instead of a key
+    /// held by the connector
+    pub fn near_signing_callback(&mut self, signer: CallbackNearSigner) -> &mut Self {
+        self.custom_near_signer(Some(Arc::new(signer)))
+    }
+}
+
+impl AuroraConnector {
+    /// Transfers NEP-141 tokens to the Aurora engine, which mints the corresponding ERC-20 tokens for the recipient on Aurora.
+    /// Aurora is screened as the Ethereum side, so the deposit is a [`TransferDirection::NearToEth`] transfer
+    #[tracing::instrument(skip_all, name = "DEPOSIT")]
+    pub async fn deposit(
+        &self,
+        near_token_id: String,
+        amount: u128,
+        recipient_address: Address,
+    ) -> Result<CryptoHash> {
+        self.disabled_operations.check("deposit")?;
+        screening::check(self.transfer_screening.as_ref(), || {
+            Ok(ScreenedTransfer {
+                direction: TransferDirection::NearToEth,
+                token: near_token_id.clone(),
+                amount,
+                sender: self.near_signer()?.account_id().to_string(),
+                recipient: format!("{recipient_address:?}"),
+            })
+        })
+        .await?;
+
+        let near_rpc = self.near_rpc()?;
+        let aurora_engine_account_id = self.aurora_engine_account_id()?.to_string();
+        let recipient = hex::encode(recipient_address);
+
+        let args = near_args::to_bytes(&FtTransferCallArgs {
+            receiver_id: aurora_engine_account_id,
+            amount,
+            msg: recipient,
+        });
+
+        let tx_hash = signer::near_change(
+            near_rpc.as_ref(),
+            self.near_signer()?,
+            near_token_id,
+            self.method_names.near("ft_transfer_call"),
+            args,
+            self.near_tx_options.gas(300_000_000_000_000),
+            1,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = format!("{:?}", tx_hash),
+            "Sent deposit transaction"
+        );
+
+        Ok(tx_hash)
+    }
+
+    /// Burns ERC-20 tokens on Aurora through the `exit_to_near` precompile. The precompile schedules the transfer of the NEP-141 tokens to the recipient on Near, so no separate finalization is needed.
+    /// Screened as a [`TransferDirection::EthToNear`] transfer
+    #[tracing::instrument(skip_all, name = "WITHDRAW")]
+    pub async fn withdraw(
+        &self,
+        erc20_address: Address,
+        amount: u128,
+        recipient_account_id: AccountId,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("withdraw")?;
+        screening::check(self.transfer_screening.as_ref(), || {
+            Ok(ScreenedTransfer {
+                direction: TransferDirection::EthToNear,
+                token: format!("{erc20_address:?}"),
+                amount,
+                sender: format!("{:?}", self.aurora_signer()?.address()),
+                recipient: recipient_account_id.to_string(),
+            })
+        })
+        .await?;
+
+        let call =
+            tx_opts.apply(self.withdraw_call(erc20_address, amount, &recipient_account_id)?);
+
+        let tx_hash = contract_call::send_eth_call(
+            &self.aurora_client()?,
+            call,
+            tx_opts.confirmations,
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = format!("{:?}", tx_hash),
+            "Sent withdraw transaction"
+        );
+
+        Ok(tx_hash)
+    }
+
+    /// Metadata of a NEP-141 token, e.g. to check its decimals before computing the bridged amounts
+    pub async fn get_token_metadata(&self, token_id: AccountId) -> Result<FungibleTokenMetadata> {
+        Ok(self.near_rpc()?.get_ft_metadata(token_id).await?)
+    }
+
+    /// Decimals of an ERC-20 token on Aurora
+    pub async fn get_erc20_decimals(&self, erc20_address: Address) -> Result<u8> {
+        token_amount::erc20_decimals(self.aurora_endpoint()?, erc20_address).await
+    }
+
+    /// Verifies the configured capabilities: RPC reachability, presence of the bridge contracts and signer balances. Unset configuration values are skipped
+    pub async fn self_check(&self) -> SelfCheckReport {
+        let aurora_endpoint = self.aurora_endpoint.as_deref();
+        let near_endpoint = self.near_endpoint.as_deref();
+        let mut report = SelfCheckReport::default();
+
+        report.push(
+            "Aurora RPC",
+            self_check::check_eth_rpc(aurora_endpoint).await,
+        );
+        report.push("NEAR RPC", self_check::check_near_rpc(near_endpoint).await);
+        report.push(
+            "Aurora engine",
+            self_check::check_near_code(near_endpoint, self.aurora_engine_account_id.as_deref())
+                .await,
+        );
+        report.push(
+            "Aurora signer balance",
+            self_check::check_eth_balance(
+                aurora_endpoint,
+                self.aurora_private_key
+                    .as_ref()
+                    .map(|_| self.aurora_signer().map(|wallet| wallet.address())),
+            )
+            .await,
+        );
+
+        report
+    }
+
+    /// Sends a transaction calling an arbitrary Aurora contract function, signed with the configured Aurora key
+    #[tracing::instrument(skip_all, name = "CALL AURORA CONTRACT")]
+    pub async fn call_aurora_contract(
+        &self,
+        address: Address,
+        abi_fn: &str,
+        args: Vec<Token>,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("call_aurora_contract")?;
+
+        contract_call::call_eth_contract(
+            self.aurora_client()?,
+            address,
+            abi_fn,
+            args,
+            tx_opts,
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await
+    }
+
+    /// Calls an arbitrary NEAR contract method, signed with the configured NEAR account
+    #[tracing::instrument(skip_all, name = "CALL NEAR CONTRACT")]
+    pub async fn call_near_contract(
+        &self,
+        account_id: AccountId,
+        method_name: String,
+        args: Vec<u8>,
+        gas: u64,
+        deposit: u128,
+    ) -> Result<CryptoHash> {
+        self.disabled_operations.check("call_near_contract")?;
+
+        let tx_hash = signer::near_change(
+            self.near_rpc()?.as_ref(),
+            self.near_signer()?,
+            account_id.to_string(),
+            method_name,
+            args,
+            gas,
+            deposit,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = format!("{:?}", tx_hash),
+            "Sent contract call transaction"
+        );
+
+        Ok(tx_hash)
+    }
+
+    fn withdraw_call(
+        &self,
+        erc20_address: Address,
+        amount: u128,
+        recipient_account_id: &AccountId,
+    ) -> Result<ContractCall<EthSignerClient, ()>> {
+        let erc20 = self.aurora_erc20(erc20_address)?;
+
+        Ok(self.method_names.eth(erc20.withdraw_to_near(
+            recipient_account_id.as_bytes().to_vec().into(),
+            amount.into(),
+        )))
+    }
+
+    fn near_signer(&self) -> Result<Arc<dyn NearSigner>> {
+        if let Some(signer) = &self.custom_near_signer {
+            return Ok(signer.clone());
+        }
+
+        let near_private_key =
+            self.near_private_key
+                .as_ref()
+                .ok_or(BridgeSdkError::ConfigError(
+                    "Near account private key is not set".to_string(),
+                ))?;
+        let near_signer = self
+            .near_signer
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Near signer account id is not set".to_string(),
+            ))?;
+
+        Ok(Arc::new(near_crypto::InMemorySigner::from_secret_key(
+            AccountId::from_str(near_signer).map_err(|_| {
+                BridgeSdkError::ConfigError("Invalid near signer account id".to_string())
+            })?,
+            SecretKey::from_str(near_private_key)
+                .map_err(|_| BridgeSdkError::ConfigError("Invalid near private key".to_string()))?,
+        )))
+    }
+
+    fn aurora_erc20(&self, address: Address) -> Result<AuroraErc20<EthSignerClient>> {
+        Ok(AuroraErc20::new(address, self.aurora_client()?))
+    }
+
+    fn aurora_client(&self) -> Result<Arc<EthSignerClient>> {
+        self.aurora_client_cache.get_or_try_init(|| {
+            let aurora_provider =
+                failover::eth_provider(self.aurora_endpoint()?).map_err(|_| {
+                    BridgeSdkError::ConfigError("Invalid aurora rpc endpoint url".to_string())
+                })?;
+
+            let wallet = self.aurora_signer()?;
+
+            Ok(Arc::new(SignerMiddleware::new(aurora_provider, wallet)))
+        })
+    }
+
+    fn aurora_signer(&self) -> Result<DynEthSigner> {
+        if let Some(signer) = &self.custom_aurora_signer {
+            return Ok(DynEthSigner::new(signer.clone()));
+        }
+
+        let aurora_private_key =
+            self.aurora_private_key
+                .as_ref()
+                .ok_or(BridgeSdkError::ConfigError(
+                    "Aurora private key is not set".to_string(),
+                ))?;
+
+        let aurora_chain_id = self.aurora_chain_id.ok_or(BridgeSdkError::ConfigError(
+            "Aurora chain id is not set".to_string(),
+        ))?;
+
+        let private_key_bytes = hex::decode(aurora_private_key).map_err(|_| {
+            BridgeSdkError::ConfigError("Aurora private key is not a valid hex string".to_string())
+        })?;
+
+        if private_key_bytes.len() != 32 {
+            return Err(BridgeSdkError::ConfigError(
+                "Aurora private key is of invalid length".to_string(),
+            ));
+        }
+
+        let wallet = LocalWallet::from_bytes(&private_key_bytes)
+            .map_err(|_| BridgeSdkError::ConfigError("Invalid aurora private key".to_string()))?
+            .with_chain_id(aurora_chain_id);
+
+        Ok(DynEthSigner::new(Arc::new(wallet)))
+    }
+
+    fn aurora_endpoint(&self) -> Result<&str> {
+        Ok(self
+            .aurora_endpoint
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Aurora rpc endpoint is not set".to_string(),
+            ))?)
+    }
+
+    fn near_rpc(&self) -> Result<Arc<dyn NearRpc>> {
+        match &self.near_rpc {
+            Some(near_rpc) => Ok(near_rpc.clone()),
+            // Cached, so that the operations share the health of the endpoints
+            None => self.near_rpc_cache.get_or_try_init(|| {
+                Ok(Arc::new(
+                    NearRpcClient::new(self.near_endpoint()?)
+                        .with_retry_policy(self.near_retry_policy),
+                ))
+            }),
+        }
+    }
+
+    fn near_endpoint(&self) -> Result<&str> {
+        Ok(self
+            .near_endpoint
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Near rpc endpoint is not set".to_string(),
+            ))?)
+    }
+
+    fn aurora_engine_account_id(&self) -> Result<&str> {
+        Ok(self
+            .aurora_engine_account_id
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Aurora engine account id is not set".to_string(),
+            ))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::AbiDecode;
+    use near_crypto::{InMemorySigner, KeyType};
+    use near_primitives::transaction::Action;
+    use near_rpc_client::MockNearRpc;
+
+    const AURORA_ENGINE_ID: &str = "aurora";
+
+    fn connector(near_rpc: Arc<MockNearRpc>) -> AuroraConnector {
+        let signer = InMemorySigner::from_seed(
+            "relayer.near".parse().unwrap(),
+            KeyType::ED25519,
+            "relayer.near",
+        );
+
+        AuroraConnectorBuilder::default()
+            // Only used to build the calls, nothing is sent to it
+            .aurora_endpoint(Some("http://localhost:8545".to_string()))
+            .aurora_chain_id(Some(1313161554))
+            .aurora_private_key(Some(hex::encode([1u8; 32])))
+            .near_endpoint(None)
+            .near_private_key(None)
+            .near_signer(None)
+            .aurora_engine_account_id(Some(AURORA_ENGINE_ID.to_string()))
+            .custom_near_signer(Some(Arc::new(signer)))
+            .near_rpc(Some(near_rpc))
+            .build()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_deposit_encodes_the_aurora_recipient_in_the_msg() {
+        let near_rpc = Arc::new(MockNearRpc::new());
+        let recipient = Address::repeat_byte(0xab);
+
+        let tx_hash = connector(near_rpc.clone())
+            .deposit("usdc.near".to_string(), 100, recipient)
+            .await
+            .unwrap();
+
+        let sent = near_rpc.sent_transactions();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].get_hash(), tx_hash);
+        assert_eq!(sent[0].transaction.receiver_id.as_str(), "usdc.near");
+
+        let Action::FunctionCall(call) = &sent[0].transaction.actions[0] else {
+            panic!("Expected a function call");
+        };
+        assert_eq!(call.method_name, "ft_transfer_call");
+        // The engine expects the recipient address as hex without the `0x` prefix
+        assert_eq!(
+            call.args,
+            near_args::to_bytes(&FtTransferCallArgs {
+                receiver_id: AURORA_ENGINE_ID.to_string(),
+                amount: 100,
+                msg: "ab".repeat(20),
+            })
+        );
+        assert_eq!(call.deposit, 1);
+    }
+
+    #[test]
+    fn test_withdraw_calls_withdraw_to_near() {
+        let erc20_address = Address::repeat_byte(0x11);
+        let recipient: AccountId = "alice.near".parse().unwrap();
+
+        let call = connector(Arc::new(MockNearRpc::new()))
+            .withdraw_call(erc20_address, 100, &recipient)
+            .unwrap();
+
+        assert_eq!(call.tx.to_addr(), Some(&erc20_address));
+        let data = WithdrawToNearCall::decode(call.tx.data().unwrap()).unwrap();
+        assert_eq!(data.recipient.to_vec(), b"alice.near");
+        assert_eq!(data.amount, U256::from(100));
+    }
+}
@@ -0,0 +1,6 @@
+#[macro_use]
+extern crate derive_builder;
+
+mod aurora_connector;
+
+pub use aurora_connector::{AuroraConnector, AuroraConnectorBuilder};
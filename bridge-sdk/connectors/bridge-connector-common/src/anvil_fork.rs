@@ -0,0 +1,243 @@
+use crate::{
+    contract_call,
+    failover::{self, EthProvider},
+    result::{BridgeSdkError, Result},
+};
+use ethers::{
+    abi::Detokenize,
+    contract::{abigen, ContractCall},
+    providers::Middleware,
+    types::{Address, TransactionReceipt, TxHash, H256, U256},
+    utils::{keccak256, Anvil, AnvilInstance},
+};
+use near_primitives::hash::CryptoHash;
+use std::sync::Arc;
+
+abigen!(
+    NearBridgeAdmin,
+    r#"[
+        function admin() public view returns (address)
+        function adminSstore(uint key, uint value) public
+        function bridgeState() public view returns (uint,uint,uint,uint)
+        function blockHashes(uint64) public view returns (bytes32)
+        function blockMerkleRoots(uint64) public view returns (bytes32)
+    ]"#
+);
+
+/// Balance given to the impersonated accounts for the gas of their transactions, 100 ETH
+const IMPERSONATED_BALANCE: u128 = 100_000_000_000_000_000_000;
+
+/// Gas limit of the simulated transactions, set so that a reverting call is still sent rather than failing to estimate
+const SIMULATION_GAS: u64 = 15_000_000;
+
+/// Storage slots searched for the light client mappings
+const MAX_STORAGE_SLOT: u64 = 128;
+
+/// Outcome of a transaction simulated on an [`AnvilFork`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForkSimulation {
+    pub tx_hash: TxHash,
+    /// Block of the fork including the transaction
+    pub block_number: u64,
+    pub gas_used: U256,
+    /// Revert reason if the transaction failed
+    pub revert_reason: Option<String>,
+}
+
+impl ForkSimulation {
+    pub fn succeeded(&self) -> bool {
+        self.revert_reason.is_none()
+    }
+}
+
+/// Local `anvil` node forking an Ethereum network at its latest block, on which bridge transactions are executed
+/// without spending real gas. Requires the `anvil` binary of Foundry in the `PATH`. The node is stopped when dropped
+pub struct AnvilFork {
+    provider: Arc<EthProvider>,
+    // Kills the node when dropped
+    _anvil: AnvilInstance,
+}
+
+impl AnvilFork {
+    /// Forks the first endpoint of `eth_endpoint`
+    pub fn spawn(eth_endpoint: &str) -> Result<Self> {
+        let fork_url =
+            failover::endpoints(eth_endpoint)
+                .next()
+                .ok_or(BridgeSdkError::ConfigError(
+                    "Ethereum rpc endpoint is not set".to_string(),
+                ))?;
+        let anvil = Anvil::new()
+            .fork(fork_url)
+            .try_spawn()
+            .map_err(|err| BridgeSdkError::ConfigError(format!("Failed to start anvil: {err}")))?;
+        let provider = failover::eth_provider(&anvil.endpoint())?;
+
+        tracing::info!(endpoint = anvil.endpoint(), "Started anvil fork");
+
+        Ok(Self {
+            provider: Arc::new(provider),
+            _anvil: anvil,
+        })
+    }
+
+    /// Provider of the fork, e.g. to build the simulated contract calls
+    pub fn provider(&self) -> Arc<EthProvider> {
+        self.provider.clone()
+    }
+
+    /// Lets the fork accept transactions from `address` without its key, and funds it for their gas
+    pub async fn impersonate(&self, address: Address) -> Result<()> {
+        self.provider
+            .request::<_, ()>("anvil_impersonateAccount", [address])
+            .await?;
+        self.provider
+            .request::<_, ()>(
+                "anvil_setBalance",
+                (address, U256::from(IMPERSONATED_BALANCE)),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Makes the Near light client at `light_client_address` accept proofs against the Near block `block_height`, as
+    /// if the block had been relayed. Its hash and merkle root are written to the light client storage by the
+    /// impersonated admin with `adminSstore`. The sync height of the light client is left unchanged
+    pub async fn inject_near_block(
+        &self,
+        light_client_address: Address,
+        block_height: u64,
+        block_hash: CryptoHash,
+        block_merkle_root: CryptoHash,
+    ) -> Result<()> {
+        let light_client = NearBridgeAdmin::new(light_client_address, self.provider());
+        let admin = light_client.admin().call().await?;
+        self.impersonate(admin).await?;
+
+        // The slots of the mappings depend on the light client version, so they're found from the last synced block
+        let (sync_height, ..) = light_client.bridge_state().call().await?;
+        let sync_height = sync_height.as_u64();
+        let hashes_slot = self
+            .find_mapping_slot(
+                light_client_address,
+                sync_height,
+                light_client.block_hashes(sync_height).call().await?,
+            )
+            .await?;
+        let merkle_roots_slot = self
+            .find_mapping_slot(
+                light_client_address,
+                sync_height,
+                light_client.block_merkle_roots(sync_height).call().await?,
+            )
+            .await?;
+
+        for (slot, value) in [
+            (hashes_slot, block_hash),
+            (merkle_roots_slot, block_merkle_root),
+        ] {
+            let call = light_client
+                .admin_sstore(
+                    U256::from_big_endian(mapping_location(block_height, slot).as_bytes()),
+                    U256::from_big_endian(&value.0),
+                )
+                .from(admin);
+            contract_call::check_receipt(&*self.provider, self.send(call).await?).await?;
+        }
+
+        if light_client.block_merkle_roots(block_height).call().await? != block_merkle_root.0 {
+            return Err(BridgeSdkError::ConfigError(
+                "Failed to inject the Near block into the light client".to_string(),
+            ));
+        }
+
+        tracing::info!(
+            block_height,
+            "Injected Near block into the light client of the fork"
+        );
+
+        Ok(())
+    }
+
+    /// Sends `call` from the impersonated `from` and reports its outcome. A revert is reported with its reason
+    /// instead of failing
+    pub async fn simulate<D: Detokenize>(
+        &self,
+        from: Address,
+        call: ContractCall<EthProvider, D>,
+    ) -> Result<ForkSimulation> {
+        self.impersonate(from).await?;
+
+        let receipt = self.send(call.from(from).gas(SIMULATION_GAS)).await?;
+        let revert_reason =
+            match contract_call::check_receipt(&*self.provider, receipt.clone()).await {
+                Ok(_) => None,
+                Err(BridgeSdkError::EthTxFailed { reason, .. }) => Some(reason),
+                Err(err) => return Err(err),
+            };
+
+        Ok(ForkSimulation {
+            tx_hash: receipt.transaction_hash,
+            block_number: receipt.block_number.unwrap_or_default().as_u64(),
+            gas_used: receipt.gas_used.unwrap_or_default(),
+            revert_reason,
+        })
+    }
+
+    async fn send<D: Detokenize>(
+        &self,
+        call: ContractCall<EthProvider, D>,
+    ) -> Result<TransactionReceipt> {
+        let pending_tx = call.send().await?;
+        let tx_hash = pending_tx.tx_hash();
+
+        pending_tx.await?.ok_or(BridgeSdkError::EthTxFailed {
+            tx_hash,
+            reason: "dropped by the fork".to_string(),
+        })
+    }
+
+    async fn find_mapping_slot(&self, address: Address, key: u64, value: [u8; 32]) -> Result<u64> {
+        if value != [0; 32] {
+            for slot in 0..MAX_STORAGE_SLOT {
+                let stored = self
+                    .provider
+                    .get_storage_at(address, mapping_location(key, slot), None)
+                    .await?;
+                if stored.0 == value {
+                    return Ok(slot);
+                }
+            }
+        }
+
+        Err(BridgeSdkError::ConfigError(
+            "Failed to locate the block mappings in the light client storage".to_string(),
+        ))
+    }
+}
+
+/// Storage location of the value of a `mapping(uint64 => ...)` at `slot`
+fn mapping_location(key: u64, slot: u64) -> H256 {
+    let mut preimage = [0; 64];
+    preimage[24..32].copy_from_slice(&key.to_be_bytes());
+    preimage[56..64].copy_from_slice(&slot.to_be_bytes());
+
+    H256(keccak256(preimage))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::abi::{encode, Token};
+
+    #[test]
+    fn test_mapping_location_matches_solidity_layout() {
+        let expected = keccak256(encode(&[
+            Token::Uint(U256::from(123_456_789u64)),
+            Token::Uint(U256::from(7)),
+        ]));
+
+        assert_eq!(mapping_location(123_456_789, 7), H256(expected));
+    }
+}
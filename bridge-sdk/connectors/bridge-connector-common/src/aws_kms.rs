@@ -0,0 +1,59 @@
+use crate::{
+    result::{BridgeSdkError, Result},
+    signer::{EthSigner, SignerError},
+};
+use async_trait::async_trait;
+use ethers::{
+    signers::{AwsSigner, Signer},
+    types::{transaction::eip2718::TypedTransaction, Address, Signature},
+};
+use rusoto_core::Region;
+use rusoto_kms::KmsClient;
+
+/// Ethereum signer backed by an AWS KMS `ECC_SECG_P256K1` key. The private key never leaves KMS
+#[derive(Debug)]
+pub struct AwsKmsEthSigner(AwsSigner);
+
+impl AwsKmsEthSigner {
+    /// Loads the address of the KMS key. The region and the credentials are read from the standard AWS
+    /// environment variables and profile files
+    pub async fn new(key_id: &str, chain_id: u64) -> Result<Self> {
+        Self::with_region(key_id, chain_id, Region::default()).await
+    }
+
+    pub async fn with_region(key_id: &str, chain_id: u64, region: Region) -> Result<Self> {
+        let signer = AwsSigner::new(KmsClient::new(region), key_id, chain_id)
+            .await
+            .map_err(|err| BridgeSdkError::SignerError(err.to_string()))?;
+
+        Ok(Self(signer))
+    }
+}
+
+#[async_trait]
+impl EthSigner for AwsKmsEthSigner {
+    fn address(&self) -> Address {
+        self.0.address()
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.0.chain_id()
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &TypedTransaction,
+    ) -> std::result::Result<Signature, SignerError> {
+        self.0
+            .sign_transaction(tx)
+            .await
+            .map_err(|err| SignerError(err.to_string()))
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> std::result::Result<Signature, SignerError> {
+        self.0
+            .sign_message(message)
+            .await
+            .map_err(|err| SignerError(err.to_string()))
+    }
+}
@@ -0,0 +1,452 @@
+use crate::{
+    contract_failure::ContractFailure,
+    events::{self, ConnectorEvent, ConnectorEvents},
+    failover,
+    result::{BridgeSdkError, Result},
+    transfer_journal::{TransferJournal, TransferStage},
+};
+use async_trait::async_trait;
+use eth_light_client_on_near::EthLightClientOnNear;
+use ethers::{
+    providers::Middleware,
+    types::{Address, TxHash, H256},
+    utils::keccak256,
+};
+use near_light_client_on_eth::{NearLightClientOnEth, NearOnEthClient};
+use near_primitives::{
+    hash::CryptoHash,
+    types::{AccountId, BlockReference, Finality, TransactionOrReceiptId},
+    views::ExecutionStatusView,
+};
+use near_rpc_client::{NearRpc, NearRpcError};
+use serde::{Deserialize, Serialize};
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
+
+/// How long connectors wait for a light client to sync the block of a proven transfer
+pub const DEFAULT_LIGHT_CLIENT_SYNC_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+const LIGHT_CLIENT_SYNC_INITIAL_DELAY: Duration = Duration::from_secs(10);
+const LIGHT_CLIENT_SYNC_MAX_DELAY: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferDirection {
+    NearToEth,
+    EthToNear,
+}
+
+/// Transfer request of connectors that don't need any parameters besides the token, amount and recipient
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenTransfer {
+    pub direction: TransferDirection,
+    /// Token on the source chain, in the format expected by the connector
+    pub token: String,
+    pub amount: u128,
+    /// Recipient on the destination chain
+    pub recipient: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransactionId {
+    Near(CryptoHash),
+    Eth(TxHash),
+}
+
+/// Event that initiated a transfer. It is proven on the destination chain to finalize the transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransferId {
+    Near { receipt_id: CryptoHash },
+    Eth { tx_hash: TxHash, log_index: u64 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferStatus {
+    /// The initiating transaction is not executed yet
+    Pending,
+    /// The initiating transaction failed, so the transfer can't be finalized
+    Failed,
+    /// The initiating transaction is executed, but it can't be proven on the destination chain yet
+    Initiated,
+    /// The transfer can be finalized on the destination chain
+    ReadyToFinalize,
+}
+
+/// Costs of a transfer on top of the transferred amount. Gas of Ethereum transactions isn't included, as it depends on the network conditions when they are sent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeEstimate {
+    /// Deposit attached to NEAR transactions, in yoctoNEAR
+    pub near_deposit: u128,
+    /// Gas attached to NEAR transactions
+    pub near_gas: u64,
+    /// Fee charged in the transferred token
+    pub token_fee: u128,
+}
+
+/// Common interface of the connectors, so that relayers and UIs can drive transfers of any connector
+#[async_trait]
+pub trait BridgeConnector {
+    /// Connector specific parameters of a new transfer
+    type TransferRequest: Send + Sync;
+
+    /// Sends the transaction initiating the transfer on the source chain
+    async fn initiate_transfer(&self, request: Self::TransferRequest) -> Result<TransactionId>;
+
+    /// Proves the transfer on the destination chain and completes it
+    async fn finalize_transfer(&self, transfer_id: TransferId) -> Result<TransactionId>;
+
+    async fn get_status(&self, transfer_id: TransferId) -> Result<TransferStatus>;
+
+    async fn estimate_fees(&self, request: &Self::TransferRequest) -> Result<FeeEstimate>;
+
+    /// Picks up a transfer recorded in the journal, e.g. by a process that crashed before finalizing it: finalizes it
+    /// if it can be, and records the stage it reached. A transfer whose proof was already used is recorded as
+    /// finalized by another process. Transfers that aren't in the journal yet are recorded
+    async fn resume(
+        &self,
+        journal: &dyn TransferJournal,
+        transfer_id: TransferId,
+    ) -> Result<TransferStage>
+    where
+        Self: Sync,
+    {
+        if let Some(transfer) = journal.load(&transfer_id)? {
+            if transfer.stage != TransferStage::Initiated {
+                return Ok(transfer.stage);
+            }
+        }
+
+        let stage = match (self.get_status(transfer_id).await?, transfer_id) {
+            (TransferStatus::Failed, _) => {
+                TransferStage::Failed("Transfer failed on the source chain".to_string())
+            }
+            // The Ethereum light client on Near isn't queried, finalizing fails until it has caught up
+            (TransferStatus::ReadyToFinalize, _)
+            | (TransferStatus::Initiated, TransferId::Eth { .. }) => {
+                match self.finalize_transfer(transfer_id).await {
+                    Ok(transaction_id) => TransferStage::Finalized(Some(transaction_id)),
+                    Err(err) => match err.contract_failure() {
+                        Some(ContractFailure::ProofAlreadyUsed) => TransferStage::Finalized(None),
+                        _ => return Err(err),
+                    },
+                }
+            }
+            _ => TransferStage::Initiated,
+        };
+
+        journal.record(transfer_id, stage.clone())?;
+        Ok(stage)
+    }
+}
+
+/// Status of a transfer initiated on Ethereum. The Ethereum light client on Near isn't queried, so executed transfers are reported as `Initiated`
+pub async fn eth_transfer_status(eth_endpoint: &str, tx_hash: TxHash) -> Result<TransferStatus> {
+    let eth_provider = failover::eth_provider(eth_endpoint)?;
+
+    let status = match eth_provider.get_transaction_receipt(tx_hash).await? {
+        None => TransferStatus::Pending,
+        Some(receipt) if receipt.status == Some(0.into()) => TransferStatus::Failed,
+        Some(_) => TransferStatus::Initiated,
+    };
+
+    Ok(status)
+}
+
+/// Status of a transfer initiated on Near, based on whether the receipt can be proven against the Near light client on Ethereum
+pub async fn near_transfer_status(
+    near_rpc: &dyn NearRpc,
+    eth_endpoint: &str,
+    near_light_client_address: Address,
+    receipt_id: CryptoHash,
+    receiver_id: AccountId,
+) -> Result<TransferStatus> {
+    let near_on_eth_client =
+        NearOnEthClient::new(near_light_client_address, eth_endpoint.to_string());
+
+    let proof_block_height = near_on_eth_client.get_sync_height().await?;
+    let block_hash = near_on_eth_client
+        .get_block_hash(proof_block_height)
+        .await?;
+
+    let proof = near_rpc
+        .get_light_client_proof(
+            TransactionOrReceiptId::Receipt {
+                receipt_id,
+                receiver_id,
+            },
+            CryptoHash(block_hash),
+        )
+        .await;
+
+    match proof {
+        Ok(proof) => match proof.outcome_proof.outcome.status {
+            ExecutionStatusView::Failure(_) => Ok(TransferStatus::Failed),
+            _ => Ok(TransferStatus::ReadyToFinalize),
+        },
+        Err(NearRpcError::RpcLightClientProofError(_)) => Ok(TransferStatus::Initiated),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Index in the block of the first log of the transaction emitted by `emitter` for the event with the given signature,
+/// e.g. `Withdraw(string,address,uint256,string)`
+pub async fn find_event_log_index(
+    eth_endpoint: &str,
+    tx_hash: TxHash,
+    emitter: Address,
+    event_signature: &str,
+) -> Result<u64> {
+    let eth_provider = failover::eth_provider(eth_endpoint)?;
+
+    let receipt = eth_provider
+        .get_transaction_receipt(tx_hash)
+        .await?
+        .ok_or_else(|| {
+            BridgeSdkError::EthProofError(format!("Transaction {tx_hash:?} is not executed yet"))
+        })?;
+
+    let topic = H256(keccak256(event_signature));
+    receipt
+        .logs
+        .iter()
+        .find(|log| log.address == emitter && log.topics.first() == Some(&topic))
+        .and_then(|log| log.log_index)
+        .map(|log_index| log_index.as_u64())
+        .ok_or_else(|| {
+            BridgeSdkError::MissingEvent(format!(
+                "Transaction {tx_hash:?} didn't emit {event_signature} from {emitter:?}"
+            ))
+        })
+}
+
+/// Polls the Near light client on Ethereum with an exponential backoff until it has synced past the block
+/// including the receipt, so that the receipt can be proven against it. Fails if it isn't synced within `timeout`.
+/// Every poll finding it behind is reported to `events`
+pub async fn wait_for_light_client_sync(
+    near_rpc: &dyn NearRpc,
+    near_on_eth_client: &impl NearLightClientOnEth,
+    receipt_id: TransactionOrReceiptId,
+    timeout: Duration,
+    events: Option<&dyn ConnectorEvents>,
+) -> Result<u64> {
+    let final_block = near_rpc
+        .get_block(BlockReference::Finality(Finality::Final))
+        .await?;
+    let proof = near_rpc
+        .get_light_client_proof(receipt_id, final_block.header.hash)
+        .await?;
+    // The block merkle root of the light client head only covers the blocks before it
+    let required_height = proof.block_header_lite.inner_lite.height + 1;
+
+    wait_for_sync_height(
+        "Near light client on Ethereum",
+        required_height,
+        timeout,
+        BridgeSdkError::NearProofError,
+        || async { Ok(near_on_eth_client.get_sync_height().await?) },
+        events,
+    )
+    .await
+}
+
+/// Polls the Ethereum light client on Near with an exponential backoff until it has synced the block including the
+/// transaction, so that its events can be proven against it. Fails if it isn't synced within `timeout`. Every poll
+/// finding it behind is reported to `events`
+pub async fn wait_for_eth_light_client_sync(
+    eth_endpoint: &str,
+    eth_on_near_client: &impl EthLightClientOnNear,
+    tx_hash: TxHash,
+    timeout: Duration,
+    events: Option<&dyn ConnectorEvents>,
+) -> Result<u64> {
+    let eth_provider = failover::eth_provider(eth_endpoint)?;
+
+    let required_height = eth_provider
+        .get_transaction_receipt(tx_hash)
+        .await?
+        .and_then(|receipt| receipt.block_number)
+        .ok_or_else(|| {
+            BridgeSdkError::EthProofError(format!("Transaction {tx_hash:?} is not executed yet"))
+        })?
+        .as_u64();
+
+    wait_for_sync_height(
+        "Ethereum light client on Near",
+        required_height,
+        timeout,
+        BridgeSdkError::EthProofError,
+        || async { Ok(eth_on_near_client.last_block_number().await?) },
+        events,
+    )
+    .await
+}
+
+async fn wait_for_sync_height<F, Fut>(
+    light_client: &str,
+    required_height: u64,
+    timeout: Duration,
+    timeout_error: fn(String) -> BridgeSdkError,
+    mut sync_height: F,
+    events: Option<&dyn ConnectorEvents>,
+) -> Result<u64>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<u64>>,
+{
+    let started = Instant::now();
+    let mut delay = LIGHT_CLIENT_SYNC_INITIAL_DELAY;
+    loop {
+        let height = sync_height().await?;
+        if height >= required_height {
+            return Ok(height);
+        }
+
+        let elapsed = started.elapsed();
+        if elapsed >= timeout {
+            return Err(timeout_error(format!(
+                "{light_client} is at block {height} and hasn't synced block {required_height} within {}s",
+                timeout.as_secs()
+            )));
+        }
+
+        tracing::info!(
+            height,
+            required_height,
+            "Waiting for the {light_client} to sync"
+        );
+        events::emit(
+            events,
+            ConnectorEvent::LightClientLagging {
+                light_client: light_client.to_string(),
+                height,
+                required_height,
+            },
+        );
+        tokio::time::sleep(delay.min(timeout - elapsed)).await;
+        delay = (delay * 2).min(LIGHT_CLIENT_SYNC_MAX_DELAY);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rpc_scheduler::RpcScheduler, signer, transfer_journal::FileTransferJournal};
+    use near_crypto::{InMemorySigner, KeyType};
+    use near_rpc_client::MockNearRpc;
+    use std::sync::Arc;
+
+    const TOKEN_LOCKER_ID: &str = "locker.near";
+
+    /// Finalizes Ethereum transfers with a `finalize_withdraw` call on the mocked Near RPC
+    struct MockConnector {
+        near_rpc: MockNearRpc,
+        status: TransferStatus,
+    }
+
+    #[async_trait]
+    impl BridgeConnector for MockConnector {
+        type TransferRequest = TokenTransfer;
+
+        async fn initiate_transfer(&self, _request: TokenTransfer) -> Result<TransactionId> {
+            unimplemented!()
+        }
+
+        async fn finalize_transfer(&self, _transfer_id: TransferId) -> Result<TransactionId> {
+            let signer = InMemorySigner::from_seed(
+                "relayer.near".parse().unwrap(),
+                KeyType::ED25519,
+                "relayer.near",
+            );
+
+            let tx_hash = signer::near_change(
+                &self.near_rpc,
+                Arc::new(signer),
+                TOKEN_LOCKER_ID.to_string(),
+                "finalize_withdraw".to_string(),
+                b"{}".to_vec(),
+                300_000_000_000_000,
+                0,
+                false,
+                Some(signer::DEFAULT_NEAR_OUTCOME_TIMEOUT),
+                None,
+                &RpcScheduler::shared(),
+            )
+            .await?;
+
+            Ok(TransactionId::Near(tx_hash))
+        }
+
+        async fn get_status(&self, _transfer_id: TransferId) -> Result<TransferStatus> {
+            Ok(self.status)
+        }
+
+        async fn estimate_fees(&self, _request: &TokenTransfer) -> Result<FeeEstimate> {
+            Ok(FeeEstimate::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume() {
+        let journal = FileTransferJournal::new(
+            std::env::temp_dir().join(format!("resume-journal-{}", std::process::id())),
+        );
+        let transfer_id = |byte| TransferId::Eth {
+            tx_hash: TxHash::repeat_byte(byte),
+            log_index: 0,
+        };
+
+        let connector = MockConnector {
+            near_rpc: MockNearRpc::new(),
+            status: TransferStatus::Initiated,
+        };
+        journal
+            .record(transfer_id(1), TransferStage::Initiated)
+            .unwrap();
+
+        let stage = connector.resume(&journal, transfer_id(1)).await.unwrap();
+        let tx_hash = connector.near_rpc.sent_transactions()[0].get_hash();
+        assert_eq!(
+            stage,
+            TransferStage::Finalized(Some(TransactionId::Near(tx_hash)))
+        );
+        assert!(journal.unfinished().unwrap().is_empty());
+
+        // Recorded transfers are only finalized once
+        connector.resume(&journal, transfer_id(1)).await.unwrap();
+        assert_eq!(connector.near_rpc.sent_transactions().len(), 1);
+
+        connector.near_rpc.set_call_failure(
+            TOKEN_LOCKER_ID.parse().unwrap(),
+            "finalize_withdraw",
+            "Smart contract panicked: Event cannot be reused for depositing.",
+        );
+        assert_eq!(
+            connector.resume(&journal, transfer_id(2)).await.unwrap(),
+            TransferStage::Finalized(None)
+        );
+
+        connector.near_rpc.set_call_failure(
+            TOKEN_LOCKER_ID.parse().unwrap(),
+            "finalize_withdraw",
+            "Smart contract panicked: Pausable: paused",
+        );
+        journal
+            .record(transfer_id(3), TransferStage::Initiated)
+            .unwrap();
+        assert!(connector.resume(&journal, transfer_id(3)).await.is_err());
+        assert_eq!(journal.unfinished().unwrap().len(), 1);
+
+        let failed = MockConnector {
+            near_rpc: MockNearRpc::new(),
+            status: TransferStatus::Failed,
+        };
+        assert!(matches!(
+            failed.resume(&journal, transfer_id(4)).await.unwrap(),
+            TransferStage::Failed(_)
+        ));
+        assert!(failed.near_rpc.sent_transactions().is_empty());
+
+        std::fs::remove_dir_all(journal.dir()).unwrap();
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{
+    events::ConnectorEvents,
+    near_args,
+    result::{BridgeSdkError, Result},
+    rpc_scheduler::RpcScheduler,
+    signer::{self, NearSigner},
+};
+use base64::prelude::*;
+use near_primitives::{hash::CryptoHash, views::FinalExecutionStatus};
+use near_rpc_client::NearRpc;
+use serde::Serialize;
+use std::{sync::Arc, time::Duration};
+
+/// Method starting an upload of the signer, replacing any unfinished one
+pub const UPLOAD_INIT_METHOD: &str = "upload_init";
+/// Method appending a chunk to the upload of the signer
+pub const UPLOAD_APPEND_METHOD: &str = "upload_append";
+/// Method calling the target method with the uploaded arguments, and discarding the upload
+pub const UPLOAD_FINALIZE_METHOD: &str = "upload_finalize";
+
+/// Gas attached to the calls starting an upload and appending chunks
+const UPLOAD_GAS: u64 = 100_000_000_000_000;
+
+/// Arguments of `upload_init`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct UploadInitArgs {
+    size: u64,
+}
+
+/// Arguments of `upload_append`. Chunks are appended in order, the offset lets the contract reject a missing or
+/// repeated one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct UploadAppendArgs {
+    offset: u64,
+    /// Base64 encoded chunk
+    data: String,
+}
+
+/// Arguments of `upload_finalize`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct UploadFinalizeArgs {
+    method_name: String,
+}
+
+/// Same as [`signer::near_change`], uploading `args` in chunks first if they're larger than `chunk_size`, for
+/// contracts accepting oversized arguments, e.g. Ethereum proofs, with the init/append/finalize pattern. Each upload
+/// transaction is awaited before the next one is sent. The gas and deposit are attached to the finalizing call, whose
+/// hash is returned, once awaited for up to `outcome_timeout`. The transactions are reported to `events` and sent
+/// through `scheduler`
+#[allow(clippy::too_many_arguments)]
+pub async fn near_change(
+    near_rpc: &dyn NearRpc,
+    signer: Arc<dyn NearSigner>,
+    receiver_id: String,
+    method_name: String,
+    args: Vec<u8>,
+    gas: u64,
+    deposit: u128,
+    dry_run: bool,
+    chunk_size: Option<usize>,
+    outcome_timeout: Option<Duration>,
+    events: Option<&dyn ConnectorEvents>,
+    scheduler: &RpcScheduler,
+) -> Result<CryptoHash> {
+    let chunk_size = match chunk_size {
+        Some(chunk_size) if args.len() > chunk_size => chunk_size,
+        _ => {
+            return signer::near_change(
+                near_rpc,
+                signer,
+                receiver_id,
+                method_name,
+                args,
+                gas,
+                deposit,
+                dry_run,
+                outcome_timeout,
+                events,
+                scheduler,
+            )
+            .await
+        }
+    };
+
+    if chunk_size == 0 {
+        return Err(BridgeSdkError::InvalidArgument(
+            "Upload chunk size must be positive".to_string(),
+        ));
+    }
+
+    tracing::info!(
+        size = args.len(),
+        chunks = args.len().div_ceil(chunk_size),
+        "Uploading arguments in chunks"
+    );
+
+    upload(
+        near_rpc,
+        &signer,
+        &receiver_id,
+        UPLOAD_INIT_METHOD,
+        near_args::to_bytes(&UploadInitArgs {
+            size: args.len() as u64,
+        }),
+        dry_run,
+        events,
+        scheduler,
+    )
+    .await?;
+
+    for (index, chunk) in args.chunks(chunk_size).enumerate() {
+        let append_args = UploadAppendArgs {
+            offset: (index * chunk_size) as u64,
+            data: BASE64_STANDARD.encode(chunk),
+        };
+        upload(
+            near_rpc,
+            &signer,
+            &receiver_id,
+            UPLOAD_APPEND_METHOD,
+            near_args::to_bytes(&append_args),
+            dry_run,
+            events,
+            scheduler,
+        )
+        .await?;
+
+        tracing::debug!(offset = append_args.offset, "Uploaded chunk");
+    }
+
+    signer::near_change(
+        near_rpc,
+        signer,
+        receiver_id,
+        UPLOAD_FINALIZE_METHOD.to_string(),
+        near_args::to_bytes(&UploadFinalizeArgs { method_name }),
+        gas,
+        deposit,
+        dry_run,
+        outcome_timeout,
+        events,
+        scheduler,
+    )
+    .await
+}
+
+/// Sends an upload call and waits for it to succeed, so that the chunks are appended in order
+#[allow(clippy::too_many_arguments)]
+async fn upload(
+    near_rpc: &dyn NearRpc,
+    signer: &Arc<dyn NearSigner>,
+    receiver_id: &str,
+    method_name: &str,
+    args: Vec<u8>,
+    dry_run: bool,
+    events: Option<&dyn ConnectorEvents>,
+    scheduler: &RpcScheduler,
+) -> Result<()> {
+    let tx_hash = signer::near_change(
+        near_rpc,
+        signer.clone(),
+        receiver_id.to_string(),
+        method_name.to_string(),
+        args,
+        UPLOAD_GAS,
+        0,
+        dry_run,
+        // Awaited below, checking the status of the upload
+        None,
+        events,
+        scheduler,
+    )
+    .await?;
+
+    if dry_run {
+        return Ok(());
+    }
+
+    let outcome = near_rpc
+        .wait_for_tx_final_outcome(
+            tx_hash,
+            signer.account_id(),
+            near_rpc_client::DEFAULT_WAIT_FINAL_OUTCOME_TIMEOUT_SEC,
+        )
+        .await?;
+
+    match outcome.status {
+        FinalExecutionStatus::SuccessValue(_) => Ok(()),
+        status => Err(BridgeSdkError::NearOutcomeError(format!(
+            "Upload transaction {tx_hash} failed: {status:?}"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upload_args_encoding() {
+        assert_eq!(
+            near_args::to_bytes(&UploadAppendArgs {
+                offset: 4,
+                data: BASE64_STANDARD.encode([0xff, 0x00]),
+            }),
+            br#"{"offset":4,"data":"/wA="}"#
+        );
+        assert_eq!(
+            near_args::to_bytes(&UploadFinalizeArgs {
+                method_name: "withdraw".to_string(),
+            }),
+            br#"{"method_name":"withdraw"}"#
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use crate::result::Result;
+use std::sync::{Arc, OnceLock};
+
+/// Client of a connector, e.g. its Ethereum provider or signer middleware, built on first use and shared by the
+/// following calls, so that they reuse the HTTP connections. The connector configuration can't change once it is
+/// built, so the client never has to be rebuilt. Clones of the cache share the client, so that the clones of a
+/// connector used by concurrent tasks build it once
+#[derive(Debug)]
+pub struct ClientCache<T>(Arc<OnceLock<T>>);
+
+impl<T> Default for ClientCache<T> {
+    fn default() -> Self {
+        Self(Arc::new(OnceLock::new()))
+    }
+}
+
+impl<T> Clone for ClientCache<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Clone> ClientCache<T> {
+    /// Cached client, or the one built by `init`. A failed build isn't cached, so it's retried by the next call. If
+    /// concurrent calls build the client, all of them get the first one that is cached
+    pub fn get_or_try_init(&self, init: impl FnOnce() -> Result<T>) -> Result<T> {
+        if let Some(client) = self.0.get() {
+            return Ok(client.clone());
+        }
+
+        let client = init()?;
+        Ok(self.0.get_or_init(|| client).clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::BridgeSdkError;
+
+    #[test]
+    fn test_client_cache_only_caches_built_clients() {
+        let cache = ClientCache::<Arc<String>>::default();
+
+        assert!(cache
+            .get_or_try_init(|| Err(BridgeSdkError::ConfigError("no endpoint".to_string())))
+            .is_err());
+
+        let client = cache
+            .get_or_try_init(|| Ok(Arc::new("first".to_string())))
+            .unwrap();
+        let cached = cache
+            .get_or_try_init(|| Ok(Arc::new("second".to_string())))
+            .unwrap();
+        assert!(Arc::ptr_eq(&client, &cached));
+
+        let shared = cache
+            .clone()
+            .get_or_try_init(|| Ok(Arc::new("third".to_string())))
+            .unwrap();
+        assert!(Arc::ptr_eq(&client, &shared));
+    }
+}
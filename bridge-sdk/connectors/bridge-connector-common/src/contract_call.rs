@@ -0,0 +1,645 @@
+use crate::{
+    bridge_connector::TransactionId,
+    events::{self, ConnectorEvent, ConnectorEvents},
+    failover, nonce_journal,
+    nonce_journal::JournalEntry,
+    nonce_manager::NonceManager,
+    result::{BridgeSdkError, Result},
+    rpc_scheduler::{RpcPriority, RpcScheduler},
+    signer::EthSignerClient,
+};
+use ethers::{
+    abi::{decode, parse_abi, Detokenize, ParamType, Token},
+    contract::{builders::ContractCall, Contract},
+    providers::{Middleware, MiddlewareError},
+    signers::Signer,
+    types::{
+        transaction::eip2718::TypedTransaction, Address, BlockId, BlockNumber, Bytes,
+        TransactionReceipt, TransactionRequest, TxHash, U256,
+    },
+};
+use std::{path::Path, sync::Arc};
+
+/// Optional overrides for an Ethereum transaction. Fields left unset are filled in by ethers, i.e. estimated or
+/// fetched from the node
+#[derive(Debug, Clone, Default)]
+pub struct EthTxOptions {
+    pub value: Option<U256>,
+    pub gas_limit: Option<U256>,
+    /// EIP-1559 fee cap. Used as the gas price if the transaction is sent as a legacy one
+    pub max_fee_per_gas: Option<U256>,
+    pub max_priority_fee_per_gas: Option<U256>,
+    pub nonce: Option<U256>,
+    /// Wait until the transaction is mined and buried under this many blocks, failing if it reverted. The
+    /// transaction hash is returned right after sending if unset, except for finalizations and token deployments
+    /// which always wait for one confirmation, since they revert whenever their proof is rejected
+    pub confirmations: Option<usize>,
+}
+
+impl EthTxOptions {
+    /// Applies the overrides to a contract call
+    pub fn apply<M: Middleware, D: Detokenize>(
+        &self,
+        mut call: ContractCall<M, D>,
+    ) -> ContractCall<M, D> {
+        if let Some(value) = self.value {
+            call = call.value(value);
+        }
+        if let Some(gas_limit) = self.gas_limit {
+            call = call.gas(gas_limit);
+        }
+        if let Some(nonce) = self.nonce {
+            call = call.nonce(nonce);
+        }
+
+        match &mut call.tx {
+            TypedTransaction::Eip1559(tx) => {
+                if let Some(max_fee_per_gas) = self.max_fee_per_gas {
+                    tx.max_fee_per_gas = Some(max_fee_per_gas);
+                }
+                if let Some(max_priority_fee_per_gas) = self.max_priority_fee_per_gas {
+                    tx.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
+                }
+            }
+            tx => {
+                if let Some(max_fee_per_gas) = self.max_fee_per_gas {
+                    tx.set_gas_price(max_fee_per_gas);
+                }
+            }
+        }
+
+        call
+    }
+
+    /// Options for the transaction sent right after this one by the same account, e.g. a withdrawal following an
+    /// approval. Only the nonce differs
+    pub fn next(&self) -> Self {
+        Self {
+            nonce: self.nonce.map(|nonce| nonce + 1),
+            ..self.clone()
+        }
+    }
+}
+
+/// Optional overrides for the Near transactions of a connector. The connector defaults are used for fields left unset
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NearTxOptions {
+    /// Gas attached to every Near function call
+    pub gas: Option<u64>,
+    /// Deposit attached to calls paying for storage or fees, e.g. `finalize_withdraw`. The one yoctoNEAR required by
+    /// `ft_transfer_call` and user provided amounts aren't overridden
+    pub deposit: Option<u128>,
+    /// Ethereum proofs larger than this many bytes are uploaded in chunks of this size, for contracts accepting chunked
+    /// uploads. See [`crate::chunked_upload`]
+    pub proof_chunk_size: Option<usize>,
+}
+
+impl NearTxOptions {
+    pub fn gas(&self, default: u64) -> u64 {
+        self.gas.unwrap_or(default)
+    }
+
+    pub fn deposit(&self, default: u128) -> u128 {
+        self.deposit.unwrap_or(default)
+    }
+}
+
+/// Gas and cost estimate of an Ethereum transaction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EthGasEstimate {
+    pub gas: U256,
+    /// Base fee of the latest block, or the gas price on networks without EIP-1559
+    pub base_fee_per_gas: U256,
+    /// Priority fee suggested by the node
+    pub max_priority_fee_per_gas: U256,
+    /// Expected cost of the transaction in wei
+    pub cost: U256,
+}
+
+impl EthGasEstimate {
+    pub fn new(gas: U256, base_fee_per_gas: U256, max_priority_fee_per_gas: U256) -> Self {
+        Self {
+            gas,
+            base_fee_per_gas,
+            max_priority_fee_per_gas,
+            cost: gas.saturating_mul(base_fee_per_gas.saturating_add(max_priority_fee_per_gas)),
+        }
+    }
+}
+
+/// Runs `eth_estimateGas` for the call, including its calldata and value, and prices the gas at the current fees
+pub async fn estimate_eth_call<D: Detokenize>(
+    eth_endpoint: &str,
+    call: &ContractCall<EthSignerClient, D>,
+) -> Result<EthGasEstimate> {
+    let eth_provider = failover::eth_provider(eth_endpoint)?;
+
+    let gas = call.estimate_gas().await?;
+
+    let base_fee_per_gas = eth_provider
+        .get_block(BlockNumber::Latest)
+        .await?
+        .and_then(|block| block.base_fee_per_gas);
+
+    let estimate = match base_fee_per_gas {
+        Some(base_fee_per_gas) => {
+            let (_, max_priority_fee_per_gas) = eth_provider.estimate_eip1559_fees(None).await?;
+            EthGasEstimate::new(gas, base_fee_per_gas, max_priority_fee_per_gas)
+        }
+        None => EthGasEstimate::new(gas, eth_provider.get_gas_price().await?, U256::zero()),
+    };
+
+    tracing::debug!(
+        gas = estimate.gas.to_string(),
+        cost = estimate.cost.to_string(),
+        "Estimated transaction cost"
+    );
+
+    Ok(estimate)
+}
+
+/// Sends a transaction calling an arbitrary contract function. `abi_fn` is a human-readable
+/// function signature, e.g. `function approve(address spender, uint256 amount) returns (bool)`
+#[allow(clippy::too_many_arguments)]
+pub async fn call_eth_contract(
+    client: Arc<EthSignerClient>,
+    address: Address,
+    abi_fn: &str,
+    args: Vec<Token>,
+    tx_opts: EthTxOptions,
+    dry_run: bool,
+    nonce_journal: Option<&Path>,
+    events: Option<&dyn ConnectorEvents>,
+    scheduler: &RpcScheduler,
+) -> Result<TxHash> {
+    let abi = parse_abi(&[abi_fn]).map_err(|_| {
+        BridgeSdkError::InvalidArgument(format!("Invalid function signature: {abi_fn}"))
+    })?;
+    let function_name = abi
+        .functions()
+        .next()
+        .map(|function| function.name.clone())
+        .ok_or(BridgeSdkError::InvalidArgument(format!(
+            "No function found in signature: {abi_fn}"
+        )))?;
+
+    let contract = Contract::new(address, abi, client.clone());
+    let call = contract
+        .method::<_, ()>(&function_name, args)
+        .map_err(|e| BridgeSdkError::InvalidArgument(e.to_string()))?;
+    let call = tx_opts.apply(call);
+
+    let tx_hash = send_eth_call(
+        &client,
+        call,
+        tx_opts.confirmations,
+        dry_run,
+        nonce_journal,
+        events,
+        scheduler,
+    )
+    .await?;
+
+    tracing::info!(
+        tx_hash = format!("{:?}", tx_hash),
+        function = function_name,
+        "Sent contract call transaction"
+    );
+
+    Ok(tx_hash)
+}
+
+/// Sends the call and returns the transaction hash. With `confirmations` the transaction is awaited and a revert is
+/// returned as an error along with its reason. With `dry_run` the call is only simulated with `eth_estimateGas` and `eth_call`, and the
+/// hash the signed transaction would have is returned.
+///
+/// With `nonce_journal` the call is sent in strict mode: the transactions of a signer are sent one at a time, each one
+/// waiting for the previous one to be mined, and every signed transaction is recorded with its nonce in the journal
+/// before it's broadcast. A transaction left unresolved by a crash is broadcast again rather than signed anew, so that
+/// no operation is sent twice. The journal must not be shared by several processes.
+///
+/// The submission and confirmation of the transaction are reported to `events`. The transaction is sent once `scheduler`
+/// has capacity for a submission to the endpoint
+pub async fn send_eth_call<D: Detokenize>(
+    client: &EthSignerClient,
+    call: ContractCall<EthSignerClient, D>,
+    confirmations: Option<usize>,
+    dry_run: bool,
+    nonce_journal: Option<&Path>,
+    events: Option<&dyn ConnectorEvents>,
+    scheduler: &RpcScheduler,
+) -> Result<TxHash> {
+    if !dry_run {
+        if let Some(journal) = nonce_journal {
+            return send_eth_call_strict(client, call, confirmations, journal, events, scheduler)
+                .await;
+        }
+
+        let eth_endpoint = client.provider().url().to_string();
+        let chain_id = client.signer().chain_id();
+        let mut call = call;
+
+        // Concurrent sends from the same signer get consecutive nonces. An explicit nonce bypasses the manager
+        let reservation = match call.tx.nonce() {
+            Some(_) => {
+                NonceManager::global()
+                    .reset(chain_id, client.address())
+                    .await;
+                None
+            }
+            None => {
+                let pending_nonce = client
+                    .provider()
+                    .get_transaction_count(client.address(), Some(BlockNumber::Pending.into()))
+                    .await?;
+                let reservation = NonceManager::global()
+                    .reserve(chain_id, client.address(), pending_nonce)
+                    .await;
+                call.tx.set_nonce(reservation.nonce());
+                Some(reservation)
+            }
+        };
+
+        let pending_tx = scheduler
+            .run(&eth_endpoint, RpcPriority::Submission, async {
+                Ok(call.send().await?)
+            })
+            .await?;
+        let tx_hash = pending_tx.tx_hash();
+        if let Some(reservation) = reservation {
+            reservation.sent();
+        }
+        events::emit(
+            events,
+            ConnectorEvent::TxSubmitted {
+                tx: TransactionId::Eth(tx_hash),
+            },
+        );
+
+        if let Some(confirmations) = confirmations {
+            tracing::info!(
+                tx_hash = format!("{:?}", tx_hash),
+                confirmations,
+                "Waiting for the transaction to be confirmed"
+            );
+
+            let receipt = pending_tx
+                .confirmations(confirmations)
+                .await?
+                .ok_or_else(|| BridgeSdkError::EthTxFailed {
+                    tx_hash,
+                    reason: "dropped from the mempool".to_string(),
+                })?;
+            check_receipt(client.provider(), receipt).await?;
+            events::emit(
+                events,
+                ConnectorEvent::TxConfirmed {
+                    tx: TransactionId::Eth(tx_hash),
+                },
+            );
+        }
+
+        return Ok(tx_hash);
+    }
+
+    let eth_provider = client.provider();
+    let mut tx = call.tx;
+    tx.set_from(client.address());
+    tx.set_chain_id(client.signer().chain_id());
+    if tx.nonce().is_none() {
+        let nonce = eth_provider
+            .get_transaction_count(client.address(), Some(BlockNumber::Pending.into()))
+            .await?;
+        tx.set_nonce(nonce);
+    }
+
+    // Estimating the gas fails if the call reverts
+    eth_provider.fill_transaction(&mut tx, None).await?;
+    let output = eth_provider.call(&tx, None).await?;
+
+    let signature = client.signer().sign_transaction(&tx).await?;
+    let tx_hash = tx.hash(&signature);
+
+    tracing::info!(
+        tx_hash = format!("{:?}", tx_hash),
+        gas = tx.gas().copied().unwrap_or_default().to_string(),
+        output = output.to_string(),
+        "Dry run: simulated the transaction without sending it"
+    );
+
+    Ok(tx_hash)
+}
+
+/// Sends the call once the previous transactions of the signer recorded in `journal` are mined, recording the signed
+/// transaction before broadcasting it and waiting for it to be mined before returning
+async fn send_eth_call_strict<D: Detokenize>(
+    client: &EthSignerClient,
+    call: ContractCall<EthSignerClient, D>,
+    confirmations: Option<usize>,
+    journal: &Path,
+    events: Option<&dyn ConnectorEvents>,
+    scheduler: &RpcScheduler,
+) -> Result<TxHash> {
+    let eth_endpoint = client.provider().url().to_string();
+    let eth_provider = client.provider();
+    let chain_id = client.signer().chain_id();
+    let sender = client.address();
+
+    // Held until the transaction is mined rather than broadcast, which serializes the sends of the signer. It's never
+    // marked as sent, so that the next nonce is always taken from the node
+    let pending_nonce = eth_provider
+        .get_transaction_count(sender, Some(BlockNumber::Pending.into()))
+        .await?;
+    let reservation = NonceManager::global()
+        .reserve(chain_id, sender, pending_nonce)
+        .await;
+
+    resolve_journal(client, journal, chain_id).await?;
+
+    let mut tx = call.tx;
+    tx.set_from(sender);
+    tx.set_chain_id(chain_id);
+    if tx.nonce().is_none() {
+        tx.set_nonce(reservation.nonce());
+    }
+    eth_provider.fill_transaction(&mut tx, None).await?;
+
+    let signature = client.signer().sign_transaction(&tx).await?;
+    let entry = JournalEntry {
+        chain_id,
+        sender,
+        nonce: tx.nonce().copied().unwrap_or_default(),
+        tx_hash: tx.hash(&signature),
+        raw_tx: tx.rlp_signed(&signature),
+        signed_at: nonce_journal::unix_timestamp(),
+    };
+    let tx_hash = entry.tx_hash;
+    let raw_tx = entry.raw_tx.clone();
+    nonce_journal::record(journal, entry)?;
+
+    // A failed broadcast leaves the entry in the journal, the transaction may still have reached the mempool
+    let pending_tx = scheduler
+        .run(&eth_endpoint, RpcPriority::Submission, async {
+            Ok(eth_provider.send_raw_transaction(raw_tx).await?)
+        })
+        .await?;
+    events::emit(
+        events,
+        ConnectorEvent::TxSubmitted {
+            tx: TransactionId::Eth(tx_hash),
+        },
+    );
+
+    tracing::info!(
+        tx_hash = format!("{:?}", tx_hash),
+        "Strict mode: waiting for the transaction to be mined"
+    );
+    let receipt = pending_tx
+        .confirmations(confirmations.unwrap_or(1).max(1))
+        .await?
+        .ok_or_else(|| BridgeSdkError::EthTxFailed {
+            tx_hash,
+            reason: "dropped from the mempool".to_string(),
+        })?;
+
+    nonce_journal::remove(journal, tx_hash)?;
+    drop(reservation);
+    check_receipt(eth_provider, receipt).await?;
+    events::emit(
+        events,
+        ConnectorEvent::TxConfirmed {
+            tx: TransactionId::Eth(tx_hash),
+        },
+    );
+
+    Ok(tx_hash)
+}
+
+/// Removes the recorded transactions of the signer whose nonce was used since, and fails if one is still pending after
+/// broadcasting it again. Sending a new transaction in that case could perform an operation twice, e.g. when the
+/// process crashed before the recorded transaction reached the mempool and the operation is retried
+async fn resolve_journal(client: &EthSignerClient, journal: &Path, chain_id: u64) -> Result<()> {
+    let eth_provider = client.provider();
+    let sender = client.address();
+    let entries = nonce_journal::pending(journal, chain_id, sender)?;
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    let mined_nonce = eth_provider
+        .get_transaction_count(sender, Some(BlockNumber::Latest.into()))
+        .await?;
+
+    for entry in entries {
+        // Either the recorded transaction or another one with the same nonce was mined, the recorded one can't be
+        if entry.nonce < mined_nonce {
+            tracing::info!(
+                tx_hash = format!("{:?}", entry.tx_hash),
+                nonce = entry.nonce.to_string(),
+                "Strict mode: recorded transaction resolved"
+            );
+            nonce_journal::remove(journal, entry.tx_hash)?;
+            continue;
+        }
+
+        // Broadcasting the same signed transaction again can't create a second one. The node usually rejects it as
+        // already known
+        if let Err(err) = eth_provider
+            .send_raw_transaction(entry.raw_tx.clone())
+            .await
+        {
+            tracing::debug!(
+                tx_hash = format!("{:?}", entry.tx_hash),
+                "Strict mode: rebroadcast rejected: {err}"
+            );
+        }
+
+        return Err(BridgeSdkError::EthTxFailed {
+            tx_hash: entry.tx_hash,
+            reason: format!(
+                "pending since a previous run with nonce {}, it was broadcast again. Retry once it's mined, or remove it from {} if it can't be",
+                entry.nonce,
+                journal.display()
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+/// Turns the receipt of a reverted transaction into an error. The revert reason isn't part of the receipt, so the
+/// transaction is replayed with `eth_call` to get it
+pub async fn check_receipt<M: Middleware>(
+    provider: &M,
+    receipt: TransactionReceipt,
+) -> Result<TransactionReceipt> {
+    if receipt.status == Some(1.into()) {
+        return Ok(receipt);
+    }
+
+    let reason = replay_revert_reason(provider, &receipt).await;
+    tracing::error!(
+        tx_hash = format!("{:?}", receipt.transaction_hash),
+        reason = reason.as_str(),
+        "Transaction reverted"
+    );
+
+    Err(BridgeSdkError::EthTxFailed {
+        tx_hash: receipt.transaction_hash,
+        reason,
+    })
+}
+
+async fn replay_revert_reason<M: Middleware>(provider: &M, receipt: &TransactionReceipt) -> String {
+    let tx = match provider.get_transaction(receipt.transaction_hash).await {
+        Ok(Some(tx)) => tx,
+        Ok(None) => return "reverted, the transaction to replay wasn't found".to_string(),
+        Err(err) => return format!("reverted, failed to fetch the transaction to replay: {err}"),
+    };
+
+    let mut request = TransactionRequest::new()
+        .from(tx.from)
+        .data(tx.input)
+        .value(tx.value)
+        .gas(tx.gas);
+    if let Some(to) = tx.to {
+        request = request.to(to);
+    }
+
+    // Replayed on top of the previous block, so the transactions preceding it in its own block are missing
+    let block = receipt
+        .block_number
+        .map(|number| BlockId::Number(BlockNumber::Number(number.saturating_sub(1.into()))));
+
+    match provider.call(&request.into(), block).await {
+        Ok(_) => "reverted, but succeeded when replayed".to_string(),
+        Err(err) => match err.as_error_response().and_then(|e| e.as_revert_data()) {
+            Some(data) => decode_revert_reason(&data),
+            None => err.to_string(),
+        },
+    }
+}
+
+/// Decodes the `Error(string)` and `Panic(uint256)` reverts emitted by solidity. Custom errors are returned as hex
+pub fn decode_revert_reason(data: &[u8]) -> String {
+    const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+    const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+    if data.is_empty() {
+        return "reverted without a reason".to_string();
+    }
+    if data.len() < 4 {
+        return format!("custom error {}", Bytes::from(data.to_vec()));
+    }
+
+    let (selector, args) = data.split_at(4);
+    let token = match selector {
+        s if s == ERROR_SELECTOR => decode(&[ParamType::String], args).ok(),
+        s if s == PANIC_SELECTOR => decode(&[ParamType::Uint(256)], args).ok(),
+        _ => None,
+    };
+
+    match token.and_then(|tokens| tokens.into_iter().next()) {
+        Some(Token::String(reason)) => reason,
+        Some(Token::Uint(code)) => format!("panic code 0x{code:x}"),
+        _ => format!("custom error {}", Bytes::from(data.to_vec())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::{Http, Provider};
+
+    fn approve_call() -> ContractCall<Provider<Http>, bool> {
+        let abi = parse_abi(&["function approve(address spender, uint256 amount) returns (bool)"])
+            .unwrap();
+        let provider = Provider::<Http>::try_from("http://localhost:8545").unwrap();
+        let contract = Contract::new(Address::zero(), abi, Arc::new(provider));
+
+        contract
+            .method("approve", (Address::zero(), U256::one()))
+            .unwrap()
+    }
+
+    #[test]
+    fn apply_sets_eip1559_fees() {
+        let tx_opts = EthTxOptions {
+            gas_limit: Some(100_000.into()),
+            max_fee_per_gas: Some(30_000_000_000u64.into()),
+            max_priority_fee_per_gas: Some(1_000_000_000u64.into()),
+            nonce: Some(7.into()),
+            ..Default::default()
+        };
+
+        let call = tx_opts.apply(approve_call());
+
+        let TypedTransaction::Eip1559(tx) = call.tx else {
+            panic!("Expected an EIP-1559 transaction");
+        };
+        assert_eq!(tx.gas, Some(100_000.into()));
+        assert_eq!(tx.max_fee_per_gas, Some(30_000_000_000u64.into()));
+        assert_eq!(tx.max_priority_fee_per_gas, Some(1_000_000_000u64.into()));
+        assert_eq!(tx.nonce, Some(7.into()));
+    }
+
+    #[tokio::test]
+    async fn check_receipt_rejects_reverted_transactions() {
+        let (provider, _mock) = Provider::mocked();
+        let receipt = TransactionReceipt {
+            status: Some(1.into()),
+            ..Default::default()
+        };
+        assert!(check_receipt(&provider, receipt.clone()).await.is_ok());
+
+        let reverted = TransactionReceipt {
+            status: Some(0.into()),
+            ..receipt
+        };
+        assert!(matches!(
+            check_receipt(&provider, reverted).await,
+            Err(BridgeSdkError::EthTxFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn decode_revert_reason_handles_solidity_errors() {
+        let mut error = vec![0x08, 0xc3, 0x79, 0xa0];
+        error.extend(ethers::abi::encode(&[Token::String(
+            "Proof already used".to_string(),
+        )]));
+        assert_eq!(decode_revert_reason(&error), "Proof already used");
+
+        let mut panic = vec![0x4e, 0x48, 0x7b, 0x71];
+        panic.extend(ethers::abi::encode(&[Token::Uint(0x11.into())]));
+        assert_eq!(decode_revert_reason(&panic), "panic code 0x11");
+
+        assert_eq!(
+            decode_revert_reason(&[0xde, 0xad, 0xbe, 0xef]),
+            "custom error 0xdeadbeef"
+        );
+        assert_eq!(decode_revert_reason(&[]), "reverted without a reason");
+    }
+
+    #[test]
+    fn gas_estimate_cost_includes_priority_fee() {
+        let estimate = EthGasEstimate::new(100_000.into(), 20.into(), 2.into());
+
+        assert_eq!(estimate.cost, 2_200_000.into());
+    }
+
+    #[test]
+    fn next_increments_nonce() {
+        let tx_opts = EthTxOptions {
+            max_fee_per_gas: Some(1.into()),
+            nonce: Some(7.into()),
+            ..Default::default()
+        };
+
+        let next = tx_opts.next();
+
+        assert_eq!(next.nonce, Some(8.into()));
+        assert_eq!(next.max_fee_per_gas, Some(1.into()));
+        assert_eq!(EthTxOptions::default().next().nonce, None);
+    }
+}
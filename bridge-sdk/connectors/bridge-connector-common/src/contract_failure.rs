@@ -0,0 +1,97 @@
+use std::fmt;
+
+/// Known cause of a failed call to a bridge contract, recognized from the revert reason of an Ethereum contract or
+/// the panic message of a Near contract
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContractFailure {
+    /// The account isn't registered on the NEP-141 token, see `storage_deposit`
+    AccountNotRegistered,
+    /// The attached deposit doesn't cover the storage of the call
+    InsufficientStorageDeposit,
+    /// The proof was already used, i.e. the transfer was already finalized
+    ProofAlreadyUsed,
+    /// The proof doesn't verify against the light client of the destination chain
+    InvalidProof,
+    /// The called method is paused by the contract admin
+    Paused,
+}
+
+/// Lowercase fragments of the messages of each failure, in the order they're matched
+const KNOWN_MESSAGES: &[(&str, ContractFailure)] = &[
+    ("is not registered", ContractFailure::AccountNotRegistered),
+    (
+        "attached deposit is less than",
+        ContractFailure::InsufficientStorageDeposit,
+    ),
+    (
+        "not enough attached deposit",
+        ContractFailure::InsufficientStorageDeposit,
+    ),
+    ("proof already used", ContractFailure::ProofAlreadyUsed),
+    ("cannot be reused", ContractFailure::ProofAlreadyUsed),
+    ("invalid proof", ContractFailure::InvalidProof),
+    ("cannot verify", ContractFailure::InvalidProof),
+    ("verification failed", ContractFailure::InvalidProof),
+    ("paused", ContractFailure::Paused),
+    // `EnforcedPause()` custom error of OpenZeppelin 5
+    ("0xd93c0665", ContractFailure::Paused),
+];
+
+impl ContractFailure {
+    /// Recognizes the failure from a revert reason, a panic message or an error embedding one of them
+    pub fn from_message(message: &str) -> Option<Self> {
+        let message = message.to_lowercase();
+
+        KNOWN_MESSAGES
+            .iter()
+            .find(|(fragment, _)| message.contains(fragment))
+            .map(|(_, failure)| *failure)
+            // Reverts of `unpause` on a contract that isn't paused
+            .filter(|_| !message.contains("not paused"))
+    }
+}
+
+impl fmt::Display for ContractFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            ContractFailure::AccountNotRegistered => "account is not registered",
+            ContractFailure::InsufficientStorageDeposit => "insufficient storage deposit",
+            ContractFailure::ProofAlreadyUsed => "proof already used",
+            ContractFailure::InvalidProof => "invalid proof",
+            ContractFailure::Paused => "paused",
+        };
+
+        f.write_str(description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_message() {
+        let cases = [
+            (
+                "Smart contract panicked: The account alice.near is not registered",
+                Some(ContractFailure::AccountNotRegistered),
+            ),
+            (
+                "Proof already used",
+                Some(ContractFailure::ProofAlreadyUsed),
+            ),
+            (
+                "Event cannot be reused for depositing.",
+                Some(ContractFailure::ProofAlreadyUsed),
+            ),
+            ("Pausable: paused", Some(ContractFailure::Paused)),
+            ("custom error 0xd93c0665", Some(ContractFailure::Paused)),
+            ("Pausable: not paused", None),
+            ("panic code 0x11", None),
+        ];
+
+        for (message, failure) in cases {
+            assert_eq!(ContractFailure::from_message(message), failure, "{message}");
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::result::{BridgeSdkError, Result};
+use near_rpc_client::deadline;
+use std::{future::Future, time::Duration};
+use tokio::time::Instant;
+
+/// Runs a connector call within a time budget, e.g. `estimate_fees` within 30 seconds. The budget is propagated to
+/// the individual Near and scheduled RPC requests of the call, which fail once it's spent instead of running to their
+/// own timeouts. Fails with [`BridgeSdkError::DeadlineExceeded`] if the call doesn't complete in time
+pub async fn with_timeout<T>(
+    timeout: Duration,
+    call: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let deadline = Instant::now() + timeout;
+
+    deadline::scope(deadline, async {
+        let deadline = deadline::current().unwrap_or(deadline);
+
+        tokio::time::timeout_at(deadline, call).await.map_err(|_| {
+            BridgeSdkError::DeadlineExceeded(format!("{}s budget", timeout.as_secs_f32()))
+        })?
+    })
+    .await
+}
+
+/// Time left of the budget of the enclosing [`with_timeout`], if any
+pub fn remaining() -> Option<Duration> {
+    deadline::remaining()
+}
+
+/// Runs a single RPC request to `endpoint` within the budget of the enclosing [`with_timeout`], if any
+pub(crate) async fn run<T>(endpoint: &str, request: impl Future<Output = Result<T>>) -> Result<T> {
+    deadline::run(request)
+        .await
+        .ok_or_else(|| BridgeSdkError::DeadlineExceeded(format!("request to {endpoint}")))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_with_timeout_bounds_the_requests() {
+        let slow_request = run("http://rpc", async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(())
+        });
+
+        assert!(run("http://rpc", async { Ok(()) }).await.is_ok());
+        assert!(matches!(
+            deadline::scope(Instant::now() + Duration::from_millis(50), slow_request).await,
+            Err(BridgeSdkError::DeadlineExceeded(reason)) if reason == "request to http://rpc"
+        ));
+        assert!(matches!(
+            with_timeout(Duration::from_secs(5), async {
+                with_timeout(Duration::from_millis(50), async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    Ok(())
+                })
+                .await
+            })
+            .await,
+            Err(BridgeSdkError::DeadlineExceeded(_))
+        ));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::result::Result;
+use ethers::{
+    providers::{Provider, SubscriptionStream, Ws},
+    types::{Address, Filter, Log, H256, U256},
+    utils::keccak256,
+};
+use futures::{channel::mpsc, SinkExt, Stream, StreamExt};
+
+/// Number of received events buffered for a slow consumer before the subscription stops reading from the node
+const EVENT_BUFFER_SIZE: usize = 1024;
+
+/// Subscribes over a WebSocket endpoint to the logs of `address` with the first topic `event_signature`, e.g.
+/// `Deposited(address,string,uint256,uint256)`. Logs are decoded with `decode`, the ones that fail to decode are
+/// skipped with a warning.
+///
+/// Logs arrive as soon as their block is imported, without waiting for confirmations. Logs removed by a reorg are
+/// skipped, so an event that was already received may be dropped from the chain. The stream ends when the WebSocket
+/// connection is closed
+pub async fn subscribe_events<T: Send + 'static>(
+    ws_endpoint: &str,
+    address: Address,
+    event_signature: &str,
+    decode: impl Fn(&Log) -> Result<T> + Send + 'static,
+) -> Result<impl Stream<Item = T>> {
+    let provider = Provider::<Ws>::connect(ws_endpoint).await?;
+    let filter = Filter::new()
+        .address(address)
+        .topic0(H256(keccak256(event_signature)));
+    let subscription_id: U256 = provider.request("eth_subscribe", ("logs", filter)).await?;
+
+    // The subscription stream borrows the provider, so both are owned by a task forwarding the decoded events
+    let (mut sender, receiver) = mpsc::channel(EVENT_BUFFER_SIZE);
+    let event_signature = event_signature.to_string();
+    tokio::spawn(async move {
+        let mut logs = match SubscriptionStream::<Ws, Log>::new(subscription_id, &provider) {
+            Ok(logs) => logs,
+            Err(err) => {
+                tracing::warn!(
+                    %event_signature,
+                    ?err,
+                    "Failed to subscribe to Ethereum logs"
+                );
+                return;
+            }
+        };
+
+        while let Some(log) = logs.next().await {
+            let Some(event) = decode_log(&log, &decode) else {
+                continue;
+            };
+            // The receiver was dropped
+            if sender.send(event).await.is_err() {
+                break;
+            }
+        }
+
+        tracing::debug!(%event_signature, "Ethereum log subscription ended");
+    });
+
+    Ok(receiver)
+}
+
+fn decode_log<T>(log: &Log, decode: impl Fn(&Log) -> Result<T>) -> Option<T> {
+    if log.removed == Some(true) {
+        tracing::debug!(tx_hash = ?log.transaction_hash, "Skipped a log removed by a reorg");
+        return None;
+    }
+
+    match decode(log) {
+        Ok(event) => Some(event),
+        Err(err) => {
+            tracing::warn!(tx_hash = ?log.transaction_hash, ?err, "Skipped an undecodable log");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::BridgeSdkError;
+
+    #[test]
+    fn test_decode_log_skips_removed_and_invalid_logs() {
+        let decode = |log: &Log| match log.data.is_empty() {
+            true => Err(BridgeSdkError::EthProofError("empty log".to_string())),
+            false => Ok(log.data.len()),
+        };
+        let log = Log {
+            data: vec![1, 2].into(),
+            ..Default::default()
+        };
+
+        assert_eq!(decode_log(&log, decode), Some(2));
+        assert_eq!(decode_log(&Log::default(), decode), None);
+        assert_eq!(
+            decode_log(
+                &Log {
+                    removed: Some(true),
+                    ..log
+                },
+                decode
+            ),
+            None
+        );
+    }
+}
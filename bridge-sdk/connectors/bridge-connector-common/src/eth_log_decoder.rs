@@ -0,0 +1,209 @@
+use crate::result::{BridgeSdkError, Result};
+use ethers::{
+    abi::{self, Event, RawLog, Token},
+    types::{Address, Log, H256, U256},
+};
+use std::{collections::HashMap, sync::OnceLock};
+
+static BRIDGE_EVENTS: OnceLock<EventRegistry> = OnceLock::new();
+
+/// Events emitted by the bridge contracts, as decoded by [`EventRegistry::bridge`]
+pub const BRIDGE_EVENT_ABIS: [&str; 3] = [
+    // EthCustodian
+    "event Deposited(address indexed sender, string recipient, uint256 amount, uint256 fee)",
+    "event Withdrawn(address indexed recipient, uint128 amount)",
+    // Fast bridge
+    "event TransferTokens(uint256 indexed nonce, address relayer, address token, address recipient, uint256 amount, string unlock_recipient, bytes32 indexed transfer_id)",
+];
+
+/// Log decoded with the ABI of its event, with the indexed and non-indexed parameters in declaration order
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedEvent {
+    pub name: String,
+    pub params: Vec<(String, Token)>,
+}
+
+impl DecodedEvent {
+    pub fn param(&self, name: &str) -> Option<&Token> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// Event ABIs by topic hash, so that logs are decoded by their first topic instead of the caller matching
+/// hardcoded topics
+#[derive(Debug, Clone, Default)]
+pub struct EventRegistry {
+    events: HashMap<H256, Event>,
+}
+
+impl EventRegistry {
+    /// Registry of [`BRIDGE_EVENT_ABIS`]
+    pub fn bridge() -> &'static Self {
+        BRIDGE_EVENTS.get_or_init(|| {
+            Self::default()
+                .with_events(&BRIDGE_EVENT_ABIS)
+                .expect("Bridge event ABIs are valid")
+        })
+    }
+
+    /// Registers events from their human-readable ABI, e.g.
+    /// `event Withdrawn(address indexed recipient, uint128 amount)`
+    pub fn with_events(mut self, abis: &[&str]) -> Result<Self> {
+        let abi = abi::parse_abi(abis)
+            .map_err(|err| BridgeSdkError::InvalidArgument(format!("Invalid event ABI: {err}")))?;
+
+        for event in abi.events() {
+            self.events.insert(event.signature(), event.clone());
+        }
+
+        Ok(self)
+    }
+
+    /// Topic hash of the registered event named `name`
+    pub fn topic(&self, name: &str) -> Option<H256> {
+        self.events
+            .iter()
+            .find(|(_, event)| event.name == name)
+            .map(|(topic, _)| *topic)
+    }
+
+    /// `None` if the log isn't an event of the registry. Fails if it is, but its topics or data don't match the ABI
+    pub fn decode(&self, log: &Log) -> Result<Option<DecodedEvent>> {
+        let Some(event) = log.topics.first().and_then(|topic| self.events.get(topic)) else {
+            return Ok(None);
+        };
+
+        let decoded = event
+            .parse_log(RawLog {
+                topics: log.topics.clone(),
+                data: log.data.to_vec(),
+            })
+            .map_err(|err| BridgeSdkError::InvalidEvent(format!("{} event: {err}", event.name)))?;
+
+        Ok(Some(DecodedEvent {
+            name: event.name.clone(),
+            params: decoded
+                .params
+                .into_iter()
+                .map(|param| (param.name, param.value))
+                .collect(),
+        }))
+    }
+}
+
+/// Typed event of a bridge contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BridgeEvent {
+    Deposited {
+        sender: Address,
+        /// Near account id, or `<eth connector account id>:<address>` for deposits to the Aurora EVM
+        recipient: String,
+        amount: U256,
+        fee: U256,
+    },
+    Withdrawn {
+        recipient: Address,
+        amount: u128,
+    },
+    TransferTokens {
+        nonce: U256,
+        relayer: Address,
+        token: Address,
+        recipient: Address,
+        amount: U256,
+        unlock_recipient: String,
+        transfer_id: H256,
+    },
+}
+
+impl BridgeEvent {
+    /// `None` if the log isn't one of [`BRIDGE_EVENT_ABIS`]
+    pub fn from_log(log: &Log) -> Result<Option<Self>> {
+        let Some(event) = EventRegistry::bridge().decode(log)? else {
+            return Ok(None);
+        };
+
+        let invalid = |param: &str| {
+            BridgeSdkError::InvalidEvent(format!("{} event: invalid {param}", event.name))
+        };
+        let address = |param: &str| match event.param(param) {
+            Some(Token::Address(address)) => Ok(*address),
+            _ => Err(invalid(param)),
+        };
+        let uint = |param: &str| match event.param(param) {
+            Some(Token::Uint(value)) => Ok(*value),
+            _ => Err(invalid(param)),
+        };
+        let string = |param: &str| match event.param(param) {
+            Some(Token::String(value)) => Ok(value.clone()),
+            _ => Err(invalid(param)),
+        };
+
+        let event = match event.name.as_str() {
+            "Deposited" => Self::Deposited {
+                sender: address("sender")?,
+                recipient: string("recipient")?,
+                amount: uint("amount")?,
+                fee: uint("fee")?,
+            },
+            "Withdrawn" => Self::Withdrawn {
+                recipient: address("recipient")?,
+                amount: uint("amount")?.as_u128(),
+            },
+            "TransferTokens" => Self::TransferTokens {
+                nonce: uint("nonce")?,
+                relayer: address("relayer")?,
+                token: address("token")?,
+                recipient: address("recipient")?,
+                amount: uint("amount")?,
+                unlock_recipient: string("unlock_recipient")?,
+                transfer_id: match event.param("transfer_id") {
+                    Some(Token::FixedBytes(bytes)) if bytes.len() == 32 => H256::from_slice(bytes),
+                    _ => return Err(invalid("transfer_id")),
+                },
+            },
+            _ => return Ok(None),
+        };
+
+        Ok(Some(event))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_bridge_event() {
+        let registry = EventRegistry::bridge();
+        let recipient = Address::repeat_byte(1);
+        let log = Log {
+            topics: vec![registry.topic("Withdrawn").unwrap(), H256::from(recipient)],
+            data: abi::encode(&[Token::Uint(U256::from(1000))]).into(),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            registry.topic("Withdrawn"),
+            Some(H256(ethers::utils::keccak256("Withdrawn(address,uint128)")))
+        );
+        assert_eq!(
+            BridgeEvent::from_log(&log).unwrap(),
+            Some(BridgeEvent::Withdrawn {
+                recipient,
+                amount: 1000
+            })
+        );
+
+        // Unknown events are skipped, known ones that don't match the ABI fail
+        assert_eq!(BridgeEvent::from_log(&Log::default()).unwrap(), None);
+        assert!(BridgeEvent::from_log(&Log {
+            data: Default::default(),
+            ..log
+        })
+        .is_err());
+    }
+}
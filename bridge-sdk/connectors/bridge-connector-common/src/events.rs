@@ -0,0 +1,61 @@
+use crate::bridge_connector::{TransactionId, TransferId};
+
+/// Progress of the operations of the connectors, e.g. to show it to a user or to alert on a lagging light client
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectorEvent {
+    /// Proof of the transfer generated on the source chain, about to be submitted to the destination chain
+    ProofGenerated { transfer: TransferId },
+    /// Transaction broadcast, not necessarily executed yet
+    TxSubmitted { tx: TransactionId },
+    /// Transaction executed successfully. Only emitted for the transactions the connectors wait for, e.g. Ethereum
+    /// transactions sent with confirmations
+    TxConfirmed { tx: TransactionId },
+    /// The light client hasn't synced the block of a transfer yet, emitted every time it's polled again
+    LightClientLagging {
+        light_client: String,
+        height: u64,
+        required_height: u64,
+    },
+}
+
+/// Observer of [`ConnectorEvent`]s. Called inline by the connectors, so it should hand the event off rather than
+/// block, e.g. by sending it to a channel
+pub trait ConnectorEvents: Send + Sync {
+    fn on_event(&self, event: &ConnectorEvent);
+}
+
+impl<F: Fn(&ConnectorEvent) + Send + Sync> ConnectorEvents for F {
+    fn on_event(&self, event: &ConnectorEvent) {
+        self(event)
+    }
+}
+
+/// Logs the event and sends it to the observer of the connector, if it has one
+pub fn emit(observer: Option<&dyn ConnectorEvents>, event: ConnectorEvent) {
+    tracing::debug!(event = format!("{:?}", event), "Connector event");
+
+    if let Some(observer) = observer {
+        observer.on_event(&event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::TxHash;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_emit() {
+        let events = Mutex::new(Vec::new());
+        let observer = |event: &ConnectorEvent| events.lock().unwrap().push(event.clone());
+
+        let submitted = ConnectorEvent::TxSubmitted {
+            tx: TransactionId::Eth(TxHash::repeat_byte(1)),
+        };
+        emit(Some(&observer), submitted.clone());
+        emit(None, submitted.clone());
+
+        assert_eq!(*events.lock().unwrap(), vec![submitted]);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::result::{BridgeSdkError, Result};
+use async_trait::async_trait;
+use ethers::providers::{Http, HttpClientError, JsonRpcClient, Provider};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    fmt::Debug,
+    str::FromStr,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// JSON-RPC error codes of providers rejecting requests over their rate limit
+const RATE_LIMIT_ERROR_CODES: [i64; 2] = [429, -32005];
+
+/// Ethereum provider failing over between the configured endpoints
+pub type EthProvider = Provider<FailoverHttp>;
+
+/// HTTP transport over a list of Ethereum RPC endpoints. A request failing with a transport error, a response that
+/// isn't JSON-RPC, e.g. an HTTP 429 or 502 page, or a rate limiting error is retried on the next endpoint, which then
+/// serves the following requests. A healthy endpoint keeps serving all requests, so the first one acts as the primary
+#[derive(Debug)]
+pub struct FailoverHttp {
+    transports: Vec<Http>,
+    current: AtomicUsize,
+}
+
+impl FailoverHttp {
+    /// Fails if `eth_endpoint` has no endpoint or an invalid one
+    pub fn new(eth_endpoint: &str) -> Result<Self> {
+        let transports = endpoints(eth_endpoint)
+            .map(|endpoint| {
+                Http::from_str(endpoint).map_err(|_| {
+                    BridgeSdkError::ConfigError("Invalid ethereum rpc endpoint url".to_string())
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if transports.is_empty() {
+            return Err(BridgeSdkError::ConfigError(
+                "Ethereum rpc endpoint is not set".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            transports,
+            current: AtomicUsize::new(0),
+        })
+    }
+}
+
+#[async_trait]
+impl JsonRpcClient for FailoverHttp {
+    type Error = HttpClientError;
+
+    async fn request<T, R>(&self, method: &str, params: T) -> std::result::Result<R, Self::Error>
+    where
+        T: Debug + Serialize + Send + Sync,
+        R: DeserializeOwned + Send,
+    {
+        // Serialized once, as the request may be sent to several endpoints
+        let params = serde_json::to_value(params).map_err(|err| HttpClientError::SerdeJson {
+            err,
+            text: String::new(),
+        })?;
+        let first = self.current.load(Ordering::Relaxed);
+
+        for attempt in 0..self.transports.len() {
+            let index = (first + attempt) % self.transports.len();
+            let result = self.transports[index].request(method, &params).await;
+
+            match result {
+                Err(err) if attempt + 1 < self.transports.len() && should_fail_over(&err) => {
+                    tracing::warn!(method, %err, "Ethereum RPC endpoint failed, failing over");
+                    // Concurrent requests failing on the same endpoint only move past it once
+                    let _ = self.current.compare_exchange(
+                        index,
+                        (index + 1) % self.transports.len(),
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    );
+                }
+                result => return result,
+            }
+        }
+
+        unreachable!("The last endpoint always returns its result")
+    }
+}
+
+/// Provider of the Ethereum endpoints given as a comma separated list, e.g.
+/// `https://eth.llamarpc.com,https://ethereum-rpc.publicnode.com`. A single endpoint works as a plain HTTP provider
+pub fn eth_provider(eth_endpoint: &str) -> Result<EthProvider> {
+    Ok(Provider::new(FailoverHttp::new(eth_endpoint)?))
+}
+
+/// Endpoints of a comma separated list
+pub fn endpoints(eth_endpoint: &str) -> impl Iterator<Item = &str> {
+    eth_endpoint
+        .split(',')
+        .map(str::trim)
+        .filter(|endpoint| !endpoint.is_empty())
+}
+
+fn should_fail_over(error: &HttpClientError) -> bool {
+    match error {
+        HttpClientError::JsonRpcError(error) => {
+            RATE_LIMIT_ERROR_CODES.contains(&error.code)
+                || error.message.to_lowercase().contains("rate limit")
+        }
+        // Transport errors and responses that aren't JSON-RPC
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::providers::JsonRpcError;
+
+    fn rpc_error(code: i64, message: &str) -> HttpClientError {
+        HttpClientError::JsonRpcError(JsonRpcError {
+            code,
+            message: message.to_string(),
+            data: None,
+        })
+    }
+
+    #[test]
+    fn test_fails_over_on_rate_limiting_only() {
+        assert!(should_fail_over(&rpc_error(429, "Too Many Requests")));
+        assert!(should_fail_over(&rpc_error(-32005, "limit exceeded")));
+        assert!(should_fail_over(&rpc_error(-32000, "Rate limit reached")));
+        assert!(!should_fail_over(&rpc_error(3, "execution reverted")));
+        assert!(!should_fail_over(&rpc_error(-32000, "nonce too low")));
+    }
+
+    #[test]
+    fn test_failover_endpoints() {
+        assert_eq!(
+            endpoints(" http://a:8545, ,http://b:8545,").collect::<Vec<_>>(),
+            ["http://a:8545", "http://b:8545"]
+        );
+        assert_eq!(
+            FailoverHttp::new("http://a:8545,http://b:8545")
+                .unwrap()
+                .transports
+                .len(),
+            2
+        );
+        assert!(FailoverHttp::new(" , ").is_err());
+        assert!(FailoverHttp::new("http://a:8545,not a url").is_err());
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{
+    result::{BridgeSdkError, Result},
+    signer::{EthSigner, NearSigner, SignerError},
+};
+use async_trait::async_trait;
+use ethers::{
+    signers::{HDPath, Ledger, Signer},
+    types::{transaction::eip2718::TypedTransaction, Address, Signature},
+};
+use near_crypto::{ED25519PublicKey, KeyType, PublicKey};
+use near_primitives::{borsh, transaction::Transaction, types::AccountId};
+use slip10::BIP32Path;
+use std::str::FromStr;
+
+/// Derivation path of the first account in the NEAR Ledger app
+pub const DEFAULT_NEAR_HD_PATH: &str = "44'/397'/0'/0'/1'";
+
+/// Ethereum signer backed by the Ethereum app of a Ledger device
+#[derive(Debug)]
+pub struct LedgerEthSigner(Ledger);
+
+impl LedgerEthSigner {
+    /// Connects to the device and loads the address of the Ledger Live account with the given index
+    pub async fn new(account_index: usize, chain_id: u64) -> Result<Self> {
+        let ledger = Ledger::new(HDPath::LedgerLive(account_index), chain_id)
+            .await
+            .map_err(|err| BridgeSdkError::SignerError(err.to_string()))?;
+
+        Ok(Self(ledger))
+    }
+}
+
+#[async_trait]
+impl EthSigner for LedgerEthSigner {
+    fn address(&self) -> Address {
+        self.0.address()
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.0.chain_id()
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &TypedTransaction,
+    ) -> std::result::Result<Signature, SignerError> {
+        self.0
+            .sign_transaction(tx)
+            .await
+            .map_err(|err| SignerError(err.to_string()))
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> std::result::Result<Signature, SignerError> {
+        self.0
+            .sign_message(message)
+            .await
+            .map_err(|err| SignerError(err.to_string()))
+    }
+}
+
+/// NEAR signer backed by the NEAR app of a Ledger device. Transactions have to be confirmed on the device
+pub struct LedgerNearSigner {
+    account_id: AccountId,
+    public_key: PublicKey,
+    hd_path: BIP32Path,
+}
+
+impl LedgerNearSigner {
+    /// Connects to the device and loads the public key at `hd_path`, which has to be a full access key of `account_id`
+    pub async fn new(account_id: AccountId, hd_path: &str) -> Result<Self> {
+        let hd_path = BIP32Path::from_str(hd_path).map_err(|_| {
+            BridgeSdkError::ConfigError(format!("Invalid derivation path: {hd_path}"))
+        })?;
+
+        let public_key = run_blocking({
+            let hd_path = hd_path.clone();
+            move || near_ledger::get_public_key(hd_path)
+        })
+        .await?;
+
+        Ok(Self {
+            account_id,
+            public_key: PublicKey::ED25519(ED25519PublicKey(public_key.to_bytes())),
+            hd_path,
+        })
+    }
+}
+
+#[async_trait]
+impl NearSigner for LedgerNearSigner {
+    fn account_id(&self) -> AccountId {
+        self.account_id.clone()
+    }
+
+    fn public_key(&self) -> PublicKey {
+        self.public_key.clone()
+    }
+
+    async fn sign_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> std::result::Result<near_crypto::Signature, SignerError> {
+        let unsigned_tx = borsh::to_vec(transaction).map_err(|err| SignerError(err.to_string()))?;
+        let hd_path = self.hd_path.clone();
+
+        tracing::info!("Confirm the transaction on the Ledger device");
+
+        let signature = run_blocking(move || near_ledger::sign_transaction(unsigned_tx, hd_path))
+            .await
+            .map_err(|err| SignerError(err.to_string()))?;
+
+        near_crypto::Signature::from_parts(KeyType::ED25519, &signature)
+            .map_err(|err| SignerError(err.to_string()))
+    }
+}
+
+/// The device is accessed over blocking HID calls, which shouldn't stall the async runtime
+async fn run_blocking<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> std::result::Result<T, near_ledger::NEARLedgerError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|err| BridgeSdkError::SignerError(err.to_string()))?
+        .map_err(|err| BridgeSdkError::SignerError(format!("Ledger error: {err:?}")))
+}
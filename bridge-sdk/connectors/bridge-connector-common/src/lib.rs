@@ -1 +1,32 @@
+pub mod anvil_fork;
+#[cfg(feature = "aws-kms")]
+pub mod aws_kms;
+pub mod bridge_connector;
+pub mod chunked_upload;
+pub mod client_cache;
+pub mod contract_call;
+pub mod contract_failure;
+pub mod deadline;
+pub mod eth_events;
+pub mod eth_log_decoder;
+pub mod events;
+pub mod failover;
+#[cfg(feature = "ledger")]
+pub mod ledger;
+pub mod method_names;
+pub mod near_args;
+pub mod nonce_journal;
+pub mod nonce_manager;
+pub mod operations;
+pub mod proof_store;
+pub mod recipient_validation;
+pub mod registry;
+#[cfg(feature = "remote-signer")]
+pub mod remote_signer;
 pub mod result;
+pub mod rpc_scheduler;
+pub mod screening;
+pub mod self_check;
+pub mod signer;
+pub mod token_amount;
+pub mod transfer_journal;
@@ -0,0 +1,126 @@
+use crate::result::BridgeSdkError;
+use ethers::contract::builders::ContractCall;
+use std::{collections::HashMap, str::FromStr};
+
+/// Contract methods renamed by a private or forked bridge deployment, e.g. `withdraw` deployed as `finish_withdraw`.
+/// Methods are keyed by the name the connector uses, separately for Near methods and Ethereum functions, which have
+/// overlapping names
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MethodNames {
+    near: HashMap<String, String>,
+    eth: HashMap<String, String>,
+}
+
+impl MethodNames {
+    pub fn with_near_method(mut self, name: &str, deployed_name: &str) -> Self {
+        self.near
+            .insert(name.to_string(), deployed_name.to_string());
+        self
+    }
+
+    pub fn with_eth_function(mut self, name: &str, deployed_name: &str) -> Self {
+        self.eth.insert(name.to_string(), deployed_name.to_string());
+        self
+    }
+
+    /// Name of the Near method as deployed
+    pub fn near(&self, name: &str) -> String {
+        self.near.get(name).map_or(name, String::as_str).to_string()
+    }
+
+    /// Calls the Ethereum function as deployed. The arguments are kept, only the function selector is replaced
+    pub fn eth<M, D>(&self, mut call: ContractCall<M, D>) -> ContractCall<M, D> {
+        let Some(deployed_name) = self.eth.get(&call.function.name) else {
+            return call;
+        };
+
+        let mut function = call.function.clone();
+        function.name = deployed_name.clone();
+
+        if let Some(data) = call.tx.data() {
+            let mut data = data.to_vec();
+            data[..4].copy_from_slice(&function.short_signature());
+            call.tx.set_data(data.into());
+        }
+        call.function = function;
+
+        call
+    }
+}
+
+/// Parses a comma separated list of `near:<name>=<deployed name>` and `eth:<name>=<deployed name>` entries, e.g.
+/// `near:withdraw=finish_withdraw,eth:deposit=finishDeposit`
+impl FromStr for MethodNames {
+    type Err = BridgeSdkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |entry: &str| {
+            BridgeSdkError::InvalidArgument(format!(
+                "Invalid method name override `{entry}`, expected `near:<name>=<deployed name>` or `eth:<name>=<deployed name>`"
+            ))
+        };
+
+        s.split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .try_fold(Self::default(), |names, entry| {
+                let (chain, names_pair) = entry.split_once(':').ok_or_else(|| invalid(entry))?;
+                let (name, deployed_name) = names_pair
+                    .split_once('=')
+                    .filter(|(name, deployed_name)| !name.is_empty() && !deployed_name.is_empty())
+                    .ok_or_else(|| invalid(entry))?;
+
+                match chain {
+                    "near" => Ok(names.with_near_method(name, deployed_name)),
+                    "eth" => Ok(names.with_eth_function(name, deployed_name)),
+                    _ => Err(invalid(entry)),
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::{
+        abi::{parse_abi, Token},
+        contract::Contract,
+        providers::{Http, Provider},
+        types::{Address, U256},
+    };
+    use std::sync::Arc;
+
+    #[test]
+    fn parses_overrides() {
+        let names =
+            MethodNames::from_str("near:withdraw=finish_withdraw, eth:withdraw=burn,").unwrap();
+
+        assert_eq!(names.near("withdraw"), "finish_withdraw");
+        assert_eq!(names.near("deposit"), "deposit");
+        assert_eq!(names.eth.get("withdraw").unwrap(), "burn");
+
+        assert!(MethodNames::from_str("withdraw=finish_withdraw").is_err());
+        assert!(MethodNames::from_str("sol:withdraw=burn").is_err());
+        assert!(MethodNames::from_str("near:withdraw=").is_err());
+    }
+
+    #[test]
+    fn replaces_the_function_selector() {
+        let abi = parse_abi(&["function withdraw(uint256 amount)"]).unwrap();
+        let provider = Arc::new(Provider::<Http>::try_from("http://localhost:8545").unwrap());
+        let contract = Contract::new(Address::zero(), abi, provider);
+        let call = contract
+            .method::<_, ()>("withdraw", vec![Token::Uint(U256::from(7))])
+            .unwrap();
+        let args = call.tx.data().unwrap()[4..].to_vec();
+
+        let call = MethodNames::default()
+            .with_eth_function("withdraw", "burn")
+            .eth(call);
+
+        let data = call.tx.data().unwrap();
+        assert_eq!(data[..4], ethers::utils::id("burn(uint256)"));
+        assert_eq!(data[4..], args);
+        assert_eq!(call.function.name, "burn");
+    }
+}
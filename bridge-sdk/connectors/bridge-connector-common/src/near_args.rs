@@ -0,0 +1,143 @@
+use serde::{Serialize, Serializer};
+
+/// Arguments of `ft_transfer_call` of a NEP-141 token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FtTransferCallArgs {
+    pub receiver_id: String,
+    #[serde(serialize_with = "u128_string")]
+    pub amount: u128,
+    pub msg: String,
+}
+
+/// Arguments of `nft_transfer_call` of a NEP-171 token
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct NftTransferCallArgs {
+    pub receiver_id: String,
+    pub token_id: String,
+    pub msg: String,
+}
+
+/// Arguments of `storage_deposit` of a NEP-145 contract. The signer is registered if `account_id` is unset
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct StorageDepositArgs {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registration_only: Option<bool>,
+}
+
+/// Arguments of calls taking only an account, e.g. `storage_balance_of`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AccountIdArgs {
+    pub account_id: String,
+}
+
+/// Arguments of calls taking only a token, e.g. `log_metadata` of the lockers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TokenIdArgs {
+    pub token_id: String,
+}
+
+/// Arguments of calls taking none, encoded as `{}`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EmptyArgs {}
+
+/// Arguments of calls taking only an amount, e.g. `near_withdraw` of wNEAR
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AmountArgs {
+    #[serde(serialize_with = "u128_string")]
+    pub amount: u128,
+}
+
+/// JSON encoded arguments of a Near function call. The encoding is the same byte for byte on every platform and run:
+/// fields follow their declaration order, strings are escaped by `serde_json` rather than formatted in place, and
+/// numbers don't depend on the locale. Arguments are always built through this encoder, so that the transactions of
+/// the SDK can be reproduced when audited
+pub fn to_bytes(args: &impl Serialize) -> Vec<u8> {
+    to_string(args).into_bytes()
+}
+
+/// JSON encoded arguments, e.g. for the `msg` of `ft_transfer_call`
+pub fn to_string(args: &impl Serialize) -> String {
+    serde_json::to_string(args).expect("Near call arguments are always serializable")
+}
+
+/// Serializes a `u128` as a decimal string, as Near contracts expect for `U128` values
+pub fn u128_string<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Characters that need escaping or are often mishandled, and plain ones
+    const FUZZ_CHARS: &[char] = &[
+        '"', '\\', '/', '{', '}', ':', ',', '\n', '\r', '\t', '\0', '\u{1f}', '\u{7f}', '\u{2028}',
+        'é', '€', '🌈', 'a', 'Z', '0', ' ',
+    ];
+
+    /// Xorshift generator, so that the fuzzed inputs are the same on every run
+    fn next_random(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    fn random_string(state: &mut u64) -> String {
+        let len = next_random(state) % 16;
+        (0..len)
+            .map(|_| FUZZ_CHARS[(next_random(state) % FUZZ_CHARS.len() as u64) as usize])
+            .collect()
+    }
+
+    #[test]
+    fn test_fuzzed_near_args_round_trip() {
+        let mut state = 0x2545_f491_4f6c_dd1d;
+
+        for _ in 0..10_000 {
+            let args = FtTransferCallArgs {
+                receiver_id: random_string(&mut state),
+                amount: (u128::from(next_random(&mut state)) << 64)
+                    | u128::from(next_random(&mut state)),
+                msg: random_string(&mut state),
+            };
+            let encoded = to_bytes(&args);
+            let decoded: serde_json::Value = serde_json::from_slice(&encoded).unwrap();
+
+            assert_eq!(encoded, to_bytes(&args));
+            // Control characters are escaped, so the arguments are a single line of printable JSON
+            assert!(encoded.iter().all(|byte| *byte >= 0x20));
+            assert_eq!(decoded["receiver_id"], args.receiver_id);
+            assert_eq!(decoded["amount"], args.amount.to_string());
+            assert_eq!(decoded["msg"], args.msg);
+        }
+    }
+
+    #[test]
+    fn test_near_args_are_escaped() {
+        let args = FtTransferCallArgs {
+            receiver_id: "locker.near".to_string(),
+            amount: u128::MAX,
+            msg: r#"{"recipient":"eth:0x1"}"#.to_string(),
+        };
+
+        assert_eq!(
+            String::from_utf8(to_bytes(&args)).unwrap(),
+            r#"{"receiver_id":"locker.near","amount":"340282366920938463463374607431768211455","msg":"{\"recipient\":\"eth:0x1\"}"}"#
+        );
+    }
+
+    #[test]
+    fn test_storage_deposit_args_omit_unset_fields() {
+        assert_eq!(to_bytes(&StorageDepositArgs::default()), b"{}");
+        assert_eq!(
+            to_bytes(&StorageDepositArgs {
+                account_id: Some("alice.near".to_string()),
+                registration_only: Some(true),
+            }),
+            br#"{"account_id":"alice.near","registration_only":true}"#
+        );
+    }
+}
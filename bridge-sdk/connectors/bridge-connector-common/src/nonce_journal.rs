@@ -0,0 +1,140 @@
+use crate::result::{BridgeSdkError, Result};
+use ethers::types::{Address, Bytes, TxHash, U256};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::Path,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// Sends from different signers aren't serialized, their updates of the journal are
+static JOURNAL_UPDATE: Mutex<()> = Mutex::new(());
+
+/// Ethereum transaction signed in strict mode, recorded before it's broadcast and removed once it's mined
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub chain_id: u64,
+    pub sender: Address,
+    pub nonce: U256,
+    pub tx_hash: TxHash,
+    /// Signed transaction, broadcast again as is if the process stopped before it was mined
+    pub raw_tx: Bytes,
+    /// Unix timestamp in seconds
+    pub signed_at: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Journal {
+    entries: Vec<JournalEntry>,
+}
+
+/// Unresolved transactions of the journal at `path`, e.g. to inspect them after a crash
+pub fn load(path: &Path) -> Result<Vec<JournalEntry>> {
+    Ok(read(path)?.entries)
+}
+
+/// Unresolved transactions of `sender`, by nonce
+pub(crate) fn pending(path: &Path, chain_id: u64, sender: Address) -> Result<Vec<JournalEntry>> {
+    let mut entries: Vec<_> = load(path)?
+        .into_iter()
+        .filter(|entry| entry.chain_id == chain_id && entry.sender == sender)
+        .collect();
+    entries.sort_by_key(|entry| entry.nonce);
+
+    Ok(entries)
+}
+
+pub(crate) fn record(path: &Path, entry: JournalEntry) -> Result<()> {
+    let _update = JOURNAL_UPDATE.lock().unwrap();
+    let mut journal = read(path)?;
+    journal.entries.push(entry);
+
+    write(path, &journal)
+}
+
+pub(crate) fn remove(path: &Path, tx_hash: TxHash) -> Result<()> {
+    let _update = JOURNAL_UPDATE.lock().unwrap();
+    let mut journal = read(path)?;
+    journal.entries.retain(|entry| entry.tx_hash != tx_hash);
+
+    write(path, &journal)
+}
+
+pub(crate) fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+fn read(path: &Path) -> Result<Journal> {
+    if !path.exists() {
+        return Ok(Journal::default());
+    }
+
+    let data = fs::read_to_string(path).map_err(|err| {
+        BridgeSdkError::ConfigError(format!(
+            "Could not read nonce journal {}: {err}",
+            path.display()
+        ))
+    })?;
+
+    serde_json::from_str(&data).map_err(|err| {
+        BridgeSdkError::ConfigError(format!(
+            "Could not parse nonce journal {}: {err}",
+            path.display()
+        ))
+    })
+}
+
+fn write(path: &Path, journal: &Journal) -> Result<()> {
+    let data = serde_json::to_string_pretty(journal).expect("Nonce journal always serializes");
+
+    // Writing to a temporary file first keeps the journal intact if the process is killed mid-write
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, data)
+        .and_then(|_| fs::rename(&tmp_path, path))
+        .map_err(|err| {
+            BridgeSdkError::ConfigError(format!(
+                "Could not write nonce journal {}: {err}",
+                path.display()
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(sender: Address, nonce: u64) -> JournalEntry {
+        JournalEntry {
+            chain_id: 1,
+            sender,
+            nonce: nonce.into(),
+            tx_hash: TxHash::from_low_u64_be(nonce),
+            raw_tx: Bytes::from(vec![0x02, nonce as u8]),
+            signed_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_journal_round_trip() {
+        let path = std::env::temp_dir().join(format!("nonce-journal-{}.json", std::process::id()));
+        let sender = Address::repeat_byte(1);
+
+        record(&path, entry(sender, 8)).unwrap();
+        record(&path, entry(sender, 7)).unwrap();
+        record(&path, entry(Address::repeat_byte(2), 7)).unwrap();
+
+        assert_eq!(
+            pending(&path, 1, sender).unwrap(),
+            [entry(sender, 7), entry(sender, 8)]
+        );
+
+        remove(&path, TxHash::from_low_u64_be(7)).unwrap();
+        assert_eq!(pending(&path, 1, sender).unwrap(), [entry(sender, 8)]);
+        assert!(pending(&path, 5, sender).unwrap().is_empty());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
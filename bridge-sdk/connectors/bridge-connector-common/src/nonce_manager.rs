@@ -0,0 +1,108 @@
+use ethers::types::{Address, U256};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, OnceLock},
+};
+use tokio::sync::OwnedMutexGuard;
+
+static GLOBAL_NONCE_MANAGER: OnceLock<NonceManager> = OnceLock::new();
+
+/// Nonces of the Ethereum transactions sent by the connectors of a process. Sends from the same signer are serialized
+/// until they are broadcast, so that concurrent ones, e.g. parallel finalizations, get consecutive nonces instead of
+/// the same pending one
+#[derive(Default)]
+pub struct NonceManager {
+    accounts: Mutex<HashMap<(u64, Address), Arc<tokio::sync::Mutex<Option<U256>>>>>,
+}
+
+/// Nonce reserved for a transaction. The next nonce of the account is only advanced by [`NonceReservation::sent`],
+/// otherwise it is taken from the node again by the next reservation
+pub struct NonceReservation {
+    next_nonce: OwnedMutexGuard<Option<U256>>,
+    nonce: U256,
+    sent: bool,
+}
+
+impl NonceManager {
+    /// Manager used by `contract_call::send_eth_call`, shared by all the connectors
+    pub fn global() -> &'static NonceManager {
+        GLOBAL_NONCE_MANAGER.get_or_init(NonceManager::default)
+    }
+
+    /// Reserves the next nonce of `sender`, waiting for the pending reservation of the account if any. `pending_nonce`
+    /// is the transaction count of the account including its pending transactions, which takes precedence when
+    /// transactions were sent by another process
+    pub async fn reserve(
+        &self,
+        chain_id: u64,
+        sender: Address,
+        pending_nonce: U256,
+    ) -> NonceReservation {
+        let account = self
+            .accounts
+            .lock()
+            .expect("Nonce manager lock is poisoned")
+            .entry((chain_id, sender))
+            .or_default()
+            .clone();
+        let next_nonce = account.lock_owned().await;
+        let nonce = next_nonce.map_or(pending_nonce, |next_nonce| next_nonce.max(pending_nonce));
+
+        NonceReservation {
+            next_nonce,
+            nonce,
+            sent: false,
+        }
+    }
+
+    /// Forgets the next nonce of `sender`, e.g. after a transaction sent with an explicit nonce
+    pub async fn reset(&self, chain_id: u64, sender: Address) {
+        self.reserve(chain_id, sender, U256::zero()).await;
+    }
+}
+
+impl NonceReservation {
+    pub fn nonce(&self) -> U256 {
+        self.nonce
+    }
+
+    /// Advances the next nonce of the account once the transaction is broadcast
+    pub fn sent(mut self) {
+        *self.next_nonce = Some(self.nonce + 1);
+        self.sent = true;
+    }
+}
+
+impl Drop for NonceReservation {
+    fn drop(&mut self) {
+        if !self.sent {
+            *self.next_nonce = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reservations_get_consecutive_nonces() {
+        let manager = Arc::new(NonceManager::default());
+        let sender = Address::repeat_byte(1);
+
+        let reservation = manager.reserve(1, sender, 5.into()).await;
+        assert_eq!(reservation.nonce(), 5.into());
+
+        let concurrent = tokio::spawn({
+            let manager = manager.clone();
+            async move { manager.reserve(1, sender, 5.into()).await.nonce() }
+        });
+        tokio::task::yield_now().await;
+        reservation.sent();
+        assert_eq!(concurrent.await.unwrap(), 6.into());
+
+        // The concurrent reservation wasn't sent, so the nonce is taken from the node again
+        assert_eq!(manager.reserve(1, sender, 5.into()).await.nonce(), 5.into());
+        assert_eq!(manager.reserve(1, sender, 9.into()).await.nonce(), 9.into());
+    }
+}
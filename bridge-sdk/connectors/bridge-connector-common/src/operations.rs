@@ -0,0 +1,59 @@
+use crate::result::{BridgeSdkError, Result};
+use std::{collections::HashSet, convert::Infallible, str::FromStr};
+
+/// Connector operations disabled by configuration, e.g. withdrawals during an incident. Operations are named after
+/// the connector methods, e.g. `withdraw` or `finalize_deposit`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DisabledOperations(HashSet<String>);
+
+impl DisabledOperations {
+    pub fn new<I, S>(operations: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self(operations.into_iter().map(Into::into).collect())
+    }
+
+    pub fn is_disabled(&self, operation: &str) -> bool {
+        self.0.contains(operation)
+    }
+
+    /// Fails with [`BridgeSdkError::OperationDisabled`] if `operation` is disabled
+    pub fn check(&self, operation: &str) -> Result<()> {
+        match self.is_disabled(operation) {
+            true => Err(BridgeSdkError::OperationDisabled(operation.to_string())),
+            false => Ok(()),
+        }
+    }
+}
+
+/// Parses a comma separated list of operations
+impl FromStr for DisabledOperations {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self::new(
+            s.split(',')
+                .map(str::trim)
+                .filter(|operation| !operation.is_empty()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_disabled_operations() {
+        let disabled = DisabledOperations::from_str("withdraw, finalize_withdraw,").unwrap();
+
+        assert!(matches!(
+            disabled.check("withdraw"),
+            Err(BridgeSdkError::OperationDisabled(operation)) if operation == "withdraw"
+        ));
+        assert!(disabled.check("finalize_withdraw").is_err());
+        assert!(disabled.check("deposit").is_ok());
+    }
+}
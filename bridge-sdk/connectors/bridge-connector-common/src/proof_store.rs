@@ -0,0 +1,155 @@
+use crate::{
+    bridge_connector::TransferId,
+    nonce_journal,
+    result::{BridgeSdkError, Result},
+};
+use base64::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Proof generated for a finalization that failed to be submitted, e.g. because of a gas spike, kept so that the
+/// retry doesn't generate it again
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredProof {
+    /// Proof as passed to the destination chain contract
+    pub proof: Vec<u8>,
+    /// Block of the source chain the proof was generated against. The proof can be reused while the light client of
+    /// the destination chain still knows this block
+    pub proof_block_height: u64,
+    /// Unix timestamp in seconds
+    pub stored_at: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredProofFile {
+    proof: String,
+    proof_block_height: u64,
+    stored_at: u64,
+}
+
+/// Directory of the proofs of failed finalizations, one file per transfer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofStore {
+    dir: PathBuf,
+}
+
+impl ProofStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn save(
+        &self,
+        transfer_id: &TransferId,
+        proof: Vec<u8>,
+        proof_block_height: u64,
+    ) -> Result<()> {
+        let file = StoredProofFile {
+            proof: BASE64_STANDARD.encode(proof),
+            proof_block_height,
+            stored_at: nonce_journal::unix_timestamp(),
+        };
+        let data = serde_json::to_string_pretty(&file).expect("Stored proof always serializes");
+
+        let path = self.path(transfer_id);
+        // Writing to a temporary file first keeps a previously stored proof intact if the process is killed mid-write
+        let tmp_path = path.with_extension("tmp");
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(&tmp_path, data))
+            .and_then(|_| fs::rename(&tmp_path, &path))
+            .map_err(|err| {
+                BridgeSdkError::ConfigError(format!(
+                    "Could not store proof {}: {err}",
+                    path.display()
+                ))
+            })
+    }
+
+    pub fn load(&self, transfer_id: &TransferId) -> Result<Option<StoredProof>> {
+        let path = self.path(transfer_id);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let invalid = |err: String| {
+            BridgeSdkError::ConfigError(format!(
+                "Could not read stored proof {}: {err}",
+                path.display()
+            ))
+        };
+        let data = fs::read_to_string(&path).map_err(|err| invalid(err.to_string()))?;
+        let file: StoredProofFile =
+            serde_json::from_str(&data).map_err(|err| invalid(err.to_string()))?;
+
+        Ok(Some(StoredProof {
+            proof: BASE64_STANDARD
+                .decode(file.proof)
+                .map_err(|err| invalid(err.to_string()))?,
+            proof_block_height: file.proof_block_height,
+            stored_at: file.stored_at,
+        }))
+    }
+
+    pub fn remove(&self, transfer_id: &TransferId) -> Result<()> {
+        let path = self.path(transfer_id);
+        if !path.exists() {
+            return Ok(());
+        }
+
+        fs::remove_file(&path).map_err(|err| {
+            BridgeSdkError::ConfigError(format!(
+                "Could not remove stored proof {}: {err}",
+                path.display()
+            ))
+        })
+    }
+
+    fn path(&self, transfer_id: &TransferId) -> PathBuf {
+        let name = match transfer_id {
+            TransferId::Near { receipt_id } => format!("near-{receipt_id}"),
+            TransferId::Eth { tx_hash, log_index } => format!("eth-{tx_hash:?}-{log_index}"),
+        };
+
+        self.dir.join(format!("{name}.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::TxHash;
+
+    #[test]
+    fn test_proof_store_round_trip() {
+        let store = ProofStore::new(
+            std::env::temp_dir().join(format!("proof-store-{}", std::process::id())),
+        );
+        let transfer_id = TransferId::Eth {
+            tx_hash: TxHash::repeat_byte(1),
+            log_index: 3,
+        };
+        let other_id = TransferId::Eth {
+            tx_hash: TxHash::repeat_byte(1),
+            log_index: 4,
+        };
+
+        store.save(&transfer_id, vec![1, 2, 3], 42).unwrap();
+
+        let stored = store.load(&transfer_id).unwrap().unwrap();
+        assert_eq!(stored.proof, [1, 2, 3]);
+        assert_eq!(stored.proof_block_height, 42);
+        assert_eq!(store.load(&other_id).unwrap(), None);
+
+        store.remove(&transfer_id).unwrap();
+        assert_eq!(store.load(&transfer_id).unwrap(), None);
+
+        fs::remove_dir_all(store.dir()).unwrap();
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{
+    failover,
+    result::{BridgeSdkError, Result},
+};
+use ethers::{providers::Middleware, types::Address};
+use std::{convert::Infallible, str::FromStr};
+
+/// Validation of transfer recipients that likely can't use the bridged tokens. Exchange deposit addresses often need
+/// a tag or memo, which a bridge transfer can't carry, and an Ethereum contract can only move the ERC-20 tokens it
+/// receives if it implements a way to do so. Suspicious recipients are logged with a warning, or rejected with
+/// [`BridgeSdkError::InvalidArgument`] in strict mode. Disabled by default
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecipientValidation {
+    /// Patterns of known exchange deposit addresses or accounts, e.g. `*.binance.near`. A `*` matches any characters
+    exchange_patterns: Vec<String>,
+    /// Also flags Ethereum recipients with deployed code
+    check_contracts: bool,
+    /// Rejects flagged recipients instead of warning
+    strict: bool,
+}
+
+impl RecipientValidation {
+    pub fn new<I, S>(exchange_patterns: I, check_contracts: bool, strict: bool) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            exchange_patterns: exchange_patterns
+                .into_iter()
+                .map(|pattern| pattern.into().to_lowercase())
+                .collect(),
+            check_contracts,
+            strict,
+        }
+    }
+
+    /// Flags a Near account id or an Ethereum address matching a known exchange pattern
+    pub fn check(&self, recipient: &str) -> Result<()> {
+        let recipient = recipient.to_lowercase();
+        match self
+            .exchange_patterns
+            .iter()
+            .find(|pattern| matches_pattern(pattern, &recipient))
+        {
+            Some(pattern) => self.flag(&format!(
+                "Recipient {recipient} matches the exchange pattern `{pattern}`. Exchanges requiring a deposit tag or memo can't credit bridged tokens"
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Same as [`Self::check`] for an Ethereum recipient, also flagging contracts if enabled, which needs the Ethereum
+    /// endpoint
+    pub async fn check_eth(&self, eth_endpoint: Option<&str>, recipient: Address) -> Result<()> {
+        self.check(&format!("{recipient:?}"))?;
+
+        if !self.check_contracts {
+            return Ok(());
+        }
+
+        let eth_endpoint = eth_endpoint.ok_or(BridgeSdkError::ConfigError(
+            "Ethereum rpc endpoint is required to check for contract recipients".to_string(),
+        ))?;
+        let eth_provider = failover::eth_provider(eth_endpoint)?;
+        let code = eth_provider.get_code(recipient, None).await?;
+
+        match code.is_empty() {
+            true => Ok(()),
+            false => self.flag(&format!(
+                "Recipient {recipient:?} is a contract, which may be unable to transfer the bridged ERC-20 tokens"
+            )),
+        }
+    }
+
+    fn flag(&self, reason: &str) -> Result<()> {
+        if self.strict {
+            return Err(BridgeSdkError::InvalidArgument(reason.to_string()));
+        }
+
+        tracing::warn!("{reason}");
+        Ok(())
+    }
+}
+
+/// Parses a comma separated list of exchange patterns, with the `contracts` entry enabling the contract check and the
+/// `strict` entry rejecting flagged recipients, e.g. `*.binance.near,contracts,strict`
+impl FromStr for RecipientValidation {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let entries = s
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty());
+        let (flags, patterns): (Vec<_>, Vec<_>) =
+            entries.partition(|entry| matches!(*entry, "contracts" | "strict"));
+
+        Ok(Self::new(
+            patterns,
+            flags.contains(&"contracts"),
+            flags.contains(&"strict"),
+        ))
+    }
+}
+
+fn matches_pattern(pattern: &str, recipient: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = recipient.strip_prefix(first) else {
+        return false;
+    };
+
+    let parts: Vec<_> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipient_validation_flags_exchange_patterns() {
+        let warning = RecipientValidation::from_str("*.binance.near, contracts").unwrap();
+        assert!(warning.check("deposit.binance.near").is_ok());
+
+        let strict = RecipientValidation::from_str("*.binance.near,0xABC*,strict").unwrap();
+        assert!(matches!(
+            strict.check("Deposit.Binance.near"),
+            Err(BridgeSdkError::InvalidArgument(_))
+        ));
+        assert!(strict.check("0xabcdef").is_err());
+        assert!(strict.check("alice.near").is_ok());
+        assert!(strict.check("binance.near").is_ok());
+
+        assert!(matches_pattern("a*b*c", "aXbYc"));
+        assert!(!matches_pattern("a*b*c", "aXcYb"));
+        assert!(matches_pattern("exact.near", "exact.near"));
+        assert!(!matches_pattern("exact.near", "exact.near.x"));
+    }
+}
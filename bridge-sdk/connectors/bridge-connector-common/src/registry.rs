@@ -0,0 +1,128 @@
+use crate::{
+    near_args::{self, EmptyArgs},
+    result::{BridgeSdkError, Result},
+};
+use near_jsonrpc_primitives::types::query::QueryResponseKind;
+use near_primitives::types::{AccountId, BlockReference, Finality};
+use std::{collections::HashMap, fmt};
+
+/// View method of a registry account returning the current bridge contracts, as a JSON object of contract names, e.g.
+/// `token_locker_id` or `bridge_token_factory_address`, to account ids and addresses
+pub const REGISTRY_VIEW_METHOD: &str = "get_contracts";
+
+/// Configured contract that differs from the one published by the registry, e.g. a default rotated by a bridge upgrade
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigDrift {
+    pub name: String,
+    pub configured: String,
+    pub registry: String,
+}
+
+impl fmt::Display for ConfigDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is {}, the registry has {}",
+            self.name, self.configured, self.registry
+        )
+    }
+}
+
+/// Contracts published by `registry_account_id`
+pub async fn registry_contracts(
+    near_endpoint: &str,
+    registry_account_id: &str,
+) -> Result<HashMap<String, String>> {
+    let account_id: AccountId = registry_account_id.parse().map_err(|_| {
+        BridgeSdkError::ConfigError(format!(
+            "Invalid registry account id: {registry_account_id}"
+        ))
+    })?;
+    let response = near_rpc_client::view_raw(
+        near_endpoint,
+        BlockReference::Finality(Finality::Final),
+        account_id,
+        REGISTRY_VIEW_METHOD.to_string(),
+        near_args::to_bytes(&EmptyArgs {}),
+    )
+    .await?;
+
+    match response.kind {
+        QueryResponseKind::CallResult(result) => serde_json::from_slice(&result.result).ok(),
+        _ => None,
+    }
+    .ok_or(BridgeSdkError::NearOutcomeError(format!(
+        "Invalid {REGISTRY_VIEW_METHOD} response of {registry_account_id}"
+    )))
+}
+
+/// Configured contracts, by name, that differ from the ones published by `registry_account_id`
+pub async fn check_drift<'a>(
+    near_endpoint: &str,
+    registry_account_id: &str,
+    configured: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Result<Vec<ConfigDrift>> {
+    let registry = registry_contracts(near_endpoint, registry_account_id).await?;
+
+    Ok(find_drift(configured, &registry))
+}
+
+/// Contracts the registry doesn't publish aren't compared. Ethereum addresses are compared regardless of their
+/// checksum casing
+pub fn find_drift<'a>(
+    configured: impl IntoIterator<Item = (&'a str, &'a str)>,
+    registry: &HashMap<String, String>,
+) -> Vec<ConfigDrift> {
+    configured
+        .into_iter()
+        .filter_map(|(name, configured)| {
+            let published = registry.get(name)?;
+
+            (!published.eq_ignore_ascii_case(configured)).then(|| ConfigDrift {
+                name: name.to_string(),
+                configured: configured.to_string(),
+                registry: published.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_drift() {
+        let registry = HashMap::from([
+            (
+                "token_locker_id".to_string(),
+                "ft-locker-v2.bridge.near".to_string(),
+            ),
+            (
+                "bridge_token_factory_address".to_string(),
+                "0x252e87862a3a720287e7fd527ce6e8d0738427a2".to_string(),
+            ),
+        ]);
+
+        let drift = find_drift(
+            [
+                ("token_locker_id", "ft-locker.bridge.near"),
+                (
+                    "bridge_token_factory_address",
+                    "0x252e87862A3A720287E7fd527cE6e8d0738427A2",
+                ),
+                ("wrapped_near_id", "wrap.near"),
+            ],
+            &registry,
+        );
+
+        assert_eq!(
+            drift,
+            [ConfigDrift {
+                name: "token_locker_id".to_string(),
+                configured: "ft-locker.bridge.near".to_string(),
+                registry: "ft-locker-v2.bridge.near".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use crate::signer::{NearSigner, SignerError};
+use async_trait::async_trait;
+use base64::prelude::*;
+use near_crypto::{PublicKey, Signature};
+use near_primitives::{borsh, transaction::Transaction, types::AccountId};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// Body of the request sent to the signing endpoint
+#[derive(Debug, Serialize)]
+pub struct SignRequest {
+    pub account_id: AccountId,
+    pub public_key: PublicKey,
+    /// Base64 encoded borsh serialized transaction
+    pub transaction: String,
+}
+
+/// Body of the response expected from the signing endpoint
+#[derive(Debug, Deserialize)]
+pub struct SignResponse {
+    /// Signature in the `ed25519:<base58>` format
+    pub signature: String,
+}
+
+/// NEAR signer delegating to an external signing service, e.g. one backed by a KMS or an HSM.
+/// The transaction is POSTed as a [`SignRequest`] and the service replies with a [`SignResponse`]
+pub struct RemoteNearSigner {
+    endpoint: String,
+    auth_token: Option<String>,
+    account_id: AccountId,
+    public_key: PublicKey,
+    client: reqwest::Client,
+}
+
+impl RemoteNearSigner {
+    pub fn new(endpoint: String, account_id: AccountId, public_key: PublicKey) -> Self {
+        Self {
+            endpoint,
+            auth_token: None,
+            account_id,
+            public_key,
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Sends the token as a bearer token in the `Authorization` header
+    pub fn with_auth_token(mut self, auth_token: String) -> Self {
+        self.auth_token = Some(auth_token);
+        self
+    }
+}
+
+#[async_trait]
+impl NearSigner for RemoteNearSigner {
+    fn account_id(&self) -> AccountId {
+        self.account_id.clone()
+    }
+
+    fn public_key(&self) -> PublicKey {
+        self.public_key.clone()
+    }
+
+    async fn sign_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> std::result::Result<Signature, SignerError> {
+        let unsigned_tx = borsh::to_vec(transaction).map_err(|err| SignerError(err.to_string()))?;
+
+        let mut request = self.client.post(&self.endpoint).json(&SignRequest {
+            account_id: self.account_id.clone(),
+            public_key: self.public_key.clone(),
+            transaction: BASE64_STANDARD.encode(unsigned_tx),
+        });
+        if let Some(auth_token) = &self.auth_token {
+            request = request.bearer_auth(auth_token);
+        }
+
+        let response: SignResponse = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| SignerError(format!("Signing request failed: {err}")))?
+            .json()
+            .await
+            .map_err(|err| SignerError(format!("Invalid signing response: {err}")))?;
+
+        let signature = Signature::from_str(&response.signature)
+            .map_err(|err| SignerError(format!("Invalid signature: {err}")))?;
+
+        let (hash, _) = transaction.get_hash_and_size();
+        if !signature.verify(hash.as_ref(), &self.public_key) {
+            return Err(SignerError(
+                "Signature doesn't match the signer public key".to_string(),
+            ));
+        }
+
+        Ok(signature)
+    }
+}
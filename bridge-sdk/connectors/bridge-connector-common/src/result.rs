@@ -1,9 +1,13 @@
+use crate::{
+    contract_call::decode_revert_reason, contract_failure::ContractFailure, failover::EthProvider,
+    signer::EthSignerClient,
+};
+use eth_light_client_on_near::EthLightClientOnNearError;
 use eth_proof::{EthClientError, EthProofError};
 use ethers::{
     contract::ContractError,
-    middleware::SignerMiddleware,
-    providers::{Http, Provider},
-    signers::LocalWallet,
+    providers::{Http, Provider, ProviderError},
+    types::{TxHash, U256},
 };
 use near_light_client_on_eth::NearLightClientOnEthError;
 use near_rpc_client::NearRpcError;
@@ -15,34 +19,139 @@ pub type Result<T> = result::Result<T, BridgeSdkError>;
 pub enum BridgeSdkError {
     #[error("Configuration error: {0}")]
     ConfigError(String),
+    #[error("Invalid argument provided: {0}")]
+    InvalidArgument(String),
     #[error("Error communicating with Ethereum RPC: {0}")]
     EthRpcError(#[source] EthRpcError),
     #[error("Error communicating with Near RPC: {0}")]
     NearRpcError(#[from] NearRpcError),
     #[error("Error creating Ethereum proof: {0}")]
     EthProofError(String),
+    #[error("Invalid Ethereum proof: {0}")]
+    InvalidEthProof(String),
     #[error("Error creating Near proof: {0}")]
     NearProofError(String),
-    #[error("Unexpected error occured")]
-    UnknownError,
+    #[error("Unexpected Near transaction outcome: {0}")]
+    NearOutcomeError(String),
+    #[error("Ethereum transaction {tx_hash:?} failed: {reason}")]
+    EthTxFailed { tx_hash: TxHash, reason: String },
+    #[error("Error communicating with Solana RPC: {0}")]
+    SolanaRpcError(String),
+    #[error("Solana transaction {signature} failed: {reason}")]
+    SolanaTxFailed { signature: String, reason: String },
+    #[error("Error signing transaction: {0}")]
+    SignerError(String),
+    #[error("Operation is disabled by configuration: {0}")]
+    OperationDisabled(String),
+    #[error("Too many requests queued for RPC endpoint {0}")]
+    RpcOverloaded(String),
+    #[error("Deadline exceeded: {0}")]
+    DeadlineExceeded(String),
+    #[error("Insufficient balance: have {have}, need {need}")]
+    InsufficientBalance { have: U256, need: U256 },
+    #[error("Transfer rejected by screening: {0}")]
+    TransferRejected(String),
+    #[error("Event not found: {0}")]
+    MissingEvent(String),
+    #[error("Invalid {0}")]
+    InvalidEvent(String),
+    #[error("Error serializing arguments: {0}")]
+    SerializationError(String),
+    #[error("Error accessing local files: {0}")]
+    IoError(String),
 }
 
 #[derive(thiserror::Error, Debug)]
 #[error("{0}")]
 pub enum EthRpcError {
-    SignerContractError(#[source] ContractError<SignerMiddleware<Provider<Http>, LocalWallet>>),
-    ProviderContractError(#[source] ContractError<Provider<Http>>),
+    SignerContractError(#[source] ContractError<EthSignerClient>),
+    ProviderContractError(#[source] ContractError<EthProvider>),
+    LightClientContractError(#[source] ContractError<Provider<Http>>),
     EthClientError(#[source] EthClientError),
+    ProviderError(#[source] ProviderError),
+}
+
+impl BridgeSdkError {
+    /// Whether the operation may succeed if retried as is, e.g. once the RPC endpoint recovers, the light client
+    /// catches up or the contract is unpaused. Invalid arguments, configuration, local files, disabled operations,
+    /// proofs that can't be built from the fetched data and reverted transactions aren't
+    pub fn is_retryable(&self) -> bool {
+        if let Some(failure) = self.contract_failure() {
+            return failure == ContractFailure::Paused;
+        }
+
+        match self {
+            BridgeSdkError::EthRpcError(err) => !matches!(
+                err,
+                EthRpcError::SignerContractError(ContractError::Revert(_))
+                    | EthRpcError::ProviderContractError(ContractError::Revert(_))
+                    | EthRpcError::LightClientContractError(ContractError::Revert(_))
+            ),
+            BridgeSdkError::NearRpcError(err) => !matches!(
+                err,
+                NearRpcError::CredentialsError(_) | NearRpcError::ExecutionFailed { .. }
+            ),
+            BridgeSdkError::EthProofError(_)
+            | BridgeSdkError::NearProofError(_)
+            | BridgeSdkError::SolanaRpcError(_)
+            | BridgeSdkError::RpcOverloaded(_)
+            | BridgeSdkError::DeadlineExceeded(_) => true,
+            BridgeSdkError::ConfigError(_)
+            | BridgeSdkError::InvalidArgument(_)
+            | BridgeSdkError::InvalidEthProof(_)
+            | BridgeSdkError::OperationDisabled(_)
+            | BridgeSdkError::NearOutcomeError(_)
+            | BridgeSdkError::EthTxFailed { .. }
+            | BridgeSdkError::SolanaTxFailed { .. }
+            | BridgeSdkError::SignerError(_)
+            | BridgeSdkError::InsufficientBalance { .. }
+            | BridgeSdkError::TransferRejected(_)
+            | BridgeSdkError::MissingEvent(_)
+            | BridgeSdkError::InvalidEvent(_)
+            | BridgeSdkError::SerializationError(_)
+            | BridgeSdkError::IoError(_) => false,
+        }
+    }
+
+    /// Known cause of a failed contract call, e.g. to retry later when the contract is paused or to skip a transfer
+    /// whose proof was already used, without matching the error message
+    pub fn contract_failure(&self) -> Option<ContractFailure> {
+        match self {
+            BridgeSdkError::EthTxFailed { reason, .. }
+            | BridgeSdkError::SolanaTxFailed { reason, .. } => {
+                ContractFailure::from_message(reason)
+            }
+            BridgeSdkError::NearOutcomeError(message) => ContractFailure::from_message(message),
+            BridgeSdkError::NearRpcError(err) => ContractFailure::from_message(&err.to_string()),
+            BridgeSdkError::EthRpcError(err) => {
+                let revert = match err {
+                    EthRpcError::SignerContractError(err) => err.as_revert(),
+                    EthRpcError::ProviderContractError(err) => err.as_revert(),
+                    EthRpcError::LightClientContractError(err) => err.as_revert(),
+                    EthRpcError::EthClientError(_) | EthRpcError::ProviderError(_) => None,
+                };
+
+                match revert {
+                    Some(data) => ContractFailure::from_message(&decode_revert_reason(data)),
+                    None => ContractFailure::from_message(&err.to_string()),
+                }
+            }
+            _ => None,
+        }
+    }
 }
 
 impl From<EthProofError> for BridgeSdkError {
     fn from(error: EthProofError) -> Self {
+        // Only fetching the data can fail transiently, building the proof from it fails the same way every time
         match error {
-            EthProofError::TrieError(e) => BridgeSdkError::EthProofError(e.to_string()),
             EthProofError::EthClientError(e) => {
                 BridgeSdkError::EthRpcError(EthRpcError::EthClientError(e))
             }
-            EthProofError::Other(e) => BridgeSdkError::EthProofError(e),
+            EthProofError::TrieError(_)
+            | EthProofError::FixtureError(_)
+            | EthProofError::MetricsError(_)
+            | EthProofError::Other(_) => BridgeSdkError::InvalidEthProof(error.to_string()),
         }
     }
 }
@@ -52,14 +161,75 @@ impl From<NearLightClientOnEthError> for BridgeSdkError {
         match error {
             NearLightClientOnEthError::ConfigError(e) => BridgeSdkError::ConfigError(e),
             NearLightClientOnEthError::EthRpcError(e) => {
-                BridgeSdkError::EthRpcError(EthRpcError::ProviderContractError(e))
+                BridgeSdkError::EthRpcError(EthRpcError::LightClientContractError(e))
             }
         }
     }
 }
 
-impl From<ContractError<SignerMiddleware<Provider<Http>, LocalWallet>>> for BridgeSdkError {
-    fn from(error: ContractError<SignerMiddleware<Provider<Http>, LocalWallet>>) -> Self {
+impl From<EthLightClientOnNearError> for BridgeSdkError {
+    fn from(error: EthLightClientOnNearError) -> Self {
+        match error {
+            EthLightClientOnNearError::NearRpcError(e) => BridgeSdkError::NearRpcError(e),
+            EthLightClientOnNearError::UnexpectedResponse(e) => BridgeSdkError::EthProofError(e),
+        }
+    }
+}
+
+impl From<ProviderError> for BridgeSdkError {
+    fn from(error: ProviderError) -> Self {
+        BridgeSdkError::EthRpcError(EthRpcError::ProviderError(error))
+    }
+}
+
+impl From<ContractError<EthSignerClient>> for BridgeSdkError {
+    fn from(error: ContractError<EthSignerClient>) -> Self {
         BridgeSdkError::EthRpcError(EthRpcError::SignerContractError(error))
     }
 }
+
+impl From<ContractError<EthProvider>> for BridgeSdkError {
+    fn from(error: ContractError<EthProvider>) -> Self {
+        BridgeSdkError::EthRpcError(EthRpcError::ProviderContractError(error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_retryable() {
+        let eth_client_error = EthClientError::RpcError {
+            method: "eth_getBlockReceipts".to_string(),
+            error: serde_json::json!({"code": -32005, "message": "limit exceeded"}),
+        };
+        let retryable = [
+            BridgeSdkError::from(EthProofError::EthClientError(eth_client_error)),
+            BridgeSdkError::EthProofError("Transaction 0x01 is not executed yet".to_string()),
+            BridgeSdkError::RpcOverloaded("https://rpc.example".to_string()),
+            BridgeSdkError::NearOutcomeError("Pausable: paused".to_string()),
+        ];
+        for err in &retryable {
+            assert!(err.is_retryable(), "{err} should be retryable");
+        }
+
+        let not_retryable = [
+            BridgeSdkError::OperationDisabled("finalize_withdraw".to_string()),
+            BridgeSdkError::from(EthProofError::FixtureError(
+                "Transaction 0x01 is not recorded".to_string(),
+            )),
+            BridgeSdkError::from(EthProofError::Other(
+                "Invalid transaction type 0x80 of receipt 0".to_string(),
+            )),
+            BridgeSdkError::NearRpcError(NearRpcError::ExecutionFailed {
+                tx_hash: Default::default(),
+                error: "Smart contract panicked: ERR_PROOF_EXISTS".to_string(),
+            }),
+            BridgeSdkError::InvalidArgument("amount".to_string()),
+        ];
+        for err in &not_retryable {
+            assert!(!err.is_retryable(), "{err} shouldn't be retryable");
+        }
+    }
+}
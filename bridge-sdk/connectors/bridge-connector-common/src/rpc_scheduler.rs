@@ -0,0 +1,228 @@
+use crate::{
+    deadline,
+    result::{BridgeSdkError, Result},
+};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+static SHARED_SCHEDULER: OnceLock<Arc<RpcScheduler>> = OnceLock::new();
+
+/// Kind of RPC work, deciding which share of an endpoint's capacity it can use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RpcPriority {
+    /// Sending transactions. Time-critical, so it can use the slots reserved for it and is never rejected
+    Submission,
+    /// Generating proofs of transfers
+    Proof,
+    /// Scanning the chains for new transfers. Limited to half of the background slots, so that scanning can't
+    /// starve proof generation either
+    Scan,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RpcSchedulerConfig {
+    /// Requests running concurrently against a single endpoint
+    pub max_concurrent_requests: usize,
+    /// Slots of `max_concurrent_requests` only submissions can use
+    pub reserved_for_submissions: usize,
+    /// Proof and scan requests waiting for a single endpoint. Further requests fail with
+    /// [`BridgeSdkError::RpcOverloaded`] until the queue drains
+    pub max_queued_requests: usize,
+}
+
+impl Default for RpcSchedulerConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: 16,
+            reserved_for_submissions: 4,
+            max_queued_requests: 256,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct EndpointLimiter {
+    all: Semaphore,
+    background: Semaphore,
+    scan: Semaphore,
+    queued: AtomicUsize,
+}
+
+impl EndpointLimiter {
+    fn new(config: &RpcSchedulerConfig) -> Self {
+        let max_concurrent_requests = config.max_concurrent_requests.max(1);
+        let background = max_concurrent_requests
+            .saturating_sub(config.reserved_for_submissions)
+            .max(1);
+
+        Self {
+            all: Semaphore::new(max_concurrent_requests),
+            background: Semaphore::new(background),
+            scan: Semaphore::new((background / 2).max(1)),
+            queued: AtomicUsize::new(0),
+        }
+    }
+}
+
+/// Per-endpoint limits on concurrent RPC requests, shared by the watchers, the proof generation and the transaction
+/// submissions of a process, so that a burst of background work can't overload a provider or delay submissions
+#[derive(Debug)]
+pub struct RpcScheduler {
+    config: RpcSchedulerConfig,
+    endpoints: Mutex<HashMap<String, Arc<EndpointLimiter>>>,
+}
+
+impl RpcScheduler {
+    pub fn new(config: RpcSchedulerConfig) -> Self {
+        Self {
+            config,
+            endpoints: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Scheduler with the default config, used by the connectors and the relayer that aren't given their own, so that
+    /// they share the limits of an endpoint
+    pub fn shared() -> Arc<RpcScheduler> {
+        SHARED_SCHEDULER
+            .get_or_init(|| Arc::new(RpcScheduler::new(RpcSchedulerConfig::default())))
+            .clone()
+    }
+
+    /// Runs `request` once `endpoint` has capacity for work of the given priority. Waiting for capacity counts
+    /// against the budget of an enclosing [`deadline::with_timeout`]
+    pub async fn run<T, F>(&self, endpoint: &str, priority: RpcPriority, request: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let limiter = self.limiter(endpoint);
+
+        deadline::run(endpoint, async {
+            let _permits = self.acquire(&limiter, endpoint, priority).await?;
+
+            request.await
+        })
+        .await
+    }
+
+    async fn acquire<'a>(
+        &self,
+        limiter: &'a EndpointLimiter,
+        endpoint: &str,
+        priority: RpcPriority,
+    ) -> Result<Vec<SemaphorePermit<'a>>> {
+        let mut permits = Vec::with_capacity(3);
+
+        if priority != RpcPriority::Submission {
+            if limiter.queued.fetch_add(1, Ordering::SeqCst) >= self.config.max_queued_requests {
+                limiter.queued.fetch_sub(1, Ordering::SeqCst);
+                return Err(BridgeSdkError::RpcOverloaded(endpoint.to_string()));
+            }
+            let _queued = QueuedGuard(&limiter.queued);
+
+            if priority == RpcPriority::Scan {
+                permits.push(acquire(&limiter.scan).await);
+            }
+            permits.push(acquire(&limiter.background).await);
+        }
+        permits.push(acquire(&limiter.all).await);
+
+        Ok(permits)
+    }
+
+    fn limiter(&self, endpoint: &str) -> Arc<EndpointLimiter> {
+        // The same endpoint is configured with and without a trailing slash across the subsystems
+        let key = endpoint.trim_end_matches('/');
+
+        self.endpoints
+            .lock()
+            .expect("RPC scheduler lock is poisoned")
+            .entry(key.to_string())
+            .or_insert_with(|| Arc::new(EndpointLimiter::new(&self.config)))
+            .clone()
+    }
+}
+
+async fn acquire(semaphore: &Semaphore) -> SemaphorePermit<'_> {
+    semaphore
+        .acquire()
+        .await
+        .expect("RPC scheduler semaphores are never closed")
+}
+
+struct QueuedGuard<'a>(&'a AtomicUsize);
+
+impl Drop for QueuedGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn scheduler() -> Arc<RpcScheduler> {
+        Arc::new(RpcScheduler::new(RpcSchedulerConfig {
+            max_concurrent_requests: 2,
+            reserved_for_submissions: 1,
+            max_queued_requests: 1,
+        }))
+    }
+
+    async fn hold(scheduler: Arc<RpcScheduler>, priority: RpcPriority, duration: Duration) {
+        scheduler
+            .run("http://rpc", priority, async {
+                tokio::time::sleep(duration).await;
+                Ok(())
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn submissions_use_the_reserved_slots() {
+        let scheduler = scheduler();
+        tokio::spawn(hold(
+            scheduler.clone(),
+            RpcPriority::Proof,
+            Duration::from_secs(60),
+        ));
+        tokio::task::yield_now().await;
+
+        let submission = scheduler.run("http://rpc/", RpcPriority::Submission, async { Ok(1) });
+        let result = tokio::time::timeout(Duration::from_secs(1), submission).await;
+
+        assert_eq!(result.unwrap().unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn background_requests_are_rejected_when_the_queue_is_full() {
+        let scheduler = scheduler();
+        tokio::spawn(hold(
+            scheduler.clone(),
+            RpcPriority::Proof,
+            Duration::from_secs(60),
+        ));
+        tokio::task::yield_now().await;
+        tokio::spawn(hold(
+            scheduler.clone(),
+            RpcPriority::Proof,
+            Duration::from_secs(60),
+        ));
+        tokio::task::yield_now().await;
+
+        let result = scheduler
+            .run("http://rpc", RpcPriority::Scan, async { Ok(()) })
+            .await;
+
+        assert!(matches!(result, Err(BridgeSdkError::RpcOverloaded(_))));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{
+    bridge_connector::TransferDirection,
+    result::{BridgeSdkError, Result},
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Transfer about to be submitted by a connector, as passed to a [`TransferScreening`] hook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreenedTransfer {
+    /// Aurora and the eth-connector EVM are on the Ethereum side
+    pub direction: TransferDirection,
+    /// Token as passed to the connector: a NEP-141 or NFT contract account id, an ERC-20 address, or `ETH` for the
+    /// eth-connector
+    pub token: String,
+    /// Amount in raw units of the token. One for an NFT
+    pub amount: u128,
+    /// Signer account id or address submitting the transfer
+    pub sender: String,
+    pub recipient: String,
+}
+
+/// Screening of the transfers before they are submitted, e.g. against a sanctions list or by a compliance service.
+/// Called by the connectors for every deposit, withdrawal and transfer initiated by the signer, finalizations aren't
+/// screened
+#[async_trait]
+pub trait TransferScreening: Send + Sync {
+    /// Returns the reason if the transfer is rejected
+    async fn screen(&self, transfer: &ScreenedTransfer) -> std::result::Result<(), String>;
+}
+
+/// Screens the transfer with the hook, if any. The transfer is only built when a hook is set, so its sender isn't
+/// resolved otherwise. A rejection fails with [`BridgeSdkError::TransferRejected`]
+pub async fn check(
+    screening: Option<&Arc<dyn TransferScreening>>,
+    transfer: impl FnOnce() -> Result<ScreenedTransfer>,
+) -> Result<()> {
+    let Some(screening) = screening else {
+        return Ok(());
+    };
+    let transfer = transfer()?;
+
+    screening.screen(&transfer).await.map_err(|reason| {
+        tracing::warn!(
+            token = transfer.token,
+            amount = transfer.amount,
+            sender = transfer.sender,
+            recipient = transfer.recipient,
+            reason,
+            "Transfer rejected by screening"
+        );
+        BridgeSdkError::TransferRejected(reason)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Blocklist(Vec<String>);
+
+    #[async_trait]
+    impl TransferScreening for Blocklist {
+        async fn screen(&self, transfer: &ScreenedTransfer) -> std::result::Result<(), String> {
+            match self.0.contains(&transfer.recipient) {
+                true => Err(format!("{} is blocked", transfer.recipient)),
+                false => Ok(()),
+            }
+        }
+    }
+
+    fn transfer(recipient: &str) -> ScreenedTransfer {
+        ScreenedTransfer {
+            direction: TransferDirection::NearToEth,
+            token: "token.near".to_string(),
+            amount: 100,
+            sender: "alice.near".to_string(),
+            recipient: recipient.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_rejects_screened_transfers() {
+        let screening: Arc<dyn TransferScreening> = Arc::new(Blocklist(vec!["0xbad".to_string()]));
+
+        assert!(check(None, || Ok(transfer("0xbad"))).await.is_ok());
+        assert!(check(Some(&screening), || Ok(transfer("0x1")))
+            .await
+            .is_ok());
+        assert!(matches!(
+            check(Some(&screening), || Ok(transfer("0xbad"))).await,
+            Err(BridgeSdkError::TransferRejected(reason)) if reason == "0xbad is blocked"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_check_builds_transfer_only_with_screening() {
+        let screening: Arc<dyn TransferScreening> = Arc::new(Blocklist(vec![]));
+        let unset_signer = || {
+            Err(BridgeSdkError::ConfigError(
+                "Near signer is not set".to_string(),
+            ))
+        };
+
+        assert!(check(None, unset_signer).await.is_ok());
+        assert!(matches!(
+            check(Some(&screening), unset_signer).await,
+            Err(BridgeSdkError::ConfigError(_))
+        ));
+    }
+}
@@ -0,0 +1,321 @@
+use crate::{
+    failover::{self, EthProvider},
+    result::Result,
+};
+use ethers::{
+    contract::abigen,
+    providers::Middleware,
+    types::Address,
+    utils::{format_ether, keccak256},
+};
+use near_crypto::PublicKey;
+use near_primitives::{hash::CryptoHash, types::AccountId, views::AccessKeyPermissionView};
+use std::{fmt, future::Future, sync::Arc};
+
+abigen!(
+    AdminControlled,
+    r#"[
+      function hasRole(bytes32 role, address account) external view returns (bool)
+      function admin() external view returns (address)
+    ]"#
+);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CheckStatus {
+    Passed(String),
+    Failed(String),
+    Skipped,
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+}
+
+/// Result of verifying the capabilities a connector is configured with
+#[derive(Debug, Clone, Default)]
+pub struct SelfCheckReport {
+    pub checks: Vec<Check>,
+}
+
+impl SelfCheckReport {
+    pub fn push(&mut self, name: &str, status: CheckStatus) {
+        self.checks.push(Check {
+            name: name.to_string(),
+            status,
+        });
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| !matches!(check.status, CheckStatus::Failed(_)))
+    }
+}
+
+impl fmt::Display for SelfCheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            match &check.status {
+                CheckStatus::Passed(details) => writeln!(f, "[OK]   {}: {details}", check.name)?,
+                CheckStatus::Failed(error) => writeln!(f, "[FAIL] {}: {error}", check.name)?,
+                CheckStatus::Skipped => writeln!(f, "[SKIP] {}: not configured", check.name)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub async fn check_eth_rpc(endpoint: Option<&str>) -> CheckStatus {
+    run_check(endpoint.map(Ok), |endpoint| async move {
+        let block_number = eth_provider(endpoint)?
+            .get_block_number()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(format!("latest block {block_number}"))
+    })
+    .await
+}
+
+pub async fn check_eth_code(
+    endpoint: Option<&str>,
+    address: Option<Result<Address>>,
+) -> CheckStatus {
+    run_check(both(endpoint, address), |(endpoint, address)| async move {
+        let code = eth_provider(endpoint)?
+            .get_code(address, None)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        match code.is_empty() {
+            true => Err(format!("no contract deployed at {address:?}")),
+            false => Ok(format!("contract deployed at {address:?}")),
+        }
+    })
+    .await
+}
+
+pub async fn check_eth_balance(
+    endpoint: Option<&str>,
+    address: Option<Result<Address>>,
+) -> CheckStatus {
+    run_check(both(endpoint, address), |(endpoint, address)| async move {
+        let balance = eth_provider(endpoint)?
+            .get_balance(address, None)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        match balance.is_zero() {
+            true => Err(format!("{address:?} has no funds")),
+            false => Ok(format!("{address:?} holds {} ETH", format_ether(balance))),
+        }
+    })
+    .await
+}
+
+/// Passes if any of the endpoints is healthy, reporting each of them
+pub async fn check_near_rpc(endpoint: Option<&str>) -> CheckStatus {
+    run_check(endpoint.map(Ok), |endpoint| async move {
+        let health = near_rpc_client::health_check(endpoint).await;
+        let details = health
+            .iter()
+            .map(|endpoint| match &endpoint.status {
+                Ok(height) => format!(
+                    "{} final block {height} in {} ms",
+                    endpoint.endpoint,
+                    endpoint.latency.as_millis()
+                ),
+                Err(err) => format!("{} failed: {err}", endpoint.endpoint),
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match health.iter().any(|endpoint| endpoint.status.is_ok()) {
+            true => Ok(details),
+            false => Err(details),
+        }
+    })
+    .await
+}
+
+pub async fn check_near_code(endpoint: Option<&str>, account_id: Option<&str>) -> CheckStatus {
+    run_check(
+        both(endpoint, account_id.map(Ok)),
+        |(endpoint, account_id)| async move {
+            let account = view_near_account(endpoint, account_id).await?;
+
+            match account.code_hash == CryptoHash::default() {
+                true => Err(format!("no contract deployed on {account_id}")),
+                false => Ok(format!("contract deployed on {account_id}")),
+            }
+        },
+    )
+    .await
+}
+
+pub async fn check_near_balance(endpoint: Option<&str>, account_id: Option<&str>) -> CheckStatus {
+    run_check(
+        both(endpoint, account_id.map(Ok)),
+        |(endpoint, account_id)| async move {
+            let account = view_near_account(endpoint, account_id).await?;
+
+            match account.amount {
+                0 => Err(format!("{account_id} has no funds")),
+                amount => Ok(format!("{account_id} holds {amount} yoctoNEAR")),
+            }
+        },
+    )
+    .await
+}
+
+/// Reports what the access key `public_key` of `account_id` is allowed to sign. Fails if the key doesn't exist
+pub async fn check_near_access_key(
+    endpoint: Option<&str>,
+    account_id: Option<&str>,
+    public_key: Option<Result<PublicKey>>,
+) -> CheckStatus {
+    let key = account_id
+        .zip(public_key)
+        .map(|(account_id, public_key)| public_key.map(|public_key| (account_id, public_key)));
+
+    run_check(
+        both(endpoint, key),
+        |(endpoint, (account_id, public_key))| async move {
+            let parsed_account_id: AccountId = account_id
+                .parse()
+                .map_err(|_| format!("Invalid account id: {account_id}"))?;
+            let access_key =
+                near_rpc_client::view_access_key(endpoint, parsed_account_id, public_key.clone())
+                    .await
+                    .map_err(|e| format!("{public_key} is not a key of {account_id}: {e}"))?;
+
+            match access_key.permission {
+                AccessKeyPermissionView::FullAccess => Ok(format!(
+                    "{public_key} is a full access key of {account_id}, it can also transfer \
+                     all funds, deploy code and add keys"
+                )),
+                AccessKeyPermissionView::FunctionCall {
+                    allowance,
+                    receiver_id,
+                    method_names,
+                } => {
+                    let methods = match method_names.is_empty() {
+                        true => "any method".to_string(),
+                        false => method_names.join(", "),
+                    };
+                    let allowance = match allowance {
+                        Some(allowance) => format!("{allowance} yoctoNEAR of fees left"),
+                        None => "unlimited fees".to_string(),
+                    };
+
+                    Ok(format!(
+                        "{public_key} of {account_id} can only call {methods} on {receiver_id}, \
+                         without deposit, with {allowance}"
+                    ))
+                }
+            }
+        },
+    )
+    .await
+}
+
+/// Fails if `account` holds an admin role of `contract`, either an access control role or the `admin` of
+/// contracts with a single admin
+pub async fn check_eth_admin_roles(
+    endpoint: Option<&str>,
+    contract: Option<Result<Address>>,
+    account: Option<Result<Address>>,
+) -> CheckStatus {
+    let subject = contract
+        .zip(account)
+        .map(|(contract, account)| Ok((contract?, account?)));
+
+    run_check(
+        both(endpoint, subject),
+        |(endpoint, (contract, account))| async move {
+            let contract = AdminControlled::new(contract, Arc::new(eth_provider(endpoint)?));
+            let mut held = Vec::new();
+            let mut readable = false;
+
+            // Roles of the bridge contracts that grant more than submitting transfers and proofs
+            let roles = [
+                ("DEFAULT_ADMIN_ROLE", [0; 32]),
+                ("PAUSABLE_ADMIN_ROLE", keccak256("PAUSABLE_ADMIN_ROLE")),
+                ("UNPAUSABLE_ADMIN_ROLE", keccak256("UNPAUSABLE_ADMIN_ROLE")),
+            ];
+            for (role, role_hash) in roles {
+                // Contracts without access control revert, which leaves the role unknown
+                if let Ok(has_role) = contract.has_role(role_hash, account).call().await {
+                    readable = true;
+                    if has_role {
+                        held.push(role.to_string());
+                    }
+                }
+            }
+            if let Ok(admin) = contract.admin().call().await {
+                readable = true;
+                if admin == account {
+                    held.push("admin".to_string());
+                }
+            }
+
+            match (readable, held.is_empty()) {
+                (false, _) => Err(format!(
+                    "could not read the roles of {:?}",
+                    contract.address()
+                )),
+                (true, true) => Ok(format!(
+                    "{account:?} holds no admin role on {:?}",
+                    contract.address()
+                )),
+                (true, false) => Err(format!(
+                    "{account:?} holds {} on {:?}, bridge operations don't need admin roles",
+                    held.join(", "),
+                    contract.address()
+                )),
+            }
+        },
+    )
+    .await
+}
+
+async fn run_check<T, F, Fut>(subject: Option<Result<T>>, check: F) -> CheckStatus
+where
+    F: FnOnce(T) -> Fut,
+    Fut: Future<Output = std::result::Result<String, String>>,
+{
+    match subject {
+        None => CheckStatus::Skipped,
+        Some(Err(error)) => CheckStatus::Failed(error.to_string()),
+        Some(Ok(subject)) => match check(subject).await {
+            Ok(details) => CheckStatus::Passed(details),
+            Err(error) => CheckStatus::Failed(error),
+        },
+    }
+}
+
+/// Both the endpoint and the checked value have to be configured for the check to run
+fn both<T>(endpoint: Option<&str>, value: Option<Result<T>>) -> Option<Result<(&str, T)>> {
+    let endpoint = endpoint?;
+    Some(value?.map(|value| (endpoint, value)))
+}
+
+fn eth_provider(endpoint: &str) -> std::result::Result<EthProvider, String> {
+    failover::eth_provider(endpoint).map_err(|_| "Invalid rpc endpoint url".to_string())
+}
+
+async fn view_near_account(
+    endpoint: &str,
+    account_id: &str,
+) -> std::result::Result<near_primitives::views::AccountView, String> {
+    let account_id: AccountId = account_id
+        .parse()
+        .map_err(|_| format!("Invalid account id: {account_id}"))?;
+
+    near_rpc_client::view_account(endpoint, account_id)
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,528 @@
+use crate::{
+    bridge_connector::TransactionId,
+    events::{self, ConnectorEvent, ConnectorEvents},
+    failover::EthProvider,
+    result::{BridgeSdkError, Result},
+    rpc_scheduler::{RpcPriority, RpcScheduler},
+};
+use async_trait::async_trait;
+use ethers::{
+    middleware::SignerMiddleware,
+    signers::{LocalWallet, Signer},
+    types::{
+        transaction::eip2718::TypedTransaction, transaction::eip712::Eip712, Address, Signature,
+        U256,
+    },
+};
+use near_crypto::{InMemorySigner, PublicKey};
+use near_primitives::{
+    hash::CryptoHash,
+    transaction::{SignedTransaction, Transaction},
+    types::AccountId,
+};
+use near_rpc_client::NearRpc;
+use std::{fmt, future::Future, pin::Pin, sync::Arc, time::Duration};
+
+/// Time a Near transaction sent by [`near_change`] is awaited for by default
+pub const DEFAULT_NEAR_OUTCOME_TIMEOUT: Duration =
+    Duration::from_secs(near_rpc_client::DEFAULT_WAIT_FINAL_OUTCOME_TIMEOUT_SEC);
+
+/// Ethereum client signing transactions with the configured [`EthSigner`]
+pub type EthSignerClient = SignerMiddleware<EthProvider, DynEthSigner>;
+
+#[derive(thiserror::Error, Debug)]
+#[error("{0}")]
+pub struct SignerError(pub String);
+
+/// Signs Near transactions on behalf of `account_id`. Implemented for in-memory keys, other implementations can
+/// delegate to a hardware wallet or a remote signing service
+#[async_trait]
+pub trait NearSigner: Send + Sync {
+    fn account_id(&self) -> AccountId;
+
+    fn public_key(&self) -> PublicKey;
+
+    /// Signs the transaction. Takes the whole transaction rather than its hash, since hardware wallets display the
+    /// transaction details for confirmation
+    async fn sign_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> std::result::Result<near_crypto::Signature, SignerError>;
+}
+
+#[async_trait]
+impl NearSigner for InMemorySigner {
+    fn account_id(&self) -> AccountId {
+        self.account_id.clone()
+    }
+
+    fn public_key(&self) -> PublicKey {
+        self.public_key.clone()
+    }
+
+    async fn sign_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> std::result::Result<near_crypto::Signature, SignerError> {
+        let (hash, _) = transaction.get_hash_and_size();
+        Ok(near_crypto::Signer::sign(self, hash.as_ref()))
+    }
+}
+
+/// Signature returned asynchronously by a [`NearSigningCallback`]
+pub type NearSignatureFuture =
+    Pin<Box<dyn Future<Output = std::result::Result<near_crypto::Signature, SignerError>> + Send>>;
+
+/// Signs a Near transaction outside of the SDK, e.g. in a wallet app or a custodial signing service. The signature is
+/// over the transaction hash, see `Transaction::get_hash_and_size`
+pub type NearSigningCallback = Arc<dyn Fn(Transaction) -> NearSignatureFuture + Send + Sync>;
+
+/// [`NearSigner`] delegating transaction signing to a callback, while the SDK builds, sends and tracks the
+/// transactions
+#[derive(Clone)]
+pub struct CallbackNearSigner {
+    account_id: AccountId,
+    public_key: PublicKey,
+    callback: NearSigningCallback,
+}
+
+impl CallbackNearSigner {
+    /// `public_key` is the access key of `account_id` the callback signs with
+    pub fn new<F, Fut>(account_id: AccountId, public_key: PublicKey, callback: F) -> Self
+    where
+        F: Fn(Transaction) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = std::result::Result<near_crypto::Signature, SignerError>>
+            + Send
+            + 'static,
+    {
+        Self {
+            account_id,
+            public_key,
+            callback: Arc::new(move |transaction| Box::pin(callback(transaction))),
+        }
+    }
+}
+
+#[async_trait]
+impl NearSigner for CallbackNearSigner {
+    fn account_id(&self) -> AccountId {
+        self.account_id.clone()
+    }
+
+    fn public_key(&self) -> PublicKey {
+        self.public_key.clone()
+    }
+
+    async fn sign_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> std::result::Result<near_crypto::Signature, SignerError> {
+        (self.callback)(transaction.clone()).await
+    }
+}
+
+/// Signs Ethereum transactions. Unlike `ethers::signers::Signer` it is object safe, so that connectors can hold any
+/// implementation, e.g. a hardware wallet or a remote signing service
+#[async_trait]
+pub trait EthSigner: fmt::Debug + Send + Sync {
+    fn address(&self) -> Address;
+
+    fn chain_id(&self) -> u64;
+
+    async fn sign_transaction(
+        &self,
+        tx: &TypedTransaction,
+    ) -> std::result::Result<Signature, SignerError>;
+
+    async fn sign_message(&self, message: &[u8]) -> std::result::Result<Signature, SignerError>;
+}
+
+#[async_trait]
+impl EthSigner for LocalWallet {
+    fn address(&self) -> Address {
+        Signer::address(self)
+    }
+
+    fn chain_id(&self) -> u64 {
+        Signer::chain_id(self)
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &TypedTransaction,
+    ) -> std::result::Result<Signature, SignerError> {
+        Signer::sign_transaction(self, tx)
+            .await
+            .map_err(|err| SignerError(err.to_string()))
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> std::result::Result<Signature, SignerError> {
+        Signer::sign_message(self, message)
+            .await
+            .map_err(|err| SignerError(err.to_string()))
+    }
+}
+
+/// Adapter plugging any [`EthSigner`] into ethers' `SignerMiddleware`
+#[derive(Debug, Clone)]
+pub struct DynEthSigner(Arc<dyn EthSigner>);
+
+impl DynEthSigner {
+    pub fn new(signer: Arc<dyn EthSigner>) -> Self {
+        Self(signer)
+    }
+}
+
+#[async_trait]
+impl Signer for DynEthSigner {
+    type Error = SignerError;
+
+    async fn sign_message<S: Send + Sync + AsRef<[u8]>>(
+        &self,
+        message: S,
+    ) -> std::result::Result<Signature, Self::Error> {
+        self.0.sign_message(message.as_ref()).await
+    }
+
+    async fn sign_transaction(
+        &self,
+        message: &TypedTransaction,
+    ) -> std::result::Result<Signature, Self::Error> {
+        self.0.sign_transaction(message).await
+    }
+
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(
+        &self,
+        _payload: &T,
+    ) -> std::result::Result<Signature, Self::Error> {
+        Err(SignerError(
+            "Typed data signing is not supported".to_string(),
+        ))
+    }
+
+    fn address(&self) -> Address {
+        self.0.address()
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.0.chain_id()
+    }
+
+    /// The chain id is defined by the wrapped signer and can't be overridden
+    fn with_chain_id<T: Into<u64>>(self, _chain_id: T) -> Self {
+        self
+    }
+}
+
+/// Signature returned asynchronously by an [`EthSigningCallback`]
+pub type EthSignatureFuture =
+    Pin<Box<dyn Future<Output = std::result::Result<Signature, SignerError>> + Send>>;
+
+/// Signs a typed transaction outside of the SDK, e.g. in a browser wallet, an MPC signing service or a signing daemon
+pub type EthSigningCallback = Arc<dyn Fn(TypedTransaction) -> EthSignatureFuture + Send + Sync>;
+
+/// [`EthSigner`] delegating transaction signing to a callback, so that the key is never exposed to the SDK. Message
+/// signing isn't supported
+#[derive(Clone)]
+pub struct CallbackEthSigner {
+    address: Address,
+    chain_id: u64,
+    callback: EthSigningCallback,
+}
+
+impl CallbackEthSigner {
+    /// `address` is the account the callback signs for, the transactions are sent from it
+    pub fn new<F, Fut>(address: Address, chain_id: u64, callback: F) -> Self
+    where
+        F: Fn(TypedTransaction) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = std::result::Result<Signature, SignerError>> + Send + 'static,
+    {
+        Self {
+            address,
+            chain_id,
+            callback: Arc::new(move |tx| Box::pin(callback(tx))),
+        }
+    }
+}
+
+impl fmt::Debug for CallbackEthSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackEthSigner")
+            .field("address", &self.address)
+            .field("chain_id", &self.chain_id)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl EthSigner for CallbackEthSigner {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &TypedTransaction,
+    ) -> std::result::Result<Signature, SignerError> {
+        let mut tx = tx.clone();
+        if tx.chain_id().is_none() {
+            tx.set_chain_id(self.chain_id);
+        }
+
+        (self.callback)(tx).await
+    }
+
+    async fn sign_message(&self, _message: &[u8]) -> std::result::Result<Signature, SignerError> {
+        Err(SignerError(
+            "Message signing is not supported by the signing callback".to_string(),
+        ))
+    }
+}
+
+/// Decrypts a web3 JSON keystore file into an Ethereum signer
+pub fn keystore_signer(
+    path: &str,
+    password: Option<&str>,
+    chain_id: Option<u64>,
+) -> Result<DynEthSigner> {
+    let password = password.ok_or(BridgeSdkError::ConfigError(
+        "Ethereum keystore password is not set".to_string(),
+    ))?;
+    let chain_id = chain_id.ok_or(BridgeSdkError::ConfigError(
+        "Ethereum chain id is not set".to_string(),
+    ))?;
+
+    let wallet = LocalWallet::decrypt_keystore(path, password)
+        .map_err(|err| {
+            BridgeSdkError::ConfigError(format!("Could not decrypt keystore {path}: {err}"))
+        })?
+        .with_chain_id(chain_id);
+
+    Ok(DynEthSigner::new(Arc::new(wallet)))
+}
+
+/// Same as `near_rpc_client::change`, but sends the transaction through `near_rpc` and signs it with a [`NearSigner`].
+/// With `dry_run` the signed transaction isn't sent and the hash it would have is returned, once the call is simulated
+/// with [`NearRpc::simulate_function_call`] and the signer is checked to afford its deposit and gas.
+///
+/// The transaction is awaited for up to `outcome_timeout`, so that a failed call, e.g. a `withdraw` panicking on
+/// insufficient storage deposit, fails with [`near_rpc_client::NearRpcError::ExecutionFailed`] rather than returning
+/// its hash. With `None` it's only broadcast. The submission and outcome of the transaction are reported to `events`.
+/// The transaction is sent once `scheduler` has capacity for a submission to the endpoint
+#[allow(clippy::too_many_arguments)]
+pub async fn near_change(
+    near_rpc: &dyn NearRpc,
+    signer: Arc<dyn NearSigner>,
+    receiver_id: String,
+    method_name: String,
+    args: Vec<u8>,
+    gas: u64,
+    deposit: u128,
+    dry_run: bool,
+    outcome_timeout: Option<Duration>,
+    events: Option<&dyn ConnectorEvents>,
+    scheduler: &RpcScheduler,
+) -> Result<CryptoHash> {
+    let simulated_call = dry_run.then(|| (receiver_id.clone(), method_name.clone(), args.clone()));
+    let transaction = near_rpc
+        .prepare_transaction(
+            signer.account_id(),
+            signer.public_key(),
+            receiver_id,
+            method_name,
+            args,
+            gas,
+            deposit,
+        )
+        .await?;
+
+    let signature = signer.sign_transaction(&transaction).await?;
+    let signed_transaction = SignedTransaction::new(signature, transaction);
+
+    if let Some((receiver_id, method_name, args)) = simulated_call {
+        let tx_hash = signed_transaction.get_hash();
+        let receiver_id = receiver_id.parse().map_err(|_| {
+            BridgeSdkError::InvalidArgument(format!("Invalid receiver account id: {receiver_id}"))
+        })?;
+        let simulation = near_rpc
+            .simulate_function_call(
+                signer.account_id(),
+                receiver_id,
+                method_name,
+                args,
+                gas,
+                deposit,
+            )
+            .await?;
+
+        let cost = simulation.max_gas_cost + deposit;
+        if simulation.signer_balance < cost {
+            return Err(BridgeSdkError::InsufficientBalance {
+                have: U256::from(simulation.signer_balance),
+                need: U256::from(cost),
+            });
+        }
+
+        match &simulation.result {
+            Some(result) => tracing::info!(
+                tx_hash = tx_hash.to_string(),
+                gas,
+                deposit,
+                max_gas_cost = simulation.max_gas_cost,
+                result = String::from_utf8_lossy(&result.result).to_string(),
+                logs = ?result.logs,
+                "Dry run: simulated the call as a view call without sending the transaction"
+            ),
+            None => tracing::info!(
+                tx_hash = tx_hash.to_string(),
+                gas,
+                deposit,
+                max_gas_cost = simulation.max_gas_cost,
+                reason = simulation.view_error.as_deref(),
+                "Dry run: signed the transaction without sending it, the call can't be simulated as a view call"
+            ),
+        }
+
+        return Ok(tx_hash);
+    }
+
+    let tx_hash = scheduler
+        .run(near_rpc.endpoint(), RpcPriority::Submission, async {
+            Ok(near_rpc.send_transaction(signed_transaction).await?)
+        })
+        .await?;
+    events::emit(
+        events,
+        ConnectorEvent::TxSubmitted {
+            tx: TransactionId::Near(tx_hash),
+        },
+    );
+
+    if let Some(timeout) = outcome_timeout {
+        near_rpc
+            .wait_for_tx_success(tx_hash, signer.account_id(), timeout.as_secs())
+            .await?;
+        events::emit(
+            events,
+            ConnectorEvent::TxConfirmed {
+                tx: TransactionId::Near(tx_hash),
+            },
+        );
+    }
+
+    Ok(tx_hash)
+}
+
+impl From<SignerError> for BridgeSdkError {
+    fn from(error: SignerError) -> Self {
+        BridgeSdkError::SignerError(error.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::KeyType;
+
+    #[tokio::test]
+    async fn in_memory_near_signer_signs_transaction_hash() {
+        let signer = InMemorySigner::from_seed(
+            "alice.near".parse().unwrap(),
+            KeyType::ED25519,
+            "alice.near",
+        );
+        let transaction = Transaction {
+            signer_id: signer.account_id.clone(),
+            public_key: signer.public_key.clone(),
+            nonce: 1,
+            receiver_id: "bob.near".parse().unwrap(),
+            block_hash: CryptoHash::default(),
+            actions: vec![],
+        };
+
+        let signature = signer.sign_transaction(&transaction).await.unwrap();
+
+        let (hash, _) = transaction.get_hash_and_size();
+        assert!(signature.verify(hash.as_ref(), &signer.public_key));
+    }
+
+    #[tokio::test]
+    async fn callback_near_signer_signs_with_the_callback() {
+        let key = InMemorySigner::from_seed(
+            "alice.near".parse().unwrap(),
+            KeyType::ED25519,
+            "alice.near",
+        );
+        let signer = CallbackNearSigner::new(key.account_id.clone(), key.public_key.clone(), {
+            let key = key.clone();
+            move |transaction: Transaction| {
+                let (hash, _) = transaction.get_hash_and_size();
+                let signature = near_crypto::Signer::sign(&key, hash.as_ref());
+                async move { Ok(signature) }
+            }
+        });
+        let transaction = Transaction {
+            signer_id: signer.account_id(),
+            public_key: signer.public_key(),
+            nonce: 1,
+            receiver_id: "bob.near".parse().unwrap(),
+            block_hash: CryptoHash::default(),
+            actions: vec![],
+        };
+
+        let signature = signer.sign_transaction(&transaction).await.unwrap();
+
+        let (hash, _) = transaction.get_hash_and_size();
+        assert!(signature.verify(hash.as_ref(), &key.public_key));
+    }
+
+    #[tokio::test]
+    async fn wallet_is_usable_through_dyn_signer() {
+        let wallet = LocalWallet::from_bytes(&[1; 32])
+            .unwrap()
+            .with_chain_id(5u64);
+        let signer = DynEthSigner::new(Arc::new(wallet.clone()));
+
+        assert_eq!(Signer::address(&signer), Signer::address(&wallet));
+        assert_eq!(Signer::chain_id(&signer), 5);
+        assert_eq!(
+            Signer::sign_message(&signer, b"message").await.unwrap(),
+            Signer::sign_message(&wallet, b"message").await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn callback_signer_signs_for_the_chain() {
+        let wallet = LocalWallet::from_bytes(&[1; 32])
+            .unwrap()
+            .with_chain_id(5u64);
+        let signer = CallbackEthSigner::new(Signer::address(&wallet), 5, {
+            let wallet = wallet.clone();
+            move |tx| {
+                let wallet = wallet.clone();
+                async move {
+                    Signer::sign_transaction(&wallet, &tx)
+                        .await
+                        .map_err(|err| SignerError(err.to_string()))
+                }
+            }
+        });
+        let tx = TypedTransaction::default();
+
+        let signature = EthSigner::sign_transaction(&signer, &tx).await.unwrap();
+
+        let mut expected_tx = tx.clone();
+        expected_tx.set_chain_id(5u64);
+        assert_eq!(
+            signature,
+            Signer::sign_transaction(&wallet, &expected_tx)
+                .await
+                .unwrap()
+        );
+        assert!(EthSigner::sign_message(&signer, b"message").await.is_err());
+    }
+}
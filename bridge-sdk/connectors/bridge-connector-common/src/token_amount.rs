@@ -0,0 +1,144 @@
+use crate::{
+    failover,
+    result::{BridgeSdkError, Result},
+};
+use ethers::{contract::abigen, types::Address};
+use std::{fmt, future::Future, str::FromStr, sync::Arc};
+
+abigen!(
+    Erc20Decimals,
+    r#"[
+      function decimals() external view returns (uint8)
+    ]"#
+);
+
+/// Decimals of ETH, as held by the eth-connector on both chains
+pub const ETH_DECIMALS: u8 = 18;
+
+/// Token amount as entered by a user: either raw units, e.g. `1500000`, or whole tokens with a decimal point, e.g. `1.5`,
+/// which are converted with the token's decimals
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenAmount {
+    Raw(u128),
+    Tokens { whole: u128, fraction: String },
+}
+
+impl TokenAmount {
+    /// Amount in raw units of a token with the given decimals
+    pub fn to_raw(&self, decimals: u8) -> Result<u128> {
+        let (whole, fraction) = match self {
+            Self::Raw(amount) => return Ok(*amount),
+            Self::Tokens { whole, fraction } => (whole, fraction),
+        };
+
+        if fraction.len() > decimals as usize {
+            return Err(BridgeSdkError::InvalidArgument(format!(
+                "Amount `{self}` has more than {decimals} decimal places"
+            )));
+        }
+
+        let scale = 10u128.checked_pow(decimals as u32);
+        let fraction = format!("{fraction:0<width$}", width = decimals as usize);
+        let fraction = if fraction.is_empty() {
+            Some(0)
+        } else {
+            fraction.parse::<u128>().ok()
+        };
+
+        scale
+            .zip(fraction)
+            .and_then(|(scale, fraction)| whole.checked_mul(scale)?.checked_add(fraction))
+            .ok_or_else(|| {
+                BridgeSdkError::InvalidArgument(format!(
+                    "Amount `{self}` with {decimals} decimals is too large"
+                ))
+            })
+    }
+
+    /// Amount in raw units. The decimals are only awaited for amounts in whole tokens, so raw amounts don't need a
+    /// metadata lookup
+    pub async fn resolve(&self, decimals: impl Future<Output = Result<u8>>) -> Result<u128> {
+        match self {
+            Self::Raw(amount) => Ok(*amount),
+            Self::Tokens { .. } => self.to_raw(decimals.await?),
+        }
+    }
+}
+
+impl FromStr for TokenAmount {
+    type Err = BridgeSdkError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            BridgeSdkError::InvalidArgument(format!(
+                "Invalid amount `{s}`, expected raw units, e.g. `1500000`, or whole tokens, e.g. `1.5`"
+            ))
+        };
+        let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+
+        match s.split_once('.') {
+            None => s.parse().map(Self::Raw).map_err(|_| invalid()),
+            Some((whole, fraction))
+                if !(whole.is_empty() && fraction.is_empty())
+                    && is_digits(whole)
+                    && is_digits(fraction) =>
+            {
+                Ok(Self::Tokens {
+                    whole: if whole.is_empty() {
+                        0
+                    } else {
+                        whole.parse().map_err(|_| invalid())?
+                    },
+                    fraction: fraction.trim_end_matches('0').to_string(),
+                })
+            }
+            Some(_) => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for TokenAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Raw(amount) => write!(f, "{amount}"),
+            Self::Tokens { whole, fraction } => write!(f, "{whole}.{fraction}"),
+        }
+    }
+}
+
+/// Decimals of an ERC-20 token, read with `decimals()`
+pub async fn erc20_decimals(eth_endpoint: &str, token_address: Address) -> Result<u8> {
+    let provider = failover::eth_provider(eth_endpoint)?;
+
+    Ok(Erc20Decimals::new(token_address, Arc::new(provider))
+        .decimals()
+        .call()
+        .await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_amount_to_raw() {
+        let amount = |s: &str| s.parse::<TokenAmount>().unwrap();
+
+        assert_eq!(amount("1500000").to_raw(6).unwrap(), 1_500_000);
+        assert_eq!(amount("1.5").to_raw(6).unwrap(), 1_500_000);
+        assert_eq!(amount(".25").to_raw(2).unwrap(), 25);
+        assert_eq!(amount("2.").to_raw(0).unwrap(), 2);
+        assert_eq!(amount("1.50").to_raw(1).unwrap(), 15);
+        assert_eq!(
+            amount("1.5").to_raw(24).unwrap(),
+            1_500_000_000_000_000_000_000_000
+        );
+
+        assert!(amount("1.005").to_raw(2).is_err());
+        assert!(amount("1000000000000000.0").to_raw(24).is_err());
+        assert!(".".parse::<TokenAmount>().is_err());
+        assert!("1.5.0".parse::<TokenAmount>().is_err());
+        assert!("-1".parse::<TokenAmount>().is_err());
+        assert!("1e18".parse::<TokenAmount>().is_err());
+    }
+}
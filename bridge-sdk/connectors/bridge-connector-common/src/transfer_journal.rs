@@ -0,0 +1,187 @@
+use crate::{
+    bridge_connector::{TransactionId, TransferId},
+    nonce_journal,
+    result::{BridgeSdkError, Result},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Stage of a transfer recorded in a [`TransferJournal`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransferStage {
+    /// Initiated on the source chain, not finalized yet
+    Initiated,
+    /// Finalized by the transaction, `None` if the transfer was finalized by another process, e.g. a relayer
+    Finalized(Option<TransactionId>),
+    /// Can't be finalized, e.g. because the initiating transaction failed
+    Failed(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournaledTransfer {
+    pub transfer_id: TransferId,
+    pub stage: TransferStage,
+    /// Unix timestamp in seconds
+    pub updated_at: u64,
+}
+
+/// Persistent record of the transfers initiated by a process, so that a crashed process or a later CLI invocation
+/// can finalize them with [`crate::bridge_connector::BridgeConnector::resume`] instead of losing track of them
+pub trait TransferJournal: Send + Sync {
+    /// Records the stage of the transfer, replacing the previous one
+    fn record(&self, transfer_id: TransferId, stage: TransferStage) -> Result<()>;
+
+    fn load(&self, transfer_id: &TransferId) -> Result<Option<JournaledTransfer>>;
+
+    /// Transfers still at the `Initiated` stage
+    fn unfinished(&self) -> Result<Vec<JournaledTransfer>>;
+}
+
+/// Journal keeping one JSON file per transfer in a directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTransferJournal {
+    dir: PathBuf,
+}
+
+impl FileTransferJournal {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, transfer_id: &TransferId) -> PathBuf {
+        let name = match transfer_id {
+            TransferId::Near { receipt_id } => format!("near-{receipt_id}"),
+            TransferId::Eth { tx_hash, log_index } => format!("eth-{tx_hash:?}-{log_index}"),
+        };
+
+        self.dir.join(format!("{name}.json"))
+    }
+
+    fn read(path: &Path) -> Result<JournaledTransfer> {
+        fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|data| serde_json::from_str(&data).map_err(|err| err.to_string()))
+            .map_err(|err| {
+                BridgeSdkError::IoError(format!(
+                    "Could not read journaled transfer {}: {err}",
+                    path.display()
+                ))
+            })
+    }
+}
+
+impl TransferJournal for FileTransferJournal {
+    fn record(&self, transfer_id: TransferId, stage: TransferStage) -> Result<()> {
+        let transfer = JournaledTransfer {
+            transfer_id,
+            stage,
+            updated_at: nonce_journal::unix_timestamp(),
+        };
+        let data =
+            serde_json::to_string_pretty(&transfer).expect("Journaled transfer always serializes");
+
+        let path = self.path(&transfer_id);
+        // Writing to a temporary file first keeps the previous stage intact if the process is killed mid-write
+        let tmp_path = path.with_extension("tmp");
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(&tmp_path, data))
+            .and_then(|_| fs::rename(&tmp_path, &path))
+            .map_err(|err| {
+                BridgeSdkError::IoError(format!(
+                    "Could not journal transfer {}: {err}",
+                    path.display()
+                ))
+            })
+    }
+
+    fn load(&self, transfer_id: &TransferId) -> Result<Option<JournaledTransfer>> {
+        let path = self.path(transfer_id);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        Self::read(&path).map(Some)
+    }
+
+    fn unfinished(&self) -> Result<Vec<JournaledTransfer>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => {
+                return Err(BridgeSdkError::IoError(format!(
+                    "Could not read transfer journal {}: {err}",
+                    self.dir.display()
+                )))
+            }
+        };
+
+        let mut transfers = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some("json") {
+                continue;
+            }
+
+            let transfer = Self::read(&path)?;
+            if transfer.stage == TransferStage::Initiated {
+                transfers.push(transfer);
+            }
+        }
+
+        transfers.sort_by_key(|transfer| transfer.updated_at);
+        Ok(transfers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::TxHash;
+
+    #[test]
+    fn test_file_transfer_journal() {
+        let journal = FileTransferJournal::new(
+            std::env::temp_dir().join(format!("transfer-journal-{}", std::process::id())),
+        );
+        let transfer_id = TransferId::Eth {
+            tx_hash: TxHash::repeat_byte(1),
+            log_index: 3,
+        };
+        let finalized_id = TransferId::Eth {
+            tx_hash: TxHash::repeat_byte(2),
+            log_index: 0,
+        };
+
+        assert!(journal.unfinished().unwrap().is_empty());
+
+        journal
+            .record(transfer_id, TransferStage::Initiated)
+            .unwrap();
+        journal
+            .record(finalized_id, TransferStage::Initiated)
+            .unwrap();
+        journal
+            .record(
+                finalized_id,
+                TransferStage::Finalized(Some(TransactionId::Eth(TxHash::repeat_byte(3)))),
+            )
+            .unwrap();
+
+        let unfinished = journal.unfinished().unwrap();
+        assert_eq!(unfinished.len(), 1);
+        assert_eq!(unfinished[0].transfer_id, transfer_id);
+        assert_eq!(
+            journal.load(&finalized_id).unwrap().unwrap().stage,
+            TransferStage::Finalized(Some(TransactionId::Eth(TxHash::repeat_byte(3))))
+        );
+
+        fs::remove_dir_all(journal.dir()).unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+use bridge_connector_common::result::{BridgeSdkError, Result};
+use ethers::{
+    abi::{self, ParamType, Token},
+    types::{Address, Log, TxHash, U256},
+};
+
+/// `Deposited` event emitted by the EthCustodian on `depositToNear` and `depositToEVM`, as received by
+/// `subscribe_deposit_events`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositEvent {
+    pub tx_hash: TxHash,
+    /// Index of the event in the block, as proven by `finalize_deposit`
+    pub log_index: u64,
+    pub block_number: u64,
+    pub sender: Address,
+    /// Near account id, or `<eth connector account id>:<address>` for deposits to the Aurora EVM
+    pub recipient: String,
+    pub amount: u128,
+    pub fee: u128,
+}
+
+impl DepositEvent {
+    pub(crate) fn from_log(log: &Log) -> Result<Self> {
+        let invalid =
+            |reason: &str| BridgeSdkError::InvalidEvent(format!("deposit event: {reason}"));
+        let amount =
+            |amount: &U256| u128::try_from(*amount).map_err(|_| invalid("amount overflows u128"));
+
+        // `sender` is indexed
+        let sender = log
+            .topics
+            .get(1)
+            .map(|topic| Address::from(*topic))
+            .ok_or(invalid("missing sender"))?;
+        let fields = abi::decode(
+            &[
+                ParamType::String,
+                ParamType::Uint(256),
+                ParamType::Uint(256),
+            ],
+            &log.data,
+        )
+        .map_err(|_| invalid("invalid data"))?;
+        let [Token::String(recipient), Token::Uint(deposit_amount), Token::Uint(fee)] =
+            fields.as_slice()
+        else {
+            return Err(invalid("invalid data"));
+        };
+
+        Ok(Self {
+            tx_hash: log
+                .transaction_hash
+                .ok_or(invalid("missing transaction hash"))?,
+            log_index: log.log_index.ok_or(invalid("missing log index"))?.as_u64(),
+            block_number: log
+                .block_number
+                .ok_or(invalid("missing block number"))?
+                .as_u64(),
+            sender,
+            recipient: recipient.clone(),
+            amount: amount(deposit_amount)?,
+            fee: amount(fee)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::{H256, U64};
+
+    #[test]
+    fn test_deposit_event_from_log() {
+        let sender = Address::repeat_byte(1);
+        let log = Log {
+            topics: vec![H256::zero(), H256::from(sender)],
+            data: abi::encode(&[
+                Token::String("alice.near".to_string()),
+                Token::Uint(U256::from(1000)),
+                Token::Uint(U256::from(10)),
+            ])
+            .into(),
+            transaction_hash: Some(TxHash::repeat_byte(2)),
+            log_index: Some(U256::from(3)),
+            block_number: Some(U64::from(4)),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            DepositEvent::from_log(&log).unwrap(),
+            DepositEvent {
+                tx_hash: TxHash::repeat_byte(2),
+                log_index: 3,
+                block_number: 4,
+                sender,
+                recipient: "alice.near".to_string(),
+                amount: 1000,
+                fee: 10,
+            }
+        );
+        assert!(DepositEvent::from_log(&Log {
+            topics: vec![H256::zero()],
+            ..log
+        })
+        .is_err());
+    }
+}
@@ -1,13 +1,45 @@
-use borsh::BorshSerialize;
-use bridge_connector_common::result::{BridgeSdkError, Result};
-use ethers::{abi::Address, prelude::*};
+use async_trait::async_trait;
+use borsh::{BorshSchema, BorshSerialize};
+use bridge_connector_common::{
+    bridge_connector::{
+        self, BridgeConnector, FeeEstimate, TokenTransfer, TransactionId, TransferDirection,
+        TransferId, TransferStatus,
+    },
+    chunked_upload,
+    client_cache::ClientCache,
+    contract_call::{self, EthGasEstimate, EthTxOptions, NearTxOptions},
+    eth_events,
+    events::{self, ConnectorEvent, ConnectorEvents},
+    failover,
+    method_names::MethodNames,
+    operations::DisabledOperations,
+    recipient_validation::RecipientValidation,
+    result::{BridgeSdkError, Result},
+    rpc_scheduler::{RpcPriority, RpcScheduler},
+    screening::{self, ScreenedTransfer, TransferScreening},
+    self_check::{self, SelfCheckReport},
+    signer::{
+        self, CallbackEthSigner, CallbackNearSigner, DynEthSigner, EthSigner, EthSignerClient,
+        NearSigner,
+    },
+};
+use eth_light_client_on_near::EthOnNearClient;
+use eth_proof::{EthRPCClient, EthRPCClientOptions, EthRpc, ProofDataSource, ProofOptions};
+use ethers::{
+    abi::{Address, Token},
+    prelude::*,
+};
+use futures::Stream;
 use near_crypto::SecretKey;
 use near_light_client_on_eth::NearOnEthClient;
 use near_primitives::{
     hash::CryptoHash,
     types::{AccountId, TransactionOrReceiptId},
 };
-use std::{str::FromStr, sync::Arc};
+use near_rpc_client::{retry::RetryPolicy, NearRpc, NearRpcClient};
+use std::{path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+
+use crate::deposit_event::DepositEvent;
 
 abigen!(
     EthCustodian,
@@ -18,17 +50,23 @@ abigen!(
     ]"#
 );
 
-#[derive(BorshSerialize)]
+/// Event emitted by the EthCustodian on `depositToNear` and `depositToEVM`, which is proven by `finalize_deposit`
+const DEPOSITED_EVENT: &str = "Deposited(address,string,uint256,uint256)";
+
+#[derive(BorshSerialize, BorshSchema)]
 pub struct WithdrawArgs {
     pub recipient_address: [u8; 20],
     pub amount: u128,
 }
 
-/// Bridging ETH from Ethereum to Near and back
-#[derive(Builder)]
+/// Bridging ETH from Ethereum to Near and back. Clones share the cached clients
+#[derive(Builder, Clone)]
 pub struct EthConnector {
     #[doc = r"Ethereum RPC endpoint. Required for `deposit_to_near`, `deposit_to_evm`, `finalize_withdraw`"]
     eth_endpoint: Option<String>,
+    #[doc = r"Ethereum WebSocket RPC endpoint. Required for `subscribe_deposit_events`"]
+    #[builder(default)]
+    eth_ws_endpoint: Option<String>,
     #[doc = r"Ethereum chain id. Required for `deposit_to_near`, `deposit_to_evm`, `finalize_withdraw`"]
     eth_chain_id: Option<u64>,
     #[doc = r"Ethereum private key. Required for `deposit_to_near`, `deposit_to_evm`, `finalize_withdraw`"]
@@ -45,6 +83,93 @@ pub struct EthConnector {
     eth_connector_account_id: Option<String>,
     #[doc = r"NEAR light client address on Ethereum. Required for `finalize_withdraw`"]
     near_light_client_address: Option<String>,
+    #[doc = r"Operations rejected with `OperationDisabled`, e.g. during an incident"]
+    #[builder(default)]
+    disabled_operations: DisabledOperations,
+    #[doc = r"Contract methods renamed by the deployment, e.g. `withdraw` deployed as `finish_withdraw`"]
+    #[builder(default)]
+    method_names: MethodNames,
+    #[doc = r"Gas and attached deposit of the Near transactions, overriding the connector defaults"]
+    #[builder(default)]
+    near_tx_options: NearTxOptions,
+    #[doc = r"Screening of the transfers before they are submitted, e.g. for sanctions compliance. A rejected transfer fails with `TransferRejected`"]
+    #[builder(default)]
+    transfer_screening: Option<Arc<dyn TransferScreening>>,
+    #[doc = r"Validation of the recipients that likely can't use the bridged tokens, e.g. exchange deposit addresses requiring a tag. Disabled by default"]
+    #[builder(default)]
+    recipient_validation: RecipientValidation,
+    #[doc = r"Simulates transactions instead of sending them and returns the hashes they would have. Ethereum calls are checked with `eth_estimateGas` and `eth_call`, Near calls are run as view calls and checked against the signer balance"]
+    #[builder(default)]
+    dry_run: bool,
+    #[doc = r"Waits for the light client of the destination chain to sync the block of a proven transfer instead of failing. Enabled by default"]
+    #[builder(default = "true")]
+    wait_for_light_client: bool,
+    #[doc = r"How long to wait for a light client to sync before failing"]
+    #[builder(default = "bridge_connector::DEFAULT_LIGHT_CLIENT_SYNC_TIMEOUT")]
+    light_client_sync_timeout: Duration,
+    #[doc = r"Ethereum light client account id on Near. When set, proofs of Ethereum events wait for it to sync the proven block"]
+    #[builder(default)]
+    eth_light_client_account_id: Option<String>,
+    #[doc = r"Custom NEAR signer, e.g. a hardware wallet or a remote signer. Takes precedence over `near_private_key`"]
+    #[builder(default)]
+    custom_near_signer: Option<Arc<dyn NearSigner>>,
+    #[doc = r"Path of an encrypted web3 JSON keystore holding the Ethereum key. Takes precedence over `eth_private_key`"]
+    #[builder(default)]
+    eth_keystore_path: Option<String>,
+    #[doc = r"Password of the Ethereum keystore. Required with `eth_keystore_path`"]
+    #[builder(default)]
+    eth_keystore_password: Option<String>,
+    #[doc = r"Custom Ethereum signer, e.g. a hardware wallet or a remote signer. Takes precedence over `eth_private_key`"]
+    #[builder(default)]
+    custom_eth_signer: Option<Arc<dyn EthSigner>>,
+    #[doc = r"Near RPC serving the requests instead of `near_endpoint`, e.g. a `MockNearRpc` in tests"]
+    #[builder(default)]
+    near_rpc: Option<Arc<dyn NearRpc>>,
+    #[doc = r"Ethereum RPC serving the proof data instead of `eth_endpoint`, e.g. a `MockEthRpc` in tests"]
+    #[builder(default)]
+    eth_rpc: Option<Arc<dyn EthRpc>>,
+    #[doc = r"Retries of the Near requests failing with a transient error. Ignored with `near_rpc`"]
+    #[builder(default)]
+    near_retry_policy: RetryPolicy,
+    #[doc = r"Time each Near transaction is awaited for its outcome, failing if it did. Only broadcast with `None`"]
+    #[builder(default = "Some(signer::DEFAULT_NEAR_OUTCOME_TIMEOUT)")]
+    near_outcome_timeout: Option<Duration>,
+    #[doc = r"Retries, rate limit and response size of the Ethereum proof data requests. Ignored with `eth_rpc`"]
+    #[builder(default)]
+    eth_rpc_options: EthRPCClientOptions,
+    #[doc = r"Block cache and metrics of the generated Ethereum proofs"]
+    #[builder(default)]
+    proof_options: ProofOptions,
+    #[doc = r"Source of the Ethereum blocks the proofs are generated from, e.g. a local node database, instead of `eth_rpc`"]
+    #[builder(default)]
+    proof_data_source: Option<Arc<dyn ProofDataSource>>,
+    #[doc = r"Journal of the strict mode of the Ethereum transactions, see `contract_call::send_eth_call`. Disabled if unset"]
+    #[builder(default)]
+    eth_nonce_journal: Option<PathBuf>,
+    #[doc = r"Observer of the progress of the operations, e.g. transactions submitted and light clients lagging. Events are only logged if unset"]
+    #[builder(default)]
+    events: Option<Arc<dyn ConnectorEvents>>,
+    #[doc = r"Limits of the concurrent requests to each RPC endpoint. Connectors and relayers given the same scheduler share the limits of an endpoint"]
+    #[builder(default = "RpcScheduler::shared()")]
+    rpc_scheduler: Arc<RpcScheduler>,
+    #[builder(setter(skip))]
+    eth_client_cache: ClientCache<Arc<EthSignerClient>>,
+    #[builder(setter(skip))]
+    near_rpc_cache: ClientCache<Arc<dyn NearRpc>>,
+}
+
+impl EthConnectorBuilder {
+    /// Signs the Ethereum transactions with a callback, e.g. a browser wallet or an MPC signing service, instead of a
+    /// key held by the connector
+    pub fn eth_signing_callback(&mut self, signer: CallbackEthSigner) -> &mut Self {
+        self.custom_eth_signer(Some(Arc::new(signer)))
+    }
+
+    /// Signs the Near transactions with a callback, e.g. a wallet app or a custodial signing service, instead of a key
+    /// held by the connector
+    pub fn near_signing_callback(&mut self, signer: CallbackNearSigner) -> &mut Self {
+        self.custom_near_signer(Some(Arc::new(signer)))
+    }
 }
 
 impl EthConnector {
@@ -54,41 +179,113 @@ impl EthConnector {
         &self,
         amount: u128,
         recipient_account_id: String,
+        tx_opts: EthTxOptions,
     ) -> Result<TxHash> {
+        self.disabled_operations.check("deposit_to_near")?;
+        screening::check(self.transfer_screening.as_ref(), || {
+            Ok(ScreenedTransfer {
+                direction: TransferDirection::EthToNear,
+                token: "ETH".to_string(),
+                amount,
+                sender: format!("{:?}", self.eth_signer()?.address()),
+                recipient: recipient_account_id.clone(),
+            })
+        })
+        .await?;
+        self.recipient_validation.check(&recipient_account_id)?;
+
         let eth_custodian = self.eth_custodian()?;
-        let call = eth_custodian
-            .deposit_to_near(recipient_account_id, U256::zero())
+        let call = tx_opts
+            .apply(eth_custodian.deposit_to_near(recipient_account_id, U256::zero()))
             .value(amount);
 
-        let tx = call.send().await?;
+        let tx_hash = contract_call::send_eth_call(
+            &self.eth_client()?,
+            call,
+            tx_opts.confirmations,
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
 
-        tracing::info!(tx_hash = format!("{:?}", tx.tx_hash()), "Sent deposit transaction");
+        tracing::info!(tx_hash = format!("{:?}", tx_hash), "Sent deposit transaction");
 
-        Ok(tx.tx_hash())
+        Ok(tx_hash)
     }
 
     /// Transfers ETH to the EthCustodian and sets recipient as an Aurora EVM account. A proof from this transaction is then used to mint nETH on Aurora
     #[tracing::instrument(skip_all, name = "DEPOSIT TO EVM")]
-    pub async fn deposit_to_evm(&self, amount: u128, recipient_address: String) -> Result<TxHash> {
+    pub async fn deposit_to_evm(
+        &self,
+        amount: u128,
+        recipient_address: String,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("deposit_to_evm")?;
+        screening::check(self.transfer_screening.as_ref(), || {
+            Ok(ScreenedTransfer {
+                direction: TransferDirection::EthToNear,
+                token: "ETH".to_string(),
+                amount,
+                sender: format!("{:?}", self.eth_signer()?.address()),
+                recipient: recipient_address.clone(),
+            })
+        })
+        .await?;
+        self.recipient_validation.check(&recipient_address)?;
+
         let eth_custodian = self.eth_custodian()?;
-        let call = eth_custodian
-            .deposit_to_evm(recipient_address, U256::zero())
+        let call = tx_opts
+            .apply(eth_custodian.deposit_to_evm(recipient_address, U256::zero()))
             .value(amount);
 
-        let tx = call.send().await?;
+        let tx_hash = contract_call::send_eth_call(
+            &self.eth_client()?,
+            call,
+            tx_opts.confirmations,
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
 
-        tracing::info!(tx_hash = format!("{:?}", tx.tx_hash()), "Sent deposit transaction");
+        tracing::info!(tx_hash = format!("{:?}", tx_hash), "Sent deposit transaction");
 
-        Ok(tx.tx_hash())
+        Ok(tx_hash)
     }
 
     /// Generates a proof of the deposit transaction and uses it to mint nETH either on Near or Aurora, depending on the recipient field of the deposit transaction
     #[tracing::instrument(skip_all, name = "FINALIZE DEPOSIT")]
     pub async fn finalize_deposit(&self, tx_hash: TxHash, log_index: u64) -> Result<CryptoHash> {
-        let eth_endpoint = self.eth_endpoint()?;
-        let near_endpoint = self.near_endpoint()?;
+        self.disabled_operations.check("finalize_deposit")?;
+
+        let eth_rpc = self.eth_rpc()?;
+        let near_rpc = self.near_rpc()?;
+
+        self.wait_for_eth_light_client(tx_hash).await?;
 
-        let proof = eth_proof::get_proof_for_event(tx_hash, log_index, eth_endpoint).await?;
+        let proof = self
+            .rpc_scheduler
+            .run(eth_rpc.endpoint(), RpcPriority::Proof, async {
+                Ok(eth_proof::get_proof_for_event_from_rpc_or_source(
+                    eth_rpc.as_ref(),
+                    self.proof_data_source.as_deref(),
+                    tx_hash,
+                    log_index,
+                    &self.proof_options,
+                )
+                .await?)
+            })
+            .await?;
+        events::emit(
+            self.events.as_deref(),
+            ConnectorEvent::ProofGenerated {
+                transfer: TransferId::Eth { tx_hash, log_index },
+            },
+        );
 
         let mut args = Vec::new();
         proof
@@ -97,14 +294,19 @@ impl EthConnector {
 
         tracing::debug!("Retrieved Ethereum proof");
 
-        let tx_hash = near_rpc_client::change(
-            near_endpoint,
+        let tx_hash = chunked_upload::near_change(
+            near_rpc.as_ref(),
             self.near_signer()?,
             self.eth_connector_account_id()?.to_string(),
-            "deposit".to_string(),
+            self.method_names.near("deposit"),
             args,
-            300_000_000_000_000,
+            self.near_tx_options.gas(300_000_000_000_000),
             0,
+            self.dry_run,
+            self.near_tx_options.proof_chunk_size,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
         )
         .await?;
 
@@ -113,10 +315,55 @@ impl EthConnector {
         Ok(tx_hash)
     }
 
+    /// Same as `finalize_deposit`, proving the `Deposited` event the EthCustodian emitted in the transaction
+    #[tracing::instrument(skip_all, name = "FINALIZE DEPOSIT")]
+    pub async fn finalize_deposit_from_tx(&self, tx_hash: TxHash) -> Result<CryptoHash> {
+        self.disabled_operations.check("finalize_deposit")?;
+
+        let log_index = bridge_connector::find_event_log_index(
+            self.eth_endpoint()?,
+            tx_hash,
+            self.eth_custodian_address()?,
+            DEPOSITED_EVENT,
+        )
+        .await?;
+
+        tracing::debug!(log_index, "Found deposit event");
+
+        self.finalize_deposit(tx_hash, log_index).await
+    }
+
+    /// Subscribes to the `Deposited` events of the EthCustodian over the WebSocket endpoint, e.g. to finalize deposits
+    /// as soon as they are made instead of polling for them. Events arrive without waiting for confirmations
+    pub async fn subscribe_deposit_events(&self) -> Result<impl Stream<Item = DepositEvent>> {
+        eth_events::subscribe_events(
+            self.eth_ws_endpoint()?,
+            self.eth_custodian_address()?,
+            DEPOSITED_EVENT,
+            DepositEvent::from_log,
+        )
+        .await
+    }
+
     /// Burns nNEAR on Near. A proof of this transaction is then used to unlock ETH on Ethereum
     #[tracing::instrument(skip_all, name = "WITHDRAW")]
     pub async fn withdraw(&self, amount: u128, recipient_address: Address) -> Result<CryptoHash> {
-        let near_endpoint = self.near_endpoint()?;
+        self.disabled_operations.check("withdraw")?;
+        screening::check(self.transfer_screening.as_ref(), || {
+            Ok(ScreenedTransfer {
+                direction: TransferDirection::NearToEth,
+                token: "ETH".to_string(),
+                amount,
+                sender: self.near_signer()?.account_id().to_string(),
+                recipient: format!("{recipient_address:?}"),
+            })
+        })
+        .await?;
+        self.recipient_validation
+            .check_eth(self.eth_endpoint.as_deref(), recipient_address)
+            .await?;
+
+        let near_rpc = self.near_rpc()?;
         let eth_connector_account_id = self.eth_connector_account_id()?.to_string();
 
         let mut args = Vec::new();
@@ -126,16 +373,20 @@ impl EthConnector {
         };
         args_struct
             .serialize(&mut args)
-            .map_err(|_| BridgeSdkError::UnknownError)?;
+            .map_err(|err| BridgeSdkError::SerializationError(err.to_string()))?;
 
-        let tx_hash = near_rpc_client::change(
-            near_endpoint,
+        let tx_hash = signer::near_change(
+            near_rpc.as_ref(),
             self.near_signer()?,
             eth_connector_account_id,
-            "withdraw".to_string(),
+            self.method_names.near("withdraw"),
             args,
-            300_000_000_000_000,
+            self.near_tx_options.gas(300_000_000_000_000),
             1,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
         )
         .await?;
 
@@ -147,13 +398,216 @@ impl EthConnector {
 
     /// Generates a proof of the withdraw transaction and uses it to unlock ETH on Ethereum
     #[tracing::instrument(skip_all, name = "FINALIZE WITHDRAW")]
-    pub async fn finalize_withdraw(&self, receipt_id: CryptoHash) -> Result<TxHash> {
+    pub async fn finalize_withdraw(
+        &self,
+        receipt_id: CryptoHash,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("finalize_withdraw")?;
+
+        let (proof, proof_block_height) = self.get_near_proof(receipt_id).await?;
+
+        let eth_custodian = self.eth_custodian()?;
+        let call = tx_opts.apply(
+            self.method_names
+                .eth(eth_custodian.withdraw(proof.into(), proof_block_height)),
+        );
+        let tx_hash = contract_call::send_eth_call(
+            &self.eth_client()?,
+            call,
+            tx_opts.confirmations.or(Some(1)),
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(tx_hash = format!("{:?}", tx_hash), "Sent finalize withdraw transaction");
+
+        Ok(tx_hash)
+    }
+
+    /// Estimates the gas and cost of `finalize_withdraw` with the current proof, without sending the transaction
+    #[tracing::instrument(skip_all, name = "ESTIMATE FINALIZE WITHDRAW")]
+    pub async fn estimate_finalize_withdraw_gas(
+        &self,
+        receipt_id: CryptoHash,
+    ) -> Result<EthGasEstimate> {
+        let (proof, proof_block_height) = self.get_near_proof(receipt_id).await?;
+
+        let eth_custodian = self.eth_custodian()?;
+        let call = self
+            .method_names
+            .eth(eth_custodian.withdraw(proof.into(), proof_block_height));
+
+        contract_call::estimate_eth_call(self.eth_endpoint()?, &call).await
+    }
+
+    /// Verifies the configured capabilities: RPC reachability, presence of the bridge contracts and signer balances. Unset configuration values are skipped
+    pub async fn self_check(&self) -> SelfCheckReport {
+        let eth_endpoint = self.eth_endpoint.as_deref();
+        let near_endpoint = self.near_endpoint.as_deref();
+        let mut report = SelfCheckReport::default();
+
+        report.push(
+            "Ethereum RPC",
+            self_check::check_eth_rpc(eth_endpoint).await,
+        );
+        report.push("NEAR RPC", self_check::check_near_rpc(near_endpoint).await);
+        report.push(
+            "EthCustodian",
+            self_check::check_eth_code(
+                eth_endpoint,
+                self.eth_custodian_address
+                    .as_ref()
+                    .map(|_| self.eth_custodian_address()),
+            )
+            .await,
+        );
+        report.push(
+            "NEAR light client",
+            self_check::check_eth_code(
+                eth_endpoint,
+                self.near_light_client_address
+                    .as_ref()
+                    .map(|_| self.near_light_client_address()),
+            )
+            .await,
+        );
+        report.push(
+            "Eth connector",
+            self_check::check_near_code(near_endpoint, self.eth_connector_account_id.as_deref())
+                .await,
+        );
+        report.push(
+            "Ethereum signer balance",
+            self_check::check_eth_balance(
+                eth_endpoint,
+                self.eth_private_key
+                    .as_ref()
+                    .map(|_| self.eth_signer().map(|wallet| wallet.address())),
+            )
+            .await,
+        );
+        report.push(
+            "NEAR signer balance",
+            self_check::check_near_balance(near_endpoint, self.near_signer.as_deref()).await,
+        );
+
+        report
+    }
+
+    /// Sends a transaction calling an arbitrary Ethereum contract function, signed with the configured Ethereum key
+    #[tracing::instrument(skip_all, name = "CALL ETH CONTRACT")]
+    pub async fn call_eth_contract(
+        &self,
+        address: Address,
+        abi_fn: &str,
+        args: Vec<Token>,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("call_eth_contract")?;
+
+        contract_call::call_eth_contract(
+            self.eth_client()?,
+            address,
+            abi_fn,
+            args,
+            tx_opts,
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await
+    }
+
+    /// Calls an arbitrary NEAR contract method, signed with the configured NEAR account
+    #[tracing::instrument(skip_all, name = "CALL NEAR CONTRACT")]
+    pub async fn call_near_contract(
+        &self,
+        account_id: AccountId,
+        method_name: String,
+        args: Vec<u8>,
+        gas: u64,
+        deposit: u128,
+    ) -> Result<CryptoHash> {
+        self.disabled_operations.check("call_near_contract")?;
+
+        let tx_hash = signer::near_change(
+            self.near_rpc()?.as_ref(),
+            self.near_signer()?,
+            account_id.to_string(),
+            method_name,
+            args,
+            gas,
+            deposit,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(tx_hash = format!("{:?}", tx_hash), "Sent contract call transaction");
+
+        Ok(tx_hash)
+    }
+
+    async fn wait_for_eth_light_client(&self, tx_hash: TxHash) -> Result<()> {
+        let Some(eth_light_client_account_id) = &self.eth_light_client_account_id else {
+            return Ok(());
+        };
+        if !self.wait_for_light_client {
+            return Ok(());
+        }
+
+        let eth_on_near_client = EthOnNearClient::new(
+            AccountId::from_str(eth_light_client_account_id).map_err(|_| {
+                BridgeSdkError::ConfigError("Invalid Ethereum light client account id".to_string())
+            })?,
+            self.near_endpoint()?.to_string(),
+        );
+
+        bridge_connector::wait_for_eth_light_client_sync(
+            self.eth_endpoint()?,
+            &eth_on_near_client,
+            tx_hash,
+            self.light_client_sync_timeout,
+            self.events.as_deref(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_near_proof(&self, receipt_id: CryptoHash) -> Result<(Vec<u8>, u64)> {
         let eth_endpoint = self.eth_endpoint()?;
-        let near_endpoint = self.near_endpoint()?;
+        let near_rpc = self.near_rpc()?;
 
         let near_on_eth_client =
             NearOnEthClient::new(self.near_light_client_address()?, eth_endpoint.to_string());
 
+        let transfer = TransferId::Near { receipt_id };
+        let receipt_id = TransactionOrReceiptId::Receipt {
+            receipt_id,
+            receiver_id: AccountId::from_str(self.eth_connector_account_id()?).map_err(|_| {
+                BridgeSdkError::ConfigError("Invalid ETH connector account id".to_string())
+            })?,
+        };
+
+        if self.wait_for_light_client {
+            bridge_connector::wait_for_light_client_sync(
+                near_rpc.as_ref(),
+                &near_on_eth_client,
+                receipt_id.clone(),
+                self.light_client_sync_timeout,
+                self.events.as_deref(),
+            )
+            .await?;
+        }
+
         let proof_block_height = near_on_eth_client.get_sync_height().await?;
         let block_hash = near_on_eth_client
             .get_block_hash(proof_block_height)
@@ -161,19 +615,18 @@ impl EthConnector {
 
         tracing::debug!(proof_block_height, "Retrieved light client block height");
 
-        let receipt_id = TransactionOrReceiptId::Receipt {
-            receipt_id,
-            receiver_id: AccountId::from_str(&self.eth_connector_account_id()?).map_err(|_| {
-                BridgeSdkError::ConfigError("Invalid ETH connector account id".to_string())
-            })?,
-        };
-
-        let proof_data = near_rpc_client::get_light_client_proof(
-            near_endpoint,
-            receipt_id,
-            CryptoHash(block_hash),
-        )
-        .await?;
+        let proof_data = self
+            .rpc_scheduler
+            .run(near_rpc.endpoint(), RpcPriority::Proof, async {
+                Ok(near_rpc
+                    .get_light_client_proof(receipt_id, CryptoHash(block_hash))
+                    .await?)
+            })
+            .await?;
+        events::emit(
+            self.events.as_deref(),
+            ConnectorEvent::ProofGenerated { transfer },
+        );
 
         let mut buffer: Vec<u8> = Vec::new();
         proof_data.serialize(&mut buffer).map_err(|_| {
@@ -182,16 +635,14 @@ impl EthConnector {
 
         tracing::debug!("Retrieved Near proof");
 
-        let eth_custodian = self.eth_custodian()?;
-        let call = eth_custodian.withdraw(buffer.into(), proof_block_height);
-        let tx = call.send().await?;
-
-        tracing::info!(tx_hash = format!("{:?}", tx.tx_hash()), "Sent finalize withdraw transaction");
-
-        Ok(tx.tx_hash())
+        Ok((buffer, proof_block_height))
     }
 
-    fn near_signer(&self) -> Result<near_crypto::InMemorySigner> {
+    fn near_signer(&self) -> Result<Arc<dyn NearSigner>> {
+        if let Some(signer) = &self.custom_near_signer {
+            return Ok(signer.clone());
+        }
+
         let near_private_key =
             self.near_private_key
                 .as_ref()
@@ -205,29 +656,45 @@ impl EthConnector {
                 "Near signer account id is not set".to_string(),
             ))?;
 
-        Ok(near_crypto::InMemorySigner::from_secret_key(
+        Ok(Arc::new(near_crypto::InMemorySigner::from_secret_key(
             AccountId::from_str(near_signer).map_err(|_| {
                 BridgeSdkError::ConfigError("Invalid near signer account id".to_string())
             })?,
             SecretKey::from_str(near_private_key)
                 .map_err(|_| BridgeSdkError::ConfigError("Invalid near private key".to_string()))?,
-        ))
+        )))
     }
 
-    fn eth_custodian(&self) -> Result<EthCustodian<SignerMiddleware<Provider<Http>, LocalWallet>>> {
-        let eth_provider = Provider::<Http>::try_from(self.eth_endpoint()?).map_err(|_| {
-            BridgeSdkError::ConfigError("Invalid ethereum rpc endpoint url".to_string())
-        })?;
+    fn eth_custodian(&self) -> Result<EthCustodian<EthSignerClient>> {
+        Ok(EthCustodian::new(
+            self.eth_custodian_address()?,
+            self.eth_client()?,
+        ))
+    }
 
-        let wallet = self.eth_signer()?;
+    fn eth_client(&self) -> Result<Arc<EthSignerClient>> {
+        self.eth_client_cache.get_or_try_init(|| {
+            let eth_provider = failover::eth_provider(self.eth_endpoint()?)?;
 
-        let signer = SignerMiddleware::new(eth_provider, wallet);
-        let client = Arc::new(signer);
+            let wallet = self.eth_signer()?;
 
-        Ok(EthCustodian::new(self.eth_custodian_address()?, client))
+            Ok(Arc::new(SignerMiddleware::new(eth_provider, wallet)))
+        })
     }
 
-    fn eth_signer(&self) -> Result<LocalWallet> {
+    fn eth_signer(&self) -> Result<DynEthSigner> {
+        if let Some(signer) = &self.custom_eth_signer {
+            return Ok(DynEthSigner::new(signer.clone()));
+        }
+
+        if let Some(eth_keystore_path) = &self.eth_keystore_path {
+            return signer::keystore_signer(
+                eth_keystore_path,
+                self.eth_keystore_password.as_deref(),
+                self.eth_chain_id,
+            );
+        }
+
         let eth_private_key = self
             .eth_private_key
             .as_ref()
@@ -255,9 +722,11 @@ impl EthConnector {
             ));
         }
 
-        Ok(LocalWallet::from_bytes(&private_key_bytes)
+        let wallet = LocalWallet::from_bytes(&private_key_bytes)
             .map_err(|_| BridgeSdkError::ConfigError("Invalid ethereum private key".to_string()))?
-            .with_chain_id(eth_chain_id))
+            .with_chain_id(eth_chain_id);
+
+        Ok(DynEthSigner::new(Arc::new(wallet)))
     }
 
     fn near_light_client_address(&self) -> Result<Address> {
@@ -284,6 +753,15 @@ impl EthConnector {
             ))?)
     }
 
+    fn eth_ws_endpoint(&self) -> Result<&str> {
+        Ok(self
+            .eth_ws_endpoint
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Ethereum WebSocket endpoint is not set".to_string(),
+            ))?)
+    }
+
     fn eth_custodian_address(&self) -> Result<Address> {
         self.eth_custodian_address
             .as_ref()
@@ -298,6 +776,16 @@ impl EthConnector {
             })
     }
 
+    fn eth_rpc(&self) -> Result<Arc<dyn EthRpc>> {
+        match &self.eth_rpc {
+            Some(eth_rpc) => Ok(eth_rpc.clone()),
+            None => Ok(Arc::new(EthRPCClient::new_with_options(
+                self.eth_endpoint()?,
+                self.eth_rpc_options.clone(),
+            ))),
+        }
+    }
+
     fn eth_endpoint(&self) -> Result<&str> {
         Ok(self
             .eth_endpoint
@@ -307,6 +795,19 @@ impl EthConnector {
             ))?)
     }
 
+    fn near_rpc(&self) -> Result<Arc<dyn NearRpc>> {
+        match &self.near_rpc {
+            Some(near_rpc) => Ok(near_rpc.clone()),
+            // Cached, so that the operations share the health of the endpoints
+            None => self.near_rpc_cache.get_or_try_init(|| {
+                Ok(Arc::new(
+                    NearRpcClient::new(self.near_endpoint()?)
+                        .with_retry_policy(self.near_retry_policy),
+                ))
+            }),
+        }
+    }
+
     fn near_endpoint(&self) -> Result<&str> {
         Ok(self
             .near_endpoint
@@ -316,3 +817,219 @@ impl EthConnector {
             ))?)
     }
 }
+
+/// The connector only bridges ETH, so the token of the transfer request is ignored
+#[async_trait]
+impl BridgeConnector for EthConnector {
+    type TransferRequest = TokenTransfer;
+
+    async fn initiate_transfer(&self, request: TokenTransfer) -> Result<TransactionId> {
+        match request.direction {
+            TransferDirection::EthToNear => Ok(TransactionId::Eth(
+                self.deposit_to_near(request.amount, request.recipient, EthTxOptions::default())
+                    .await?,
+            )),
+            TransferDirection::NearToEth => {
+                let recipient_address = Address::from_str(&request.recipient).map_err(|_| {
+                    BridgeSdkError::InvalidArgument("Invalid recipient address".to_string())
+                })?;
+
+                Ok(TransactionId::Near(
+                    self.withdraw(request.amount, recipient_address).await?,
+                ))
+            }
+        }
+    }
+
+    async fn finalize_transfer(&self, transfer_id: TransferId) -> Result<TransactionId> {
+        match transfer_id {
+            TransferId::Eth { tx_hash, log_index } => Ok(TransactionId::Near(
+                self.finalize_deposit(tx_hash, log_index).await?,
+            )),
+            TransferId::Near { receipt_id } => Ok(TransactionId::Eth(
+                self.finalize_withdraw(receipt_id, EthTxOptions::default())
+                    .await?,
+            )),
+        }
+    }
+
+    async fn get_status(&self, transfer_id: TransferId) -> Result<TransferStatus> {
+        match transfer_id {
+            TransferId::Eth { tx_hash, .. } => {
+                bridge_connector::eth_transfer_status(self.eth_endpoint()?, tx_hash).await
+            }
+            TransferId::Near { receipt_id } => {
+                bridge_connector::near_transfer_status(
+                    self.near_rpc()?.as_ref(),
+                    self.eth_endpoint()?,
+                    self.near_light_client_address()?,
+                    receipt_id,
+                    AccountId::from_str(self.eth_connector_account_id()?).map_err(|_| {
+                        BridgeSdkError::ConfigError("Invalid eth connector account id".to_string())
+                    })?,
+                )
+                .await
+            }
+        }
+    }
+
+    async fn estimate_fees(&self, request: &TokenTransfer) -> Result<FeeEstimate> {
+        let near_deposit = match request.direction {
+            TransferDirection::EthToNear => 0,
+            TransferDirection::NearToEth => 1,
+        };
+
+        Ok(FeeEstimate {
+            near_deposit,
+            near_gas: self.near_tx_options.gas(300_000_000_000_000),
+            token_fee: 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use borsh::BorshDeserialize;
+    use eth_proof::{MockEthRpc, Proof};
+    use ethers::utils::keccak256;
+    use near_crypto::{InMemorySigner, KeyType};
+    use near_primitives::transaction::{Action, FunctionCallAction};
+    use near_rpc_client::MockNearRpc;
+    use serde_json::json;
+
+    const ETH_CONNECTOR_ACCOUNT_ID: &str = "aurora";
+
+    fn connector(near_rpc: Arc<MockNearRpc>, eth_rpc: Arc<MockEthRpc>) -> EthConnector {
+        let signer = InMemorySigner::from_seed(
+            "relayer.near".parse().unwrap(),
+            KeyType::ED25519,
+            "relayer.near",
+        );
+
+        EthConnectorBuilder::default()
+            .eth_endpoint(None)
+            .eth_chain_id(None)
+            .eth_private_key(None)
+            .eth_custodian_address(Some(format!("{:?}", custodian_address())))
+            .near_endpoint(None)
+            .near_private_key(None)
+            .near_signer(None)
+            .eth_connector_account_id(Some(ETH_CONNECTOR_ACCOUNT_ID.to_string()))
+            .near_light_client_address(None)
+            .custom_near_signer(Some(Arc::new(signer)))
+            .near_rpc(Some(near_rpc))
+            .eth_rpc(Some(eth_rpc))
+            .build()
+            .unwrap()
+    }
+
+    fn custodian_address() -> Address {
+        Address::repeat_byte(1)
+    }
+
+    /// Receipt of a deposit emitting `Deposited` as its log 3, alone in block 16
+    fn mock_deposit(eth_rpc: &MockEthRpc, tx_hash: TxHash) {
+        let hash = format!("{:#x}", H256::zero());
+        let bloom = format!("0x{}", "00".repeat(256));
+
+        eth_rpc.add_receipt(
+            tx_hash,
+            json!({
+                "blockHash": hash,
+                "blockNumber": "0x10",
+                "transactionIndex": "0x0",
+                "type": "0x2",
+                "cumulativeGasUsed": "0x5208",
+                "logsBloom": bloom,
+                "logs": [{
+                    "address": format!("{:#x}", custodian_address()),
+                    "topics": [format!("{:#x}", H256(keccak256(DEPOSITED_EVENT)))],
+                    "data": "0x",
+                    "logIndex": "0x3",
+                }],
+                "status": "0x1",
+            }),
+        );
+        eth_rpc.add_block(
+            16,
+            json!({
+                "parentHash": hash,
+                "sha3Uncles": hash,
+                "miner": format!("{:#x}", Address::zero()),
+                "stateRoot": hash,
+                "transactionsRoot": hash,
+                "receiptsRoot": hash,
+                "logsBloom": bloom,
+                "difficulty": "0x0",
+                "number": "0x10",
+                "gasLimit": "0x1c9c380",
+                "gasUsed": "0x5208",
+                "timestamp": "0x65f1b057",
+                "extraData": "0x",
+                "mixHash": hash,
+                "nonce": "0x0000000000000000",
+            }),
+        );
+    }
+
+    fn function_call(action: &Action) -> &FunctionCallAction {
+        match action {
+            Action::FunctionCall(call) => call,
+            action => panic!("Expected a function call, got {action:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_finalize_deposit_proves_event_from_eth_rpc() {
+        let near_rpc = Arc::new(MockNearRpc::new());
+        let eth_rpc = Arc::new(MockEthRpc::new(20));
+        let tx_hash = TxHash::repeat_byte(7);
+        mock_deposit(&eth_rpc, tx_hash);
+
+        let near_tx_hash = connector(near_rpc.clone(), eth_rpc)
+            .finalize_deposit(tx_hash, 3)
+            .await
+            .unwrap();
+
+        let sent = near_rpc.sent_transactions();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].get_hash(), near_tx_hash);
+        assert_eq!(
+            sent[0].transaction.receiver_id.as_str(),
+            ETH_CONNECTOR_ACCOUNT_ID
+        );
+
+        let call = function_call(&sent[0].transaction.actions[0]);
+        assert_eq!(call.method_name, "deposit");
+        let proof = Proof::try_from_slice(&call.args).unwrap();
+        assert_eq!((proof.log_index, proof.receipt_index), (0, 0));
+        assert!(!proof.proof.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_withdraw_sends_call_through_near_rpc() {
+        let near_rpc = Arc::new(MockNearRpc::new());
+        let recipient = Address::repeat_byte(2);
+
+        let tx_hash = connector(near_rpc.clone(), Arc::new(MockEthRpc::new(20)))
+            .withdraw(100, recipient)
+            .await
+            .unwrap();
+
+        let sent = near_rpc.sent_transactions();
+        assert_eq!(sent[0].get_hash(), tx_hash);
+
+        let call = function_call(&sent[0].transaction.actions[0]);
+        assert_eq!(call.method_name, "withdraw");
+        assert_eq!(call.deposit, 1);
+        assert_eq!(
+            call.args,
+            borsh::to_vec(&WithdrawArgs {
+                recipient_address: recipient.to_fixed_bytes(),
+                amount: 100,
+            })
+            .unwrap()
+        );
+    }
+}
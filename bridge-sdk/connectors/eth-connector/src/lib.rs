@@ -1,6 +1,8 @@
 #[macro_use]
 extern crate derive_builder;
 
+mod deposit_event;
 mod eth_connector;
 
-pub use eth_connector::{EthConnector, EthConnectorBuilder};
+pub use deposit_event::DepositEvent;
+pub use eth_connector::{EthConnector, EthConnectorBuilder, WithdrawArgs};
@@ -1,11 +1,36 @@
+use async_trait::async_trait;
 use base64::prelude::*;
-use borsh::BorshSerialize;
-use bridge_connector_common::result::{BridgeSdkError, Result};
+use borsh::{BorshSchema, BorshSerialize};
+use bridge_connector_common::{
+    bridge_connector::{
+        self, BridgeConnector, FeeEstimate, TransactionId, TransferDirection, TransferId,
+        TransferStatus,
+    },
+    chunked_upload,
+    client_cache::ClientCache,
+    contract_call::{self, EthGasEstimate, EthTxOptions, NearTxOptions},
+    events::{self, ConnectorEvent, ConnectorEvents},
+    failover,
+    method_names::MethodNames,
+    near_args::{self, FtTransferCallArgs},
+    operations::DisabledOperations,
+    result::{BridgeSdkError, Result},
+    rpc_scheduler::{RpcPriority, RpcScheduler},
+    screening::{self, ScreenedTransfer, TransferScreening},
+    self_check::{self, SelfCheckReport},
+    signer::{
+        self, CallbackEthSigner, CallbackNearSigner, DynEthSigner, EthSigner, EthSignerClient,
+        NearSigner,
+    },
+};
 use derive_builder::Builder;
-use ethers::prelude::*;
+use eth_proof::{EthRPCClient, EthRPCClientOptions, EthRpc, ProofDataSource, ProofOptions};
+use ethers::{abi::Token, prelude::*};
 use near_crypto::SecretKey;
 use near_primitives::{hash::CryptoHash, types::AccountId};
-use std::{str::FromStr, sync::Arc};
+use near_rpc_client::{borsh_schema, retry::RetryPolicy, NearRpc, NearRpcClient};
+use serde::Serialize;
+use std::{path::PathBuf, str::FromStr, sync::Arc, time::Duration};
 
 abigen!(
     FastBridgeContract,
@@ -14,23 +39,31 @@ abigen!(
     ]"#
 );
 
-#[derive(BorshSerialize, Debug, Clone, Copy, PartialEq)]
+#[derive(BorshSerialize, BorshSchema, Debug, Clone, Copy, PartialEq)]
 pub struct EthAddress(pub [u8; 20]);
 
-#[derive(BorshSerialize, Debug, Clone, PartialEq)]
+#[derive(BorshSerialize, BorshSchema, Debug, Clone, PartialEq)]
 pub struct TransferDataEthereum {
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::AccountId>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::AccountId>"
+    )))]
     pub token_near: AccountId,
     pub token_eth: EthAddress,
     pub amount: u128,
 }
 
-#[derive(BorshSerialize, Debug, Clone, PartialEq)]
+#[derive(BorshSerialize, BorshSchema, Debug, Clone, PartialEq)]
 pub struct TransferDataNear {
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::AccountId>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::AccountId>"
+    )))]
     pub token: AccountId,
     pub amount: u128,
 }
 
-#[derive(BorshSerialize, Debug, Clone, PartialEq)]
+#[derive(BorshSerialize, BorshSchema, Debug, Clone, PartialEq)]
 pub struct TransferMessage {
     pub valid_till: u64,
     pub transfer: TransferDataEthereum,
@@ -40,7 +73,37 @@ pub struct TransferMessage {
     pub aurora_sender: Option<EthAddress>,
 }
 
-#[derive(Builder)]
+/// Arguments of `lp_unlock` of the fast bridge contract
+#[derive(Serialize)]
+struct LpUnlockArgs {
+    proof: eth_proof::Proof,
+}
+
+/// Arguments of `withdraw` of the fast bridge contract. Unset arguments are omitted
+#[derive(Serialize)]
+struct WithdrawArgs {
+    token_id: AccountId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recipient_id: Option<AccountId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    msg: Option<String>,
+}
+
+/// Parameters of a fast bridge transfer from Near to Ethereum, see `FastBridge::transfer`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FastBridgeTransfer {
+    pub token_id: AccountId,
+    pub amount: u128,
+    pub fee_amount: u128,
+    pub eth_token_address: Address,
+    pub recipient: Address,
+    pub valid_till: u64,
+}
+
+/// Fast transfers from Near to Ethereum, completed by a liquidity provider. Clones share the cached clients
+#[derive(Builder, Clone)]
 pub struct FastBridge {
     #[doc = r"Ethereum RPC endpoint. Required for `transfer_on_eth`, `lp_unlock`"]
     eth_endpoint: Option<String>,
@@ -58,6 +121,81 @@ pub struct FastBridge {
     fast_bridge_account_id: Option<String>,
     #[doc = r"Fast bridge address on Ethereum. Required for `transfer_on_eth`"]
     fast_bridge_address: Option<String>,
+    #[doc = r"Operations rejected with `OperationDisabled`, e.g. during an incident"]
+    #[builder(default)]
+    disabled_operations: DisabledOperations,
+    #[doc = r"Contract methods renamed by the deployment, e.g. `withdraw` deployed as `finish_withdraw`"]
+    #[builder(default)]
+    method_names: MethodNames,
+    #[doc = r"Gas and attached deposit of the Near transactions, overriding the connector defaults"]
+    #[builder(default)]
+    near_tx_options: NearTxOptions,
+    #[doc = r"Screening of the transfers before they are submitted, e.g. for sanctions compliance. A rejected transfer fails with `TransferRejected`"]
+    #[builder(default)]
+    transfer_screening: Option<Arc<dyn TransferScreening>>,
+    #[doc = r"Simulates transactions instead of sending them and returns the hashes they would have. Ethereum calls are checked with `eth_estimateGas` and `eth_call`, Near calls are run as view calls and checked against the signer balance"]
+    #[builder(default)]
+    dry_run: bool,
+    #[doc = r"Custom NEAR signer, e.g. a hardware wallet or a remote signer. Takes precedence over `near_private_key`"]
+    #[builder(default)]
+    custom_near_signer: Option<Arc<dyn NearSigner>>,
+    #[doc = r"Path of an encrypted web3 JSON keystore holding the Ethereum key. Takes precedence over `eth_private_key`"]
+    #[builder(default)]
+    eth_keystore_path: Option<String>,
+    #[doc = r"Password of the Ethereum keystore. Required with `eth_keystore_path`"]
+    #[builder(default)]
+    eth_keystore_password: Option<String>,
+    #[doc = r"Custom Ethereum signer, e.g. a hardware wallet or a remote signer. Takes precedence over `eth_private_key`"]
+    #[builder(default)]
+    custom_eth_signer: Option<Arc<dyn EthSigner>>,
+    #[doc = r"Near RPC serving the requests instead of `near_endpoint`, e.g. a `MockNearRpc` in tests"]
+    #[builder(default)]
+    near_rpc: Option<Arc<dyn NearRpc>>,
+    #[doc = r"Ethereum RPC serving the proof data instead of `eth_endpoint`, e.g. a `MockEthRpc` in tests"]
+    #[builder(default)]
+    eth_rpc: Option<Arc<dyn EthRpc>>,
+    #[doc = r"Retries of the Near requests failing with a transient error. Ignored with `near_rpc`"]
+    #[builder(default)]
+    near_retry_policy: RetryPolicy,
+    #[doc = r"Time each Near transaction is awaited for its outcome, failing if it did. Only broadcast with `None`"]
+    #[builder(default = "Some(signer::DEFAULT_NEAR_OUTCOME_TIMEOUT)")]
+    near_outcome_timeout: Option<Duration>,
+    #[doc = r"Retries, rate limit and response size of the Ethereum proof data requests. Ignored with `eth_rpc`"]
+    #[builder(default)]
+    eth_rpc_options: EthRPCClientOptions,
+    #[doc = r"Block cache and metrics of the generated Ethereum proofs"]
+    #[builder(default)]
+    proof_options: ProofOptions,
+    #[doc = r"Source of the Ethereum blocks the proofs are generated from, e.g. a local node database, instead of `eth_rpc`"]
+    #[builder(default)]
+    proof_data_source: Option<Arc<dyn ProofDataSource>>,
+    #[doc = r"Journal of the strict mode of the Ethereum transactions, see `contract_call::send_eth_call`. Disabled if unset"]
+    #[builder(default)]
+    eth_nonce_journal: Option<PathBuf>,
+    #[doc = r"Observer of the progress of the operations, e.g. transactions submitted and light clients lagging. Events are only logged if unset"]
+    #[builder(default)]
+    events: Option<Arc<dyn ConnectorEvents>>,
+    #[doc = r"Limits of the concurrent requests to each RPC endpoint. Connectors and relayers given the same scheduler share the limits of an endpoint"]
+    #[builder(default = "RpcScheduler::shared()")]
+    rpc_scheduler: Arc<RpcScheduler>,
+    #[builder(setter(skip))]
+    eth_client_cache: ClientCache<Arc<EthSignerClient>>,
+    #[builder(setter(skip))]
+    near_rpc_cache: ClientCache<Arc<dyn NearRpc>>,
+}
+
+impl FastBridgeBuilder {
+    /// Signs the Ethereum transactions with a callback, e.g. a browser wallet or an MPC signing service, instead of a
+    /// key held by the connector
+    pub fn eth_signing_callback(&mut self, signer: CallbackEthSigner) -> &mut Self {
+        self.custom_eth_signer(Some(Arc::new(signer)))
+    }
+
+    /// Signs the Near transactions with a callback, e.g. a wallet app or a custodial signing service, instead of a key
+    /// held by the connector
+    pub fn near_signing_callback(&mut self, signer: CallbackNearSigner) -> &mut Self {
+        self.custom_near_signer(Some(Arc::new(signer)))
+    }
 }
 
 impl FastBridge {
@@ -72,7 +210,19 @@ impl FastBridge {
         recipient: Address,
         valid_till: u64,
     ) -> Result<CryptoHash> {
-        let near_endpoint = self.near_endpoint()?;
+        self.disabled_operations.check("transfer")?;
+        screening::check(self.transfer_screening.as_ref(), || {
+            Ok(ScreenedTransfer {
+                direction: TransferDirection::NearToEth,
+                token: token_id.to_string(),
+                amount,
+                sender: self.near_signer()?.account_id().to_string(),
+                recipient: format!("{recipient:?}"),
+            })
+        })
+        .await?;
+
+        let near_rpc = self.near_rpc()?;
         let fast_bridge_account_id = self.fast_bridge_account_id()?.to_string();
 
         let message = TransferMessage {
@@ -94,23 +244,27 @@ impl FastBridge {
         let mut buffer: Vec<u8> = Vec::new();
         message
             .serialize(&mut buffer)
-            .map_err(|_| BridgeSdkError::UnknownError)?;
+            .map_err(|err| BridgeSdkError::SerializationError(err.to_string()))?;
         let msg = BASE64_STANDARD.encode(&buffer);
 
-        let args = format!(
-            r#"{{"receiver_id":"{fast_bridge_account_id}","amount":"{amount}","msg":"{msg}"}}"#
-        )
-        .to_string()
-        .into_bytes();
+        let args = near_args::to_bytes(&FtTransferCallArgs {
+            receiver_id: fast_bridge_account_id,
+            amount,
+            msg,
+        });
 
-        let tx_hash = near_rpc_client::change(
-            near_endpoint,
+        let tx_hash = signer::near_change(
+            near_rpc.as_ref(),
             self.near_signer()?,
             token_id.to_string(),
-            "ft_transfer_call".to_string(),
+            self.method_names.near("ft_transfer_call"),
             args,
-            200_000_000_000_000,
+            self.near_tx_options.gas(200_000_000_000_000),
             1,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
         )
         .await?;
 
@@ -123,6 +277,7 @@ impl FastBridge {
     }
 
     /// Completes fast bridge transfer by sending tokens to the recipient on Ethereum. The proof from this transaction is to be used to unlock tokens on NEAR for unlock_recipient
+    #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(skip_all, name = "TRANSFER ON ETH")]
     pub async fn complete_transfer_on_eth(
         &self,
@@ -132,52 +287,113 @@ impl FastBridge {
         amount: U256,
         unlock_recipient: String,
         valid_till_block_height: U256,
+        tx_opts: EthTxOptions,
     ) -> Result<TxHash> {
+        self.disabled_operations.check("complete_transfer_on_eth")?;
+
         let fast_bridge = self.fast_bridge_contract()?;
-        let transfer_call = fast_bridge
-            .transfer_tokens(
+        let transfer_call = tx_opts
+            .apply(self.method_names.eth(fast_bridge.transfer_tokens(
                 token,
                 recipient,
                 nonce,
                 amount,
                 unlock_recipient,
                 valid_till_block_height,
-            )
+            )))
             .value(amount);
 
-        let tx = transfer_call.send().await?;
+        let tx_hash = contract_call::send_eth_call(
+            &self.eth_client()?,
+            transfer_call,
+            tx_opts.confirmations.or(Some(1)),
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
 
         tracing::info!(
-            tx_hash = format!("{:?}", tx.tx_hash()),
+            tx_hash = format!("{:?}", tx_hash),
             "Completed fast bridge transfer"
         );
 
-        Ok(tx.tx_hash())
+        Ok(tx_hash)
+    }
+
+    /// Estimates the gas and cost of `complete_transfer_on_eth` without sending the transaction, so that liquidity providers can decide whether a transfer is worth completing
+    #[tracing::instrument(skip_all, name = "ESTIMATE TRANSFER ON ETH")]
+    pub async fn estimate_complete_transfer_on_eth_gas(
+        &self,
+        token: Address,
+        recipient: Address,
+        nonce: U256,
+        amount: U256,
+        unlock_recipient: String,
+        valid_till_block_height: U256,
+    ) -> Result<EthGasEstimate> {
+        let fast_bridge = self.fast_bridge_contract()?;
+        let transfer_call = self
+            .method_names
+            .eth(fast_bridge.transfer_tokens(
+                token,
+                recipient,
+                nonce,
+                amount,
+                unlock_recipient,
+                valid_till_block_height,
+            ))
+            .value(amount);
+
+        contract_call::estimate_eth_call(self.eth_endpoint()?, &transfer_call).await
     }
 
     /// Unlocks tokens on Near following a successful transfer completion on Ethereum.
     #[tracing::instrument(skip_all, name = "LP UNLOCK")]
     pub async fn lp_unlock(&self, tx_hash: TxHash, log_index: u64) -> Result<CryptoHash> {
-        let eth_endpoint = self.eth_endpoint()?;
-        let near_endpoint = self.near_endpoint()?;
-
-        let proof = eth_proof::get_proof_for_event(tx_hash, log_index, eth_endpoint).await?;
+        self.disabled_operations.check("lp_unlock")?;
+
+        let eth_rpc = self.eth_rpc()?;
+        let near_rpc = self.near_rpc()?;
+
+        let proof = self
+            .rpc_scheduler
+            .run(eth_rpc.endpoint(), RpcPriority::Proof, async {
+                Ok(eth_proof::get_proof_for_event_from_rpc_or_source(
+                    eth_rpc.as_ref(),
+                    self.proof_data_source.as_deref(),
+                    tx_hash,
+                    log_index,
+                    &self.proof_options,
+                )
+                .await?)
+            })
+            .await?;
+        events::emit(
+            self.events.as_deref(),
+            ConnectorEvent::ProofGenerated {
+                transfer: TransferId::Eth { tx_hash, log_index },
+            },
+        );
 
-        let serialized_proof = serde_json::to_string(&proof).unwrap();
-        let args = format!(r#"{{"proof":{serialized_proof}}}"#)
-            .to_string()
-            .into_bytes();
+        let args = near_args::to_bytes(&LpUnlockArgs { proof });
 
         tracing::debug!("Retrieved Ethereum proof");
 
-        let tx_hash = near_rpc_client::change(
-            near_endpoint,
+        let tx_hash = chunked_upload::near_change(
+            near_rpc.as_ref(),
             self.near_signer()?,
             self.fast_bridge_account_id()?.to_string(),
-            "lp_unlock".to_string(),
+            self.method_names.near("lp_unlock"),
             args,
-            120_000_000_000_000,
+            self.near_tx_options.gas(120_000_000_000_000),
             0,
+            self.dry_run,
+            self.near_tx_options.proof_chunk_size,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
         )
         .await?;
 
@@ -198,30 +414,29 @@ impl FastBridge {
         recipient_id: Option<AccountId>,
         msg: Option<String>,
     ) -> Result<CryptoHash> {
-        let near_endpoint = self.near_endpoint()?;
+        self.disabled_operations.check("withdraw")?;
 
-        let mut json = format!(r#"{{"token_id": "{token_id}""#);
-        if let Some(recipient_id) = recipient_id {
-            json.push_str(&format!(r#","recipient_id": "{recipient_id}""#));
-        }
-        if let Some(amount) = amount {
-            json.push_str(&format!(r#","amount": "{amount}""#));
-        }
-        if let Some(msg) = msg {
-            json.push_str(&format!(r#","msg": "{msg}""#));
-        }
-        json.push_str("}");
+        let near_rpc = self.near_rpc()?;
 
-        let args = json.to_string().into_bytes();
+        let args = near_args::to_bytes(&WithdrawArgs {
+            token_id,
+            recipient_id,
+            amount: amount.map(|amount| amount.to_string()),
+            msg,
+        });
 
-        let tx_hash = near_rpc_client::change(
-            near_endpoint,
+        let tx_hash = signer::near_change(
+            near_rpc.as_ref(),
             self.near_signer()?,
             self.fast_bridge_account_id()?.to_string(),
-            "withdraw".to_string(),
+            self.method_names.near("withdraw"),
             args,
-            20_000_000_000_000,
+            self.near_tx_options.gas(20_000_000_000_000),
             0,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
         )
         .await?;
 
@@ -233,7 +448,112 @@ impl FastBridge {
         Ok(tx_hash)
     }
 
-    fn near_signer(&self) -> Result<near_crypto::InMemorySigner> {
+    /// Verifies the configured capabilities: RPC reachability, presence of the bridge contracts and signer balances. Unset configuration values are skipped
+    pub async fn self_check(&self) -> SelfCheckReport {
+        let eth_endpoint = self.eth_endpoint.as_deref();
+        let near_endpoint = self.near_endpoint.as_deref();
+        let mut report = SelfCheckReport::default();
+
+        report.push(
+            "Ethereum RPC",
+            self_check::check_eth_rpc(eth_endpoint).await,
+        );
+        report.push("NEAR RPC", self_check::check_near_rpc(near_endpoint).await);
+        report.push(
+            "Fast bridge contract",
+            self_check::check_eth_code(
+                eth_endpoint,
+                self.fast_bridge_address
+                    .as_ref()
+                    .map(|_| self.fast_bridge_address()),
+            )
+            .await,
+        );
+        report.push(
+            "Fast bridge",
+            self_check::check_near_code(near_endpoint, self.fast_bridge_account_id.as_deref())
+                .await,
+        );
+        report.push(
+            "Ethereum signer balance",
+            self_check::check_eth_balance(
+                eth_endpoint,
+                self.eth_private_key
+                    .as_ref()
+                    .map(|_| self.eth_signer().map(|wallet| wallet.address())),
+            )
+            .await,
+        );
+        report.push(
+            "NEAR signer balance",
+            self_check::check_near_balance(near_endpoint, self.near_signer.as_deref()).await,
+        );
+
+        report
+    }
+
+    /// Sends a transaction calling an arbitrary Ethereum contract function, signed with the configured Ethereum key
+    #[tracing::instrument(skip_all, name = "CALL ETH CONTRACT")]
+    pub async fn call_eth_contract(
+        &self,
+        address: Address,
+        abi_fn: &str,
+        args: Vec<Token>,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("call_eth_contract")?;
+
+        contract_call::call_eth_contract(
+            self.eth_client()?,
+            address,
+            abi_fn,
+            args,
+            tx_opts,
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await
+    }
+
+    /// Calls an arbitrary NEAR contract method, signed with the configured NEAR account
+    #[tracing::instrument(skip_all, name = "CALL NEAR CONTRACT")]
+    pub async fn call_near_contract(
+        &self,
+        account_id: AccountId,
+        method_name: String,
+        args: Vec<u8>,
+        gas: u64,
+        deposit: u128,
+    ) -> Result<CryptoHash> {
+        self.disabled_operations.check("call_near_contract")?;
+
+        let tx_hash = signer::near_change(
+            self.near_rpc()?.as_ref(),
+            self.near_signer()?,
+            account_id.to_string(),
+            method_name,
+            args,
+            gas,
+            deposit,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(tx_hash = format!("{:?}", tx_hash), "Sent contract call transaction");
+
+        Ok(tx_hash)
+    }
+
+    fn near_signer(&self) -> Result<Arc<dyn NearSigner>> {
+        if let Some(signer) = &self.custom_near_signer {
+            return Ok(signer.clone());
+        }
+
         let near_private_key =
             self.near_private_key
                 .as_ref()
@@ -247,38 +567,45 @@ impl FastBridge {
                 "Near signer account id is not set".to_string(),
             ))?;
 
-        Ok(near_crypto::InMemorySigner::from_secret_key(
+        Ok(Arc::new(near_crypto::InMemorySigner::from_secret_key(
             AccountId::from_str(near_signer).map_err(|_| {
                 BridgeSdkError::ConfigError("Invalid near signer account id".to_string())
             })?,
             SecretKey::from_str(near_private_key)
                 .map_err(|_| BridgeSdkError::ConfigError("Invalid near private key".to_string()))?,
+        )))
+    }
+
+    fn fast_bridge_contract(&self) -> Result<FastBridgeContract<EthSignerClient>> {
+        Ok(FastBridgeContract::new(
+            self.fast_bridge_address()?,
+            self.eth_client()?,
         ))
     }
 
-    fn fast_bridge_contract(
-        &self,
-    ) -> Result<FastBridgeContract<SignerMiddleware<Provider<Http>, LocalWallet>>> {
-        let eth_endpoint = self
-            .eth_endpoint
-            .as_ref()
-            .ok_or(BridgeSdkError::ConfigError(
-                "Ethereum rpc endpoint is not set".to_string(),
-            ))?;
+    fn eth_client(&self) -> Result<Arc<EthSignerClient>> {
+        self.eth_client_cache.get_or_try_init(|| {
+            let eth_provider = failover::eth_provider(self.eth_endpoint()?)?;
 
-        let eth_provider = Provider::<Http>::try_from(eth_endpoint).map_err(|_| {
-            BridgeSdkError::ConfigError("Invalid ethereum rpc endpoint url".to_string())
-        })?;
+            let wallet = self.eth_signer()?;
 
-        let wallet = self.eth_signer()?;
+            Ok(Arc::new(SignerMiddleware::new(eth_provider, wallet)))
+        })
+    }
 
-        let signer = SignerMiddleware::new(eth_provider, wallet);
-        let client = Arc::new(signer);
+    fn eth_signer(&self) -> Result<DynEthSigner> {
+        if let Some(signer) = &self.custom_eth_signer {
+            return Ok(DynEthSigner::new(signer.clone()));
+        }
 
-        Ok(FastBridgeContract::new(self.fast_bridge_address()?, client))
-    }
+        if let Some(eth_keystore_path) = &self.eth_keystore_path {
+            return signer::keystore_signer(
+                eth_keystore_path,
+                self.eth_keystore_password.as_deref(),
+                self.eth_chain_id,
+            );
+        }
 
-    fn eth_signer(&self) -> Result<LocalWallet> {
         let eth_private_key = self
             .eth_private_key
             .as_ref()
@@ -306,9 +633,11 @@ impl FastBridge {
             ));
         }
 
-        Ok(LocalWallet::from_bytes(&private_key_bytes)
+        let wallet = LocalWallet::from_bytes(&private_key_bytes)
             .map_err(|_| BridgeSdkError::ConfigError("Invalid ethereum private key".to_string()))?
-            .with_chain_id(eth_chain_id))
+            .with_chain_id(eth_chain_id);
+
+        Ok(DynEthSigner::new(Arc::new(wallet)))
     }
 
     fn fast_bridge_address(&self) -> Result<Address> {
@@ -326,6 +655,16 @@ impl FastBridge {
             })
     }
 
+    fn eth_rpc(&self) -> Result<Arc<dyn EthRpc>> {
+        match &self.eth_rpc {
+            Some(eth_rpc) => Ok(eth_rpc.clone()),
+            None => Ok(Arc::new(EthRPCClient::new_with_options(
+                self.eth_endpoint()?,
+                self.eth_rpc_options.clone(),
+            ))),
+        }
+    }
+
     fn eth_endpoint(&self) -> Result<&str> {
         Ok(self
             .eth_endpoint
@@ -335,6 +674,19 @@ impl FastBridge {
             ))?)
     }
 
+    fn near_rpc(&self) -> Result<Arc<dyn NearRpc>> {
+        match &self.near_rpc {
+            Some(near_rpc) => Ok(near_rpc.clone()),
+            // Cached, so that the operations share the health of the endpoints
+            None => self.near_rpc_cache.get_or_try_init(|| {
+                Ok(Arc::new(
+                    NearRpcClient::new(self.near_endpoint()?)
+                        .with_retry_policy(self.near_retry_policy),
+                ))
+            }),
+        }
+    }
+
     fn near_endpoint(&self) -> Result<&str> {
         Ok(self
             .near_endpoint
@@ -353,3 +705,56 @@ impl FastBridge {
             ))?)
     }
 }
+
+/// Fast bridge transfers are completed on Ethereum by a liquidity provider, so the transfers finalized by the connector
+/// are the `lp_unlock` calls, proving the completion on Ethereum
+#[async_trait]
+impl BridgeConnector for FastBridge {
+    type TransferRequest = FastBridgeTransfer;
+
+    async fn initiate_transfer(&self, request: FastBridgeTransfer) -> Result<TransactionId> {
+        let tx_hash = self
+            .transfer(
+                request.token_id,
+                request.amount,
+                request.fee_amount,
+                request.eth_token_address,
+                request.recipient,
+                request.valid_till,
+            )
+            .await?;
+
+        Ok(TransactionId::Near(tx_hash))
+    }
+
+    async fn finalize_transfer(&self, transfer_id: TransferId) -> Result<TransactionId> {
+        match transfer_id {
+            TransferId::Eth { tx_hash, log_index } => Ok(TransactionId::Near(
+                self.lp_unlock(tx_hash, log_index).await?,
+            )),
+            TransferId::Near { .. } => Err(BridgeSdkError::InvalidArgument(
+                "Fast bridge transfers are finalized with the transfer on Ethereum".to_string(),
+            )),
+        }
+    }
+
+    async fn get_status(&self, transfer_id: TransferId) -> Result<TransferStatus> {
+        match transfer_id {
+            TransferId::Eth { tx_hash, .. } => {
+                bridge_connector::eth_transfer_status(self.eth_endpoint()?, tx_hash).await
+            }
+            TransferId::Near { .. } => Err(BridgeSdkError::InvalidArgument(
+                "Fast bridge transfers are finalized with the transfer on Ethereum".to_string(),
+            )),
+        }
+    }
+
+    async fn estimate_fees(&self, request: &FastBridgeTransfer) -> Result<FeeEstimate> {
+        Ok(FeeEstimate {
+            near_deposit: 1,
+            near_gas: self.near_tx_options.gas(200_000_000_000_000)
+                + self.near_tx_options.gas(120_000_000_000_000),
+            token_fee: request.fee_amount,
+        })
+    }
+}
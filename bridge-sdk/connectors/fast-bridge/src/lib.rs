@@ -1,3 +1,3 @@
 mod fast_bridge;
 
-pub use fast_bridge::{FastBridge, FastBridgeBuilder};
+pub use fast_bridge::{FastBridge, FastBridgeBuilder, TransferMessage};
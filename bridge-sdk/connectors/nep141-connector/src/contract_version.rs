@@ -0,0 +1,401 @@
+use borsh::BorshSerialize;
+use bridge_connector_common::{
+    near_args,
+    result::{BridgeSdkError, Result},
+};
+use ethers::{
+    abi::{self, ParamType, Token},
+    types::{Address, H256},
+    utils::rlp::Rlp,
+};
+use near_primitives::types::AccountId;
+use omni_connector::omni_types::{ChainKind, InitTransferMsg, OmniAddress, ProofKind};
+use std::str::FromStr;
+
+/// Deposit attached to `log_metadata` of the omni locker to pay for the stored metadata. The excess is refunded
+const OMNI_LOG_METADATA_DEPOSIT: u128 = 200_000_000_000_000_000_000_000;
+
+/// Generation of the token locker on Near and the token factory on Ethereum the connector talks to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ContractVersion {
+    /// Rainbow bridge token locker and `BridgeTokenFactory`. Transfers to Ethereum are proven against the Near light
+    /// client on Ethereum
+    #[default]
+    V1,
+    /// Omni locker and `OmniBridge`. Transfers to Ethereum are signed by the MPC network, so they are finalized with
+    /// `sign_transfer` and `fin_transfer` of the omni connector
+    Omni,
+}
+
+/// Contract versions detected at the configured addresses by `detect_contract_interface`. A contract isn't probed
+/// when its address isn't configured
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ContractInterface {
+    pub token_locker: Option<ContractVersion>,
+    pub bridge_token_factory: Option<ContractVersion>,
+}
+
+impl ContractInterface {
+    /// Version of the deployment. Fails if neither contract was probed, or if the locker and the factory are of
+    /// different versions, e.g. the factory address of another deployment
+    pub fn version(self) -> Result<ContractVersion> {
+        match (self.token_locker, self.bridge_token_factory) {
+            (Some(locker), Some(factory)) if locker != factory => Err(BridgeSdkError::ConfigError(
+                format!("Token locker is {locker:?} but the bridge token factory is {factory:?}"),
+            )),
+            (Some(version), _) | (None, Some(version)) => Ok(version),
+            (None, None) => Err(BridgeSdkError::ConfigError(
+                "Neither the token locker nor the bridge token factory is set".to_string(),
+            )),
+        }
+    }
+}
+
+/// Token of a withdrawal from Ethereum, as named by the withdrawal event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum WithdrawnToken {
+    /// Account id of the NEP-141 token
+    Near(AccountId),
+    /// Address of the bridged token on Ethereum, which the omni locker maps to the NEP-141 token
+    Eth(Address),
+}
+
+#[derive(BorshSerialize)]
+struct StorageDepositAction {
+    token_id: AccountId,
+    account_id: AccountId,
+    storage_deposit_amount: Option<u128>,
+}
+
+#[derive(BorshSerialize)]
+struct FinTransferArgs {
+    chain_kind: ChainKind,
+    storage_deposit_actions: Vec<StorageDepositAction>,
+    prover_args: Vec<u8>,
+}
+
+impl ContractVersion {
+    /// Deposit attached to `log_metadata`
+    pub fn log_metadata_deposit(self) -> u128 {
+        match self {
+            ContractVersion::V1 => 0,
+            ContractVersion::Omni => OMNI_LOG_METADATA_DEPOSIT,
+        }
+    }
+
+    /// `msg` of the `ft_transfer_call` depositing tokens to the locker
+    pub fn deposit_msg(self, eth_receiver: &str) -> Result<String> {
+        match self {
+            ContractVersion::V1 => Ok(eth_receiver.to_string()),
+            ContractVersion::Omni => {
+                let recipient = Address::from_str(eth_receiver).map_err(|_| {
+                    BridgeSdkError::InvalidArgument(format!(
+                        "Invalid Ethereum recipient: {eth_receiver}"
+                    ))
+                })?;
+
+                Ok(near_args::to_string(&InitTransferMsg {
+                    recipient: OmniAddress::Eth(recipient),
+                    fee: 0,
+                    native_token_fee: 0,
+                }))
+            }
+        }
+    }
+
+    /// Locker method finalizing a withdrawal from Ethereum, and its borsh encoded arguments
+    pub fn finalize_withdraw_call(
+        self,
+        proof: &impl BorshSerialize,
+    ) -> Result<(&'static str, Vec<u8>)> {
+        let serialization_error =
+            |_| BridgeSdkError::EthProofError("Failed to serialize proof".to_string());
+
+        match self {
+            ContractVersion::V1 => {
+                let mut args = Vec::new();
+                proof.serialize(&mut args).map_err(serialization_error)?;
+
+                Ok(("withdraw", args))
+            }
+            ContractVersion::Omni => {
+                let mut prover_args = Vec::new();
+                ProofKind::InitTransfer
+                    .serialize(&mut prover_args)
+                    .and_then(|_| proof.serialize(&mut prover_args))
+                    .map_err(serialization_error)?;
+
+                let mut args = Vec::new();
+                FinTransferArgs {
+                    chain_kind: ChainKind::Eth,
+                    storage_deposit_actions: Vec::new(),
+                    prover_args,
+                }
+                .serialize(&mut args)
+                .map_err(serialization_error)?;
+
+                Ok(("fin_transfer", args))
+            }
+        }
+    }
+
+    /// Event emitted on Ethereum by `withdraw`, which is proven by `finalize_withdraw`
+    pub fn withdraw_event(self) -> &'static str {
+        match self {
+            ContractVersion::V1 => "Withdraw(string,address,uint256,string)",
+            ContractVersion::Omni => {
+                "InitTransfer(address,address,uint64,uint128,uint128,uint128,string,string)"
+            }
+        }
+    }
+
+    /// Token and Near recipient of a withdrawal, decoded from the RLP encoded event emitted by `withdraw`
+    pub(crate) fn withdraw_event_transfer(
+        self,
+        log_entry_data: &[u8],
+    ) -> Result<(WithdrawnToken, AccountId)> {
+        let invalid =
+            |reason: &str| BridgeSdkError::InvalidEvent(format!("withdrawal event: {reason}"));
+        let account_id = |account_id: &str| {
+            AccountId::from_str(account_id)
+                .map_err(|_| invalid(&format!("invalid account id `{account_id}`")))
+        };
+
+        let log = Rlp::new(log_entry_data);
+        let topics: Vec<H256> = log.list_at(1).map_err(|_| invalid("invalid topics"))?;
+        let data: Vec<u8> = log.val_at(2).map_err(|_| invalid("invalid data"))?;
+
+        match self {
+            ContractVersion::V1 => {
+                // `sender` is indexed
+                let fields = abi::decode(
+                    &[ParamType::String, ParamType::Uint(256), ParamType::String],
+                    &data,
+                )
+                .map_err(|_| invalid("invalid data"))?;
+                let [Token::String(token), _, Token::String(recipient)] = fields.as_slice() else {
+                    return Err(invalid("invalid data"));
+                };
+
+                Ok((
+                    WithdrawnToken::Near(account_id(token)?),
+                    account_id(recipient)?,
+                ))
+            }
+            ContractVersion::Omni => {
+                // `sender`, `tokenAddress` and `originNonce` are indexed
+                let token = topics
+                    .get(2)
+                    .map(|topic| Address::from(*topic))
+                    .ok_or(invalid("missing token address"))?;
+                let fields = abi::decode(
+                    &[
+                        ParamType::Uint(128),
+                        ParamType::Uint(128),
+                        ParamType::Uint(128),
+                        ParamType::String,
+                        ParamType::String,
+                    ],
+                    &data,
+                )
+                .map_err(|_| invalid("invalid data"))?;
+                let [_, _, _, Token::String(recipient), _] = fields.as_slice() else {
+                    return Err(invalid("invalid data"));
+                };
+                let recipient = recipient
+                    .strip_prefix("near:")
+                    .ok_or(invalid(&format!("recipient `{recipient}` isn't on Near")))?;
+
+                Ok((WithdrawnToken::Eth(token), account_id(recipient)?))
+            }
+        }
+    }
+
+    /// Amount and origin nonce of a withdrawal, decoded from the topics and data of the event emitted by `withdraw`
+    pub(crate) fn withdraw_event_amount(
+        self,
+        topics: &[H256],
+        data: &[u8],
+    ) -> Result<(u128, Option<u64>)> {
+        let invalid = || BridgeSdkError::InvalidEvent("withdrawal event data".to_string());
+
+        match self {
+            ContractVersion::V1 => {
+                let fields = abi::decode(
+                    &[ParamType::String, ParamType::Uint(256), ParamType::String],
+                    data,
+                )
+                .map_err(|_| invalid())?;
+                let [_, Token::Uint(amount), _] = fields.as_slice() else {
+                    return Err(invalid());
+                };
+
+                Ok((u128::try_from(*amount).map_err(|_| invalid())?, None))
+            }
+            ContractVersion::Omni => {
+                let fields = abi::decode(&[ParamType::Uint(128)], data).map_err(|_| invalid())?;
+                let [Token::Uint(amount)] = fields.as_slice() else {
+                    return Err(invalid());
+                };
+                // `originNonce` is the third indexed field
+                let nonce = topics
+                    .get(3)
+                    .map(|topic| ethers::types::U256::from_big_endian(topic.as_bytes()).low_u64());
+
+                Ok((amount.as_u128(), nonce))
+            }
+        }
+    }
+
+    /// Fails for operations proving Near receipts against the light client, which the omni contracts don't accept
+    pub fn check_light_client_proofs(self, operation: &str) -> Result<()> {
+        match self {
+            ContractVersion::V1 => Ok(()),
+            ContractVersion::Omni => Err(BridgeSdkError::ConfigError(format!(
+                "`{operation}` isn't supported by the omni contracts, use the omni connector instead"
+            ))),
+        }
+    }
+}
+
+impl FromStr for ContractVersion {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "v1" => Ok(ContractVersion::V1),
+            "omni" => Ok(ContractVersion::Omni),
+            _ => Err(format!(
+                "Unknown contract version, expected `v1`, `omni` or `auto`: {s}"
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::utils::{keccak256, rlp::RlpStream};
+
+    #[test]
+    fn deposit_msg_follows_the_version() {
+        let receiver = "0x5a08feed678c056650b3eb4a5cb1b9bb6f0fe265";
+
+        assert_eq!(ContractVersion::V1.deposit_msg(receiver).unwrap(), receiver);
+
+        let msg: serde_json::Value =
+            serde_json::from_str(&ContractVersion::Omni.deposit_msg(receiver).unwrap()).unwrap();
+        assert_eq!(msg["recipient"], format!("eth:{receiver}"));
+        assert_eq!(msg["fee"], "0");
+
+        assert!(ContractVersion::Omni.deposit_msg("alice.near").is_err());
+    }
+
+    fn log_entry(topics: &[H256], data: &[Token]) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(3);
+        stream.append(&Address::repeat_byte(1));
+        stream.append_list(topics);
+        stream.append(&abi::encode(data));
+        stream.out().to_vec()
+    }
+
+    #[test]
+    fn withdraw_event_transfer_follows_the_version() {
+        let topic = H256(keccak256(ContractVersion::V1.withdraw_event()));
+        let log = log_entry(
+            &[topic, H256::repeat_byte(2)],
+            &[
+                Token::String("token.near".to_string()),
+                Token::Uint(10.into()),
+                Token::String("alice.near".to_string()),
+            ],
+        );
+        assert_eq!(
+            ContractVersion::V1.withdraw_event_transfer(&log).unwrap(),
+            (
+                WithdrawnToken::Near("token.near".parse().unwrap()),
+                "alice.near".parse().unwrap()
+            )
+        );
+
+        let token = Address::repeat_byte(3);
+        let topic = H256(keccak256(ContractVersion::Omni.withdraw_event()));
+        let omni_log = |recipient: &str| {
+            log_entry(
+                &[topic, H256::repeat_byte(2), H256::from(token), H256::zero()],
+                &[
+                    Token::Uint(10.into()),
+                    Token::Uint(0.into()),
+                    Token::Uint(0.into()),
+                    Token::String(recipient.to_string()),
+                    Token::String(String::new()),
+                ],
+            )
+        };
+        assert_eq!(
+            ContractVersion::Omni
+                .withdraw_event_transfer(&omni_log("near:alice.near"))
+                .unwrap(),
+            (WithdrawnToken::Eth(token), "alice.near".parse().unwrap())
+        );
+        assert!(ContractVersion::Omni
+            .withdraw_event_transfer(&omni_log("eth:0x5a08feed678c056650b3eb4a5cb1b9bb6f0fe265"))
+            .is_err());
+    }
+
+    #[test]
+    fn withdraw_event_amount_follows_the_version() {
+        let data = abi::encode(&[
+            Token::String("token.near".to_string()),
+            Token::Uint(10.into()),
+            Token::String("alice.near".to_string()),
+        ]);
+        assert_eq!(
+            ContractVersion::V1
+                .withdraw_event_amount(&[H256::zero(), H256::repeat_byte(2)], &data)
+                .unwrap(),
+            (10, None)
+        );
+
+        let data = abi::encode(&[
+            Token::Uint(10.into()),
+            Token::Uint(0.into()),
+            Token::Uint(0.into()),
+            Token::String("near:alice.near".to_string()),
+            Token::String(String::new()),
+        ]);
+        let topics = [
+            H256::zero(),
+            H256::repeat_byte(2),
+            H256::repeat_byte(3),
+            H256::from_low_u64_be(7),
+        ];
+        assert_eq!(
+            ContractVersion::Omni
+                .withdraw_event_amount(&topics, &data)
+                .unwrap(),
+            (10, Some(7))
+        );
+    }
+
+    #[test]
+    fn contract_interface_version_requires_matching_contracts() {
+        let interface = |token_locker, bridge_token_factory| ContractInterface {
+            token_locker,
+            bridge_token_factory,
+        };
+        let (v1, omni) = (Some(ContractVersion::V1), Some(ContractVersion::Omni));
+
+        assert_eq!(
+            interface(omni, None).version().unwrap(),
+            ContractVersion::Omni
+        );
+        assert_eq!(interface(None, v1).version().unwrap(), ContractVersion::V1);
+        assert_eq!(
+            interface(omni, omni).version().unwrap(),
+            ContractVersion::Omni
+        );
+        assert!(interface(v1, omni).version().is_err());
+        assert!(interface(None, None).version().is_err());
+    }
+}
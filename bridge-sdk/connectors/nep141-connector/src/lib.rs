@@ -1,6 +1,11 @@
 #[macro_use]
 extern crate derive_builder;
 
+mod contract_version;
 mod nep141_connector;
+mod token_mapping_cache;
+mod transfer_result;
 
+pub use contract_version::{ContractInterface, ContractVersion};
 pub use nep141_connector::{Nep141Connector, Nep141ConnectorBuilder};
+pub use transfer_result::{BridgeNearResult, DepositResult, WithdrawResult};
@@ -1,16 +1,65 @@
+use async_trait::async_trait;
 use borsh::BorshSerialize;
-use bridge_connector_common::result::{BridgeSdkError, Result};
-use eth_proof;
-use ethers::{abi::Address, prelude::*};
+use bridge_connector_common::{
+    anvil_fork::{AnvilFork, ForkSimulation},
+    bridge_connector::{
+        self, BridgeConnector, FeeEstimate, TokenTransfer, TransactionId, TransferDirection,
+        TransferId, TransferStatus,
+    },
+    chunked_upload,
+    client_cache::ClientCache,
+    contract_call::{self, EthGasEstimate, EthTxOptions, NearTxOptions},
+    eth_events,
+    events::{self, ConnectorEvent, ConnectorEvents},
+    failover::{self, EthProvider},
+    method_names::MethodNames,
+    near_args::{
+        self, AccountIdArgs, AmountArgs, EmptyArgs, FtTransferCallArgs, StorageDepositArgs,
+        TokenIdArgs,
+    },
+    operations::DisabledOperations,
+    proof_store::ProofStore,
+    recipient_validation::RecipientValidation,
+    result::{BridgeSdkError, Result},
+    rpc_scheduler::{RpcPriority, RpcScheduler},
+    screening::{self, ScreenedTransfer, TransferScreening},
+    self_check::{self, SelfCheckReport},
+    signer::{
+        self, CallbackEthSigner, CallbackNearSigner, DynEthSigner, EthSigner, EthSignerClient,
+        NearSigner,
+    },
+    transfer_journal::{TransferJournal, TransferStage},
+};
+use eth_light_client_on_near::EthOnNearClient;
+use eth_proof::{self, EthRPCClient, EthRPCClientOptions, EthRpc, ProofDataSource, ProofOptions};
+use ethers::{
+    abi::{Address, Token},
+    prelude::*,
+};
+use futures::Stream;
 use near_crypto::SecretKey;
+use near_jsonrpc_primitives::types::query::QueryResponseKind;
 use near_light_client_on_eth::NearOnEthClient;
 use near_primitives::{
     hash::CryptoHash,
-    types::{AccountId, TransactionOrReceiptId},
+    types::{AccountId, BlockReference, Finality, TransactionOrReceiptId},
+    views::{FinalExecutionOutcomeView, FinalExecutionStatus},
+};
+use near_rpc_client::{retry::RetryPolicy, FungibleTokenMetadata, NearRpc, NearRpcClient};
+use omni_connector::omni_types::{OmniAddress, TokenAddressArgs};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashSet, future::Future, path::PathBuf, str::FromStr, sync::Arc, time::Duration,
 };
-use std::{str::FromStr, sync::Arc};
 use tracing;
 
+use crate::{
+    contract_version::WithdrawnToken,
+    token_mapping_cache::TokenMappingCache,
+    transfer_result::{BridgeNearResult, DepositResult, WithdrawResult},
+    ContractInterface, ContractVersion,
+};
+
 abigen!(
     BridgeTokenFactory,
     r#"[
@@ -18,6 +67,15 @@ abigen!(
       function deposit(bytes memory proofData, uint64 proofBlockHeight) external
       function withdraw(string memory token, uint128 amount, string memory recipient) external
       function nearToEthToken(string calldata nearTokenId) external view returns (address)
+      function ethToNearToken(address ethTokenAddress) external view returns (string)
+    ]"#
+);
+
+abigen!(
+    OmniBridge,
+    r#"[
+      function initTransfer(address tokenAddress, uint128 amount, uint128 fee, uint128 nativeFee, string recipient, string message) external payable
+      function currentOriginNonce() external view returns (uint64)
     ]"#
 );
 
@@ -25,15 +83,29 @@ abigen!(
     ERC20,
     r#"[
       function allowance(address _owner, address _spender) public view returns (uint256 remaining)
+      function balanceOf(address _owner) public view returns (uint256 balance)
       function approve(address spender, uint256 amount) external returns (bool)
     ]"#
 );
 
-/// Bridging NEAR-originated NEP-141 tokens to Ethereum and back
-#[derive(Builder)]
+/// Storage the token locker records for each used proof, paid by the attached deposit. `Locker::record_proof` in
+/// rainbow-token-connector inserts the 32 bytes proof key into the `used_events` `UnorderedSet` with prefix `u`,
+/// which writes two records, see `test_used_proof_storage_bytes`
+const USED_PROOF_STORAGE_BYTES: u128 = 172;
+
+/// Storage usage charged by the Near runtime for each record on top of its key and value, `num_extra_bytes_record`
+/// of the runtime config
+#[cfg(test)]
+const STORAGE_RECORD_OVERHEAD_BYTES: usize = 40;
+
+/// Bridging NEAR-originated NEP-141 tokens to Ethereum and back. Clones share the cached clients
+#[derive(Builder, Clone)]
 pub struct Nep141Connector {
     #[doc = r"Ethereum RPC endpoint. Required for `deploy_token`, `mint`, `burn`, `withdraw`"]
     eth_endpoint: Option<String>,
+    #[doc = r"Ethereum WebSocket RPC endpoint. Required for `subscribe_withdraw_events`"]
+    #[builder(default)]
+    eth_ws_endpoint: Option<String>,
     #[doc = r"Ethereum chain id. Required for `deploy_token`, `mint`, `burn`, `withdraw`"]
     eth_chain_id: Option<u64>,
     #[doc = r"Ethereum private key. Required for `deploy_token`, `mint`, `burn`"]
@@ -50,6 +122,112 @@ pub struct Nep141Connector {
     token_locker_id: Option<String>,
     #[doc = r"NEAR light client address on Ethereum. Required for `deploy_token`, `mint`"]
     near_light_client_address: Option<String>,
+    #[doc = r"Operations rejected with `OperationDisabled`, e.g. during an incident"]
+    #[builder(default)]
+    disabled_operations: DisabledOperations,
+    #[doc = r"Contract methods renamed by the deployment, e.g. `withdraw` deployed as `finish_withdraw`"]
+    #[builder(default)]
+    method_names: MethodNames,
+    #[doc = r"Gas and attached deposit of the Near transactions, overriding the connector defaults"]
+    #[builder(default)]
+    near_tx_options: NearTxOptions,
+    #[doc = r"Screening of the transfers before they are submitted, e.g. for sanctions compliance. A rejected transfer fails with `TransferRejected`"]
+    #[builder(default)]
+    transfer_screening: Option<Arc<dyn TransferScreening>>,
+    #[doc = r"Validation of the recipients that likely can't use the bridged tokens, e.g. exchange deposit addresses requiring a tag. Disabled by default"]
+    #[builder(default)]
+    recipient_validation: RecipientValidation,
+    #[doc = r"Simulates transactions instead of sending them and returns the hashes they would have. Ethereum calls are checked with `eth_estimateGas` and `eth_call`, Near calls are run as view calls and checked against the signer balance"]
+    #[builder(default)]
+    dry_run: bool,
+    #[doc = r"Registers the recipient of `finalize_withdraw` on the token with `storage_deposit`, paid by the signer, when it isn't registered yet. Otherwise the finalization fails before sending the transaction"]
+    #[builder(default)]
+    register_withdraw_recipient: bool,
+    #[doc = r"Version of the token locker and factory contracts. With `Omni`, `bridge_token_factory_address` is the `OmniBridge` address"]
+    #[builder(default)]
+    contract_version: ContractVersion,
+    #[doc = r"Waits for the light client of the destination chain to sync the block of a proven transfer instead of failing. Enabled by default"]
+    #[builder(default = "true")]
+    wait_for_light_client: bool,
+    #[doc = r"How long to wait for a light client to sync before failing"]
+    #[builder(default = "bridge_connector::DEFAULT_LIGHT_CLIENT_SYNC_TIMEOUT")]
+    light_client_sync_timeout: Duration,
+    #[doc = r"Ethereum light client account id on Near. When set, proofs of Ethereum events wait for it to sync the proven block"]
+    #[builder(default)]
+    eth_light_client_account_id: Option<String>,
+    #[doc = r"Keeps the proofs of the finalizations that fail to be submitted, e.g. because of a gas spike, so that `retry_finalize` can reuse them instead of generating them again"]
+    #[builder(default)]
+    proof_store: Option<ProofStore>,
+    #[doc = r"wNEAR account id on Near, e.g. `wrap.near`. Required for `bridge_near`, `finalize_near_withdraw`, `unwrap_near`"]
+    #[builder(default)]
+    wrapped_near_id: Option<String>,
+    #[doc = r"Custom NEAR signer, e.g. a hardware wallet or a remote signer. Takes precedence over `near_private_key`"]
+    #[builder(default)]
+    custom_near_signer: Option<Arc<dyn NearSigner>>,
+    #[doc = r"Path of an encrypted web3 JSON keystore holding the Ethereum key. Takes precedence over `eth_private_key`"]
+    #[builder(default)]
+    eth_keystore_path: Option<String>,
+    #[doc = r"Password of the Ethereum keystore. Required with `eth_keystore_path`"]
+    #[builder(default)]
+    eth_keystore_password: Option<String>,
+    #[doc = r"Custom Ethereum signer, e.g. a hardware wallet or a remote signer. Takes precedence over `eth_private_key`"]
+    #[builder(default)]
+    custom_eth_signer: Option<Arc<dyn EthSigner>>,
+    #[doc = r"Near RPC serving the requests instead of `near_endpoint`, e.g. a `MockNearRpc` in tests"]
+    #[builder(default)]
+    near_rpc: Option<Arc<dyn NearRpc>>,
+    #[doc = r"Ethereum RPC serving the proof data instead of `eth_endpoint`, e.g. a `MockEthRpc` in tests"]
+    #[builder(default)]
+    eth_rpc: Option<Arc<dyn EthRpc>>,
+    #[doc = r"Retries of the Near requests failing with a transient error. Ignored with `near_rpc`"]
+    #[builder(default)]
+    near_retry_policy: RetryPolicy,
+    #[doc = r"Time each Near transaction is awaited for its outcome, failing if it did. Only broadcast with `None`"]
+    #[builder(default = "Some(signer::DEFAULT_NEAR_OUTCOME_TIMEOUT)")]
+    near_outcome_timeout: Option<Duration>,
+    #[doc = r"Retries, rate limit and response size of the Ethereum proof data requests. Ignored with `eth_rpc`"]
+    #[builder(default)]
+    eth_rpc_options: EthRPCClientOptions,
+    #[doc = r"Block cache and metrics of the generated Ethereum proofs"]
+    #[builder(default)]
+    proof_options: ProofOptions,
+    #[doc = r"Source of the Ethereum blocks the proofs are generated from, e.g. a local node database, instead of `eth_rpc`"]
+    #[builder(default)]
+    proof_data_source: Option<Arc<dyn ProofDataSource>>,
+    #[doc = r"Journal of the strict mode of the Ethereum transactions, see `contract_call::send_eth_call`. Disabled if unset"]
+    #[builder(default)]
+    eth_nonce_journal: Option<PathBuf>,
+    #[doc = r"Observer of the progress of the operations, e.g. transactions submitted and light clients lagging. Events are only logged if unset"]
+    #[builder(default)]
+    events: Option<Arc<dyn ConnectorEvents>>,
+    #[doc = r"Limits of the concurrent requests to each RPC endpoint. Connectors and relayers given the same scheduler share the limits of an endpoint"]
+    #[builder(default = "RpcScheduler::shared()")]
+    rpc_scheduler: Arc<RpcScheduler>,
+    #[doc = r"Records the transfers initiated by `deposit_and_wait` and `withdraw_and_wait`, so that they can be finalized with `resume` if the process stops before"]
+    #[builder(default)]
+    transfer_journal: Option<Arc<dyn TransferJournal>>,
+    #[builder(setter(skip))]
+    token_mappings: TokenMappingCache,
+    #[builder(setter(skip))]
+    eth_provider_cache: ClientCache<Arc<EthProvider>>,
+    #[builder(setter(skip))]
+    eth_client_cache: ClientCache<Arc<EthSignerClient>>,
+    #[builder(setter(skip))]
+    near_rpc_cache: ClientCache<Arc<dyn NearRpc>>,
+}
+
+impl Nep141ConnectorBuilder {
+    /// Signs the Ethereum transactions with a callback, e.g. a browser wallet or an MPC signing service, instead of a
+    /// key held by the connector
+    pub fn eth_signing_callback(&mut self, signer: CallbackEthSigner) -> &mut Self {
+        self.custom_eth_signer(Some(Arc::new(signer)))
+    }
+
+    /// Signs the Near transactions with a callback, e.g. a wallet app or a custodial signing service, instead of a key
+    /// held by the connector
+    pub fn near_signing_callback(&mut self, signer: CallbackNearSigner) -> &mut Self {
+        self.custom_near_signer(Some(Arc::new(signer)))
+    }
 }
 
 impl Nep141Connector {
@@ -59,32 +237,72 @@ impl Nep141Connector {
             eth_chain_id: None,
             bridge_token_factory_address: None,
             eth_endpoint: None,
+            eth_ws_endpoint: None,
             eth_private_key: None,
             near_endpoint: None,
             near_private_key: None,
             near_signer: None,
             token_locker_id: None,
             near_light_client_address: None,
+            disabled_operations: DisabledOperations::default(),
+            method_names: MethodNames::default(),
+            near_tx_options: NearTxOptions::default(),
+            transfer_screening: None,
+            recipient_validation: RecipientValidation::default(),
+            dry_run: false,
+            register_withdraw_recipient: false,
+            contract_version: ContractVersion::default(),
+            wait_for_light_client: true,
+            light_client_sync_timeout: bridge_connector::DEFAULT_LIGHT_CLIENT_SYNC_TIMEOUT,
+            eth_light_client_account_id: None,
+            proof_store: None,
+            wrapped_near_id: None,
+            custom_near_signer: None,
+            eth_keystore_path: None,
+            eth_keystore_password: None,
+            custom_eth_signer: None,
+            near_rpc: None,
+            eth_rpc: None,
+            near_retry_policy: RetryPolicy::DEFAULT,
+            near_outcome_timeout: Some(signer::DEFAULT_NEAR_OUTCOME_TIMEOUT),
+            eth_rpc_options: EthRPCClientOptions::default(),
+            proof_options: ProofOptions::default(),
+            proof_data_source: None,
+            eth_nonce_journal: None,
+            events: None,
+            rpc_scheduler: RpcScheduler::shared(),
+            transfer_journal: None,
+            token_mappings: TokenMappingCache::default(),
+            eth_provider_cache: ClientCache::default(),
+            eth_client_cache: ClientCache::default(),
+            near_rpc_cache: ClientCache::default(),
         }
     }
 
     /// Logs token metadata to token_locker contract. The proof from this transaction is then used to deploy a corresponding token on Ethereum
     #[tracing::instrument(skip_all, name = "LOG METADATA")]
     pub async fn log_token_metadata(&self, near_token_id: String) -> Result<CryptoHash> {
-        let near_endpoint = self.near_endpoint()?;
+        self.disabled_operations.check("log_token_metadata")?;
+
+        let near_rpc = self.near_rpc()?;
 
-        let args = format!(r#"{{"token_id":"{near_token_id}"}}"#)
-            .to_string()
-            .into_bytes();
+        let args = near_args::to_bytes(&TokenIdArgs {
+            token_id: near_token_id,
+        });
 
-        let tx_id = near_rpc_client::change(
-            near_endpoint,
+        let tx_id = signer::near_change(
+            near_rpc.as_ref(),
             self.near_signer()?,
             self.token_locker_id()?.to_string(),
-            "log_metadata".to_string(),
+            self.method_names.near("log_metadata"),
             args,
-            300_000_000_000_000,
-            0,
+            self.near_tx_options.gas(300_000_000_000_000),
+            self.near_tx_options
+                .deposit(self.contract_version.log_metadata_deposit()),
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
         )
         .await?;
 
@@ -100,21 +318,28 @@ impl Nep141Connector {
         near_token_id: String,
         amount: u128,
     ) -> Result<CryptoHash> {
-        let near_endpoint = self.near_endpoint()?;
+        self.disabled_operations.check("storage_deposit_for_token")?;
+
+        let near_rpc = self.near_rpc()?;
         let token_locker = self.token_locker_id()?.to_string();
 
-        let args = format!(r#"{{"account_id":"{token_locker}"}}"#)
-            .to_string()
-            .into_bytes();
+        let args = near_args::to_bytes(&StorageDepositArgs {
+            account_id: Some(token_locker),
+            registration_only: None,
+        });
 
-        let tx_id = near_rpc_client::change(
-            near_endpoint,
+        let tx_id = signer::near_change(
+            near_rpc.as_ref(),
             self.near_signer()?,
             near_token_id,
-            "storage_deposit".to_string(),
+            self.method_names.near("storage_deposit"),
             args,
-            300_000_000_000_000,
+            self.near_tx_options.gas(300_000_000_000_000),
             amount,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
         )
         .await?;
 
@@ -125,48 +350,39 @@ impl Nep141Connector {
 
     /// Deploys an ERC-20 token that will be used when bridging NEP-141 tokens to Ethereum. Requires a receipt from log_metadata transaction on Near
     #[tracing::instrument(skip_all, name = "DEPLOY TOKEN")]
-    pub async fn deploy_token(&self, receipt_id: CryptoHash) -> Result<TxHash> {
-        let eth_endpoint = self.eth_endpoint()?;
-        let near_endpoint = self.near_endpoint()?;
-
-        let near_on_eth_client =
-            NearOnEthClient::new(self.near_light_client_address()?, eth_endpoint.to_string());
-
-        let proof_block_height = near_on_eth_client.get_sync_height().await?;
-        let block_hash = near_on_eth_client
-            .get_block_hash(proof_block_height)
-            .await?;
-
-        tracing::debug!(proof_block_height, "Retrieved light client block height");
+    pub async fn deploy_token(
+        &self,
+        receipt_id: CryptoHash,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("deploy_token")?;
+        self.contract_version
+            .check_light_client_proofs("deploy_token")?;
 
-        let receipt_id = TransactionOrReceiptId::Receipt {
-            receipt_id,
-            receiver_id: AccountId::from_str(&self.token_locker_id()?)
-                .map_err(|_| BridgeSdkError::UnknownError)?,
-        };
+        let (buffer, proof_block_height) = self.get_near_proof(receipt_id).await?;
 
-        let proof_data = near_rpc_client::get_light_client_proof(
-            near_endpoint,
-            receipt_id,
-            CryptoHash(block_hash),
+        let factory = self.bridge_token_factory()?;
+        let call = tx_opts.apply(
+            self.method_names
+                .eth(factory.new_bridge_token(buffer.into(), proof_block_height)),
+        );
+
+        let tx_hash = contract_call::send_eth_call(
+            &self.eth_client()?,
+            call,
+            tx_opts.confirmations.or(Some(1)),
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
         )
         .await?;
 
-        let mut buffer: Vec<u8> = Vec::new();
-        proof_data.serialize(&mut buffer).map_err(|_| {
-            BridgeSdkError::NearProofError("Failed to deserialize proof".to_string())
-        })?;
+        self.token_mappings.clear();
 
-        tracing::debug!("Retrieved Near receipt proof");
+        tracing::info!(tx_hash = format!("{:?}", tx_hash), "Sent token deploy transaction");
 
-        let factory = self.bridge_token_factory()?;
-        let call = factory.new_bridge_token(buffer.into(), proof_block_height);
-
-        let tx = call.send().await?;
-
-        tracing::info!(tx_hash = format!("{:?}", tx.tx_hash()), "Sent token deploy transaction");
-
-        Ok(tx.tx_hash())
+        Ok(tx_hash)
     }
 
     /// Transfers NEP-141 tokens to the token locker. The proof from this transaction is then used to mint the corresponding tokens on Ethereum
@@ -177,23 +393,47 @@ impl Nep141Connector {
         amount: u128,
         eth_receiver: String,
     ) -> Result<CryptoHash> {
-        let near_endpoint = self.near_endpoint()?;
+        self.disabled_operations.check("deposit")?;
+        screening::check(self.transfer_screening.as_ref(), || {
+            Ok(ScreenedTransfer {
+                direction: TransferDirection::NearToEth,
+                token: near_token_id.clone(),
+                amount,
+                sender: self.near_signer()?.account_id().to_string(),
+                recipient: eth_receiver.clone(),
+            })
+        })
+        .await?;
+        match Address::from_str(&eth_receiver) {
+            Ok(address) => {
+                self.recipient_validation
+                    .check_eth(self.eth_endpoint.as_deref(), address)
+                    .await?
+            }
+            Err(_) => self.recipient_validation.check(&eth_receiver)?,
+        }
+
+        let near_rpc = self.near_rpc()?;
         let token_locker = self.token_locker_id()?.to_string();
 
-        let args = format!(
-            r#"{{"receiver_id":"{token_locker}","amount":"{amount}","msg":"{eth_receiver}"}}"#
-        )
-        .to_string()
-        .into_bytes();
+        let args = near_args::to_bytes(&FtTransferCallArgs {
+            receiver_id: token_locker,
+            amount,
+            msg: self.contract_version.deposit_msg(&eth_receiver)?,
+        });
 
-        let tx_hash = near_rpc_client::change(
-            near_endpoint,
+        let tx_hash = signer::near_change(
+            near_rpc.as_ref(),
             self.near_signer()?,
             near_token_id,
-            "ft_transfer_call".to_string(),
+            self.method_names.near("ft_transfer_call"),
             args,
-            300_000_000_000_000,
+            self.near_tx_options.gas(300_000_000_000_000),
             1,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
         )
         .await?;
 
@@ -202,49 +442,221 @@ impl Nep141Connector {
         Ok(tx_hash)
     }
 
-    /// Mints the corresponding bridged tokens on Ethereum. Requires a proof from the deposit transaction on Near
+    /// Same as `deposit`, then waits for the transaction to be executed and decodes the locked amount, the locker
+    /// receipt to prove with `finalize_deposit` and, with the omni locker, the transfer nonce
+    pub async fn deposit_and_wait(
+        &self,
+        near_token_id: String,
+        amount: u128,
+        eth_receiver: String,
+    ) -> Result<DepositResult> {
+        if self.dry_run {
+            return Err(BridgeSdkError::ConfigError(
+                "The deposit is only decoded once the transaction is executed, so `deposit_and_wait` can't complete a dry run".to_string(),
+            ));
+        }
+
+        let tx_hash = self.deposit(near_token_id, amount, eth_receiver).await?;
+        let outcome = self
+            .near_rpc()?
+            .wait_for_tx_final_outcome(
+                tx_hash,
+                self.near_signer()?.account_id(),
+                near_rpc_client::DEFAULT_WAIT_FINAL_OUTCOME_TIMEOUT_SEC,
+            )
+            .await?;
+
+        let deposit =
+            DepositResult::from_outcome(&outcome, self.token_locker_id()?, self.contract_version)?;
+        self.record_initiated(TransferId::Near {
+            receipt_id: deposit.receipt_id,
+        })?;
+
+        tracing::info!(
+            receipt_id = format!("{:?}", deposit.receipt_id),
+            amount = deposit.amount,
+            nonce = deposit.nonce,
+            "Deposit executed"
+        );
+
+        Ok(deposit)
+    }
+
+    /// Mints the corresponding bridged tokens on Ethereum. Requires a proof from the deposit transaction on Near.
+    /// Fails with `EthTxFailed` and the revert reason if the proof is rejected
     #[tracing::instrument(skip_all, name = "FINALIZE DEPOSIT")]
-    pub async fn finalize_deposit(&self, receipt_id: CryptoHash) -> Result<TxHash> {
-        let eth_endpoint = self.eth_endpoint()?;
-        let near_endpoint = self.near_endpoint()?;
+    pub async fn finalize_deposit(
+        &self,
+        receipt_id: CryptoHash,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("finalize_deposit")?;
+        self.contract_version
+            .check_light_client_proofs("finalize_deposit")?;
 
-        let near_on_eth_client =
-            NearOnEthClient::new(self.near_light_client_address()?, eth_endpoint.to_string());
+        let (proof, proof_block_height) = self.get_near_proof(receipt_id).await?;
 
-        let proof_block_height = near_on_eth_client.get_sync_height().await?;
-        let block_hash = near_on_eth_client
-            .get_block_hash(proof_block_height)
-            .await?;
+        self.submit_deposit_proof(receipt_id, proof, proof_block_height, tx_opts)
+            .await
+    }
 
-        tracing::debug!(proof_block_height, "Retrieved light client block height");
+    /// Finalizes several deposits against the same light client block, so that the light client is queried once
+    /// rather than for every deposit. The proofs are generated concurrently, then the transactions are sent one after
+    /// the other with consecutive nonces. Returns the result of each finalization in the order of `receipt_ids`, a
+    /// failed one doesn't stop the following ones. Fails if a receipt is repeated
+    #[tracing::instrument(skip_all, name = "FINALIZE DEPOSITS")]
+    pub async fn finalize_deposits(
+        &self,
+        receipt_ids: Vec<CryptoHash>,
+        tx_opts: EthTxOptions,
+    ) -> Result<Vec<Result<TxHash>>> {
+        self.disabled_operations.check("finalize_deposit")?;
+        self.contract_version
+            .check_light_client_proofs("finalize_deposit")?;
+
+        let mut unique_ids = HashSet::new();
+        if let Some(receipt_id) = receipt_ids.iter().find(|id| !unique_ids.insert(*id)) {
+            return Err(BridgeSdkError::InvalidArgument(format!(
+                "Deposit {receipt_id} is repeated"
+            )));
+        }
 
-        let receipt_id = TransactionOrReceiptId::Receipt {
-            receipt_id,
-            receiver_id: AccountId::from_str(&self.token_locker_id()?)
-                .map_err(|_| BridgeSdkError::UnknownError)?,
-        };
+        let (proof_block_height, block_hash) = self.near_light_client_head(&receipt_ids).await?;
 
-        let proof_data = near_rpc_client::get_light_client_proof(
-            near_endpoint,
-            receipt_id,
-            CryptoHash(block_hash),
+        let proofs = futures::future::try_join_all(
+            receipt_ids
+                .iter()
+                .map(|receipt_id| self.get_near_proof_at(*receipt_id, block_hash)),
         )
         .await?;
 
-        tracing::debug!(proof_block_height, "Retrieved Near proof");
+        tracing::debug!(
+            proof_block_height,
+            proofs = proofs.len(),
+            "Retrieved Near proofs"
+        );
+
+        let results = send_each(
+            receipt_ids.iter().copied().zip(proofs),
+            tx_opts,
+            move |(receipt_id, proof), tx_opts| {
+                self.submit_deposit_proof(receipt_id, proof, proof_block_height, tx_opts)
+            },
+        )
+        .await;
+
+        for (receipt_id, result) in receipt_ids.iter().zip(&results) {
+            if let Err(err) = result {
+                tracing::error!(
+                    receipt_id = receipt_id.to_string(),
+                    error = err.to_string(),
+                    "Failed to finalize deposit"
+                );
+            }
+        }
 
-        let mut buffer: Vec<u8> = Vec::new();
-        proof_data.serialize(&mut buffer).map_err(|_| {
-            BridgeSdkError::NearProofError("Falied to deserialize proof".to_string())
-        })?;
+        Ok(results)
+    }
+
+    async fn submit_deposit_proof(
+        &self,
+        receipt_id: CryptoHash,
+        proof: Vec<u8>,
+        proof_block_height: u64,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        let send = async {
+            let factory = self.bridge_token_factory()?;
+            let call = tx_opts.apply(
+                self.method_names
+                    .eth(factory.deposit(proof.clone().into(), proof_block_height)),
+            );
+
+            contract_call::send_eth_call(
+                &self.eth_client()?,
+                call,
+                tx_opts.confirmations.or(Some(1)),
+                self.dry_run,
+                self.eth_nonce_journal.as_deref(),
+                self.events.as_deref(),
+                &self.rpc_scheduler,
+            )
+            .await
+        };
+        let tx_hash = self.keep_proof_on_failure(
+            TransferId::Near { receipt_id },
+            &proof,
+            proof_block_height,
+            send.await,
+        )?;
+
+        tracing::info!(tx_hash = format!("{:?}", tx_hash), "Sent finalize deposit transaction");
+
+        Ok(tx_hash)
+    }
+
+    /// Estimates the gas and cost of `finalize_deposit` with the current proof, without sending the transaction
+    #[tracing::instrument(skip_all, name = "ESTIMATE FINALIZE DEPOSIT")]
+    pub async fn estimate_finalize_deposit_gas(
+        &self,
+        receipt_id: CryptoHash,
+    ) -> Result<EthGasEstimate> {
+        self.contract_version
+            .check_light_client_proofs("finalize_deposit")?;
+
+        let (proof, proof_block_height) = self.get_near_proof(receipt_id).await?;
 
         let factory = self.bridge_token_factory()?;
-        let call = factory.deposit(buffer.into(), proof_block_height);
-        let tx = call.send().await?;
+        let call = self
+            .method_names
+            .eth(factory.deposit(proof.into(), proof_block_height));
+
+        contract_call::estimate_eth_call(self.eth_endpoint()?, &call).await
+    }
+
+    /// Executes `finalize_deposit` on a local `anvil` fork of the Ethereum network, without waiting for the light
+    /// client or spending real gas. The final Near block is injected into the light client of the fork, and the
+    /// transaction is sent from the impersonated Ethereum signer. Requires the `anvil` binary of Foundry
+    #[tracing::instrument(skip_all, name = "SIMULATE FINALIZE DEPOSIT")]
+    pub async fn simulate_finalize_deposit(
+        &self,
+        receipt_id: CryptoHash,
+    ) -> Result<ForkSimulation> {
+        self.contract_version
+            .check_light_client_proofs("finalize_deposit")?;
+
+        let fork = AnvilFork::spawn(self.eth_endpoint()?)?;
+        let block = self
+            .near_rpc()?
+            .get_block(BlockReference::Finality(Finality::Final))
+            .await?;
+        fork.inject_near_block(
+            self.near_light_client_address()?,
+            block.header.height,
+            block.header.hash,
+            block.header.block_merkle_root,
+        )
+        .await?;
+
+        let proof = self
+            .get_near_proof_at(receipt_id, block.header.hash)
+            .await?;
 
-        tracing::info!(tx_hash = format!("{:?}", tx.tx_hash()), "Sent finalize deposit transaction");
-        
-        Ok(tx.tx_hash())
+        let factory =
+            BridgeTokenFactory::new(self.bridge_token_factory_address()?, fork.provider());
+        let call = self
+            .method_names
+            .eth(factory.deposit(proof.into(), block.header.height));
+        let simulation = fork.simulate(self.eth_signer()?.address(), call).await?;
+
+        tracing::info!(
+            near_block_height = block.header.height,
+            gas_used = simulation.gas_used.to_string(),
+            revert_reason = simulation.revert_reason.as_deref(),
+            "Simulated finalize deposit on the fork"
+        );
+
+        Ok(simulation)
     }
 
     /// Burns bridged tokens on Ethereum. The proof from this transaction is then used to withdraw the corresponding tokens on Near
@@ -254,68 +666,466 @@ impl Nep141Connector {
         near_token_id: String,
         amount: u128,
         receiver: String,
+        tx_opts: EthTxOptions,
     ) -> Result<TxHash> {
-        let factory = self.bridge_token_factory()?;
+        self.disabled_operations.check("withdraw")?;
+        screening::check(self.transfer_screening.as_ref(), || {
+            Ok(ScreenedTransfer {
+                direction: TransferDirection::EthToNear,
+                token: near_token_id.clone(),
+                amount,
+                sender: format!("{:?}", self.eth_signer()?.address()),
+                recipient: receiver.clone(),
+            })
+        })
+        .await?;
+        self.recipient_validation.check(&receiver)?;
 
-        let erc20_address = factory
-            .near_to_eth_token(near_token_id.clone())
-            .call()
-            .await?;
+        let erc20_address = self.get_erc20_for_nep141(near_token_id.clone()).await?;
 
         tracing::debug!(address = format!("{:?}", erc20_address), "Retrieved ERC20 address");
 
         let bridge_token = &self.bridge_token(erc20_address)?;
 
         let signer = self.eth_signer()?;
+        let amount256: ethers::types::U256 = amount.into();
+        check_bridged_balance(bridge_token, signer.address(), amount256).await?;
+
         let bridge_token_factory_address = self.bridge_token_factory_address()?;
         let allowance = bridge_token
             .allowance(signer.address(), bridge_token_factory_address.clone())
             .call()
             .await?;
 
-        let amount256: ethers::types::U256 = amount.into();
+        let mut tx_opts = tx_opts;
         if allowance < amount256 {
-            bridge_token
-                .approve(bridge_token_factory_address, amount256 - allowance)
-                .send()
-                .await?
-                .await
-                .map_err(|e| ContractError::from(e))?;
-            
+            let approve_call = tx_opts
+                .apply(bridge_token.approve(bridge_token_factory_address, amount256 - allowance));
+
+            if self.dry_run {
+                tracing::warn!("The withdrawal can't be simulated before the tokens are approved");
+                return contract_call::send_eth_call(
+                    &self.eth_client()?,
+                    approve_call,
+                    None,
+                    true,
+                    self.eth_nonce_journal.as_deref(),
+                    self.events.as_deref(),
+                    &self.rpc_scheduler,
+                )
+                .await;
+            }
+
+            contract_call::send_eth_call(
+                &self.eth_client()?,
+                approve_call,
+                Some(1),
+                false,
+                self.eth_nonce_journal.as_deref(),
+                self.events.as_deref(),
+                &self.rpc_scheduler,
+            )
+            .await?;
+
             tracing::debug!("Approved tokens for spending");
+            tx_opts = tx_opts.next();
         }
 
-        let withdraw_call = factory.withdraw(near_token_id, amount, receiver);
-        let tx = withdraw_call.send().await?;
+        let withdraw_call = match self.contract_version {
+            ContractVersion::V1 => {
+                self.bridge_token_factory()?
+                    .withdraw(near_token_id, amount, receiver)
+            }
+            ContractVersion::Omni => self.omni_bridge()?.init_transfer(
+                erc20_address,
+                amount,
+                0,
+                0,
+                format!("near:{receiver}"),
+                String::new(),
+            ),
+        };
+        let withdraw_call = tx_opts.apply(self.method_names.eth(withdraw_call));
+        let tx_hash = contract_call::send_eth_call(
+            &self.eth_client()?,
+            withdraw_call,
+            tx_opts.confirmations,
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
 
-        tracing::info!(tx_hash = format!("{:?}", tx.tx_hash()), "Sent withdraw transaction");
+        tracing::info!(tx_hash = format!("{:?}", tx_hash), "Sent withdraw transaction");
 
-        Ok(tx.tx_hash())
+        Ok(tx_hash)
+    }
+
+    /// Same as `withdraw`, waiting for at least one confirmation, then decodes the withdrawal event to prove with
+    /// `finalize_withdraw` from the transaction receipt
+    pub async fn withdraw_and_wait(
+        &self,
+        near_token_id: String,
+        amount: u128,
+        receiver: String,
+        tx_opts: EthTxOptions,
+    ) -> Result<WithdrawResult> {
+        if self.dry_run {
+            return Err(BridgeSdkError::ConfigError(
+                "The withdrawal is only decoded once the transaction is executed, so `withdraw_and_wait` can't complete a dry run".to_string(),
+            ));
+        }
+
+        let tx_opts = EthTxOptions {
+            confirmations: Some(tx_opts.confirmations.unwrap_or(1)),
+            ..tx_opts
+        };
+        let tx_hash = self
+            .withdraw(near_token_id, amount, receiver, tx_opts)
+            .await?;
+        let withdrawal = self.withdrawal_result(tx_hash).await?;
+        self.record_initiated(TransferId::Eth {
+            tx_hash,
+            log_index: withdrawal.log_index,
+        })?;
+
+        tracing::info!(
+            log_index = withdrawal.log_index,
+            amount = withdrawal.amount,
+            nonce = withdrawal.nonce,
+            "Withdrawal executed"
+        );
+
+        Ok(withdrawal)
+    }
+
+    /// Bridges NEAR to Ethereum as wNEAR: wraps `amount` yoctoNEAR with `near_deposit`, deposits the wNEAR to the
+    /// token locker and finalizes the deposit on Ethereum. The signer is registered on wNEAR first if it isn't yet.
+    /// wNEAR has the 24 decimals of NEAR, so `amount` is bridged as is
+    #[tracing::instrument(skip_all, name = "BRIDGE NEAR")]
+    pub async fn bridge_near(
+        &self,
+        amount: u128,
+        eth_recipient: String,
+        tx_opts: EthTxOptions,
+    ) -> Result<BridgeNearResult> {
+        self.disabled_operations.check("bridge_near")?;
+        self.contract_version
+            .check_light_client_proofs("bridge_near")?;
+        if self.dry_run {
+            return Err(BridgeSdkError::ConfigError(
+                "Each step of `bridge_near` needs the previous one to be executed, so it can't complete a dry run".to_string(),
+            ));
+        }
+
+        let wrapped_near_id = self.wrapped_near_id()?.to_string();
+        let signer_id = self.near_signer()?.account_id();
+
+        if let Some(deposit) = self
+            .storage_registration_deposit(&wrapped_near_id, signer_id.as_str())
+            .await?
+        {
+            let tx_hash = signer::near_change(
+                self.near_rpc()?.as_ref(),
+                self.near_signer()?,
+                wrapped_near_id.clone(),
+                self.method_names.near("storage_deposit"),
+                near_args::to_bytes(&StorageDepositArgs {
+                    account_id: None,
+                    registration_only: Some(true),
+                }),
+                self.near_tx_options.gas(300_000_000_000_000),
+                deposit,
+                false,
+                self.near_outcome_timeout,
+                self.events.as_deref(),
+                &self.rpc_scheduler,
+            )
+            .await?;
+            self.wait_for_near_tx(tx_hash).await?;
+
+            tracing::info!(
+                tx_hash = tx_hash.to_string(),
+                "Registered the signer on wNEAR"
+            );
+        }
+
+        let tx_hash = signer::near_change(
+            self.near_rpc()?.as_ref(),
+            self.near_signer()?,
+            wrapped_near_id.clone(),
+            self.method_names.near("near_deposit"),
+            b"{}".to_vec(),
+            self.near_tx_options.gas(300_000_000_000_000),
+            amount,
+            false,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+        self.wait_for_near_tx(tx_hash).await?;
+
+        tracing::info!(tx_hash = tx_hash.to_string(), amount, "Wrapped NEAR");
+
+        let deposit = self
+            .deposit_and_wait(wrapped_near_id, amount, eth_recipient)
+            .await?;
+        let finalize_tx_hash = self.finalize_deposit(deposit.receipt_id, tx_opts).await?;
+
+        Ok(BridgeNearResult {
+            deposit,
+            finalize_tx_hash,
+        })
+    }
+
+    /// Completes a return of wNEAR from Ethereum: finalizes the withdrawal on Near, then unwraps the withdrawn wNEAR
+    /// to NEAR. The signer must be the recipient of the withdrawal
+    #[tracing::instrument(skip_all, name = "FINALIZE NEAR WITHDRAW")]
+    pub async fn finalize_near_withdraw(&self, tx_hash: TxHash) -> Result<CryptoHash> {
+        self.disabled_operations.check("finalize_near_withdraw")?;
+        if self.dry_run {
+            return Err(BridgeSdkError::ConfigError(
+                "wNEAR can only be unwrapped once the withdrawal is finalized, so `finalize_near_withdraw` can't complete a dry run".to_string(),
+            ));
+        }
+
+        let withdrawal = self.withdrawal_result(tx_hash).await?;
+        let finalize_tx_hash = self
+            .finalize_withdraw(tx_hash, withdrawal.log_index)
+            .await?;
+        self.wait_for_near_tx(finalize_tx_hash).await?;
+
+        self.unwrap_near(withdrawal.amount).await
+    }
+
+    /// Unwraps wNEAR of the signer to NEAR with `near_withdraw`
+    #[tracing::instrument(skip_all, name = "UNWRAP NEAR")]
+    pub async fn unwrap_near(&self, amount: u128) -> Result<CryptoHash> {
+        self.disabled_operations.check("unwrap_near")?;
+
+        let tx_hash = signer::near_change(
+            self.near_rpc()?.as_ref(),
+            self.near_signer()?,
+            self.wrapped_near_id()?.to_string(),
+            self.method_names.near("near_withdraw"),
+            near_args::to_bytes(&AmountArgs { amount }),
+            self.near_tx_options.gas(300_000_000_000_000),
+            1,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = tx_hash.to_string(),
+            amount,
+            "Sent unwrap NEAR transaction"
+        );
+
+        Ok(tx_hash)
+    }
+
+    /// Records the initiated transfer in the transfer journal, if any
+    fn record_initiated(&self, transfer_id: TransferId) -> Result<()> {
+        match &self.transfer_journal {
+            Some(journal) => journal.record(transfer_id, TransferStage::Initiated),
+            None => Ok(()),
+        }
+    }
+
+    /// Decodes the withdrawal event emitted by the bridge token factory in an executed transaction
+    async fn withdrawal_result(&self, tx_hash: TxHash) -> Result<WithdrawResult> {
+        let receipt = self
+            .eth_provider()?
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .ok_or(BridgeSdkError::EthProofError(format!(
+                "Transaction {tx_hash:?} is not executed yet"
+            )))?;
+
+        let factory_address = self.bridge_token_factory_address()?;
+        let topic = H256(ethers::utils::keccak256(
+            self.contract_version.withdraw_event(),
+        ));
+        let log = receipt
+            .logs
+            .iter()
+            .find(|log| log.address == factory_address && log.topics.first() == Some(&topic))
+            .ok_or(BridgeSdkError::EthProofError(format!(
+                "Transaction {tx_hash:?} didn't emit {}",
+                self.contract_version.withdraw_event()
+            )))?;
+
+        WithdrawResult::from_log(log, self.contract_version)
+    }
+
+    /// Subscribes to the withdrawal events of the bridge token factory over the WebSocket endpoint, e.g. to finalize
+    /// withdrawals as soon as they are made instead of polling for them. Events arrive without waiting for confirmations
+    pub async fn subscribe_withdraw_events(&self) -> Result<impl Stream<Item = WithdrawResult>> {
+        let contract_version = self.contract_version;
+
+        eth_events::subscribe_events(
+            self.eth_ws_endpoint()?,
+            self.bridge_token_factory_address()?,
+            contract_version.withdraw_event(),
+            move |log| WithdrawResult::from_log(log, contract_version),
+        )
+        .await
+    }
+
+    /// Metadata of a NEP-141 token, e.g. to check its decimals before computing the bridged amounts
+    pub async fn get_token_metadata(&self, token_id: AccountId) -> Result<FungibleTokenMetadata> {
+        Ok(self.near_rpc()?.get_ft_metadata(token_id).await?)
+    }
+
+    /// Address of the ERC-20 token bridged from the NEP-141 token. Zero if the token isn't deployed yet
+    pub async fn get_erc20_for_nep141(&self, near_token_id: String) -> Result<Address> {
+        if let Some(address) = self.token_mappings.erc20(&near_token_id) {
+            return Ok(address);
+        }
+
+        let address = self
+            .bridge_token_factory_reader()?
+            .near_to_eth_token(near_token_id.clone())
+            .call()
+            .await?;
+
+        if let Ok(near_token_id) = AccountId::from_str(&near_token_id) {
+            self.token_mappings.insert(near_token_id, address);
+        }
+
+        Ok(address)
+    }
+
+    /// Account id of the NEP-141 token the ERC-20 token was bridged from
+    pub async fn get_nep141_for_erc20(&self, address: Address) -> Result<AccountId> {
+        if let Some(near_token_id) = self.token_mappings.nep141(address) {
+            return Ok(near_token_id);
+        }
+
+        let near_token_id: AccountId = match self.contract_version {
+            ContractVersion::V1 => {
+                let near_token_id = self
+                    .bridge_token_factory_reader()?
+                    .eth_to_near_token(address)
+                    .call()
+                    .await?;
+
+                AccountId::from_str(&near_token_id).map_err(|_| {
+                    BridgeSdkError::InvalidArgument(format!(
+                        "{address:?} isn't a token deployed by the bridge token factory"
+                    ))
+                })?
+            }
+            ContractVersion::Omni => {
+                self.view(
+                    self.token_locker_id()?,
+                    "get_token_id",
+                    TokenAddressArgs {
+                        address: OmniAddress::Eth(address),
+                    },
+                )
+                .await?
+            }
+        };
+
+        self.token_mappings.insert(near_token_id.clone(), address);
+
+        Ok(near_token_id)
+    }
+
+    /// Checks that the signer holds `amount` of the bridged token, so that `withdraw` fails with
+    /// `InsufficientBalance` instead of reverting on chain. A missing allowance isn't checked, `withdraw` approves the
+    /// tokens itself
+    pub async fn check_withdraw_preconditions(
+        &self,
+        near_token_id: String,
+        amount: u128,
+    ) -> Result<()> {
+        let erc20_address = self.get_erc20_for_nep141(near_token_id).await?;
+        let bridge_token = self.bridge_token(erc20_address)?;
+
+        check_bridged_balance(&bridge_token, self.eth_signer()?.address(), amount.into()).await
     }
 
     /// Withdraws NEP-141 tokens from the token locker. Requires a proof from the burn transaction on Ethereum
     #[tracing::instrument(skip_all, name = "FINALIZE WITHDRAW")]
     pub async fn finalize_withdraw(&self, tx_hash: TxHash, log_index: u64) -> Result<CryptoHash> {
-        let eth_endpoint = self.eth_endpoint()?;
-        let near_endpoint = self.near_endpoint()?;
+        self.disabled_operations.check("finalize_withdraw")?;
+
+        let eth_rpc = self.eth_rpc()?;
+        // Checked before the proof is generated rather than when it's submitted
+        self.near_rpc()?;
+
+        self.wait_for_eth_light_client(tx_hash).await?;
+
+        let proof = self
+            .rpc_scheduler
+            .run(eth_rpc.endpoint(), RpcPriority::Proof, async {
+                Ok(eth_proof::get_proof_for_event_from_rpc_or_source(
+                    eth_rpc.as_ref(),
+                    self.proof_data_source.as_deref(),
+                    tx_hash,
+                    log_index,
+                    &self.proof_options,
+                )
+                .await?)
+            })
+            .await?;
+        events::emit(
+            self.events.as_deref(),
+            ConnectorEvent::ProofGenerated {
+                transfer: TransferId::Eth { tx_hash, log_index },
+            },
+        );
 
-        let proof = eth_proof::get_proof_for_event(tx_hash, log_index, eth_endpoint).await?;
+        tracing::debug!("Retrieved Ethereum proof");
 
-        let mut args = Vec::new();
-        proof
-            .serialize(&mut args)
+        let proof_data = borsh::to_vec(&proof)
             .map_err(|_| BridgeSdkError::EthProofError("Failed to serialize proof".to_string()))?;
+        let proof_block_height = proof.block_number()?;
+        let result = self.submit_withdraw_proof(&proof).await;
+
+        self.keep_proof_on_failure(
+            TransferId::Eth { tx_hash, log_index },
+            &proof_data,
+            proof_block_height,
+            result,
+        )
+    }
 
-        tracing::debug!("Retrieved Ethereum proof");
+    async fn submit_withdraw_proof(&self, proof: &eth_proof::Proof) -> Result<CryptoHash> {
+        let near_rpc = self.near_rpc()?;
+        let (method_name, args) = self.contract_version.finalize_withdraw_call(proof)?;
 
-        let tx_hash = near_rpc_client::change(
-            near_endpoint,
+        self.check_withdraw_recipient_storage(&proof.log_entry_data)
+            .await?;
+
+        let storage_byte_cost = near_rpc.get_storage_byte_cost().await?;
+        // One extra yoctoNEAR is forwarded by the locker with `ft_transfer`
+        let deposit = self
+            .near_tx_options
+            .deposit(USED_PROOF_STORAGE_BYTES * storage_byte_cost + 1);
+
+        tracing::debug!(deposit, "Calculated required storage deposit");
+
+        let tx_hash = chunked_upload::near_change(
+            near_rpc.as_ref(),
             self.near_signer()?,
             self.token_locker_id()?.to_string(),
-            "withdraw".to_string(),
+            self.method_names.near(method_name),
             args,
-            300_000_000_000_000,
-            60_000_000_000_000_000_000_000,
+            self.near_tx_options.gas(300_000_000_000_000),
+            deposit,
+            self.dry_run,
+            self.near_tx_options.proof_chunk_size,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
         )
         .await?;
 
@@ -324,6 +1134,602 @@ impl Nep141Connector {
         Ok(tx_hash)
     }
 
+    /// Finalizes the transfer again after a failed `finalize_deposit` or `finalize_withdraw`, reusing the proof kept
+    /// by `proof_store` if the light client of the destination chain still knows the proven block. The proof is
+    /// generated again otherwise, or if none was stored
+    #[tracing::instrument(skip_all, name = "RETRY FINALIZE")]
+    pub async fn retry_finalize(
+        &self,
+        transfer_id: TransferId,
+        tx_opts: EthTxOptions,
+    ) -> Result<TransactionId> {
+        let stored_proof = match &self.proof_store {
+            Some(proof_store) => proof_store.load(&transfer_id)?,
+            None => None,
+        };
+
+        match transfer_id {
+            TransferId::Near { receipt_id } => {
+                self.disabled_operations.check("finalize_deposit")?;
+                self.contract_version
+                    .check_light_client_proofs("finalize_deposit")?;
+
+                let near_on_eth_client = NearOnEthClient::new(
+                    self.near_light_client_address()?,
+                    self.eth_endpoint()?.to_string(),
+                );
+                let stored_proof = match stored_proof {
+                    Some(stored_proof)
+                        if near_on_eth_client
+                            .get_block_hash(stored_proof.proof_block_height)
+                            .await?
+                            != [0; 32] =>
+                    {
+                        Some(stored_proof)
+                    }
+                    _ => None,
+                };
+
+                let tx_hash = match stored_proof {
+                    Some(stored_proof) => {
+                        tracing::info!(
+                            proof_block_height = stored_proof.proof_block_height,
+                            "Reusing the stored proof"
+                        );
+                        self.submit_deposit_proof(
+                            receipt_id,
+                            stored_proof.proof,
+                            stored_proof.proof_block_height,
+                            tx_opts,
+                        )
+                        .await?
+                    }
+                    None => self.finalize_deposit(receipt_id, tx_opts).await?,
+                };
+
+                Ok(TransactionId::Eth(tx_hash))
+            }
+            TransferId::Eth { tx_hash, log_index } => {
+                self.disabled_operations.check("finalize_withdraw")?;
+
+                let stored_proof = match stored_proof {
+                    Some(stored_proof) => {
+                        let proof = borsh::from_slice::<eth_proof::Proof>(&stored_proof.proof)
+                            .map_err(|_| {
+                                BridgeSdkError::EthProofError("Invalid stored proof".to_string())
+                            })?;
+
+                        self.eth_light_client_knows(&proof)
+                            .await?
+                            .then_some((proof, stored_proof))
+                    }
+                    None => None,
+                };
+
+                let near_tx_hash = match stored_proof {
+                    Some((proof, stored_proof)) => {
+                        tracing::info!(
+                            proof_block_height = stored_proof.proof_block_height,
+                            "Reusing the stored proof"
+                        );
+                        let result = self.submit_withdraw_proof(&proof).await;
+
+                        self.keep_proof_on_failure(
+                            transfer_id,
+                            &stored_proof.proof,
+                            stored_proof.proof_block_height,
+                            result,
+                        )?
+                    }
+                    None => self.finalize_withdraw(tx_hash, log_index).await?,
+                };
+
+                Ok(TransactionId::Near(near_tx_hash))
+            }
+        }
+    }
+
+    /// Same as `finalize_withdraw`, proving the withdrawal event the bridge token factory emitted in the transaction
+    #[tracing::instrument(skip_all, name = "FINALIZE WITHDRAW")]
+    pub async fn finalize_withdraw_from_tx(&self, tx_hash: TxHash) -> Result<CryptoHash> {
+        self.disabled_operations.check("finalize_withdraw")?;
+
+        let log_index = self.withdraw_log_index(tx_hash).await?;
+
+        self.finalize_withdraw(tx_hash, log_index).await
+    }
+
+    /// Index of the withdrawal event the bridge token factory emitted in the transaction
+    pub async fn withdraw_log_index(&self, tx_hash: TxHash) -> Result<u64> {
+        let log_index = bridge_connector::find_event_log_index(
+            self.eth_endpoint()?,
+            tx_hash,
+            self.bridge_token_factory_address()?,
+            self.contract_version.withdraw_event(),
+        )
+        .await?;
+
+        tracing::debug!(log_index, "Found withdrawal event");
+
+        Ok(log_index)
+    }
+
+    /// Probes the token locker and the bridge token factory for view methods only the omni contracts have, and switches
+    /// the connector to the detected version so that the matching encodings are used. Unset contracts aren't probed
+    pub async fn detect_contract_interface(&mut self) -> Result<ContractInterface> {
+        let mut interface = ContractInterface::default();
+
+        if self.token_locker_id.is_some() {
+            let probe = self
+                .view::<serde_json::Value>(
+                    self.token_locker_id()?,
+                    "get_token_id",
+                    TokenAddressArgs {
+                        address: OmniAddress::Eth(Address::zero()),
+                    },
+                )
+                .await;
+
+            interface.token_locker = match probe {
+                Err(BridgeSdkError::NearRpcError(error))
+                    if format!("{error:?}").contains("MethodNotFound") =>
+                {
+                    Some(ContractVersion::V1)
+                }
+                // The omni locker panics for the unknown token, which still shows that the method exists
+                Err(BridgeSdkError::NearRpcError(_)) | Ok(_) => Some(ContractVersion::Omni),
+                Err(error) => return Err(error),
+            };
+        }
+
+        if self.bridge_token_factory_address.is_some() {
+            let probe = self
+                .omni_bridge_reader()?
+                .current_origin_nonce()
+                .call()
+                .await;
+
+            interface.bridge_token_factory = match probe {
+                Ok(_) => Some(ContractVersion::Omni),
+                Err(error) if error.is_revert() => Some(ContractVersion::V1),
+                Err(error) => return Err(error.into()),
+            };
+        }
+
+        self.contract_version = interface.version()?;
+
+        tracing::info!(
+            token_locker = ?interface.token_locker,
+            bridge_token_factory = ?interface.bridge_token_factory,
+            "Detected contract version {:?}",
+            self.contract_version
+        );
+
+        Ok(interface)
+    }
+
+    /// Verifies the configured capabilities: RPC reachability, presence of the bridge contracts and signer balances. Unset configuration values are skipped
+    pub async fn self_check(&self) -> SelfCheckReport {
+        let eth_endpoint = self.eth_endpoint.as_deref();
+        let near_endpoint = self.near_endpoint.as_deref();
+        let mut report = SelfCheckReport::default();
+
+        report.push(
+            "Ethereum RPC",
+            self_check::check_eth_rpc(eth_endpoint).await,
+        );
+        report.push("NEAR RPC", self_check::check_near_rpc(near_endpoint).await);
+        report.push(
+            "Bridge token factory",
+            self_check::check_eth_code(
+                eth_endpoint,
+                self.bridge_token_factory_address
+                    .as_ref()
+                    .map(|_| self.bridge_token_factory_address()),
+            )
+            .await,
+        );
+        report.push(
+            "NEAR light client",
+            self_check::check_eth_code(
+                eth_endpoint,
+                self.near_light_client_address
+                    .as_ref()
+                    .map(|_| self.near_light_client_address()),
+            )
+            .await,
+        );
+        report.push(
+            "Token locker",
+            self_check::check_near_code(near_endpoint, self.token_locker_id.as_deref()).await,
+        );
+        report.push(
+            "Ethereum signer balance",
+            self_check::check_eth_balance(
+                eth_endpoint,
+                self.eth_private_key
+                    .as_ref()
+                    .map(|_| self.eth_signer().map(|wallet| wallet.address())),
+            )
+            .await,
+        );
+        report.push(
+            "NEAR signer balance",
+            self_check::check_near_balance(near_endpoint, self.near_signer.as_deref()).await,
+        );
+
+        report
+    }
+
+    /// Sends a transaction calling an arbitrary Ethereum contract function, signed with the configured Ethereum key
+    #[tracing::instrument(skip_all, name = "CALL ETH CONTRACT")]
+    pub async fn call_eth_contract(
+        &self,
+        address: Address,
+        abi_fn: &str,
+        args: Vec<Token>,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("call_eth_contract")?;
+
+        contract_call::call_eth_contract(
+            self.eth_client()?,
+            address,
+            abi_fn,
+            args,
+            tx_opts,
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await
+    }
+
+    /// Calls an arbitrary NEAR contract method, signed with the configured NEAR account
+    #[tracing::instrument(skip_all, name = "CALL NEAR CONTRACT")]
+    pub async fn call_near_contract(
+        &self,
+        account_id: AccountId,
+        method_name: String,
+        args: Vec<u8>,
+        gas: u64,
+        deposit: u128,
+    ) -> Result<CryptoHash> {
+        self.disabled_operations.check("call_near_contract")?;
+
+        let tx_hash = signer::near_change(
+            self.near_rpc()?.as_ref(),
+            self.near_signer()?,
+            account_id.to_string(),
+            method_name,
+            args,
+            gas,
+            deposit,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(tx_hash = format!("{:?}", tx_hash), "Sent contract call transaction");
+
+        Ok(tx_hash)
+    }
+
+    /// Stores the proof of a failed finalization for `retry_finalize`, or removes the stored one once the finalization
+    /// is sent. Failing to update the store is only logged, the outcome of the finalization is returned either way
+    fn keep_proof_on_failure<T>(
+        &self,
+        transfer_id: TransferId,
+        proof: &[u8],
+        proof_block_height: u64,
+        result: Result<T>,
+    ) -> Result<T> {
+        let Some(proof_store) = self.proof_store.as_ref().filter(|_| !self.dry_run) else {
+            return result;
+        };
+
+        let update = match &result {
+            Ok(_) => proof_store.remove(&transfer_id),
+            Err(_) => proof_store
+                .save(&transfer_id, proof.to_vec(), proof_block_height)
+                .map(|_| {
+                    tracing::warn!(
+                        proof_block_height,
+                        "Stored the proof of the failed finalization for `retry_finalize`"
+                    )
+                }),
+        };
+        if let Err(err) = update {
+            tracing::warn!("Failed to update the proof store: {err}");
+        }
+
+        result
+    }
+
+    /// Whether the Ethereum light client on Near still has the block of the proof. Assumed when the light client
+    /// isn't configured, the proof is then rejected by the contract if it's not
+    async fn eth_light_client_knows(&self, proof: &eth_proof::Proof) -> Result<bool> {
+        let Some(eth_light_client_account_id) = &self.eth_light_client_account_id else {
+            return Ok(true);
+        };
+
+        let eth_on_near_client = EthOnNearClient::new(
+            AccountId::from_str(eth_light_client_account_id).map_err(|_| {
+                BridgeSdkError::ConfigError("Invalid Ethereum light client account id".to_string())
+            })?,
+            self.near_endpoint()?.to_string(),
+        );
+        let block_hash = eth_on_near_client
+            .block_hash_safe(proof.block_number()?)
+            .await?;
+
+        Ok(block_hash == Some(proof.block_hash().0))
+    }
+
+    async fn wait_for_eth_light_client(&self, tx_hash: TxHash) -> Result<()> {
+        let Some(eth_light_client_account_id) = &self.eth_light_client_account_id else {
+            return Ok(());
+        };
+        if !self.wait_for_light_client {
+            return Ok(());
+        }
+
+        let eth_on_near_client = EthOnNearClient::new(
+            AccountId::from_str(eth_light_client_account_id).map_err(|_| {
+                BridgeSdkError::ConfigError("Invalid Ethereum light client account id".to_string())
+            })?,
+            self.near_endpoint()?.to_string(),
+        );
+
+        bridge_connector::wait_for_eth_light_client_sync(
+            self.eth_endpoint()?,
+            &eth_on_near_client,
+            tx_hash,
+            self.light_client_sync_timeout,
+            self.events.as_deref(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_near_proof(&self, receipt_id: CryptoHash) -> Result<(Vec<u8>, u64)> {
+        let (proof_block_height, block_hash) = self.near_light_client_head(&[receipt_id]).await?;
+
+        let buffer = self.get_near_proof_at(receipt_id, block_hash).await?;
+
+        tracing::debug!(proof_block_height, "Retrieved Near proof");
+
+        Ok((buffer, proof_block_height))
+    }
+
+    /// Height and hash of the latest Near block of the light client on Ethereum, once it has synced the blocks of all
+    /// the receipts
+    async fn near_light_client_head(
+        &self,
+        receipt_ids: &[CryptoHash],
+    ) -> Result<(u64, CryptoHash)> {
+        let eth_endpoint = self.eth_endpoint()?;
+        let near_rpc = self.near_rpc()?;
+
+        let near_on_eth_client =
+            NearOnEthClient::new(self.near_light_client_address()?, eth_endpoint.to_string());
+
+        if self.wait_for_light_client {
+            for receipt_id in receipt_ids {
+                bridge_connector::wait_for_light_client_sync(
+                    near_rpc.as_ref(),
+                    &near_on_eth_client,
+                    self.locker_receipt(*receipt_id)?,
+                    self.light_client_sync_timeout,
+                    self.events.as_deref(),
+                )
+                .await?;
+            }
+        }
+
+        let proof_block_height = near_on_eth_client.get_sync_height().await?;
+        let block_hash = near_on_eth_client
+            .get_block_hash(proof_block_height)
+            .await?;
+
+        tracing::debug!(proof_block_height, "Retrieved light client block height");
+
+        Ok((proof_block_height, CryptoHash(block_hash)))
+    }
+
+    /// Proof of the receipt against the Near block `light_client_head`
+    async fn get_near_proof_at(
+        &self,
+        receipt_id: CryptoHash,
+        light_client_head: CryptoHash,
+    ) -> Result<Vec<u8>> {
+        let near_rpc = self.near_rpc()?;
+        let transfer = TransferId::Near { receipt_id };
+        let receipt_id = self.locker_receipt(receipt_id)?;
+
+        let proof_data = self
+            .rpc_scheduler
+            .run(near_rpc.endpoint(), RpcPriority::Proof, async {
+                Ok(near_rpc
+                    .get_light_client_proof(receipt_id, light_client_head)
+                    .await?)
+            })
+            .await?;
+        events::emit(
+            self.events.as_deref(),
+            ConnectorEvent::ProofGenerated { transfer },
+        );
+
+        let mut buffer: Vec<u8> = Vec::new();
+        proof_data.serialize(&mut buffer).map_err(|_| {
+            BridgeSdkError::NearProofError("Falied to deserialize proof".to_string())
+        })?;
+
+        Ok(buffer)
+    }
+
+    fn locker_receipt(&self, receipt_id: CryptoHash) -> Result<TransactionOrReceiptId> {
+        Ok(TransactionOrReceiptId::Receipt {
+            receipt_id,
+            receiver_id: AccountId::from_str(self.token_locker_id()?).map_err(|err| {
+                BridgeSdkError::ConfigError(format!("Invalid token locker account id: {err}"))
+            })?,
+        })
+    }
+
+    /// The locker can't transfer tokens to an account that isn't registered on the token, so the recipient is checked
+    /// before the proof is submitted
+    async fn check_withdraw_recipient_storage(&self, log_entry_data: &[u8]) -> Result<()> {
+        let (token, recipient) = self
+            .contract_version
+            .withdraw_event_transfer(log_entry_data)?;
+        let token_id: AccountId = match token {
+            WithdrawnToken::Near(token_id) => token_id,
+            WithdrawnToken::Eth(address) => self.get_nep141_for_erc20(address).await?,
+        };
+
+        let Some(deposit) = self
+            .storage_registration_deposit(token_id.as_str(), recipient.as_str())
+            .await?
+        else {
+            return Ok(());
+        };
+
+        if !self.register_withdraw_recipient {
+            return Err(BridgeSdkError::InvalidArgument(format!(
+                "Recipient {recipient} isn't registered on {token_id}. Register it with `storage_deposit` or enable `register_withdraw_recipient`"
+            )));
+        }
+
+        let tx_hash = signer::near_change(
+            self.near_rpc()?.as_ref(),
+            self.near_signer()?,
+            token_id.to_string(),
+            self.method_names.near("storage_deposit"),
+            near_args::to_bytes(&StorageDepositArgs {
+                account_id: Some(recipient.to_string()),
+                registration_only: Some(true),
+            }),
+            self.near_tx_options.gas(300_000_000_000_000),
+            deposit,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = tx_hash.to_string(),
+            recipient = recipient.to_string(),
+            "Sent storage deposit transaction for the withdrawal recipient"
+        );
+
+        Ok(())
+    }
+
+    /// Deposit registering `account_id` on the token, or `None` if it is registered already
+    async fn storage_registration_deposit(
+        &self,
+        token_id: &str,
+        account_id: &str,
+    ) -> Result<Option<u128>> {
+        let storage_balance: Option<serde_json::Value> = self
+            .view(
+                token_id,
+                "storage_balance_of",
+                AccountIdArgs {
+                    account_id: account_id.to_string(),
+                },
+            )
+            .await?;
+        if storage_balance.is_some() {
+            return Ok(None);
+        }
+
+        let bounds: serde_json::Value = self
+            .view(token_id, "storage_balance_bounds", EmptyArgs {})
+            .await?;
+        let deposit = bounds["min"]
+            .as_str()
+            .and_then(|min| min.parse::<u128>().ok())
+            .ok_or(BridgeSdkError::NearOutcomeError(format!(
+                "Invalid storage_balance_bounds of {token_id}"
+            )))?;
+
+        Ok(Some(deposit))
+    }
+
+    /// Waits for a transaction of the Near signer to be executed, failing if it failed
+    async fn wait_for_near_tx(&self, tx_hash: CryptoHash) -> Result<FinalExecutionOutcomeView> {
+        let outcome = self
+            .near_rpc()?
+            .wait_for_tx_final_outcome(
+                tx_hash,
+                self.near_signer()?.account_id(),
+                near_rpc_client::DEFAULT_WAIT_FINAL_OUTCOME_TIMEOUT_SEC,
+            )
+            .await?;
+
+        match &outcome.status {
+            FinalExecutionStatus::SuccessValue(_) => Ok(outcome),
+            status => Err(BridgeSdkError::NearOutcomeError(format!(
+                "Transaction {tx_hash} failed: {status:?}"
+            ))),
+        }
+    }
+
+    async fn view<T: DeserializeOwned>(
+        &self,
+        contract_id: &str,
+        method_name: &str,
+        args: impl Serialize,
+    ) -> Result<T> {
+        let contract_id = AccountId::from_str(contract_id).map_err(|_| {
+            BridgeSdkError::InvalidArgument(format!("Invalid account id: {contract_id}"))
+        })?;
+        let response = self
+            .near_rpc()?
+            .view_raw(
+                BlockReference::Finality(Finality::Final),
+                contract_id.clone(),
+                method_name.to_string(),
+                near_args::to_bytes(&args),
+            )
+            .await?;
+
+        match response.kind {
+            QueryResponseKind::CallResult(result) => serde_json::from_slice(&result.result).ok(),
+            _ => None,
+        }
+        .ok_or(BridgeSdkError::NearOutcomeError(format!(
+            "Invalid {method_name} response of {contract_id}"
+        )))
+    }
+
+    fn eth_ws_endpoint(&self) -> Result<&str> {
+        Ok(self
+            .eth_ws_endpoint
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Ethereum WebSocket endpoint is not set".to_string(),
+            ))?)
+    }
+
+    fn eth_rpc(&self) -> Result<Arc<dyn EthRpc>> {
+        match &self.eth_rpc {
+            Some(eth_rpc) => Ok(eth_rpc.clone()),
+            None => Ok(Arc::new(EthRPCClient::new_with_options(
+                self.eth_endpoint()?,
+                self.eth_rpc_options.clone(),
+            ))),
+        }
+    }
+
     fn eth_endpoint(&self) -> Result<&str> {
         Ok(self
             .eth_endpoint
@@ -333,6 +1739,19 @@ impl Nep141Connector {
             ))?)
     }
 
+    fn near_rpc(&self) -> Result<Arc<dyn NearRpc>> {
+        match &self.near_rpc {
+            Some(near_rpc) => Ok(near_rpc.clone()),
+            // Cached, so that the operations share the health of the endpoints
+            None => self.near_rpc_cache.get_or_try_init(|| {
+                Ok(Arc::new(
+                    NearRpcClient::new(self.near_endpoint()?)
+                        .with_retry_policy(self.near_retry_policy),
+                ))
+            }),
+        }
+    }
+
     fn near_endpoint(&self) -> Result<&str> {
         Ok(self
             .near_endpoint
@@ -351,6 +1770,15 @@ impl Nep141Connector {
             ))?)
     }
 
+    fn wrapped_near_id(&self) -> Result<&str> {
+        Ok(self
+            .wrapped_near_id
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "wNEAR account id is not set".to_string(),
+            ))?)
+    }
+
     fn near_light_client_address(&self) -> Result<Address> {
         self.near_light_client_address
             .as_ref()
@@ -381,7 +1809,11 @@ impl Nep141Connector {
             })
     }
 
-    fn near_signer(&self) -> Result<near_crypto::InMemorySigner> {
+    fn near_signer(&self) -> Result<Arc<dyn NearSigner>> {
+        if let Some(signer) = &self.custom_near_signer {
+            return Ok(signer.clone());
+        }
+
         let near_private_key =
             self.near_private_key
                 .as_ref()
@@ -395,64 +1827,80 @@ impl Nep141Connector {
                 "Near signer account id is not set".to_string(),
             ))?;
 
-        Ok(near_crypto::InMemorySigner::from_secret_key(
+        Ok(Arc::new(near_crypto::InMemorySigner::from_secret_key(
             AccountId::from_str(near_signer).map_err(|_| {
                 BridgeSdkError::ConfigError("Invalid near signer account id".to_string())
             })?,
             SecretKey::from_str(near_private_key)
                 .map_err(|_| BridgeSdkError::ConfigError("Invalid near private key".to_string()))?,
-        ))
+        )))
     }
 
-    fn bridge_token_factory(
-        &self,
-    ) -> Result<BridgeTokenFactory<SignerMiddleware<Provider<Http>, LocalWallet>>> {
-        let eth_endpoint = self
-            .eth_endpoint
-            .as_ref()
-            .ok_or(BridgeSdkError::ConfigError(
-                "Ethereum rpc endpoint is not set".to_string(),
-            ))?;
-
-        let eth_provider = Provider::<Http>::try_from(eth_endpoint).map_err(|_| {
-            BridgeSdkError::ConfigError("Invalid ethereum rpc endpoint url".to_string())
-        })?;
+    fn bridge_token_factory(&self) -> Result<BridgeTokenFactory<EthSignerClient>> {
+        Ok(BridgeTokenFactory::new(
+            self.bridge_token_factory_address()?,
+            self.eth_client()?,
+        ))
+    }
 
-        let wallet = self.eth_signer()?;
+    /// Bridge token factory for view calls, which don't need an Ethereum signer
+    fn bridge_token_factory_reader(&self) -> Result<BridgeTokenFactory<EthProvider>> {
+        Ok(BridgeTokenFactory::new(
+            self.bridge_token_factory_address()?,
+            self.eth_provider()?,
+        ))
+    }
 
-        let signer = SignerMiddleware::new(eth_provider, wallet);
-        let client = Arc::new(signer);
+    fn omni_bridge(&self) -> Result<OmniBridge<EthSignerClient>> {
+        Ok(OmniBridge::new(
+            self.bridge_token_factory_address()?,
+            self.eth_client()?,
+        ))
+    }
 
-        Ok(BridgeTokenFactory::new(
+    /// `OmniBridge` for view calls, which don't need an Ethereum signer
+    fn omni_bridge_reader(&self) -> Result<OmniBridge<EthProvider>> {
+        Ok(OmniBridge::new(
             self.bridge_token_factory_address()?,
-            client,
+            self.eth_provider()?,
         ))
     }
 
-    fn bridge_token(
-        &self,
-        address: Address,
-    ) -> Result<ERC20<SignerMiddleware<Provider<Http>, LocalWallet>>> {
-        let eth_endpoint = self
-            .eth_endpoint
-            .as_ref()
-            .ok_or(BridgeSdkError::ConfigError(
-                "Ethereum rpc endpoint is not set".to_string(),
-            ))?;
+    fn bridge_token(&self, address: Address) -> Result<ERC20<EthSignerClient>> {
+        Ok(ERC20::new(address, self.eth_client()?))
+    }
 
-        let eth_provider = Provider::<Http>::try_from(eth_endpoint).map_err(|_| {
-            BridgeSdkError::ConfigError("Invalid ethereum rpc endpoint url".to_string())
-        })?;
+    fn eth_provider(&self) -> Result<Arc<EthProvider>> {
+        self.eth_provider_cache.get_or_try_init(|| {
+            let eth_provider = failover::eth_provider(self.eth_endpoint()?)?;
 
-        let wallet = self.eth_signer()?;
+            Ok(Arc::new(eth_provider))
+        })
+    }
 
-        let signer = SignerMiddleware::new(eth_provider, wallet);
-        let client = Arc::new(signer);
+    /// Signer middleware sharing the connections of [`Self::eth_provider`]
+    fn eth_client(&self) -> Result<Arc<EthSignerClient>> {
+        self.eth_client_cache.get_or_try_init(|| {
+            let eth_provider = self.eth_provider()?.as_ref().clone();
+            let wallet = self.eth_signer()?;
 
-        Ok(ERC20::new(address, client))
+            Ok(Arc::new(SignerMiddleware::new(eth_provider, wallet)))
+        })
     }
 
-    fn eth_signer(&self) -> Result<LocalWallet> {
+    fn eth_signer(&self) -> Result<DynEthSigner> {
+        if let Some(signer) = &self.custom_eth_signer {
+            return Ok(DynEthSigner::new(signer.clone()));
+        }
+
+        if let Some(eth_keystore_path) = &self.eth_keystore_path {
+            return signer::keystore_signer(
+                eth_keystore_path,
+                self.eth_keystore_password.as_deref(),
+                self.eth_chain_id,
+            );
+        }
+
         let eth_private_key = self
             .eth_private_key
             .as_ref()
@@ -480,8 +1928,284 @@ impl Nep141Connector {
             ));
         }
 
-        Ok(LocalWallet::from_bytes(&private_key_bytes)
+        let wallet = LocalWallet::from_bytes(&private_key_bytes)
             .map_err(|_| BridgeSdkError::ConfigError("Invalid ethereum private key".to_string()))?
-            .with_chain_id(eth_chain_id))
+            .with_chain_id(eth_chain_id);
+
+        Ok(DynEthSigner::new(Arc::new(wallet)))
+    }
+}
+
+#[async_trait]
+impl BridgeConnector for Nep141Connector {
+    type TransferRequest = TokenTransfer;
+
+    async fn initiate_transfer(&self, request: TokenTransfer) -> Result<TransactionId> {
+        match request.direction {
+            TransferDirection::NearToEth => Ok(TransactionId::Near(
+                self.deposit(request.token, request.amount, request.recipient)
+                    .await?,
+            )),
+            TransferDirection::EthToNear => Ok(TransactionId::Eth(
+                self.withdraw(
+                    request.token,
+                    request.amount,
+                    request.recipient,
+                    EthTxOptions::default(),
+                )
+                .await?,
+            )),
+        }
+    }
+
+    async fn finalize_transfer(&self, transfer_id: TransferId) -> Result<TransactionId> {
+        match transfer_id {
+            TransferId::Near { receipt_id } => Ok(TransactionId::Eth(
+                self.finalize_deposit(receipt_id, EthTxOptions::default())
+                    .await?,
+            )),
+            TransferId::Eth { tx_hash, log_index } => Ok(TransactionId::Near(
+                self.finalize_withdraw(tx_hash, log_index).await?,
+            )),
+        }
+    }
+
+    async fn get_status(&self, transfer_id: TransferId) -> Result<TransferStatus> {
+        match transfer_id {
+            TransferId::Near { receipt_id } => {
+                bridge_connector::near_transfer_status(
+                    self.near_rpc()?.as_ref(),
+                    self.eth_endpoint()?,
+                    self.near_light_client_address()?,
+                    receipt_id,
+                    AccountId::from_str(self.token_locker_id()?).map_err(|_| {
+                        BridgeSdkError::ConfigError("Invalid token locker account id".to_string())
+                    })?,
+                )
+                .await
+            }
+            TransferId::Eth { tx_hash, .. } => {
+                bridge_connector::eth_transfer_status(self.eth_endpoint()?, tx_hash).await
+            }
+        }
+    }
+
+    async fn estimate_fees(&self, request: &TokenTransfer) -> Result<FeeEstimate> {
+        match request.direction {
+            TransferDirection::NearToEth => Ok(FeeEstimate {
+                near_deposit: 1,
+                near_gas: self.near_tx_options.gas(300_000_000_000_000),
+                token_fee: 0,
+            }),
+            TransferDirection::EthToNear => {
+                let storage_byte_cost = self.near_rpc()?.get_storage_byte_cost().await?;
+
+                Ok(FeeEstimate {
+                    near_deposit: self
+                        .near_tx_options
+                        .deposit(USED_PROOF_STORAGE_BYTES * storage_byte_cost + 1),
+                    near_gas: self.near_tx_options.gas(300_000_000_000_000),
+                    token_fee: 0,
+                })
+            }
+        }
+    }
+}
+
+/// Sends a transaction for each item, one after the other. With a nonce in `tx_opts`, each transaction gets the one
+/// following the previous transaction, which keeps its nonce if it wasn't sent, e.g. because its gas estimation failed
+async fn send_each<T, F, Fut>(
+    items: impl IntoIterator<Item = T>,
+    tx_opts: EthTxOptions,
+    mut send: F,
+) -> Vec<Result<TxHash>>
+where
+    F: FnMut(T, EthTxOptions) -> Fut,
+    Fut: Future<Output = Result<TxHash>>,
+{
+    let mut tx_opts = tx_opts;
+    let mut results = Vec::new();
+    for item in items {
+        let result = send(item, tx_opts.clone()).await;
+        if matches!(result, Ok(_) | Err(BridgeSdkError::EthTxFailed { .. })) {
+            tx_opts = tx_opts.next();
+        }
+        results.push(result);
+    }
+
+    results
+}
+
+async fn check_bridged_balance(
+    bridge_token: &ERC20<EthSignerClient>,
+    owner: Address,
+    amount: U256,
+) -> Result<()> {
+    let balance = bridge_token.balance_of(owner).call().await?;
+
+    match balance < amount {
+        true => Err(BridgeSdkError::InsufficientBalance {
+            have: balance,
+            need: amount,
+        }),
+        false => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::{InMemorySigner, KeyType};
+    use near_primitives::transaction::Action;
+    use near_rpc_client::{MockNearRpc, NearRpcError};
+
+    const TOKEN_LOCKER_ID: &str = "locker.near";
+
+    fn connector(near_rpc: Arc<MockNearRpc>) -> Nep141Connector {
+        connector_builder(near_rpc).build().unwrap()
+    }
+
+    fn connector_builder(near_rpc: Arc<MockNearRpc>) -> Nep141ConnectorBuilder {
+        let signer = InMemorySigner::from_seed(
+            "relayer.near".parse().unwrap(),
+            KeyType::ED25519,
+            "relayer.near",
+        );
+
+        let mut builder = Nep141ConnectorBuilder::default();
+        builder
+            .eth_endpoint(None)
+            .eth_chain_id(None)
+            .eth_private_key(None)
+            .bridge_token_factory_address(None)
+            .near_endpoint(None)
+            .near_private_key(None)
+            .near_signer(None)
+            .token_locker_id(Some(TOKEN_LOCKER_ID.to_string()))
+            .near_light_client_address(None)
+            .custom_near_signer(Some(Arc::new(signer)))
+            .near_rpc(Some(near_rpc));
+        builder
+    }
+
+    #[tokio::test]
+    async fn test_log_token_metadata_sends_call_through_near_rpc() {
+        let near_rpc = Arc::new(MockNearRpc::new());
+
+        let tx_hash = connector(near_rpc.clone())
+            .log_token_metadata("token.near".to_string())
+            .await
+            .unwrap();
+
+        let sent = near_rpc.sent_transactions();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].get_hash(), tx_hash);
+        assert_eq!(sent[0].transaction.receiver_id.as_str(), TOKEN_LOCKER_ID);
+
+        let Action::FunctionCall(call) = &sent[0].transaction.actions[0] else {
+            panic!("Expected a function call");
+        };
+        assert_eq!(call.method_name, "log_metadata");
+        assert_eq!(
+            call.args,
+            near_args::to_bytes(&TokenIdArgs {
+                token_id: "token.near".to_string(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_near_call_failure_is_returned() {
+        let near_rpc = Arc::new(MockNearRpc::new());
+        near_rpc.set_call_failure(
+            TOKEN_LOCKER_ID.parse().unwrap(),
+            "log_metadata",
+            "Smart contract panicked: ERR_TOKEN_NOT_FOUND",
+        );
+
+        let err = connector(near_rpc.clone())
+            .log_token_metadata("token.near".to_string())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            BridgeSdkError::NearRpcError(NearRpcError::ExecutionFailed { .. })
+        ));
+
+        // Only broadcast without an outcome timeout
+        let tx_hash = connector_builder(near_rpc.clone())
+            .near_outcome_timeout(None)
+            .build()
+            .unwrap()
+            .log_token_metadata("token.near".to_string())
+            .await
+            .unwrap();
+        assert_eq!(near_rpc.sent_transactions()[1].get_hash(), tx_hash);
+    }
+
+    #[tokio::test]
+    async fn test_finalize_deposits_rejects_repeated_receipts() {
+        let receipt_ids = vec![
+            CryptoHash([1; 32]),
+            CryptoHash([2; 32]),
+            CryptoHash([1; 32]),
+        ];
+
+        let err = connector(Arc::new(MockNearRpc::new()))
+            .finalize_deposits(receipt_ids, EthTxOptions::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, BridgeSdkError::InvalidArgument(_)));
+    }
+
+    #[tokio::test]
+    async fn test_send_each_continues_after_failures() {
+        let tx_opts = EthTxOptions {
+            nonce: Some(5.into()),
+            ..Default::default()
+        };
+        let mut nonces = Vec::new();
+
+        let results = send_each(0..4, tx_opts, |item, tx_opts| {
+            nonces.push(tx_opts.nonce.unwrap().as_u64());
+            async move {
+                match item {
+                    1 => Err(BridgeSdkError::EthTxFailed {
+                        tx_hash: TxHash::repeat_byte(item),
+                        reason: "Proof already used".to_string(),
+                    }),
+                    2 => Err(BridgeSdkError::SignerError("Rejected".to_string())),
+                    _ => Ok(TxHash::repeat_byte(item)),
+                }
+            }
+        })
+        .await;
+
+        // The transaction that wasn't sent leaves its nonce to the next one
+        assert_eq!(nonces, [5, 6, 7, 7]);
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap(), &TxHash::repeat_byte(0));
+        assert!(matches!(
+            results[1],
+            Err(BridgeSdkError::EthTxFailed { .. })
+        ));
+        assert!(matches!(results[2], Err(BridgeSdkError::SignerError(_))));
+        assert_eq!(results[3].as_ref().unwrap(), &TxHash::repeat_byte(3));
+    }
+
+    #[test]
+    fn test_used_proof_storage_bytes() {
+        // `Proof::get_key` is the sha256 of the proof location, stored borsh serialized
+        let proof_key = borsh::to_vec(&vec![0u8; 32]).unwrap();
+        let index = 0u64.to_le_bytes();
+
+        // Element index `ui<key>` -> u64 and vector element `ue<index>` -> key of the `UnorderedSet`
+        let index_record = [b"ui".as_slice(), &proof_key].concat().len() + index.len();
+        let element_record = [b"ue".as_slice(), &index].concat().len() + proof_key.len();
+
+        assert_eq!(
+            USED_PROOF_STORAGE_BYTES as usize,
+            index_record + element_record + 2 * STORAGE_RECORD_OVERHEAD_BYTES
+        );
     }
 }
@@ -0,0 +1,83 @@
+use ethers::types::Address;
+use near_primitives::types::AccountId;
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+};
+
+/// NEP-141 to ERC-20 token mappings read from the bridge contracts. A mapping can't change once the token is
+/// deployed, so only missing tokens are looked up again. Clones share the mappings
+#[derive(Debug, Default, Clone)]
+pub(crate) struct TokenMappingCache {
+    erc20_by_nep141: Arc<RwLock<HashMap<String, Address>>>,
+    nep141_by_erc20: Arc<RwLock<HashMap<Address, AccountId>>>,
+}
+
+impl TokenMappingCache {
+    pub(crate) fn erc20(&self, near_token_id: &str) -> Option<Address> {
+        self.erc20_by_nep141
+            .read()
+            .expect("Token mapping cache lock is poisoned")
+            .get(near_token_id)
+            .copied()
+    }
+
+    pub(crate) fn nep141(&self, address: Address) -> Option<AccountId> {
+        self.nep141_by_erc20
+            .read()
+            .expect("Token mapping cache lock is poisoned")
+            .get(&address)
+            .cloned()
+    }
+
+    /// Records a mapping in both directions. The zero address, returned for tokens that aren't deployed yet, isn't
+    /// recorded
+    pub(crate) fn insert(&self, near_token_id: AccountId, address: Address) {
+        if address.is_zero() {
+            return;
+        }
+
+        self.erc20_by_nep141
+            .write()
+            .expect("Token mapping cache lock is poisoned")
+            .insert(near_token_id.to_string(), address);
+        self.nep141_by_erc20
+            .write()
+            .expect("Token mapping cache lock is poisoned")
+            .insert(address, near_token_id);
+    }
+
+    pub(crate) fn clear(&self) {
+        self.erc20_by_nep141
+            .write()
+            .expect("Token mapping cache lock is poisoned")
+            .clear();
+        self.nep141_by_erc20
+            .write()
+            .expect("Token mapping cache lock is poisoned")
+            .clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_mapping_cache() {
+        let cache = TokenMappingCache::default();
+        let token_id: AccountId = "token.near".parse().unwrap();
+        let address = Address::from_low_u64_be(1);
+
+        cache.insert(token_id.clone(), Address::zero());
+        assert_eq!(cache.erc20("token.near"), None);
+
+        cache.clone().insert(token_id.clone(), address);
+        assert_eq!(cache.erc20("token.near"), Some(address));
+        assert_eq!(cache.nep141(address), Some(token_id));
+
+        cache.clear();
+        assert_eq!(cache.erc20("token.near"), None);
+        assert_eq!(cache.nep141(address), None);
+    }
+}
@@ -0,0 +1,187 @@
+use bridge_connector_common::result::{BridgeSdkError, Result};
+use ethers::types::{Log, TxHash};
+use near_primitives::{
+    hash::CryptoHash,
+    views::{ExecutionStatusView, FinalExecutionOutcomeView},
+};
+use omni_connector::omni_types::DepositOutcome;
+use serde::Deserialize;
+
+use crate::ContractVersion;
+
+/// Result of `deposit_and_wait`, decoded from the executed transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepositResult {
+    pub tx_hash: CryptoHash,
+    /// Receipt of the token locker proven by `finalize_deposit`
+    pub receipt_id: CryptoHash,
+    /// Origin nonce assigned by the omni locker. Unset with the V1 locker
+    pub nonce: Option<u128>,
+    /// Amount locked, after any refund of the token to the sender
+    pub amount: u128,
+}
+
+/// Result of `withdraw_and_wait`, or an event received by `subscribe_withdraw_events`, decoded from the withdrawal event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WithdrawResult {
+    pub tx_hash: TxHash,
+    /// Index of the withdrawal event in the block, proven by `finalize_withdraw`
+    pub log_index: u64,
+    /// Origin nonce assigned by `OmniBridge`. Unset with the V1 factory
+    pub nonce: Option<u64>,
+    pub amount: u128,
+}
+
+/// Result of `bridge_near`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BridgeNearResult {
+    /// Deposit of the wrapped NEAR to the token locker
+    pub deposit: DepositResult,
+    /// Finalization minting the bridged wNEAR on Ethereum
+    pub finalize_tx_hash: TxHash,
+}
+
+impl WithdrawResult {
+    /// Decodes the withdrawal event emitted by the bridge token factory
+    pub(crate) fn from_log(log: &Log, contract_version: ContractVersion) -> Result<Self> {
+        let invalid =
+            |reason: &str| BridgeSdkError::InvalidEvent(format!("withdrawal event: {reason}"));
+        let (amount, nonce) = contract_version.withdraw_event_amount(&log.topics, &log.data)?;
+
+        Ok(Self {
+            tx_hash: log
+                .transaction_hash
+                .ok_or(invalid("missing transaction hash"))?,
+            log_index: log.log_index.ok_or(invalid("missing log index"))?.as_u64(),
+            nonce,
+            amount,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Nep297Event {
+    standard: String,
+    event: String,
+    data: Vec<FtTransferData>,
+}
+
+#[derive(Deserialize)]
+struct FtTransferData {
+    old_owner_id: String,
+    new_owner_id: String,
+    amount: String,
+}
+
+impl DepositResult {
+    /// Decodes the deposit from the outcome of the `ft_transfer_call` to the token locker
+    pub(crate) fn from_outcome(
+        outcome: &FinalExecutionOutcomeView,
+        token_locker_id: &str,
+        contract_version: ContractVersion,
+    ) -> Result<Self> {
+        let token_id = outcome.transaction.receiver_id.as_str();
+        let amount = outcome
+            .receipts_outcome
+            .iter()
+            .filter(|receipt| receipt.outcome.executor_id.as_str() == token_id)
+            .flat_map(|receipt| &receipt.outcome.logs)
+            .flat_map(|log| parse_ft_transfers(log))
+            .fold(
+                0i128,
+                |locked, (old_owner_id, new_owner_id, amount)| match (
+                    old_owner_id.as_str(),
+                    new_owner_id.as_str(),
+                ) {
+                    (_, locker) if locker == token_locker_id => locked + amount as i128,
+                    (locker, _) if locker == token_locker_id => locked - amount as i128,
+                    _ => locked,
+                },
+            );
+        let amount = u128::try_from(amount)
+            .ok()
+            .filter(|amount| *amount > 0)
+            .ok_or(BridgeSdkError::NearOutcomeError(
+                "No tokens were locked, the deposit was likely refunded".to_string(),
+            ))?;
+
+        let (receipt_id, nonce) = match contract_version {
+            ContractVersion::V1 => {
+                let receipt_id = outcome
+                    .receipts_outcome
+                    .iter()
+                    .find(|receipt| {
+                        receipt.outcome.executor_id.as_str() == token_locker_id
+                            && !matches!(receipt.outcome.status, ExecutionStatusView::Failure(_))
+                    })
+                    .map(|receipt| receipt.id)
+                    .ok_or(BridgeSdkError::NearOutcomeError(format!(
+                        "No successful receipt of {token_locker_id} in the deposit transaction"
+                    )))?;
+
+                (receipt_id, None)
+            }
+            ContractVersion::Omni => {
+                let deposit = DepositOutcome::from_outcome(outcome).ok_or(
+                    BridgeSdkError::NearOutcomeError(
+                        "InitTransferEvent was not emitted, the transfer was likely refunded"
+                            .to_string(),
+                    ),
+                )?;
+
+                (deposit.receipt_id, Some(deposit.nonce))
+            }
+        };
+
+        Ok(DepositResult {
+            tx_hash: outcome.transaction.hash,
+            receipt_id,
+            nonce,
+            amount,
+        })
+    }
+}
+
+/// Sender, receiver and amount of the transfers of a NEP-141 `ft_transfer` event log, e.g.
+/// `EVENT_JSON:{"standard":"nep141","event":"ft_transfer","data":[...]}`
+fn parse_ft_transfers(log: &str) -> Vec<(String, String, u128)> {
+    let Some(event) = log
+        .strip_prefix("EVENT_JSON:")
+        .and_then(|event| serde_json::from_str::<Nep297Event>(event).ok())
+    else {
+        return Vec::new();
+    };
+    if event.standard != "nep141" || event.event != "ft_transfer" {
+        return Vec::new();
+    }
+
+    event
+        .data
+        .into_iter()
+        .filter_map(|transfer| {
+            Some((
+                transfer.old_owner_id,
+                transfer.new_owner_id,
+                transfer.amount.parse().ok()?,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ft_transfer_events_are_parsed() {
+        let log = r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_transfer","data":[{"old_owner_id":"alice.near","new_owner_id":"locker.near","amount":"100","memo":"deposit"}]}"#;
+        assert_eq!(
+            parse_ft_transfers(log),
+            [("alice.near".to_string(), "locker.near".to_string(), 100)]
+        );
+
+        let mint = r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_mint","data":[{"owner_id":"alice.near","amount":"100"}]}"#;
+        assert!(parse_ft_transfers(mint).is_empty());
+        assert!(parse_ft_transfers("Transfer 100 from alice.near to locker.near").is_empty());
+    }
+}
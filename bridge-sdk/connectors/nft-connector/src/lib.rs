@@ -0,0 +1,6 @@
+#[macro_use]
+extern crate derive_builder;
+
+mod nft_connector;
+
+pub use nft_connector::{NftConnector, NftConnectorBuilder};
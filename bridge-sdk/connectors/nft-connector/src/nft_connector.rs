@@ -0,0 +1,972 @@
+use borsh::BorshSerialize;
+use bridge_connector_common::{
+    bridge_connector::{self, TransferDirection, TransferId},
+    chunked_upload,
+    client_cache::ClientCache,
+    contract_call::{self, EthTxOptions, NearTxOptions},
+    events::{self, ConnectorEvent, ConnectorEvents},
+    failover,
+    method_names::MethodNames,
+    near_args::{self, NftTransferCallArgs, TokenIdArgs},
+    operations::DisabledOperations,
+    result::{BridgeSdkError, Result},
+    rpc_scheduler::{RpcPriority, RpcScheduler},
+    screening::{self, ScreenedTransfer, TransferScreening},
+    self_check::{self, SelfCheckReport},
+    signer::{
+        self, CallbackEthSigner, CallbackNearSigner, DynEthSigner, EthSigner, EthSignerClient,
+        NearSigner,
+    },
+};
+use eth_light_client_on_near::EthOnNearClient;
+use eth_proof::{EthRPCClient, EthRPCClientOptions, EthRpc, ProofDataSource, ProofOptions};
+use ethers::{
+    abi::{Address, Token},
+    prelude::*,
+};
+use near_crypto::SecretKey;
+use near_light_client_on_eth::NearOnEthClient;
+use near_primitives::{
+    hash::CryptoHash,
+    types::{AccountId, TransactionOrReceiptId},
+};
+use near_rpc_client::{retry::RetryPolicy, NearRpc, NearRpcClient};
+use std::{path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+
+abigen!(
+    BridgeNftFactory,
+    r#"[
+      function newBridgeToken(bytes memory proofData, uint64 proofBlockHeight) external returns (address)
+      function deposit(bytes memory proofData, uint64 proofBlockHeight) external
+      function withdraw(string memory token, string memory tokenId, string memory recipient) external
+      function nearToEthToken(string calldata nearTokenId) external view returns (address)
+    ]"#
+);
+
+abigen!(
+    ERC721,
+    r#"[
+      function isApprovedForAll(address owner, address operator) external view returns (bool)
+      function setApprovalForAll(address operator, bool approved) external
+    ]"#
+);
+
+/// Storage the NFT locker records for each used proof, paid by the attached deposit. Like the token locker, it inserts
+/// the 32 bytes proof key into the `used_events` `UnorderedSet` with prefix `u`, which writes two records, see
+/// `test_used_proof_storage_bytes`
+const USED_PROOF_STORAGE_BYTES: u128 = 172;
+
+/// Storage usage charged by the Near runtime for each record on top of its key and value, `num_extra_bytes_record`
+/// of the runtime config
+#[cfg(test)]
+const STORAGE_RECORD_OVERHEAD_BYTES: usize = 40;
+
+/// Bridging NEAR-originated NEP-171 tokens to Ethereum as ERC-721 and back. Clones share the cached clients
+#[derive(Builder, Clone)]
+pub struct NftConnector {
+    #[doc = r"Ethereum RPC endpoint. Required for `deploy_token`, `finalize_deposit`, `withdraw`, `finalize_withdraw`"]
+    eth_endpoint: Option<String>,
+    #[doc = r"Ethereum chain id. Required for `deploy_token`, `finalize_deposit`, `withdraw`"]
+    eth_chain_id: Option<u64>,
+    #[doc = r"Ethereum private key. Required for `deploy_token`, `finalize_deposit`, `withdraw`"]
+    eth_private_key: Option<String>,
+    #[doc = r"Bridged NFT factory address on Ethereum. Required for `deploy_token`, `finalize_deposit`, `withdraw`"]
+    nft_factory_address: Option<String>,
+    #[doc = r"NEAR RPC endpoint. Required for `log_token_metadata`, `deploy_token`, `deposit`, `finalize_deposit`, `finalize_withdraw`"]
+    near_endpoint: Option<String>,
+    #[doc = r"NEAR private key. Required for `log_token_metadata`, `deposit`, `finalize_withdraw`"]
+    near_private_key: Option<String>,
+    #[doc = r"NEAR account id of the transaction signer. Required for `log_token_metadata`, `deposit`, `finalize_withdraw`"]
+    near_signer: Option<String>,
+    #[doc = r"NFT locker account id on Near. Required for `log_token_metadata`, `deploy_token`, `deposit`, `finalize_deposit`, `finalize_withdraw`"]
+    nft_locker_id: Option<String>,
+    #[doc = r"NEAR light client address on Ethereum. Required for `deploy_token`, `finalize_deposit`"]
+    near_light_client_address: Option<String>,
+    #[doc = r"Operations rejected with `OperationDisabled`, e.g. during an incident"]
+    #[builder(default)]
+    disabled_operations: DisabledOperations,
+    #[doc = r"Contract methods renamed by the deployment, e.g. `withdraw` deployed as `finish_withdraw`"]
+    #[builder(default)]
+    method_names: MethodNames,
+    #[doc = r"Gas and attached deposit of the Near transactions, overriding the connector defaults"]
+    #[builder(default)]
+    near_tx_options: NearTxOptions,
+    #[doc = r"Screening of the transfers before they are submitted, e.g. for sanctions compliance. A rejected transfer fails with `TransferRejected`"]
+    #[builder(default)]
+    transfer_screening: Option<Arc<dyn TransferScreening>>,
+    #[doc = r"Simulates transactions instead of sending them and returns the hashes they would have. Ethereum calls are checked with `eth_estimateGas` and `eth_call`, Near calls are run as view calls and checked against the signer balance"]
+    #[builder(default)]
+    dry_run: bool,
+    #[doc = r"Waits for the light client of the destination chain to sync the block of a proven transfer instead of failing. Enabled by default"]
+    #[builder(default = "true")]
+    wait_for_light_client: bool,
+    #[doc = r"How long to wait for a light client to sync before failing"]
+    #[builder(default = "bridge_connector::DEFAULT_LIGHT_CLIENT_SYNC_TIMEOUT")]
+    light_client_sync_timeout: Duration,
+    #[doc = r"Ethereum light client account id on Near. When set, proofs of Ethereum events wait for it to sync the proven block"]
+    #[builder(default)]
+    eth_light_client_account_id: Option<String>,
+    #[doc = r"Custom NEAR signer, e.g. a hardware wallet or a remote signer. Takes precedence over `near_private_key`"]
+    #[builder(default)]
+    custom_near_signer: Option<Arc<dyn NearSigner>>,
+    #[doc = r"Path of an encrypted web3 JSON keystore holding the Ethereum key. Takes precedence over `eth_private_key`"]
+    #[builder(default)]
+    eth_keystore_path: Option<String>,
+    #[doc = r"Password of the Ethereum keystore. Required with `eth_keystore_path`"]
+    #[builder(default)]
+    eth_keystore_password: Option<String>,
+    #[doc = r"Custom Ethereum signer, e.g. a hardware wallet or a remote signer. Takes precedence over `eth_private_key`"]
+    #[builder(default)]
+    custom_eth_signer: Option<Arc<dyn EthSigner>>,
+    #[doc = r"Near RPC serving the requests instead of `near_endpoint`, e.g. a `MockNearRpc` in tests"]
+    #[builder(default)]
+    near_rpc: Option<Arc<dyn NearRpc>>,
+    #[doc = r"Ethereum RPC serving the proof data instead of `eth_endpoint`, e.g. a `MockEthRpc` in tests"]
+    #[builder(default)]
+    eth_rpc: Option<Arc<dyn EthRpc>>,
+    #[doc = r"Retries of the Near requests failing with a transient error. Ignored with `near_rpc`"]
+    #[builder(default)]
+    near_retry_policy: RetryPolicy,
+    #[doc = r"Time each Near transaction is awaited for its outcome, failing if it did. Only broadcast with `None`"]
+    #[builder(default = "Some(signer::DEFAULT_NEAR_OUTCOME_TIMEOUT)")]
+    near_outcome_timeout: Option<Duration>,
+    #[doc = r"Retries, rate limit and response size of the Ethereum proof data requests. Ignored with `eth_rpc`"]
+    #[builder(default)]
+    eth_rpc_options: EthRPCClientOptions,
+    #[doc = r"Block cache and metrics of the generated Ethereum proofs"]
+    #[builder(default)]
+    proof_options: ProofOptions,
+    #[doc = r"Source of the Ethereum blocks the proofs are generated from, e.g. a local node database, instead of `eth_rpc`"]
+    #[builder(default)]
+    proof_data_source: Option<Arc<dyn ProofDataSource>>,
+    #[doc = r"Journal of the strict mode of the Ethereum transactions, see `contract_call::send_eth_call`. Disabled if unset"]
+    #[builder(default)]
+    eth_nonce_journal: Option<PathBuf>,
+    #[doc = r"Observer of the progress of the operations, e.g. transactions submitted and light clients lagging. Events are only logged if unset"]
+    #[builder(default)]
+    events: Option<Arc<dyn ConnectorEvents>>,
+    #[doc = r"Limits of the concurrent requests to each RPC endpoint. Connectors and relayers given the same scheduler share the limits of an endpoint"]
+    #[builder(default = "RpcScheduler::shared()")]
+    rpc_scheduler: Arc<RpcScheduler>,
+    #[builder(setter(skip))]
+    eth_client_cache: ClientCache<Arc<EthSignerClient>>,
+    #[builder(setter(skip))]
+    near_rpc_cache: ClientCache<Arc<dyn NearRpc>>,
+}
+
+impl NftConnectorBuilder {
+    /// Signs the Ethereum transactions with a callback, e.g. a browser wallet or an MPC signing service, instead of a
+    /// key held by the connector
+    pub fn eth_signing_callback(&mut self, signer: CallbackEthSigner) -> &mut Self {
+        self.custom_eth_signer(Some(Arc::new(signer)))
+    }
+
+    /// Signs the Near transactions with a callback, e.g. a wallet app or a custodial signing service, instead of a key
+    /// held by the connector
+    pub fn near_signing_callback(&mut self, signer: CallbackNearSigner) -> &mut Self {
+        self.custom_near_signer(Some(Arc::new(signer)))
+    }
+}
+
+impl NftConnector {
+    /// Logs NFT contract metadata to the nft_locker contract. The proof from this transaction is then used to deploy a corresponding ERC-721 token on Ethereum
+    #[tracing::instrument(skip_all, name = "LOG METADATA")]
+    pub async fn log_token_metadata(&self, near_token_id: String) -> Result<CryptoHash> {
+        self.disabled_operations.check("log_token_metadata")?;
+
+        let near_rpc = self.near_rpc()?;
+
+        let args = near_args::to_bytes(&TokenIdArgs {
+            token_id: near_token_id,
+        });
+
+        let tx_id = signer::near_change(
+            near_rpc.as_ref(),
+            self.near_signer()?,
+            self.nft_locker_id()?.to_string(),
+            self.method_names.near("log_metadata"),
+            args,
+            self.near_tx_options.gas(300_000_000_000_000),
+            0,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(tx_hash = tx_id.to_string(), "Sent log transaction");
+
+        Ok(tx_id)
+    }
+
+    /// Deploys an ERC-721 token that will be used when bridging NEP-171 tokens to Ethereum. Requires a receipt from log_metadata transaction on Near
+    #[tracing::instrument(skip_all, name = "DEPLOY TOKEN")]
+    pub async fn deploy_token(
+        &self,
+        receipt_id: CryptoHash,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("deploy_token")?;
+
+        let (proof, proof_block_height) = self.get_near_proof(receipt_id).await?;
+
+        let factory = self.nft_factory()?;
+        let call = tx_opts.apply(
+            self.method_names
+                .eth(factory.new_bridge_token(proof.into(), proof_block_height)),
+        );
+
+        let tx_hash = contract_call::send_eth_call(
+            &self.eth_client()?,
+            call,
+            tx_opts.confirmations.or(Some(1)),
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = format!("{:?}", tx_hash),
+            "Sent token deploy transaction"
+        );
+
+        Ok(tx_hash)
+    }
+
+    /// Transfers a NEP-171 token to the NFT locker. The proof from this transaction is then used to mint the corresponding ERC-721 token on Ethereum
+    #[tracing::instrument(skip_all, name = "DEPOSIT")]
+    pub async fn deposit(
+        &self,
+        near_token_id: String,
+        token_id: String,
+        eth_receiver: String,
+    ) -> Result<CryptoHash> {
+        self.disabled_operations.check("deposit")?;
+        screening::check(self.transfer_screening.as_ref(), || {
+            Ok(ScreenedTransfer {
+                direction: TransferDirection::NearToEth,
+                token: near_token_id.clone(),
+                amount: 1,
+                sender: self.near_signer()?.account_id().to_string(),
+                recipient: eth_receiver.clone(),
+            })
+        })
+        .await?;
+
+        let near_rpc = self.near_rpc()?;
+        let nft_locker = self.nft_locker_id()?.to_string();
+
+        let args = near_args::to_bytes(&NftTransferCallArgs {
+            receiver_id: nft_locker,
+            token_id,
+            msg: eth_receiver,
+        });
+
+        let tx_hash = signer::near_change(
+            near_rpc.as_ref(),
+            self.near_signer()?,
+            near_token_id,
+            self.method_names.near("nft_transfer_call"),
+            args,
+            self.near_tx_options.gas(300_000_000_000_000),
+            1,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = format!("{:?}", tx_hash),
+            "Sent deposit transaction"
+        );
+
+        Ok(tx_hash)
+    }
+
+    /// Mints the corresponding ERC-721 token on Ethereum. Requires a proof from the deposit transaction on Near
+    #[tracing::instrument(skip_all, name = "FINALIZE DEPOSIT")]
+    pub async fn finalize_deposit(
+        &self,
+        receipt_id: CryptoHash,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("finalize_deposit")?;
+
+        let (proof, proof_block_height) = self.get_near_proof(receipt_id).await?;
+
+        let factory = self.nft_factory()?;
+        let call = tx_opts.apply(
+            self.method_names
+                .eth(factory.deposit(proof.into(), proof_block_height)),
+        );
+        let tx_hash = contract_call::send_eth_call(
+            &self.eth_client()?,
+            call,
+            tx_opts.confirmations.or(Some(1)),
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = format!("{:?}", tx_hash),
+            "Sent finalize deposit transaction"
+        );
+
+        Ok(tx_hash)
+    }
+
+    /// Burns a bridged ERC-721 token on Ethereum. The proof from this transaction is then used to withdraw the original NEP-171 token on Near
+    #[tracing::instrument(skip_all, name = "WITHDRAW")]
+    pub async fn withdraw(
+        &self,
+        near_token_id: String,
+        token_id: String,
+        receiver: String,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("withdraw")?;
+        screening::check(self.transfer_screening.as_ref(), || {
+            Ok(ScreenedTransfer {
+                direction: TransferDirection::EthToNear,
+                token: near_token_id.clone(),
+                amount: 1,
+                sender: format!("{:?}", self.eth_signer()?.address()),
+                recipient: receiver.clone(),
+            })
+        })
+        .await?;
+
+        let factory = self.nft_factory()?;
+
+        let erc721_address = factory
+            .near_to_eth_token(near_token_id.clone())
+            .call()
+            .await?;
+
+        tracing::debug!(
+            address = format!("{:?}", erc721_address),
+            "Retrieved ERC721 address"
+        );
+
+        let bridge_token = self.bridge_token(erc721_address)?;
+
+        let signer = self.eth_signer()?;
+        let nft_factory_address = self.nft_factory_address()?;
+        let is_approved = bridge_token
+            .is_approved_for_all(signer.address(), nft_factory_address)
+            .call()
+            .await?;
+
+        let mut tx_opts = tx_opts;
+        if !is_approved {
+            let approve_call =
+                tx_opts.apply(bridge_token.set_approval_for_all(nft_factory_address, true));
+
+            if self.dry_run {
+                tracing::warn!("The withdrawal can't be simulated before the factory is approved");
+                return contract_call::send_eth_call(
+                    &self.eth_client()?,
+                    approve_call,
+                    None,
+                    true,
+                    self.eth_nonce_journal.as_deref(),
+                    self.events.as_deref(),
+                    &self.rpc_scheduler,
+                )
+                .await;
+            }
+
+            contract_call::send_eth_call(
+                &self.eth_client()?,
+                approve_call,
+                Some(1),
+                false,
+                self.eth_nonce_journal.as_deref(),
+                self.events.as_deref(),
+                &self.rpc_scheduler,
+            )
+            .await?;
+
+            tracing::debug!("Approved factory as an operator");
+            tx_opts = tx_opts.next();
+        }
+
+        let withdraw_call = tx_opts.apply(self.method_names.eth(factory.withdraw(
+            near_token_id,
+            token_id,
+            receiver,
+        )));
+        let tx_hash = contract_call::send_eth_call(
+            &self.eth_client()?,
+            withdraw_call,
+            tx_opts.confirmations,
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = format!("{:?}", tx_hash),
+            "Sent withdraw transaction"
+        );
+
+        Ok(tx_hash)
+    }
+
+    /// Withdraws the NEP-171 token from the NFT locker. Requires a proof from the burn transaction on Ethereum
+    #[tracing::instrument(skip_all, name = "FINALIZE WITHDRAW")]
+    pub async fn finalize_withdraw(&self, tx_hash: TxHash, log_index: u64) -> Result<CryptoHash> {
+        self.disabled_operations.check("finalize_withdraw")?;
+
+        let eth_rpc = self.eth_rpc()?;
+        let near_rpc = self.near_rpc()?;
+
+        self.wait_for_eth_light_client(tx_hash).await?;
+
+        let proof = self
+            .rpc_scheduler
+            .run(eth_rpc.endpoint(), RpcPriority::Proof, async {
+                Ok(eth_proof::get_proof_for_event_from_rpc_or_source(
+                    eth_rpc.as_ref(),
+                    self.proof_data_source.as_deref(),
+                    tx_hash,
+                    log_index,
+                    &self.proof_options,
+                )
+                .await?)
+            })
+            .await?;
+        events::emit(
+            self.events.as_deref(),
+            ConnectorEvent::ProofGenerated {
+                transfer: TransferId::Eth { tx_hash, log_index },
+            },
+        );
+
+        let mut args = Vec::new();
+        proof
+            .serialize(&mut args)
+            .map_err(|_| BridgeSdkError::EthProofError("Failed to serialize proof".to_string()))?;
+
+        tracing::debug!("Retrieved Ethereum proof");
+
+        let storage_byte_cost = near_rpc.get_storage_byte_cost().await?;
+        // One extra yoctoNEAR is forwarded by the locker with `nft_transfer`
+        let deposit = self
+            .near_tx_options
+            .deposit(USED_PROOF_STORAGE_BYTES * storage_byte_cost + 1);
+
+        let tx_hash = chunked_upload::near_change(
+            near_rpc.as_ref(),
+            self.near_signer()?,
+            self.nft_locker_id()?.to_string(),
+            self.method_names.near("withdraw"),
+            args,
+            self.near_tx_options.gas(300_000_000_000_000),
+            deposit,
+            self.dry_run,
+            self.near_tx_options.proof_chunk_size,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = format!("{:?}", tx_hash),
+            "Sent finalize withdraw transaction"
+        );
+
+        Ok(tx_hash)
+    }
+
+    /// Verifies the configured capabilities: RPC reachability, presence of the bridge contracts and signer balances. Unset configuration values are skipped
+    pub async fn self_check(&self) -> SelfCheckReport {
+        let eth_endpoint = self.eth_endpoint.as_deref();
+        let near_endpoint = self.near_endpoint.as_deref();
+        let mut report = SelfCheckReport::default();
+
+        report.push(
+            "Ethereum RPC",
+            self_check::check_eth_rpc(eth_endpoint).await,
+        );
+        report.push("NEAR RPC", self_check::check_near_rpc(near_endpoint).await);
+        report.push(
+            "NFT factory",
+            self_check::check_eth_code(
+                eth_endpoint,
+                self.nft_factory_address
+                    .as_ref()
+                    .map(|_| self.nft_factory_address()),
+            )
+            .await,
+        );
+        report.push(
+            "NEAR light client",
+            self_check::check_eth_code(
+                eth_endpoint,
+                self.near_light_client_address
+                    .as_ref()
+                    .map(|_| self.near_light_client_address()),
+            )
+            .await,
+        );
+        report.push(
+            "NFT locker",
+            self_check::check_near_code(near_endpoint, self.nft_locker_id.as_deref()).await,
+        );
+        report.push(
+            "Ethereum signer balance",
+            self_check::check_eth_balance(
+                eth_endpoint,
+                self.eth_private_key
+                    .as_ref()
+                    .map(|_| self.eth_signer().map(|wallet| wallet.address())),
+            )
+            .await,
+        );
+        report.push(
+            "NEAR signer balance",
+            self_check::check_near_balance(near_endpoint, self.near_signer.as_deref()).await,
+        );
+
+        report
+    }
+
+    /// Sends a transaction calling an arbitrary Ethereum contract function, signed with the configured Ethereum key
+    #[tracing::instrument(skip_all, name = "CALL ETH CONTRACT")]
+    pub async fn call_eth_contract(
+        &self,
+        address: Address,
+        abi_fn: &str,
+        args: Vec<Token>,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("call_eth_contract")?;
+
+        contract_call::call_eth_contract(
+            self.eth_client()?,
+            address,
+            abi_fn,
+            args,
+            tx_opts,
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await
+    }
+
+    /// Calls an arbitrary NEAR contract method, signed with the configured NEAR account
+    #[tracing::instrument(skip_all, name = "CALL NEAR CONTRACT")]
+    pub async fn call_near_contract(
+        &self,
+        account_id: AccountId,
+        method_name: String,
+        args: Vec<u8>,
+        gas: u64,
+        deposit: u128,
+    ) -> Result<CryptoHash> {
+        self.disabled_operations.check("call_near_contract")?;
+
+        let tx_hash = signer::near_change(
+            self.near_rpc()?.as_ref(),
+            self.near_signer()?,
+            account_id.to_string(),
+            method_name,
+            args,
+            gas,
+            deposit,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = format!("{:?}", tx_hash),
+            "Sent contract call transaction"
+        );
+
+        Ok(tx_hash)
+    }
+
+    async fn wait_for_eth_light_client(&self, tx_hash: TxHash) -> Result<()> {
+        let Some(eth_light_client_account_id) = &self.eth_light_client_account_id else {
+            return Ok(());
+        };
+        if !self.wait_for_light_client {
+            return Ok(());
+        }
+
+        let eth_on_near_client = EthOnNearClient::new(
+            AccountId::from_str(eth_light_client_account_id).map_err(|_| {
+                BridgeSdkError::ConfigError("Invalid Ethereum light client account id".to_string())
+            })?,
+            self.near_endpoint()?.to_string(),
+        );
+
+        bridge_connector::wait_for_eth_light_client_sync(
+            self.eth_endpoint()?,
+            &eth_on_near_client,
+            tx_hash,
+            self.light_client_sync_timeout,
+            self.events.as_deref(),
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_near_proof(&self, receipt_id: CryptoHash) -> Result<(Vec<u8>, u64)> {
+        let eth_endpoint = self.eth_endpoint()?;
+        let near_rpc = self.near_rpc()?;
+
+        let near_on_eth_client =
+            NearOnEthClient::new(self.near_light_client_address()?, eth_endpoint.to_string());
+
+        let transfer = TransferId::Near { receipt_id };
+        let receipt_id = TransactionOrReceiptId::Receipt {
+            receipt_id,
+            receiver_id: AccountId::from_str(self.nft_locker_id()?).map_err(|_| {
+                BridgeSdkError::ConfigError("Invalid NFT locker account id".to_string())
+            })?,
+        };
+
+        if self.wait_for_light_client {
+            bridge_connector::wait_for_light_client_sync(
+                near_rpc.as_ref(),
+                &near_on_eth_client,
+                receipt_id.clone(),
+                self.light_client_sync_timeout,
+                self.events.as_deref(),
+            )
+            .await?;
+        }
+
+        let proof_block_height = near_on_eth_client.get_sync_height().await?;
+        let block_hash = near_on_eth_client
+            .get_block_hash(proof_block_height)
+            .await?;
+
+        tracing::debug!(proof_block_height, "Retrieved light client block height");
+
+        let proof_data = self
+            .rpc_scheduler
+            .run(near_rpc.endpoint(), RpcPriority::Proof, async {
+                Ok(near_rpc
+                    .get_light_client_proof(receipt_id, CryptoHash(block_hash))
+                    .await?)
+            })
+            .await?;
+        events::emit(
+            self.events.as_deref(),
+            ConnectorEvent::ProofGenerated { transfer },
+        );
+
+        let mut buffer: Vec<u8> = Vec::new();
+        proof_data
+            .serialize(&mut buffer)
+            .map_err(|_| BridgeSdkError::NearProofError("Failed to serialize proof".to_string()))?;
+
+        tracing::debug!("Retrieved Near receipt proof");
+
+        Ok((buffer, proof_block_height))
+    }
+
+    fn eth_rpc(&self) -> Result<Arc<dyn EthRpc>> {
+        match &self.eth_rpc {
+            Some(eth_rpc) => Ok(eth_rpc.clone()),
+            None => Ok(Arc::new(EthRPCClient::new_with_options(
+                self.eth_endpoint()?,
+                self.eth_rpc_options.clone(),
+            ))),
+        }
+    }
+
+    fn eth_endpoint(&self) -> Result<&str> {
+        Ok(self
+            .eth_endpoint
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Ethereum rpc endpoint is not set".to_string(),
+            ))?)
+    }
+
+    fn near_rpc(&self) -> Result<Arc<dyn NearRpc>> {
+        match &self.near_rpc {
+            Some(near_rpc) => Ok(near_rpc.clone()),
+            // Cached, so that the operations share the health of the endpoints
+            None => self.near_rpc_cache.get_or_try_init(|| {
+                Ok(Arc::new(
+                    NearRpcClient::new(self.near_endpoint()?)
+                        .with_retry_policy(self.near_retry_policy),
+                ))
+            }),
+        }
+    }
+
+    fn near_endpoint(&self) -> Result<&str> {
+        Ok(self
+            .near_endpoint
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Near rpc endpoint is not set".to_string(),
+            ))?)
+    }
+
+    fn nft_locker_id(&self) -> Result<&str> {
+        Ok(self
+            .nft_locker_id
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "NFT locker account id is not set".to_string(),
+            ))?)
+    }
+
+    fn near_light_client_address(&self) -> Result<Address> {
+        self.near_light_client_address
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Near on Eth light client address is not set".to_string(),
+            ))
+            .and_then(|addr| {
+                Address::from_str(addr).map_err(|_| {
+                    BridgeSdkError::ConfigError(
+                        "near_light_client_address is not a valid Ethereum address".to_string(),
+                    )
+                })
+            })
+    }
+
+    fn nft_factory_address(&self) -> Result<Address> {
+        self.nft_factory_address
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "NFT factory address is not set".to_string(),
+            ))
+            .and_then(|addr| {
+                Address::from_str(addr).map_err(|_| {
+                    BridgeSdkError::ConfigError(
+                        "nft_factory_address is not a valid Ethereum address".to_string(),
+                    )
+                })
+            })
+    }
+
+    fn near_signer(&self) -> Result<Arc<dyn NearSigner>> {
+        if let Some(signer) = &self.custom_near_signer {
+            return Ok(signer.clone());
+        }
+
+        let near_private_key =
+            self.near_private_key
+                .as_ref()
+                .ok_or(BridgeSdkError::ConfigError(
+                    "Near account private key is not set".to_string(),
+                ))?;
+        let near_signer = self
+            .near_signer
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Near signer account id is not set".to_string(),
+            ))?;
+
+        Ok(Arc::new(near_crypto::InMemorySigner::from_secret_key(
+            AccountId::from_str(near_signer).map_err(|_| {
+                BridgeSdkError::ConfigError("Invalid near signer account id".to_string())
+            })?,
+            SecretKey::from_str(near_private_key)
+                .map_err(|_| BridgeSdkError::ConfigError("Invalid near private key".to_string()))?,
+        )))
+    }
+
+    fn nft_factory(&self) -> Result<BridgeNftFactory<EthSignerClient>> {
+        Ok(BridgeNftFactory::new(
+            self.nft_factory_address()?,
+            self.eth_client()?,
+        ))
+    }
+
+    fn bridge_token(&self, address: Address) -> Result<ERC721<EthSignerClient>> {
+        Ok(ERC721::new(address, self.eth_client()?))
+    }
+
+    fn eth_client(&self) -> Result<Arc<EthSignerClient>> {
+        self.eth_client_cache.get_or_try_init(|| {
+            let eth_provider = failover::eth_provider(self.eth_endpoint()?)?;
+
+            let wallet = self.eth_signer()?;
+
+            Ok(Arc::new(SignerMiddleware::new(eth_provider, wallet)))
+        })
+    }
+
+    fn eth_signer(&self) -> Result<DynEthSigner> {
+        if let Some(signer) = &self.custom_eth_signer {
+            return Ok(DynEthSigner::new(signer.clone()));
+        }
+
+        if let Some(eth_keystore_path) = &self.eth_keystore_path {
+            return signer::keystore_signer(
+                eth_keystore_path,
+                self.eth_keystore_password.as_deref(),
+                self.eth_chain_id,
+            );
+        }
+
+        let eth_private_key = self
+            .eth_private_key
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Ethereum private key is not set".to_string(),
+            ))?;
+
+        let eth_chain_id = self.eth_chain_id.ok_or(BridgeSdkError::ConfigError(
+            "Ethereum chain id is not set".to_string(),
+        ))?;
+
+        let private_key_bytes = hex::decode(eth_private_key).map_err(|_| {
+            BridgeSdkError::ConfigError(
+                "Ethereum private key is not a valid hex string".to_string(),
+            )
+        })?;
+
+        if private_key_bytes.len() != 32 {
+            return Err(BridgeSdkError::ConfigError(
+                "Ethereum private key is of invalid length".to_string(),
+            ));
+        }
+
+        let wallet = LocalWallet::from_bytes(&private_key_bytes)
+            .map_err(|_| BridgeSdkError::ConfigError("Invalid ethereum private key".to_string()))?
+            .with_chain_id(eth_chain_id);
+
+        Ok(DynEthSigner::new(Arc::new(wallet)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::{InMemorySigner, KeyType};
+    use near_primitives::transaction::{Action, FunctionCallAction};
+    use near_rpc_client::MockNearRpc;
+
+    const NFT_LOCKER_ID: &str = "nft-locker.near";
+
+    fn connector(near_rpc: Arc<MockNearRpc>) -> NftConnector {
+        let signer = InMemorySigner::from_seed(
+            "relayer.near".parse().unwrap(),
+            KeyType::ED25519,
+            "relayer.near",
+        );
+
+        NftConnectorBuilder::default()
+            .eth_endpoint(None)
+            .eth_chain_id(None)
+            .eth_private_key(None)
+            .nft_factory_address(None)
+            .near_endpoint(None)
+            .near_private_key(None)
+            .near_signer(None)
+            .nft_locker_id(Some(NFT_LOCKER_ID.to_string()))
+            .near_light_client_address(None)
+            .custom_near_signer(Some(Arc::new(signer)))
+            .near_rpc(Some(near_rpc))
+            .build()
+            .unwrap()
+    }
+
+    fn function_call(near_rpc: &MockNearRpc, tx_hash: CryptoHash) -> (String, FunctionCallAction) {
+        let sent = near_rpc.sent_transactions();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].get_hash(), tx_hash);
+
+        let Action::FunctionCall(call) = &sent[0].transaction.actions[0] else {
+            panic!("Expected a function call");
+        };
+        (
+            sent[0].transaction.receiver_id.to_string(),
+            (**call).clone(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_log_token_metadata() {
+        let near_rpc = Arc::new(MockNearRpc::new());
+
+        let tx_hash = connector(near_rpc.clone())
+            .log_token_metadata("nft.near".to_string())
+            .await
+            .unwrap();
+
+        let (receiver_id, call) = function_call(&near_rpc, tx_hash);
+        assert_eq!(receiver_id, NFT_LOCKER_ID);
+        assert_eq!(call.method_name, "log_metadata");
+        assert_eq!(
+            call.args,
+            near_args::to_bytes(&TokenIdArgs {
+                token_id: "nft.near".to_string(),
+            })
+        );
+        assert_eq!(call.deposit, 0);
+    }
+
+    #[tokio::test]
+    async fn test_deposit_transfers_the_token_to_the_locker() {
+        let near_rpc = Arc::new(MockNearRpc::new());
+        let eth_receiver = "0x1111111111111111111111111111111111111111";
+
+        let tx_hash = connector(near_rpc.clone())
+            .deposit(
+                "nft.near".to_string(),
+                "42".to_string(),
+                eth_receiver.to_string(),
+            )
+            .await
+            .unwrap();
+
+        let (receiver_id, call) = function_call(&near_rpc, tx_hash);
+        assert_eq!(receiver_id, "nft.near");
+        assert_eq!(call.method_name, "nft_transfer_call");
+        assert_eq!(
+            call.args,
+            near_args::to_bytes(&NftTransferCallArgs {
+                receiver_id: NFT_LOCKER_ID.to_string(),
+                token_id: "42".to_string(),
+                msg: eth_receiver.to_string(),
+            })
+        );
+        // `nft_transfer_call` requires exactly 1 yoctoNEAR
+        assert_eq!(call.deposit, 1);
+    }
+
+    #[test]
+    fn test_used_proof_storage_bytes() {
+        // `Proof::get_key` is the sha256 of the proof location, stored borsh serialized
+        let proof_key = borsh::to_vec(&vec![0u8; 32]).unwrap();
+        let index = 0u64.to_le_bytes();
+
+        // Element index `ui<key>` -> u64 and vector element `ue<index>` -> key of the `UnorderedSet`
+        let index_record = [b"ui".as_slice(), &proof_key].concat().len() + index.len();
+        let element_record = [b"ue".as_slice(), &index].concat().len() + proof_key.len();
+
+        assert_eq!(
+            USED_PROOF_STORAGE_BYTES as usize,
+            index_record + element_record + 2 * STORAGE_RECORD_OVERHEAD_BYTES
+        );
+    }
+}
@@ -0,0 +1,11 @@
+#[macro_use]
+extern crate derive_builder;
+
+mod omni_connector;
+pub mod omni_types;
+pub mod payload;
+pub mod solana_bridge;
+mod solana_rpc_client;
+
+pub use omni_connector::{BridgeDeposit, OmniConnector, OmniConnectorBuilder};
+pub use solana_rpc_client::{SimulationResult, SolanaRpcClient, SolanaRpcError};
@@ -0,0 +1,840 @@
+use crate::{
+    omni_types::{
+        ChainKind, DepositOutcome, InitTransferMsg, OmniAddress, ProofKind, SignTransferArgs,
+    },
+    payload::TransferMessagePayload,
+    solana_bridge::SolanaBridge,
+    solana_rpc_client::{self, SolanaRpcClient, SolanaRpcError},
+};
+use borsh::BorshSerialize;
+use bridge_connector_common::{
+    bridge_connector::{TransferDirection, TransferId},
+    chunked_upload,
+    client_cache::ClientCache,
+    contract_call::{self, EthTxOptions, NearTxOptions},
+    events::{self, ConnectorEvent, ConnectorEvents},
+    failover,
+    method_names::MethodNames,
+    near_args::{self, FtTransferCallArgs},
+    operations::DisabledOperations,
+    result::{BridgeSdkError, Result},
+    rpc_scheduler::{RpcPriority, RpcScheduler},
+    screening::{self, ScreenedTransfer, TransferScreening},
+    self_check::{self, SelfCheckReport},
+    signer::{
+        self, CallbackEthSigner, CallbackNearSigner, DynEthSigner, EthSigner, EthSignerClient,
+        NearSigner,
+    },
+};
+use eth_proof::{EthRPCClient, EthRPCClientOptions, EthRpc, ProofDataSource, ProofOptions};
+use ethers::{
+    abi::{Address, Token},
+    prelude::*,
+};
+use near_crypto::SecretKey;
+use near_primitives::{hash::CryptoHash, types::AccountId};
+use near_rpc_client::{retry::RetryPolicy, FungibleTokenMetadata, NearRpc, NearRpcClient};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer as _},
+    signer::keypair::keypair_from_seed,
+    transaction::Transaction,
+};
+use std::{path::PathBuf, str::FromStr, sync::Arc, time::Duration};
+
+abigen!(
+    OmniBridge,
+    r#"[
+      struct BridgeDeposit { uint128 nonce; address token; uint128 amount; address recipient; string feeRecipient; }
+      function finTransfer(bytes signature, BridgeDeposit payload) external
+    ]"#
+);
+
+/// Deposit attached to `sign_transfer` to pay for the MPC signature request. The excess is refunded
+const SIGN_TRANSFER_DEPOSIT: u128 = 250_000_000_000_000_000_000_000;
+
+#[derive(BorshSerialize)]
+struct ClaimFeeArgs {
+    chain_kind: ChainKind,
+    prover_args: Vec<u8>,
+}
+
+/// Bridging tokens through the omni locker on NEAR. Clones share the cached clients
+#[derive(Builder, Clone)]
+pub struct OmniConnector {
+    #[doc = r"Ethereum RPC endpoint. Required for `fin_transfer`, `claim_fee`"]
+    eth_endpoint: Option<String>,
+    #[doc = r"Ethereum chain id. Required for `fin_transfer`"]
+    eth_chain_id: Option<u64>,
+    #[doc = r"Ethereum private key. Required for `fin_transfer`"]
+    eth_private_key: Option<String>,
+    #[doc = r"Omni bridge address on Ethereum. Required for `fin_transfer`"]
+    omni_bridge_address: Option<String>,
+    #[doc = r"NEAR RPC endpoint. Required for `init_transfer`, `sign_transfer`, `claim_fee`"]
+    near_endpoint: Option<String>,
+    #[doc = r"NEAR private key. Required for `init_transfer`, `sign_transfer`, `claim_fee`"]
+    near_private_key: Option<String>,
+    #[doc = r"NEAR account id of the transaction signer. Required for `init_transfer`, `sign_transfer`, `claim_fee`"]
+    near_signer: Option<String>,
+    #[doc = r"Omni locker account id on Near. Required for `init_transfer`, `sign_transfer`, `claim_fee`"]
+    omni_locker_id: Option<String>,
+    #[doc = r"Operations rejected with `OperationDisabled`, e.g. during an incident"]
+    #[builder(default)]
+    disabled_operations: DisabledOperations,
+    #[doc = r"Contract methods renamed by the deployment, e.g. `withdraw` deployed as `finish_withdraw`"]
+    #[builder(default)]
+    method_names: MethodNames,
+    #[doc = r"Gas and attached deposit of the Near transactions, overriding the connector defaults"]
+    #[builder(default)]
+    near_tx_options: NearTxOptions,
+    #[doc = r"Screening of the transfers before they are submitted, e.g. for sanctions compliance. A rejected transfer fails with `TransferRejected`"]
+    #[builder(default)]
+    transfer_screening: Option<Arc<dyn TransferScreening>>,
+    #[doc = r"Simulates transactions instead of sending them and returns the hashes they would have. Ethereum calls are checked with `eth_estimateGas` and `eth_call`, Near calls are run as view calls and checked against the signer balance"]
+    #[builder(default)]
+    dry_run: bool,
+    #[doc = r"Custom NEAR signer, e.g. a hardware wallet or a remote signer. Takes precedence over `near_private_key`"]
+    #[builder(default)]
+    custom_near_signer: Option<Arc<dyn NearSigner>>,
+    #[doc = r"Path of an encrypted web3 JSON keystore holding the Ethereum key. Takes precedence over `eth_private_key`"]
+    #[builder(default)]
+    eth_keystore_path: Option<String>,
+    #[doc = r"Password of the Ethereum keystore. Required with `eth_keystore_path`"]
+    #[builder(default)]
+    eth_keystore_password: Option<String>,
+    #[doc = r"Custom Ethereum signer, e.g. a hardware wallet or a remote signer. Takes precedence over `eth_private_key`"]
+    #[builder(default)]
+    custom_eth_signer: Option<Arc<dyn EthSigner>>,
+    #[doc = r"Near RPC serving the requests instead of `near_endpoint`, e.g. a `MockNearRpc` in tests"]
+    #[builder(default)]
+    near_rpc: Option<Arc<dyn NearRpc>>,
+    #[doc = r"Ethereum RPC serving the proof data instead of `eth_endpoint`, e.g. a `MockEthRpc` in tests"]
+    #[builder(default)]
+    eth_rpc: Option<Arc<dyn EthRpc>>,
+    #[doc = r"Retries of the Near requests failing with a transient error. Ignored with `near_rpc`"]
+    #[builder(default)]
+    near_retry_policy: RetryPolicy,
+    #[doc = r"Time each Near transaction is awaited for its outcome, failing if it did. Only broadcast with `None`"]
+    #[builder(default = "Some(signer::DEFAULT_NEAR_OUTCOME_TIMEOUT)")]
+    near_outcome_timeout: Option<Duration>,
+    #[doc = r"Retries, rate limit and response size of the Ethereum proof data requests. Ignored with `eth_rpc`"]
+    #[builder(default)]
+    eth_rpc_options: EthRPCClientOptions,
+    #[doc = r"Block cache and metrics of the generated Ethereum proofs"]
+    #[builder(default)]
+    proof_options: ProofOptions,
+    #[doc = r"Source of the Ethereum blocks the proofs are generated from, e.g. a local node database, instead of `eth_rpc`"]
+    #[builder(default)]
+    proof_data_source: Option<Arc<dyn ProofDataSource>>,
+    #[doc = r"Journal of the strict mode of the Ethereum transactions, see `contract_call::send_eth_call`. Disabled if unset"]
+    #[builder(default)]
+    eth_nonce_journal: Option<PathBuf>,
+    #[doc = r"Observer of the progress of the operations, e.g. transactions submitted and light clients lagging. Events are only logged if unset"]
+    #[builder(default)]
+    events: Option<Arc<dyn ConnectorEvents>>,
+    #[doc = r"Limits of the concurrent requests to each RPC endpoint. Connectors and relayers given the same scheduler share the limits of an endpoint"]
+    #[builder(default = "RpcScheduler::shared()")]
+    rpc_scheduler: Arc<RpcScheduler>,
+    #[doc = r"Solana RPC endpoint. Required for `fin_transfer_sol`"]
+    #[builder(default)]
+    solana_endpoint: Option<String>,
+    #[doc = r"Base58 encoded Solana keypair paying for and signing the Solana transactions, as exported by the Solana wallets. Required for `fin_transfer_sol`"]
+    #[builder(default)]
+    solana_keypair: Option<String>,
+    #[doc = r"Omni bridge program id on Solana. Required for `fin_transfer_sol`"]
+    #[builder(default)]
+    solana_bridge_program_id: Option<String>,
+    #[doc = r"Wormhole core program id on Solana, which the bridge program posts its messages to. Required for `fin_transfer_sol`"]
+    #[builder(default)]
+    solana_wormhole_program_id: Option<String>,
+    #[builder(setter(skip))]
+    eth_client_cache: ClientCache<Arc<EthSignerClient>>,
+    #[builder(setter(skip))]
+    near_rpc_cache: ClientCache<Arc<dyn NearRpc>>,
+}
+
+impl OmniConnectorBuilder {
+    /// Signs the Ethereum transactions with a callback, e.g. a browser wallet or an MPC signing service, instead of a
+    /// key held by the connector
+    pub fn eth_signing_callback(&mut self, signer: CallbackEthSigner) -> &mut Self {
+        self.custom_eth_signer(Some(Arc::new(signer)))
+    }
+
+    /// Signs the Near transactions with a callback, e.g. a wallet app or a custodial signing service, instead of a key
+    /// held by the connector
+    pub fn near_signing_callback(&mut self, signer: CallbackNearSigner) -> &mut Self {
+        self.custom_near_signer(Some(Arc::new(signer)))
+    }
+}
+
+impl OmniConnector {
+    /// Transfers tokens to the omni locker. The locker records the transfer and assigns it a nonce that is used by `sign_transfer`.
+    /// Waits for the transaction to be executed and returns the assigned nonce
+    #[tracing::instrument(skip_all, name = "INIT TRANSFER")]
+    pub async fn init_transfer(
+        &self,
+        near_token_id: String,
+        amount: u128,
+        fee: u128,
+        native_fee: u128,
+        recipient: OmniAddress,
+    ) -> Result<DepositOutcome> {
+        if self.dry_run {
+            return Err(BridgeSdkError::ConfigError(
+                "The transfer nonce is only assigned once the transaction is executed, so `init_transfer` can't complete a dry run".to_string(),
+            ));
+        }
+
+        self.disabled_operations.check("init_transfer")?;
+        screening::check(self.transfer_screening.as_ref(), || {
+            Ok(ScreenedTransfer {
+                direction: TransferDirection::NearToEth,
+                token: near_token_id.clone(),
+                amount,
+                sender: self.near_signer()?.account_id().to_string(),
+                recipient: recipient.to_string(),
+            })
+        })
+        .await?;
+
+        let near_rpc = self.near_rpc()?;
+        let omni_locker_id = self.omni_locker_id()?.to_string();
+
+        let msg = InitTransferMsg {
+            recipient,
+            fee,
+            native_token_fee: native_fee,
+        };
+        let args = near_args::to_bytes(&FtTransferCallArgs {
+            receiver_id: omni_locker_id,
+            amount,
+            msg: near_args::to_string(&msg),
+        });
+
+        let near_signer = self.near_signer()?;
+        let tx_hash = signer::near_change(
+            near_rpc.as_ref(),
+            near_signer.clone(),
+            near_token_id,
+            self.method_names.near("ft_transfer_call"),
+            args,
+            self.near_tx_options.gas(300_000_000_000_000),
+            1,
+            false,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = format!("{:?}", tx_hash),
+            "Sent init transfer transaction"
+        );
+
+        let outcome = near_rpc
+            .wait_for_tx_final_outcome(
+                tx_hash,
+                near_signer.account_id(),
+                near_rpc_client::DEFAULT_WAIT_FINAL_OUTCOME_TIMEOUT_SEC,
+            )
+            .await?;
+
+        let deposit =
+            DepositOutcome::from_outcome(&outcome).ok_or(BridgeSdkError::NearOutcomeError(
+                "InitTransferEvent was not emitted, the transfer was likely refunded".to_string(),
+            ))?;
+
+        tracing::info!(nonce = deposit.nonce, "Transfer initialized");
+
+        Ok(deposit)
+    }
+
+    /// Requests the MPC signature of a pending transfer. The signature is emitted in the `SignTransferEvent` log and is then used by `fin_transfer`
+    #[tracing::instrument(skip_all, name = "SIGN TRANSFER")]
+    pub async fn sign_transfer(
+        &self,
+        nonce: u128,
+        fee_recipient: Option<AccountId>,
+    ) -> Result<CryptoHash> {
+        self.disabled_operations.check("sign_transfer")?;
+
+        let near_rpc = self.near_rpc()?;
+
+        let args = near_args::to_bytes(&SignTransferArgs {
+            nonce,
+            fee_recipient,
+        });
+
+        let tx_hash = signer::near_change(
+            near_rpc.as_ref(),
+            self.near_signer()?,
+            self.omni_locker_id()?.to_string(),
+            self.method_names.near("sign_transfer"),
+            args,
+            self.near_tx_options.gas(300_000_000_000_000),
+            self.near_tx_options.deposit(SIGN_TRANSFER_DEPOSIT),
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = format!("{:?}", tx_hash),
+            "Sent sign transfer transaction"
+        );
+
+        Ok(tx_hash)
+    }
+
+    /// Finalizes a signed transfer to an Ethereum recipient. Transfers to Solana are finalized with `fin_transfer_sol`
+    #[allow(clippy::too_many_arguments)]
+    #[tracing::instrument(skip_all, name = "FIN TRANSFER")]
+    pub async fn fin_transfer(
+        &self,
+        nonce: u128,
+        token: Address,
+        amount: u128,
+        recipient: OmniAddress,
+        fee_recipient: String,
+        signature: Vec<u8>,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("fin_transfer")?;
+
+        let recipient = match recipient {
+            OmniAddress::Eth(address) => address,
+            OmniAddress::Sol(_) => {
+                return Err(BridgeSdkError::InvalidArgument(
+                    "Transfers to Solana are finalized with `fin_transfer_sol`".to_string(),
+                ))
+            }
+            other => {
+                return Err(BridgeSdkError::InvalidArgument(format!(
+                    "Finalization on {:?} is not supported",
+                    other.chain_kind()
+                )))
+            }
+        };
+
+        let omni_bridge = self.omni_bridge()?;
+        let payload = BridgeDeposit {
+            nonce,
+            token,
+            amount,
+            recipient,
+            fee_recipient,
+        };
+
+        let call = tx_opts.apply(
+            self.method_names
+                .eth(omni_bridge.fin_transfer(signature.into(), payload)),
+        );
+        let tx_hash = contract_call::send_eth_call(
+            &self.eth_client()?,
+            call,
+            tx_opts.confirmations.or(Some(1)),
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = format!("{:?}", tx_hash),
+            "Sent finalize transfer transaction"
+        );
+
+        Ok(tx_hash)
+    }
+
+    /// Finalizes a signed transfer to a Solana recipient with the `finalize_transfer` instruction of the bridge program,
+    /// which mints or releases `mint`, the bridged token on Solana. Waits for the transaction to be confirmed and
+    /// returns its signature
+    #[tracing::instrument(skip_all, name = "FIN TRANSFER SOL")]
+    pub async fn fin_transfer_sol(
+        &self,
+        payload: &TransferMessagePayload,
+        signature: Vec<u8>,
+        mint: &str,
+    ) -> Result<Signature> {
+        self.disabled_operations.check("fin_transfer")?;
+
+        let solana_client = self.solana_client()?;
+        let payer = self.solana_keypair()?;
+        let mint = Pubkey::from_str(mint)
+            .map_err(|_| BridgeSdkError::InvalidArgument(format!("Invalid Solana mint: {mint}")))?;
+        let token_program_id = solana_client
+            .get_account_owner(&mint)
+            .await
+            .map_err(solana_error)?
+            .ok_or(BridgeSdkError::InvalidArgument(format!(
+                "Solana mint {mint} doesn't exist"
+            )))?;
+
+        // The bridge program posts the Wormhole message to a new account, which has to sign its creation
+        let message = Keypair::new();
+        let instruction = self
+            .solana_bridge()?
+            .finalize_transfer(
+                &payer.pubkey(),
+                &message.pubkey(),
+                &mint,
+                &token_program_id,
+                payload,
+                &signature,
+            )
+            .map_err(BridgeSdkError::InvalidArgument)?;
+        let blockhash = solana_client
+            .get_latest_blockhash()
+            .await
+            .map_err(solana_error)?;
+        let transaction = Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&payer.pubkey()),
+            &[&payer, &message],
+            blockhash,
+        );
+        let tx_signature = transaction.signatures[0];
+
+        if self.dry_run {
+            let simulation = solana_client
+                .simulate_transaction(&transaction)
+                .await
+                .map_err(solana_error)?;
+
+            tracing::info!(
+                signature = tx_signature.to_string(),
+                units_consumed = simulation.units_consumed,
+                logs = ?simulation.logs,
+                "Simulated finalize transfer transaction"
+            );
+
+            return match simulation.err {
+                Some(error) => Err(solana_error(SolanaRpcError::TransactionFailed {
+                    signature: tx_signature,
+                    error,
+                })),
+                None => Ok(tx_signature),
+            };
+        }
+
+        solana_client
+            .send_transaction(&transaction)
+            .await
+            .map_err(solana_error)?;
+
+        tracing::info!(
+            signature = tx_signature.to_string(),
+            "Sent finalize transfer transaction"
+        );
+
+        solana_client
+            .wait_for_confirmation(&tx_signature, solana_rpc_client::CONFIRMATION_TIMEOUT)
+            .await
+            .map_err(solana_error)?;
+
+        Ok(tx_signature)
+    }
+
+    /// Claims the transfer fee on Near for the relayer. Requires a proof of the `fin_transfer` transaction on Ethereum
+    #[tracing::instrument(skip_all, name = "CLAIM FEE")]
+    pub async fn claim_fee(&self, tx_hash: TxHash, log_index: u64) -> Result<CryptoHash> {
+        self.disabled_operations.check("claim_fee")?;
+
+        let eth_rpc = self.eth_rpc()?;
+        let near_rpc = self.near_rpc()?;
+
+        let proof = self
+            .rpc_scheduler
+            .run(eth_rpc.endpoint(), RpcPriority::Proof, async {
+                Ok(eth_proof::get_proof_for_event_from_rpc_or_source(
+                    eth_rpc.as_ref(),
+                    self.proof_data_source.as_deref(),
+                    tx_hash,
+                    log_index,
+                    &self.proof_options,
+                )
+                .await?)
+            })
+            .await?;
+        events::emit(
+            self.events.as_deref(),
+            ConnectorEvent::ProofGenerated {
+                transfer: TransferId::Eth { tx_hash, log_index },
+            },
+        );
+
+        let mut prover_args = Vec::new();
+        ProofKind::FinTransfer
+            .serialize(&mut prover_args)
+            .and_then(|_| proof.serialize(&mut prover_args))
+            .map_err(|_| BridgeSdkError::EthProofError("Failed to serialize proof".to_string()))?;
+
+        tracing::debug!("Retrieved Ethereum proof");
+
+        let mut args = Vec::new();
+        ClaimFeeArgs {
+            chain_kind: ChainKind::Eth,
+            prover_args,
+        }
+        .serialize(&mut args)
+        .map_err(|err| BridgeSdkError::SerializationError(err.to_string()))?;
+
+        let tx_hash = chunked_upload::near_change(
+            near_rpc.as_ref(),
+            self.near_signer()?,
+            self.omni_locker_id()?.to_string(),
+            self.method_names.near("claim_fee"),
+            args,
+            self.near_tx_options.gas(300_000_000_000_000),
+            0,
+            self.dry_run,
+            self.near_tx_options.proof_chunk_size,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = format!("{:?}", tx_hash),
+            "Sent claim fee transaction"
+        );
+
+        Ok(tx_hash)
+    }
+
+    /// Metadata of a NEP-141 token, e.g. to check its decimals before computing the bridged amounts
+    pub async fn get_token_metadata(&self, token_id: AccountId) -> Result<FungibleTokenMetadata> {
+        Ok(self.near_rpc()?.get_ft_metadata(token_id).await?)
+    }
+
+    /// Verifies the configured capabilities: RPC reachability, presence of the bridge contracts and signer balances. Unset configuration values are skipped
+    pub async fn self_check(&self) -> SelfCheckReport {
+        let eth_endpoint = self.eth_endpoint.as_deref();
+        let near_endpoint = self.near_endpoint.as_deref();
+        let mut report = SelfCheckReport::default();
+
+        report.push(
+            "Ethereum RPC",
+            self_check::check_eth_rpc(eth_endpoint).await,
+        );
+        report.push("NEAR RPC", self_check::check_near_rpc(near_endpoint).await);
+        report.push(
+            "Omni bridge",
+            self_check::check_eth_code(
+                eth_endpoint,
+                self.omni_bridge_address
+                    .as_ref()
+                    .map(|_| self.omni_bridge_address()),
+            )
+            .await,
+        );
+        report.push(
+            "Omni locker",
+            self_check::check_near_code(near_endpoint, self.omni_locker_id.as_deref()).await,
+        );
+        report.push(
+            "Ethereum signer balance",
+            self_check::check_eth_balance(
+                eth_endpoint,
+                self.eth_private_key
+                    .as_ref()
+                    .map(|_| self.eth_signer().map(|wallet| wallet.address())),
+            )
+            .await,
+        );
+        report.push(
+            "NEAR signer balance",
+            self_check::check_near_balance(near_endpoint, self.near_signer.as_deref()).await,
+        );
+
+        report
+    }
+
+    /// Sends a transaction calling an arbitrary Ethereum contract function, signed with the configured Ethereum key
+    #[tracing::instrument(skip_all, name = "CALL ETH CONTRACT")]
+    pub async fn call_eth_contract(
+        &self,
+        address: Address,
+        abi_fn: &str,
+        args: Vec<Token>,
+        tx_opts: EthTxOptions,
+    ) -> Result<TxHash> {
+        self.disabled_operations.check("call_eth_contract")?;
+
+        contract_call::call_eth_contract(
+            self.eth_client()?,
+            address,
+            abi_fn,
+            args,
+            tx_opts,
+            self.dry_run,
+            self.eth_nonce_journal.as_deref(),
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await
+    }
+
+    /// Calls an arbitrary NEAR contract method, signed with the configured NEAR account
+    #[tracing::instrument(skip_all, name = "CALL NEAR CONTRACT")]
+    pub async fn call_near_contract(
+        &self,
+        account_id: AccountId,
+        method_name: String,
+        args: Vec<u8>,
+        gas: u64,
+        deposit: u128,
+    ) -> Result<CryptoHash> {
+        self.disabled_operations.check("call_near_contract")?;
+
+        let tx_hash = signer::near_change(
+            self.near_rpc()?.as_ref(),
+            self.near_signer()?,
+            account_id.to_string(),
+            method_name,
+            args,
+            gas,
+            deposit,
+            self.dry_run,
+            self.near_outcome_timeout,
+            self.events.as_deref(),
+            &self.rpc_scheduler,
+        )
+        .await?;
+
+        tracing::info!(
+            tx_hash = format!("{:?}", tx_hash),
+            "Sent contract call transaction"
+        );
+
+        Ok(tx_hash)
+    }
+
+    fn near_signer(&self) -> Result<Arc<dyn NearSigner>> {
+        if let Some(signer) = &self.custom_near_signer {
+            return Ok(signer.clone());
+        }
+
+        let near_private_key =
+            self.near_private_key
+                .as_ref()
+                .ok_or(BridgeSdkError::ConfigError(
+                    "Near account private key is not set".to_string(),
+                ))?;
+        let near_signer = self
+            .near_signer
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Near signer account id is not set".to_string(),
+            ))?;
+
+        Ok(Arc::new(near_crypto::InMemorySigner::from_secret_key(
+            AccountId::from_str(near_signer).map_err(|_| {
+                BridgeSdkError::ConfigError("Invalid near signer account id".to_string())
+            })?,
+            SecretKey::from_str(near_private_key)
+                .map_err(|_| BridgeSdkError::ConfigError("Invalid near private key".to_string()))?,
+        )))
+    }
+
+    fn omni_bridge(&self) -> Result<OmniBridge<EthSignerClient>> {
+        Ok(OmniBridge::new(
+            self.omni_bridge_address()?,
+            self.eth_client()?,
+        ))
+    }
+
+    fn eth_client(&self) -> Result<Arc<EthSignerClient>> {
+        self.eth_client_cache.get_or_try_init(|| {
+            let eth_provider = failover::eth_provider(self.eth_endpoint()?)?;
+
+            let wallet = self.eth_signer()?;
+
+            Ok(Arc::new(SignerMiddleware::new(eth_provider, wallet)))
+        })
+    }
+
+    fn eth_signer(&self) -> Result<DynEthSigner> {
+        if let Some(signer) = &self.custom_eth_signer {
+            return Ok(DynEthSigner::new(signer.clone()));
+        }
+
+        if let Some(eth_keystore_path) = &self.eth_keystore_path {
+            return signer::keystore_signer(
+                eth_keystore_path,
+                self.eth_keystore_password.as_deref(),
+                self.eth_chain_id,
+            );
+        }
+
+        let eth_private_key = self
+            .eth_private_key
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Ethereum private key is not set".to_string(),
+            ))?;
+
+        let eth_chain_id = self.eth_chain_id.ok_or(BridgeSdkError::ConfigError(
+            "Ethereum chain id is not set".to_string(),
+        ))?;
+
+        let private_key_bytes = hex::decode(eth_private_key).map_err(|_| {
+            BridgeSdkError::ConfigError(
+                "Ethereum private key is not a valid hex string".to_string(),
+            )
+        })?;
+
+        if private_key_bytes.len() != 32 {
+            return Err(BridgeSdkError::ConfigError(
+                "Ethereum private key is of invalid length".to_string(),
+            ));
+        }
+
+        let wallet = LocalWallet::from_bytes(&private_key_bytes)
+            .map_err(|_| BridgeSdkError::ConfigError("Invalid ethereum private key".to_string()))?
+            .with_chain_id(eth_chain_id);
+
+        Ok(DynEthSigner::new(Arc::new(wallet)))
+    }
+
+    fn omni_bridge_address(&self) -> Result<Address> {
+        self.omni_bridge_address
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Omni bridge address is not set".to_string(),
+            ))
+            .and_then(|addr| {
+                Address::from_str(addr).map_err(|_| {
+                    BridgeSdkError::ConfigError(
+                        "omni_bridge_address is not a valid Ethereum address".to_string(),
+                    )
+                })
+            })
+    }
+
+    fn eth_rpc(&self) -> Result<Arc<dyn EthRpc>> {
+        match &self.eth_rpc {
+            Some(eth_rpc) => Ok(eth_rpc.clone()),
+            None => Ok(Arc::new(EthRPCClient::new_with_options(
+                self.eth_endpoint()?,
+                self.eth_rpc_options.clone(),
+            ))),
+        }
+    }
+
+    fn eth_endpoint(&self) -> Result<&str> {
+        Ok(self
+            .eth_endpoint
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Ethereum rpc endpoint is not set".to_string(),
+            ))?)
+    }
+
+    fn near_rpc(&self) -> Result<Arc<dyn NearRpc>> {
+        match &self.near_rpc {
+            Some(near_rpc) => Ok(near_rpc.clone()),
+            // Cached, so that the operations share the health of the endpoints
+            None => self.near_rpc_cache.get_or_try_init(|| {
+                Ok(Arc::new(
+                    NearRpcClient::new(self.near_endpoint()?)
+                        .with_retry_policy(self.near_retry_policy),
+                ))
+            }),
+        }
+    }
+
+    fn near_endpoint(&self) -> Result<&str> {
+        Ok(self
+            .near_endpoint
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Near rpc endpoint is not set".to_string(),
+            ))?)
+    }
+
+    fn omni_locker_id(&self) -> Result<&str> {
+        Ok(self
+            .omni_locker_id
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Omni locker account id is not set".to_string(),
+            ))?)
+    }
+
+    fn solana_client(&self) -> Result<SolanaRpcClient> {
+        self.solana_endpoint
+            .as_deref()
+            .map(SolanaRpcClient::new)
+            .ok_or(BridgeSdkError::ConfigError(
+                "Solana rpc endpoint is not set".to_string(),
+            ))
+    }
+
+    fn solana_keypair(&self) -> Result<Keypair> {
+        let solana_keypair = self
+            .solana_keypair
+            .as_ref()
+            .ok_or(BridgeSdkError::ConfigError(
+                "Solana keypair is not set".to_string(),
+            ))?;
+
+        let keypair_bytes = bs58::decode(solana_keypair).into_vec().map_err(|_| {
+            BridgeSdkError::ConfigError("Solana keypair is not a valid base58 string".to_string())
+        })?;
+
+        if keypair_bytes.len() != 64 {
+            return Err(BridgeSdkError::ConfigError(
+                "Solana keypair is of invalid length".to_string(),
+            ));
+        }
+
+        // The keypair is the 32 bytes secret key followed by its public key
+        let keypair = keypair_from_seed(&keypair_bytes[..32])
+            .map_err(|_| BridgeSdkError::ConfigError("Invalid Solana keypair".to_string()))?;
+        if keypair.pubkey().to_bytes()[..] != keypair_bytes[32..] {
+            return Err(BridgeSdkError::ConfigError(
+                "Solana keypair public key doesn't match its secret key".to_string(),
+            ));
+        }
+
+        Ok(keypair)
+    }
+
+    fn solana_bridge(&self) -> Result<SolanaBridge> {
+        Ok(SolanaBridge {
+            program_id: solana_program_id(
+                self.solana_bridge_program_id.as_deref(),
+                "solana_bridge_program_id",
+            )?,
+            wormhole_program_id: solana_program_id(
+                self.solana_wormhole_program_id.as_deref(),
+                "solana_wormhole_program_id",
+            )?,
+        })
+    }
+}
+
+fn solana_program_id(program_id: Option<&str>, name: &str) -> Result<Pubkey> {
+    let program_id = program_id.ok_or(BridgeSdkError::ConfigError(format!("{name} is not set")))?;
+
+    Pubkey::from_str(program_id).map_err(|_| {
+        BridgeSdkError::ConfigError(format!("{name} is not a valid Solana public key"))
+    })
+}
+
+fn solana_error(error: SolanaRpcError) -> BridgeSdkError {
+    match error {
+        SolanaRpcError::TransactionFailed { signature, error } => BridgeSdkError::SolanaTxFailed {
+            signature: signature.to_string(),
+            reason: error.to_string(),
+        },
+        other => BridgeSdkError::SolanaRpcError(other.to_string()),
+    }
+}
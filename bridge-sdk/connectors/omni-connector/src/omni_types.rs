@@ -0,0 +1,253 @@
+use borsh::{
+    schema::{Declaration, Definition},
+    BorshSchema, BorshSerialize,
+};
+use bridge_connector_common::near_args::u128_string;
+use ethers::types::H160;
+use near_primitives::{hash::CryptoHash, types::AccountId, views::FinalExecutionOutcomeView};
+use near_rpc_client::borsh_schema;
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, Write},
+    str::FromStr,
+};
+
+#[derive(BorshSerialize, BorshSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainKind {
+    Eth,
+    Near,
+    Sol,
+}
+
+/// Proof kinds accepted by the omni locker prover
+#[derive(BorshSerialize, BorshSchema, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofKind {
+    InitTransfer,
+    FinTransfer,
+    DeployToken,
+}
+
+/// Chain-prefixed address understood by the omni locker, e.g. `eth:0x...`, `near:alice.near`, `sol:...`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OmniAddress {
+    Eth(H160),
+    Near(AccountId),
+    /// Base58-encoded Solana public key
+    Sol(String),
+}
+
+impl OmniAddress {
+    pub fn chain_kind(&self) -> ChainKind {
+        match self {
+            OmniAddress::Eth(_) => ChainKind::Eth,
+            OmniAddress::Near(_) => ChainKind::Near,
+            OmniAddress::Sol(_) => ChainKind::Sol,
+        }
+    }
+}
+
+impl fmt::Display for OmniAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OmniAddress::Eth(address) => write!(f, "eth:0x{}", hex::encode(address)),
+            OmniAddress::Near(account_id) => write!(f, "near:{account_id}"),
+            OmniAddress::Sol(address) => write!(f, "sol:{address}"),
+        }
+    }
+}
+
+impl FromStr for OmniAddress {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (chain, address) = s
+            .split_once(':')
+            .ok_or(format!("Omni address must be prefixed with a chain: {s}"))?;
+
+        match chain {
+            "eth" => {
+                Ok(OmniAddress::Eth(address.parse().map_err(|_| {
+                    format!("Invalid Ethereum address: {address}")
+                })?))
+            }
+            "near" => {
+                Ok(OmniAddress::Near(address.parse().map_err(|_| {
+                    format!("Invalid Near account id: {address}")
+                })?))
+            }
+            "sol" if !address.is_empty() => Ok(OmniAddress::Sol(address.to_string())),
+            _ => Err(format!("Unsupported omni address: {s}")),
+        }
+    }
+}
+
+/// Same layout as a derived enum encoding: the chain kind followed by the address
+impl BorshSerialize for OmniAddress {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.chain_kind().serialize(writer)?;
+
+        match self {
+            OmniAddress::Eth(address) => address.0.serialize(writer),
+            OmniAddress::Near(account_id) => account_id.serialize(writer),
+            OmniAddress::Sol(address) => address.serialize(writer),
+        }
+    }
+}
+
+/// Borsh layout of [`OmniAddress`], which is serialized by hand
+mod schema {
+    use near_rpc_client::borsh_schema::AccountId;
+
+    #[derive(borsh::BorshSchema)]
+    pub enum OmniAddress {
+        Eth([u8; 20]),
+        Near(AccountId),
+        Sol(String),
+    }
+}
+
+impl BorshSchema for OmniAddress {
+    fn add_definitions_recursively(definitions: &mut BTreeMap<Declaration, Definition>) {
+        schema::OmniAddress::add_definitions_recursively(definitions)
+    }
+
+    fn declaration() -> Declaration {
+        schema::OmniAddress::declaration()
+    }
+}
+
+impl Serialize for OmniAddress {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// `msg` of the `ft_transfer_call` initiating a transfer with the omni locker
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct InitTransferMsg {
+    pub recipient: OmniAddress,
+    #[serde(serialize_with = "u128_string")]
+    pub fee: u128,
+    #[serde(serialize_with = "u128_string")]
+    pub native_token_fee: u128,
+}
+
+/// Arguments of `sign_transfer` of the omni locker
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SignTransferArgs {
+    #[serde(serialize_with = "u128_string")]
+    pub nonce: u128,
+    pub fee_recipient: Option<AccountId>,
+}
+
+/// Arguments of `get_token_id` of the omni locker
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TokenAddressArgs {
+    pub address: OmniAddress,
+}
+
+/// Result of `init_transfer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepositOutcome {
+    pub tx_hash: CryptoHash,
+    /// Receipt that emitted the `InitTransferEvent`
+    pub receipt_id: CryptoHash,
+    /// Origin nonce assigned to the transfer by the omni locker. Used by `sign_transfer`
+    pub nonce: u128,
+}
+
+impl DepositOutcome {
+    /// Looks up the `InitTransferEvent` logged by the omni locker in the transaction outcome
+    pub fn from_outcome(outcome: &FinalExecutionOutcomeView) -> Option<Self> {
+        outcome.receipts_outcome.iter().find_map(|receipt| {
+            let nonce = receipt
+                .outcome
+                .logs
+                .iter()
+                .find_map(|log| parse_init_transfer_nonce(log))?;
+
+            Some(DepositOutcome {
+                tx_hash: outcome.transaction.hash,
+                receipt_id: receipt.id,
+                nonce,
+            })
+        })
+    }
+}
+
+/// Extracts the origin nonce from a log like `{"InitTransferEvent":{"transfer_message":{"origin_nonce":1,...}}}`
+fn parse_init_transfer_nonce(log: &str) -> Option<u128> {
+    let log = log.strip_prefix("EVENT_JSON:").unwrap_or(log);
+    let event: Value = serde_json::from_str(log).ok()?;
+
+    match event
+        .get("InitTransferEvent")?
+        .get("transfer_message")?
+        .get("origin_nonce")?
+    {
+        Value::Number(nonce) => nonce.as_u64().map(u128::from),
+        Value::String(nonce) => nonce.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn omni_address_roundtrip() {
+        let addresses = [
+            "eth:0x3fefc5a4b1c02f21cbc8d3613643ba0635b9a873",
+            "near:ft-locker.bridge.near",
+            "sol:9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin",
+        ];
+
+        for address in addresses {
+            let omni_address = OmniAddress::from_str(address).unwrap();
+            assert_eq!(omni_address.to_string(), address);
+        }
+    }
+
+    #[test]
+    fn omni_address_schema_follows_chain_kind() {
+        let container = borsh::schema_container_of::<OmniAddress>();
+        let Some(Definition::Enum { variants, .. }) = container.get_definition("OmniAddress")
+        else {
+            panic!("OmniAddress isn't defined as an enum");
+        };
+
+        let names: Vec<_> = variants.iter().map(|(_, name, _)| name.as_str()).collect();
+        assert_eq!(names, ["Eth", "Near", "Sol"]);
+    }
+
+    #[test]
+    fn init_transfer_nonce_is_parsed() {
+        let log = r#"{"InitTransferEvent":{"transfer_message":{"origin_nonce":42,"token":"near:wrap.near","amount":"100","recipient":"eth:0x3fefc5a4b1c02f21cbc8d3613643ba0635b9a873","fee":{"fee":"0","native_fee":"0"},"sender":"near:alice.near","msg":""}}}"#;
+        assert_eq!(parse_init_transfer_nonce(log), Some(42));
+
+        let log = r#"EVENT_JSON:{"InitTransferEvent":{"transfer_message":{"origin_nonce":"7"}}}"#;
+        assert_eq!(parse_init_transfer_nonce(log), Some(7));
+
+        assert_eq!(
+            parse_init_transfer_nonce("Transfer 100 from alice.near"),
+            None
+        );
+        assert_eq!(
+            parse_init_transfer_nonce(r#"{"SignTransferEvent":{"nonce":42}}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn omni_address_rejects_unknown_chain() {
+        assert!(OmniAddress::from_str("btc:1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2").is_err());
+        assert!(OmniAddress::from_str("0x3fefc5a4b1c02f21cbc8d3613643ba0635b9a873").is_err());
+    }
+}
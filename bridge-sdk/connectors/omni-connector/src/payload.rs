@@ -0,0 +1,250 @@
+use crate::{
+    omni_connector::{BridgeDeposit, FinTransferCall},
+    omni_types::OmniAddress,
+};
+use borsh::{BorshSchema, BorshSerialize};
+use ethers::{
+    abi::AbiEncode,
+    types::{Address, Bytes, H256},
+    utils::keccak256,
+};
+use near_primitives::{types::AccountId, views::FinalExecutionOutcomeView};
+use near_rpc_client::borsh_schema;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Transfer message signed by the MPC network on `sign_transfer`. The omni bridge on Ethereum rebuilds its borsh
+/// encoding from the [`BridgeDeposit`] passed to `finTransfer` and checks the signature against its keccak-256 hash
+#[derive(BorshSerialize, BorshSchema, Debug, Clone, PartialEq, Eq)]
+pub struct TransferMessagePayload {
+    pub nonce: u128,
+    /// NEP-141 token id on Near
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::AccountId>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::AccountId>"
+    )))]
+    pub token: AccountId,
+    pub amount: u128,
+    pub recipient: OmniAddress,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<Option<borsh_schema::AccountId>>",
+        definitions = "borsh_schema::add_definitions::<Option<borsh_schema::AccountId>>"
+    )))]
+    pub fee_recipient: Option<AccountId>,
+}
+
+impl TransferMessagePayload {
+    /// Message signed by the MPC network
+    pub fn signature_hash(&self) -> H256 {
+        H256(keccak256(
+            borsh::to_vec(self).expect("Borsh serialization can't fail"),
+        ))
+    }
+
+    /// Payload of `finTransfer`. `eth_token` is the address of the bridged token on Ethereum. Fails for recipients on
+    /// other chains
+    pub fn to_bridge_deposit(&self, eth_token: Address) -> Result<BridgeDeposit, String> {
+        let recipient = match &self.recipient {
+            OmniAddress::Eth(address) => *address,
+            other => {
+                return Err(format!(
+                    "Finalization on {:?} is not supported",
+                    other.chain_kind()
+                ))
+            }
+        };
+
+        Ok(BridgeDeposit {
+            nonce: self.nonce,
+            token: eth_token,
+            amount: self.amount,
+            recipient,
+            fee_recipient: self
+                .fee_recipient
+                .as_ref()
+                .map(AccountId::to_string)
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// ABI-encoded `finTransfer` calldata, including the function selector
+pub fn fin_transfer_calldata(signature: Vec<u8>, payload: BridgeDeposit) -> Bytes {
+    FinTransferCall {
+        signature: signature.into(),
+        payload,
+    }
+    .encode()
+    .into()
+}
+
+/// Signature of the MPC network as emitted in the `SignTransferEvent` log
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MpcSignature {
+    pub big_r: SerializableAffinePoint,
+    pub s: SerializableScalar,
+    pub recovery_id: u8,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SerializableAffinePoint {
+    /// Hex-encoded compressed point
+    pub affine_point: String,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SerializableScalar {
+    /// Hex-encoded big-endian scalar
+    pub scalar: String,
+}
+
+impl MpcSignature {
+    /// Looks up the signature of the `SignTransferEvent` logged by the omni locker in the `sign_transfer` outcome
+    pub fn from_outcome(outcome: &FinalExecutionOutcomeView) -> Option<Self> {
+        outcome
+            .receipts_outcome
+            .iter()
+            .flat_map(|receipt| &receipt.outcome.logs)
+            .find_map(|log| parse_sign_transfer_signature(log))
+    }
+
+    /// 65-byte `r || s || v` signature expected by `finTransfer`
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        let big_r = hex::decode(&self.big_r.affine_point)
+            .map_err(|_| format!("Invalid affine point: {}", self.big_r.affine_point))?;
+        let s = hex::decode(&self.s.scalar)
+            .map_err(|_| format!("Invalid scalar: {}", self.s.scalar))?;
+
+        // The x coordinate of the compressed point follows its parity byte
+        let r = match big_r.as_slice() {
+            [0x02 | 0x03, r @ ..] if r.len() == 32 => r,
+            _ => return Err(format!("Invalid affine point: {}", self.big_r.affine_point)),
+        };
+        if s.len() != 32 {
+            return Err(format!("Invalid scalar: {}", self.s.scalar));
+        }
+
+        let mut signature = Vec::with_capacity(65);
+        signature.extend_from_slice(r);
+        signature.extend_from_slice(&s);
+        signature.push(self.recovery_id + 27);
+
+        Ok(signature)
+    }
+}
+
+/// Extracts the signature from a log like `{"SignTransferEvent":{"signature":{...},"message_payload":{...}}}`
+fn parse_sign_transfer_signature(log: &str) -> Option<MpcSignature> {
+    let log = log.strip_prefix("EVENT_JSON:").unwrap_or(log);
+    let event: Value = serde_json::from_str(log).ok()?;
+
+    serde_json::from_value(event.get("SignTransferEvent")?.get("signature")?.clone()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::{abi::AbiDecode, signers::LocalWallet, types::Signature};
+    use std::str::FromStr;
+
+    const RECIPIENT: &str = "3fefc5a4b1c02f21cbc8d3613643ba0635b9a873";
+
+    fn payload() -> TransferMessagePayload {
+        TransferMessagePayload {
+            nonce: 42,
+            token: "wrap.near".parse().unwrap(),
+            amount: 1_000_000,
+            recipient: OmniAddress::from_str(&format!("eth:0x{RECIPIENT}")).unwrap(),
+            fee_recipient: Some("relayer.near".parse().unwrap()),
+        }
+    }
+
+    #[test]
+    fn payload_borsh_layout() {
+        let mut expected = Vec::new();
+        expected.extend_from_slice(&42u128.to_le_bytes());
+        expected.extend_from_slice(&9u32.to_le_bytes());
+        expected.extend_from_slice(b"wrap.near");
+        expected.extend_from_slice(&1_000_000u128.to_le_bytes());
+        expected.push(0);
+        expected.extend_from_slice(&hex::decode(RECIPIENT).unwrap());
+        expected.push(1);
+        expected.extend_from_slice(&12u32.to_le_bytes());
+        expected.extend_from_slice(b"relayer.near");
+
+        assert_eq!(borsh::to_vec(&payload()).unwrap(), expected);
+        assert_eq!(payload().signature_hash(), H256(keccak256(&expected)));
+    }
+
+    #[test]
+    fn bridge_deposit_rejects_near_recipient() {
+        let payload = TransferMessagePayload {
+            recipient: OmniAddress::from_str("near:alice.near").unwrap(),
+            ..payload()
+        };
+
+        assert!(payload.to_bridge_deposit(Address::zero()).is_err());
+    }
+
+    #[test]
+    fn fin_transfer_calldata_roundtrip() {
+        let eth_token = Address::repeat_byte(0x11);
+        let deposit = payload().to_bridge_deposit(eth_token).unwrap();
+        assert_eq!(deposit.fee_recipient, "relayer.near");
+
+        let calldata = fin_transfer_calldata(vec![0xab; 65], deposit.clone());
+
+        let selector =
+            &keccak256("finTransfer(bytes,(uint128,address,uint128,address,string))".as_bytes())
+                [..4];
+        assert_eq!(&calldata[..4], selector);
+
+        let decoded = FinTransferCall::decode(&calldata).unwrap();
+        assert_eq!(decoded.signature.to_vec(), vec![0xab; 65]);
+        assert_eq!(decoded.payload, deposit);
+    }
+
+    #[test]
+    fn mpc_signature_recovers_signer() {
+        let wallet = LocalWallet::from_bytes(&[1; 32]).unwrap();
+        let hash = payload().signature_hash();
+        let signature = wallet.sign_hash(hash).unwrap();
+
+        let mut r = [0u8; 32];
+        signature.r.to_big_endian(&mut r);
+        let mut s = [0u8; 32];
+        signature.s.to_big_endian(&mut s);
+        let recovery_id = (signature.v - 27) as u8;
+
+        let mpc_signature: MpcSignature = serde_json::from_str(&format!(
+            r#"{{"big_r":{{"affine_point":"0{}{}"}},"s":{{"scalar":"{}"}},"recovery_id":{recovery_id}}}"#,
+            2 + recovery_id,
+            hex::encode(r),
+            hex::encode(s),
+        ))
+        .unwrap();
+
+        let bytes = mpc_signature.to_bytes().unwrap();
+        assert_eq!(bytes, signature.to_vec());
+
+        let recovered = Signature::try_from(bytes.as_slice())
+            .unwrap()
+            .recover(hash)
+            .unwrap();
+        assert_eq!(recovered, ethers::signers::Signer::address(&wallet));
+    }
+
+    #[test]
+    fn sign_transfer_signature_is_parsed() {
+        let log = r#"{"SignTransferEvent":{"signature":{"big_r":{"affine_point":"02ab"},"s":{"scalar":"cd"},"recovery_id":1},"message_payload":{"nonce":"42"}}}"#;
+        let signature = parse_sign_transfer_signature(log).unwrap();
+        assert_eq!(signature.big_r.affine_point, "02ab");
+        assert_eq!(signature.s.scalar, "cd");
+        assert_eq!(signature.recovery_id, 1);
+
+        assert_eq!(
+            parse_sign_transfer_signature(r#"{"InitTransferEvent":{"transfer_message":{}}}"#),
+            None
+        );
+    }
+}
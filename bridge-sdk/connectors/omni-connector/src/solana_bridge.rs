@@ -0,0 +1,231 @@
+use crate::{omni_types::OmniAddress, payload::TransferMessagePayload};
+use borsh::BorshSerialize;
+use solana_sdk::{
+    hash::hashv,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+use std::str::FromStr;
+
+const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    solana_sdk::pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+const CONFIG_SEED: &[u8] = b"config";
+const AUTHORITY_SEED: &[u8] = b"authority";
+const VAULT_SEED: &[u8] = b"vault";
+const USED_NONCES_SEED: &[u8] = b"used_nonces";
+const WORMHOLE_BRIDGE_SEED: &[u8] = b"Bridge";
+const WORMHOLE_FEE_COLLECTOR_SEED: &[u8] = b"fee_collector";
+const WORMHOLE_SEQUENCE_SEED: &[u8] = b"Sequence";
+
+/// Nonces tracked by each `used_nonces` account of the bridge program
+const USED_NONCES_PER_ACCOUNT: u128 = 1024;
+
+/// Arguments of the `finalize_transfer` instruction. The transfer message is the one signed by the MPC network, with
+/// the recipient as a Solana public key
+#[derive(BorshSerialize)]
+struct FinalizeTransferArgs {
+    nonce: u128,
+    token: String,
+    amount: u128,
+    recipient: [u8; 32],
+    fee_recipient: Option<String>,
+    signature: [u8; 65],
+}
+
+/// Omni bridge program on Solana, which mints or releases the tokens of the transfers signed by the MPC network and
+/// posts a Wormhole message for the fee claim on Near
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolanaBridge {
+    pub program_id: Pubkey,
+    pub wormhole_program_id: Pubkey,
+}
+
+impl SolanaBridge {
+    /// `finalize_transfer` instruction of a transfer to a Solana recipient. `message` is the fresh account the
+    /// Wormhole message is posted to, and signs the transaction together with `payer`. `token_program_id` is the
+    /// owner of `mint`, the SPL token or Token-2022 program
+    pub fn finalize_transfer(
+        &self,
+        payer: &Pubkey,
+        message: &Pubkey,
+        mint: &Pubkey,
+        token_program_id: &Pubkey,
+        payload: &TransferMessagePayload,
+        signature: &[u8],
+    ) -> Result<Instruction, String> {
+        let recipient = match &payload.recipient {
+            OmniAddress::Sol(address) => Pubkey::from_str(address)
+                .map_err(|_| format!("Invalid Solana recipient: {address}"))?,
+            other => {
+                return Err(format!(
+                    "Recipient on {:?} can't be finalized on Solana",
+                    other.chain_kind()
+                ))
+            }
+        };
+        let signature: [u8; 65] = signature
+            .try_into()
+            .map_err(|_| format!("Signature must be 65 bytes, got {}", signature.len()))?;
+        let used_nonces_index = u64::try_from(payload.nonce / USED_NONCES_PER_ACCOUNT)
+            .map_err(|_| format!("Nonce {} is out of range", payload.nonce))?;
+
+        let args = FinalizeTransferArgs {
+            nonce: payload.nonce,
+            token: payload.token.to_string(),
+            amount: payload.amount,
+            recipient: recipient.to_bytes(),
+            fee_recipient: payload.fee_recipient.as_ref().map(ToString::to_string),
+            signature,
+        };
+        let mut data = instruction_discriminator("finalize_transfer").to_vec();
+        args.serialize(&mut data)
+            .map_err(|err| format!("Couldn't serialize the instruction: {err}"))?;
+
+        let config = self.find_address(&[CONFIG_SEED]);
+        let (token_account, _) = Pubkey::find_program_address(
+            &[recipient.as_ref(), token_program_id.as_ref(), mint.as_ref()],
+            &ASSOCIATED_TOKEN_PROGRAM_ID,
+        );
+        let (wormhole_bridge, _) =
+            Pubkey::find_program_address(&[WORMHOLE_BRIDGE_SEED], &self.wormhole_program_id);
+        let (fee_collector, _) =
+            Pubkey::find_program_address(&[WORMHOLE_FEE_COLLECTOR_SEED], &self.wormhole_program_id);
+        let (sequence, _) = Pubkey::find_program_address(
+            &[WORMHOLE_SEQUENCE_SEED, config.as_ref()],
+            &self.wormhole_program_id,
+        );
+
+        let accounts = vec![
+            AccountMeta::new(
+                self.find_address(&[USED_NONCES_SEED, &used_nonces_index.to_le_bytes()]),
+                false,
+            ),
+            AccountMeta::new_readonly(self.find_address(&[AUTHORITY_SEED]), false),
+            AccountMeta::new_readonly(recipient, false),
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(self.find_address(&[VAULT_SEED, mint.as_ref()]), false),
+            AccountMeta::new(token_account, false),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(wormhole_bridge, false),
+            AccountMeta::new(fee_collector, false),
+            AccountMeta::new(sequence, false),
+            AccountMeta::new(*message, true),
+            AccountMeta::new_readonly(self.wormhole_program_id, false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(*token_program_id, false),
+        ];
+
+        Ok(Instruction::new_with_bytes(
+            self.program_id,
+            &data,
+            accounts,
+        ))
+    }
+
+    fn find_address(&self, seeds: &[&[u8]]) -> Pubkey {
+        Pubkey::find_program_address(seeds, &self.program_id).0
+    }
+}
+
+/// Anchor selects the instruction by the first 8 bytes of the SHA-256 hash of `global:<name>`
+fn instruction_discriminator(name: &str) -> [u8; 8] {
+    let hash = hashv(&[b"global:", name.as_bytes()]);
+    let mut discriminator = [0; 8];
+    discriminator.copy_from_slice(&hash.to_bytes()[..8]);
+    discriminator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bridge() -> SolanaBridge {
+        SolanaBridge {
+            program_id: Pubkey::new_from_array([1; 32]),
+            wormhole_program_id: Pubkey::new_from_array([2; 32]),
+        }
+    }
+
+    fn payload(recipient: OmniAddress) -> TransferMessagePayload {
+        TransferMessagePayload {
+            nonce: 2050,
+            token: "token.near".parse().unwrap(),
+            amount: 100,
+            recipient,
+            fee_recipient: Some("relayer.near".parse().unwrap()),
+        }
+    }
+
+    #[test]
+    fn finalize_transfer_instruction_layout() {
+        let recipient = Pubkey::new_from_array([3; 32]);
+        let payer = Pubkey::new_from_array([4; 32]);
+        let message = Pubkey::new_from_array([5; 32]);
+        let mint = Pubkey::new_from_array([6; 32]);
+        let token_program_id = Pubkey::new_from_array([7; 32]);
+
+        let instruction = bridge()
+            .finalize_transfer(
+                &payer,
+                &message,
+                &mint,
+                &token_program_id,
+                &payload(OmniAddress::Sol(recipient.to_string())),
+                &[9; 65],
+            )
+            .unwrap();
+
+        assert_eq!(instruction.program_id, bridge().program_id);
+        assert_eq!(
+            instruction.data[..8],
+            instruction_discriminator("finalize_transfer")
+        );
+
+        let mut args = instruction.data[8..].to_vec();
+        let signature = args.split_off(args.len() - 65);
+        assert_eq!(signature, [9; 65]);
+        assert_eq!(
+            args,
+            borsh::to_vec(&(
+                2050u128,
+                "token.near",
+                100u128,
+                [3u8; 32],
+                Some("relayer.near")
+            ))
+            .unwrap()
+        );
+
+        assert_eq!(instruction.accounts.len(), 18);
+        assert_eq!(
+            instruction.accounts[0].pubkey,
+            Pubkey::find_program_address(
+                &[USED_NONCES_SEED, &2u64.to_le_bytes()],
+                &bridge().program_id
+            )
+            .0
+        );
+        assert_eq!(instruction.accounts[2].pubkey, recipient);
+        assert!(instruction.accounts[10].is_signer && instruction.accounts[10].pubkey == message);
+        assert!(instruction.accounts[12].is_signer && instruction.accounts[12].pubkey == payer);
+        assert_eq!(instruction.accounts[17].pubkey, token_program_id);
+    }
+
+    #[test]
+    fn finalize_transfer_rejects_invalid_payload() {
+        let key = Pubkey::new_from_array([1; 32]);
+        let finalize = |recipient, signature: &[u8]| {
+            bridge().finalize_transfer(&key, &key, &key, &key, &payload(recipient), signature)
+        };
+
+        assert!(finalize(OmniAddress::Near("alice.near".parse().unwrap()), &[0; 65]).is_err());
+        assert!(finalize(OmniAddress::Sol("not-base58!".to_string()), &[0; 65]).is_err());
+        assert!(finalize(OmniAddress::Sol(key.to_string()), &[0; 64]).is_err());
+    }
+}
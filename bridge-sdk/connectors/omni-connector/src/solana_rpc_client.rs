@@ -0,0 +1,295 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use solana_sdk::{hash::Hash, pubkey::Pubkey, signature::Signature, transaction::Transaction};
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+/// Commitment the blockhashes are fetched at and the transactions are confirmed with
+const COMMITMENT: &str = "confirmed";
+
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Time a sent transaction has to be confirmed. Its blockhash expires after about a minute, after which it can't be
+/// included anymore
+pub const CONFIRMATION_TIMEOUT: Duration = Duration::from_secs(90);
+
+#[derive(thiserror::Error, Debug)]
+pub enum SolanaRpcError {
+    #[error("Solana RPC request failed: {0}")]
+    TransportError(#[from] reqwest::Error),
+    #[error("Couldn't parse Solana RPC response: {0}")]
+    ParseError(String),
+    #[error("{method} failed: {error}")]
+    RpcError { method: String, error: Value },
+    #[error("Solana transaction {signature} failed: {error}")]
+    TransactionFailed { signature: Signature, error: Value },
+    #[error("Solana transaction {0} wasn't confirmed in time")]
+    ConfirmationTimeout(Signature),
+}
+
+/// Outcome of `simulateTransaction`
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationResult {
+    /// Error the transaction would fail with
+    pub err: Option<Value>,
+    #[serde(default)]
+    pub logs: Option<Vec<String>>,
+    pub units_consumed: Option<u64>,
+}
+
+/// JSON-RPC client of the Solana methods used to finalize transfers
+#[derive(Clone)]
+pub struct SolanaRpcClient {
+    endpoint_url: String,
+    client: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<Value>,
+}
+
+/// Result of the methods returning a value with the slot it was read at
+#[derive(Deserialize)]
+struct WithContext<T> {
+    value: T,
+}
+
+#[derive(Deserialize)]
+struct LatestBlockhash {
+    blockhash: String,
+}
+
+#[derive(Deserialize)]
+struct AccountInfo {
+    owner: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignatureStatus {
+    err: Option<Value>,
+    confirmation_status: Option<String>,
+}
+
+impl SolanaRpcClient {
+    pub fn new(endpoint_url: &str) -> Self {
+        Self {
+            endpoint_url: endpoint_url.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub async fn get_latest_blockhash(&self) -> Result<Hash, SolanaRpcError> {
+        let blockhash: WithContext<LatestBlockhash> = self
+            .request("getLatestBlockhash", json!([{ "commitment": COMMITMENT }]))
+            .await?;
+
+        Hash::from_str(&blockhash.value.blockhash)
+            .map_err(|err| SolanaRpcError::ParseError(format!("Invalid blockhash: {err}")))
+    }
+
+    /// Program owning the account, `None` if the account doesn't exist
+    pub async fn get_account_owner(
+        &self,
+        address: &Pubkey,
+    ) -> Result<Option<Pubkey>, SolanaRpcError> {
+        let account: WithContext<Option<AccountInfo>> = self
+            .request(
+                "getAccountInfo",
+                json!([
+                    address.to_string(),
+                    { "encoding": "base64", "commitment": COMMITMENT }
+                ]),
+            )
+            .await?;
+
+        account
+            .value
+            .map(|account| {
+                Pubkey::from_str(&account.owner)
+                    .map_err(|err| SolanaRpcError::ParseError(format!("Invalid owner: {err}")))
+            })
+            .transpose()
+    }
+
+    /// Executes the signed transaction without committing it
+    pub async fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<SimulationResult, SolanaRpcError> {
+        let simulation: WithContext<SimulationResult> = self
+            .request(
+                "simulateTransaction",
+                json!([
+                    encode_transaction(transaction)?,
+                    { "encoding": "base64", "commitment": COMMITMENT }
+                ]),
+            )
+            .await?;
+
+        Ok(simulation.value)
+    }
+
+    /// Sends the signed transaction once its preflight simulation succeeds, and returns its signature
+    pub async fn send_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Signature, SolanaRpcError> {
+        let signature: String = self
+            .request(
+                "sendTransaction",
+                json!([
+                    encode_transaction(transaction)?,
+                    { "encoding": "base64", "preflightCommitment": COMMITMENT }
+                ]),
+            )
+            .await?;
+
+        Signature::from_str(&signature)
+            .map_err(|err| SolanaRpcError::ParseError(format!("Invalid signature: {err}")))
+    }
+
+    /// Polls the status of the transaction until it's confirmed, fails or `timeout` elapses
+    pub async fn wait_for_confirmation(
+        &self,
+        signature: &Signature,
+        timeout: Duration,
+    ) -> Result<(), SolanaRpcError> {
+        let deadline = Instant::now() + timeout;
+
+        loop {
+            let statuses: WithContext<Vec<Option<SignatureStatus>>> = self
+                .request("getSignatureStatuses", json!([[signature.to_string()]]))
+                .await?;
+
+            if let Some(Some(status)) = statuses.value.into_iter().next() {
+                if let Some(error) = status.err {
+                    return Err(SolanaRpcError::TransactionFailed {
+                        signature: *signature,
+                        error,
+                    });
+                }
+
+                if matches!(
+                    status.confirmation_status.as_deref(),
+                    Some("confirmed" | "finalized")
+                ) {
+                    return Ok(());
+                }
+            }
+
+            if Instant::now() >= deadline {
+                return Err(SolanaRpcError::ConfirmationTimeout(*signature));
+            }
+
+            tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+        }
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<T, SolanaRpcError> {
+        let body = self
+            .client
+            .post(&self.endpoint_url)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": method,
+                "params": params,
+            }))
+            .send()
+            .await?
+            .bytes()
+            .await?;
+
+        parse_response(method, &body)
+    }
+}
+
+fn encode_transaction(transaction: &Transaction) -> Result<String, SolanaRpcError> {
+    let data = bincode::serialize(transaction)
+        .map_err(|err| SolanaRpcError::ParseError(format!("Invalid transaction: {err}")))?;
+
+    Ok(STANDARD.encode(data))
+}
+
+fn parse_response<T: DeserializeOwned>(method: &str, body: &[u8]) -> Result<T, SolanaRpcError> {
+    let response: RpcResponse<T> =
+        serde_json::from_slice(body).map_err(|err| SolanaRpcError::ParseError(err.to_string()))?;
+
+    match (response.result, response.error) {
+        (_, Some(error)) => Err(SolanaRpcError::RpcError {
+            method: method.to_string(),
+            error,
+        }),
+        (Some(result), None) => Ok(result),
+        (None, None) => Err(SolanaRpcError::ParseError(format!(
+            "{method} returned neither a result nor an error"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let blockhash: WithContext<LatestBlockhash> = parse_response(
+            "getLatestBlockhash",
+            br#"{"jsonrpc":"2.0","id":1,"result":{"context":{"slot":2792},"value":{"blockhash":"EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N","lastValidBlockHeight":3090}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            blockhash.value.blockhash,
+            "EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N"
+        );
+
+        let account: WithContext<Option<AccountInfo>> = parse_response(
+            "getAccountInfo",
+            br#"{"jsonrpc":"2.0","id":1,"result":{"context":{"slot":1},"value":null}}"#,
+        )
+        .unwrap();
+        assert!(account.value.is_none());
+
+        let err = parse_response::<String>(
+            "sendTransaction",
+            br#"{"jsonrpc":"2.0","id":1,"error":{"code":-32002,"message":"Transaction simulation failed"}}"#,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, SolanaRpcError::RpcError { method, .. } if method == "sendTransaction")
+        );
+
+        assert!(matches!(
+            parse_response::<String>("sendTransaction", br#"{"jsonrpc":"2.0","id":1}"#),
+            Err(SolanaRpcError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_signature_status() {
+        let statuses: WithContext<Vec<Option<SignatureStatus>>> = parse_response(
+            "getSignatureStatuses",
+            br#"{"jsonrpc":"2.0","id":1,"result":{"context":{"slot":82},"value":[{"slot":48,"confirmations":null,"err":{"InstructionError":[0,{"Custom":6002}]},"status":{"Err":{"InstructionError":[0,{"Custom":6002}]}},"confirmationStatus":"finalized"},null]}}"#,
+        )
+        .unwrap();
+
+        let status = statuses.value[0].as_ref().unwrap();
+        assert_eq!(status.confirmation_status.as_deref(), Some("finalized"));
+        assert_eq!(
+            status.err,
+            Some(json!({"InstructionError": [0, {"Custom": 6002}]}))
+        );
+        assert!(statuses.value[1].is_none());
+    }
+}
@@ -0,0 +1,9 @@
+use near_rpc_client::NearRpcError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum EthLightClientOnNearError {
+    #[error("Error communicating with Near: {0}")]
+    NearRpcError(#[from] NearRpcError),
+    #[error("Unexpected light client response: {0}")]
+    UnexpectedResponse(String),
+}
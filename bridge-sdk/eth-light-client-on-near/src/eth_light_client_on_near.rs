@@ -0,0 +1,155 @@
+use async_trait::async_trait;
+use borsh::BorshDeserialize;
+use near_jsonrpc_primitives::types::query::QueryResponseKind;
+use near_primitives::types::{AccountId, BlockReference, Finality};
+use near_rpc_client::NearRpcError;
+use serde::Serialize;
+
+use crate::error::EthLightClientOnNearError;
+
+/// State of the light client, to tell whether it keeps up with Ethereum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ClientHealth {
+    /// Last Ethereum execution block known to the light client
+    pub last_block_number: u64,
+    /// Slot of the last finalized beacon block submitted to the light client
+    pub finalized_beacon_block_slot: u64,
+}
+
+/// Ethereum light client on Near as read by the SDK. Implemented by [`EthOnNearClient`], and by
+/// `MockEthOnNearClient` with the `mock` feature
+#[async_trait]
+pub trait EthLightClientOnNear: Send + Sync {
+    /// Number of the last Ethereum block known to the light client
+    async fn last_block_number(&self) -> Result<u64, EthLightClientOnNearError>;
+
+    /// Hash of a finalized Ethereum block, `None` if it isn't known to the light client
+    async fn block_hash_safe(
+        &self,
+        block_number: u64,
+    ) -> Result<Option<[u8; 32]>, EthLightClientOnNearError>;
+}
+
+/// Ethereum light client on Near, e.g. `client-eth2.bridge.near`
+pub struct EthOnNearClient {
+    near_endpoint: String,
+    eth_on_near_client_account_id: AccountId,
+    block_reference: BlockReference,
+}
+
+impl EthOnNearClient {
+    pub fn new(eth_on_near_client_account_id: AccountId, near_rpc_endpoint: String) -> Self {
+        Self {
+            near_endpoint: near_rpc_endpoint,
+            eth_on_near_client_account_id,
+            block_reference: BlockReference::Finality(Finality::Final),
+        }
+    }
+
+    /// Reads the light client state at a past block instead of the final one, e.g. to find out why a proof was
+    /// rejected
+    pub fn at_block(mut self, block_reference: BlockReference) -> Self {
+        self.block_reference = block_reference;
+        self
+    }
+
+    /// Number of the last Ethereum block known to the light client. Events of this block and the ones before it can be proven
+    pub async fn last_block_number(&self) -> Result<u64, EthLightClientOnNearError> {
+        self.view("last_block_number", Vec::new()).await
+    }
+
+    /// Hash of a finalized Ethereum block. `None` if the block isn't finalized on the light client yet or was already
+    /// garbage collected
+    pub async fn block_hash_safe(
+        &self,
+        block_number: u64,
+    ) -> Result<Option<[u8; 32]>, EthLightClientOnNearError> {
+        let args = borsh::to_vec(&block_number).expect("Borsh serialization can't fail");
+
+        self.view("block_hash_safe", args).await
+    }
+
+    pub async fn client_health(&self) -> Result<ClientHealth, EthLightClientOnNearError> {
+        Ok(ClientHealth {
+            last_block_number: self.last_block_number().await?,
+            finalized_beacon_block_slot: self
+                .view("finalized_beacon_block_slot", Vec::new())
+                .await?,
+        })
+    }
+
+    /// The light client takes its view arguments and serializes its results with borsh
+    async fn view<T: BorshDeserialize>(
+        &self,
+        method_name: &str,
+        args: Vec<u8>,
+    ) -> Result<T, EthLightClientOnNearError> {
+        let response = near_rpc_client::view_raw(
+            &self.near_endpoint,
+            self.block_reference.clone(),
+            self.eth_on_near_client_account_id.clone(),
+            method_name.to_string(),
+            args,
+        )
+        .await?;
+
+        let QueryResponseKind::CallResult(result) = response.kind else {
+            return Err(NearRpcError::UnexpectedResponse.into());
+        };
+
+        borsh::from_slice(&result.result).map_err(|_| {
+            EthLightClientOnNearError::UnexpectedResponse(format!("Invalid {method_name} response"))
+        })
+    }
+}
+
+#[async_trait]
+impl EthLightClientOnNear for EthOnNearClient {
+    async fn last_block_number(&self) -> Result<u64, EthLightClientOnNearError> {
+        EthOnNearClient::last_block_number(self).await
+    }
+
+    async fn block_hash_safe(
+        &self,
+        block_number: u64,
+    ) -> Result<Option<[u8; 32]>, EthLightClientOnNearError> {
+        EthOnNearClient::block_hash_safe(self, block_number).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_client() -> EthOnNearClient {
+        EthOnNearClient::new(
+            "client-eth2.sepolia.testnet".parse().unwrap(),
+            "https://rpc.testnet.near.org".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_last_block_number() {
+        let last_block_number = get_client().last_block_number().await.unwrap();
+        assert!(last_block_number > 6_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_block_hash_safe() {
+        let client = get_client();
+        let last_block_number = client.last_block_number().await.unwrap();
+
+        assert!(client
+            .block_hash_safe(last_block_number)
+            .await
+            .unwrap()
+            .is_some());
+        assert_eq!(
+            client
+                .block_hash_safe(last_block_number + 1000)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+}
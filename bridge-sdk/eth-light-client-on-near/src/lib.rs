@@ -0,0 +1,9 @@
+mod error;
+mod eth_light_client_on_near;
+#[cfg(any(test, feature = "mock"))]
+mod mock;
+
+pub use error::EthLightClientOnNearError;
+pub use eth_light_client_on_near::{ClientHealth, EthLightClientOnNear, EthOnNearClient};
+#[cfg(any(test, feature = "mock"))]
+pub use mock::MockEthOnNearClient;
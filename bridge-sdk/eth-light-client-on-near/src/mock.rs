@@ -0,0 +1,99 @@
+use crate::{error::EthLightClientOnNearError, EthLightClientOnNear};
+use async_trait::async_trait;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+
+/// In-memory Ethereum light client on Near with scripted heights and block hashes, to simulate a light client that is
+/// behind or has finalized a block in tests
+#[derive(Debug, Default)]
+pub struct MockEthOnNearClient {
+    state: Mutex<MockState>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    last_block_number: u64,
+    next_block_numbers: VecDeque<u64>,
+    block_hashes: HashMap<u64, [u8; 32]>,
+}
+
+impl MockEthOnNearClient {
+    pub fn new(last_block_number: u64) -> Self {
+        Self::with_block_numbers([last_block_number])
+    }
+
+    /// Returns the block numbers one per `last_block_number` call and keeps the last one, e.g. to simulate a light
+    /// client that catches up while it is polled
+    pub fn with_block_numbers(block_numbers: impl IntoIterator<Item = u64>) -> Self {
+        let next_block_numbers: VecDeque<u64> = block_numbers.into_iter().collect();
+
+        Self {
+            state: Mutex::new(MockState {
+                last_block_number: next_block_numbers.front().copied().unwrap_or_default(),
+                next_block_numbers,
+                block_hashes: HashMap::new(),
+            }),
+        }
+    }
+
+    pub fn set_last_block_number(&self, last_block_number: u64) {
+        let mut state = self.state();
+        state.next_block_numbers.clear();
+        state.last_block_number = last_block_number;
+    }
+
+    pub fn set_block_hash(&self, block_number: u64, block_hash: [u8; 32]) {
+        self.state().block_hashes.insert(block_number, block_hash);
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state
+            .lock()
+            .expect("Mock light client lock is poisoned")
+    }
+}
+
+#[async_trait]
+impl EthLightClientOnNear for MockEthOnNearClient {
+    async fn last_block_number(&self) -> Result<u64, EthLightClientOnNearError> {
+        let mut state = self.state();
+        if let Some(block_number) = state.next_block_numbers.pop_front() {
+            state.last_block_number = block_number;
+        }
+
+        Ok(state.last_block_number)
+    }
+
+    /// Only the hashes of blocks up to the current last block number are known, like in the light client contract
+    async fn block_hash_safe(
+        &self,
+        block_number: u64,
+    ) -> Result<Option<[u8; 32]>, EthLightClientOnNearError> {
+        let state = self.state();
+
+        Ok(state
+            .block_hashes
+            .get(&block_number)
+            .filter(|_| block_number <= state.last_block_number)
+            .copied())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn blocks_become_available_once_synced() {
+        let client = MockEthOnNearClient::with_block_numbers([10, 20]);
+        client.set_block_hash(15, [1; 32]);
+
+        assert_eq!(client.last_block_number().await.unwrap(), 10);
+        assert_eq!(client.block_hash_safe(15).await.unwrap(), None);
+        assert_eq!(client.last_block_number().await.unwrap(), 20);
+        assert_eq!(client.block_hash_safe(15).await.unwrap(), Some([1; 32]));
+        assert_eq!(client.block_hash_safe(16).await.unwrap(), None);
+    }
+}
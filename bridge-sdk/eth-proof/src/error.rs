@@ -7,6 +7,10 @@ pub enum EthProofError {
     TrieError(#[from] TrieError),
     #[error("Could not fetch data for Ethereum proof: {0}")]
     EthClientError(#[from] EthClientError),
+    #[error("Invalid proof fixture: {0}")]
+    FixtureError(String),
+    #[error("Invalid proof metrics: {0}")]
+    MetricsError(String),
     #[error("Could not generate Ethereum proof: {0}")]
     Other(String),
 }
@@ -0,0 +1,96 @@
+use crate::eth_rpc_client::{
+    types::{BlockHeader, BlockId, StorageProof, TransactionReceipt},
+    EthClientError, EthRPCClient,
+};
+use async_trait::async_trait;
+use ethereum_types::{H160, H256, U64};
+use serde_json::Value;
+
+/// Ethereum RPC reads of the SDK. Implemented by [`EthRPCClient`], and by `MockEthRpc` with the `mock` feature, so
+/// that code reading Ethereum through this trait can be tested without a node. Every implementation is a
+/// [`crate::ProofDataSource`]
+#[async_trait]
+pub trait EthRpc: Send + Sync {
+    /// Endpoint the requests are sent to, which the connectors schedule their requests by
+    fn endpoint(&self) -> &str;
+
+    async fn get_transaction_receipt_by_hash(
+        &self,
+        tx_hash: &H256,
+    ) -> Result<TransactionReceipt, EthClientError>;
+
+    /// Header of the block, from `eth_getBlockByNumber` without transaction bodies
+    async fn get_block_by_number(&self, block_number: U64) -> Result<BlockHeader, EthClientError>;
+
+    /// Receipts of the block, in the order of their transactions
+    async fn get_block_receipts(
+        &self,
+        block_number: U64,
+    ) -> Result<Vec<TransactionReceipt>, EthClientError>;
+
+    /// Raw `result` of `eth_getLogs` for the logs emitted by `address` with the first topic `topic`
+    async fn get_logs(
+        &self,
+        address: &H160,
+        topic: &H256,
+        from_block: U64,
+        to_block: U64,
+    ) -> Result<Value, EthClientError>;
+
+    async fn get_proof(
+        &self,
+        address: &H160,
+        keys: &[H256],
+        block: BlockId,
+    ) -> Result<StorageProof, EthClientError>;
+
+    async fn get_block_number(&self) -> Result<U64, EthClientError>;
+}
+
+#[async_trait]
+impl EthRpc for EthRPCClient {
+    fn endpoint(&self) -> &str {
+        &self.endpoint_url
+    }
+
+    async fn get_transaction_receipt_by_hash(
+        &self,
+        tx_hash: &H256,
+    ) -> Result<TransactionReceipt, EthClientError> {
+        EthRPCClient::get_transaction_receipt_by_hash(self, tx_hash).await
+    }
+
+    async fn get_block_by_number(&self, block_number: U64) -> Result<BlockHeader, EthClientError> {
+        EthRPCClient::get_block_by_number(self, block_number).await
+    }
+
+    async fn get_block_receipts(
+        &self,
+        block_number: U64,
+    ) -> Result<Vec<TransactionReceipt>, EthClientError> {
+        EthRPCClient::get_block_receipts(self, block_number).await
+    }
+
+    async fn get_logs(
+        &self,
+        address: &H160,
+        topic: &H256,
+        from_block: U64,
+        to_block: U64,
+    ) -> Result<Value, EthClientError> {
+        EthRPCClient::get_logs(self, address, topic, from_block, to_block).await
+    }
+
+    async fn get_proof(
+        &self,
+        address: &H160,
+        keys: &[H256],
+        block: BlockId,
+    ) -> Result<StorageProof, EthClientError> {
+        EthRPCClient::get_proof(self, address, keys, block).await
+    }
+
+    async fn get_block_number(&self) -> Result<U64, EthClientError> {
+        EthRPCClient::get_block_number(self).await
+    }
+}
@@ -1,108 +1,644 @@
-use ::serde::Deserialize;
-use ethereum_types::{H256, U64};
-use reqwest::Client;
+use crate::{
+    rate_limit::RateLimiter,
+    retry::{self, RetryPolicy},
+};
+use ::serde::{de::DeserializeOwned, Deserialize};
+use ethereum_types::{H160, H256, U64};
+use futures::{StreamExt, TryStreamExt};
+use reqwest::{Client, Response, StatusCode};
 use serde_json::{json, Value};
-use types::{BlockHeader, TransactionReceipt};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 
 mod serde;
 pub mod types;
 
+use types::{BlockHeader, BlockId, StorageProof, TransactionReceipt};
+
 #[derive(thiserror::Error, Debug)]
 pub enum EthClientError {
     #[error("Ethereum RPC error: {0}")]
     TransportError(#[from] reqwest::Error),
     #[error("Couldn't deserialize Ethereum RPC response: {0}")]
     ParseError(#[from] serde_json::Error),
+    #[error("Ethereum RPC response to {method} exceeds {max_size} bytes")]
+    ResponseTooLarge { method: String, max_size: usize },
+    #[error("{method} failed: {error}")]
+    RpcError { method: String, error: Value },
+    #[error("Ethereum RPC endpoint doesn't accept batches of {method} requests")]
+    BatchUnsupported { method: String },
+}
+
+impl EthClientError {
+    /// Whether the node doesn't implement the method, e.g. `eth_getBlockReceipts` on older clients
+    pub fn is_unsupported_method(&self) -> bool {
+        match self {
+            Self::RpcError { error, .. } => {
+                error["code"] == METHOD_NOT_FOUND_CODE
+                    || error["message"].as_str().is_some_and(|message| {
+                        let message = message.to_lowercase();
+                        message.contains("method")
+                            && UNSUPPORTED_METHOD_MESSAGES
+                                .iter()
+                                .any(|unsupported| message.contains(unsupported))
+                    })
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Largest response read by default. Receipts of the busiest blocks take a few tens of MB
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 128 * 1024 * 1024;
+
+/// Largest number of requests sent in a single batch. Most providers reject larger batches
+const MAX_BATCH_SIZE: usize = 50;
+
+/// Requests of a single fetch, e.g. the receipts of a block, sent at once by default
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 16;
+
+/// JSON-RPC error code of an unknown method
+pub(crate) const METHOD_NOT_FOUND_CODE: i64 = -32601;
+
+/// Messages of gateways rejecting an unknown method with another error code, e.g. `Unsupported method: ...` or
+/// `the method ... does not exist/is not available`. Only matched in messages mentioning the method, so that e.g. an
+/// unsupported block tag isn't taken for an unknown method
+const UNSUPPORTED_METHOD_MESSAGES: &[&str] = &[
+    "not found",
+    "does not exist",
+    "not available",
+    "not supported",
+    "unsupported",
+];
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<Value>,
+}
+
+/// Hashes of the transactions of a block, as returned by `eth_getBlockByNumber` without transaction bodies
+#[derive(Deserialize)]
+struct BlockTransactions {
+    transactions: Vec<H256>,
+}
+
+#[derive(Deserialize)]
+struct BatchRpcResponse<T> {
+    id: usize,
+    result: Option<T>,
+    error: Option<Value>,
+}
+
+/// Options of an [`EthRPCClient`]. The defaults retry with [`RetryPolicy::DEFAULT`], without a rate limit
+#[derive(Debug, Clone)]
+pub struct EthRPCClientOptions {
+    /// Largest response read, see [`EthRPCClient::with_max_response_size`]
+    pub max_response_size: usize,
+    pub retry_policy: RetryPolicy,
+    /// Limiter every request waits for, including the ones retried or sent to another endpoint. The same limiter can
+    /// be passed to several clients sharing a provider
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    /// Requests sent at once when a fetch takes several of them, e.g. the receipts of a busy block on a node without
+    /// `eth_getBlockReceipts`
+    pub max_concurrent_requests: usize,
+}
+
+impl Default for EthRPCClientOptions {
+    fn default() -> Self {
+        Self {
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            retry_policy: RetryPolicy::DEFAULT,
+            rate_limiter: None,
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+        }
+    }
 }
 
 pub struct EthRPCClient {
-    endpoint_url: String,
+    /// As passed to [`Self::new`], possibly a list of endpoints
+    pub(crate) endpoint_url: String,
+    endpoint_urls: Vec<String>,
+    current_endpoint: AtomicUsize,
     client: Client,
+    max_response_size: usize,
+    retry_policy: RetryPolicy,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    max_concurrent_requests: usize,
 }
 
 impl EthRPCClient {
+    /// `endpoint_url` can be a comma separated list of endpoints. A request failing with a transport error or an HTTP
+    /// 429 or 5xx status is retried on the next endpoint, which then serves the following requests. Requests failing
+    /// with a transient error on every endpoint are retried as set by [`EthRPCClientOptions::retry_policy`]
     pub fn new(endpoint_url: &str) -> Self {
+        Self::new_with_options(endpoint_url, EthRPCClientOptions::default())
+    }
+
+    /// Same as [`Self::new`], with the given options instead of the default ones
+    pub fn new_with_options(endpoint_url: &str, options: EthRPCClientOptions) -> Self {
+        let mut endpoint_urls: Vec<String> = endpoint_url
+            .split(',')
+            .map(str::trim)
+            .filter(|endpoint_url| !endpoint_url.is_empty())
+            .map(str::to_string)
+            .collect();
+        if endpoint_urls.is_empty() {
+            endpoint_urls.push(endpoint_url.to_string());
+        }
+
         Self {
             endpoint_url: endpoint_url.to_string(),
+            endpoint_urls,
+            current_endpoint: AtomicUsize::new(0),
             client: reqwest::Client::new(),
+            max_response_size: options.max_response_size,
+            retry_policy: options.retry_policy,
+            rate_limiter: options.rate_limiter,
+            max_concurrent_requests: options.max_concurrent_requests.max(1),
         }
     }
 
+    /// Fails requests whose response is larger than `max_response_size` bytes instead of buffering it, e.g. to bound
+    /// the memory of a relayer
+    pub fn with_max_response_size(mut self, max_response_size: usize) -> Self {
+        self.max_response_size = max_response_size;
+        self
+    }
+
+    /// Retries transient errors as set by `retry_policy` instead of [`RetryPolicy::DEFAULT`]
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
     pub async fn get_transaction_receipt_by_hash(
         &self,
         tx_hash: &H256,
     ) -> Result<TransactionReceipt, EthClientError> {
-        let json_value = json!({
-            "id": 1,
-            "jsonrpc": "2.0",
-            "method": "eth_getTransactionReceipt",
-            "params": [format!("{tx_hash:#x}")]
-        });
+        self.request_as(
+            "eth_getTransactionReceipt",
+            json!([format!("{tx_hash:#x}")]),
+        )
+        .await
+    }
 
-        let res = self
-            .client
-            .post(&self.endpoint_url)
-            .json(&json_value)
-            .send()
-            .await?
-            .text()
-            .await?;
+    /// Header of the block, from `eth_getBlockByNumber` without transaction bodies
+    pub async fn get_block_by_number(
+        &self,
+        block_number: U64,
+    ) -> Result<BlockHeader, EthClientError> {
+        self.request_as(
+            "eth_getBlockByNumber",
+            json!([format!("0x{:x}", block_number), false]),
+        )
+        .await
+    }
 
-        let val: Value = serde_json::from_str(&res)?;
-        let receipt = TransactionReceipt::deserialize(&val["result"])?;
+    /// Receipts of the block, see [`Self::get_block_receipts_as`]
+    pub async fn get_block_receipts(
+        &self,
+        block_number: U64,
+    ) -> Result<Vec<TransactionReceipt>, EthClientError> {
+        self.get_block_receipts_as(block_number).await
+    }
 
-        Ok(receipt)
+    /// Returns the raw `result` of `eth_getLogs` for the logs emitted by `address` with the first topic `topic`
+    pub async fn get_logs(
+        &self,
+        address: &H160,
+        topic: &H256,
+        from_block: U64,
+        to_block: U64,
+    ) -> Result<Value, EthClientError> {
+        self.request(
+            "eth_getLogs",
+            json!([{
+                "address": format!("{address:#x}"),
+                "topics": [format!("{topic:#x}")],
+                "fromBlock": format!("0x{:x}", from_block),
+                "toBlock": format!("0x{:x}", to_block),
+            }]),
+        )
+        .await
     }
 
-    pub async fn get_block_by_number(
+    /// Proof of the account at `address` and of its storage `keys` at `block`, with `eth_getProof`
+    pub async fn get_proof(
+        &self,
+        address: &H160,
+        keys: &[H256],
+        block: BlockId,
+    ) -> Result<StorageProof, EthClientError> {
+        let keys: Vec<String> = keys.iter().map(|key| format!("{key:#x}")).collect();
+
+        self.request_as(
+            "eth_getProof",
+            json!([format!("{address:#x}"), keys, block_parameter(block)]),
+        )
+        .await
+    }
+
+    /// Returns the number of the latest block
+    pub async fn get_block_number(&self) -> Result<U64, EthClientError> {
+        self.request_as("eth_blockNumber", json!([])).await
+    }
+
+    /// Raw `result` of the request, e.g. to record a proof fixture. Fails with the RPC error if there's no result
+    pub(crate) async fn request(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, EthClientError> {
+        self.request_as(method, params).await
+    }
+
+    /// Same as [`Self::request`], parsing the `result` right into `T`. Large responses, e.g. the receipts of a busy
+    /// block, aren't held as a `Value` tree in memory on top of their parsed form
+    pub(crate) async fn request_as<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: Value,
+    ) -> Result<T, EthClientError> {
+        let body = self.send(method, rpc_request(1, method, params)).await?;
+
+        parse_response(method, &body)
+    }
+
+    /// Receipts of the block with `eth_getBlockReceipts`. Nodes that don't support it are asked for the receipt of
+    /// each transaction of the block instead: in batches, or concurrently one by one if they reject batches too
+    pub(crate) async fn get_block_receipts_as<T: DeserializeOwned + Send>(
         &self,
         block_number: U64,
-    ) -> Result<BlockHeader, EthClientError> {
-        let json_value = json!({
-            "id": 1,
-            "jsonrpc": "2.0",
-            "method": "eth_getBlockByNumber",
-            "params": [format!("0x{:x}", block_number), false]
-        });
+    ) -> Result<Vec<T>, EthClientError> {
+        let block_receipts = self
+            .request_as(
+                "eth_getBlockReceipts",
+                json!([format!("0x{:x}", block_number)]),
+            )
+            .await;
+
+        match block_receipts {
+            Err(err) if err.is_unsupported_method() => {
+                tracing::debug!(
+                    %block_number,
+                    "eth_getBlockReceipts is unsupported, fetching the receipt of each transaction"
+                );
+                self.get_transaction_receipts_as(block_number).await
+            }
+            block_receipts => block_receipts,
+        }
+    }
 
-        let res = self
-            .client
-            .post(&self.endpoint_url)
-            .json(&json_value)
-            .send()
-            .await?
-            .text()
+    async fn get_transaction_receipts_as<T: DeserializeOwned + Send>(
+        &self,
+        block_number: U64,
+    ) -> Result<Vec<T>, EthClientError> {
+        let block: BlockTransactions = self
+            .request_as(
+                "eth_getBlockByNumber",
+                json!([format!("0x{:x}", block_number), false]),
+            )
             .await?;
+        let params: Vec<Value> = block
+            .transactions
+            .iter()
+            .map(|tx_hash| json!([format!("{tx_hash:#x}")]))
+            .collect();
 
-        let val: Value = serde_json::from_str(&res)?;
-        let header = BlockHeader::deserialize(&val["result"])?;
+        match self
+            .batch_request_as("eth_getTransactionReceipt", params.clone())
+            .await
+        {
+            Err(EthClientError::BatchUnsupported { .. }) => {
+                let mut receipts: Vec<(usize, T)> =
+                    futures::stream::iter(params.into_iter().enumerate())
+                        .map(|(index, params)| async move {
+                            let receipt =
+                                self.request_as("eth_getTransactionReceipt", params).await?;
+                            Ok::<_, EthClientError>((index, receipt))
+                        })
+                        .buffer_unordered(self.max_concurrent_requests)
+                        .try_collect()
+                        .await?;
+                receipts.sort_unstable_by_key(|(index, _)| *index);
 
-        Ok(header)
+                Ok(receipts.into_iter().map(|(_, receipt)| receipt).collect())
+            }
+            receipts => receipts,
+        }
     }
 
-    pub async fn get_block_receipts(
+    /// Calls `method` once for each of `params`, in JSON-RPC batches of at most [`MAX_BATCH_SIZE`] requests, e.g. to
+    /// fetch the receipts of a block one transaction at a time in a few round trips. The results are in the order of
+    /// `params`. Fails if any of the calls fails. The batches are sent concurrently
+    pub(crate) async fn batch_request_as<T: DeserializeOwned + Send>(
         &self,
-        block_number: U64,
-    ) -> Result<Vec<TransactionReceipt>, EthClientError> {
-        let json_value = json!({
-            "id": 1,
-            "jsonrpc": "2.0",
-            "method": "eth_getBlockReceipts",
-            "params": [format!("0x{:x}", block_number)]
-        });
+        method: &str,
+        params: Vec<Value>,
+    ) -> Result<Vec<T>, EthClientError> {
+        let batches: Vec<Vec<T>> = futures::stream::iter(params.chunks(MAX_BATCH_SIZE))
+            .map(|chunk| async move {
+                let batch = chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(id, params)| rpc_request(id, method, params.clone()))
+                    .collect();
+                let body = self.send(method, Value::Array(batch)).await?;
 
-        let res = self
-            .client
-            .post(&self.endpoint_url)
-            .json(&json_value)
-            .send()
-            .await?
-            .text()
+                parse_batch_response(method, &body, chunk.len())
+            })
+            .buffered(self.max_concurrent_requests)
+            .try_collect()
             .await?;
 
-        let val: Value = serde_json::from_str(&res)?;
-        let receipts = Vec::<TransactionReceipt>::deserialize(&val["result"])?;
+        Ok(batches.into_iter().flatten().collect())
+    }
+
+    async fn send(&self, method: &str, json_value: Value) -> Result<Vec<u8>, EthClientError> {
+        let mut attempt = 0;
+        loop {
+            let result = self.send_once(&json_value).await;
+            let is_transient = match &result {
+                Ok(response) => retry::is_transient_status(response.status()),
+                Err(err) => retry::is_transient_error(err),
+            };
+
+            if !is_transient || attempt + 1 >= self.retry_policy.attempts {
+                return self.read_body(method, result?).await;
+            }
+
+            tokio::time::sleep(self.retry_policy.delay(attempt)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Sends the request to each endpoint in turn until one of them responds
+    async fn send_once(&self, json_value: &Value) -> Result<Response, reqwest::Error> {
+        let first = self.current_endpoint.load(Ordering::Relaxed);
+        let endpoints = self.endpoint_urls.len();
+
+        for attempt in 0..endpoints {
+            let index = (first + attempt) % endpoints;
+            let is_last = attempt + 1 == endpoints;
+            if let Some(rate_limiter) = &self.rate_limiter {
+                rate_limiter.acquire().await;
+            }
+            let result = self
+                .client
+                .post(&self.endpoint_urls[index])
+                .json(json_value)
+                .send()
+                .await;
+
+            match result {
+                Ok(response) if is_last || !should_fail_over(response.status()) => {
+                    return Ok(response);
+                }
+                Err(err) if is_last => return Err(err),
+                _ => {
+                    // Concurrent requests failing on the same endpoint only move past it once
+                    let _ = self.current_endpoint.compare_exchange(
+                        index,
+                        (index + 1) % endpoints,
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    );
+                }
+            }
+        }
+
+        unreachable!("The last endpoint always returns its result")
+    }
+
+    /// Reads the response chunk by chunk, failing as soon as it exceeds the maximum size
+    async fn read_body(
+        &self,
+        method: &str,
+        mut response: Response,
+    ) -> Result<Vec<u8>, EthClientError> {
+        let too_large = || EthClientError::ResponseTooLarge {
+            method: method.to_string(),
+            max_size: self.max_response_size,
+        };
+
+        let content_length = response.content_length().unwrap_or_default();
+        if content_length > self.max_response_size as u64 {
+            return Err(too_large());
+        }
+
+        let mut body = Vec::with_capacity(content_length as usize);
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > self.max_response_size {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(body)
+    }
+}
+
+/// Rate limiting and server errors are retried on the next endpoint
+fn should_fail_over(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Block parameter of the state queries, blocks given by hash use the EIP-1898 object
+fn block_parameter(block: BlockId) -> Value {
+    match block {
+        BlockId::Latest => json!("latest"),
+        BlockId::Number(block_number) => json!(format!("{block_number:#x}")),
+        BlockId::Hash(block_hash) => json!({
+            "blockHash": format!("{block_hash:#x}"),
+            "requireCanonical": true,
+        }),
+    }
+}
+
+fn rpc_request(id: usize, method: &str, params: Value) -> Value {
+    json!({
+        "id": id,
+        "jsonrpc": "2.0",
+        "method": method,
+        "params": params
+    })
+}
+
+fn parse_response<T: DeserializeOwned>(method: &str, body: &[u8]) -> Result<T, EthClientError> {
+    let response: RpcResponse<T> = serde_json::from_slice(body)?;
+
+    into_result(method, response.result, response.error)
+}
+
+/// Results of a batch by request id. Nodes may respond to the requests of a batch in any order. Nodes rejecting
+/// batches respond with a single error instead of an array of responses
+fn parse_batch_response<T: DeserializeOwned>(
+    method: &str,
+    body: &[u8],
+    len: usize,
+) -> Result<Vec<T>, EthClientError> {
+    if body.iter().find(|byte| !byte.is_ascii_whitespace()) != Some(&b'[') {
+        return Err(EthClientError::BatchUnsupported {
+            method: method.to_string(),
+        });
+    }
+
+    let responses: Vec<BatchRpcResponse<T>> = serde_json::from_slice(body)?;
+    let mut results: Vec<Option<T>> = (0..len).map(|_| None).collect();
+
+    for response in responses {
+        if let Some(slot) = results.get_mut(response.id) {
+            *slot = Some(into_result(method, response.result, response.error)?);
+        }
+    }
+
+    results
+        .into_iter()
+        .enumerate()
+        .map(|(id, result)| {
+            result.ok_or_else(|| {
+                ::serde::de::Error::custom(format!(
+                    "{method} batch has no response to request {id}"
+                ))
+            })
+        })
+        .collect::<Result<_, serde_json::Error>>()
+        .map_err(EthClientError::from)
+}
+
+fn into_result<T>(
+    method: &str,
+    result: Option<T>,
+    error: Option<Value>,
+) -> Result<T, EthClientError> {
+    match (result, error) {
+        (Some(result), _) => Ok(result),
+        (None, Some(error)) => Err(EthClientError::RpcError {
+            method: method.to_string(),
+            error,
+        }),
+        (None, None) => Err(EthClientError::ParseError(::serde::de::Error::custom(
+            format!("{method} returned no result"),
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let block_number: U64 = parse_response(
+            "eth_blockNumber",
+            br#"{"jsonrpc":"2.0","id":1,"result":"0x10"}"#,
+        )
+        .unwrap();
+        assert_eq!(block_number, U64::from(16));
+
+        let error = parse_response::<U64>(
+            "eth_blockNumber",
+            br#"{"jsonrpc":"2.0","id":1,"error":{"code":-32005,"message":"limit exceeded"}}"#,
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("limit exceeded"));
+
+        assert!(parse_response::<U64>(
+            "eth_getTransactionReceipt",
+            br#"{"jsonrpc":"2.0","id":1,"result":null}"#
+        )
+        .is_err());
+
+        let error = parse_response::<U64>(
+            "eth_getBlockReceipts",
+            br#"{"jsonrpc":"2.0","id":1,"error":{"code":-32601,"message":"the method does not exist"}}"#,
+        )
+        .unwrap_err();
+        assert!(error.is_unsupported_method());
+
+        let error = parse_response::<U64>(
+            "eth_getBlockReceipts",
+            br#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"Method Not Found"}}"#,
+        )
+        .unwrap_err();
+        assert!(error.is_unsupported_method());
+
+        let error = parse_response::<U64>(
+            "eth_getBlockReceipts",
+            br#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"Unsupported block tag"}}"#,
+        )
+        .unwrap_err();
+        assert!(!error.is_unsupported_method());
+
+        let error = parse_response::<U64>(
+            "eth_getTransactionReceipt",
+            br#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"transaction type not supported"}}"#,
+        )
+        .unwrap_err();
+        assert!(!error.is_unsupported_method());
+    }
+
+    #[test]
+    fn test_block_parameter() {
+        assert_eq!(block_parameter(BlockId::Latest), json!("latest"));
+        assert_eq!(block_parameter(U64::from(255).into()), json!("0xff"));
+        assert_eq!(
+            block_parameter(H256::repeat_byte(1).into()),
+            json!({
+                "blockHash": format!("{:#x}", H256::repeat_byte(1)),
+                "requireCanonical": true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_batch_response() {
+        let block_numbers: Vec<U64> = parse_batch_response(
+            "eth_blockNumber",
+            br#"[{"jsonrpc":"2.0","id":1,"result":"0x2"},{"jsonrpc":"2.0","id":0,"result":"0x1"}]"#,
+            2,
+        )
+        .unwrap();
+        assert_eq!(block_numbers, [U64::from(1), U64::from(2)]);
+
+        assert!(parse_batch_response::<U64>(
+            "eth_blockNumber",
+            br#"[{"jsonrpc":"2.0","id":0,"result":"0x1"}]"#,
+            2,
+        )
+        .is_err());
+
+        assert!(matches!(
+            parse_batch_response::<U64>(
+                "eth_blockNumber",
+                br#" {"jsonrpc":"2.0","id":null,"error":{"code":-32600,"message":"batch requests are not supported"}}"#,
+                1,
+            ),
+            Err(EthClientError::BatchUnsupported { .. })
+        ));
+
+        // Malformed results of an accepted batch aren't taken for a rejected batch
+        assert!(matches!(
+            parse_batch_response::<U64>(
+                "eth_blockNumber",
+                br#"[{"jsonrpc":"2.0","id":0,"result":"not a number"}]"#,
+                1,
+            ),
+            Err(EthClientError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_endpoint_failover() {
+        let client = EthRPCClient::new("http://a:8545, http://b:8545,");
+        assert_eq!(client.endpoint_urls, ["http://a:8545", "http://b:8545"]);
 
-        Ok(receipts)
+        assert!(should_fail_over(StatusCode::TOO_MANY_REQUESTS));
+        assert!(should_fail_over(StatusCode::BAD_GATEWAY));
+        assert!(!should_fail_over(StatusCode::OK));
+        assert!(!should_fail_over(StatusCode::BAD_REQUEST));
     }
 }
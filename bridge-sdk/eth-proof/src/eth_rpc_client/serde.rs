@@ -1,5 +1,5 @@
 use super::types::{Bytes, U8};
-use serde::{de, Deserialize, Deserializer};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 impl<'de> Deserialize<'de> for Bytes {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -15,6 +15,15 @@ impl<'de> Deserialize<'de> for Bytes {
     }
 }
 
+impl Serialize for Bytes {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("0x{}", hex::encode(&self.0)))
+    }
+}
+
 impl<'de> Deserialize<'de> for U8 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -66,6 +75,12 @@ mod test {
         assert_eq!(bytes.0, vec![0x00]);
     }
 
+    #[test]
+    fn serialize_bytes() {
+        let bytes = Bytes(vec![0x02, 0x12, 0x34]);
+        assert_eq!(serde_json::to_string(&bytes).unwrap(), r#""0x021234""#);
+    }
+
     #[test]
     fn deserialize_u8() {
         let s = r#""0x11""#;
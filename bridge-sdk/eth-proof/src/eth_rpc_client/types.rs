@@ -1,11 +1,11 @@
-use ethereum_types::{Address, Bloom, H256, U128, U64};
+use ethereum_types::{Address, Bloom, H256, U128, U256, U64};
 use rlp::{Encodable, RlpStream};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub struct Bytes(pub Vec<u8>);
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct U8(pub u8);
 
 impl Encodable for U8 {
@@ -43,6 +43,7 @@ pub struct BlockHeader {
     pub blob_gas_used: Option<U64>,
     pub excess_blob_gas: Option<U64>,
     pub parent_beacon_block_root: Option<H256>,
+    pub requests_hash: Option<H256>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -52,17 +53,66 @@ pub struct Log {
     pub topics: Vec<H256>,
     pub data: Bytes,
     pub log_index: U64,
+    pub block_number: Option<U64>,
+    pub transaction_hash: Option<H256>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionReceipt {
     pub block_number: U64,
+    pub block_hash: Option<H256>,
     pub transaction_index: U64,
-    #[serde(rename = "type")]
+    /// EIP-2718 type, e.g. 3 for blob (EIP-4844) and 4 for set code (EIP-7702) transactions. Some nodes leave it out
+    /// for legacy transactions
+    #[serde(rename = "type", default)]
     pub transaction_type: U8,
     pub cumulative_gas_used: Bytes,
     pub logs_bloom: Bloom,
     pub logs: Vec<Log>,
     pub status: U8,
 }
+
+/// Block a state query like `eth_getProof` is made at. The state of blocks older than the last 128 or so is only
+/// kept by archive nodes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BlockId {
+    #[default]
+    Latest,
+    Number(U64),
+    /// Fails if the block isn't canonical anymore, rather than proving the state of a reorged block
+    Hash(H256),
+}
+
+impl From<U64> for BlockId {
+    fn from(block_number: U64) -> Self {
+        Self::Number(block_number)
+    }
+}
+
+impl From<H256> for BlockId {
+    fn from(block_hash: H256) -> Self {
+        Self::Hash(block_hash)
+    }
+}
+
+/// Merkle proof of an account and some of its storage slots against the state root, as returned by `eth_getProof`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageProof {
+    pub address: Address,
+    pub balance: U256,
+    pub code_hash: H256,
+    pub nonce: U64,
+    pub storage_hash: H256,
+    pub account_proof: Vec<Bytes>,
+    pub storage_proof: Vec<StorageSlotProof>,
+}
+
+/// Merkle proof of a storage slot against the storage root of its account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StorageSlotProof {
+    pub key: H256,
+    pub value: U256,
+    pub proof: Vec<Bytes>,
+}
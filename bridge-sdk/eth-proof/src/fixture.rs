@@ -0,0 +1,76 @@
+use crate::{
+    error::EthProofError,
+    eth_rpc_client::{types::TransactionReceipt, EthRPCClient},
+};
+use ethereum_types::H256;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{fs, path::Path};
+
+/// Raw RPC responses needed to generate the proof for an event. Replaying a fixture produces the same proof without
+/// access to an Ethereum node, which makes proof bugs reproducible offline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofFixture {
+    pub tx_hash: H256,
+    pub log_index: u64,
+    pub receipt: Value,
+    pub block_header: Value,
+    pub block_receipts: Value,
+}
+
+impl ProofFixture {
+    pub async fn record(
+        tx_hash: H256,
+        log_index: u64,
+        node_url: &str,
+    ) -> Result<Self, EthProofError> {
+        let client = EthRPCClient::new(node_url);
+
+        // The responses are recorded as is, so that replaying them goes through the same parsing
+        let receipt = client
+            .request(
+                "eth_getTransactionReceipt",
+                json!([format!("{tx_hash:#x}")]),
+            )
+            .await?;
+        let block_number = TransactionReceipt::deserialize(&receipt)
+            .map_err(|err| EthProofError::EthClientError(err.into()))?
+            .block_number;
+
+        let block_header = client
+            .request(
+                "eth_getBlockByNumber",
+                json!([format!("0x{:x}", block_number), false]),
+            )
+            .await?;
+        let block_receipts = Value::Array(client.get_block_receipts_as(block_number).await?);
+
+        Ok(Self {
+            tx_hash,
+            log_index,
+            receipt,
+            block_header,
+            block_receipts,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, EthProofError> {
+        let data = fs::read_to_string(path).map_err(|err| {
+            EthProofError::FixtureError(format!("Could not read fixture file: {err}"))
+        })?;
+
+        serde_json::from_str(&data).map_err(|err| {
+            EthProofError::FixtureError(format!("Could not parse fixture file: {err}"))
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), EthProofError> {
+        let data = serde_json::to_string_pretty(self).map_err(|err| {
+            EthProofError::FixtureError(format!("Could not serialize fixture: {err}"))
+        })?;
+
+        fs::write(path, data).map_err(|err| {
+            EthProofError::FixtureError(format!("Could not write fixture file: {err}"))
+        })
+    }
+}
@@ -1,7 +1,31 @@
 mod error;
+mod eth_rpc;
 mod eth_rpc_client;
+mod fixture;
+pub mod metrics;
+#[cfg(any(test, feature = "mock"))]
+mod mock;
+pub mod proof_cache;
+mod proof_data_source;
 mod proof_generator;
+mod proof_verifier;
+pub mod rate_limit;
+pub mod retry;
+mod storage_proof;
 
 pub use error::EthProofError;
-pub use eth_rpc_client::EthClientError;
-pub use proof_generator::get_proof_for_event;
+pub use eth_rpc::EthRpc;
+pub use eth_rpc_client::{
+    types, EthClientError, EthRPCClient, EthRPCClientOptions, DEFAULT_MAX_CONCURRENT_REQUESTS,
+    DEFAULT_MAX_RESPONSE_SIZE,
+};
+pub use fixture::ProofFixture;
+#[cfg(any(test, feature = "mock"))]
+pub use mock::MockEthRpc;
+pub use proof_data_source::ProofDataSource;
+pub use proof_generator::{
+    get_proof_for_event, get_proof_for_event_from_rpc_or_source, get_proof_for_event_from_source,
+    get_proof_for_event_with_options, get_proof_from_fixture, Proof, ProofOptions,
+};
+pub use proof_verifier::verify_proof;
+pub use storage_proof::{get_account_proof, get_storage_proof};
@@ -0,0 +1,151 @@
+use crate::error::EthProofError;
+use ethereum_types::H256;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Measurements of a generated proof. The fetch duration mostly depends on the data source, e.g. a degraded RPC
+/// provider, and the build duration on the number and size of the block receipts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofMetrics {
+    pub tx_hash: H256,
+    pub block_number: u64,
+    /// Unix timestamp in seconds
+    pub generated_at: u64,
+    /// Time spent fetching the receipt, the block header and the block receipts
+    pub fetch_duration_ms: u64,
+    /// Time spent building the receipt trie and the proof
+    pub build_duration_ms: u64,
+    pub block_receipts: usize,
+    /// Size of the encoded receipts inserted in the trie, in bytes
+    pub trie_size: usize,
+    pub proof_nodes: usize,
+}
+
+/// Aggregate of recorded [`ProofMetrics`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ProofMetricsSummary {
+    pub proofs: usize,
+    pub fetch_duration_p50_ms: u64,
+    pub fetch_duration_p95_ms: u64,
+    pub build_duration_p50_ms: u64,
+    pub build_duration_p95_ms: u64,
+    pub max_block_receipts: usize,
+    pub max_trie_size: usize,
+}
+
+impl ProofMetricsSummary {
+    pub fn new(metrics: &[ProofMetrics]) -> Self {
+        let fetch_durations = sorted(metrics.iter().map(|m| m.fetch_duration_ms));
+        let build_durations = sorted(metrics.iter().map(|m| m.build_duration_ms));
+
+        Self {
+            proofs: metrics.len(),
+            fetch_duration_p50_ms: percentile(&fetch_durations, 50),
+            fetch_duration_p95_ms: percentile(&fetch_durations, 95),
+            build_duration_p50_ms: percentile(&build_durations, 50),
+            build_duration_p95_ms: percentile(&build_durations, 95),
+            max_block_receipts: metrics.iter().map(|m| m.block_receipts).max().unwrap_or(0),
+            max_trie_size: metrics.iter().map(|m| m.trie_size).max().unwrap_or(0),
+        }
+    }
+}
+
+/// Metrics recorded to the file of [`crate::ProofOptions::metrics_file`]
+pub fn load_metrics(path: impl AsRef<Path>) -> Result<Vec<ProofMetrics>, EthProofError> {
+    let data = fs::read_to_string(path).map_err(|err| {
+        EthProofError::MetricsError(format!("Could not read metrics file: {err}"))
+    })?;
+
+    data.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            serde_json::from_str(line).map_err(|err| {
+                EthProofError::MetricsError(format!("Could not parse metrics file: {err}"))
+            })
+        })
+        .collect()
+}
+
+/// Appends the metrics to the file as a JSON line. A failure to write them is only logged, as it doesn't affect the
+/// proof
+pub(crate) fn record(path: &Path, metrics: &ProofMetrics) {
+    if let Err(err) = append(path, metrics) {
+        tracing::warn!(path = %path.display(), %err, "Failed to record proof metrics");
+    }
+}
+
+pub(crate) fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+pub(crate) fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+fn append(path: &Path, metrics: &ProofMetrics) -> std::io::Result<()> {
+    let mut line = serde_json::to_string(metrics)?;
+    line.push('\n');
+
+    // A single write per line keeps the lines of concurrent proofs from interleaving
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
+fn sorted(values: impl Iterator<Item = u64>) -> Vec<u64> {
+    let mut values: Vec<_> = values.collect();
+    values.sort_unstable();
+    values
+}
+
+/// Nearest-rank percentile of sorted values, zero if there are none
+fn percentile(sorted: &[u64], percent: usize) -> u64 {
+    match sorted.len() {
+        0 => 0,
+        len => sorted[((len * percent).div_ceil(100)).max(1) - 1],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(fetch_duration_ms: u64, block_receipts: usize) -> ProofMetrics {
+        ProofMetrics {
+            tx_hash: H256::zero(),
+            block_number: 1,
+            generated_at: 0,
+            fetch_duration_ms,
+            build_duration_ms: 10,
+            block_receipts,
+            trie_size: block_receipts * 100,
+            proof_nodes: 3,
+        }
+    }
+
+    #[test]
+    fn test_metrics_summary() {
+        let recorded: Vec<_> = (1..=20).map(|i| metrics(i * 100, i as usize)).collect();
+        let summary = ProofMetricsSummary::new(&recorded);
+
+        assert_eq!(summary.proofs, 20);
+        assert_eq!(summary.fetch_duration_p50_ms, 1000);
+        assert_eq!(summary.fetch_duration_p95_ms, 1900);
+        assert_eq!(summary.build_duration_p95_ms, 10);
+        assert_eq!(summary.max_block_receipts, 20);
+        assert_eq!(summary.max_trie_size, 2000);
+        assert_eq!(
+            ProofMetricsSummary::new(&[]),
+            ProofMetricsSummary::default()
+        );
+    }
+}
@@ -0,0 +1,212 @@
+use crate::{
+    eth_rpc::EthRpc,
+    eth_rpc_client::{
+        types::{BlockHeader, BlockId, Log, StorageProof, TransactionReceipt},
+        EthClientError, METHOD_NOT_FOUND_CODE,
+    },
+};
+use async_trait::async_trait;
+use ethereum_types::{H160, H256, U64};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::{collections::HashMap, sync::Mutex};
+
+/// In-memory Ethereum RPC with scripted receipts, blocks, logs and account proofs, to test code reading Ethereum
+/// through [`EthRpc`] without a node. Like any [`EthRpc`], it's a [`crate::ProofDataSource`], so that proofs of the
+/// scripted receipts can be generated. Other requests fail as if the node didn't know the method
+#[derive(Debug, Default)]
+pub struct MockEthRpc {
+    state: Mutex<MockState>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    block_number: U64,
+    receipts: HashMap<H256, Value>,
+    blocks: HashMap<U64, Value>,
+    logs: Vec<Value>,
+    proofs: HashMap<H160, StorageProof>,
+}
+
+impl MockEthRpc {
+    pub fn new(block_number: u64) -> Self {
+        let rpc = Self::default();
+        rpc.set_block_number(block_number);
+        rpc
+    }
+
+    pub fn set_block_number(&self, block_number: u64) {
+        self.state().block_number = block_number.into();
+    }
+
+    /// Raw receipt as returned by `eth_getTransactionReceipt`
+    pub fn add_receipt(&self, tx_hash: H256, receipt: Value) {
+        self.state().receipts.insert(tx_hash, receipt);
+    }
+
+    /// Raw block as returned by `eth_getBlockByNumber` without transaction bodies
+    pub fn add_block(&self, block_number: u64, block: Value) {
+        self.state().blocks.insert(block_number.into(), block);
+    }
+
+    /// Raw log as returned by `eth_getLogs`, filtered by its address, first topic and block number
+    pub fn add_log(&self, log: Value) {
+        self.state().logs.push(log);
+    }
+
+    /// Proof of the account at `proof.address`, whatever the keys and the block
+    pub fn set_proof(&self, proof: StorageProof) {
+        self.state().proofs.insert(proof.address, proof);
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state
+            .lock()
+            .expect("Mock Ethereum RPC lock is poisoned")
+    }
+}
+
+fn not_mocked(method: &str, request: String) -> EthClientError {
+    EthClientError::RpcError {
+        method: method.to_string(),
+        error: json!({
+            "code": METHOD_NOT_FOUND_CODE,
+            "message": format!("{request} is not mocked"),
+        }),
+    }
+}
+
+fn parse<T: DeserializeOwned>(value: Value) -> Result<T, EthClientError> {
+    Ok(serde_json::from_value(value)?)
+}
+
+#[async_trait]
+impl EthRpc for MockEthRpc {
+    fn endpoint(&self) -> &str {
+        "mock"
+    }
+
+    async fn get_transaction_receipt_by_hash(
+        &self,
+        tx_hash: &H256,
+    ) -> Result<TransactionReceipt, EthClientError> {
+        let receipt = self.state().receipts.get(tx_hash).cloned().ok_or_else(|| {
+            not_mocked(
+                "eth_getTransactionReceipt",
+                format!("Receipt of {tx_hash:#x}"),
+            )
+        })?;
+
+        parse(receipt)
+    }
+
+    async fn get_block_by_number(&self, block_number: U64) -> Result<BlockHeader, EthClientError> {
+        let block = self
+            .state()
+            .blocks
+            .get(&block_number)
+            .cloned()
+            .ok_or_else(|| not_mocked("eth_getBlockByNumber", format!("Block {block_number}")))?;
+
+        parse(block)
+    }
+
+    /// The scripted receipts of the block, in the order of their transactions
+    async fn get_block_receipts(
+        &self,
+        block_number: U64,
+    ) -> Result<Vec<TransactionReceipt>, EthClientError> {
+        let receipts: Vec<Value> = self.state().receipts.values().cloned().collect();
+
+        let mut receipts = receipts
+            .into_iter()
+            .map(parse::<TransactionReceipt>)
+            .collect::<Result<Vec<_>, _>>()?;
+        receipts.retain(|receipt| receipt.block_number == block_number);
+        receipts.sort_by_key(|receipt| receipt.transaction_index);
+
+        Ok(receipts)
+    }
+
+    async fn get_logs(
+        &self,
+        address: &H160,
+        topic: &H256,
+        from_block: U64,
+        to_block: U64,
+    ) -> Result<Value, EthClientError> {
+        let logs = self
+            .state()
+            .logs
+            .iter()
+            .filter(|log| {
+                serde_json::from_value::<Log>((*log).clone()).is_ok_and(|log| {
+                    log.address == *address
+                        && log.topics.first() == Some(topic)
+                        && log
+                            .block_number
+                            .is_some_and(|number| (from_block..=to_block).contains(&number))
+                })
+            })
+            .cloned()
+            .collect();
+
+        Ok(Value::Array(logs))
+    }
+
+    async fn get_proof(
+        &self,
+        address: &H160,
+        _keys: &[H256],
+        _block: BlockId,
+    ) -> Result<StorageProof, EthClientError> {
+        self.state()
+            .proofs
+            .get(address)
+            .cloned()
+            .ok_or_else(|| not_mocked("eth_getProof", format!("Proof of {address:#x}")))
+    }
+
+    async fn get_block_number(&self) -> Result<U64, EthClientError> {
+        Ok(self.state().block_number)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn serves_scripted_responses() {
+        let rpc = MockEthRpc::new(100);
+        let address = H160::repeat_byte(1);
+        let topic = H256::repeat_byte(2);
+        let log = |address: H160, block_number: u64| {
+            json!({
+                "address": format!("{address:#x}"),
+                "topics": [format!("{topic:#x}")],
+                "data": "0x",
+                "logIndex": "0x0",
+                "blockNumber": format!("{block_number:#x}"),
+                "transactionHash": format!("{:#x}", H256::repeat_byte(3)),
+            })
+        };
+        rpc.add_log(log(address, 10));
+        rpc.add_log(log(address, 50));
+        rpc.add_log(log(H160::repeat_byte(4), 10));
+
+        assert_eq!(rpc.get_block_number().await.unwrap(), U64::from(100u64));
+
+        let logs = rpc
+            .get_logs(&address, &topic, 1u64.into(), 20u64.into())
+            .await
+            .unwrap();
+        assert_eq!(logs, Value::Array(vec![log(address, 10)]));
+
+        let err = rpc
+            .get_transaction_receipt_by_hash(&H256::zero())
+            .await
+            .unwrap_err();
+        assert!(err.is_unsupported_method());
+    }
+}
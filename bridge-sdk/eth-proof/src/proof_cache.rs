@@ -0,0 +1,218 @@
+use crate::error::EthProofError;
+use cita_trie::{MemoryDB, PatriciaTrie, Trie};
+use ethereum_types::H256;
+use hasher::{Hasher, HasherKeccak};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    fs,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+/// Blocks kept in memory by [`ProofCache::default`]
+pub const DEFAULT_CACHED_BLOCKS: usize = 64;
+
+/// Receipt trie of a block, along with the encoded header and receipts it was built from
+#[derive(Debug)]
+pub(crate) struct BlockTrie {
+    pub header_data: Vec<u8>,
+    /// Encoded receipts by transaction index
+    pub receipts: Vec<(u64, Vec<u8>)>,
+    /// Size of the encoded receipts, in bytes
+    pub trie_size: usize,
+    block_hash: H256,
+    db: Arc<MemoryDB>,
+    root: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct BlockTrieFile {
+    header_data: String,
+    receipts: Vec<(u64, String)>,
+}
+
+impl BlockTrie {
+    pub fn new(header_data: Vec<u8>, receipts: Vec<(u64, Vec<u8>)>) -> Result<Self, EthProofError> {
+        let db = Arc::new(MemoryDB::new(true));
+        let mut trie = PatriciaTrie::new(db.clone(), Arc::new(HasherKeccak::new()));
+        let mut trie_size = 0;
+
+        for (transaction_index, receipt_data) in &receipts {
+            trie_size += receipt_data.len();
+            trie.insert(
+                rlp::encode(transaction_index).to_vec(),
+                receipt_data.clone(),
+            )?;
+        }
+        let root = trie.root()?;
+
+        Ok(Self {
+            block_hash: H256::from_slice(&HasherKeccak::new().digest(&header_data)),
+            header_data,
+            receipts,
+            trie_size,
+            db,
+            root,
+        })
+    }
+
+    pub fn block_hash(&self) -> H256 {
+        self.block_hash
+    }
+
+    /// Proof of the receipt of the transaction at `transaction_index`
+    pub fn proof(&self, transaction_index: u64) -> Result<Vec<Vec<u8>>, EthProofError> {
+        let trie = PatriciaTrie::from(self.db.clone(), Arc::new(HasherKeccak::new()), &self.root)?;
+
+        Ok(trie.get_proof(&rlp::encode(&transaction_index))?)
+    }
+}
+
+/// Receipt tries of recently proven blocks, so that proving several events of the same block, e.g. by a relayer,
+/// fetches the block and builds its trie once. Blocks are keyed by hash, so a reorged block is never served. The same
+/// cache can be shared by several [`crate::ProofOptions`]
+#[derive(Debug)]
+pub struct ProofCache {
+    capacity: usize,
+    dir: Option<PathBuf>,
+    blocks: Mutex<VecDeque<Arc<BlockTrie>>>,
+}
+
+impl Default for ProofCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHED_BLOCKS)
+    }
+}
+
+impl ProofCache {
+    /// Keeps up to `capacity` blocks in memory, evicting the least recently used one
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            dir: None,
+            blocks: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Also writes the blocks to `dir`, one file per block, so that they outlive the process. The files aren't
+    /// evicted
+    pub fn with_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.dir = Some(dir.into());
+        self
+    }
+
+    pub(crate) fn get(&self, block_hash: H256) -> Option<Arc<BlockTrie>> {
+        let mut blocks = self.blocks.lock().unwrap();
+        if let Some(position) = blocks
+            .iter()
+            .position(|block| block.block_hash() == block_hash)
+        {
+            let block = blocks.remove(position)?;
+            blocks.push_back(block.clone());
+            return Some(block);
+        }
+        drop(blocks);
+
+        let block = self.read(block_hash)?;
+        Some(self.remember(block))
+    }
+
+    /// Caches the block, unless its header doesn't hash to `block_hash`, e.g. when it was reorged between the
+    /// requests of the receipt and of the header
+    pub(crate) fn insert(&self, block_hash: H256, block: BlockTrie) -> Arc<BlockTrie> {
+        if block.block_hash() != block_hash {
+            return Arc::new(block);
+        }
+
+        self.write(&block);
+        self.remember(block)
+    }
+
+    fn remember(&self, block: BlockTrie) -> Arc<BlockTrie> {
+        let block = Arc::new(block);
+        let mut blocks = self.blocks.lock().unwrap();
+        blocks.push_back(block.clone());
+        while blocks.len() > self.capacity {
+            blocks.pop_front();
+        }
+
+        block
+    }
+
+    fn path(&self, block_hash: H256) -> Option<PathBuf> {
+        Some(self.dir.as_ref()?.join(format!("{block_hash:#x}.json")))
+    }
+
+    /// A missing or unreadable file is a cache miss, the block is fetched again
+    fn read(&self, block_hash: H256) -> Option<BlockTrie> {
+        let data = fs::read_to_string(self.path(block_hash)?).ok()?;
+        let file: BlockTrieFile = serde_json::from_str(&data).ok()?;
+        let receipts = file
+            .receipts
+            .into_iter()
+            .map(|(index, receipt)| Some((index, hex::decode(receipt).ok()?)))
+            .collect::<Option<_>>()?;
+        let block = BlockTrie::new(hex::decode(file.header_data).ok()?, receipts).ok()?;
+
+        (block.block_hash() == block_hash).then_some(block)
+    }
+
+    /// Failing to write the file only loses the on-disk copy
+    fn write(&self, block: &BlockTrie) {
+        let Some(path) = self.path(block.block_hash()) else {
+            return;
+        };
+
+        let file = BlockTrieFile {
+            header_data: hex::encode(&block.header_data),
+            receipts: block
+                .receipts
+                .iter()
+                .map(|(index, receipt)| (*index, hex::encode(receipt)))
+                .collect(),
+        };
+        let data = serde_json::to_string(&file).expect("Block trie always serializes");
+
+        let tmp_path = path.with_extension("tmp");
+        let result = fs::create_dir_all(path.parent().unwrap_or(&path))
+            .and_then(|_| fs::write(&tmp_path, data))
+            .and_then(|_| fs::rename(&tmp_path, &path));
+        if let Err(err) = result {
+            tracing::warn!("Could not write cached block {}: {err}", path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(header: u8) -> BlockTrie {
+        BlockTrie::new(vec![header; 4], vec![(0, vec![1, 2]), (1, vec![3, 4, 5])]).unwrap()
+    }
+
+    #[test]
+    fn test_proof_cache() {
+        let dir = std::env::temp_dir().join(format!("proof-cache-{}", std::process::id()));
+        let cache = ProofCache::new(1).with_dir(&dir);
+        let (first, second) = (block(1), block(2));
+        let (first_hash, second_hash) = (first.block_hash(), second.block_hash());
+        let first_proof = first.proof(1).unwrap();
+
+        // Not cached under a hash its header doesn't match
+        cache.insert(H256::zero(), block(3));
+        assert!(cache.get(block(3).block_hash()).is_none());
+
+        cache.insert(first_hash, first);
+        cache.insert(second_hash, second);
+        assert_eq!(cache.blocks.lock().unwrap().len(), 1);
+
+        // Evicted from memory, read back from disk
+        let cached = cache.get(first_hash).unwrap();
+        assert_eq!(cached.proof(1).unwrap(), first_proof);
+        assert_eq!(cached.trie_size, 5);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
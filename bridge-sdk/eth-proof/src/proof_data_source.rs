@@ -0,0 +1,96 @@
+use crate::{
+    error::EthProofError,
+    eth_rpc::EthRpc,
+    eth_rpc_client::types::{BlockHeader, TransactionReceipt},
+    fixture::ProofFixture,
+};
+use async_trait::async_trait;
+use ethereum_types::{H256, U64};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Source of the Ethereum data a proof is built from. Besides the RPC client, it can be implemented on top of a local
+/// node database (e.g. reth or erigon) to generate proofs without the RPC round trips, and given to the connectors as
+/// their `proof_data_source`
+#[async_trait]
+pub trait ProofDataSource: Send + Sync {
+    async fn get_transaction_receipt(
+        &self,
+        tx_hash: H256,
+    ) -> Result<TransactionReceipt, EthProofError>;
+
+    async fn get_block_header(&self, block_number: U64) -> Result<BlockHeader, EthProofError>;
+
+    async fn get_block_receipts(
+        &self,
+        block_number: U64,
+    ) -> Result<Vec<TransactionReceipt>, EthProofError>;
+}
+
+/// Proofs are generated from the RPC of the [`EthRPCClient`](crate::EthRPCClient), or of a mock
+#[async_trait]
+impl<T: EthRpc + ?Sized> ProofDataSource for T {
+    async fn get_transaction_receipt(
+        &self,
+        tx_hash: H256,
+    ) -> Result<TransactionReceipt, EthProofError> {
+        Ok(self.get_transaction_receipt_by_hash(&tx_hash).await?)
+    }
+
+    async fn get_block_header(&self, block_number: U64) -> Result<BlockHeader, EthProofError> {
+        Ok(self.get_block_by_number(block_number).await?)
+    }
+
+    async fn get_block_receipts(
+        &self,
+        block_number: U64,
+    ) -> Result<Vec<TransactionReceipt>, EthProofError> {
+        Ok(EthRpc::get_block_receipts(self, block_number).await?)
+    }
+}
+
+/// Serves the recorded responses. Requesting data that isn't part of the fixture is an error
+#[async_trait]
+impl ProofDataSource for ProofFixture {
+    async fn get_transaction_receipt(
+        &self,
+        tx_hash: H256,
+    ) -> Result<TransactionReceipt, EthProofError> {
+        if tx_hash != self.tx_hash {
+            return Err(EthProofError::FixtureError(format!(
+                "Transaction {tx_hash:#x} is not recorded"
+            )));
+        }
+
+        parse(&self.receipt)
+    }
+
+    async fn get_block_header(&self, block_number: U64) -> Result<BlockHeader, EthProofError> {
+        ensure_recorded_block(&self.receipt, block_number)?;
+        parse(&self.block_header)
+    }
+
+    async fn get_block_receipts(
+        &self,
+        block_number: U64,
+    ) -> Result<Vec<TransactionReceipt>, EthProofError> {
+        ensure_recorded_block(&self.receipt, block_number)?;
+        parse(&self.block_receipts)
+    }
+}
+
+fn ensure_recorded_block(receipt: &Value, block_number: U64) -> Result<(), EthProofError> {
+    let receipt: TransactionReceipt = parse(receipt)?;
+
+    if receipt.block_number != block_number {
+        return Err(EthProofError::FixtureError(format!(
+            "Block {block_number} is not recorded"
+        )));
+    }
+
+    Ok(())
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(value: &Value) -> Result<T, EthProofError> {
+    T::deserialize(value).map_err(|err| EthProofError::EthClientError(err.into()))
+}
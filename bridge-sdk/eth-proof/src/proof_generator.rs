@@ -1,19 +1,26 @@
 use crate::{
     error::EthProofError,
+    eth_rpc::EthRpc,
     eth_rpc_client::{
-        types::{BlockHeader, Log, TransactionReceipt, U8},
+        types::{BlockHeader, Log, TransactionReceipt},
         EthRPCClient,
     },
+    fixture::ProofFixture,
+    metrics::{self, ProofMetrics},
+    proof_cache::{BlockTrie, ProofCache},
+    proof_data_source::ProofDataSource,
 };
-use borsh::BorshSerialize;
-use cita_trie::{MemoryDB, PatriciaTrie, Trie};
+use borsh::{BorshDeserialize, BorshSerialize};
 use ethereum_types::H256;
-use hasher::HasherKeccak;
 use rlp::RlpStream;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc, time::Instant};
 
-#[derive(Debug, BorshSerialize, Serialize, Deserialize)]
+const LEGACY_TRANSACTION_TYPE: u8 = 0;
+/// Typed transactions start with a byte below the first byte of an RLP list (EIP-2718)
+const MAX_TRANSACTION_TYPE: u8 = 0x7f;
+
+#[derive(Debug, BorshSerialize, BorshDeserialize, Serialize, Deserialize)]
 pub struct Proof {
     pub log_index: u64,
     pub log_entry_data: Vec<u8>,
@@ -23,22 +30,87 @@ pub struct Proof {
     pub proof: Vec<Vec<u8>>,
 }
 
+/// Options of the proof generation. The defaults neither cache the blocks nor record any metrics
+#[derive(Debug, Clone, Default)]
+pub struct ProofOptions {
+    /// Blocks of the previously generated proofs, reused instead of fetching them again
+    pub cache: Option<Arc<ProofCache>>,
+    /// Appends the [`ProofMetrics`] of each generated proof to this file as JSON lines
+    pub metrics_file: Option<PathBuf>,
+}
+
 pub async fn get_proof_for_event(
     tx_hash: H256,
     log_index: u64,
     node_url: &str,
 ) -> Result<Proof, EthProofError> {
-    let client = EthRPCClient::new(node_url);
+    get_proof_for_event_from_source(&EthRPCClient::new(node_url), tx_hash, log_index).await
+}
 
-    let receipt = client.get_transaction_receipt_by_hash(&tx_hash).await?;
-    let block_header = client.get_block_by_number(receipt.block_number).await?;
-    let block_receipts = client.get_block_receipts(receipt.block_number).await?;
+/// Generates the proof from previously recorded RPC responses, without querying an Ethereum node
+pub async fn get_proof_from_fixture(fixture: &ProofFixture) -> Result<Proof, EthProofError> {
+    get_proof_for_event_from_source(fixture, fixture.tx_hash, fixture.log_index).await
+}
 
-    let mut trie = build_receipt_trie(&block_receipts)?;
-    trie.root()?;
+pub async fn get_proof_for_event_from_source(
+    source: &(impl ProofDataSource + ?Sized),
+    tx_hash: H256,
+    log_index: u64,
+) -> Result<Proof, EthProofError> {
+    get_proof_for_event_with_options(source, tx_hash, log_index, &ProofOptions::default()).await
+}
 
-    let receipt_key = rlp::encode(&receipt.transaction_index);
-    let proof = trie.get_proof(&receipt_key)?;
+/// Same as [`get_proof_for_event_with_options`], reading the blocks from `data_source` if it's set, e.g. a local node
+/// database, and from `eth_rpc` otherwise
+pub async fn get_proof_for_event_from_rpc_or_source(
+    eth_rpc: &dyn EthRpc,
+    data_source: Option<&dyn ProofDataSource>,
+    tx_hash: H256,
+    log_index: u64,
+    options: &ProofOptions,
+) -> Result<Proof, EthProofError> {
+    match data_source {
+        Some(data_source) => {
+            get_proof_for_event_with_options(data_source, tx_hash, log_index, options).await
+        }
+        None => get_proof_for_event_with_options(eth_rpc, tx_hash, log_index, options).await,
+    }
+}
+
+pub async fn get_proof_for_event_with_options(
+    source: &(impl ProofDataSource + ?Sized),
+    tx_hash: H256,
+    log_index: u64,
+    options: &ProofOptions,
+) -> Result<Proof, EthProofError> {
+    let fetch_started_at = Instant::now();
+    let receipt = source.get_transaction_receipt(tx_hash).await?;
+    let cache = options.cache.as_ref();
+    let cached_block = cache
+        .zip(receipt.block_hash)
+        .and_then(|(cache, block_hash)| cache.get(block_hash));
+
+    let (block, fetch_duration, build_started_at) = match cached_block {
+        Some(block) => (block, fetch_started_at.elapsed(), Instant::now()),
+        None => {
+            let (block_header, block_receipts) = tokio::try_join!(
+                source.get_block_header(receipt.block_number),
+                source.get_block_receipts(receipt.block_number),
+            )?;
+            let fetch_duration = fetch_started_at.elapsed();
+
+            let build_started_at = Instant::now();
+            let block = build_block_trie(&block_header, &block_receipts)?;
+            let block = match cache.zip(receipt.block_hash) {
+                Some((cache, block_hash)) => cache.insert(block_hash, block),
+                None => Arc::new(block),
+            };
+
+            (block, fetch_duration, build_started_at)
+        }
+    };
+
+    let proof = block.proof(receipt.transaction_index.as_u64())?;
 
     let mut log_data: Option<Vec<u8>> = None;
     let mut log_index_in_receipt = 0;
@@ -49,42 +121,60 @@ pub async fn get_proof_for_event(
         }
     }
 
-    Ok(Proof {
+    let proof = Proof {
         log_index: log_index_in_receipt as u64,
         log_entry_data: log_data.ok_or(EthProofError::Other(
             "Log not found based on the transaction hash and index provided".to_string(),
         ))?,
         receipt_index: receipt.transaction_index.as_u64(),
-        receipt_data: encode_receipt(&receipt),
-        header_data: encode_header(&block_header),
+        receipt_data: encode_receipt(&receipt)?,
+        header_data: block.header_data.clone(),
         proof,
-    })
+    };
+
+    if let Some(metrics_file) = &options.metrics_file {
+        metrics::record(
+            metrics_file,
+            &ProofMetrics {
+                tx_hash,
+                block_number: receipt.block_number.as_u64(),
+                generated_at: metrics::unix_timestamp(),
+                fetch_duration_ms: metrics::millis(fetch_duration),
+                build_duration_ms: metrics::millis(build_started_at.elapsed()),
+                block_receipts: block.receipts.len(),
+                trie_size: block.trie_size,
+                proof_nodes: proof.proof.len(),
+            },
+        );
+    }
+
+    Ok(proof)
 }
 
-fn build_receipt_trie(
+fn build_block_trie(
+    header: &BlockHeader,
     receipts: &[TransactionReceipt],
-) -> Result<PatriciaTrie<MemoryDB, HasherKeccak>, EthProofError> {
-    let memdb = Arc::new(MemoryDB::new(true));
-    let hasher = Arc::new(HasherKeccak::new());
-    let mut trie = PatriciaTrie::new(memdb, hasher);
+) -> Result<BlockTrie, EthProofError> {
+    let receipts = receipts
+        .iter()
+        .map(|receipt| Ok((receipt.transaction_index.as_u64(), encode_receipt(receipt)?)))
+        .collect::<Result<_, EthProofError>>()?;
 
-    for receipt in receipts {
-        let receipt_key = rlp::encode(&receipt.transaction_index).to_vec();
-        let receipt_data = encode_receipt(receipt);
-
-        trie.insert(receipt_key, receipt_data)?;
-    }
-
-    Ok(trie)
+    BlockTrie::new(encode_header(header), receipts)
 }
 
-fn encode_receipt(receipt: &TransactionReceipt) -> Vec<u8> {
-    let mut stream = RlpStream::new();
-
-    if receipt.transaction_type != U8(0) {
-        stream.append(&receipt.transaction_type);
+/// Legacy receipts are the RLP list alone. Receipts of typed transactions (EIP-2718), which are all the types since
+/// Berlin including blob and set code transactions, are the type byte followed by the same RLP list
+fn encode_receipt(receipt: &TransactionReceipt) -> Result<Vec<u8>, EthProofError> {
+    let transaction_type = receipt.transaction_type.0;
+    if transaction_type > MAX_TRANSACTION_TYPE {
+        return Err(EthProofError::Other(format!(
+            "Invalid transaction type {transaction_type:#x} of receipt {}",
+            receipt.transaction_index
+        )));
     }
 
+    let mut stream = RlpStream::new();
     stream.begin_list(4);
     stream
         .append(&receipt.status)
@@ -104,7 +194,13 @@ fn encode_receipt(receipt: &TransactionReceipt) -> Vec<u8> {
         stream.append(&log.data);
     }
 
-    stream.out().to_vec()
+    let mut receipt_data = Vec::new();
+    if transaction_type != LEGACY_TRANSACTION_TYPE {
+        receipt_data.push(transaction_type);
+    }
+    receipt_data.extend_from_slice(&stream.out());
+
+    Ok(receipt_data)
 }
 
 fn encode_log(log: &Log) -> Vec<u8> {
@@ -152,6 +248,7 @@ fn encode_header(header: &BlockHeader) -> Vec<u8> {
         .parent_beacon_block_root
         .as_ref()
         .map(|v| stream.append(v));
+    header.requests_hash.as_ref().map(|v| stream.append(v));
 
     stream.finalize_unbounded_list();
     stream.out().to_vec()
@@ -160,7 +257,8 @@ fn encode_header(header: &BlockHeader) -> Vec<u8> {
 #[cfg(test)]
 pub mod tests {
     use super::*;
-    use hasher::Hasher;
+    use crate::eth_rpc_client::types::{Bytes, U8};
+    use hasher::{Hasher, HasherKeccak};
     use serde_json::Value;
     use std::path::PathBuf;
     use std::{fs, str::FromStr};
@@ -206,12 +304,134 @@ pub mod tests {
         verify_proof(proof, "post_dencun_proof.json");
     }
 
-    fn read_proof_data(file_name: &str) -> (u64, u64, String, String, String, Vec<String>) {
+    /// The fixture is a post-Dencun block of 24 receipts of every transaction type, built offline since it's replayed
+    /// without a node. Its hash and receipts root were computed independently of the SDK
+    #[tokio::test]
+    async fn replay_proof_from_fixture() {
+        let fixture = ProofFixture::load(test_data_path("synthetic_block_fixture.json")).unwrap();
+        let proof = get_proof_from_fixture(&fixture).await.unwrap();
+
+        assert_eq!(
+            proof.block_hash(),
+            H256::from_str(fixture.block_header["hash"].as_str().unwrap()).unwrap()
+        );
+        assert_eq!(proof.receipt_index, 17);
+        assert_eq!(proof.log_index, 1);
+        crate::verify_proof(&proof).unwrap();
+    }
+
+    #[tokio::test]
+    async fn data_source_replaces_the_rpc() {
+        let fixture = ProofFixture::load(test_data_path("synthetic_block_fixture.json")).unwrap();
+        let options = ProofOptions::default();
+        let eth_rpc = crate::MockEthRpc::new(0);
+
+        let proof = get_proof_for_event_from_rpc_or_source(
+            &eth_rpc,
+            Some(&fixture),
+            fixture.tx_hash,
+            fixture.log_index,
+            &options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(proof.receipt_index, 17);
+
+        // The mock has no receipts
+        assert!(get_proof_for_event_from_rpc_or_source(
+            &eth_rpc,
+            None,
+            fixture.tx_hash,
+            fixture.log_index,
+            &options,
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn record_metrics_to_options_file() {
+        let fixture = ProofFixture::load(test_data_path("synthetic_block_fixture.json")).unwrap();
+        let metrics_file =
+            std::env::temp_dir().join(format!("proof-metrics-{}.jsonl", std::process::id()));
+        let options = ProofOptions {
+            metrics_file: Some(metrics_file.clone()),
+            ..Default::default()
+        };
+
+        get_proof_for_event_with_options(&fixture, fixture.tx_hash, fixture.log_index, &options)
+            .await
+            .unwrap();
+        // Proofs generated without the option aren't recorded
+        get_proof_from_fixture(&fixture).await.unwrap();
+
+        let metrics = crate::metrics::load_metrics(&metrics_file).unwrap();
+        assert_eq!(metrics.len(), 1);
+        assert_eq!(metrics[0].tx_hash, fixture.tx_hash);
+        assert_eq!(metrics[0].block_receipts, 24);
+
+        fs::remove_file(&metrics_file).unwrap();
+    }
+
+    #[test]
+    fn encode_typed_receipts() {
+        let receipt = |transaction_type| TransactionReceipt {
+            block_number: 1.into(),
+            block_hash: None,
+            transaction_index: 0.into(),
+            transaction_type: U8(transaction_type),
+            cumulative_gas_used: Bytes(vec![0x52, 0x08]),
+            logs_bloom: Default::default(),
+            logs: Vec::new(),
+            status: U8(1),
+        };
+        let legacy = encode_receipt(&receipt(0)).unwrap();
+        assert!(rlp::Rlp::new(&legacy).is_list());
+
+        // Blob and set code receipts only differ from the legacy one by their type prefix
+        for transaction_type in [1, 2, 3, 4] {
+            let typed = encode_receipt(&receipt(transaction_type)).unwrap();
+            assert_eq!(typed[0], transaction_type);
+            assert_eq!(typed[1..], legacy);
+        }
+
+        assert!(encode_receipt(&receipt(0x80)).is_err());
+    }
+
+    /// The header is block 0x315 of a Prague devnet, with the requests hash of EIP-7685. Its only transaction is a
+    /// blob transaction, whose receipt follows from the header: 21000 gas used and no logs
+    #[test]
+    fn build_prague_block_trie() {
+        let data = fs::read_to_string(test_data_path("prague_block.json")).unwrap();
+        let block: Value = serde_json::from_str(&data).unwrap();
+        let header = BlockHeader::deserialize(&block["block_header"]).unwrap();
+        let receipts = Vec::<TransactionReceipt>::deserialize(&block["block_receipts"]).unwrap();
+        assert!(header.requests_hash.is_some());
+        assert_eq!(receipts[0].transaction_type, U8(3));
+
+        let block_trie = build_block_trie(&header, &receipts).unwrap();
+        assert_eq!(
+            block_trie.block_hash(),
+            H256::from_str(block["block_header"]["hash"].as_str().unwrap()).unwrap()
+        );
+
+        // The trie of a single receipt is its leaf, so the root is the hash of the first proof node
+        let proof = block_trie.proof(0).unwrap();
+        assert_eq!(
+            H256::from_slice(&HasherKeccak::new().digest(&proof[0])),
+            header.receipts_root
+        );
+    }
+
+    fn test_data_path(file_name: &str) -> PathBuf {
         let mut data_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
         data_dir.push("src/test_data");
         data_dir.push(file_name);
+        data_dir
+    }
 
-        let data = fs::read_to_string(data_dir).unwrap();
+    fn read_proof_data(file_name: &str) -> (u64, u64, String, String, String, Vec<String>) {
+        let data = fs::read_to_string(test_data_path(file_name)).unwrap();
         let obj: Value = serde_json::from_str(&data).unwrap();
 
         let expected_log_index = obj["log_index"].as_u64().unwrap();
@@ -0,0 +1,93 @@
+use crate::{error::EthProofError, proof_generator::Proof};
+use cita_trie::{MemoryDB, PatriciaTrie, Trie};
+use ethereum_types::H256;
+use hasher::{Hasher, HasherKeccak};
+use rlp::{DecoderError, Rlp};
+use std::sync::Arc;
+
+/// Index of `receiptsRoot` in the RLP encoded block header
+const RECEIPTS_ROOT_INDEX: usize = 5;
+/// Index of `number` in the RLP encoded block header
+const BLOCK_NUMBER_INDEX: usize = 8;
+/// Index of the logs in the RLP encoded receipt
+const RECEIPT_LOGS_INDEX: usize = 3;
+
+impl Proof {
+    /// Hash of the block the proof was generated for. It has to match a block known to the light client for the
+    /// proof to be accepted
+    pub fn block_hash(&self) -> H256 {
+        H256::from_slice(&HasherKeccak::new().digest(&self.header_data))
+    }
+
+    /// Number of the block the proof was generated for
+    pub fn block_number(&self) -> Result<u64, EthProofError> {
+        Rlp::new(&self.header_data)
+            .val_at(BLOCK_NUMBER_INDEX)
+            .map_err(decoder_error)
+    }
+}
+
+/// Checks that the receipt is included in the receipts trie of the block header and that the log was emitted by the
+/// receipt. The header itself isn't checked, so `Proof::block_hash` has to be compared with a trusted block hash
+pub fn verify_proof(proof: &Proof) -> Result<(), EthProofError> {
+    let receipts_root: H256 = Rlp::new(&proof.header_data)
+        .val_at(RECEIPTS_ROOT_INDEX)
+        .map_err(decoder_error)?;
+
+    let trie = PatriciaTrie::new(Arc::new(MemoryDB::new(true)), Arc::new(HasherKeccak::new()));
+    let receipt_key = rlp::encode(&proof.receipt_index);
+    let receipt = trie
+        .verify_proof(receipts_root.as_bytes(), &receipt_key, proof.proof.clone())?
+        .ok_or(EthProofError::Other(
+            "The receipt is not included in the block receipts".to_string(),
+        ))?;
+
+    if receipt != proof.receipt_data {
+        return Err(EthProofError::Other(
+            "The proven receipt doesn't match the receipt data".to_string(),
+        ));
+    }
+
+    // Typed receipts are prefixed with their type, which is below the RLP list prefixes
+    let receipt_rlp = match proof.receipt_data.first() {
+        Some(&prefix) if prefix < 0xc0 => &proof.receipt_data[1..],
+        _ => &proof.receipt_data[..],
+    };
+    let log = Rlp::new(receipt_rlp)
+        .at(RECEIPT_LOGS_INDEX)
+        .and_then(|logs| logs.at(proof.log_index as usize))
+        .map_err(decoder_error)?;
+
+    if log.as_raw() != proof.log_entry_data {
+        return Err(EthProofError::Other(
+            "The log entry doesn't match the log of the receipt".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn decoder_error(err: DecoderError) -> EthProofError {
+    EthProofError::Other(format!("Invalid RLP in the proof: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_proof_for_event;
+    use std::str::FromStr;
+
+    const RPC_URL: &str = "https://eth.llamarpc.com";
+
+    #[tokio::test]
+    async fn verifies_generated_proof() {
+        let tx_hash =
+            H256::from_str("0x42639810a1238a76ca947b848f5b88a854ac36471d1c4f6a15631393790f89af")
+                .unwrap();
+        let mut proof = get_proof_for_event(tx_hash, 360, RPC_URL).await.unwrap();
+        verify_proof(&proof).unwrap();
+
+        proof.log_entry_data[0] ^= 1;
+        assert!(verify_proof(&proof).is_err());
+    }
+}
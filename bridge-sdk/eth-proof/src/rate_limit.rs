@@ -0,0 +1,109 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Requests allowed to an Ethereum RPC provider, e.g. to stay under the limit of an API key during proof generation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// Sustained rate of requests
+    pub requests_per_second: f64,
+    /// Requests sent at once after a pause, before the sustained rate applies
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// Rate with a burst of one second of requests
+    pub fn per_second(requests_per_second: f64) -> Self {
+        Self {
+            requests_per_second,
+            burst: requests_per_second.ceil().max(1.0) as u32,
+        }
+    }
+}
+
+/// Token bucket shared by the clients it's passed to, so that their requests are limited together
+#[derive(Debug)]
+pub struct RateLimiter {
+    rate_limit: RateLimit,
+    bucket: Mutex<Bucket>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(rate_limit: RateLimit) -> Self {
+        Self {
+            rate_limit,
+            bucket: Mutex::new(Bucket {
+                tokens: f64::from(rate_limit.burst),
+                updated_at: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn rate_limit(&self) -> RateLimit {
+        self.rate_limit
+    }
+
+    /// Waits until a request can be sent
+    pub async fn acquire(&self) {
+        while let Err(wait) = self.try_acquire(Instant::now()) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Takes a token, or returns how long until one is available
+    fn try_acquire(&self, now: Instant) -> Result<(), Duration> {
+        let rate = self.rate_limit.requests_per_second;
+        let burst = f64::from(self.rate_limit.burst.max(1));
+        let mut bucket = self.bucket.lock().unwrap();
+
+        let elapsed = now.saturating_duration_since(bucket.updated_at);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * rate).min(burst);
+        bucket.updated_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+
+        match rate > 0.0 {
+            true => Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate)),
+            // A zero rate never refills the bucket, it's treated as unlimited rather than blocking forever
+            false => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket() {
+        let limiter = RateLimiter::new(RateLimit {
+            requests_per_second: 10.0,
+            burst: 2,
+        });
+        let start = limiter.bucket.lock().unwrap().updated_at;
+
+        assert_eq!(limiter.try_acquire(start), Ok(()));
+        assert_eq!(limiter.try_acquire(start), Ok(()));
+        assert_eq!(limiter.try_acquire(start), Err(Duration::from_millis(100)));
+
+        assert_eq!(
+            limiter.try_acquire(start + Duration::from_millis(100)),
+            Ok(())
+        );
+        // The bucket doesn't fill up beyond the burst after a pause
+        let later = start + Duration::from_secs(60);
+        assert_eq!(limiter.try_acquire(later), Ok(()));
+        assert_eq!(limiter.try_acquire(later), Ok(()));
+        assert!(limiter.try_acquire(later).is_err());
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{
+    error::EthProofError,
+    eth_rpc_client::{
+        types::{BlockId, StorageProof},
+        EthRPCClient,
+    },
+};
+use ethereum_types::{H160, H256};
+
+/// Fetches the proof of the account at `address` and its storage `keys` at `block`. Proofs of past blocks, e.g. to
+/// regenerate an unlock proof long after the transfer, need an archive node
+pub async fn get_storage_proof(
+    address: H160,
+    keys: &[H256],
+    block: BlockId,
+    node_url: &str,
+) -> Result<StorageProof, EthProofError> {
+    Ok(EthRPCClient::new(node_url)
+        .get_proof(&address, keys, block)
+        .await?)
+}
+
+/// Fetches the proof of the account at `address` at `block`: its nonce, balance, code hash and storage root, without
+/// any storage slot
+pub async fn get_account_proof(
+    address: H160,
+    block: BlockId,
+    node_url: &str,
+) -> Result<StorageProof, EthProofError> {
+    get_storage_proof(address, &[], block, node_url).await
+}
@@ -1,5 +1,9 @@
 mod error;
+#[cfg(any(test, feature = "mock"))]
+mod mock;
 mod near_light_client_on_eth;
 
 pub use error::NearLightClientOnEthError;
-pub use near_light_client_on_eth::NearOnEthClient;
+#[cfg(any(test, feature = "mock"))]
+pub use mock::MockNearOnEthClient;
+pub use near_light_client_on_eth::{NearLightClientOnEth, NearOnEthClient};
@@ -0,0 +1,101 @@
+use crate::{error::NearLightClientOnEthError, NearLightClientOnEth};
+use async_trait::async_trait;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+
+/// In-memory Near light client on Ethereum with scripted heights and block hashes, to simulate a light client that is
+/// behind or has synced a block in tests
+#[derive(Debug, Default)]
+pub struct MockNearOnEthClient {
+    state: Mutex<MockState>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    sync_height: u64,
+    next_heights: VecDeque<u64>,
+    block_hashes: HashMap<u64, [u8; 32]>,
+}
+
+impl MockNearOnEthClient {
+    pub fn new(sync_height: u64) -> Self {
+        Self::with_heights([sync_height])
+    }
+
+    /// Returns the heights one per `get_sync_height` call and keeps the last one, e.g. to simulate a light client that
+    /// catches up while it is polled
+    pub fn with_heights(heights: impl IntoIterator<Item = u64>) -> Self {
+        let next_heights: VecDeque<u64> = heights.into_iter().collect();
+
+        Self {
+            state: Mutex::new(MockState {
+                sync_height: next_heights.front().copied().unwrap_or_default(),
+                next_heights,
+                block_hashes: HashMap::new(),
+            }),
+        }
+    }
+
+    pub fn set_sync_height(&self, sync_height: u64) {
+        let mut state = self.state();
+        state.next_heights.clear();
+        state.sync_height = sync_height;
+    }
+
+    pub fn set_block_hash(&self, block_number: u64, block_hash: [u8; 32]) {
+        self.state().block_hashes.insert(block_number, block_hash);
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state
+            .lock()
+            .expect("Mock light client lock is poisoned")
+    }
+}
+
+#[async_trait]
+impl NearLightClientOnEth for MockNearOnEthClient {
+    async fn get_sync_height(&self) -> Result<u64, NearLightClientOnEthError> {
+        let mut state = self.state();
+        if let Some(height) = state.next_heights.pop_front() {
+            state.sync_height = height;
+        }
+
+        Ok(state.sync_height)
+    }
+
+    /// Unknown blocks have a zero hash, like in the light client contract
+    async fn get_block_hash(
+        &self,
+        block_number: u64,
+    ) -> Result<[u8; 32], NearLightClientOnEthError> {
+        Ok(self
+            .state()
+            .block_hashes
+            .get(&block_number)
+            .copied()
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn scripted_heights_are_returned_in_order() {
+        let client = MockNearOnEthClient::with_heights([10, 20]);
+        client.set_block_hash(20, [1; 32]);
+
+        assert_eq!(client.get_sync_height().await.unwrap(), 10);
+        assert_eq!(client.get_sync_height().await.unwrap(), 20);
+        assert_eq!(client.get_sync_height().await.unwrap(), 20);
+        assert_eq!(client.get_block_hash(20).await.unwrap(), [1; 32]);
+        assert_eq!(client.get_block_hash(21).await.unwrap(), [0; 32]);
+
+        client.set_sync_height(30);
+        assert_eq!(client.get_sync_height().await.unwrap(), 30);
+    }
+}
@@ -1,9 +1,10 @@
+use async_trait::async_trait;
 use ethereum_types::Address;
 use ethers::{
     contract::abigen,
     providers::{Http, Provider},
 };
-use std::sync::Arc;
+use std::{future::Future, sync::Arc};
 
 use crate::error::NearLightClientOnEthError;
 
@@ -15,50 +16,99 @@ abigen!(
     ]"#
 );
 
+/// Near light client on Ethereum as read by the SDK. Implemented by [`NearOnEthClient`], and by
+/// `MockNearOnEthClient` with the `mock` feature
+#[async_trait]
+pub trait NearLightClientOnEth: Send + Sync {
+    /// Height of the last Near block known to the light client
+    async fn get_sync_height(&self) -> Result<u64, NearLightClientOnEthError>;
+
+    async fn get_block_hash(
+        &self,
+        block_number: u64,
+    ) -> Result<[u8; 32], NearLightClientOnEthError>;
+}
+
 pub struct NearOnEthClient {
-    eth_endpoint: String,
+    eth_endpoints: Vec<String>,
     near_on_eth_client_address: Address,
 }
 
 impl NearOnEthClient {
+    /// `eth_rpc_endpoint` can be a comma separated list of endpoints, which are tried in turn until one succeeds
     pub fn new(near_one_eth_client_address: Address, eth_rpc_endpoint: String) -> Self {
         Self {
-            eth_endpoint: eth_rpc_endpoint,
+            eth_endpoints: eth_rpc_endpoint
+                .split(',')
+                .map(str::trim)
+                .filter(|endpoint| !endpoint.is_empty())
+                .map(str::to_string)
+                .collect(),
             near_on_eth_client_address: near_one_eth_client_address,
         }
     }
 
     pub async fn get_sync_height(&self) -> Result<u64, NearLightClientOnEthError> {
-        let eth_provider = self.eth_provider()?;
-        let client = Arc::new(eth_provider);
-        let contract = NearLightClient::new(self.near_on_eth_client_address, client);
+        self.call(|contract| async move {
+            let state = contract.bridge_state().call().await?;
 
-        let state = contract.bridge_state().call().await?;
-
-        Ok(state.0.as_u64())
+            Ok(state.0.as_u64())
+        })
+        .await
     }
 
     pub async fn get_block_hash(
         &self,
         block_number: u64,
     ) -> Result<[u8; 32], NearLightClientOnEthError> {
-        let eth_provider = self.eth_provider()?;
-        let client = Arc::new(eth_provider);
-        let contract = NearLightClient::new(self.near_on_eth_client_address, client);
-
-        let state = contract.block_hashes(block_number).call().await?;
+        self.call(|contract| async move {
+            let state = contract.block_hashes(block_number).call().await?;
 
-        Ok(state)
+            Ok(state)
+        })
+        .await
     }
 
-    fn eth_provider(&self) -> Result<Provider<Http>, NearLightClientOnEthError> {
-        Ok(
-            Provider::<Http>::try_from(self.eth_endpoint.clone()).map_err(|_| {
+    /// Runs `call` against each endpoint in turn until it succeeds. Fails with the error of the last endpoint
+    async fn call<T, F, Fut>(&self, call: F) -> Result<T, NearLightClientOnEthError>
+    where
+        F: Fn(NearLightClient<Provider<Http>>) -> Fut,
+        Fut: Future<Output = Result<T, NearLightClientOnEthError>>,
+    {
+        let mut result = Err(NearLightClientOnEthError::ConfigError(
+            "Ethereum endpoint is not set".to_string(),
+        ));
+
+        for eth_endpoint in &self.eth_endpoints {
+            let eth_provider = Provider::<Http>::try_from(eth_endpoint.as_str()).map_err(|_| {
                 NearLightClientOnEthError::ConfigError(
                     "Ethereum endpoint url is invalid".to_string(),
                 )
-            })?,
-        )
+            })?;
+            let contract =
+                NearLightClient::new(self.near_on_eth_client_address, Arc::new(eth_provider));
+
+            result = call(contract).await;
+            if result.is_ok() {
+                break;
+            }
+        }
+
+        result
+    }
+}
+
+#[async_trait]
+impl NearLightClientOnEth for NearOnEthClient {
+    async fn get_sync_height(&self) -> Result<u64, NearLightClientOnEthError> {
+        NearOnEthClient::get_sync_height(self).await
+    }
+
+    async fn get_block_hash(
+        &self,
+        block_number: u64,
+    ) -> Result<[u8; 32], NearLightClientOnEthError> {
+        NearOnEthClient::get_block_hash(self, block_number).await
     }
 }
 
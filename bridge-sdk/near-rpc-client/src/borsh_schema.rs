@@ -0,0 +1,65 @@
+//! Borsh schemas of the `near_primitives` types embedded in the bridge encodings. `near_primitives` doesn't implement
+//! `BorshSchema`, so the types below mirror their borsh layout and are referenced with
+//! `#[borsh(schema(with_funcs(declaration = "...", definitions = "...")))]`
+
+use borsh::{
+    schema::{Declaration, Definition},
+    BorshSchema,
+};
+use std::collections::BTreeMap;
+
+/// Mirrors `near_primitives::hash::CryptoHash`
+#[derive(BorshSchema)]
+pub struct CryptoHash(pub [u8; 32]);
+
+/// Mirrors `near_primitives::types::AccountId`, encoded as a string
+#[derive(BorshSchema)]
+pub struct AccountId(pub String);
+
+/// Mirrors `near_primitives::merkle::Direction`
+#[derive(BorshSchema)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+/// Mirrors `near_primitives::merkle::MerklePathItem`
+#[derive(BorshSchema)]
+pub struct MerklePathItem {
+    pub hash: CryptoHash,
+    pub direction: Direction,
+}
+
+/// Mirrors `near_primitives::merkle::MerklePath`
+pub type MerklePath = Vec<MerklePathItem>;
+
+/// Mirrors `near_primitives::views::ExecutionStatusView`
+#[derive(BorshSchema)]
+pub enum ExecutionStatusView {
+    Unknown,
+    Failure(TxExecutionError),
+    SuccessValue(Vec<u8>),
+    SuccessReceiptId(CryptoHash),
+}
+
+/// `near_primitives::errors::TxExecutionError` is declared without a definition. The provers only accept successful
+/// outcomes, so a proof never carries it
+pub struct TxExecutionError;
+
+impl BorshSchema for TxExecutionError {
+    fn add_definitions_recursively(_definitions: &mut BTreeMap<Declaration, Definition>) {}
+
+    fn declaration() -> Declaration {
+        "TxExecutionError".to_string()
+    }
+}
+
+/// Declaration of the field, to be used as `with_funcs(declaration = "declaration::<Mirror>")`
+pub fn declaration<T: BorshSchema>() -> Declaration {
+    T::declaration()
+}
+
+/// Definitions of the field, to be used as `with_funcs(definitions = "add_definitions::<Mirror>")`
+pub fn add_definitions<T: BorshSchema>(definitions: &mut BTreeMap<Declaration, Definition>) {
+    T::add_definitions_recursively(definitions)
+}
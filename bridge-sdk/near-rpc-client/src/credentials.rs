@@ -0,0 +1,55 @@
+use crate::error::NearRpcError;
+use near_crypto::InMemorySigner;
+use near_primitives::types::AccountId;
+use std::path::{Path, PathBuf};
+
+/// Loads a signer from a key file written by `near login`, e.g. `~/.near-credentials/testnet/alice.testnet.json`
+pub fn signer_from_credentials_file(
+    path: impl AsRef<Path>,
+) -> Result<InMemorySigner, NearRpcError> {
+    let path = path.as_ref();
+
+    InMemorySigner::from_file(path)
+        .map_err(|err| NearRpcError::CredentialsError(format!("{}: {err}", path.display())))
+}
+
+/// Path of the key file `near login` writes for `account_id` on `network`, e.g. `mainnet` or `testnet`
+pub fn default_credentials_file(network: &str, account_id: &AccountId) -> Option<PathBuf> {
+    let home = std::env::var_os("HOME")?;
+
+    Some(
+        PathBuf::from(home)
+            .join(".near-credentials")
+            .join(network)
+            .join(format!("{account_id}.json")),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::KeyType;
+
+    #[test]
+    fn loads_near_cli_key_file() {
+        let expected =
+            InMemorySigner::from_seed("alice.testnet".parse().unwrap(), KeyType::ED25519, "alice");
+        let path = std::env::temp_dir().join("near_rpc_client_credentials_test.json");
+        std::fs::write(
+            &path,
+            serde_json::json!({
+                "account_id": "alice.testnet",
+                "public_key": expected.public_key.to_string(),
+                "private_key": expected.secret_key.to_string(),
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let signer = signer_from_credentials_file(&path).unwrap();
+        assert_eq!(signer.account_id, expected.account_id);
+        assert_eq!(signer.public_key, expected.public_key);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
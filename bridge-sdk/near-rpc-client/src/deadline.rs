@@ -0,0 +1,56 @@
+use std::future::Future;
+use tokio::time::{Duration, Instant};
+
+tokio::task_local! {
+    static DEADLINE: Instant;
+}
+
+/// Runs `future` with a deadline for the RPC requests it makes, which fail with `DeadlineExceeded` instead of
+/// running past it. The deadline of an enclosing scope is kept if it's earlier
+pub async fn scope<F: Future>(deadline: Instant, future: F) -> F::Output {
+    let deadline = current().map_or(deadline, |current| current.min(deadline));
+
+    DEADLINE.scope(deadline, future).await
+}
+
+/// Deadline of the enclosing [`scope`], if any
+pub fn current() -> Option<Instant> {
+    DEADLINE.try_with(|deadline| *deadline).ok()
+}
+
+/// Time left until the deadline of the enclosing [`scope`], if any. Zero once it has passed
+pub fn remaining() -> Option<Duration> {
+    current().map(|deadline| deadline.saturating_duration_since(Instant::now()))
+}
+
+/// Runs a single request, bounded by the deadline of the enclosing [`scope`]. Returns `None` if the deadline passed
+pub async fn run<F: Future>(request: F) -> Option<F::Output> {
+    match current() {
+        Some(deadline) => tokio::time::timeout_at(deadline, request).await.ok(),
+        None => Some(request.await),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_nested_scope_keeps_earlier_deadline() {
+        let outer = Instant::now() + Duration::from_millis(50);
+
+        assert_eq!(current(), None);
+        scope(outer, async {
+            scope(outer + Duration::from_secs(10), async {
+                assert_eq!(current(), Some(outer));
+            })
+            .await;
+
+            assert!(run(tokio::time::sleep(Duration::from_secs(5)))
+                .await
+                .is_none());
+            assert_eq!(remaining(), Some(Duration::ZERO));
+        })
+        .await;
+    }
+}
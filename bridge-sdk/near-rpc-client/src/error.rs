@@ -1,11 +1,13 @@
 use near_jsonrpc_client::{
     errors::JsonRpcError,
     methods::{
-        block::RpcBlockError, broadcast_tx_async::RpcBroadcastTxAsyncError, query::RpcQueryError,
-        tx::RpcTransactionError,
+        block::RpcBlockError, broadcast_tx_async::RpcBroadcastTxAsyncError, chunk::RpcChunkError,
+        query::RpcQueryError, tx::RpcTransactionError,
+        EXPERIMENTAL_protocol_config::RpcProtocolConfigError,
     },
 };
 use near_jsonrpc_primitives::types::light_client::RpcLightClientProofError;
+use near_primitives::hash::CryptoHash;
 
 #[derive(thiserror::Error, Debug)]
 #[error("Near RPC error: {0}")]
@@ -14,9 +16,24 @@ pub enum NearRpcError {
     RpcBroadcastTxAsyncError(#[from] JsonRpcError<RpcBroadcastTxAsyncError>),
     RpcLightClientProofError(#[from] JsonRpcError<RpcLightClientProofError>),
     RpcBlockError(#[from] JsonRpcError<RpcBlockError>),
+    RpcChunkError(#[from] JsonRpcError<RpcChunkError>),
     RpcTransactionError(#[from] JsonRpcError<RpcTransactionError>),
+    RpcProtocolConfigError(#[from] JsonRpcError<RpcProtocolConfigError>),
     #[error("Could not retrieve nonce for account")]
     NonceError,
     #[error("Could not confirm that transaction was finalized")]
     FinalizationError,
+    #[error("Request deadline exceeded")]
+    DeadlineExceeded,
+    #[error("Unexpected response kind from Near RPC")]
+    UnexpectedResponse,
+    #[error("Could not load Near credentials file {0}")]
+    CredentialsError(String),
+    #[error("Transaction {tx_hash} failed: {error}")]
+    ExecutionFailed {
+        tx_hash: CryptoHash,
+        error: String,
+    },
+    #[error("No mocked response to {0}")]
+    NotMocked(String),
 }
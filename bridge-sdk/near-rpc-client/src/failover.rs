@@ -0,0 +1,134 @@
+use near_jsonrpc_client::errors::{JsonRpcError, JsonRpcServerError};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// How long an endpoint that failed is tried only after the healthy ones
+const UNHEALTHY_COOLDOWN: Duration = Duration::from_secs(60);
+
+tokio::task_local! {
+    static UNHEALTHY_SINCE: UnhealthyEndpoints;
+}
+
+/// Endpoints that failed recently, with the time they failed. Clones share the endpoints, so that the clones of a
+/// [`crate::NearRpcClient`] fail over together
+#[derive(Debug, Clone, Default)]
+pub(crate) struct UnhealthyEndpoints(Arc<Mutex<HashMap<String, Instant>>>);
+
+/// Runs `future` with the health of the endpoints of the RPC requests it makes, e.g. the one of a
+/// [`crate::NearRpcClient`]. Outside of a scope the endpoints are always tried in priority order
+pub(crate) async fn scope<F: Future>(unhealthy: UnhealthyEndpoints, future: F) -> F::Output {
+    UNHEALTHY_SINCE.scope(unhealthy, future).await
+}
+
+/// Endpoints of a comma separated list, in priority order. A string without endpoints is returned as is, so that the
+/// request fails with the error of the client
+pub fn endpoints(server_addr: &str) -> Vec<&str> {
+    let endpoints: Vec<_> = server_addr
+        .split(',')
+        .map(str::trim)
+        .filter(|endpoint| !endpoint.is_empty())
+        .collect();
+
+    match endpoints.is_empty() {
+        true => vec![server_addr],
+        false => endpoints,
+    }
+}
+
+/// Endpoints in the order they're tried: the healthy ones by priority, then the ones that failed recently, so that a
+/// request is still attempted when all of them failed
+pub(crate) fn ordered(server_addr: &str) -> Vec<&str> {
+    let mut endpoints = endpoints(server_addr);
+    let _ = UNHEALTHY_SINCE.try_with(|unhealthy| {
+        let unhealthy = unhealthy.0.lock().unwrap();
+        // Stable, so that the endpoints keep their priority within the healthy and the failed ones
+        endpoints.sort_by_key(|endpoint| match unhealthy.get(*endpoint) {
+            Some(since) => since.elapsed() < UNHEALTHY_COOLDOWN,
+            None => false,
+        });
+    });
+
+    endpoints
+}
+
+pub(crate) fn mark_healthy(endpoint: &str) {
+    let _ = UNHEALTHY_SINCE.try_with(|unhealthy| unhealthy.0.lock().unwrap().remove(endpoint));
+}
+
+pub(crate) fn mark_unhealthy(endpoint: &str) {
+    let _ = UNHEALTHY_SINCE.try_with(|unhealthy| {
+        unhealthy
+            .0
+            .lock()
+            .unwrap()
+            .insert(endpoint.to_string(), Instant::now())
+    });
+}
+
+/// Errors of the endpoint rather than of the request, e.g. timeouts, rate limiting or unavailable nodes. A request
+/// rejected by the handler, e.g. for an unknown transaction, fails the same on any endpoint
+pub(crate) fn should_fail_over<E>(error: &JsonRpcError<E>) -> bool {
+    !matches!(
+        error,
+        JsonRpcError::ServerError(
+            JsonRpcServerError::HandlerError(_) | JsonRpcServerError::RequestValidationError(_)
+        )
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SERVER_ADDR: &str = "http://primary.test, http://secondary.test,";
+
+    #[tokio::test]
+    async fn test_unhealthy_endpoints_are_tried_last() {
+        let unhealthy = UnhealthyEndpoints::default();
+        scope(unhealthy.clone(), async {
+            assert_eq!(
+                ordered(SERVER_ADDR),
+                ["http://primary.test", "http://secondary.test"]
+            );
+            mark_unhealthy("http://primary.test");
+        })
+        .await;
+
+        // Another client doesn't know the endpoint failed
+        scope(UnhealthyEndpoints::default(), async {
+            assert_eq!(
+                ordered(SERVER_ADDR),
+                ["http://primary.test", "http://secondary.test"]
+            );
+        })
+        .await;
+
+        scope(unhealthy, async {
+            assert_eq!(
+                ordered(SERVER_ADDR),
+                ["http://secondary.test", "http://primary.test"]
+            );
+
+            mark_healthy("http://primary.test");
+            assert_eq!(
+                ordered(SERVER_ADDR),
+                ["http://primary.test", "http://secondary.test"]
+            );
+        })
+        .await;
+        assert_eq!(endpoints(""), [""]);
+    }
+
+    #[test]
+    fn test_endpoints_are_tried_in_priority_order_outside_of_a_scope() {
+        mark_unhealthy("http://primary.test");
+        assert_eq!(
+            ordered(SERVER_ADDR),
+            ["http://primary.test", "http://secondary.test"]
+        );
+    }
+}
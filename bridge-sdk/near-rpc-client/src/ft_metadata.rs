@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+/// Metadata of a NEP-141 token, as returned by `ft_metadata` (NEP-148)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FungibleTokenMetadata {
+    pub spec: String,
+    pub name: String,
+    pub symbol: String,
+    /// Data URL of the token icon
+    pub icon: Option<String>,
+    pub reference: Option<String>,
+    /// Base64 encoded sha256 hash of the JSON file at `reference`
+    pub reference_hash: Option<String>,
+    /// Number of decimals of the token amounts, e.g. 6 for an amount of `1_000_000` to be one token
+    pub decimals: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ft_metadata() {
+        let metadata: FungibleTokenMetadata = serde_json::from_str(
+            r#"{
+                "spec": "ft-1.0.0",
+                "name": "Wrapped NEAR fungible token",
+                "symbol": "wNEAR",
+                "icon": null,
+                "reference": null,
+                "reference_hash": null,
+                "decimals": 24
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(metadata.symbol, "wNEAR");
+        assert_eq!(metadata.decimals, 24);
+        assert_eq!(metadata.icon, None);
+    }
+}
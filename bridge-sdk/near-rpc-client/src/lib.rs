@@ -1,6 +1,21 @@
+pub mod borsh_schema;
+mod credentials;
+pub mod deadline;
 mod error;
+mod failover;
+mod ft_metadata;
 pub mod light_client_proof;
+#[cfg(any(test, feature = "mock"))]
+mod mock;
+mod near_rpc;
 mod near_rpc_client;
+pub mod retry;
 
+pub use credentials::{default_credentials_file, signer_from_credentials_file};
 pub use error::NearRpcError;
+pub use failover::endpoints;
+pub use ft_metadata::FungibleTokenMetadata;
+#[cfg(any(test, feature = "mock"))]
+pub use mock::MockNearRpc;
+pub use near_rpc::{NearRpc, NearRpcClient};
 pub use near_rpc_client::*;
@@ -1,16 +1,30 @@
+use crate::borsh_schema;
+use borsh::BorshSchema;
 use near_jsonrpc_client::methods::light_client_proof::RpcLightClientExecutionProofResponse;
 use near_primitives::borsh::{BorshDeserialize, BorshSerialize};
 
 // This code adjusts the struct `RpcLightClientExecutionProofResponse`
 // by removing some fields that are incompatible with the bridge proof
 
-#[derive(BorshSerialize, BorshDeserialize)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
 pub struct ExecutionOutcomeView {
     pub logs: Vec<String>,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<Vec<borsh_schema::CryptoHash>>",
+        definitions = "borsh_schema::add_definitions::<Vec<borsh_schema::CryptoHash>>"
+    )))]
     pub receipt_ids: Vec<near_primitives::hash::CryptoHash>,
     pub gas_burnt: near_primitives::types::Gas,
     pub tokens_burnt: near_primitives::types::Balance,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::AccountId>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::AccountId>"
+    )))]
     pub executor_id: near_primitives::types::AccountId,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::ExecutionStatusView>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::ExecutionStatusView>"
+    )))]
     pub status: near_primitives::views::ExecutionStatusView,
     // pub metadata: ExecutionMetadataView, incompatible with bridge proof
 }
@@ -28,10 +42,22 @@ impl From<near_primitives::views::ExecutionOutcomeView> for ExecutionOutcomeView
     }
 }
 
-#[derive(BorshSerialize, BorshDeserialize)]
+#[derive(BorshSerialize, BorshDeserialize, BorshSchema)]
 pub struct ExecutionOutcomeWithIdView {
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::MerklePath>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::MerklePath>"
+    )))]
     pub proof: near_primitives::merkle::MerklePath,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::CryptoHash>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::CryptoHash>"
+    )))]
     pub block_hash: near_primitives::hash::CryptoHash,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::CryptoHash>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::CryptoHash>"
+    )))]
     pub id: near_primitives::hash::CryptoHash,
     pub outcome: ExecutionOutcomeView,
 }
@@ -47,9 +73,17 @@ impl From<near_primitives::views::ExecutionOutcomeWithIdView> for ExecutionOutco
     }
 }
 
-#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(BorshDeserialize, BorshSerialize, BorshSchema)]
 pub struct LightClientBlockLiteView {
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::CryptoHash>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::CryptoHash>"
+    )))]
     pub prev_block_hash: near_primitives::hash::CryptoHash,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::CryptoHash>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::CryptoHash>"
+    )))]
     pub inner_rest_hash: near_primitives::hash::CryptoHash,
     pub inner_lite: BlockHeaderInnerLiteView,
 }
@@ -64,16 +98,40 @@ impl From<near_primitives::views::LightClientBlockLiteView> for LightClientBlock
     }
 }
 
-#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(BorshDeserialize, BorshSerialize, BorshSchema)]
 pub struct BlockHeaderInnerLiteView {
     pub height: near_primitives::types::BlockHeight,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::CryptoHash>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::CryptoHash>"
+    )))]
     pub epoch_id: near_primitives::hash::CryptoHash,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::CryptoHash>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::CryptoHash>"
+    )))]
     pub next_epoch_id: near_primitives::hash::CryptoHash,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::CryptoHash>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::CryptoHash>"
+    )))]
     pub prev_state_root: near_primitives::hash::CryptoHash,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::CryptoHash>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::CryptoHash>"
+    )))]
     pub outcome_root: near_primitives::hash::CryptoHash,
     // pub timestamp: u64, incompatible with bridge proof
     pub timestamp_nanosec: u64,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::CryptoHash>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::CryptoHash>"
+    )))]
     pub next_bp_hash: near_primitives::hash::CryptoHash,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::CryptoHash>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::CryptoHash>"
+    )))]
     pub block_merkle_root: near_primitives::hash::CryptoHash,
 }
 
@@ -92,11 +150,19 @@ impl From<near_primitives::views::BlockHeaderInnerLiteView> for BlockHeaderInner
     }
 }
 
-#[derive(BorshDeserialize, BorshSerialize)]
+#[derive(BorshDeserialize, BorshSerialize, BorshSchema)]
 pub struct LightClientExecutionProof {
     pub outcome_proof: ExecutionOutcomeWithIdView,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::MerklePath>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::MerklePath>"
+    )))]
     pub outcome_root_proof: near_primitives::merkle::MerklePath,
     pub block_header_lite: LightClientBlockLiteView,
+    #[borsh(schema(with_funcs(
+        declaration = "borsh_schema::declaration::<borsh_schema::MerklePath>",
+        definitions = "borsh_schema::add_definitions::<borsh_schema::MerklePath>"
+    )))]
     pub block_proof: near_primitives::merkle::MerklePath,
 }
 
@@ -131,3 +197,23 @@ fn test_decode_encode_valid_proofs() {
         assert_eq!(encoded_proof, bytes_proof);
     }
 }
+
+#[test]
+fn test_schema_defines_nested_views() {
+    let container = borsh::schema_container_of::<LightClientExecutionProof>();
+
+    assert_eq!(container.declaration(), "LightClientExecutionProof");
+    for declaration in [
+        "ExecutionOutcomeWithIdView",
+        "LightClientBlockLiteView",
+        "BlockHeaderInnerLiteView",
+        "MerklePathItem",
+        "CryptoHash",
+        "ExecutionStatusView",
+    ] {
+        assert!(
+            container.get_definition(declaration).is_some(),
+            "{declaration} isn't defined"
+        );
+    }
+}
@@ -0,0 +1,422 @@
+use crate::{error::NearRpcError, light_client_proof::LightClientExecutionProof, NearRpc};
+use async_trait::async_trait;
+use borsh::BorshDeserialize;
+use near_crypto::PublicKey;
+use near_jsonrpc_client::{
+    errors::{JsonRpcError, JsonRpcServerError},
+    methods::block::RpcBlockError,
+};
+use near_jsonrpc_primitives::types::query::{QueryResponseKind, RpcQueryResponse};
+use near_primitives::{
+    errors::{ActionError, ActionErrorKind, FunctionCallError, TxExecutionError},
+    hash::CryptoHash,
+    transaction::{Action, FunctionCallAction, SignedTransaction, Transaction},
+    types::{AccountId, BlockId, BlockReference, TransactionOrReceiptId},
+    views::{
+        AccountView, BlockView, CallResult, ChunkView, ExecutionMetadataView, ExecutionOutcomeView,
+        ExecutionOutcomeWithIdView, ExecutionStatusView, FinalExecutionOutcomeView,
+        FinalExecutionStatus,
+    },
+};
+use serde::Serialize;
+use std::{collections::HashMap, sync::Mutex};
+
+/// Storage cost per byte on mainnet, returned until another one is set
+const DEFAULT_STORAGE_BYTE_COST: u128 = 10_000_000_000_000_000_000;
+
+/// In-memory Near RPC with scripted blocks, view results and proofs, to test code using Near through [`NearRpc`]
+/// without a node. Sent transactions are recorded and succeed unless their call is set to fail. Other requests fail
+/// with [`NearRpcError::NotMocked`]
+#[derive(Debug, Default)]
+pub struct MockNearRpc {
+    state: Mutex<MockState>,
+}
+
+#[derive(Debug, Default)]
+struct MockState {
+    blocks: Vec<BlockView>,
+    chunks: HashMap<CryptoHash, ChunkView>,
+    accounts: HashMap<AccountId, AccountView>,
+    view_results: HashMap<(AccountId, String), Vec<u8>>,
+    /// Borsh serialized proofs by transaction hash or receipt id
+    light_client_proofs: HashMap<CryptoHash, Vec<u8>>,
+    storage_byte_cost: Option<u128>,
+    transactions: Vec<SignedTransaction>,
+    /// Panic messages of the function calls failing once sent, by receiver and method
+    call_failures: HashMap<(AccountId, String), String>,
+}
+
+impl MockNearRpc {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The block with the greatest height is the final one
+    pub fn add_block(&self, block: BlockView) {
+        self.state().blocks.push(block);
+    }
+
+    pub fn add_chunk(&self, chunk: ChunkView) {
+        self.state().chunks.insert(chunk.header.chunk_hash, chunk);
+    }
+
+    pub fn set_account(&self, account_id: AccountId, account: AccountView) {
+        self.state().accounts.insert(account_id, account);
+    }
+
+    /// JSON result of the view method, whatever its arguments
+    pub fn set_view_result(
+        &self,
+        contract_account_id: AccountId,
+        method_name: &str,
+        result: &impl Serialize,
+    ) {
+        let result = serde_json::to_vec(result).expect("View result always serializes");
+        self.state()
+            .view_results
+            .insert((contract_account_id, method_name.to_string()), result);
+    }
+
+    /// Proof of the transaction or receipt `id`, whatever the light client head
+    pub fn set_light_client_proof(&self, id: CryptoHash, proof: &LightClientExecutionProof) {
+        let proof = borsh::to_vec(proof).expect("Light client proof always serializes");
+        self.state().light_client_proofs.insert(id, proof);
+    }
+
+    pub fn set_storage_byte_cost(&self, storage_byte_cost: u128) {
+        self.state().storage_byte_cost = Some(storage_byte_cost);
+    }
+
+    /// Fails the calls of `method_name` on `receiver_id` sent afterwards with `message`, like a panicking contract
+    pub fn set_call_failure(&self, receiver_id: AccountId, method_name: &str, message: &str) {
+        self.state()
+            .call_failures
+            .insert((receiver_id, method_name.to_string()), message.to_string());
+    }
+
+    /// Transactions sent so far, in order
+    pub fn sent_transactions(&self) -> Vec<SignedTransaction> {
+        self.state().transactions.clone()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().expect("Mock Near RPC lock is poisoned")
+    }
+}
+
+impl MockState {
+    fn final_block(&self) -> Option<&BlockView> {
+        self.blocks.iter().max_by_key(|block| block.header.height)
+    }
+
+    /// Status of the first function call of the transaction set to fail, success otherwise
+    fn execution_status(&self, transaction: &Transaction) -> ExecutionStatusView {
+        let failure = transaction.actions.iter().find_map(|action| match action {
+            Action::FunctionCall(call) => self
+                .call_failures
+                .get(&(transaction.receiver_id.clone(), call.method_name.clone())),
+            _ => None,
+        });
+
+        match failure {
+            Some(message) => {
+                ExecutionStatusView::Failure(TxExecutionError::ActionError(ActionError {
+                    index: Some(0),
+                    kind: ActionErrorKind::FunctionCallError(FunctionCallError::ExecutionError(
+                        message.clone(),
+                    )),
+                }))
+            }
+            None => ExecutionStatusView::SuccessValue(Vec::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl NearRpc for MockNearRpc {
+    fn endpoint(&self) -> &str {
+        "mock"
+    }
+
+    async fn view_raw(
+        &self,
+        _block_reference: BlockReference,
+        contract_account_id: AccountId,
+        method_name: String,
+        _args: Vec<u8>,
+    ) -> Result<RpcQueryResponse, NearRpcError> {
+        let state = self.state();
+        let result = state
+            .view_results
+            .get(&(contract_account_id.clone(), method_name.clone()))
+            .cloned()
+            .ok_or_else(|| {
+                NearRpcError::NotMocked(format!("view {contract_account_id}.{method_name}"))
+            })?;
+        let final_block = state.final_block();
+
+        Ok(RpcQueryResponse {
+            kind: QueryResponseKind::CallResult(CallResult {
+                result,
+                logs: Vec::new(),
+            }),
+            block_height: final_block.map_or(0, |block| block.header.height),
+            block_hash: final_block.map_or_else(CryptoHash::default, |block| block.header.hash),
+        })
+    }
+
+    async fn view_account(&self, account_id: AccountId) -> Result<AccountView, NearRpcError> {
+        self.state()
+            .accounts
+            .get(&account_id)
+            .cloned()
+            .ok_or_else(|| NearRpcError::NotMocked(format!("account {account_id}")))
+    }
+
+    /// Blocks that aren't scripted are unknown, like the heights without a block on a node
+    async fn get_block(&self, block_reference: BlockReference) -> Result<BlockView, NearRpcError> {
+        let state = self.state();
+        let block = match &block_reference {
+            BlockReference::BlockId(BlockId::Height(height)) => state
+                .blocks
+                .iter()
+                .find(|block| block.header.height == *height),
+            BlockReference::BlockId(BlockId::Hash(hash)) => {
+                state.blocks.iter().find(|block| block.header.hash == *hash)
+            }
+            _ => state.final_block(),
+        };
+
+        block.cloned().ok_or_else(|| {
+            NearRpcError::RpcBlockError(JsonRpcError::ServerError(
+                JsonRpcServerError::HandlerError(RpcBlockError::UnknownBlock {
+                    error_message: format!("{block_reference:?} is not mocked"),
+                }),
+            ))
+        })
+    }
+
+    async fn get_chunk(&self, chunk_hash: CryptoHash) -> Result<ChunkView, NearRpcError> {
+        self.state()
+            .chunks
+            .get(&chunk_hash)
+            .cloned()
+            .ok_or_else(|| NearRpcError::NotMocked(format!("chunk {chunk_hash}")))
+    }
+
+    async fn get_light_client_proof(
+        &self,
+        id: TransactionOrReceiptId,
+        _light_client_head: CryptoHash,
+    ) -> Result<LightClientExecutionProof, NearRpcError> {
+        let id = match id {
+            TransactionOrReceiptId::Transaction {
+                transaction_hash, ..
+            } => transaction_hash,
+            TransactionOrReceiptId::Receipt { receipt_id, .. } => receipt_id,
+        };
+
+        let state = self.state();
+        let proof = state
+            .light_client_proofs
+            .get(&id)
+            .ok_or_else(|| NearRpcError::NotMocked(format!("light client proof of {id}")))?;
+
+        Ok(LightClientExecutionProof::try_from_slice(proof)
+            .expect("Mocked light client proof always deserializes"))
+    }
+
+    async fn get_storage_byte_cost(&self) -> Result<u128, NearRpcError> {
+        Ok(self
+            .state()
+            .storage_byte_cost
+            .unwrap_or(DEFAULT_STORAGE_BYTE_COST))
+    }
+
+    /// The nonce follows the transactions of the signer sent so far
+    async fn prepare_transaction(
+        &self,
+        signer_id: AccountId,
+        public_key: PublicKey,
+        receiver_id: String,
+        method_name: String,
+        args: Vec<u8>,
+        gas: u64,
+        deposit: u128,
+    ) -> Result<Transaction, NearRpcError> {
+        let receiver_id = receiver_id
+            .parse()
+            .map_err(|_| NearRpcError::NotMocked(format!("receiver {receiver_id}")))?;
+        let state = self.state();
+        let sent = state
+            .transactions
+            .iter()
+            .filter(|transaction| transaction.transaction.signer_id == signer_id)
+            .count() as u64;
+
+        Ok(Transaction {
+            signer_id,
+            public_key,
+            nonce: sent + 1,
+            receiver_id,
+            block_hash: state
+                .final_block()
+                .map_or_else(CryptoHash::default, |block| block.header.hash),
+            actions: vec![Action::FunctionCall(Box::new(FunctionCallAction {
+                method_name,
+                args,
+                gas,
+                deposit,
+            }))],
+        })
+    }
+
+    async fn send_transaction(
+        &self,
+        signed_transaction: SignedTransaction,
+    ) -> Result<CryptoHash, NearRpcError> {
+        let tx_hash = signed_transaction.get_hash();
+        self.state().transactions.push(signed_transaction);
+
+        Ok(tx_hash)
+    }
+
+    /// Outcome of a sent transaction, executed at once in the final block without any receipt
+    async fn wait_for_tx_final_outcome(
+        &self,
+        tx_hash: CryptoHash,
+        _account_id: AccountId,
+        _timeout_sec: u64,
+    ) -> Result<FinalExecutionOutcomeView, NearRpcError> {
+        let state = self.state();
+        let transaction = state
+            .transactions
+            .iter()
+            .find(|transaction| transaction.get_hash() == tx_hash)
+            .ok_or_else(|| NearRpcError::NotMocked(format!("transaction {tx_hash}")))?;
+
+        let status = state.execution_status(&transaction.transaction);
+        let final_status = match &status {
+            ExecutionStatusView::Failure(err) => FinalExecutionStatus::Failure(err.clone()),
+            _ => FinalExecutionStatus::SuccessValue(Vec::new()),
+        };
+
+        Ok(FinalExecutionOutcomeView {
+            status: final_status,
+            transaction: transaction.clone().into(),
+            transaction_outcome: ExecutionOutcomeWithIdView {
+                proof: Vec::new(),
+                block_hash: state
+                    .final_block()
+                    .map_or_else(CryptoHash::default, |block| block.header.hash),
+                id: tx_hash,
+                outcome: ExecutionOutcomeView {
+                    logs: Vec::new(),
+                    receipt_ids: Vec::new(),
+                    gas_burnt: 0,
+                    tokens_burnt: 0,
+                    executor_id: transaction.transaction.signer_id.clone(),
+                    status,
+                    metadata: ExecutionMetadataView::default(),
+                },
+            },
+            receipts_outcome: Vec::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_crypto::{InMemorySigner, KeyType};
+    use near_primitives::types::Finality;
+
+    #[tokio::test]
+    async fn serves_scripted_responses() {
+        let rpc = MockNearRpc::new();
+        let token_id: AccountId = "wrap.near".parse().unwrap();
+        rpc.set_view_result(
+            token_id.clone(),
+            "ft_metadata",
+            &serde_json::json!({
+                "spec": "ft-1.0.0",
+                "name": "Wrapped NEAR fungible token",
+                "symbol": "wNEAR",
+                "icon": null,
+                "reference": null,
+                "reference_hash": null,
+                "decimals": 24
+            }),
+        );
+
+        let metadata = rpc.get_ft_metadata(token_id.clone()).await.unwrap();
+        assert_eq!(metadata.symbol, "wNEAR");
+        assert_eq!(
+            rpc.get_storage_byte_cost().await.unwrap(),
+            DEFAULT_STORAGE_BYTE_COST
+        );
+
+        assert!(matches!(
+            rpc.view_account(token_id).await,
+            Err(NearRpcError::NotMocked(_))
+        ));
+        assert!(matches!(
+            rpc.get_block(BlockReference::Finality(Finality::Final))
+                .await,
+            Err(NearRpcError::RpcBlockError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn records_sent_transactions() {
+        let rpc = MockNearRpc::new();
+        let signer = InMemorySigner::from_seed(
+            "alice.near".parse().unwrap(),
+            KeyType::ED25519,
+            "alice.near",
+        );
+        let locker_id: AccountId = "locker.near".parse().unwrap();
+        rpc.set_call_failure(
+            locker_id.clone(),
+            "withdraw",
+            "Smart contract panicked: ERR",
+        );
+
+        let mut tx_hashes = Vec::new();
+        for method_name in ["deposit", "withdraw"] {
+            let transaction = rpc
+                .prepare_transaction(
+                    signer.account_id.clone(),
+                    signer.public_key.clone(),
+                    locker_id.to_string(),
+                    method_name.to_string(),
+                    b"{}".to_vec(),
+                    1,
+                    0,
+                )
+                .await
+                .unwrap();
+            tx_hashes.push(
+                rpc.send_transaction(transaction.sign(&signer))
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        let sent = rpc.sent_transactions();
+        assert_eq!(sent.len(), 2);
+        assert_eq!(sent[1].transaction.nonce, 2);
+
+        rpc.wait_for_tx_success(tx_hashes[0], signer.account_id.clone(), 1)
+            .await
+            .unwrap();
+        let err = rpc
+            .wait_for_tx_success(tx_hashes[1], signer.account_id.clone(), 1)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            NearRpcError::ExecutionFailed { error, .. } if error == "Smart contract panicked: ERR"
+        ));
+    }
+}
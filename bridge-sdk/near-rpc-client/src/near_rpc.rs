@@ -0,0 +1,312 @@
+use crate::{
+    error::NearRpcError,
+    failover::{self, UnhealthyEndpoints},
+    ft_metadata::FungibleTokenMetadata,
+    light_client_proof::LightClientExecutionProof,
+    near_rpc_client::{self, FunctionCallSimulation},
+    retry::{self, RetryPolicy},
+};
+use async_trait::async_trait;
+use near_crypto::PublicKey;
+use near_jsonrpc_primitives::types::query::{QueryResponseKind, RpcQueryResponse};
+use near_primitives::{
+    hash::CryptoHash,
+    transaction::{SignedTransaction, Transaction},
+    types::{AccountId, BlockReference, Finality, TransactionOrReceiptId},
+    views::{AccountView, BlockView, ChunkView, FinalExecutionOutcomeView},
+};
+use std::future::Future;
+
+/// Near RPC requests of the SDK. Implemented by [`NearRpcClient`], and by `MockNearRpc` with the `mock` feature, so
+/// that code reading Near or sending transactions through this trait can be tested without a node
+#[async_trait]
+pub trait NearRpc: Send + Sync {
+    /// Endpoint the requests are sent to, which the connectors schedule their requests by
+    fn endpoint(&self) -> &str;
+
+    /// Calls a view method with raw arguments, see [`near_rpc_client::view_raw`]
+    async fn view_raw(
+        &self,
+        block_reference: BlockReference,
+        contract_account_id: AccountId,
+        method_name: String,
+        args: Vec<u8>,
+    ) -> Result<RpcQueryResponse, NearRpcError>;
+
+    async fn view_account(&self, account_id: AccountId) -> Result<AccountView, NearRpcError>;
+
+    async fn get_block(&self, block_reference: BlockReference) -> Result<BlockView, NearRpcError>;
+
+    async fn get_chunk(&self, chunk_hash: CryptoHash) -> Result<ChunkView, NearRpcError>;
+
+    async fn get_light_client_proof(
+        &self,
+        id: TransactionOrReceiptId,
+        light_client_head: CryptoHash,
+    ) -> Result<LightClientExecutionProof, NearRpcError>;
+
+    async fn get_storage_byte_cost(&self) -> Result<u128, NearRpcError>;
+
+    /// Builds an unsigned function call transaction, see [`near_rpc_client::prepare_transaction`]
+    #[allow(clippy::too_many_arguments)]
+    async fn prepare_transaction(
+        &self,
+        signer_id: AccountId,
+        public_key: PublicKey,
+        receiver_id: String,
+        method_name: String,
+        args: Vec<u8>,
+        gas: u64,
+        deposit: u128,
+    ) -> Result<Transaction, NearRpcError>;
+
+    /// Broadcasts the transaction without waiting for it to be executed
+    async fn send_transaction(
+        &self,
+        signed_transaction: SignedTransaction,
+    ) -> Result<CryptoHash, NearRpcError>;
+
+    /// Polls the transaction until it's executed, see [`near_rpc_client::wait_for_tx_final_outcome`]
+    async fn wait_for_tx_final_outcome(
+        &self,
+        tx_hash: CryptoHash,
+        account_id: AccountId,
+        timeout_sec: u64,
+    ) -> Result<FinalExecutionOutcomeView, NearRpcError>;
+
+    /// Same as [`Self::wait_for_tx_final_outcome`], failing with [`NearRpcError::ExecutionFailed`] if the transaction
+    /// or one of its receipts failed, e.g. because the called contract panicked
+    async fn wait_for_tx_success(
+        &self,
+        tx_hash: CryptoHash,
+        account_id: AccountId,
+        timeout_sec: u64,
+    ) -> Result<FinalExecutionOutcomeView, NearRpcError> {
+        let outcome = self
+            .wait_for_tx_final_outcome(tx_hash, account_id, timeout_sec)
+            .await?;
+
+        match near_rpc_client::execution_failure(&outcome) {
+            Some(error) => Err(NearRpcError::ExecutionFailed { tx_hash, error }),
+            None => Ok(outcome),
+        }
+    }
+
+    /// Runs the function call as a view call of the final state, since Near has no transaction simulation. Methods
+    /// using host functions prohibited in view calls can't run to completion, but a call failing earlier, e.g. on its
+    /// arguments or a missing method, fails with the error the transaction would fail with
+    async fn simulate_function_call(
+        &self,
+        signer_id: AccountId,
+        receiver_id: AccountId,
+        method_name: String,
+        args: Vec<u8>,
+        gas: u64,
+        deposit: u128,
+    ) -> Result<FunctionCallSimulation, NearRpcError> {
+        let signer_balance = self.view_account(signer_id).await?.amount;
+        let gas_price = self
+            .get_block(BlockReference::Finality(Finality::Final))
+            .await?
+            .header
+            .gas_price;
+
+        let (result, view_error) = match self
+            .view_raw(
+                BlockReference::Finality(Finality::Final),
+                receiver_id,
+                method_name,
+                args,
+            )
+            .await
+        {
+            Ok(response) => match response.kind {
+                QueryResponseKind::CallResult(result) => (Some(result), None),
+                _ => return Err(NearRpcError::UnexpectedResponse),
+            },
+            Err(err) if near_rpc_client::is_prohibited_in_view(&err) => {
+                (None, Some(err.to_string()))
+            }
+            Err(err) => return Err(err),
+        };
+
+        Ok(FunctionCallSimulation {
+            result,
+            view_error,
+            max_gas_cost: u128::from(gas) * gas_price,
+            signer_balance,
+        })
+    }
+
+    /// Metadata of the NEP-141 token `token_id`
+    async fn get_ft_metadata(
+        &self,
+        token_id: AccountId,
+    ) -> Result<FungibleTokenMetadata, NearRpcError> {
+        let response = self
+            .view_raw(
+                BlockReference::Finality(Finality::Final),
+                token_id,
+                "ft_metadata".to_string(),
+                b"{}".to_vec(),
+            )
+            .await?;
+
+        near_rpc_client::call_result(response)
+    }
+}
+
+/// [`NearRpc`] of the endpoints of `server_addr`, with the failover of the free functions of the crate and its own
+/// retry policy. The endpoints that failed are tried after the healthy ones by the following requests of the client
+/// and of its clones
+#[derive(Debug, Clone)]
+pub struct NearRpcClient {
+    server_addr: String,
+    retry_policy: RetryPolicy,
+    unhealthy_endpoints: UnhealthyEndpoints,
+}
+
+impl NearRpcClient {
+    /// `server_addr` can be a comma separated list of endpoints in priority order
+    pub fn new(server_addr: impl Into<String>) -> Self {
+        Self {
+            server_addr: server_addr.into(),
+            retry_policy: RetryPolicy::DEFAULT,
+            unhealthy_endpoints: UnhealthyEndpoints::default(),
+        }
+    }
+
+    /// Retries transient errors as set by `retry_policy` instead of [`RetryPolicy::DEFAULT`]
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    pub fn server_addr(&self) -> &str {
+        &self.server_addr
+    }
+
+    /// Probes each endpoint of the client, see [`near_rpc_client::health_check`]
+    pub async fn health_check(&self) -> Vec<near_rpc_client::EndpointHealth> {
+        self.scope(near_rpc_client::health_check(&self.server_addr))
+            .await
+    }
+
+    /// Runs `request` with the retry policy and the endpoint health of the client
+    async fn scope<F: Future>(&self, request: F) -> F::Output {
+        retry::scope(
+            self.retry_policy,
+            failover::scope(self.unhealthy_endpoints.clone(), request),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl NearRpc for NearRpcClient {
+    fn endpoint(&self) -> &str {
+        &self.server_addr
+    }
+
+    async fn view_raw(
+        &self,
+        block_reference: BlockReference,
+        contract_account_id: AccountId,
+        method_name: String,
+        args: Vec<u8>,
+    ) -> Result<RpcQueryResponse, NearRpcError> {
+        self.scope(near_rpc_client::view_raw(
+            &self.server_addr,
+            block_reference,
+            contract_account_id,
+            method_name,
+            args,
+        ))
+        .await
+    }
+
+    async fn view_account(&self, account_id: AccountId) -> Result<AccountView, NearRpcError> {
+        self.scope(near_rpc_client::view_account(&self.server_addr, account_id))
+            .await
+    }
+
+    async fn get_block(&self, block_reference: BlockReference) -> Result<BlockView, NearRpcError> {
+        self.scope(near_rpc_client::get_block(
+            &self.server_addr,
+            block_reference,
+        ))
+        .await
+    }
+
+    async fn get_chunk(&self, chunk_hash: CryptoHash) -> Result<ChunkView, NearRpcError> {
+        self.scope(near_rpc_client::get_chunk(&self.server_addr, chunk_hash))
+            .await
+    }
+
+    async fn get_light_client_proof(
+        &self,
+        id: TransactionOrReceiptId,
+        light_client_head: CryptoHash,
+    ) -> Result<LightClientExecutionProof, NearRpcError> {
+        self.scope(near_rpc_client::get_light_client_proof(
+            &self.server_addr,
+            id,
+            light_client_head,
+        ))
+        .await
+    }
+
+    async fn get_storage_byte_cost(&self) -> Result<u128, NearRpcError> {
+        self.scope(near_rpc_client::get_storage_byte_cost(&self.server_addr))
+            .await
+    }
+
+    async fn prepare_transaction(
+        &self,
+        signer_id: AccountId,
+        public_key: PublicKey,
+        receiver_id: String,
+        method_name: String,
+        args: Vec<u8>,
+        gas: u64,
+        deposit: u128,
+    ) -> Result<Transaction, NearRpcError> {
+        self.scope(near_rpc_client::prepare_transaction(
+            &self.server_addr,
+            signer_id,
+            public_key,
+            receiver_id,
+            method_name,
+            args,
+            gas,
+            deposit,
+        ))
+        .await
+    }
+
+    async fn send_transaction(
+        &self,
+        signed_transaction: SignedTransaction,
+    ) -> Result<CryptoHash, NearRpcError> {
+        self.scope(near_rpc_client::send_transaction(
+            &self.server_addr,
+            signed_transaction,
+        ))
+        .await
+    }
+
+    async fn wait_for_tx_final_outcome(
+        &self,
+        tx_hash: CryptoHash,
+        account_id: AccountId,
+        timeout_sec: u64,
+    ) -> Result<FinalExecutionOutcomeView, NearRpcError> {
+        self.scope(near_rpc_client::wait_for_tx_final_outcome(
+            tx_hash,
+            account_id,
+            &self.server_addr,
+            timeout_sec,
+        ))
+        .await
+    }
+}
@@ -1,18 +1,34 @@
+use crate::deadline;
 use crate::error::NearRpcError;
+use crate::failover;
+use crate::ft_metadata::FungibleTokenMetadata;
 use crate::light_client_proof::LightClientExecutionProof;
+use crate::retry;
 use lazy_static::lazy_static;
-use near_jsonrpc_client::{methods, JsonRpcClient, JsonRpcClientConnector};
-use near_jsonrpc_primitives::types::query::{QueryResponseKind, RpcQueryResponse};
+use near_crypto::PublicKey;
+use near_jsonrpc_client::{
+    errors::JsonRpcError, methods, JsonRpcClient, JsonRpcClientConnector, MethodCallResult,
+};
+use near_jsonrpc_primitives::types::chunks::ChunkReference;
+use near_jsonrpc_primitives::types::query::{QueryResponseKind, RpcQueryError, RpcQueryResponse};
 use near_jsonrpc_primitives::types::transactions::TransactionInfo;
+use near_primitives::errors::{ActionError, ActionErrorKind, FunctionCallError, TxExecutionError};
 use near_primitives::hash::CryptoHash;
-use near_primitives::transaction::{Action, FunctionCallAction, Transaction};
+use near_primitives::transaction::{Action, FunctionCallAction, SignedTransaction, Transaction};
 use near_primitives::types::{AccountId, BlockReference, Finality, FunctionArgs};
-use near_primitives::views::{FinalExecutionOutcomeView, QueryRequest};
+use near_primitives::views::{
+    AccessKeyView, AccountView, CallResult, ExecutionStatusView, FinalExecutionOutcomeView,
+    FinalExecutionStatus, QueryRequest,
+};
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
-use tokio::time;
+use serde::de::DeserializeOwned;
+use tokio::time::{self, Duration};
 
 pub const DEFAULT_WAIT_FINAL_OUTCOME_TIMEOUT_SEC: u64 = 500;
 
+/// Error of the host functions a view call can't use, e.g. `storage_write`, `predecessor_account_id` or the promises
+const PROHIBITED_IN_VIEW_ERROR: &str = "ProhibitedInView";
+
 lazy_static! {
     static ref DEFAULT_CONNECTOR: JsonRpcClientConnector = JsonRpcClient::with(
         new_near_rpc_client(Some(std::time::Duration::from_secs(30)))
@@ -30,22 +46,211 @@ fn new_near_rpc_client(timeout: Option<std::time::Duration>) -> reqwest::Client
     builder.build().unwrap()
 }
 
+/// Sends a request, bounded by the deadline of the enclosing [`deadline::scope`] if any. `server_addr` can be a comma
+/// separated list of endpoints in priority order. A request failing with an error of the endpoint, e.g. a timeout or
+/// rate limiting, is retried on the next one. Within a [`crate::NearRpcClient`], the failed endpoint is then only tried
+/// after the healthy ones for a while
+async fn call<M>(server_addr: &str, request: M) -> Result<M::Response, NearRpcError>
+where
+    M: methods::RpcMethod,
+    NearRpcError: From<JsonRpcError<M::Error>>,
+{
+    Ok(send(server_addr, &request).await??)
+}
+
+/// Same as [`call`], returning the error of the request as is. Transient errors are retried as set by the
+/// [`retry::RetryPolicy`] of the enclosing [`retry::scope`]
+async fn send<M: methods::RpcMethod>(
+    server_addr: &str,
+    request: &M,
+) -> Result<MethodCallResult<M::Response, M::Error>, NearRpcError> {
+    let policy = retry::current();
+    let mut attempt = 0;
+
+    loop {
+        match send_once(server_addr, request).await? {
+            Err(err) if attempt + 1 < policy.attempts && retry::is_transient(&err) => {
+                deadline::run(time::sleep(policy.delay(attempt)))
+                    .await
+                    .ok_or(NearRpcError::DeadlineExceeded)?;
+                attempt += 1;
+            }
+            response => return Ok(response),
+        }
+    }
+}
+
+/// Sends the request to each endpoint in turn until one of them responds
+async fn send_once<M: methods::RpcMethod>(
+    server_addr: &str,
+    request: &M,
+) -> Result<MethodCallResult<M::Response, M::Error>, NearRpcError> {
+    let endpoints = failover::ordered(server_addr);
+
+    for (index, endpoint) in endpoints.iter().enumerate() {
+        let client = DEFAULT_CONNECTOR.connect(*endpoint);
+        let response = deadline::run(client.call(request))
+            .await
+            .ok_or(NearRpcError::DeadlineExceeded)?;
+
+        match response {
+            Err(err) if failover::should_fail_over(&err) => {
+                failover::mark_unhealthy(endpoint);
+                if index + 1 == endpoints.len() {
+                    return Ok(Err(err));
+                }
+            }
+            response => {
+                failover::mark_healthy(endpoint);
+                return Ok(response);
+            }
+        }
+    }
+
+    unreachable!("The last endpoint always returns its response")
+}
+
+/// Health of a Near RPC endpoint as probed by [`health_check`]
+#[derive(Debug)]
+pub struct EndpointHealth {
+    pub endpoint: String,
+    pub latency: Duration,
+    /// Height of the final block, or the error of the endpoint
+    pub status: Result<u64, NearRpcError>,
+}
+
+/// Probes each endpoint of `server_addr` for its final block. Called through [`crate::NearRpcClient::health_check`],
+/// the endpoints failing the probe are tried after the healthy ones by the following requests of the client, and the
+/// ones responding again get their priority back
+pub async fn health_check(server_addr: &str) -> Vec<EndpointHealth> {
+    let mut health = Vec::new();
+
+    for endpoint in failover::endpoints(server_addr) {
+        let client = DEFAULT_CONNECTOR.connect(endpoint);
+        let request = methods::block::RpcBlockRequest {
+            block_reference: BlockReference::Finality(Finality::Final),
+        };
+
+        let started_at = time::Instant::now();
+        let status = match deadline::run(client.call(request)).await {
+            Some(response) => response
+                .map(|block| block.header.height)
+                .map_err(NearRpcError::from),
+            None => Err(NearRpcError::DeadlineExceeded),
+        };
+
+        match status {
+            Ok(_) => failover::mark_healthy(endpoint),
+            Err(_) => failover::mark_unhealthy(endpoint),
+        }
+
+        health.push(EndpointHealth {
+            endpoint: endpoint.to_string(),
+            latency: started_at.elapsed(),
+            status,
+        });
+    }
+
+    health
+}
+
 pub async fn view(
     server_addr: &str,
     contract_account_id: AccountId,
     method_name: String,
     args: serde_json::Value,
 ) -> Result<RpcQueryResponse, NearRpcError> {
-    let client = DEFAULT_CONNECTOR.connect(server_addr);
+    view_raw(
+        server_addr,
+        BlockReference::Finality(Finality::Final),
+        contract_account_id,
+        method_name,
+        args.to_string().into_bytes(),
+    )
+    .await
+}
+
+/// Same as [`view`], for methods taking their arguments in another encoding than JSON, e.g. borsh, and at a given
+/// block
+pub async fn view_raw(
+    server_addr: &str,
+    block_reference: BlockReference,
+    contract_account_id: AccountId,
+    method_name: String,
+    args: Vec<u8>,
+) -> Result<RpcQueryResponse, NearRpcError> {
     let request = methods::query::RpcQueryRequest {
-        block_reference: BlockReference::Finality(Finality::Final),
+        block_reference,
         request: QueryRequest::CallFunction {
             account_id: contract_account_id,
             method_name,
-            args: FunctionArgs::from(args.to_string().into_bytes()),
+            args: FunctionArgs::from(args),
         },
     };
-    Ok(client.call(request).await?)
+    call(server_addr, request).await
+}
+
+pub async fn view_account(
+    server_addr: &str,
+    account_id: AccountId,
+) -> Result<AccountView, NearRpcError> {
+    let request = methods::query::RpcQueryRequest {
+        block_reference: BlockReference::Finality(Finality::Final),
+        request: QueryRequest::ViewAccount { account_id },
+    };
+
+    match call(server_addr, request).await?.kind {
+        QueryResponseKind::ViewAccount(account) => Ok(account),
+        _ => Err(NearRpcError::UnexpectedResponse),
+    }
+}
+
+/// Nonce and permission of the access key `public_key` of `account_id`
+pub async fn view_access_key(
+    server_addr: &str,
+    account_id: AccountId,
+    public_key: PublicKey,
+) -> Result<AccessKeyView, NearRpcError> {
+    let request = methods::query::RpcQueryRequest {
+        block_reference: BlockReference::Finality(Finality::Final),
+        request: QueryRequest::ViewAccessKey {
+            account_id,
+            public_key,
+        },
+    };
+
+    match call(server_addr, request).await?.kind {
+        QueryResponseKind::AccessKey(access_key) => Ok(access_key),
+        _ => Err(NearRpcError::UnexpectedResponse),
+    }
+}
+
+/// Metadata of the NEP-141 token `token_id`
+pub async fn get_ft_metadata(
+    server_addr: &str,
+    token_id: AccountId,
+) -> Result<FungibleTokenMetadata, NearRpcError> {
+    let response = view(
+        server_addr,
+        token_id,
+        "ft_metadata".to_string(),
+        serde_json::json!({}),
+    )
+    .await?;
+
+    call_result(response)
+}
+
+/// JSON result of a view call
+pub(crate) fn call_result<T: DeserializeOwned>(
+    response: RpcQueryResponse,
+) -> Result<T, NearRpcError> {
+    match response.kind {
+        QueryResponseKind::CallResult(result) => {
+            serde_json::from_slice(&result.result).map_err(|_| NearRpcError::UnexpectedResponse)
+        }
+        _ => Err(NearRpcError::UnexpectedResponse),
+    }
 }
 
 pub async fn get_light_client_proof(
@@ -53,47 +258,67 @@ pub async fn get_light_client_proof(
     id: near_primitives::types::TransactionOrReceiptId,
     light_client_head: CryptoHash,
 ) -> Result<LightClientExecutionProof, NearRpcError> {
-    let client = DEFAULT_CONNECTOR.connect(server_addr);
-
     let request =
         near_jsonrpc_client::methods::light_client_proof::RpcLightClientExecutionProofRequest {
             id,
             light_client_head,
         };
 
-    Ok(client.call(request).await?.into())
+    Ok(call(server_addr, request).await?.into())
 }
 
 pub async fn get_final_block_timestamp(server_addr: &str) -> Result<u64, NearRpcError> {
-    let client = DEFAULT_CONNECTOR.connect(server_addr);
     let request = methods::block::RpcBlockRequest {
         block_reference: BlockReference::Finality(Finality::Final),
     };
 
-    let block_info = client.call(request).await?;
+    let block_info = call(server_addr, request).await?;
     Ok(block_info.header.timestamp)
 }
 
 pub async fn get_last_near_block_height(server_addr: &str) -> Result<u64, NearRpcError> {
-    let client = DEFAULT_CONNECTOR.connect(server_addr);
     let request = methods::block::RpcBlockRequest {
         block_reference: BlockReference::latest(),
     };
 
-    let block_info = client.call(request).await?;
+    let block_info = call(server_addr, request).await?;
     Ok(block_info.header.height as u64)
 }
 
+pub async fn get_storage_byte_cost(server_addr: &str) -> Result<u128, NearRpcError> {
+    let request = methods::EXPERIMENTAL_protocol_config::RpcProtocolConfigRequest {
+        block_reference: BlockReference::Finality(Finality::Final),
+    };
+
+    let protocol_config = call(server_addr, request).await?;
+    Ok(protocol_config
+        .config_view
+        .runtime_config
+        .storage_amount_per_byte)
+}
+
 pub async fn get_block(
     server_addr: &str,
     block_reference: BlockReference,
 ) -> Result<near_primitives::views::BlockView, NearRpcError> {
-    let client = DEFAULT_CONNECTOR.connect(server_addr);
     let request = methods::block::RpcBlockRequest { block_reference };
-    let block_info = client.call(request).await?;
+    let block_info = call(server_addr, request).await?;
     Ok(block_info)
 }
 
+pub async fn get_chunk(
+    server_addr: &str,
+    chunk_hash: CryptoHash,
+) -> Result<near_primitives::views::ChunkView, NearRpcError> {
+    let request = methods::chunk::RpcChunkRequest {
+        chunk_reference: ChunkReference::ChunkHash {
+            chunk_id: chunk_hash,
+        },
+    };
+    let chunk = call(server_addr, request).await?;
+    Ok(chunk)
+}
+
 pub async fn change(
     server_addr: &str,
     signer: near_crypto::InMemorySigner,
@@ -103,23 +328,50 @@ pub async fn change(
     gas: u64,
     deposit: u128,
 ) -> Result<CryptoHash, NearRpcError> {
-    let client = DEFAULT_CONNECTOR.connect(server_addr);
+    let transaction = prepare_transaction(
+        server_addr,
+        signer.account_id.clone(),
+        signer.public_key.clone(),
+        receiver_id,
+        method_name,
+        args,
+        gas,
+        deposit,
+    )
+    .await?;
+
+    send_transaction(server_addr, transaction.sign(&signer)).await
+}
+
+/// Builds an unsigned function call transaction using the next nonce of the access key
+#[allow(clippy::too_many_arguments)]
+pub async fn prepare_transaction(
+    server_addr: &str,
+    signer_id: AccountId,
+    public_key: PublicKey,
+    receiver_id: String,
+    method_name: String,
+    args: Vec<u8>,
+    gas: u64,
+    deposit: u128,
+) -> Result<Transaction, NearRpcError> {
     let rpc_request = methods::query::RpcQueryRequest {
         block_reference: BlockReference::latest(),
         request: near_primitives::views::QueryRequest::ViewAccessKey {
-            account_id: signer.account_id.clone(),
-            public_key: signer.public_key.clone(),
+            account_id: signer_id.clone(),
+            public_key: public_key.clone(),
         },
     };
-    let access_key_query_response = client.call(rpc_request).await?;
+    let access_key_query_response = call(server_addr, rpc_request).await?;
 
     let current_nonce = match access_key_query_response.kind {
         QueryResponseKind::AccessKey(access_key) => access_key.nonce,
         _ => Err(NearRpcError::NonceError)?,
     };
-    let transaction = Transaction {
-        signer_id: signer.account_id.clone(),
-        public_key: signer.public_key.clone(),
+
+    Ok(Transaction {
+        signer_id,
+        public_key,
         nonce: current_nonce + 1,
         receiver_id: receiver_id.parse().unwrap(),
         block_hash: access_key_query_response.block_hash,
@@ -129,12 +381,40 @@ pub async fn change(
             gas,
             deposit,
         }))],
-    };
-    let request = methods::broadcast_tx_async::RpcBroadcastTxAsyncRequest {
-        signed_transaction: transaction.sign(&signer),
-    };
+    })
+}
 
-    Ok(client.call(request).await?)
+/// Function call of a transaction run as a view call by [`crate::NearRpc::simulate_function_call`]
+#[derive(Debug, Clone)]
+pub struct FunctionCallSimulation {
+    /// Return value and logs of the method, `None` if it can't run as a view call
+    pub result: Option<CallResult>,
+    /// Why the method can't run as a view call, e.g. as it writes to the storage or calls another contract
+    pub view_error: Option<String>,
+    /// Fee of the attached gas at the current gas price, an upper bound of the gas the transaction burns
+    pub max_gas_cost: u128,
+    /// Balance of the signer, which pays the deposit and the gas
+    pub signer_balance: u128,
+}
+
+pub(crate) fn is_prohibited_in_view(err: &NearRpcError) -> bool {
+    match err {
+        NearRpcError::RpcQueryError(err) => matches!(
+            err.handler_error(),
+            Some(RpcQueryError::ContractExecutionError { vm_error, .. })
+                if vm_error.contains(PROHIBITED_IN_VIEW_ERROR)
+        ),
+        _ => false,
+    }
+}
+
+pub async fn send_transaction(
+    server_addr: &str,
+    signed_transaction: SignedTransaction,
+) -> Result<CryptoHash, NearRpcError> {
+    let request = methods::broadcast_tx_async::RpcBroadcastTxAsyncRequest { signed_transaction };
+
+    call(server_addr, request).await
 }
 
 pub async fn change_and_wait_for_outcome(
@@ -172,7 +452,6 @@ pub async fn wait_for_tx_final_outcome(
     server_addr: &str,
     timeout_sec: u64,
 ) -> Result<FinalExecutionOutcomeView, NearRpcError> {
-    let client = DEFAULT_CONNECTOR.connect(server_addr);
     let sent_at = time::Instant::now();
     let tx_info = TransactionInfo::TransactionId {
         tx_hash: hash,
@@ -180,12 +459,11 @@ pub async fn wait_for_tx_final_outcome(
     };
 
     loop {
-        let response = client
-            .call(methods::tx::RpcTransactionStatusRequest {
-                transaction_info: tx_info.clone(),
-                wait_until: near_primitives::views::TxExecutionStatus::Executed,
-            })
-            .await;
+        let request = methods::tx::RpcTransactionStatusRequest {
+            transaction_info: tx_info.clone(),
+            wait_until: near_primitives::views::TxExecutionStatus::Executed,
+        };
+        let response = send(server_addr, &request).await?;
 
         let delta = (time::Instant::now() - sent_at).as_secs();
         if delta > timeout_sec {
@@ -210,3 +488,76 @@ pub async fn wait_for_tx_final_outcome(
         }
     }
 }
+
+/// Error of the transaction, or of the first failed receipt. A failed receipt doesn't fail the transaction, e.g. a
+/// panicking `withdraw` called by the transaction through a cross-contract call
+pub fn execution_failure(outcome: &FinalExecutionOutcomeView) -> Option<String> {
+    if let FinalExecutionStatus::Failure(err) = &outcome.status {
+        return Some(failure_message(err));
+    }
+
+    outcome
+        .receipts_outcome
+        .iter()
+        .find_map(|receipt| match &receipt.outcome.status {
+            ExecutionStatusView::Failure(err) => Some(failure_message(err)),
+            _ => None,
+        })
+}
+
+/// Panic message of a failed function call, e.g. `Smart contract panicked: ERR_NOT_ENOUGH_STORAGE`, or the
+/// description of any other failure
+fn failure_message(err: &TxExecutionError) -> String {
+    match err {
+        TxExecutionError::ActionError(ActionError {
+            kind: ActionErrorKind::FunctionCallError(FunctionCallError::ExecutionError(message)),
+            ..
+        }) => message.clone(),
+        err => err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_jsonrpc_client::errors::JsonRpcServerError;
+    use near_primitives::errors::InvalidTxError;
+
+    #[test]
+    fn test_is_prohibited_in_view() {
+        let contract_error = |vm_error: &str| {
+            NearRpcError::RpcQueryError(JsonRpcError::ServerError(
+                JsonRpcServerError::HandlerError(RpcQueryError::ContractExecutionError {
+                    vm_error: vm_error.to_string(),
+                    block_height: 1,
+                    block_hash: CryptoHash::default(),
+                }),
+            ))
+        };
+
+        assert!(is_prohibited_in_view(&contract_error(
+            "wasm execution failed with error: HostError(ProhibitedInView { method_name: \"storage_write\" })"
+        )));
+        assert!(!is_prohibited_in_view(&contract_error(
+            "wasm execution failed with error: MethodResolveError(MethodNotFound)"
+        )));
+        assert!(!is_prohibited_in_view(&NearRpcError::NonceError));
+    }
+
+    #[test]
+    fn test_failure_message() {
+        let panic = TxExecutionError::ActionError(ActionError {
+            index: Some(0),
+            kind: ActionErrorKind::FunctionCallError(FunctionCallError::ExecutionError(
+                "Smart contract panicked: ERR_NOT_ENOUGH_STORAGE".to_string(),
+            )),
+        });
+        assert_eq!(
+            failure_message(&panic),
+            "Smart contract panicked: ERR_NOT_ENOUGH_STORAGE"
+        );
+
+        let expired = TxExecutionError::InvalidTxError(InvalidTxError::Expired);
+        assert_eq!(failure_message(&expired), expired.to_string());
+    }
+}
@@ -0,0 +1,116 @@
+use near_jsonrpc_client::errors::{
+    JsonRpcError, JsonRpcServerError, JsonRpcServerResponseStatusError,
+};
+use std::{
+    collections::hash_map::RandomState,
+    future::Future,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+tokio::task_local! {
+    static RETRY_POLICY: RetryPolicy;
+}
+
+/// Retries of a request failing with a transient error, e.g. a timeout, rate limiting or an unavailable node, after
+/// every endpoint was tried. The delay doubles with each retry
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts of a request, including the first one
+    pub attempts: u32,
+    /// Delay before the first retry
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Fraction of the delay that is randomized, e.g. `0.2` for ±20%, so that clients don't retry in lockstep
+    pub jitter: f64,
+}
+
+impl RetryPolicy {
+    pub const DEFAULT: Self = Self {
+        attempts: 3,
+        base_delay: Duration::from_millis(500),
+        max_delay: Duration::from_secs(10),
+        jitter: 0.2,
+    };
+
+    /// Fails on the first error
+    pub const NONE: Self = Self {
+        attempts: 1,
+        ..Self::DEFAULT
+    };
+
+    /// Delay before the retry following `attempt`, counted from zero
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay);
+        let jitter = self.jitter.clamp(0.0, 1.0) * (2.0 * random_fraction() - 1.0);
+
+        delay.mul_f64(1.0 + jitter)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Runs `future` with the retry policy of the RPC requests it makes, e.g. the one of a [`crate::NearRpcClient`]. The
+/// policy of the innermost scope applies
+pub async fn scope<F: Future>(policy: RetryPolicy, future: F) -> F::Output {
+    RETRY_POLICY.scope(policy, future).await
+}
+
+/// Retry policy of the enclosing [`scope`], [`RetryPolicy::DEFAULT`] outside of one
+pub fn current() -> RetryPolicy {
+    RETRY_POLICY.try_with(|policy| *policy).unwrap_or_default()
+}
+
+/// Timeouts, rate limiting and unavailable nodes. Other errors fail the same when retried
+pub(crate) fn is_transient<E>(error: &JsonRpcError<E>) -> bool {
+    matches!(
+        error,
+        JsonRpcError::TransportError(_)
+            | JsonRpcError::ServerError(JsonRpcServerError::ResponseStatusError(
+                JsonRpcServerResponseStatusError::TooManyRequests
+                    | JsonRpcServerResponseStatusError::ServiceUnavailable
+                    | JsonRpcServerResponseStatusError::TimeoutError
+            ))
+    )
+}
+
+/// Random number in `[0, 1)`, from the random keys of the standard hasher
+fn random_fraction() -> f64 {
+    (RandomState::new().build_hasher().finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_delay_doubles_up_to_the_max() {
+        let policy = RetryPolicy {
+            jitter: 0.0,
+            ..RetryPolicy::DEFAULT
+        };
+
+        assert_eq!(policy.delay(0), Duration::from_millis(500));
+        assert_eq!(policy.delay(2), Duration::from_secs(2));
+        assert_eq!(policy.delay(40), Duration::from_secs(10));
+
+        let jittered = RetryPolicy::DEFAULT.delay(1);
+        assert!(jittered >= Duration::from_millis(800) && jittered <= Duration::from_millis(1200));
+    }
+
+    #[tokio::test]
+    async fn test_scope_sets_the_policy() {
+        assert_eq!(current(), RetryPolicy::DEFAULT);
+        scope(RetryPolicy::NONE, async {
+            assert_eq!(current(), RetryPolicy::NONE);
+        })
+        .await;
+    }
+}
@@ -0,0 +1,35 @@
+//! Single entry point to the bridge crates. Every crate is behind a feature of the same name, so that e.g. a service
+//! only verifying Ethereum events builds `eth-proof` without the connectors and their Near and Ethereum clients
+
+pub mod prelude;
+
+#[cfg(feature = "eth-proof")]
+pub use eth_proof;
+#[cfg(feature = "near-rpc-client")]
+pub use near_rpc_client;
+
+#[cfg(feature = "light-clients")]
+pub use eth_light_client_on_near;
+#[cfg(feature = "light-clients")]
+pub use near_light_client_on_eth;
+
+#[cfg(feature = "common")]
+pub use bridge_connector_common;
+
+#[cfg(feature = "aurora-connector")]
+pub use aurora_connector;
+#[cfg(feature = "eth-connector")]
+pub use eth_connector;
+#[cfg(feature = "fast-bridge")]
+pub use fast_bridge;
+#[cfg(feature = "nep141-connector")]
+pub use nep141_connector;
+#[cfg(feature = "nft-connector")]
+pub use nft_connector;
+#[cfg(feature = "omni-connector")]
+pub use omni_connector;
+
+#[cfg(feature = "indexer")]
+pub use bridge_indexer;
+#[cfg(feature = "relayer")]
+pub use bridge_relayer;
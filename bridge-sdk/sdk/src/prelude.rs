@@ -0,0 +1,49 @@
+//! Types most applications need, e.g. `use bridge_sdk::prelude::*;` to build a connector and handle its errors
+
+#[cfg(feature = "common")]
+pub use bridge_connector_common::{
+    bridge_connector::{
+        BridgeConnector, FeeEstimate, TransactionId, TransferDirection, TransferId, TransferStatus,
+    },
+    contract_failure::ContractFailure,
+    eth_log_decoder::BridgeEvent,
+    events::{ConnectorEvent, ConnectorEvents},
+    result::BridgeSdkError,
+    transfer_journal::{FileTransferJournal, TransferJournal, TransferStage},
+};
+#[cfg(feature = "eth-proof")]
+pub use eth_proof::{EthProofError, EthRpc, Proof};
+#[cfg(feature = "near-rpc-client")]
+pub use near_rpc_client::{NearRpc, NearRpcClient, NearRpcError};
+
+#[cfg(feature = "light-clients")]
+pub use eth_light_client_on_near::{EthLightClientOnNear, EthOnNearClient};
+#[cfg(feature = "light-clients")]
+pub use near_light_client_on_eth::{NearLightClientOnEth, NearOnEthClient};
+
+#[cfg(all(feature = "light-clients", feature = "mock"))]
+pub use eth_light_client_on_near::MockEthOnNearClient;
+#[cfg(all(feature = "eth-proof", feature = "mock"))]
+pub use eth_proof::MockEthRpc;
+#[cfg(all(feature = "light-clients", feature = "mock"))]
+pub use near_light_client_on_eth::MockNearOnEthClient;
+#[cfg(all(feature = "near-rpc-client", feature = "mock"))]
+pub use near_rpc_client::MockNearRpc;
+
+#[cfg(feature = "aurora-connector")]
+pub use aurora_connector::{AuroraConnector, AuroraConnectorBuilder};
+#[cfg(feature = "eth-connector")]
+pub use eth_connector::{EthConnector, EthConnectorBuilder};
+#[cfg(feature = "fast-bridge")]
+pub use fast_bridge::{FastBridge, FastBridgeBuilder};
+#[cfg(feature = "nep141-connector")]
+pub use nep141_connector::{Nep141Connector, Nep141ConnectorBuilder};
+#[cfg(feature = "nft-connector")]
+pub use nft_connector::{NftConnector, NftConnectorBuilder};
+#[cfg(feature = "omni-connector")]
+pub use omni_connector::{OmniConnector, OmniConnectorBuilder};
+
+#[cfg(feature = "indexer")]
+pub use bridge_indexer::{Indexer, IndexerConfig, IndexerError};
+#[cfg(feature = "relayer")]
+pub use bridge_relayer::{Relayer, RelayerConfig, RelayerError};
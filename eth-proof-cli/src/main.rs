@@ -0,0 +1,257 @@
+use borsh::BorshSerialize;
+use clap::{Parser, Subcommand};
+use eth_proof::{
+    metrics::ProofMetricsSummary,
+    proof_cache::ProofCache,
+    rate_limit::{RateLimit, RateLimiter},
+    types::BlockId,
+    EthProofError, EthRPCClient, EthRPCClientOptions, Proof, ProofFixture, ProofOptions,
+};
+use ethereum_types::{H160, H256};
+use serde::{Deserialize, Serialize};
+use std::{error::Error, fs, path::PathBuf, sync::Arc};
+
+/// Generates and verifies the Ethereum proofs used by the bridge, without the bridge configuration
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct Arguments {
+    /// Appends the duration, block receipt count and trie size of the generated proofs to this file as JSON lines
+    #[clap(long, global = true)]
+    metrics_file: Option<PathBuf>,
+    /// Requests per second sent to the Ethereum RPC, e.g. to stay under the limit of an API key. Unlimited if unset
+    #[clap(long, global = true)]
+    rate_limit: Option<f64>,
+    /// Keeps the blocks of the generated proofs in this directory, so that proving other events of the same blocks
+    /// doesn't fetch them again
+    #[clap(long, global = true)]
+    cache_dir: Option<PathBuf>,
+    #[command(subcommand)]
+    cmd: SubCommand,
+}
+
+#[derive(Subcommand, Debug)]
+enum SubCommand {
+    /// Generates the proof that a transaction emitted an event, as accepted by the bridge provers on Near
+    Event {
+        #[clap(long, required_unless_present = "fixture")]
+        eth_rpc: Option<String>,
+        #[clap(long, required_unless_present = "fixture")]
+        tx_hash: Option<H256>,
+        /// Index of the log in the block
+        #[clap(long, required_unless_present = "fixture")]
+        log_index: Option<u64>,
+        /// Generates the proof from a recorded proof fixture instead of querying a node
+        #[clap(long, conflicts_with_all = ["eth_rpc", "tx_hash", "log_index"])]
+        fixture: Option<String>,
+        /// Prints the borsh encoded proof passed to the provers instead of JSON
+        #[clap(long)]
+        borsh: bool,
+    },
+    /// Fetches the proof of an account and its storage slots with `eth_getProof`
+    Storage {
+        #[clap(long)]
+        eth_rpc: String,
+        #[clap(long)]
+        address: H160,
+        /// Storage slot to prove. Can be repeated
+        #[clap(long = "key")]
+        keys: Vec<H256>,
+        /// Block the proof is generated for. Defaults to the latest block. Blocks older than the state kept by the
+        /// node require an archive node
+        #[clap(long)]
+        block_number: Option<u64>,
+        /// Hash of the block the proof is generated for, failing if the block was reorged
+        #[clap(long, conflicts_with = "block_number")]
+        block_hash: Option<H256>,
+    },
+    /// Checks that the receipt of an event proof is included in its block and that the log belongs to the receipt
+    Verify {
+        /// JSON proof printed by the `event` command
+        #[clap(long)]
+        proof_file: String,
+        /// Trusted hash of the block, e.g. read from the light client. The proof header has to match it
+        #[clap(long)]
+        block_hash: Option<H256>,
+    },
+    /// Summarizes the proof metrics recorded with `--metrics-file`, e.g. to spot a degraded RPC provider
+    Metrics {
+        /// Metrics file written with `--metrics-file`
+        #[clap(long)]
+        file: PathBuf,
+    },
+}
+
+/// Event proof with hex encoded fields
+#[derive(Serialize, Deserialize, Debug)]
+struct ProofJson {
+    block_hash: H256,
+    log_index: u64,
+    log_entry_data: String,
+    receipt_index: u64,
+    receipt_data: String,
+    header_data: String,
+    proof: Vec<String>,
+}
+
+impl From<&Proof> for ProofJson {
+    fn from(proof: &Proof) -> Self {
+        Self {
+            block_hash: proof.block_hash(),
+            log_index: proof.log_index,
+            log_entry_data: to_hex(&proof.log_entry_data),
+            receipt_index: proof.receipt_index,
+            receipt_data: to_hex(&proof.receipt_data),
+            header_data: to_hex(&proof.header_data),
+            proof: proof.proof.iter().map(|node| to_hex(node)).collect(),
+        }
+    }
+}
+
+impl TryFrom<ProofJson> for Proof {
+    type Error = hex::FromHexError;
+
+    fn try_from(proof: ProofJson) -> Result<Self, Self::Error> {
+        Ok(Self {
+            log_index: proof.log_index,
+            log_entry_data: from_hex(&proof.log_entry_data)?,
+            receipt_index: proof.receipt_index,
+            receipt_data: from_hex(&proof.receipt_data)?,
+            header_data: from_hex(&proof.header_data)?,
+            proof: proof
+                .proof
+                .iter()
+                .map(|node| from_hex(node))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let args = Arguments::parse();
+
+    let options = EthRPCClientOptions {
+        rate_limiter: args
+            .rate_limit
+            .map(|rate| Arc::new(RateLimiter::new(RateLimit::per_second(rate)))),
+        ..Default::default()
+    };
+
+    let proof_options = ProofOptions {
+        cache: args
+            .cache_dir
+            .map(|dir| Arc::new(ProofCache::default().with_dir(dir))),
+        metrics_file: args.metrics_file,
+    };
+
+    if let Err(err) = run(args.cmd, options, proof_options).await {
+        eprintln!("{err}");
+        std::process::exit(1);
+    }
+}
+
+async fn run(
+    cmd: SubCommand,
+    options: EthRPCClientOptions,
+    proof_options: ProofOptions,
+) -> Result<(), Box<dyn Error>> {
+    match cmd {
+        SubCommand::Event {
+            eth_rpc,
+            tx_hash,
+            log_index,
+            fixture,
+            borsh,
+        } => {
+            let proof = match (fixture, eth_rpc, tx_hash, log_index) {
+                (Some(fixture), ..) => {
+                    let fixture = ProofFixture::load(fixture)?;
+                    eth_proof::get_proof_for_event_with_options(
+                        &fixture,
+                        fixture.tx_hash,
+                        fixture.log_index,
+                        &proof_options,
+                    )
+                    .await?
+                }
+                (None, Some(eth_rpc), Some(tx_hash), Some(log_index)) => {
+                    let client = EthRPCClient::new_with_options(&eth_rpc, options);
+                    eth_proof::get_proof_for_event_with_options(
+                        &client,
+                        tx_hash,
+                        log_index,
+                        &proof_options,
+                    )
+                    .await?
+                }
+                _ => unreachable!("Arguments are checked by clap"),
+            };
+
+            if borsh {
+                let mut buffer = Vec::new();
+                proof.serialize(&mut buffer)?;
+                println!("{}", to_hex(&buffer));
+            } else {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&ProofJson::from(&proof))?
+                );
+            }
+        }
+        SubCommand::Storage {
+            eth_rpc,
+            address,
+            keys,
+            block_number,
+            block_hash,
+        } => {
+            let block = match (block_number, block_hash) {
+                (Some(block_number), _) => BlockId::Number(block_number.into()),
+                (None, Some(block_hash)) => BlockId::Hash(block_hash),
+                (None, None) => BlockId::Latest,
+            };
+            let proof = EthRPCClient::new_with_options(&eth_rpc, options)
+                .get_proof(&address, &keys, block)
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&proof)?);
+        }
+        SubCommand::Verify {
+            proof_file,
+            block_hash,
+        } => {
+            let proof_json: ProofJson = serde_json::from_str(&fs::read_to_string(proof_file)?)?;
+            let proof = Proof::try_from(proof_json)?;
+
+            eth_proof::verify_proof(&proof)?;
+
+            if let Some(block_hash) = block_hash {
+                if proof.block_hash() != block_hash {
+                    return Err(EthProofError::Other(format!(
+                        "The proof is for block {:#x}, not {block_hash:#x}",
+                        proof.block_hash()
+                    ))
+                    .into());
+                }
+            }
+
+            println!("Proof is valid for block {:#x}", proof.block_hash());
+        }
+        SubCommand::Metrics { file } => {
+            let metrics = eth_proof::metrics::load_metrics(file)?;
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&ProofMetricsSummary::new(&metrics))?
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn to_hex(data: &[u8]) -> String {
+    format!("0x{}", hex::encode(data))
+}
+
+fn from_hex(data: &str) -> Result<Vec<u8>, hex::FromHexError> {
+    hex::decode(data.strip_prefix("0x").unwrap_or(data))
+}